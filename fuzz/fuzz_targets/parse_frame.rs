@@ -0,0 +1,29 @@
+// Frames of every link type the capture path reads; the first input byte
+// picks the datalink. Run with `cargo +nightly fuzz run parse_frame`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/parser.rs"]
+#[allow(dead_code)]
+mod parser;
+
+/// Ethernet, raw IP, Linux cooked v1 and v2, BSD loopback, PPP, PPPoE, and one the parser doesn't know
+const DATALINKS: [i32; 8] = [1, 101, 113, 276, 0, 9, 51, 147];
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&pick, frame)) = data.split_first() else { return };
+    let link = parser::LinkType::from_datalink(DATALINKS[pick as usize % DATALINKS.len()]);
+    if let Some(packet) = parser::parse(link, frame) {
+        // The payload is always a part of the frame
+        if let Some(payload) = packet.payload {
+            let frame_range = frame.as_ptr_range();
+            let payload_range = payload.as_ptr_range();
+            assert!(frame_range.start <= payload_range.start && payload_range.end <= frame_range.end);
+        }
+        if packet.ports.is_some() {
+            assert!(matches!(packet.protocol, 6 | 17));
+        }
+    }
+});
@@ -0,0 +1,19 @@
+// Bare IPv4 and IPv6 packets, where most of the header walking happens.
+// Run with `cargo +nightly fuzz run parse_ip`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/parser.rs"]
+#[allow(dead_code)]
+mod parser;
+
+fuzz_target!(|data: &[u8]| {
+    let packet = parser::parse(parser::LinkType::Raw, data);
+    // Cutting a packet short never makes more of it readable
+    if let Some(shorter) = data.len().checked_sub(1).and_then(|len| parser::parse(parser::LinkType::Raw, &data[..len])) {
+        assert!(packet.is_some());
+        assert!(shorter.payload.is_none_or(|payload| packet.and_then(|packet| packet.payload).is_some_and(|full| payload.len() <= full.len())));
+    }
+});
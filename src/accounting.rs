@@ -0,0 +1,425 @@
+// Per-process accounting of the capture thread. `Attribution::packet` finds
+// the socket and process of a parsed packet and counts it everywhere it
+// belongs: the protocol and interface counters, the inter-process matrix, the
+// listening services and, through `Accounting::record`, that process's
+// counters, connections, first-contact trail and packet history; once a
+// second, `RateCalculator` turns the counters into per-second rates. The
+// capture loop in main.rs feeds live packets through both, the replay tests
+// feed them packets read from pcap fixtures. What a packet counts for is set
+// by `ByteCounting`: its full length on the wire, or only its TCP/UDP payload.
+
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+use crate::capture::{lookup_local_peers, lookup_socket};
+use crate::classify::Classifier;
+use crate::filter::ProcessFilter;
+use crate::parser::Packet;
+use crate::quic::QuicFlows;
+use crate::services::ServiceTracker;
+use crate::traffic_stats::{Protocol, TrafficCounter};
+use crate::types::{self, Connection, LocalFlow, PacketDirection, PacketInfo, ProcessIdentifier, ProcessInfo};
+use crate::units::format_bytes;
+
+/// What the bytes of a process count
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteCounting {
+    /// Whole frames, link, IP and transport headers included, as a provider bills them
+    #[default]
+    Wire,
+    /// Only what the application sent or received, the TCP or UDP payload
+    Payload,
+}
+
+impl std::str::FromStr for ByteCounting {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        match input.to_ascii_lowercase().as_str() {
+            "wire" => Ok(ByteCounting::Wire),
+            "payload" => Ok(ByteCounting::Payload),
+            _ => Err(format!("'{}' is not one of wire or payload", input)),
+        }
+    }
+}
+
+impl ByteCounting {
+    /// Bytes a packet of `wire_len` bytes on the wire counts for
+    pub fn bytes(self, wire_len: u64, packet: &Packet) -> u64 {
+        match self {
+            ByteCounting::Wire => wire_len,
+            ByteCounting::Payload => packet.payload_len.unwrap_or(0) as u64,
+        }
+    }
+
+    /// Shown wherever the counts are, e.g. "wire bytes"
+    pub fn label(self) -> &'static str {
+        match self {
+            ByteCounting::Wire => "wire bytes",
+            ByteCounting::Payload => "payload bytes",
+        }
+    }
+}
+
+/// A packet some process's socket matched
+pub struct AttributedPacket<'a> {
+    /// As captured
+    pub conn: Connection,
+    /// Oriented local -> remote, so both directions count as one connection
+    pub flow: Connection,
+    /// The process's socket sent the packet
+    pub outbound: bool,
+    /// Never left the machine
+    pub loopback: bool,
+    pub quic: bool,
+    pub wire_len: u64,
+    /// What the packet counts for, by the `ByteCounting` in use
+    pub bytes: u64,
+    /// Traffic category from the classification rules
+    pub category: Option<&'a str>,
+}
+
+/// The socket matched the reverse direction when the packet is inbound
+pub fn oriented(conn: Connection, outbound: bool) -> Connection {
+    if outbound {
+        return conn;
+    }
+    Connection {
+        source_port: conn.dest_port,
+        dest_port: conn.source_port,
+        source_ip: conn.dest_ip,
+        dest_ip: conn.source_ip,
+        protocol: conn.protocol,
+    }
+}
+
+/// Connections remembered per process to count the distinct ones; past this
+/// many the set starts over, and a connection seen again afterwards counts twice
+const MAX_CONNECTIONS_SEEN: usize = 10_000;
+
+#[derive(Default)]
+pub struct Accounting {
+    // Distinct connections per PID, used for `connection_count`
+    connections_seen: HashMap<i32, HashSet<Connection>>,
+    // Remote hosts already in each process's first-contact audit trail, at most `MAX_HOST_CONTACTS`
+    hosts_seen: HashMap<i32, HashSet<IpAddr>>,
+}
+
+impl Accounting {
+    /// Add a packet to the counters of process `pid`
+    pub fn record(&mut self, pid: i32, stats: &mut ProcessInfo, packet: &AttributedPacket) {
+        let (conn, flow, bytes) = (&packet.conn, &packet.flow, packet.bytes);
+        match conn.protocol {
+            6 => stats.tcp_bytes += bytes,
+            17 => stats.udp_bytes += bytes,
+            _ => {}
+        }
+        if packet.quic {
+            stats.quic_bytes += bytes;
+        }
+        if let Some(category) = packet.category {
+            stats.count_category(category, bytes);
+        }
+        if matches!(conn.protocol, 6 | 17) {
+            stats.count_port(flow.source_port, flow.dest_port, bytes);
+        }
+        match conn.source_ip {
+            IpAddr::V4(_) => stats.ipv4_bytes += bytes,
+            IpAddr::V6(_) => stats.ipv6_bytes += bytes,
+        }
+        stats.last_seen = SystemTime::now();
+        let seen = self.connections_seen.entry(pid).or_default();
+        if seen.len() >= MAX_CONNECTIONS_SEEN && !seen.contains(flow) {
+            seen.clear();
+        }
+        if seen.insert(*flow) {
+            stats.connection_count += 1;
+        }
+        let hosts = self.hosts_seen.entry(pid).or_default();
+        if stats.host_contacts.len() < types::MAX_HOST_CONTACTS
+            && hosts.insert(flow.dest_ip) {
+                stats.host_contacts.push(types::HostContact {
+                    host: flow.dest_ip,
+                    port: flow.dest_port,
+                    protocol: flow.protocol,
+                    first_seen: SystemTime::now(),
+                });
+            }
+
+        // Which side of the socket matched tells the direction
+        if packet.outbound {
+            stats.sent += bytes;
+            if packet.loopback {
+                stats.loopback_sent += bytes;
+            }
+        } else {
+            stats.received += bytes;
+            if packet.loopback {
+                stats.loopback_received += bytes;
+            }
+        }
+        stats.packet_history.push(packet_info(packet));
+    }
+
+    /// Drop what was kept for `pid`, once its process is gone
+    pub fn forget(&mut self, pid: i32) {
+        self.connections_seen.remove(&pid);
+        self.hosts_seen.remove(&pid);
+    }
+}
+
+/// What the capture thread keeps from packet to packet, besides the socket tables
+pub struct Attribution {
+    pub processes: HashMap<i32, ProcessInfo>,
+    /// Loopback/local socket traffic keyed by (sender PID, receiver PID)
+    pub local_flows: HashMap<(i32, i32), LocalFlow>,
+    pub services: ServiceTracker,
+    pub accounting: Accounting,
+    pub filter: ProcessFilter,
+    pub counting: ByteCounting,
+    /// Mark VPN daemons, whose traffic is the tunnelled copy of other processes'
+    pub mark_tunnels: bool,
+    quic_flows: QuicFlows,
+    classifier: Classifier,
+}
+
+impl Attribution {
+    pub fn new(filter: ProcessFilter, classifier: Classifier, counting: ByteCounting, mark_tunnels: bool) -> Self {
+        Attribution {
+            processes: HashMap::new(),
+            local_flows: HashMap::new(),
+            services: ServiceTracker::default(),
+            accounting: Accounting::default(),
+            filter,
+            counting,
+            mark_tunnels,
+            quic_flows: QuicFlows::default(),
+            classifier,
+        }
+    }
+
+    /// Count a captured packet of `wire_len` bytes; returns the PID it was
+    /// attributed to. A socket the tables don't know may have been opened since
+    /// they were read, possibly by a process that won't live until the next
+    /// refresh, so `resolve` gets to add it right away
+    pub fn packet(
+        &mut self,
+        parsed: Option<Packet>,
+        wire_len: u64,
+        conn_map: &mut HashMap<Connection, u64>,
+        inode_map: &mut HashMap<u64, ProcessIdentifier>,
+        traffic: &mut TrafficCounter,
+        resolve: impl FnOnce(&Connection, &mut HashMap<u64, ProcessIdentifier>, &mut HashMap<Connection, u64>) -> bool,
+    ) -> Option<i32> {
+        let Some(parsed) = parsed else {
+            traffic.count_unparsed(wire_len);
+            return None;
+        };
+        let Some(conn) = Connection::from_packet(&parsed) else {
+            traffic.count(Protocol::from_number(parsed.protocol), wire_len, false);
+            return None;
+        };
+        // What the packet adds to its process's counters; the protocol and
+        // interface counters always take the wire length
+        let bytes = self.counting.bytes(wire_len, &parsed);
+        // Traffic between two local sockets also feeds the inter-process matrix
+        let local_peers = lookup_local_peers(conn_map, &conn);
+        // Never leaves the machine: loopback addresses, or both ends are our sockets
+        let loopback = conn.source_ip.is_loopback() || conn.dest_ip.is_loopback() || local_peers.is_some();
+        if let Some((from_inode, to_inode)) = local_peers
+            && let (Some(from), Some(to)) = (inode_map.get(&from_inode), inode_map.get(&to_inode))
+            && (self.filter.matches(from) || self.filter.matches(to)) {
+                self.local_flows
+                    .entry((from.pid, to.pid))
+                    .or_insert_with(|| LocalFlow::new(from, to))
+                    .bytes += bytes;
+            }
+        // Check both directions of the connection, then unconnected/wildcard sockets
+        let mut found = lookup_socket(conn_map, &conn).filter(|(_, inode)| inode_map.contains_key(inode));
+        if found.is_none() && resolve(&conn, inode_map, conn_map) {
+            found = lookup_socket(conn_map, &conn);
+        }
+        let Some((outbound, found_inode)) = found else {
+            traffic.count(Protocol::from_number(conn.protocol), wire_len, false);
+            return None;
+        };
+        let flow = oriented(conn, outbound);
+        let quic = self.quic_flows.classify(&flow, || parsed.payload);
+        let protocol = if quic { Protocol::Quic } else { Protocol::from_number(conn.protocol) };
+        traffic.count(protocol, wire_len, inode_map.contains_key(&found_inode));
+
+        let identifier = inode_map.get(&found_inode).filter(|identifier| self.filter.matches(identifier))?;
+        let pid = identifier.pid;
+        let stats = self.processes.entry(pid).or_insert_with(|| ProcessInfo::new(identifier));
+        // The outer packets of a VPN: the same bytes show up again on the tunnel interface
+        if self.mark_tunnels && !stats.tunnel
+            && (crate::tunnel::is_carrier_process(&stats.name)
+                || crate::tunnel::is_tunnel_port(flow.protocol, flow.source_port, flow.dest_port)) {
+                stats.tunnel = true;
+            }
+        // Connections accepted on a listening port also count towards that service
+        self.services.record(&flow, outbound, bytes, pid);
+
+        let category = self.classifier.classify(&flow, || parsed.payload);
+        self.accounting.record(pid, stats, &AttributedPacket {
+            conn,
+            flow,
+            outbound,
+            loopback,
+            quic,
+            wire_len,
+            bytes,
+            category: category.map(|category| self.classifier.name(category)),
+        });
+        Some(pid)
+    }
+
+    /// Drop what was kept for `pid`, once its process is gone
+    pub fn forget(&mut self, pid: i32) {
+        self.processes.remove(&pid);
+        self.accounting.forget(pid);
+        self.local_flows.retain(|(from, to), _| *from != pid && *to != pid);
+    }
+}
+
+/// Entry of the packet history, with its columns formatted once up front
+fn packet_info(packet: &AttributedPacket) -> PacketInfo {
+    // The history shows packets from the process's point of view
+    let (direction, src_ip, src_port, dst_ip, dst_port) = if packet.outbound {
+        (PacketDirection::Sent, packet.conn.source_ip, packet.conn.source_port, packet.conn.dest_ip, packet.conn.dest_port)
+    } else {
+        (PacketDirection::Received, packet.conn.dest_ip, packet.conn.dest_port, packet.conn.source_ip, packet.conn.source_port)
+    };
+    let timestamp = SystemTime::now();
+    let local_time: chrono::DateTime<chrono::Local> = timestamp.into();
+    let cached_proto = match packet.conn.protocol {
+        6 => "TCP".to_string(),
+        17 if packet.quic => crate::quic::LABEL.to_string(),
+        17 => "UDP".to_string(),
+        1 => "ICMP".to_string(),
+        other => other.to_string(),
+    };
+    PacketInfo {
+        timestamp,
+        direction,
+        protocol: packet.conn.protocol,
+        src_ip,
+        src_port,
+        dst_ip,
+        dst_port,
+        size: packet.wire_len as usize,
+        cached_ts: local_time.format("%H:%M:%S%.3f").to_string(),
+        cached_src: format!("{}:{}", src_ip, src_port),
+        cached_dst: format!("{}:{}", dst_ip, dst_port),
+        cached_proto,
+        cached_size: format_bytes(packet.wire_len),
+    }
+}
+
+/// Turns the processes' counters into per-second rates
+#[derive(Default)]
+pub struct RateCalculator {
+    // Only the (sent, received, connections) counters are needed for the rate calculation
+    previous: HashMap<i32, (u64, u64, usize)>,
+    // Loopback (sent, received) counters at the last rate calculation
+    previous_loopback: HashMap<i32, (u64, u64)>,
+}
+
+impl RateCalculator {
+    /// Rates over the `interval` seconds since the last update; processes that
+    /// showed up since then are averaged over the `since_start` seconds of the capture
+    pub fn update(&mut self, processes: &mut HashMap<i32, ProcessInfo>, interval: f64, since_start: f64) {
+        for (pid, stats) in processes.iter_mut() {
+            if let Some((prev_sent, prev_received, prev_connections)) = self.previous.get(pid) {
+                let sent_diff = stats.sent.saturating_sub(*prev_sent);
+                let received_diff = stats.received.saturating_sub(*prev_received);
+                let new_connections = stats.connection_count.saturating_sub(*prev_connections);
+                stats.sent_rate = (sent_diff as f64 / interval) as u64;
+                stats.received_rate = (received_diff as f64 / interval) as u64;
+                stats.new_connection_rate = (new_connections as f64 / interval) as u64;
+            } else if since_start > 0.0 {
+                stats.sent_rate = (stats.sent as f64 / since_start) as u64;
+                stats.received_rate = (stats.received as f64 / since_start) as u64;
+                stats.new_connection_rate = (stats.connection_count as f64 / since_start) as u64;
+            }
+
+            let current = (stats.loopback_sent, stats.loopback_received);
+            let (prev_sent, prev_received) = self.previous_loopback.insert(*pid, current).unwrap_or_default();
+            stats.loopback_sent_rate = (current.0.saturating_sub(prev_sent) as f64 / interval) as u64;
+            stats.loopback_received_rate = (current.1.saturating_sub(prev_received) as f64 / interval) as u64;
+        }
+        self.restart(processes);
+    }
+
+    /// Drop the counters kept for `pid`, once its process is gone
+    pub fn forget(&mut self, pid: i32) {
+        self.previous.remove(&pid);
+        self.previous_loopback.remove(&pid);
+    }
+
+    /// Measure the next rates from the counters as they are now, e.g. after an
+    /// outage, rather than averaging over it
+    pub fn restart(&mut self, processes: &HashMap<i32, ProcessInfo>) {
+        self.previous = processes.iter()
+            .map(|(pid, stats)| (*pid, (stats.sent, stats.received, stats.connection_count)))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ProcessIdentifier;
+
+    #[test]
+    fn bounds_and_forgets_what_it_keeps_per_process() {
+        let identifier = ProcessIdentifier { pid: 7, name: "scanner".to_string(), container_name: None, user_name: None, unit: None };
+        let mut stats = ProcessInfo::new(&identifier);
+        let (mut accounting, mut rates) = (Accounting::default(), RateCalculator::default());
+        for port in 0..=MAX_CONNECTIONS_SEEN as u32 {
+            let conn = Connection {
+                source_ip: [192, 168, 1, 10].into(),
+                source_port: 40000,
+                dest_ip: [10, 0, (port >> 8) as u8, port as u8].into(),
+                dest_port: 80,
+                protocol: 6,
+            };
+            accounting.record(7, &mut stats, &AttributedPacket {
+                conn,
+                flow: conn,
+                outbound: true,
+                loopback: false,
+                quic: false,
+                wire_len: 60,
+                bytes: 60,
+                category: None,
+            });
+        }
+        assert_eq!(stats.connection_count, MAX_CONNECTIONS_SEEN + 1);
+        assert_eq!(accounting.connections_seen[&7].len(), 1);
+        assert_eq!(accounting.hosts_seen[&7].len(), types::MAX_HOST_CONTACTS);
+
+        let mut processes = HashMap::from([(7, stats)]);
+        rates.update(&mut processes, 1.0, 1.0);
+        accounting.forget(7);
+        rates.forget(7);
+        assert!(accounting.connections_seen.is_empty() && accounting.hosts_seen.is_empty());
+        assert!(rates.previous.is_empty() && rates.previous_loopback.is_empty());
+    }
+
+    #[test]
+    fn forgets_local_flows_with_an_exited_end() {
+        let process = |pid, name: &str| ProcessIdentifier { pid, name: name.to_string(), container_name: None, user_name: None, unit: None };
+        let (nginx, postgres, redis) = (process(10, "nginx"), process(20, "postgres"), process(30, "redis"));
+        let mut attribution = Attribution::new(ProcessFilter::default(), Classifier::new(&[]).0, ByteCounting::default(), false);
+        for (from, to) in [(&nginx, &postgres), (&postgres, &nginx), (&nginx, &redis)] {
+            attribution.local_flows.insert((from.pid, to.pid), LocalFlow::new(from, to));
+        }
+        attribution.forget(20);
+        let mut left: Vec<(i32, i32)> = attribution.local_flows.keys().copied().collect();
+        left.sort();
+        assert_eq!(left, vec![(10, 30)]);
+    }
+}
@@ -0,0 +1,247 @@
+// Alert actions that take a while run as tokio tasks instead of on the UI
+// tick: custom commands (a webhook or script may need up to COMMAND_TIMEOUT)
+// and kills, which wait for the process to be gone. At most MAX_RUNNING of
+// them run at once; the rest wait their turn. Each finished action comes back
+// as a Completion that the tick loop reports like any other alert result.
+// A command's stdout and stderr are kept, up to OUTPUT_LIMIT each, so the
+// Events view can show why a script failed.
+
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::{mpsc, Semaphore};
+use crate::types::Alert;
+use crate::units::format_bytes;
+
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+const MAX_RUNNING: usize = 4;
+
+/// Bytes kept of a command's stdout, and again of its stderr; the rest is read and dropped
+pub const OUTPUT_LIMIT: usize = 16 * 1024;
+
+/// How long a finished command's output may take to close; something it left
+/// running in the background can hold the pipes open indefinitely
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+
+/// What the tick loop needs to report an action once it is done
+pub struct Ticket {
+    pub pid: i32,
+    pub name: String,
+    pub alert: Alert,
+    pub bytes: u64,    // Sent and received when the alert fired
+    pub fired: String, // The "🔔 Alert fired" line, for the audit log
+}
+
+pub struct Completion {
+    pub ticket: Ticket,
+    pub was_killed: bool,
+    pub message: String, // Failures start with ❌ like the other alert results
+    pub output: Option<CommandOutput>, // Custom commands that got to run
+}
+
+/// What a custom command printed
+#[derive(Debug, Default, PartialEq)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub truncated: bool, // Either stream went past OUTPUT_LIMIT
+}
+
+/// One stream's bytes as they come in
+#[derive(Default)]
+struct Captured {
+    kept: Vec<u8>,
+    truncated: bool,
+}
+
+pub struct ActionQueue {
+    slots: Arc<Semaphore>,
+    done_tx: mpsc::UnboundedSender<Completion>,
+    done_rx: mpsc::UnboundedReceiver<Completion>,
+    pending: usize,
+}
+
+impl ActionQueue {
+    pub fn new() -> Self {
+        let (done_tx, done_rx) = mpsc::unbounded_channel();
+        ActionQueue { slots: Arc::new(Semaphore::new(MAX_RUNNING)), done_tx, done_rx, pending: 0 }
+    }
+
+    /// Actions queued or running
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Run an alert's custom command, killing it if it outlives COMMAND_TIMEOUT;
+    /// `usage` describes what triggered it, for the result message
+    pub fn run_command(&mut self, ticket: Ticket, command: std::process::Command, usage: String) {
+        self.spawn(async move {
+            let start = Instant::now();
+            let (name, pid) = (&ticket.name, ticket.pid);
+            let mut command = tokio::process::Command::from(command);
+            // Printing to the terminal would scribble over the UI, and reading it would steal keys
+            command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+            let (message, output) = match command.spawn() {
+                Ok(mut child) => {
+                    let stdout = Arc::new(Mutex::new(Captured::default()));
+                    let stderr = Arc::new(Mutex::new(Captured::default()));
+                    let readers = [
+                        child.stdout.take().map(|pipe| tokio::spawn(read_bounded(pipe, stdout.clone()))),
+                        child.stderr.take().map(|pipe| tokio::spawn(read_bounded(pipe, stderr.clone()))),
+                    ];
+                    let message = match tokio::time::timeout(COMMAND_TIMEOUT, child.wait()).await {
+                        Ok(Ok(status)) if status.success() => format!(
+                            "✅ Custom command executed successfully for {} (PID {}) in {:.2}s:\nUsage: {}",
+                            name, pid, start.elapsed().as_secs_f64(), usage
+                        ),
+                        Ok(Ok(status)) => format!(
+                            "❌ Custom command failed (exit code: {}) for {} (PID {}) after {:.2}s:\nUsage: {}",
+                            status.code().unwrap_or(-1), name, pid, start.elapsed().as_secs_f64(), format_bytes(ticket.bytes)
+                        ),
+                        Ok(Err(e)) => format!(
+                            "❌ Error waiting for custom command for {} (PID {}):\n{} | Usage: {}",
+                            name, pid, e, format_bytes(ticket.bytes)
+                        ),
+                        Err(_) => {
+                            // Reaps the child too, so no zombie is left behind
+                            let _ = child.kill().await;
+                            format!(
+                                "⏰ Custom command timed out after {}s for {} (PID {}):\nUsage: {}",
+                                COMMAND_TIMEOUT.as_secs(), name, pid, format_bytes(ticket.bytes)
+                            )
+                        }
+                    };
+                    let deadline = tokio::time::Instant::now() + OUTPUT_GRACE;
+                    for reader in readers.into_iter().flatten() {
+                        let abort = reader.abort_handle();
+                        if tokio::time::timeout_at(deadline, reader).await.is_err() {
+                            abort.abort();
+                        }
+                    }
+                    (message, Some(CommandOutput::from_streams(&stdout, &stderr)))
+                }
+                Err(e) => (format!(
+                    "❌ Failed to spawn custom command for {} (PID {}):\n{} | Usage: {}",
+                    name, pid, e, format_bytes(ticket.bytes)
+                ), None),
+            };
+            Completion { ticket, was_killed: false, message, output }
+        });
+    }
+
+    /// Kill the ticket's process and wait up to 2 s for it to go
+    pub fn kill(&mut self, ticket: Ticket) {
+        self.spawn(async move {
+            let (pid, name) = (ticket.pid, ticket.name.clone());
+            let (was_killed, message) = tokio::task::spawn_blocking(move || kill_and_wait(pid, &name))
+                .await
+                .unwrap_or_else(|e| (false, format!("❌ Failed to kill {} (PID {}): {}", ticket.name, ticket.pid, e)));
+            Completion { ticket, was_killed, message, output: None }
+        });
+    }
+
+    fn spawn(&mut self, action: impl Future<Output = Completion> + Send + 'static) {
+        self.pending += 1;
+        let slots = self.slots.clone();
+        let done_tx = self.done_tx.clone();
+        tokio::spawn(async move {
+            // The semaphore is never closed, so a permit always comes
+            let _permit = slots.acquire_owned().await;
+            let _ = done_tx.send(action.await);
+        });
+    }
+
+    /// Actions that finished since the last call
+    pub fn finished(&mut self) -> Vec<Completion> {
+        let mut finished = Vec::new();
+        while let Ok(completion) = self.done_rx.try_recv() {
+            finished.push(completion);
+        }
+        self.pending -= finished.len();
+        finished
+    }
+}
+
+impl CommandOutput {
+    fn from_streams(stdout: &Mutex<Captured>, stderr: &Mutex<Captured>) -> Self {
+        let stdout = stdout.lock().unwrap_or_else(|e| e.into_inner());
+        let stderr = stderr.lock().unwrap_or_else(|e| e.into_inner());
+        CommandOutput {
+            stdout: String::from_utf8_lossy(&stdout.kept).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr.kept).into_owned(),
+            truncated: stdout.truncated || stderr.truncated,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stdout.is_empty() && self.stderr.is_empty()
+    }
+}
+
+/// Keep the first OUTPUT_LIMIT bytes of `pipe` and drain the rest, so a chatty
+/// command never blocks on a full pipe
+async fn read_bounded(mut pipe: impl AsyncRead + Unpin, into: Arc<Mutex<Captured>>) {
+    let mut chunk = [0u8; 4096];
+    while let Ok(read) = pipe.read(&mut chunk).await && read > 0 {
+        let mut captured = into.lock().unwrap_or_else(|e| e.into_inner());
+        let room = OUTPUT_LIMIT.saturating_sub(captured.kept.len());
+        captured.truncated |= read > room;
+        captured.kept.extend_from_slice(&chunk[..read.min(room)]);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn kill_and_wait(pid: i32, name: &str) -> (bool, String) {
+    use nix::errno::Errno;
+    use nix::sys::signal::{self, Signal};
+    use nix::unistd::Pid;
+
+    match signal::kill(Pid::from_raw(pid), Some(Signal::SIGKILL)) {
+        Ok(_) => {}
+        // Already gone counts as a success
+        Err(Errno::ESRCH) => return (true, format!("💀 Process {} (PID {}) was already gone", name, pid)),
+        Err(e) => return (false, format!("❌ Failed to send kill signal to {} (PID {}): {}", name, pid, e)),
+    }
+
+    // Signal 0 tells whether the process still exists
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(2) {
+        match signal::kill(Pid::from_raw(pid), None) {
+            Ok(_) => std::thread::sleep(Duration::from_millis(100)),
+            Err(Errno::ESRCH) => return (true, format!("💀 Killed {} (PID {}) due to bandwidth limit", name, pid)),
+            Err(_) => break,
+        }
+    }
+    (false, format!("❌ Failed to kill {} (PID {}): Process still running", name, pid))
+}
+
+#[cfg(target_os = "windows")]
+fn kill_and_wait(pid: i32, name: &str) -> (bool, String) {
+    use crate::process::control::{terminate, ControlError};
+    match terminate(pid, Duration::from_secs(2)) {
+        Ok(()) => (true, format!("💀 Killed {} (PID {}) due to bandwidth limit", name, pid)),
+        Err(ControlError::NotFound) => (true, format!("💀 Process {} (PID {}) was already gone", name, pid)),
+        Err(e) => (false, format!("❌ Failed to kill {} (PID {}): {}", name, pid, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn keeps_the_start_of_long_output() {
+        let long = vec![b'x'; OUTPUT_LIMIT + 5000];
+        let stdout = Arc::new(Mutex::new(Captured::default()));
+        read_bounded(long.as_slice(), stdout.clone()).await;
+        let stderr = Arc::new(Mutex::new(Captured::default()));
+        read_bounded(&b"line 1: curl: not found\n"[..], stderr.clone()).await;
+
+        let output = CommandOutput::from_streams(&stdout, &stderr);
+        assert_eq!(output.stdout.len(), OUTPUT_LIMIT);
+        assert_eq!(output.stderr, "line 1: curl: not found\n");
+        assert!(output.truncated);
+    }
+}
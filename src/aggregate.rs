@@ -0,0 +1,282 @@
+// `--aggregate host1,host2`: one TUI for several machines. Each host runs the
+// collector with `--agent`, which serves the snapshot stream over TCP; this
+// module takes the place of the capture thread, reads every host's frames and
+// merges their processes into one table, names prefixed with the host.
+//
+// PIDs of different hosts collide, so each host gets its own range of table
+// keys: host n (counted from 1) shows PID p as n * HOST_PID_STRIDE + p.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read};
+use std::net::TcpStream;
+use std::sync::mpsc as std_mpsc;
+use std::time::{Duration, SystemTime};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use crate::delta::{DeltaTracker, StatsDelta};
+use crate::filter::ProcessFilter;
+use crate::stream::{DEFAULT_AGENT_PORT, STREAM_PROTOCOL_VERSION};
+use crate::types::{ProcessIdentifier, ProcessInfo, RemoteHost};
+
+/// Table keys reserved per host; above any Linux PID (pid_max is at most 2^22)
+pub const HOST_PID_STRIDE: i32 = 10_000_000;
+
+/// Hosts that fit in the table's i32 keys
+pub const MAX_HOSTS: usize = (i32::MAX / HOST_PID_STRIDE - 1) as usize;
+
+/// Wait between attempts to reach an agent that is down
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Largest frame accepted from an agent
+const MAX_FRAME_BYTES: usize = 64 * 1024 * 1024;
+
+/// The parts of a `StreamFrame` the aggregator uses; other fields are skipped
+#[derive(Deserialize)]
+struct RemoteFrame {
+    version: u8,
+    processes: Vec<RemoteProcess>,
+}
+
+#[derive(Deserialize)]
+struct RemoteProcess {
+    pid: i32,
+    name: String,
+    sent_bytes: u64,
+    received_bytes: u64,
+    sent_rate_bytes: u64,
+    received_rate_bytes: u64,
+    container_name: Option<String>,
+    user_name: Option<String>,
+    #[serde(default)]
+    unit: Option<String>,
+    tcp_bytes: u64,
+    udp_bytes: u64,
+    #[serde(default)]
+    quic_bytes: u64,
+    connection_count: usize,
+    new_connection_rate: u64,
+    #[serde(default)]
+    tunnel: bool,
+}
+
+/// What a host's reader thread reports
+enum HostEvent {
+    Connected(usize),
+    Frame(usize, RemoteFrame),
+    Disconnected(usize, String),
+}
+
+/// Parse the `--aggregate` list into (label, address) pairs: `host`, `host:port`,
+/// `[v6]:port` or a bare IPv6 address; the port defaults to the agent's
+pub fn parse_hosts(hosts: &[String]) -> Result<Vec<(String, String)>, String> {
+    if hosts.len() > MAX_HOSTS {
+        return Err(format!("--aggregate takes at most {} hosts", MAX_HOSTS));
+    }
+    hosts.iter().map(|host| {
+        let host = host.trim();
+        let (label, port) = if let Some(rest) = host.strip_prefix('[') {
+            let (name, after) = rest.split_once(']').ok_or_else(|| format!("'{}' is missing a ']'", host))?;
+            (name, after.strip_prefix(':'))
+        } else {
+            match host.split_once(':') {
+                Some((name, port)) if !port.contains(':') => (name, Some(port)),
+                // A name, an IPv4 address or an IPv6 address without a port
+                _ => (host, None),
+            }
+        };
+        if label.is_empty() {
+            return Err(format!("'{}' has no host name", host));
+        }
+        let port = match port {
+            Some(port) => port.parse::<u16>().map_err(|_| format!("'{}' has an invalid port", host))?,
+            None => DEFAULT_AGENT_PORT,
+        };
+        let address = if label.contains(':') { format!("[{}]:{}", label, port) } else { format!("{}:{}", label, port) };
+        Ok((label.to_string(), address))
+    }).collect()
+}
+
+/// Table key of a remote process
+fn table_key(host: usize, pid: i32) -> i32 {
+    (host as i32 + 1) * HOST_PID_STRIDE + pid
+}
+
+/// Read one length-prefixed frame
+fn read_frame(stream: &mut TcpStream) -> io::Result<RemoteFrame> {
+    let mut length = [0u8; 4];
+    stream.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME_BYTES {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("frame of {} bytes", length)));
+    }
+    let mut body = vec![0u8; length];
+    stream.read_exact(&mut body)?;
+    let frame: RemoteFrame = rmp_serde::from_slice(&body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    if frame.version != STREAM_PROTOCOL_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("stream protocol {} (expected {})", frame.version, STREAM_PROTOCOL_VERSION),
+        ));
+    }
+    Ok(frame)
+}
+
+/// Connect to one agent and forward its frames, reconnecting whenever it goes away
+fn follow_host(index: usize, address: String, events: std_mpsc::Sender<HostEvent>) {
+    loop {
+        let error = match TcpStream::connect(&address) {
+            Ok(mut stream) => {
+                if events.send(HostEvent::Connected(index)).is_err() {
+                    return;
+                }
+                loop {
+                    match read_frame(&mut stream) {
+                        Ok(frame) => {
+                            if events.send(HostEvent::Frame(index, frame)).is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => break e,
+                    }
+                }
+            }
+            Err(e) => e,
+        };
+        if events.send(HostEvent::Disconnected(index, error.to_string())).is_err() {
+            return;
+        }
+        std::thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+/// Merge the agents' snapshots into updates for the UI until it exits
+pub fn run(
+    tx: mpsc::Sender<StatsDelta>,
+    hosts_tx: std_mpsc::Sender<Vec<RemoteHost>>,
+    hosts: Vec<(String, String)>,
+    filter: &mut ProcessFilter,
+) {
+    let (events_tx, events_rx) = std_mpsc::channel();
+    let mut status: Vec<RemoteHost> = hosts.iter().map(|(label, address)| RemoteHost {
+        label: label.clone(),
+        address: address.clone(),
+        connected: false,
+        error: None,
+    }).collect();
+    for (index, (_, address)) in hosts.into_iter().enumerate() {
+        let events = events_tx.clone();
+        std::thread::spawn(move || follow_host(index, address, events));
+    }
+    drop(events_tx);
+    let _ = hosts_tx.send(status.clone());
+
+    let mut bandwidth_map: HashMap<i32, ProcessInfo> = HashMap::new();
+    let mut tracker = DeltaTracker::default();
+    // Table keys each host reported in its latest frame
+    let mut reported: Vec<HashSet<i32>> = vec![HashSet::new(); status.len()];
+
+    while let Ok(event) = events_rx.recv() {
+        let (host, present) = match event {
+            HostEvent::Connected(host) => {
+                status[host].connected = true;
+                status[host].error = None;
+                let _ = hosts_tx.send(status.clone());
+                continue;
+            }
+            HostEvent::Disconnected(host, error) => {
+                let changed = status[host].connected || status[host].error.as_ref() != Some(&error);
+                status[host].connected = false;
+                status[host].error = Some(error);
+                if changed {
+                    let _ = hosts_tx.send(status.clone());
+                }
+                // Nothing is known about its processes until it is back
+                (host, HashSet::new())
+            }
+            HostEvent::Frame(host, frame) => {
+                let label = &status[host].label;
+                let mut present = HashSet::new();
+                for process in frame.processes {
+                    let identifier = ProcessIdentifier {
+                        pid: process.pid,
+                        name: process.name,
+                        container_name: process.container_name,
+                        user_name: process.user_name,
+                        unit: process.unit,
+                    };
+                    if !filter.matches(&identifier) {
+                        continue;
+                    }
+                    let key = table_key(host, process.pid);
+                    present.insert(key);
+                    let stats = bandwidth_map.entry(key).or_insert_with(|| {
+                        ProcessInfo::new(&ProcessIdentifier {
+                            name: format!("{}/{}", label, identifier.name),
+                            ..identifier.clone()
+                        })
+                    });
+                    if process.sent_bytes != stats.sent || process.received_bytes != stats.received {
+                        stats.last_seen = SystemTime::now();
+                    }
+                    stats.sent = process.sent_bytes;
+                    stats.received = process.received_bytes;
+                    stats.sent_rate = process.sent_rate_bytes;
+                    stats.received_rate = process.received_rate_bytes;
+                    stats.tcp_bytes = process.tcp_bytes;
+                    stats.udp_bytes = process.udp_bytes;
+                    stats.quic_bytes = process.quic_bytes;
+                    stats.connection_count = process.connection_count;
+                    stats.new_connection_rate = process.new_connection_rate;
+                    stats.tunnel = process.tunnel;
+                    tracker.touch(key);
+                }
+                (host, present)
+            }
+        };
+
+        // Processes the host no longer reports have exited (or are out of reach): keep their totals, drop their rates
+        for key in reported[host].difference(&present) {
+            if let Some(stats) = bandwidth_map.get_mut(key) {
+                stats.sent_rate = 0;
+                stats.received_rate = 0;
+                stats.new_connection_rate = 0;
+                tracker.touch(*key);
+            }
+        }
+        reported[host] = present;
+
+        match tx.try_send(tracker.build(&bandwidth_map)) {
+            Ok(_) => tracker.delivered(&bandwidth_map),
+            // Unsent changes stay pending for the next update
+            Err(mpsc::error::TrySendError::Full(_)) => {}
+            // Receiver gone; the UI has exited
+            Err(_) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(host: &str) -> Result<(String, String), String> {
+        parse_hosts(&[host.to_string()]).map(|mut hosts| hosts.remove(0))
+    }
+
+    #[test]
+    fn parses_hosts() {
+        assert_eq!(parse("web1"), Ok(("web1".to_string(), "web1:7878".to_string())));
+        assert_eq!(parse("10.0.0.2:9000"), Ok(("10.0.0.2".to_string(), "10.0.0.2:9000".to_string())));
+        assert_eq!(parse("fd00::2"), Ok(("fd00::2".to_string(), "[fd00::2]:7878".to_string())));
+        assert_eq!(parse("[fd00::2]:9000"), Ok(("fd00::2".to_string(), "[fd00::2]:9000".to_string())));
+        assert!(parse("").is_err());
+        assert!(parse("web1:http").is_err());
+        assert!(parse("[fd00::2").is_err());
+    }
+
+    #[test]
+    fn keeps_hosts_apart() {
+        assert_ne!(table_key(0, 1234), table_key(1, 1234));
+        assert!(table_key(MAX_HOSTS - 1, 4_194_304) > 0);
+    }
+}
@@ -0,0 +1,151 @@
+// Alert evaluation: decides on each UI tick which alerts fire, honouring every
+// alert's cooldown, hysteresis (after firing, re-arm only once usage drops back
+// below a share of the threshold) and trigger limit.
+
+use std::time::{Duration, Instant};
+use crate::types::{Alert, App};
+
+/// Minimum time between two firings of an alert unless the editor says otherwise
+pub const DEFAULT_COOLDOWN_SECS: u64 = 60;
+
+/// What an alert has done this session; reset whenever the alert is saved again
+#[derive(Default)]
+pub struct AlertState {
+    last_triggered: Option<Instant>,
+    /// Set after firing when the alert has a re-arm level it hasn't dropped below yet
+    disarmed: bool,
+    trigger_count: u32,
+}
+
+impl AlertState {
+    /// Whether the alert may fire now, recording the firing if so
+    fn fire(&mut self, alert: &Alert, exceeded: bool, progress: f64) -> bool {
+        if self.disarmed
+            && alert.rearm_below_percent.is_some_and(|percent| progress * 100.0 < percent as f64) {
+                self.disarmed = false;
+            }
+        if !exceeded || self.disarmed {
+            return false;
+        }
+        if alert.max_triggers.is_some_and(|max| self.trigger_count >= max) {
+            return false;
+        }
+        if self.last_triggered.is_some_and(|last| last.elapsed() < Duration::from_secs(alert.cooldown_secs)) {
+            return false;
+        }
+        self.last_triggered = Some(Instant::now());
+        self.trigger_count += 1;
+        self.disarmed = alert.rearm_below_percent.is_some();
+        true
+    }
+
+    /// Short status for the overview, e.g. "2/3 fired, waiting to re-arm"
+    pub fn summary(&self, alert: &Alert) -> Option<String> {
+        if self.trigger_count == 0 {
+            return None;
+        }
+        let count = match alert.max_triggers {
+            Some(max) => format!("{}/{} fired", self.trigger_count, max),
+            None => format!("{} fired", self.trigger_count),
+        };
+        Some(if alert.max_triggers.is_some_and(|max| self.trigger_count >= max) {
+            format!("{}, done", count)
+        } else if self.disarmed {
+            format!("{}, waiting to re-arm", count)
+        } else {
+            count
+        })
+    }
+}
+
+/// Alerts that fire on this tick, skipping muted ones
+pub fn due_alerts(app: &mut App) -> Vec<(i32, Alert)> {
+    app.attach_alert_rules();
+    let alerts = &app.alerts;
+    app.alert_states.retain(|pid, _| alerts.contains_key(pid));
+
+    let mut due = Vec::new();
+    for (pid, alert) in &app.alerts {
+        // Muted alerts stay quiet, and exited processes can't be acted on
+        if app.alert_mutes.contains_key(pid) || app.is_exited(*pid) {
+            continue;
+        }
+        let Some(stats) = app.stats.get(pid) else { continue };
+        let anomaly = app.anomalies.score(*pid);
+        let exceeded = alert.is_exceeded(stats, anomaly);
+        let progress = alert.progress(stats, anomaly);
+        if app.alert_states.entry(*pid).or_default().fire(alert, exceeded, progress) {
+            due.push((*pid, alert.clone()));
+        }
+    }
+    due
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AlertAction;
+
+    fn alert(cooldown_secs: u64, rearm_below_percent: Option<u8>, max_triggers: Option<u32>) -> Alert {
+        Alert {
+            process_pid: 1,
+            threshold_bytes: 1000,
+            action: AlertAction::SystemAlert,
+            max_new_connections: None,
+            on_anomaly: false,
+            cooldown_secs,
+            rearm_below_percent,
+            max_triggers,
+            notify_every_secs: None,
+            dry_run: false,
+            process_name: None,
+        }
+    }
+
+    #[test]
+    fn fires_once_the_threshold_is_crossed() {
+        let alert = alert(0, None, None);
+        let mut state = AlertState::default();
+        assert!(!state.fire(&alert, false, 0.9));
+        assert_eq!(state.summary(&alert), None);
+        assert!(state.fire(&alert, true, 1.2));
+        assert!(state.fire(&alert, true, 1.2));
+        assert_eq!(state.summary(&alert).as_deref(), Some("2 fired"));
+    }
+
+    #[test]
+    fn waits_out_the_cooldown() {
+        let alert = alert(60, None, None);
+        let mut state = AlertState::default();
+        assert!(state.fire(&alert, true, 1.5));
+        assert!(!state.fire(&alert, true, 1.5));
+        state.last_triggered = Some(Instant::now() - Duration::from_secs(61));
+        assert!(state.fire(&alert, true, 1.5));
+        assert_eq!(state.trigger_count, 2);
+    }
+
+    #[test]
+    fn rearms_only_below_the_rearm_level() {
+        let alert = alert(0, Some(50), None);
+        let mut state = AlertState::default();
+        assert!(state.fire(&alert, true, 1.1));
+        assert_eq!(state.summary(&alert).as_deref(), Some("1 fired, waiting to re-arm"));
+        // Dipping under the threshold but not under half of it keeps the alert disarmed
+        assert!(!state.fire(&alert, false, 0.8));
+        assert!(!state.fire(&alert, true, 1.1));
+        assert!(!state.fire(&alert, false, 0.4));
+        assert_eq!(state.summary(&alert).as_deref(), Some("1 fired"));
+        assert!(state.fire(&alert, true, 1.1));
+    }
+
+    #[test]
+    fn stops_after_the_trigger_limit() {
+        let alert = alert(0, None, Some(2));
+        let mut state = AlertState::default();
+        assert!(state.fire(&alert, true, 2.0));
+        assert_eq!(state.summary(&alert).as_deref(), Some("1/2 fired"));
+        assert!(state.fire(&alert, true, 2.0));
+        assert!(!state.fire(&alert, true, 2.0));
+        assert_eq!(state.summary(&alert).as_deref(), Some("2/2 fired, done"));
+    }
+}
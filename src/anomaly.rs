@@ -0,0 +1,142 @@
+// Baseline learning: every process's combined bandwidth is compared against
+// what is normal for that process (an exponentially weighted mean and
+// variance), so a busy server and a quiet agent are each judged by their own
+// history instead of one static threshold. Settings live in the `anomaly`
+// section of the config.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::types::ProcessInfo;
+
+/// Weight of each one-second sample; roughly the last couple of minutes dominate
+const EWMA_ALPHA: f64 = 0.02;
+/// Samples a baseline needs before it is trusted
+const WARMUP_SAMPLES: u32 = 60;
+/// Deviations below this many bytes/s never count, so idle processes don't flag on a few packets
+const MIN_DEVIATION: f64 = 64.0 * 1024.0;
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+fn default_sigma() -> f64 {
+    4.0
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AnomalySettings {
+    /// Highlight processes that are well above their baseline
+    #[serde(default)]
+    pub enabled: bool,
+    /// Standard deviations above the baseline that count as anomalous
+    #[serde(default = "default_sigma")]
+    pub sigma: f64,
+}
+
+impl Default for AnomalySettings {
+    fn default() -> Self {
+        AnomalySettings {
+            enabled: false,
+            sigma: default_sigma(),
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct Baseline {
+    mean: f64,
+    variance: f64,
+    samples: u32,
+}
+
+impl Baseline {
+    fn update(&mut self, value: f64) {
+        if self.samples == 0 {
+            self.mean = value;
+        } else {
+            let diff = value - self.mean;
+            let increment = EWMA_ALPHA * diff;
+            self.mean += increment;
+            self.variance = (1.0 - EWMA_ALPHA) * (self.variance + diff * increment);
+        }
+        self.samples = self.samples.saturating_add(1);
+    }
+
+    /// Standard deviations `value` lies above the mean; `None` while still learning
+    fn sigmas(&self, value: f64) -> Option<f64> {
+        if self.samples < WARMUP_SAMPLES {
+            return None;
+        }
+        let deviation = value - self.mean;
+        if deviation < MIN_DEVIATION {
+            return Some(0.0);
+        }
+        // A perfectly flat baseline would make any change infinitely unusual
+        let stddev = self.variance.sqrt().max(MIN_DEVIATION / 4.0);
+        Some(deviation / stddev)
+    }
+}
+
+/// How unusual a process's current rate is compared to its baseline
+#[derive(Clone, Copy)]
+pub struct AnomalyScore {
+    pub sigmas: f64,
+    pub baseline: f64, // learned mean, bytes per second
+    pub current: f64,  // bytes per second
+    pub threshold: f64,
+}
+
+impl AnomalyScore {
+    pub fn is_anomalous(&self) -> bool {
+        self.sigmas >= self.threshold
+    }
+}
+
+/// Per-process baselines, fed once per second from the UI loop. Learning runs
+/// even with highlighting off so anomaly alerts work and turning it on needs no warm-up.
+#[derive(Default)]
+pub struct AnomalyDetector {
+    pub settings: AnomalySettings,
+    baselines: HashMap<i32, Baseline>,
+    scores: HashMap<i32, AnomalyScore>,
+    last_sample: Option<Instant>,
+}
+
+impl AnomalyDetector {
+    /// Score every process against its baseline, then fold the sample in.
+    /// Returns the PIDs that just became anomalous.
+    pub fn observe(&mut self, stats: &HashMap<i32, ProcessInfo>) -> Vec<i32> {
+        if self.last_sample.is_some_and(|last| last.elapsed() < SAMPLE_INTERVAL) {
+            return Vec::new();
+        }
+        self.last_sample = Some(Instant::now());
+        self.baselines.retain(|pid, _| stats.contains_key(pid));
+
+        let threshold = self.settings.sigma;
+        let mut scores = HashMap::new();
+        let mut newly_anomalous = Vec::new();
+        for (pid, info) in stats {
+            let current = (info.sent_rate + info.received_rate) as f64;
+            let baseline = self.baselines.entry(*pid).or_default();
+            if let Some(sigmas) = baseline.sigmas(current) {
+                let score = AnomalyScore { sigmas, baseline: baseline.mean, current, threshold };
+                let was_anomalous = self.scores.get(pid).is_some_and(AnomalyScore::is_anomalous);
+                if score.is_anomalous() && !was_anomalous {
+                    newly_anomalous.push(*pid);
+                }
+                scores.insert(*pid, score);
+            }
+            baseline.update(current);
+        }
+        self.scores = scores;
+        newly_anomalous
+    }
+
+    /// Latest score of a process whose baseline has finished learning
+    pub fn score(&self, pid: i32) -> Option<AnomalyScore> {
+        self.scores.get(&pid).copied()
+    }
+
+    /// Whether a process should be highlighted right now
+    pub fn is_highlighted(&self, pid: i32) -> bool {
+        self.settings.enabled && self.scores.get(&pid).is_some_and(AnomalyScore::is_anomalous)
+    }
+}
@@ -0,0 +1,204 @@
+// `--syslog`: every alert that fires and every kill, by an alert or by hand,
+// also goes to the system log with structured fields, so automated actions
+// can be audited centrally. journald gets them as native journal fields
+// (MONITETORING_PID, MONITETORING_ACTION, ...); without journald they go to
+// syslog, with the same fields as key=value pairs after the message.
+
+use std::io;
+#[cfg(target_os = "linux")]
+use std::os::unix::net::UnixDatagram;
+use crate::types::{Alert, AlertAction};
+
+#[cfg(target_os = "linux")]
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+#[cfg(target_os = "linux")]
+const SYSLOG_SOCKET: &str = "/dev/log";
+
+/// Syslog facility "user" and the severities used
+const FACILITY_USER: u8 = 1;
+const SEVERITY_WARNING: u8 = 4;
+const SEVERITY_NOTICE: u8 = 5;
+
+/// One audited action
+pub struct AuditEvent<'a> {
+    pub event: &'static str, // "alert" or "kill"
+    pub pid: i32,
+    pub name: &'a str,
+    pub bytes: u64, // Sent and received so far
+    pub threshold: Option<String>,
+    pub action: &'static str,
+    pub result: &'static str,
+    pub message: &'a str,
+}
+
+impl AuditEvent<'_> {
+    fn severity(&self) -> u8 {
+        if self.action == "kill" { SEVERITY_WARNING } else { SEVERITY_NOTICE }
+    }
+
+    /// Structured fields, without the message
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("EVENT", self.event.to_string()),
+            ("PID", self.pid.to_string()),
+            ("NAME", self.name.to_string()),
+            ("BYTES", self.bytes.to_string()),
+        ];
+        if let Some(threshold) = &self.threshold {
+            fields.push(("THRESHOLD", threshold.clone()));
+        }
+        fields.push(("ACTION", self.action.to_string()));
+        fields.push(("RESULT", self.result.to_string()));
+        fields
+    }
+}
+
+/// Short name of an alert's action for the audit fields
+pub fn action_name(action: &AlertAction) -> &'static str {
+    match action {
+        AlertAction::Kill => "kill",
+        AlertAction::CustomCommand(_) => "command",
+        AlertAction::SystemAlert => "notify",
+        AlertAction::CapturePcap(_) => "pcap",
+        AlertAction::Block => "block",
+    }
+}
+
+/// The limit an alert watches, e.g. "1048576 bytes" or "50 connections/s"
+pub fn threshold_text(alert: &Alert) -> String {
+    if alert.on_anomaly {
+        "baseline".to_string()
+    } else if let Some(limit) = alert.max_new_connections {
+        format!("{} connections/s", limit)
+    } else {
+        format!("{} bytes", alert.threshold_bytes)
+    }
+}
+
+// Only Linux has a system log to connect to; elsewhere `open` fails
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+enum Target {
+    Journald,
+    Syslog,
+}
+
+pub struct AuditLog {
+    #[cfg(target_os = "linux")]
+    socket: UnixDatagram,
+    target: Target,
+}
+
+impl AuditLog {
+    /// Connect to journald, or to syslog when journald isn't running
+    #[cfg(target_os = "linux")]
+    pub fn open() -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        if socket.connect(JOURNALD_SOCKET).is_ok() {
+            return Ok(AuditLog { socket, target: Target::Journald });
+        }
+        socket.connect(SYSLOG_SOCKET)
+            .map_err(|e| io::Error::new(e.kind(), format!("neither journald nor syslog is listening ({}: {})", SYSLOG_SOCKET, e)))?;
+        Ok(AuditLog { socket, target: Target::Syslog })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open() -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "--syslog is only available on Linux"))
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self.target {
+            Target::Journald => "the systemd journal",
+            Target::Syslog => "syslog",
+        }
+    }
+
+    /// Write one event; a log that went away is not worth interrupting monitoring for
+    pub fn record(&self, event: &AuditEvent) {
+        let datagram = match self.target {
+            Target::Journald => journal_entry(event),
+            Target::Syslog => syslog_line(event),
+        };
+        #[cfg(target_os = "linux")]
+        let _ = self.socket.send(&datagram);
+        #[cfg(not(target_os = "linux"))]
+        let _ = datagram;
+    }
+}
+
+/// Native journal protocol: one KEY=value line per field; values with a
+/// newline are sent as KEY, a newline, their little-endian u64 length and the raw value
+fn journal_entry(event: &AuditEvent) -> Vec<u8> {
+    let mut fields = vec![
+        ("MESSAGE".to_string(), event.message.to_string()),
+        ("PRIORITY".to_string(), event.severity().to_string()),
+        ("SYSLOG_IDENTIFIER".to_string(), "monitetoring".to_string()),
+    ];
+    fields.extend(event.fields().into_iter().map(|(key, value)| (format!("MONITETORING_{}", key), value)));
+
+    let mut entry = Vec::new();
+    for (key, value) in fields {
+        entry.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            entry.push(b'\n');
+            entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            entry.push(b'=');
+        }
+        entry.extend_from_slice(value.as_bytes());
+        entry.push(b'\n');
+    }
+    entry
+}
+
+/// BSD syslog line with the fields appended as key="value" pairs
+fn syslog_line(event: &AuditEvent) -> Vec<u8> {
+    let fields: Vec<String> = event.fields().into_iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key.to_lowercase(), value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    format!(
+        "<{}>{} monitetoring[{}]: {} [{}]",
+        FACILITY_USER * 8 + event.severity(),
+        chrono::Local::now().format("%b %e %H:%M:%S"),
+        std::process::id(),
+        event.message.replace('\n', " "),
+        fields.join(" ")
+    ).into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(message: &'static str) -> AuditEvent<'static> {
+        AuditEvent {
+            event: "alert",
+            pid: 42,
+            name: "curl",
+            bytes: 2048,
+            threshold: Some("1024 bytes".to_string()),
+            action: "kill",
+            result: "killed",
+            message,
+        }
+    }
+
+    #[test]
+    fn writes_journal_fields() {
+        let entry = String::from_utf8_lossy(&journal_entry(&event("Killed curl"))).into_owned();
+        assert!(entry.starts_with("MESSAGE=Killed curl\nPRIORITY=4\n"));
+        assert!(entry.contains("MONITETORING_PID=42\n"));
+        assert!(entry.contains("MONITETORING_THRESHOLD=1024 bytes\n"));
+        assert!(entry.ends_with("MONITETORING_RESULT=killed\n"));
+
+        let entry = journal_entry(&event("two\nlines"));
+        assert!(entry.starts_with(b"MESSAGE\n\x09\0\0\0\0\0\0\0two\nlines\n"));
+    }
+
+    #[test]
+    fn writes_syslog_lines() {
+        let line = String::from_utf8(syslog_line(&event("Killed \"curl\""))).unwrap();
+        assert!(line.starts_with("<12>"));
+        assert!(line.ends_with(": Killed \"curl\" [event=\"alert\" pid=\"42\" name=\"curl\" bytes=\"2048\" threshold=\"1024 bytes\" action=\"kill\" result=\"killed\"]"));
+    }
+}
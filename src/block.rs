@@ -0,0 +1,373 @@
+// Cutting a process off the network without killing it, on Linux. The process
+// moves into a cgroup v2 group of its own and a firewall rule drops whatever
+// its sockets send or receive; where that isn't possible (no unified cgroup
+// hierarchy), the rules drop traffic to and from the remote hosts it has
+// talked to instead, which also affects other processes using those hosts.
+//
+// nftables rules live in an `inet monitetoring` table; with only iptables
+// around, rules carry a `monitetoring pid <n>` comment. Blocks are lifted when
+// monitetoring exits, and leftovers from a crashed run are cleared at start.
+
+use std::net::IpAddr;
+
+/// What a block's rules match on
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlockTarget {
+    Cgroup(String), // Path below the cgroup v2 root, e.g. "monitetoring/block-1234"
+    Endpoints(Vec<IpAddr>),
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Firewall {
+    Nftables,
+    Iptables,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Clone, Debug)]
+pub struct NetworkBlock {
+    pub firewall: Firewall,
+    pub target: BlockTarget,
+    /// Cgroup the process came from, to put it back on unblock
+    pub original_cgroup: Option<String>,
+}
+
+impl NetworkBlock {
+    /// "all of its traffic" or "traffic with 3 remote hosts", for notifications
+    pub fn describe(&self) -> String {
+        match &self.target {
+            BlockTarget::Cgroup(_) => "all of its traffic".to_string(),
+            BlockTarget::Endpoints(hosts) => format!("traffic with {} remote hosts", hosts.len()),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+const CGROUP2_ROOT: &str = "/sys/fs/cgroup";
+#[cfg(target_os = "linux")]
+const CGROUP_DIR: &str = "monitetoring";
+#[cfg(target_os = "linux")]
+const NFT_TABLE: &str = "monitetoring";
+
+#[cfg(target_os = "linux")]
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new(program).args(args).output()
+        .map_err(|e| format!("can't run {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn comment(pid: i32) -> String {
+    format!("monitetoring pid {}", pid)
+}
+
+#[cfg(target_os = "linux")]
+fn firewall() -> Result<Firewall, String> {
+    if run("nft", &["--version"]).is_ok() {
+        Ok(Firewall::Nftables)
+    } else if run("iptables", &["--version"]).is_ok() {
+        Ok(Firewall::Iptables)
+    } else {
+        Err("neither nft nor iptables is installed".to_string())
+    }
+}
+
+/// The process's cgroup v2 path, from the `0::` line of /proc/<pid>/cgroup
+#[cfg(target_os = "linux")]
+fn parse_cgroup2(content: &str) -> Option<String> {
+    content.lines().find_map(|line| line.strip_prefix("0::")).map(|path| path.trim().to_string())
+}
+
+/// Move `pid` into a cgroup of its own; returns the new path and the one it left
+#[cfg(target_os = "linux")]
+fn isolate(pid: i32) -> Result<(String, String), String> {
+    let root = std::path::Path::new(CGROUP2_ROOT);
+    if !root.join("cgroup.controllers").exists() {
+        return Err("no cgroup v2 hierarchy".to_string());
+    }
+    let original = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()
+        .and_then(|content| parse_cgroup2(&content))
+        .ok_or_else(|| format!("can't read the cgroup of PID {}", pid))?;
+    let path = format!("{}/block-{}", CGROUP_DIR, pid);
+    let dir = root.join(&path);
+    std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(dir.join("cgroup.procs"), pid.to_string()))
+        .map_err(|e| format!("can't move PID {} into {}: {}", pid, dir.display(), e))?;
+    Ok((path, original))
+}
+
+/// nft statements of a block, one per rule: (chain, statement)
+#[cfg(target_os = "linux")]
+fn nft_rules(target: &BlockTarget) -> Vec<(&'static str, String)> {
+    match target {
+        BlockTarget::Cgroup(path) => {
+            let matcher = format!("socket cgroupv2 level {} \"{}\"", path.split('/').count(), path);
+            vec![("output", matcher.clone()), ("input", matcher)]
+        }
+        BlockTarget::Endpoints(hosts) => hosts.iter().flat_map(|host| {
+            let family = if host.is_ipv4() { "ip" } else { "ip6" };
+            [("output", format!("{} daddr {}", family, host)), ("input", format!("{} saddr {}", family, host))]
+        }).collect(),
+    }
+}
+
+/// iptables arguments of a block's rules, without the -I/-D in front: (program, arguments)
+#[cfg(target_os = "linux")]
+fn iptables_rules(pid: i32, target: &BlockTarget) -> Vec<(&'static str, Vec<String>)> {
+    let tail = ["-m", "comment", "--comment", &comment(pid), "-j", "DROP"].map(String::from);
+    let rule = |program, chain: &str, matcher: &[&str]| {
+        let mut args = vec![chain.to_string()];
+        args.extend(matcher.iter().map(|arg| arg.to_string()));
+        args.extend(tail.iter().cloned());
+        (program, args)
+    };
+    match target {
+        BlockTarget::Cgroup(path) => ["iptables", "ip6tables"].into_iter().flat_map(|program| [
+            rule(program, "OUTPUT", &["-m", "cgroup", "--path", path]),
+            rule(program, "INPUT", &["-m", "cgroup", "--path", path]),
+        ]).collect(),
+        BlockTarget::Endpoints(hosts) => hosts.iter().flat_map(|host| {
+            let program = if host.is_ipv4() { "iptables" } else { "ip6tables" };
+            let host = host.to_string();
+            [rule(program, "OUTPUT", &["-d", &host]), rule(program, "INPUT", &["-s", &host])]
+        }).collect(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn ensure_nft_table() -> Result<(), String> {
+    run("nft", &["add", "table", "inet", NFT_TABLE])?;
+    for chain in ["output", "input"] {
+        run("nft", &[&format!("add chain inet {} {} {{ type filter hook {} priority 0 ; }}", NFT_TABLE, chain, chain)])?;
+    }
+    Ok(())
+}
+
+/// Rule handles tagged with `pid`'s comment in `nft -a list table` output: (chain, handle)
+#[cfg(target_os = "linux")]
+fn nft_handles(listing: &str, pid: i32) -> Vec<(String, u64)> {
+    let tag = format!("comment \"{}\"", comment(pid));
+    let mut chain = String::new();
+    let mut handles = Vec::new();
+    for line in listing.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("chain ") {
+            chain = rest.split_whitespace().next().unwrap_or_default().to_string();
+        } else if line.contains(&tag)
+            && let Some((_, handle)) = line.rsplit_once("# handle ")
+            && let Ok(handle) = handle.trim().parse() {
+                handles.push((chain.clone(), handle));
+            }
+    }
+    handles
+}
+
+/// Rules tagged with a `monitetoring pid <n>` comment in `iptables -S` output,
+/// as the arguments that delete them
+#[cfg(target_os = "linux")]
+fn tagged_iptables_rules(listing: &str) -> Vec<Vec<String>> {
+    listing.lines()
+        .filter_map(|line| line.strip_prefix("-A "))
+        .filter(|rule| rule.contains("--comment \"monitetoring pid "))
+        .map(|rule| {
+            // Split like a shell would; iptables quotes only comments with spaces in them
+            let mut args = vec!["-D".to_string()];
+            let mut quoted = false;
+            let mut arg = String::new();
+            for c in rule.chars() {
+                match c {
+                    '"' => quoted = !quoted,
+                    ' ' if !quoted => {
+                        if !arg.is_empty() {
+                            args.push(std::mem::take(&mut arg));
+                        }
+                    }
+                    c => arg.push(c),
+                }
+            }
+            if !arg.is_empty() {
+                args.push(arg);
+            }
+            args
+        })
+        .collect()
+}
+
+/// Drop the network traffic of `pid`; `endpoints` are the remote hosts it has
+/// talked to, blocked instead when it can't get a cgroup of its own
+#[cfg(target_os = "linux")]
+pub fn block(pid: i32, endpoints: &[IpAddr]) -> Result<NetworkBlock, String> {
+    let firewall = firewall()?;
+    let (target, original_cgroup) = match isolate(pid) {
+        Ok((path, original)) => (BlockTarget::Cgroup(path), Some(original)),
+        Err(_) if !endpoints.is_empty() => (BlockTarget::Endpoints(endpoints.to_vec()), None),
+        Err(e) => return Err(format!("{}, and it has no remote hosts to block instead", e)),
+    };
+    let block = NetworkBlock { firewall, target, original_cgroup };
+    let added = match firewall {
+        Firewall::Nftables => ensure_nft_table().and_then(|_| {
+            nft_rules(&block.target).into_iter().try_for_each(|(chain, statement)| {
+                let rule = format!("add rule inet {} {} {} drop comment \"{}\"", NFT_TABLE, chain, statement, comment(pid));
+                run("nft", &[&rule]).map(|_| ())
+            })
+        }),
+        Firewall::Iptables => iptables_rules(pid, &block.target).into_iter().try_for_each(|(program, args)| {
+            let mut insert = vec!["-I"];
+            insert.extend(args.iter().map(String::as_str));
+            run(program, &insert).map(|_| ())
+        }),
+    };
+    if let Err(e) = added {
+        // Don't leave half a block behind
+        let _ = unblock(pid, &block);
+        return Err(e);
+    }
+    Ok(block)
+}
+
+/// Remove the rules of a block and put the process back into its cgroup
+#[cfg(target_os = "linux")]
+pub fn unblock(pid: i32, block: &NetworkBlock) -> Result<(), String> {
+    let mut result = Ok(());
+    match block.firewall {
+        Firewall::Nftables => {
+            let listing = run("nft", &["-a", "list", "table", "inet", NFT_TABLE]).unwrap_or_default();
+            for (chain, handle) in nft_handles(&listing, pid) {
+                let rule = format!("delete rule inet {} {} handle {}", NFT_TABLE, chain, handle);
+                if let Err(e) = run("nft", &[&rule]) {
+                    result = Err(e);
+                }
+            }
+        }
+        Firewall::Iptables => {
+            for (program, args) in iptables_rules(pid, &block.target) {
+                let mut delete = vec!["-D"];
+                delete.extend(args.iter().map(String::as_str));
+                // A rule that never got inserted can't be deleted either
+                let _ = run(program, &delete);
+            }
+        }
+    }
+    if let BlockTarget::Cgroup(path) = &block.target {
+        let root = std::path::Path::new(CGROUP2_ROOT);
+        if let Some(original) = &block.original_cgroup {
+            // The process may have exited meanwhile
+            let _ = std::fs::write(root.join(original.trim_start_matches('/')).join("cgroup.procs"), pid.to_string());
+        }
+        let _ = std::fs::remove_dir(root.join(path));
+    }
+    result
+}
+
+/// Clear what a run that didn't exit cleanly left: the nft table, tagged
+/// iptables and ip6tables rules, and empty block cgroups
+#[cfg(target_os = "linux")]
+pub fn clear_leftovers() {
+    if run("nft", &["list", "table", "inet", NFT_TABLE]).is_ok() {
+        let _ = run("nft", &["delete", "table", "inet", NFT_TABLE]);
+    }
+    for program in ["iptables", "ip6tables"] {
+        let Ok(listing) = run(program, &["-S"]) else { continue };
+        for rule in tagged_iptables_rules(&listing) {
+            let _ = run(program, &rule.iter().map(String::as_str).collect::<Vec<_>>());
+        }
+    }
+    if let Ok(entries) = std::fs::read_dir(std::path::Path::new(CGROUP2_ROOT).join(CGROUP_DIR)) {
+        for entry in entries.flatten().filter(|entry| entry.file_name().to_string_lossy().starts_with("block-")) {
+            // Fails, and keeps the group, while a process is still inside
+            let _ = std::fs::remove_dir(entry.path());
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn block(_pid: i32, _endpoints: &[IpAddr]) -> Result<NetworkBlock, String> {
+    Err("blocking a process's network is only available on Linux".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn unblock(_pid: i32, _block: &NetworkBlock) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn clear_leftovers() {}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cgroup2_path() {
+        let content = "12:net_cls,net_prio:/\n0::/user.slice/user-1000.slice/session-2.scope\n";
+        assert_eq!(parse_cgroup2(content).as_deref(), Some("/user.slice/user-1000.slice/session-2.scope"));
+        assert_eq!(parse_cgroup2("12:net_cls:/\n"), None);
+    }
+
+    #[test]
+    fn builds_rules() {
+        let cgroup = BlockTarget::Cgroup("monitetoring/block-42".to_string());
+        assert_eq!(nft_rules(&cgroup)[0], ("output", "socket cgroupv2 level 2 \"monitetoring/block-42\"".to_string()));
+        let hosts = BlockTarget::Endpoints(vec!["93.184.216.34".parse().unwrap(), "2606:4700::1".parse().unwrap()]);
+        let rules = nft_rules(&hosts);
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[3], ("input", "ip6 saddr 2606:4700::1".to_string()));
+        let (program, args) = &iptables_rules(42, &hosts)[0];
+        assert_eq!(*program, "iptables");
+        assert_eq!(args.join(" "), "OUTPUT -d 93.184.216.34 -m comment --comment monitetoring pid 42 -j DROP");
+    }
+
+    #[test]
+    fn finds_rule_handles() {
+        let listing = "table inet monitetoring { # handle 7
+	chain output { # handle 1
+		type filter hook output priority filter; policy accept;
+		socket cgroupv2 level 2 \"monitetoring/block-42\" drop comment \"monitetoring pid 42\" # handle 4
+		ip daddr 1.2.3.4 drop comment \"monitetoring pid 420\" # handle 5
+	}
+	chain input { # handle 2
+		socket cgroupv2 level 2 \"monitetoring/block-42\" drop comment \"monitetoring pid 42\" # handle 6
+	}
+}
+";
+        assert_eq!(nft_handles(listing, 42), vec![("output".to_string(), 4), ("input".to_string(), 6)]);
+    }
+
+    #[test]
+    fn finds_leftover_iptables_rules() {
+        let listing = "-P INPUT ACCEPT
+-P OUTPUT ACCEPT
+-A INPUT -s 1.2.3.4/32 -m comment --comment \"monitetoring pid 42\" -j DROP
+-A INPUT -s 10.0.0.0/8 -m comment --comment \"office network\" -j ACCEPT
+-A OUTPUT -m cgroup --path monitetoring/block-7 -m comment --comment \"monitetoring pid 7\" -j DROP
+";
+        let rules: Vec<String> = tagged_iptables_rules(listing).iter().map(|rule| rule.join("|")).collect();
+        assert_eq!(rules, [
+            "-D|INPUT|-s|1.2.3.4/32|-m|comment|--comment|monitetoring pid 42|-j|DROP",
+            "-D|OUTPUT|-m|cgroup|--path|monitetoring/block-7|-m|comment|--comment|monitetoring pid 7|-j|DROP",
+        ]);
+    }
+
+    #[test]
+    fn blocks_once_and_forgets_exited_processes() {
+        use crate::types::{App, ProcessIdentifier, ProcessInfo};
+        let mut app = App::new(false, false);
+        app.demo_mode = true;
+        let identifier = ProcessIdentifier { pid: 4242, name: "curl".to_string(), container_name: None, user_name: None, unit: None };
+        app.stats.insert(4242, ProcessInfo::new(&identifier));
+
+        assert!(app.block_process(4242).unwrap().starts_with("⛔ Blocked"));
+        // A re-fired Block alert leaves the first block as it is
+        assert_eq!(app.block_process(4242).unwrap(), "⛔ curl (PID 4242) is already blocked");
+        assert_eq!(app.blocks.len(), 1);
+
+        app.retire_exited_processes(vec![4242]);
+        assert!(app.blocks.is_empty());
+        assert_eq!(app.process_actions(4242).last(), Some(&"Block"));
+    }
+}
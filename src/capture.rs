@@ -1,64 +1,204 @@
-use crate::types::Connection;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+use pcap::{Active, Capture, Inactive};
+use crate::types::{CaptureEvent, CaptureLoss, Connection};
 
-pub fn connection_from_packet(packet_data: &[u8]) -> Option<Connection> {
-    use etherparse::{InternetSlice, SlicedPacket, TransportSlice};
+/// Find the socket a packet belongs to. Returns whether the socket sent the packet
+/// (as opposed to receiving it) together with the socket inode.
+///
+/// Exact matches in either direction win. Otherwise the packet is matched against
+/// sockets without a remote end (unconnected UDP, listeners) bound to the local
+/// address, the IPv4 wildcard or the IPv6 wildcard, which on dual-stack sockets
+/// also receives IPv4 traffic.
+pub fn lookup_socket(conn_map: &HashMap<Connection, u64>, conn: &Connection) -> Option<(bool, u64)> {
+    let reverse = Connection {
+        source_port: conn.dest_port,
+        dest_port: conn.source_port,
+        source_ip: conn.dest_ip,
+        dest_ip: conn.source_ip,
+        protocol: conn.protocol,
+    };
+    if let Some(inode) = conn_map.get(conn) {
+        return Some((true, *inode));
+    }
+    if let Some(inode) = conn_map.get(&reverse) {
+        return Some((false, *inode));
+    }
 
-    // NOTE [Linux -i any]: The "any" pseudo interface often uses Linux cooked
-    // capture (SLL/SLL2). On some systems this can differ by kernel/version and
-    // the pcap datalink. We currently try multiple parsers and heuristic
-    // offsets. A more robust approach is to make parsing datalink-aware by
-    // reading the datalink from the activated pcap handle and branching to the
-    // correct parser/offsets accordingly, rather than guessing here.
-    // For best reliability, prefer selecting a concrete interface (e.g., eth0).
+    unbound_socket(conn_map, conn.source_ip, conn.source_port, conn.protocol)
+        .map(|inode| (true, inode))
+        .or_else(|| unbound_socket(conn_map, conn.dest_ip, conn.dest_port, conn.protocol).map(|inode| (false, inode)))
+}
 
-    // Helper to build a Connection from a parsed SlicedPacket
-    fn from_sliced(sliced: SlicedPacket<'_>) -> Option<Connection> {
-        let net = sliced.net?;
-        let (source_ip, dest_ip, protocol) = match net {
-            InternetSlice::Ipv4(ipv4) => (
-                ipv4.header().source_addr().into(),
-                ipv4.header().destination_addr().into(),
-                ipv4.header().protocol(),
-            ),
-            InternetSlice::Ipv6(ipv6) => (
-                ipv6.header().source_addr().into(),
-                ipv6.header().destination_addr().into(),
-                ipv6.header().next_header(),
-            ),
-            _ => return None,
+/// Socket without a remote end bound to this local address, the IPv4 wildcard or the IPv6 wildcard
+fn unbound_socket(conn_map: &HashMap<Connection, u64>, local_ip: IpAddr, local_port: u16, protocol: u8) -> Option<u64> {
+    let wildcards = match local_ip {
+        IpAddr::V4(_) => vec![local_ip, IpAddr::V4(Ipv4Addr::UNSPECIFIED), IpAddr::V6(Ipv6Addr::UNSPECIFIED)],
+        IpAddr::V6(_) => vec![local_ip, IpAddr::V6(Ipv6Addr::UNSPECIFIED)],
+    };
+    wildcards.into_iter().find_map(|ip| {
+        let remote = match ip {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
         };
+        conn_map.get(&Connection {
+            source_port: local_port,
+            dest_port: 0,
+            source_ip: ip,
+            dest_ip: remote,
+            protocol,
+        }).copied()
+    })
+}
 
-        let transport = sliced.transport?;
-        let (source_port, dest_port) = match transport {
-            TransportSlice::Tcp(tcp) => (tcp.source_port(), tcp.destination_port()),
-            TransportSlice::Udp(udp) => (udp.source_port(), udp.destination_port()),
-            _ => return None,
-        };
+/// For traffic that never leaves the host (loopback, or a host address talking to
+/// itself), find the sending and the receiving socket. Returns `(sender, receiver)` inodes.
+pub fn lookup_local_peers(conn_map: &HashMap<Connection, u64>, conn: &Connection) -> Option<(u64, u64)> {
+    let local = (conn.source_ip.is_loopback() && conn.dest_ip.is_loopback()) || conn.source_ip == conn.dest_ip;
+    if !local {
+        return None;
+    }
+    let reverse = Connection {
+        source_port: conn.dest_port,
+        dest_port: conn.source_port,
+        source_ip: conn.dest_ip,
+        dest_ip: conn.source_ip,
+        protocol: conn.protocol,
+    };
+    let sender = conn_map.get(conn).copied()
+        .or_else(|| unbound_socket(conn_map, conn.source_ip, conn.source_port, conn.protocol))?;
+    let receiver = conn_map.get(&reverse).copied()
+        .or_else(|| unbound_socket(conn_map, conn.dest_ip, conn.dest_port, conn.protocol))?;
+    Some((sender, receiver))
+}
+
+/// Handle settings every capture gets, the first one and any reopened later
+#[derive(Clone, Copy)]
+pub struct CaptureSettings {
+    pub snaplen: i32,
+    pub immediate_mode: bool,
+    pub buffer_mb: Option<u32>,
+}
+
+/// Longest wait between attempts to get a lost capture back
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Apply the settings, activate the handle and make reads non-blocking, so
+/// next_packet() returns right away when there is no traffic
+pub fn activate(cap: Capture<Inactive>, iface: &str, settings: CaptureSettings) -> Result<Capture<Active>, pcap::Error> {
+    let cap = if iface != "any" { cap.promisc(true) } else { cap };
+    let cap = cap.snaplen(settings.snaplen).immediate_mode(settings.immediate_mode);
+    let cap = match settings.buffer_mb {
+        Some(mb) => cap.buffer_size(mb as i32 * 1024 * 1024),
+        None => cap,
+    };
+    cap.timeout(100).open()?.setnonblock()
+}
+
+/// Why no capture is opened once --drop-privileges gave up what opening one takes
+pub const REOPEN_AFTER_DROP: &str = "--drop-privileges gave up what opening a capture takes, so none opens until monitetoring restarts";
+
+/// Open `iface` again after its capture was lost, or open another interface instead
+pub fn reopen(iface: &str, settings: CaptureSettings) -> Result<Capture<Active>, String> {
+    let cap = Capture::from_device(iface).map_err(|e| e.to_string())?;
+    activate(cap, iface, settings).map_err(|e| e.to_string())
+}
 
-        Some(Connection {
+/// Wait before reconnect attempt `attempt` (counted from 1): 1s, 2s, 4s, ... up to 30s
+pub fn reconnect_delay(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.saturating_sub(1).min(5)).min(MAX_RECONNECT_DELAY)
+}
+
+/// Keep trying to capture again after `error` took the capture on `iface` down.
+/// Each attempt is reported on `events`; an interface picked by the user
+/// (sent on `control`) replaces the target and is tried right away. Gives up
+/// only once `stopped` says nobody is reading the results anymore.
+pub fn recover(
+    iface: &str,
+    error: String,
+    settings: CaptureSettings,
+    events: &Sender<CaptureEvent>,
+    control: &Receiver<String>,
+    announce: bool,
+    stopped: impl Fn() -> bool,
+) -> Option<(Capture<Active>, String)> {
+    if announce {
+        eprintln!("⚠️  Capture on {} lost: {}; reconnecting", iface, error);
+    }
+    let mut target = iface.to_string();
+    let mut error = error;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let delay = reconnect_delay(attempt);
+        let _ = events.send(CaptureEvent::Lost(CaptureLoss {
+            iface: target.clone(),
+            error: error.clone(),
+            attempt,
+            retry_at: Instant::now() + delay,
+        }));
+        match control.recv_timeout(delay) {
+            Ok(picked) => {
+                target = picked;
+                attempt = 0;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // Only the TUI can pick an interface
+            Err(RecvTimeoutError::Disconnected) => std::thread::sleep(delay),
+        }
+        if stopped() {
+            return None;
+        }
+        match reopen(&target, settings) {
+            Ok(cap) => {
+                if announce {
+                    eprintln!("✅ Capturing on {} again", target);
+                }
+                let _ = events.send(CaptureEvent::Restored(target.clone()));
+                return Some((cap, target));
+            }
+            Err(e) => error = e,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(source: &str, source_port: u16, dest: &str, dest_port: u16) -> Connection {
+        Connection {
+            source_ip: source.parse().unwrap(),
             source_port,
+            dest_ip: dest.parse().unwrap(),
             dest_port,
-            source_ip,
-            dest_ip,
-            protocol: protocol.into(),
-        })
+            protocol: 6,
+        }
     }
 
-    // Try common decoders first
-    if let Ok(s) = SlicedPacket::from_ethernet(packet_data)
-        && let Some(conn) = from_sliced(s) { return Some(conn); }
-    if let Ok(s) = SlicedPacket::from_ip(packet_data)
-        && let Some(conn) = from_sliced(s) { return Some(conn); }
-
-    // Heuristic fallbacks for Linux cooked capture (SLL/SLL2)
-    // SLL v1: 16-byte header; SLL v2: 20-byte header. After header, IP payload starts.
-    // We attempt both offsets.
-    if packet_data.len() > 16
-        && let Ok(s) = SlicedPacket::from_ip(&packet_data[16..])
-            && let Some(conn) = from_sliced(s) { return Some(conn); }
-    if packet_data.len() > 20
-        && let Ok(s) = SlicedPacket::from_ip(&packet_data[20..])
-            && let Some(conn) = from_sliced(s) { return Some(conn); }
+    #[test]
+    fn finds_both_ends_of_local_traffic() {
+        let conn_map = HashMap::from([
+            (conn("127.0.0.1", 51000, "127.0.0.1", 5432), 100), // Client socket
+            (conn("127.0.0.1", 5432, "127.0.0.1", 51000), 200), // Accepted server socket
+            (conn("0.0.0.0", 8080, "0.0.0.0", 0), 300),         // Listener on every address
+            (conn("192.168.1.10", 40000, "192.168.1.10", 8080), 400),
+        ]);
+        // Connected sockets on both ends, looked up from either side
+        assert_eq!(lookup_local_peers(&conn_map, &conn("127.0.0.1", 51000, "127.0.0.1", 5432)), Some((100, 200)));
+        assert_eq!(lookup_local_peers(&conn_map, &conn("127.0.0.1", 5432, "127.0.0.1", 51000)), Some((200, 100)));
+        // A host address talking to itself, received by a wildcard listener
+        assert_eq!(lookup_local_peers(&conn_map, &conn("192.168.1.10", 40000, "192.168.1.10", 8080)), Some((400, 300)));
+        // One end isn't a local socket, or the traffic leaves the machine
+        assert_eq!(lookup_local_peers(&conn_map, &conn("127.0.0.1", 51001, "127.0.0.1", 5432)), None);
+        assert_eq!(lookup_local_peers(&conn_map, &conn("192.168.1.10", 40000, "93.184.216.34", 443)), None);
+    }
 
-    None
-}
\ No newline at end of file
+    #[test]
+    fn backs_off_reconnects() {
+        let delays: Vec<u64> = (1..=8).map(|attempt| reconnect_delay(attempt).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 30, 30, 30]);
+    }
+}
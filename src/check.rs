@@ -0,0 +1,104 @@
+// `--check`: a bounded capture that fails when traffic goes over budget, for
+// CI jobs and cron guards against bandwidth regressions. It measures for
+// `--duration` (5 s by default, like `--json`), prints what it saw and exits
+// with EXIT_EXCEEDED when a process, or all of them together, moved more
+// than `--max-process`/`--max-total` allow. Errors keep exiting with 1.
+
+use crate::delta::StatsDelta;
+use crate::units::format_bytes;
+
+/// Exit code of a check that found traffic over a threshold
+pub const EXIT_EXCEEDED: i32 = 2;
+
+#[derive(Clone, Copy, Default)]
+pub struct Thresholds {
+    pub per_process: Option<u64>, // Bytes sent and received by any one process
+    pub total: Option<u64>,       // Bytes of all processes together
+}
+
+impl Thresholds {
+    pub fn is_empty(&self) -> bool {
+        self.per_process.is_none() && self.total.is_none()
+    }
+}
+
+/// Traffic of one process over the check
+pub struct Usage {
+    pub pid: i32,
+    pub name: String,
+    pub bytes: u64,
+    pub tunnel: bool, // Its traffic is the encrypted copy of the others', so the total leaves it out
+}
+
+pub fn usages(stats: &StatsDelta) -> Vec<Usage> {
+    let mut usages: Vec<Usage> = stats.iter()
+        .map(|(pid, update)| Usage {
+            pid: *pid,
+            name: update.info.name.clone(),
+            bytes: update.info.sent + update.info.received,
+            tunnel: update.info.tunnel,
+        })
+        .collect();
+    usages.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.pid.cmp(&b.pid)));
+    usages
+}
+
+/// "firefox (PID 1234): 12.0 MB, limit 10.0 MB" for each threshold that was exceeded
+pub fn offenders(thresholds: &Thresholds, usages: &[Usage]) -> Vec<String> {
+    let mut offenders = Vec::new();
+    if let Some(limit) = thresholds.per_process {
+        for usage in usages.iter().filter(|usage| usage.bytes > limit) {
+            offenders.push(format!(
+                "{} (PID {}): {}, limit {}",
+                usage.name, usage.pid, format_bytes(usage.bytes), format_bytes(limit)
+            ));
+        }
+    }
+    let total: u64 = usages.iter().filter(|usage| !usage.tunnel).map(|usage| usage.bytes).sum();
+    if let Some(limit) = thresholds.total
+        && total > limit {
+        offenders.push(format!("All processes: {}, limit {}", format_bytes(total), format_bytes(limit)));
+    }
+    offenders
+}
+
+/// The report to print and the exit code to end with
+pub fn evaluate(thresholds: &Thresholds, stats: &StatsDelta, duration: std::time::Duration) -> (String, i32) {
+    let usages = usages(stats);
+    let total: u64 = usages.iter().filter(|usage| !usage.tunnel).map(|usage| usage.bytes).sum();
+    let offenders = offenders(thresholds, &usages);
+    let mut lines = vec![format!(
+        "Checked {} processes over {:.1} s: {} in total",
+        usages.len(), duration.as_secs_f64(), format_bytes(total)
+    )];
+    if offenders.is_empty() {
+        lines.push("✅ Within thresholds".to_string());
+        return (lines.join("\n"), 0);
+    }
+    lines.push(format!("❌ {} over threshold:", offenders.len()));
+    lines.extend(offenders.iter().map(|offender| format!("  {}", offender)));
+    (lines.join("\n"), EXIT_EXCEEDED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(pid: i32, name: &str, bytes: u64, tunnel: bool) -> Usage {
+        Usage { pid, name: name.to_string(), bytes, tunnel }
+    }
+
+    #[test]
+    fn lists_processes_and_total_over_threshold() {
+        let usages = [usage(1, "rsync", 3000, false), usage(2, "curl", 900, false), usage(3, "wireguard", 4000, true)];
+        let thresholds = Thresholds { per_process: Some(2048), total: Some(3500) };
+        assert_eq!(offenders(&thresholds, &usages), vec![
+            "rsync (PID 1): 2.9 KB, limit 2.0 KB".to_string(),
+            "wireguard (PID 3): 3.9 KB, limit 2.0 KB".to_string(),
+            "All processes: 3.8 KB, limit 3.4 KB".to_string(),
+        ]);
+        // The tunnel's copy doesn't count towards the total
+        let thresholds = Thresholds { per_process: None, total: Some(4000) };
+        assert!(offenders(&thresholds, &usages).is_empty());
+    }
+}
@@ -0,0 +1,285 @@
+// Traffic classification: rules from the config's `classification` list sort
+// connections into categories such as "Streaming", "Backups" or "Updates" by
+// port, remote network and the server name (SNI) a TLS client asks for. The
+// first matching rule wins. A connection is classified once, when it is first
+// seen, and again if the ClientHello of a TLS handshake names its server; the
+// bytes of every packet then count towards the category of its process.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use serde::{Deserialize, Serialize};
+use crate::types::Connection;
+
+/// Connections remembered before the oldest knowledge is dropped and relearned
+const MAX_FLOWS: usize = 8192;
+
+/// Packets of a TCP connection looked at for a ClientHello before giving up
+const SNI_WITHIN: u8 = 8;
+
+/// A category and what its traffic looks like; every list that is set has to match
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClassRule {
+    pub category: String,
+    /// Local or remote ports, e.g. "443" or "8000-8100"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<String>,
+    /// Remote addresses or networks, e.g. "10.0.0.0/8" or "2001:db8::/32"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub networks: Vec<String>,
+    /// TLS server names; "*.example.com" also covers example.com itself
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sni: Vec<String>,
+}
+
+struct Rule {
+    category: usize,
+    ports: Vec<(u16, u16)>,
+    networks: Vec<(IpAddr, u8)>,
+    sni: Vec<String>,
+}
+
+fn parse_ports(input: &str) -> Option<(u16, u16)> {
+    let (low, high) = input.split_once('-').unwrap_or((input, input));
+    let (low, high) = (low.trim().parse().ok()?, high.trim().parse().ok()?);
+    (low <= high).then_some((low, high))
+}
+
+fn parse_network(input: &str) -> Option<(IpAddr, u8)> {
+    let (address, prefix) = match input.split_once('/') {
+        Some((address, prefix)) => (address.trim().parse::<IpAddr>().ok()?, Some(prefix.trim().parse::<u8>().ok()?)),
+        None => (input.trim().parse::<IpAddr>().ok()?, None),
+    };
+    let bits = if address.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix.unwrap_or(bits);
+    (prefix <= bits).then_some((address, prefix))
+}
+
+fn in_network(ip: IpAddr, (network, prefix): (IpAddr, u8)) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+fn sni_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(domain) => name == domain || name.strip_suffix(domain).is_some_and(|rest| rest.ends_with('.')),
+        None => name == pattern,
+    }
+}
+
+impl Rule {
+    /// `flow` is oriented local -> remote
+    fn matches(&self, flow: &Connection, sni: Option<&str>) -> bool {
+        (self.ports.is_empty() || self.ports.iter().any(|(low, high)| {
+            (*low..=*high).contains(&flow.source_port) || (*low..=*high).contains(&flow.dest_port)
+        }))
+            && (self.networks.is_empty() || self.networks.iter().any(|network| in_network(flow.dest_ip, *network)))
+            && (self.sni.is_empty() || sni.is_some_and(|name| self.sni.iter().any(|pattern| sni_matches(pattern, name))))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct FlowState {
+    category: Option<usize>,
+    packets: u8,
+    settled: bool, // No ClientHello to wait for any more
+}
+
+pub struct Classifier {
+    categories: Vec<String>,
+    rules: Vec<Rule>,
+    wants_sni: bool,
+    flows: HashMap<Connection, FlowState>,
+}
+
+impl Classifier {
+    /// Compile the config's rules; returns the problems with the ones left out
+    pub fn new(rules: &[ClassRule]) -> (Self, Vec<String>) {
+        let mut categories: Vec<String> = Vec::new();
+        let mut compiled = Vec::new();
+        let mut problems = Vec::new();
+        for rule in rules {
+            let category = rule.category.trim();
+            let ports: Option<Vec<_>> = rule.ports.iter().map(|ports| parse_ports(ports)).collect();
+            let networks: Option<Vec<_>> = rule.networks.iter().map(|network| parse_network(network)).collect();
+            let problem = if category.is_empty() {
+                Some("a rule without a category".to_string())
+            } else if ports.is_none() {
+                Some(format!("{}: ports must look like 443 or 8000-8100", category))
+            } else if networks.is_none() {
+                Some(format!("{}: networks must look like 10.0.0.0/8 or 2001:db8::1", category))
+            } else if rule.ports.is_empty() && rule.networks.is_empty() && rule.sni.is_empty() {
+                Some(format!("{}: no ports, networks or sni to match", category))
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                problems.push(problem);
+                continue;
+            }
+            let index = categories.iter().position(|known| known == category).unwrap_or_else(|| {
+                categories.push(category.to_string());
+                categories.len() - 1
+            });
+            compiled.push(Rule {
+                category: index,
+                ports: ports.unwrap_or_default(),
+                networks: networks.unwrap_or_default(),
+                sni: rule.sni.iter().map(|pattern| pattern.trim().to_ascii_lowercase()).collect(),
+            });
+        }
+        let wants_sni = compiled.iter().any(|rule| !rule.sni.is_empty());
+        (Classifier { categories, rules: compiled, wants_sni, flows: HashMap::new() }, problems)
+    }
+
+    /// Category names, in the order the config first mentions them
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    pub fn name(&self, category: usize) -> &str {
+        &self.categories[category]
+    }
+
+    fn matching(&self, flow: &Connection, sni: Option<&str>) -> Option<usize> {
+        self.rules.iter().find(|rule| rule.matches(flow, sni)).map(|rule| rule.category)
+    }
+
+    /// Category of a packet of `flow` (oriented local -> remote); `payload` is
+    /// only looked at while a TCP connection may still send its ClientHello
+    pub fn classify<'a>(&mut self, flow: &Connection, payload: impl FnOnce() -> Option<&'a [u8]>) -> Option<usize> {
+        if self.rules.is_empty() {
+            return None;
+        }
+        let mut state = match self.flows.get(flow) {
+            Some(state) if state.settled => return state.category,
+            Some(state) => *state,
+            None => FlowState {
+                category: self.matching(flow, None),
+                packets: 0,
+                settled: !self.wants_sni || flow.protocol != 6,
+            },
+        };
+        if !state.settled {
+            match payload().and_then(client_hello_sni) {
+                Some(name) => {
+                    state.category = self.matching(flow, Some(&name));
+                    state.settled = true;
+                }
+                None => {
+                    state.packets += 1;
+                    state.settled = state.packets >= SNI_WITHIN;
+                }
+            }
+        }
+        if self.flows.len() >= MAX_FLOWS && !self.flows.contains_key(flow) {
+            self.flows.clear();
+        }
+        self.flows.insert(*flow, state);
+        state.category
+    }
+}
+
+/// Server name of a TLS ClientHello, if `payload` starts with one that names it
+pub fn client_hello_sni(payload: &[u8]) -> Option<String> {
+    fn u16_at(data: &[u8], at: usize) -> Option<usize> {
+        Some(u16::from_be_bytes([*data.get(at)?, *data.get(at + 1)?]) as usize)
+    }
+
+    // Handshake record carrying a ClientHello
+    if payload.first() != Some(&0x16) || payload.get(5) != Some(&0x01) {
+        return None;
+    }
+    // Record (5) and handshake (4) headers, client version (2) and random (32)
+    let mut at = 5 + 4 + 2 + 32;
+    at += 1 + *payload.get(at)? as usize; // Session ID
+    at += 2 + u16_at(payload, at)?;       // Cipher suites
+    at += 1 + *payload.get(at)? as usize; // Compression methods
+    let extensions_end = (at + 2 + u16_at(payload, at)?).min(payload.len());
+    at += 2;
+    while at + 4 <= extensions_end {
+        let (kind, length) = (u16_at(payload, at)?, u16_at(payload, at + 2)?);
+        at += 4;
+        if kind == 0 {
+            // Server name list: list length (2), name type (1, 0 for host names), name length (2)
+            if *payload.get(at + 2)? != 0 {
+                return None;
+            }
+            let name_length = u16_at(payload, at + 3)?;
+            let name = payload.get(at + 5..at + 5 + name_length)?;
+            return std::str::from_utf8(name).ok().map(str::to_ascii_lowercase);
+        }
+        at += length;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(protocol: u8, dest: &str, dest_port: u16) -> Connection {
+        Connection {
+            source_port: 50000,
+            dest_port,
+            source_ip: "192.168.1.2".parse().unwrap(),
+            dest_ip: dest.parse().unwrap(),
+            protocol,
+        }
+    }
+
+    fn rule(category: &str, ports: &[&str], networks: &[&str], sni: &[&str]) -> ClassRule {
+        let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        ClassRule { category: category.to_string(), ports: strings(ports), networks: strings(networks), sni: strings(sni) }
+    }
+
+    fn client_hello(name: &str) -> Vec<u8> {
+        let length = |bytes: &[u8]| (bytes.len() as u16).to_be_bytes();
+        let entry = [&[0][..], &length(name.as_bytes()), name.as_bytes()].concat();
+        let list = [&length(&entry)[..], &entry].concat();
+        let extension = [&[0, 0][..], &length(&list), &list].concat();
+        // Version, random, no session ID, one cipher suite, no compression, the extension
+        let hello = [&[0x03, 0x03][..], &[0; 32], &[0, 0, 2, 0x13, 0x01, 1, 0], &length(&extension), &extension].concat();
+        let handshake = [&[0x01, 0][..], &length(&hello), &hello].concat();
+        [&[0x16, 0x03, 0x01][..], &length(&handshake), &handshake].concat()
+    }
+
+    #[test]
+    fn reads_the_server_name_of_a_client_hello() {
+        assert_eq!(client_hello_sni(&client_hello("Video.Example.com")).as_deref(), Some("video.example.com"));
+        let hello = client_hello("video.example.com");
+        assert_eq!(client_hello_sni(&hello[..hello.len() - 3]), None);
+        assert_eq!(client_hello_sni(b"GET / HTTP/1.1\r\n"), None);
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let (mut classifier, problems) = Classifier::new(&[
+            rule("Streaming", &[], &[], &["*.example.com"]),
+            rule("Backups", &["22"], &["10.0.0.0/8"], &[]),
+            rule("Updates", &["8000-8100"], &[], &[]),
+            rule("Broken", &["99999"], &[], &[]),
+        ]);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(classifier.categories(), ["Streaming", "Backups", "Updates"]);
+
+        assert_eq!(classifier.classify(&flow(6, "10.1.2.3", 22), || None), Some(1));
+        assert_eq!(classifier.classify(&flow(6, "192.0.2.1", 22), || None), None);
+        assert_eq!(classifier.classify(&flow(17, "192.0.2.1", 8080), || None), Some(2));
+
+        // A TLS connection is classified again once its ClientHello names the server
+        let tls = flow(6, "192.0.2.9", 443);
+        assert_eq!(classifier.classify(&tls, || None), None);
+        let hello = client_hello("example.com");
+        assert_eq!(classifier.classify(&tls, || Some(&hello[..])), Some(0));
+        assert_eq!(classifier.classify(&tls, || None), Some(0));
+    }
+}
@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crate::types::Alert;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -13,6 +13,50 @@ fn default_frequent_connection_threshold() -> usize {
     20
 }
 
+fn default_memory_budget_mb() -> u64 {
+    crate::types::DEFAULT_MEMORY_BUDGET_MB
+}
+
+fn default_history_window_secs() -> u64 {
+    crate::types::DEFAULT_HISTORY_WINDOW_SECS
+}
+
+fn default_sample_interval_ms() -> u64 {
+    crate::types::DEFAULT_SAMPLE_INTERVAL_MS
+}
+
+fn default_refresh_interval_ms() -> u64 {
+    crate::types::DEFAULT_REFRESH_INTERVAL_MS
+}
+
+fn default_quota_mb() -> u64 {
+    crate::types::DEFAULT_QUOTA_MB
+}
+
+fn default_cleanup_interval_secs() -> u64 {
+    crate::types::DEFAULT_CLEANUP_INTERVAL_SECS
+}
+
+fn default_idle_after_secs() -> u64 {
+    crate::types::DEFAULT_IDLE_AFTER_SECS
+}
+
+fn default_snaplen() -> i32 {
+    crate::types::DEFAULT_SNAPLEN
+}
+
+fn default_packet_budget() -> usize {
+    crate::types::DEFAULT_PACKET_BUDGET
+}
+
+fn default_packet_budget_mb() -> usize {
+    crate::types::DEFAULT_PACKET_BUDGET_MB
+}
+
+pub fn default_protected() -> Vec<String> {
+    crate::protect::DEFAULT_PROTECTED.iter().map(ToString::to_string).collect()
+}
+
 #[derive(Parser)]
 #[command(version)]
 pub struct Cli {
@@ -21,10 +65,140 @@ pub struct Cli {
     pub iface: Option<String>,
     #[arg(long, help = "Output in JSON format instead of TUI")]
     pub json: bool,
+    #[arg(long, value_name = "VERSION", value_parser = clap::value_parser!(crate::json_output::JsonSchema), help = "JSON output layout: v1, an array of processes, or v2, an object with capture metadata and protocol breakdowns [default: v1]")]
+    pub json_schema: Option<crate::json_output::JsonSchema>,
+    #[arg(long, help = "List each process's connections in --json-schema v2 output")]
+    pub json_connections: bool,
+    #[arg(long, help = "Measure for --duration, print processes over --max-process/--max-total and exit with 2 if there are any")]
+    pub check: bool,
+    #[arg(long, value_name = "SIZE", value_parser = crate::units::parse_bytes, requires = "check", help = "With --check: bytes any one process may send and receive, e.g. 50MB")]
+    pub max_process: Option<u64>,
+    #[arg(long, value_name = "SIZE", value_parser = crate::units::parse_bytes, requires = "check", help = "With --check: bytes all processes together may send and receive, e.g. 200MB")]
+    pub max_total: Option<u64>,
     #[arg(long, help = "Enable container detection and display")]
     pub containers: bool,
     #[arg(long, help = "Reset saved configuration and exit")]
     pub reset: bool,
+    #[arg(long, help = "Give the binary the capabilities packet capture needs (CAP_NET_RAW, CAP_NET_ADMIN) so it runs without sudo, then exit (Linux only)")]
+    pub setup_capabilities: bool,
+    #[arg(long, help = "Give up root or capabilities once the capture is open (Linux only)")]
+    pub drop_privileges: bool,
+    #[arg(long, value_name = "MB", value_parser = clap::value_parser!(u64).range(1..), help = "Memory budget for chart/packet histories in MB [default: 256]")]
+    pub memory_budget: Option<u64>,
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(60..), help = "Seconds of chart history to keep, at least 60 [default: 300]")]
+    pub history_window: Option<u64>,
+    #[arg(long, value_name = "FILE", help = "Keep chart history older than the history window in this file, a ring that overwrites its oldest data once full; `<`/`>` scroll the chart back to it")]
+    pub history_store: Option<PathBuf>,
+    #[arg(long, value_name = "MB", value_parser = clap::value_parser!(u64).range(1..), help = "Size of the --history-store file in MB [default: 64]")]
+    pub history_store_size: Option<u64>,
+    #[arg(long, help = "Start with the debug overlay shown: frame times, update backlog, capture lag and history memory (F12 toggles it)")]
+    pub debug_ui: bool,
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(100..), help = "Milliseconds between chart history samples [default: 100]")]
+    pub sample_interval: Option<u64>,
+    #[arg(long, value_name = "SECS", help = "Keep exited processes in the table, greyed out with their final totals, for this many seconds [default: 0]")]
+    pub keep_exited: Option<u64>,
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..), help = "Seconds between checks for exited processes [default: 5]")]
+    pub cleanup_interval: Option<u64>,
+    #[arg(long, value_name = "SECS", help = "Seconds without traffic after which `z` hides a process from the table [default: 30]")]
+    pub idle_after: Option<u64>,
+    #[arg(long, value_name = "BYTES", value_parser = clap::value_parser!(i32).range(crate::types::MIN_SNAPLEN as i64..=crate::types::MAX_SNAPLEN as i64), help = "Bytes captured per packet; small values (e.g. 128) capture headers only and save CPU [default: 65535]")]
+    pub snaplen: Option<i32>,
+    #[arg(long, value_name = "MB", value_parser = clap::value_parser!(u32).range(1..=1024), help = "Kernel capture buffer size in MB; raise it if packets are dropped [default: libpcap's]")]
+    pub buffer_size: Option<u32>,
+    #[arg(long, help = "Deliver packets as soon as they arrive instead of in batches (more CPU, lower latency)")]
+    pub immediate: bool,
+    #[arg(long, help = "Count VPN daemons (OpenVPN, WireGuard, Tailscale...) in the totals, even though their traffic is the tunnelled copy of other processes'")]
+    pub count_tunnel_traffic: bool,
+    #[arg(long, value_name = "MODE", help = "What process traffic counts: wire (whole frames, headers included) or payload (TCP/UDP payload only) [default: wire]")]
+    pub count_bytes: Option<crate::accounting::ByteCounting>,
+    #[arg(long, value_name = "UNITS", help = "Byte units: binary (KB = 1024 B), iec (KiB, MiB) or si (kB = 1000 B) [default: binary]")]
+    pub units: Option<crate::units::ByteUnits>,
+    #[arg(long, help = "Show rates in bits per second (Mbit/s) instead of bytes")]
+    pub bit_rates: bool,
+    #[arg(long, value_name = "PRICE", help = "Price of one GB of traffic; shows estimated costs in the Overview (e.g. 0.09 for cloud egress)")]
+    pub cost_per_gb: Option<f64>,
+    #[arg(long, value_name = "CODE", help = "Currency of the cost estimates, an ISO code such as EUR or a symbol [default: USD]")]
+    pub currency: Option<String>,
+    #[arg(long, value_name = "DIRECTION", help = "Traffic the provider charges for: both, sent or received [default: both]")]
+    pub cost_billed: Option<crate::cost::Billed>,
+    #[arg(long, value_name = "AMOUNT", help = "Raise a quota alert once the estimated cost passes this amount")]
+    pub cost_budget: Option<f64>,
+    #[arg(long, value_name = "PACKETS", value_parser = clap::value_parser!(u64).range(1_000..), help = "Packets kept across all per-process packet histories [default: 200000]")]
+    pub packet_budget: Option<u64>,
+    #[arg(long, value_name = "MB", value_parser = clap::value_parser!(u64).range(1..=4096), help = "Memory for all per-process packet histories in MB [default: 64]")]
+    pub packet_budget_mb: Option<u64>,
+    #[arg(long, value_name = "TARGET", help = "Stream msgpack snapshots to stdout ('-') or a TCP listen address (e.g. 127.0.0.1:7878) instead of the TUI")]
+    pub stream: Option<String>,
+    #[arg(long, value_name = "ADDR", num_args = 0..=1, default_missing_value = "127.0.0.1:7878", conflicts_with = "stream", help = "Run as a headless collector for --aggregate, serving snapshots on this address; other machines need e.g. 0.0.0.0:7878, and it has no authentication [default: 127.0.0.1:7878]")]
+    pub agent: Option<String>,
+    #[arg(long, value_name = "HOSTS", value_delimiter = ',', conflicts_with_all = ["demo", "json"], help = "Show the processes of these hosts running --agent (host[:port], comma-separated) instead of capturing locally")]
+    pub aggregate: Vec<String>,
+    #[arg(long, value_name = "SINK=URL", conflicts_with = "json", help = "Push per-process and total metrics to influx=http://host:8086/write?db=NAME or otlp=http://host:4318/v1/metrics (repeatable)")]
+    pub metrics: Vec<String>,
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..), help = "Seconds between metrics pushes [default: 10]")]
+    pub metrics_interval: Option<u64>,
+    #[arg(long, help = "Also record every alert and kill in the systemd journal, or syslog without journald (Linux only)")]
+    pub syslog: bool,
+
+    #[arg(long, help = "Only log what Kill, Block and custom-command alert actions would do, without running them")]
+    pub dry_run: bool,
+
+    #[arg(long, value_name = "ENTRY", value_delimiter = ',', help = "Also refuse to kill these processes: a name, pid:<n> or user:<name> (adds to the config file's protected list)")]
+    pub protect: Vec<String>,
+
+    #[arg(long, help = "Allow killing processes on the protected list")]
+    pub allow_protected_kills: bool,
+    #[arg(long, value_name = "PID", help = "Only account traffic of this process")]
+    pub pid: Option<i32>,
+    #[arg(long, value_name = "REGEX", help = "Only account traffic of processes whose name matches this regular expression")]
+    pub process_name: Option<String>,
+    #[arg(long, value_name = "NAME", help = "Only account traffic of processes in this container (implies --containers, Linux only)")]
+    pub container: Option<String>,
+    #[arg(long, value_name = "DURATION", value_parser = crate::schedule::parse_duration, help = "Stop monitoring after this long, e.g. 90s, 10m or 1h30m (JSON output then covers the whole run) [default for --json and --check: 5s]")]
+    pub duration: Option<std::time::Duration>,
+    #[arg(long, value_name = "HH:MM-HH:MM", help = "Wait for this daily time window (local time) and stop when it ends, e.g. 02:00-03:00")]
+    pub schedule: Option<crate::schedule::CaptureWindow>,
+    #[arg(long, help = "Print a summary of the session (totals, top processes and destinations, alerts, peak rates) to stderr on exit")]
+    pub summary: bool,
+    #[arg(long, value_name = "PATH", help = "Write the session summary to this file on exit")]
+    pub summary_file: Option<PathBuf>,
+    #[arg(long, value_name = "PATH", help = "Write the session as a standalone HTML report (charts, processes, top hosts, alert timeline) on exit")]
+    pub report: Option<PathBuf>,
+    #[arg(long, help = "Run with simulated processes and traffic (no root or capture needed)")]
+    pub demo: bool,
+    #[cfg(target_os = "windows")]
+    #[arg(long, help = "Estimate per-process TCP traffic from Windows' connection statistics instead of capturing packets (no Npcap needed)")]
+    pub estats: bool,
+    #[cfg(feature = "web")]
+    #[arg(long, value_name = "ADDR", help = "Serve a web dashboard on this address (e.g. 127.0.0.1:8080) instead of the TUI; it has no authentication")]
+    pub web: Option<String>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run a command, monitor the traffic of its process tree and print a summary when it exits
+    Run {
+        #[arg(long, help = "Keep running the command as root under sudo instead of as the user who ran sudo")]
+        as_root: bool,
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+    /// Compare two --json snapshots and list the processes whose traffic grew most
+    Diff {
+        #[arg(value_name = "BEFORE")]
+        before: PathBuf,
+        #[arg(value_name = "AFTER")]
+        after: PathBuf,
+        #[arg(long, value_name = "N", default_value_t = 20, help = "Processes to list")]
+        top: usize,
+    },
+    /// Check privileges, packet capture, socket attribution and container sockets, and measure overhead
+    Doctor {
+        #[arg(long, value_name = "SECS", default_value_t = 3, help = "Seconds to listen for packets")]
+        listen: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -42,6 +216,87 @@ pub struct SavedConfig {
     pub frequent_connection_threshold: usize,
     #[serde(default)]
     pub setup_offered: bool,
+    #[serde(default = "default_memory_budget_mb")]
+    pub memory_budget_mb: u64,
+    #[serde(default = "default_history_window_secs")]
+    pub history_window_secs: u64,
+    #[serde(default = "default_sample_interval_ms")]
+    pub sample_interval_ms: u64,
+    #[serde(default = "default_refresh_interval_ms")]
+    pub refresh_interval_ms: u64, // Time between UI refreshes
+    #[serde(default = "default_quota_mb")]
+    pub quota_mb: u64, // Data quota of the overview
+    #[serde(default)]
+    pub exited_retention_secs: u64,
+    #[serde(default = "default_cleanup_interval_secs")]
+    pub cleanup_interval_secs: u64,
+    #[serde(default = "default_idle_after_secs")]
+    pub idle_after_secs: u64,
+    #[serde(default = "default_snaplen")]
+    pub snaplen: i32,
+    #[serde(default)]
+    pub capture_buffer_mb: Option<u32>, // None keeps libpcap's default
+    #[serde(default)]
+    pub immediate_mode: bool,
+    #[serde(default = "default_packet_budget")]
+    pub packet_budget: usize,
+    #[serde(default = "default_packet_budget_mb")]
+    pub packet_budget_mb: usize,
+    #[serde(default)]
+    pub theme: crate::ui::theme::Theme,
+    #[serde(default)]
+    pub keys: std::collections::HashMap<String, String>, // Action name -> key, see `ui::keymap`
+    #[serde(default)]
+    pub anomaly: crate::anomaly::AnomalySettings,
+    #[serde(default)]
+    pub cost: crate::cost::CostSettings,
+    #[serde(default)]
+    pub units: crate::units::UnitStyle,
+    #[serde(default = "default_protected")]
+    pub protected: Vec<String>, // Process names, `pid:<n>` or `user:<name>` that are never killed
+    #[serde(default)]
+    pub pinned: Vec<String>, // Process names listed above the rest of the table (`f`)
+    #[serde(default)]
+    pub classification: Vec<crate::classify::ClassRule>, // Traffic categories, first match wins
+    #[serde(default)]
+    pub count_bytes: crate::accounting::ByteCounting,
+}
+
+impl Default for SavedConfig {
+    fn default() -> Self {
+        SavedConfig {
+            interface: "any".to_string(),
+            json_mode: false,
+            containers_mode: false,
+            show_total_columns: false,
+            alerts: vec![],
+            large_packet_threshold: default_large_packet_threshold(),
+            frequent_connection_threshold: default_frequent_connection_threshold(),
+            setup_offered: false,
+            memory_budget_mb: default_memory_budget_mb(),
+            history_window_secs: default_history_window_secs(),
+            sample_interval_ms: default_sample_interval_ms(),
+            refresh_interval_ms: default_refresh_interval_ms(),
+            quota_mb: default_quota_mb(),
+            exited_retention_secs: 0,
+            cleanup_interval_secs: default_cleanup_interval_secs(),
+            idle_after_secs: default_idle_after_secs(),
+            snaplen: default_snaplen(),
+            capture_buffer_mb: None,
+            immediate_mode: false,
+            packet_budget: default_packet_budget(),
+            packet_budget_mb: default_packet_budget_mb(),
+            theme: crate::ui::theme::Theme::default(),
+            keys: std::collections::HashMap::new(),
+            anomaly: crate::anomaly::AnomalySettings::default(),
+            cost: crate::cost::CostSettings::default(),
+            units: crate::units::UnitStyle::default(),
+            protected: default_protected(),
+            pinned: vec![],
+            classification: vec![],
+            count_bytes: crate::accounting::ByteCounting::default(),
+        }
+    }
 }
 
 pub fn get_config_path() -> Result<PathBuf, io::Error> {
@@ -68,13 +323,21 @@ pub fn save_config(config: &SavedConfig) -> Result<(), io::Error> {
 }
 
 pub fn load_config() -> Option<SavedConfig> {
-    let config_path = get_config_path().ok()?;
+    read_config().ok().flatten()
+}
+
+/// The saved config, `None` if there is no config file yet, or why the file can't be used
+pub fn read_config() -> Result<Option<SavedConfig>, String> {
+    let config_path = get_config_path().map_err(|e| format!("can't find the config file: {}", e))?;
     if !config_path.exists() {
-        return None;
+        return Ok(None);
     }
-    
-    let content = fs::read_to_string(config_path).ok()?;
-    serde_json::from_str(&content).ok()
+
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| format!("can't read {}: {}", config_path.display(), e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("{} is not valid: {}", config_path.display(), e))
 }
 
 pub fn reset_config() -> Result<bool, io::Error> {
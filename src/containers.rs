@@ -0,0 +1,183 @@
+// Details about the containers processes run in, beyond the short ID of the
+// Container column: the image, the Compose project and service, and the
+// Kubernetes pod and namespace. The Docker or Podman API socket answers for
+// their own containers; Kubernetes containers run by containerd or CRI-O have
+// no such socket, so their pod is named from the container's hostname and its
+// namespace read from the service account mounted into it. Lookups run on a
+// thread of their own, once per container, so a slow socket never holds up
+// the UI.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc;
+
+/// Docker's socket, then Podman's Docker-compatible one
+#[cfg(target_os = "linux")]
+pub const API_SOCKETS: [&str; 2] = ["/var/run/docker.sock", "/run/podman/podman.sock"];
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct ContainerMeta {
+    pub image: Option<String>,
+    pub compose_project: Option<String>,
+    pub compose_service: Option<String>,
+    pub pod: Option<String>,
+    pub namespace: Option<String>,
+}
+
+impl ContainerMeta {
+    /// "namespace/pod", as kubectl users know it
+    pub fn pod_name(&self) -> Option<String> {
+        let pod = self.pod.as_ref()?;
+        Some(match &self.namespace {
+            Some(namespace) => format!("{}/{}", namespace, pod),
+            None => pod.clone(),
+        })
+    }
+
+    /// Most telling name for the Container column: the pod, the Compose service or the image
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub fn label(&self) -> Option<String> {
+        match (self.pod_name(), &self.compose_project, &self.compose_service) {
+            (Some(pod), _, _) => Some(pod),
+            (None, Some(project), Some(service)) => Some(format!("{}/{}", project, service)),
+            _ => self.image.clone(),
+        }
+    }
+
+    /// Pick the fields out of a container as the Docker API's inspect call returns it
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn from_inspect(inspect: &serde_json::Value) -> Self {
+        let config = &inspect["Config"];
+        let label = |name: &str| config["Labels"][name].as_str().map(str::to_string);
+        ContainerMeta {
+            image: config["Image"].as_str().map(str::to_string),
+            compose_project: label("com.docker.compose.project"),
+            compose_service: label("com.docker.compose.service"),
+            pod: label("io.kubernetes.pod.name"),
+            namespace: label("io.kubernetes.pod.namespace"),
+        }
+    }
+}
+
+pub struct ContainerResolver {
+    requested: HashSet<String>,
+    requests: mpsc::Sender<(String, i32)>,
+    answers: mpsc::Receiver<(String, ContainerMeta)>,
+    known: HashMap<String, ContainerMeta>, // Container column value -> what the lookup found
+}
+
+impl ContainerResolver {
+    pub fn new() -> Self {
+        let (requests, pending) = mpsc::channel::<(String, i32)>();
+        let (answer, answers) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (container, pid) in pending {
+                let meta = lookup(&container, pid).unwrap_or_default();
+                if answer.send((container, meta)).is_err() {
+                    return;
+                }
+            }
+        });
+        ContainerResolver { requested: HashSet::new(), requests, answers, known: HashMap::new() }
+    }
+
+    /// Look `container` up unless that was done before; `pid` is one of its processes
+    pub fn request(&mut self, container: &str, pid: i32) {
+        if self.requested.insert(container.to_string()) {
+            let _ = self.requests.send((container.to_string(), pid));
+        }
+    }
+
+    /// Take in the lookups that finished since the last call
+    pub fn poll(&mut self) {
+        while let Ok((container, meta)) = self.answers.try_recv() {
+            self.known.insert(container, meta);
+        }
+    }
+
+    pub fn get(&self, container: &str) -> Option<&ContainerMeta> {
+        self.known.get(container)
+    }
+}
+
+/// `container` is the Container column's "runtime:id"
+#[cfg(target_os = "linux")]
+fn lookup(container: &str, pid: i32) -> Option<ContainerMeta> {
+    let (runtime, id) = container.split_once(':')?;
+    match runtime {
+        "docker" | "podman" => API_SOCKETS.iter().find_map(|socket| inspect(socket, id)),
+        "k8s" => Some(kubernetes_meta(pid)),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn lookup(_container: &str, _pid: i32) -> Option<ContainerMeta> {
+    None
+}
+
+/// Ask an API socket about a container; both runtimes take a short ID
+#[cfg(target_os = "linux")]
+fn inspect(socket: &str, id: &str) -> Option<ContainerMeta> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+
+    let mut stream = UnixStream::connect(socket).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(2))).ok()?;
+    stream.set_write_timeout(Some(Duration::from_secs(2))).ok()?;
+    // HTTP/1.0 gets the body back in one piece and the connection closed after it
+    write!(stream, "GET /containers/{}/json HTTP/1.0\r\nHost: localhost\r\n\r\n", id).ok()?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    if head.split_whitespace().nth(1) != Some("200") {
+        return None;
+    }
+    let inspect: serde_json::Value = serde_json::from_str(body).ok()?;
+    Some(ContainerMeta::from_inspect(&inspect))
+}
+
+/// Kubernetes sets a pod's hostname to its name and mounts the namespace
+/// into every container with a service account
+#[cfg(target_os = "linux")]
+fn kubernetes_meta(pid: i32) -> ContainerMeta {
+    let environ = std::fs::read(format!("/proc/{}/environ", pid)).unwrap_or_default();
+    let pod = environ
+        .split(|byte| *byte == 0)
+        .find_map(|variable| variable.strip_prefix(b"HOSTNAME="))
+        .map(|name| String::from_utf8_lossy(name).into_owned());
+    let namespace = std::fs::read_to_string(format!("/proc/{}/root/var/run/secrets/kubernetes.io/serviceaccount/namespace", pid))
+        .ok()
+        .map(|namespace| namespace.trim().to_string())
+        .filter(|namespace| !namespace.is_empty());
+    ContainerMeta { pod, namespace, ..Default::default() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_inspect_output_and_labels_by_what_is_known() {
+        let inspect = serde_json::json!({
+            "Id": "4f66ad9a0b2e",
+            "Config": {
+                "Image": "nginx:1.27",
+                "Labels": {
+                    "com.docker.compose.project": "shop",
+                    "com.docker.compose.service": "web"
+                }
+            }
+        });
+        let meta = ContainerMeta::from_inspect(&inspect);
+        assert_eq!(meta.image.as_deref(), Some("nginx:1.27"));
+        assert_eq!(meta.label().as_deref(), Some("shop/web"));
+
+        let plain = ContainerMeta::from_inspect(&serde_json::json!({ "Config": { "Image": "redis", "Labels": null } }));
+        assert_eq!(plain.label().as_deref(), Some("redis"));
+
+        let pod = ContainerMeta { pod: Some("api-7d9f".to_string()), namespace: Some("prod".to_string()), ..Default::default() };
+        assert_eq!(pod.label().as_deref(), Some("prod/api-7d9f"));
+        assert_eq!(ContainerMeta::default().label(), None);
+    }
+}
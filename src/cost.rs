@@ -0,0 +1,154 @@
+// Cost estimation for metered connections (cloud egress, mobile hotspots): a
+// price per GB turns byte counts into money, per process and in total, and a
+// budget raises a quota alert once the estimate passes it. Settings live in
+// the `cost` section of the config; `--cost-per-gb` and friends override them
+// for one run. Loopback traffic never leaves the machine and isn't billed.
+
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Currencies without minor units
+const WHOLE_CURRENCIES: [&str; 3] = ["JPY", "KRW", "HUF"];
+
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+/// Which direction the provider charges for
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Billed {
+    #[default]
+    Both,
+    Sent, // Cloud egress
+    Received,
+}
+
+impl FromStr for Billed {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        match input.to_ascii_lowercase().as_str() {
+            "both" => Ok(Billed::Both),
+            "sent" | "egress" | "upload" => Ok(Billed::Sent),
+            "received" | "ingress" | "download" => Ok(Billed::Received),
+            _ => Err(format!("'{}' is not one of both, sent or received", input)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CostSettings {
+    /// Price of one GB (1024³ bytes); no estimates without it
+    #[serde(default)]
+    pub per_gb: Option<f64>,
+    /// ISO code such as USD or EUR, or a symbol used as is
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    #[serde(default)]
+    pub billed: Billed,
+    /// Estimated total at which a quota alert fires
+    #[serde(default)]
+    pub budget: Option<f64>,
+}
+
+impl Default for CostSettings {
+    fn default() -> Self {
+        CostSettings {
+            per_gb: None,
+            currency: default_currency(),
+            billed: Billed::default(),
+            budget: None,
+        }
+    }
+}
+
+impl CostSettings {
+    pub fn is_enabled(&self) -> bool {
+        self.per_gb.is_some_and(|price| price > 0.0)
+    }
+
+    pub fn billed_bytes(&self, sent: u64, received: u64) -> u64 {
+        match self.billed {
+            Billed::Both => sent.saturating_add(received),
+            Billed::Sent => sent,
+            Billed::Received => received,
+        }
+    }
+
+    /// Estimated cost of this much traffic, 0 when no price is set
+    pub fn cost(&self, sent: u64, received: u64) -> f64 {
+        self.cost_of(self.billed_bytes(sent, received))
+    }
+
+    pub fn cost_of(&self, bytes: u64) -> f64 {
+        match self.per_gb {
+            Some(price) if price > 0.0 => bytes as f64 / BYTES_PER_GB * price,
+            _ => 0.0,
+        }
+    }
+
+    /// "$1.25", "€0.0031", "¥120" or "3.50 CHF"; fractions of a cent keep four decimals
+    pub fn format(&self, amount: f64) -> String {
+        let code = self.currency.trim().to_ascii_uppercase();
+        let decimals = if WHOLE_CURRENCIES.contains(&code.as_str()) {
+            0
+        } else if amount != 0.0 && amount.abs() < 0.01 {
+            4
+        } else {
+            2
+        };
+        let number = format!("{:.*}", decimals, amount);
+        let symbol = match code.as_str() {
+            "USD" => "$",
+            "EUR" => "€",
+            "GBP" => "£",
+            "JPY" => "¥",
+            "INR" => "₹",
+            "KRW" => "₩",
+            // Anything that isn't an ISO code is taken as the symbol itself
+            _ if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) => {
+                return format!("{} {}", number, code);
+            }
+            _ => self.currency.trim(),
+        };
+        format!("{}{}", symbol, number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(per_gb: f64, currency: &str, billed: Billed) -> CostSettings {
+        CostSettings { per_gb: Some(per_gb), currency: currency.to_string(), billed, budget: None }
+    }
+
+    #[test]
+    fn prices_billed_direction() {
+        let gb = 1024 * 1024 * 1024;
+        assert_eq!(settings(0.09, "USD", Billed::Both).cost(gb, gb), 0.18);
+        assert_eq!(settings(0.09, "USD", Billed::Sent).cost(2 * gb, gb), 0.18);
+        assert_eq!(settings(0.09, "USD", Billed::Received).cost(2 * gb, gb), 0.09);
+        assert_eq!(CostSettings::default().cost(gb, gb), 0.0);
+        assert!(!CostSettings::default().is_enabled());
+    }
+
+    #[test]
+    fn formats_currencies() {
+        assert_eq!(settings(1.0, "usd", Billed::Both).format(1.254), "$1.25");
+        assert_eq!(settings(1.0, "EUR", Billed::Both).format(0.00312), "€0.0031");
+        assert_eq!(settings(1.0, "JPY", Billed::Both).format(119.6), "¥120");
+        assert_eq!(settings(1.0, "CHF", Billed::Both).format(3.5), "3.50 CHF");
+        assert_eq!(settings(1.0, "kr", Billed::Both).format(0.0), "kr0.00");
+    }
+
+    #[test]
+    fn parses_billed() {
+        assert_eq!("Egress".parse::<Billed>(), Ok(Billed::Sent));
+        assert_eq!("both".parse::<Billed>(), Ok(Billed::Both));
+        assert!("up".parse::<Billed>().is_err());
+    }
+}
@@ -0,0 +1,196 @@
+// Incremental updates from the capture thread to its consumers.
+//
+// Instead of cloning the whole process map (packet histories included) every
+// 100 ms, the producer sends only the processes that changed since the last
+// update it managed to deliver, each with its counters and the packets and
+// host contacts recorded since. Consumers apply every update in order.
+
+use std::collections::{HashMap, HashSet};
+use crate::types::{HostContact, PacketInfo, ProcessInfo};
+
+/// Changes to one process since the previous update
+pub struct ProcessUpdate {
+    /// Counters and metadata, with empty histories (see `ProcessInfo::summary`)
+    pub info: ProcessInfo,
+    pub new_packets: Vec<PacketInfo>,
+    /// Eviction counter of the producer's packet history, so mirrors drop the same packets
+    pub packets_evicted: u64,
+    pub new_host_contacts: Vec<HostContact>,
+}
+
+/// Per-PID changes sent over the capture channel
+pub type StatsDelta = HashMap<i32, ProcessUpdate>;
+
+impl ProcessUpdate {
+    /// Fold this update into the consumer's copy of the process
+    pub fn apply_to(self, entry: &mut ProcessInfo) {
+        entry.sent = self.info.sent;
+        entry.received = self.info.received;
+        // A restart keeps counting from where the earlier PID left off
+        if let Some(restart) = &entry.restart {
+            entry.sent += restart.sent;
+            entry.received += restart.received;
+        }
+        entry.sent_rate = self.info.sent_rate;
+        entry.received_rate = self.info.received_rate;
+        entry.name = self.info.name;
+        entry.tcp_bytes = self.info.tcp_bytes;
+        entry.udp_bytes = self.info.udp_bytes;
+        entry.quic_bytes = self.info.quic_bytes;
+        entry.ipv4_bytes = self.info.ipv4_bytes;
+        entry.ipv6_bytes = self.info.ipv6_bytes;
+        entry.connection_count = self.info.connection_count;
+        entry.new_connection_rate = self.info.new_connection_rate;
+        entry.socket_states = self.info.socket_states;
+        entry.tcp_quality = self.info.tcp_quality;
+        entry.tunnel = self.info.tunnel;
+        entry.loopback_sent = self.info.loopback_sent;
+        entry.loopback_received = self.info.loopback_received;
+        entry.loopback_sent_rate = self.info.loopback_sent_rate;
+        entry.loopback_received_rate = self.info.loopback_received_rate;
+        entry.last_seen = self.info.last_seen;
+        entry.port_bytes = self.info.port_bytes;
+        entry.category_bytes = self.info.category_bytes;
+        for packet in self.new_packets {
+            entry.packet_history.push(packet);
+        }
+        entry.packet_history.sync_evictions(self.packets_evicted);
+        entry.host_contacts.extend(self.new_host_contacts);
+    }
+}
+
+/// Remembers what the consumer has already been sent, so each update only
+/// carries what changed since the last delivered one
+#[derive(Default)]
+pub struct DeltaTracker {
+    dirty: HashSet<i32>,
+    sent_packets: HashMap<i32, u64>, // `PacketHistory::recorded()` at the last delivery
+    sent_host_contacts: HashMap<i32, usize>,
+}
+
+impl DeltaTracker {
+    /// Mark a process as changed
+    pub fn touch(&mut self, pid: i32) {
+        self.dirty.insert(pid);
+    }
+
+    /// Mark every process as changed, e.g. after rates were recalculated
+    pub fn touch_all(&mut self, map: &HashMap<i32, ProcessInfo>) {
+        self.dirty.extend(map.keys().copied());
+    }
+
+    /// Collect the changes since the last delivered update. Nothing is marked
+    /// as sent until `delivered` is called, so a dropped update is resent in full.
+    pub fn build(&self, map: &HashMap<i32, ProcessInfo>) -> StatsDelta {
+        self.dirty
+            .iter()
+            .filter_map(|pid| {
+                let info = map.get(pid)?;
+                let packet_mark = self.sent_packets.get(pid).copied().unwrap_or(0);
+                let host_mark = self.sent_host_contacts.get(pid).copied().unwrap_or(0);
+                Some((*pid, ProcessUpdate {
+                    info: info.summary(),
+                    new_packets: info.packet_history.recorded_since(packet_mark).cloned().collect(),
+                    packets_evicted: info.packet_history.evicted(),
+                    new_host_contacts: info.host_contacts.get(host_mark..).unwrap_or_default().to_vec(),
+                }))
+            })
+            .collect()
+    }
+
+    /// Whether `pid` has changes the consumer hasn't been sent yet
+    pub fn is_pending(&self, pid: i32) -> bool {
+        self.dirty.contains(&pid)
+    }
+
+    /// Drop what was kept for `pid`, once its process is gone
+    pub fn forget(&mut self, pid: i32) {
+        self.dirty.remove(&pid);
+        self.sent_packets.remove(&pid);
+        self.sent_host_contacts.remove(&pid);
+    }
+
+    /// Record that the last built update reached the consumer
+    pub fn delivered(&mut self, map: &HashMap<i32, ProcessInfo>) {
+        for pid in self.dirty.drain() {
+            if let Some(info) = map.get(&pid) {
+                self.sent_packets.insert(pid, info.packet_history.recorded());
+                self.sent_host_contacts.insert(pid, info.host_contacts.len());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounting::{Accounting, AttributedPacket};
+    use crate::types::{Connection, ProcessIdentifier};
+
+    fn record(accounting: &mut Accounting, map: &mut HashMap<i32, ProcessInfo>, pid: i32, host: [u8; 4]) {
+        let identifier = ProcessIdentifier { pid, name: "curl".to_string(), container_name: None, user_name: None, unit: None };
+        let stats = map.entry(pid).or_insert_with(|| ProcessInfo::new(&identifier));
+        let conn = Connection {
+            source_ip: [192, 168, 1, 10].into(),
+            source_port: 50000,
+            dest_ip: host.into(),
+            dest_port: 443,
+            protocol: 6,
+        };
+        accounting.record(pid, stats, &AttributedPacket {
+            conn,
+            flow: conn,
+            outbound: true,
+            loopback: false,
+            quic: false,
+            wire_len: 100,
+            bytes: 100,
+            category: None,
+        });
+    }
+
+    #[test]
+    fn sends_what_changed_since_the_last_delivery() {
+        let (mut accounting, mut map, mut tracker) = (Accounting::default(), HashMap::new(), DeltaTracker::default());
+        record(&mut accounting, &mut map, 7, [1, 1, 1, 1]);
+        record(&mut accounting, &mut map, 8, [1, 1, 1, 1]);
+        tracker.touch(7);
+        assert!(tracker.is_pending(7) && !tracker.is_pending(8));
+        let update = tracker.build(&map);
+        assert_eq!(update.keys().collect::<Vec<_>>(), [&7]);
+        assert_eq!((update[&7].new_packets.len(), update[&7].new_host_contacts.len()), (1, 1));
+
+        // Not delivered: the next update carries the same packets again
+        assert_eq!(tracker.build(&map)[&7].new_packets.len(), 1);
+        tracker.delivered(&map);
+        assert!(tracker.build(&map).is_empty());
+
+        // Only what was recorded since then, and the producer's evictions
+        record(&mut accounting, &mut map, 7, [2, 2, 2, 2]);
+        map.get_mut(&7).unwrap().packet_history.evict_oldest(1);
+        tracker.touch_all(&map);
+        let update = tracker.build(&map);
+        assert_eq!(update.len(), 2);
+        assert_eq!((update[&7].new_packets.len(), update[&7].packets_evicted), (1, 1));
+        assert_eq!(update[&7].new_host_contacts[0].host, std::net::IpAddr::from([2, 2, 2, 2]));
+        assert_eq!(update[&8].new_packets.len(), 1);
+    }
+
+    #[test]
+    fn forgets_processes_that_are_gone() {
+        let (mut accounting, mut map, mut tracker) = (Accounting::default(), HashMap::new(), DeltaTracker::default());
+        record(&mut accounting, &mut map, 7, [1, 1, 1, 1]);
+        tracker.touch(7);
+        tracker.delivered(&map);
+        tracker.touch(7);
+        tracker.forget(7);
+        assert!(!tracker.is_pending(7));
+        assert!(tracker.sent_packets.is_empty() && tracker.sent_host_contacts.is_empty());
+
+        // A new process under the same PID starts from its first packet
+        map.clear();
+        record(&mut Accounting::default(), &mut map, 7, [3, 3, 3, 3]);
+        tracker.touch(7);
+        assert_eq!(tracker.build(&map)[&7].new_packets.len(), 1);
+    }
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+use crate::delta::{DeltaTracker, StatsDelta};
+use crate::filter::ProcessFilter;
+use crate::types::{HostContact, LocalFlow, PacketDirection, PacketInfo, ProcessIdentifier, ProcessInfo, ServiceTraffic};
+use crate::units::format_bytes;
+
+/// Shape of the synthetic traffic generated for a demo process
+#[derive(Clone, Copy)]
+enum TrafficPattern {
+    Steady,
+    Bursty,
+    Periodic(f64), // period in seconds
+    Download(f64), // seconds until the transfer finishes
+}
+
+struct DemoProcess {
+    identifier: ProcessIdentifier,
+    pattern: TrafficPattern,
+    sent_rate: f64,     // bytes/s at peak
+    received_rate: f64, // bytes/s at peak
+    protocol: u8,
+    local_port: u16,
+    remote: (IpAddr, u16),
+    connections: usize,
+}
+
+impl DemoProcess {
+    /// Browsers talk HTTP/3 to the sites that offer it
+    fn is_quic(&self) -> bool {
+        self.protocol == 17 && self.remote.1 == 443
+    }
+}
+
+/// Small xorshift generator so the demo needs no extra dependencies
+struct Rng(u64);
+
+impl Rng {
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn demo_process(
+    pid: i32,
+    name: &str,
+    user: &str,
+    container: Option<&str>,
+    pattern: TrafficPattern,
+    (sent_rate, received_rate): (f64, f64),
+    protocol: u8,
+    remote: ([u8; 4], u16),
+    connections: usize,
+) -> DemoProcess {
+    DemoProcess {
+        identifier: ProcessIdentifier {
+            pid,
+            name: name.to_string(),
+            container_name: container.map(str::to_string),
+            user_name: Some(user.to_string()),
+            // The daemons run as services, containers in scopes of their own, the rest in alice's desktop session
+            unit: Some(match (name, container) {
+                ("sshd", _) => "ssh.service".to_string(),
+                ("systemd-resolve", _) => "systemd-resolved.service".to_string(),
+                ("syncthing", _) => "syncthing@alice.service".to_string(),
+                (_, Some(container)) => format!("docker-{}.scope", container),
+                _ => "session-2.scope".to_string(),
+            }),
+        },
+        pattern,
+        sent_rate,
+        received_rate,
+        protocol,
+        local_port: 40_000 + (pid % 20_000) as u16,
+        remote: (IpAddr::V4(Ipv4Addr::from(remote.0)), remote.1),
+        connections,
+    }
+}
+
+/// Demo PIDs lie above Linux's largest possible PID (`PID_MAX_LIMIT`, 4194304),
+/// so none of them can name a real process
+const fn demo_pid(n: i32) -> i32 {
+    5_000_000 + n
+}
+
+fn demo_processes(containers_mode: bool) -> Vec<DemoProcess> {
+    let container = |name| if containers_mode { Some(name) } else { None };
+    vec![
+        demo_process(demo_pid(2417), "firefox", "alice", None, TrafficPattern::Bursty, (40_000.0, 900_000.0), 17, ([151, 101, 1, 69], 443), 14),
+        demo_process(demo_pid(3120), "spotify", "alice", None, TrafficPattern::Periodic(20.0), (8_000.0, 320_000.0), 6, ([35, 186, 224, 25], 443), 3),
+        demo_process(demo_pid(1033), "sshd", "root", None, TrafficPattern::Steady, (2_500.0, 1_200.0), 6, ([192, 168, 1, 20], 51_822), 1),
+        demo_process(demo_pid(4580), "curl", "alice", None, TrafficPattern::Download(90.0), (6_000.0, 4_500_000.0), 6, ([93, 184, 215, 14], 443), 1),
+        demo_process(demo_pid(812), "systemd-resolve", "systemd-resolve", None, TrafficPattern::Bursty, (1_500.0, 3_000.0), 17, ([1, 1, 1, 1], 53), 2),
+        demo_process(demo_pid(5901), "nginx", "www-data", container("web-frontend"), TrafficPattern::Periodic(45.0), (1_800_000.0, 150_000.0), 6, ([203, 0, 113, 50], 51_000), 32),
+        demo_process(demo_pid(6022), "syncthing", "alice", None, TrafficPattern::Periodic(60.0), (600_000.0, 550_000.0), 6, ([198, 51, 100, 7], 22_000), 4),
+        demo_process(demo_pid(7144), "postgres", "postgres", container("db"), TrafficPattern::Steady, (220_000.0, 60_000.0), 6, ([10, 0, 0, 12], 5432), 8),
+    ]
+}
+
+/// Loopback chatter between demo processes: (from PID, to PID, peak bytes/s)
+const DEMO_LOCAL_FLOWS: [(i32, i32, f64); 5] = [
+    (demo_pid(5901), demo_pid(7144), 180_000.0),   // nginx queries postgres
+    (demo_pid(7144), demo_pid(5901), 1_200_000.0),
+    (demo_pid(2417), demo_pid(812), 2_000.0),      // firefox asks the local DNS stub
+    (demo_pid(812), demo_pid(2417), 3_500.0),
+    (demo_pid(6022), demo_pid(6022), 40_000.0),    // syncthing talking to its own GUI listener
+];
+
+/// Demo processes that serve a listening port: (PID, port, worker processes)
+const DEMO_SERVICES: [(i32, u16, usize); 3] = [
+    (demo_pid(1033), 22, 1),
+    (demo_pid(5901), 443, 4),
+    (demo_pid(7144), 5432, 6),
+];
+
+/// Traffic level between 0 and 1 for a pattern at time `t` (seconds since start)
+fn intensity(pattern: TrafficPattern, t: f64, rng: &mut Rng) -> f64 {
+    match pattern {
+        TrafficPattern::Steady => 0.8 + 0.2 * rng.next_f64(),
+        TrafficPattern::Bursty => {
+            if rng.next_f64() < 0.15 { 0.5 + 0.5 * rng.next_f64() } else { 0.02 * rng.next_f64() }
+        }
+        TrafficPattern::Periodic(period) => {
+            let phase = (t / period * std::f64::consts::TAU).sin();
+            (0.55 + 0.45 * phase) * (0.85 + 0.15 * rng.next_f64())
+        }
+        TrafficPattern::Download(duration) => {
+            if t < duration { (t / 10.0).min(1.0) * (0.9 + 0.1 * rng.next_f64()) } else { 0.0 }
+        }
+    }
+}
+
+fn demo_packet(process: &DemoProcess, direction: PacketDirection, size: usize) -> PacketInfo {
+    let local = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 42));
+    let ((src_ip, src_port), (dst_ip, dst_port)) = match direction {
+        PacketDirection::Sent => ((local, process.local_port), process.remote),
+        PacketDirection::Received => (process.remote, (local, process.local_port)),
+    };
+    let timestamp = SystemTime::now();
+    let dt: chrono::DateTime<chrono::Local> = timestamp.into();
+    PacketInfo {
+        timestamp,
+        direction,
+        protocol: process.protocol,
+        src_ip,
+        src_port,
+        dst_ip,
+        dst_port,
+        size,
+        cached_ts: dt.format("%H:%M:%S%.3f").to_string(),
+        cached_src: format!("{}:{}", src_ip, src_port),
+        cached_dst: format!("{}:{}", dst_ip, dst_port),
+        cached_proto: match process.protocol {
+            6 => "TCP".to_string(),
+            _ if process.is_quic() => crate::quic::LABEL.to_string(),
+            _ => "UDP".to_string(),
+        },
+        cached_size: format_bytes(size as u64),
+    }
+}
+
+/// Generate synthetic traffic in place of packet capture, sending updates the
+/// same way the capture thread does
+pub fn run(
+    tx: mpsc::Sender<StatsDelta>,
+    local_flows_tx: std::sync::mpsc::Sender<Vec<LocalFlow>>,
+    services_tx: std::sync::mpsc::Sender<Vec<ServiceTraffic>>,
+    filter: &mut ProcessFilter,
+    containers_mode: bool,
+    json_capture: Option<Duration>, // JSON mode: how long to collect before the single output
+) {
+    let processes: Vec<DemoProcess> = demo_processes(containers_mode)
+        .into_iter()
+        .filter(|process| filter.matches(&process.identifier))
+        .collect();
+    let mut rng = Rng(SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0x2545_F491_4F6C_DD1D)
+        | 1);
+    let mut bandwidth_map: HashMap<i32, ProcessInfo> = HashMap::new();
+    let mut tracker = DeltaTracker::default();
+    let mut local_flows: HashMap<(i32, i32), LocalFlow> = HashMap::new();
+    let mut previous_local_bytes: HashMap<(i32, i32), u64> = HashMap::new();
+    let mut last_local_flows = Instant::now();
+    let start = Instant::now();
+    let tick = Duration::from_millis(100);
+
+    loop {
+        std::thread::sleep(tick);
+        let t = start.elapsed().as_secs_f64();
+
+        for process in &processes {
+            let level = intensity(process.pattern, t, &mut rng);
+            let sent = (process.sent_rate * level * tick.as_secs_f64()) as u64;
+            let received = (process.received_rate * level * tick.as_secs_f64()) as u64;
+            if sent == 0 && received == 0 && !bandwidth_map.contains_key(&process.identifier.pid) {
+                continue;
+            }
+
+            let stats = bandwidth_map
+                .entry(process.identifier.pid)
+                .or_insert_with(|| ProcessInfo::new(&process.identifier));
+            tracker.touch(process.identifier.pid);
+            stats.sent += sent;
+            stats.received += received;
+            stats.sent_rate = (sent as f64 / tick.as_secs_f64()) as u64;
+            stats.received_rate = (received as f64 / tick.as_secs_f64()) as u64;
+            match process.protocol {
+                6 => stats.tcp_bytes += sent + received,
+                _ => stats.udp_bytes += sent + received,
+            }
+            if process.is_quic() {
+                stats.quic_bytes += sent + received;
+            }
+            stats.ipv4_bytes += sent + received;
+            stats.count_port(process.local_port, process.remote.1, sent + received);
+            stats.connection_count = process.connections;
+            // Bursts open a fresh batch of connections
+            stats.new_connection_rate = match process.pattern {
+                TrafficPattern::Bursty => (level * process.connections as f64) as u64,
+                _ => 0,
+            };
+            if process.protocol == 6 {
+                stats.socket_states.established = process.connections as u32;
+                // LAN peers answer in about a millisecond, internet hosts in tens of them
+                let lan = matches!(process.remote.0, IpAddr::V4(ip) if ip.is_private());
+                let rtt = if lan { 0.8 } else { 25.0 } * (1.0 + 0.3 * rng.next_f64());
+                stats.tcp_quality.avg_rtt_ms = Some(rtt);
+                stats.tcp_quality.max_rtt_ms = Some(rtt * 1.8);
+                let lost = rng.next_f64() < 0.02 * level;
+                if lost {
+                    stats.tcp_quality.retransmits += 1;
+                }
+                stats.tcp_quality.retransmit_percent = Some(if lost { 0.5 } else { 0.0 });
+            }
+            if stats.host_contacts.is_empty() {
+                stats.host_contacts.push(HostContact {
+                    host: process.remote.0,
+                    port: process.remote.1,
+                    protocol: process.protocol,
+                    first_seen: SystemTime::now(),
+                });
+            }
+            if sent + received > 0 {
+                stats.last_seen = SystemTime::now();
+            }
+
+            // A handful of representative packets per tick keeps the details view populated
+            for (direction, bytes) in [(PacketDirection::Sent, sent), (PacketDirection::Received, received)] {
+                let mut remaining = bytes as usize;
+                for _ in 0..3 {
+                    if remaining == 0 {
+                        break;
+                    }
+                    let size = remaining.min(64 + (rng.next_f64() * 1_436.0) as usize);
+                    remaining -= size;
+                    stats.packet_history.push(demo_packet(process, direction, size));
+                }
+            }
+        }
+
+        for (from, to, peak) in DEMO_LOCAL_FLOWS {
+            let (Some(sender), Some(receiver)) = (
+                processes.iter().find(|p| p.identifier.pid == from),
+                processes.iter().find(|p| p.identifier.pid == to),
+            ) else {
+                continue;
+            };
+            let level = intensity(sender.pattern, t, &mut rng);
+            local_flows
+                .entry((from, to))
+                .or_insert_with(|| LocalFlow::new(&sender.identifier, &receiver.identifier))
+                .bytes += (peak * level * tick.as_secs_f64()) as u64;
+        }
+        if last_local_flows.elapsed() >= Duration::from_secs(1) {
+            let interval = last_local_flows.elapsed().as_secs_f64();
+            last_local_flows = Instant::now();
+            for (key, flow) in local_flows.iter_mut() {
+                let previous = previous_local_bytes.insert(*key, flow.bytes).unwrap_or(0);
+                flow.rate = ((flow.bytes - previous) as f64 / interval) as u64;
+            }
+            let _ = local_flows_tx.send(local_flows.values().cloned().collect());
+
+            // The served processes' own traffic stands in for their listening ports
+            let services = DEMO_SERVICES.iter().filter_map(|&(pid, port, workers)| {
+                let stats = bandwidth_map.get(&pid)?;
+                let process = processes.iter().find(|p| p.identifier.pid == pid)?;
+                let mut service = ServiceTraffic::new(process.protocol, port, &process.identifier);
+                service.sent = stats.sent;
+                service.received = stats.received;
+                service.sent_rate = stats.sent_rate;
+                service.received_rate = stats.received_rate;
+                service.connections = process.connections;
+                service.workers = workers;
+                Some(service)
+            });
+            let _ = services_tx.send(services.collect());
+        }
+
+        if let Some(limit) = json_capture {
+            if start.elapsed() > limit {
+                tracker.touch_all(&bandwidth_map);
+                let _ = tx.blocking_send(tracker.build(&bandwidth_map));
+                return;
+            }
+            continue;
+        }
+
+        match tx.try_send(tracker.build(&bandwidth_map)) {
+            Ok(_) => tracker.delivered(&bandwidth_map),
+            // Unsent changes stay pending for the next update
+            Err(mpsc::error::TrySendError::Full(_)) => {}
+            // Receiver gone; the UI has exited
+            Err(_) => return,
+        }
+    }
+}
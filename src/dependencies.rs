@@ -1,6 +1,12 @@
 #[cfg(target_os = "windows")]
 use std::process::Command;
 
+/// Npcap installer the wizard downloads; the download page has newer releases
+#[cfg(target_os = "windows")]
+const NPCAP_INSTALLER_URL: &str = "https://npcap.com/dist/npcap-1.79.exe";
+#[cfg(target_os = "windows")]
+const NPCAP_DOWNLOAD_PAGE: &str = "https://npcap.com/#download";
+
 #[derive(Debug, Clone)]
 pub struct DependencyInfo {
     pub name: &'static str,
@@ -165,6 +171,7 @@ impl DependencyChecker {
     fn check_npcap_windows() -> bool {
         // First approach: Check for common DLL files directly in system directories
         let system_paths = [
+            "C:\\Windows\\System32\\Npcap\\wpcap.dll",
             "C:\\Windows\\System32\\wpcap.dll",
             "C:\\Windows\\System32\\packet.dll",
             "C:\\Windows\\SysWOW64\\wpcap.dll", 
@@ -334,6 +341,101 @@ impl DependencyChecker {
         println!();
     }
     
+    /// Windows first-run wizard for a missing Npcap: download and start the
+    /// installer, or show the manual steps, then check again in place.
+    /// Ok(true) once Npcap is found, Ok(false) to carry on without it
+    #[cfg(target_os = "windows")]
+    pub fn npcap_wizard() -> std::io::Result<bool> {
+        use std::io::{self, Write};
+
+        println!();
+        println!("🔧 NPCAP SETUP");
+        println!("{}", "=".repeat(50));
+        println!();
+        println!("⚠️  Npcap, the packet capture driver monitetoring (and Wireshark) uses, isn't installed.");
+        println!();
+
+        loop {
+            println!("What would you like to do?");
+            println!("1. ⬇️  Download and start the Npcap installer (recommended)");
+            println!("2. 📋 Show the manual installation steps");
+            println!("3. 🔄 Check again (after installing Npcap yourself)");
+            println!("4. ⏭️  Continue without Npcap");
+            println!("5. ❌ Exit");
+            println!();
+            print!("Please choose (1-5): ");
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            println!();
+            match input.trim() {
+                "1" => {
+                    if let Err(e) = Self::run_npcap_installer() {
+                        println!("❌ {}", e);
+                        println!("   Download the installer from {} instead, run it and choose 3.", NPCAP_DOWNLOAD_PAGE);
+                        let _ = Command::new("cmd").args(["/C", "start", "", NPCAP_DOWNLOAD_PAGE]).status();
+                        println!();
+                        continue;
+                    }
+                }
+                "2" => {
+                    Self::display_installation_guides(&[Self::get_npcap_guide_windows()]);
+                    println!("Choose 3 once the installer has finished.");
+                    println!();
+                    continue;
+                }
+                "3" => {}
+                "4" => return Ok(false),
+                "5" => std::process::exit(0),
+                _ => {
+                    println!("❌ Please enter a number from 1 to 5.");
+                    println!();
+                    continue;
+                }
+            }
+
+            if Self::check_npcap_windows() {
+                println!("✅ Npcap is installed, starting monitetoring.");
+                println!();
+                return Ok(true);
+            }
+            println!("⚠️  Npcap still isn't detected. If the installer asked for a reboot, reboot and start monitetoring again.");
+            println!();
+        }
+    }
+
+    /// Download the Npcap installer to the temp directory and run it elevated, waiting until it closes
+    #[cfg(target_os = "windows")]
+    fn run_npcap_installer() -> Result<(), String> {
+        let installer = std::env::temp_dir().join("npcap-installer.exe");
+        let installer = installer.display().to_string();
+        println!("⬇️  Downloading {} ...", NPCAP_INSTALLER_URL);
+        let download = Command::new("powershell")
+            .args(["-NoProfile", "-Command"])
+            .arg(format!(
+                "$ProgressPreference = 'SilentlyContinue'; Invoke-WebRequest -UseBasicParsing -Uri '{}' -OutFile '{}'",
+                NPCAP_INSTALLER_URL, installer
+            ))
+            .status()
+            .map_err(|e| format!("Can't run PowerShell to download Npcap: {}", e))?;
+        if !download.success() {
+            return Err("Downloading the Npcap installer failed".to_string());
+        }
+
+        println!("🚀 Starting the installer; confirm the Administrator prompt and follow its steps.");
+        println!("   Keep 'Install Npcap in WinPcap API-compatible Mode' ticked.");
+        let install = Command::new("powershell")
+            .args(["-NoProfile", "-Command"])
+            .arg(format!("Start-Process -FilePath '{}' -Verb RunAs -Wait", installer))
+            .status()
+            .map_err(|e| format!("Can't start the Npcap installer: {}", e))?;
+        if !install.success() {
+            return Err("The Npcap installer didn't run (was the Administrator prompt declined?)".to_string());
+        }
+        Ok(())
+    }
+
     /// Prompt user whether to continue with installation or exit
     pub fn prompt_installation_action() -> std::io::Result<bool> {
         use std::io::{self, Write};
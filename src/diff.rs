@@ -0,0 +1,204 @@
+// Before/after comparisons, e.g. around a config change. `monitetoring diff
+// before.json after.json` compares two `--json` snapshots (either schema); in the TUI, `B`
+// marks a baseline and `b` compares the live counters with it. PIDs don't
+// survive a restart, so processes are matched by name and container, with the
+// processes sharing both added up.
+
+use std::collections::HashMap;
+use std::path::Path;
+use serde::Deserialize;
+use crate::types::ProcessInfo;
+use crate::units::format_bytes;
+
+/// Process name and container, e.g. "nginx [web]"
+pub type ProcessKey = String;
+
+/// Bytes sent and received per process
+pub type Snapshot = HashMap<ProcessKey, (u64, u64)>;
+
+/// The fields of a `--json` entry a comparison needs
+#[derive(Deserialize)]
+struct SnapshotEntry {
+    name: String,
+    sent_bytes: u64,
+    received_bytes: u64,
+    #[serde(default)]
+    container_name: Option<String>,
+}
+
+fn key(name: &str, container: Option<&str>) -> ProcessKey {
+    match container {
+        Some(container) => format!("{} [{}]", name, container),
+        None => name.to_string(),
+    }
+}
+
+fn add(snapshot: &mut Snapshot, key: ProcessKey, sent: u64, received: u64) {
+    let entry = snapshot.entry(key).or_default();
+    entry.0 += sent;
+    entry.1 += received;
+}
+
+/// `--json` output: the bare array of schema v1 or the object of schema v2
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SnapshotFile {
+    V1(Vec<SnapshotEntry>),
+    V2 { processes: Vec<SnapshotEntry> },
+}
+
+/// Parse what `--json` prints
+pub fn parse_snapshot(json: &str) -> Result<Snapshot, String> {
+    let (SnapshotFile::V1(entries) | SnapshotFile::V2 { processes: entries }) =
+        serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let mut snapshot = Snapshot::new();
+    for entry in entries {
+        add(&mut snapshot, key(&entry.name, entry.container_name.as_deref()), entry.sent_bytes, entry.received_bytes);
+    }
+    Ok(snapshot)
+}
+
+pub fn load_snapshot(path: &Path) -> Result<Snapshot, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path.display(), e))?;
+    parse_snapshot(&json).map_err(|e| format!("{} is not a --json snapshot: {}", path.display(), e))
+}
+
+/// The live counters, in the same shape as a loaded snapshot
+pub fn snapshot_of(stats: &HashMap<i32, ProcessInfo>) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    for info in stats.values() {
+        add(&mut snapshot, key(&info.name, info.container_name.as_deref()), info.sent, info.received);
+    }
+    snapshot
+}
+
+/// How one process's traffic differs between two snapshots
+pub struct Change {
+    pub process: ProcessKey,
+    pub before: Option<(u64, u64)>, // None for processes new in the second snapshot
+    pub after: Option<(u64, u64)>,  // None for processes gone from it
+}
+
+impl Change {
+    pub fn sent_delta(&self) -> i64 {
+        self.after.map_or(0, |(sent, _)| sent as i64) - self.before.map_or(0, |(sent, _)| sent as i64)
+    }
+
+    pub fn received_delta(&self) -> i64 {
+        self.after.map_or(0, |(_, received)| received as i64) - self.before.map_or(0, |(_, received)| received as i64)
+    }
+
+    pub fn status(&self) -> &'static str {
+        match (self.before, self.after) {
+            (None, _) => "new",
+            (_, None) => "gone",
+            _ => "",
+        }
+    }
+}
+
+/// Every process of either snapshot, the ones that grew most first
+pub fn compare(before: &Snapshot, after: &Snapshot) -> Vec<Change> {
+    let mut changes: Vec<Change> = before.keys()
+        .chain(after.keys().filter(|process| !before.contains_key(*process)))
+        .map(|process| Change {
+            process: process.clone(),
+            before: before.get(process).copied(),
+            after: after.get(process).copied(),
+        })
+        .collect();
+    changes.sort_by(|a, b| {
+        (b.sent_delta() + b.received_delta()).cmp(&(a.sent_delta() + a.received_delta()))
+            .then_with(|| a.process.cmp(&b.process))
+    });
+    changes
+}
+
+/// "+1.5 MB", "-200 B" or "0 B"
+pub fn format_delta(delta: i64) -> String {
+    let sign = match delta.signum() {
+        1 => "+",
+        -1 => "-",
+        _ => "",
+    };
+    format!("{}{}", sign, format_bytes(delta.unsigned_abs()))
+}
+
+fn snapshot_totals(snapshot: &Snapshot) -> (u64, u64) {
+    snapshot.values().fold((0, 0), |(sent, received), (s, r)| (sent + s, received + r))
+}
+
+/// Plain-text comparison of two snapshots, listing at most `top` processes
+pub fn report(before: &Snapshot, after: &Snapshot, labels: (&str, &str), top: usize) -> String {
+    let changes = compare(before, after);
+    let (before_sent, before_received) = snapshot_totals(before);
+    let (after_sent, after_received) = snapshot_totals(after);
+    let mut lines = vec![
+        format!("Before: {} ({} processes)", labels.0, before.len()),
+        format!("After:  {} ({} processes)", labels.1, after.len()),
+        String::new(),
+        format!(
+            "Total:  {} sent ({}), {} received ({})",
+            format_bytes(after_sent),
+            format_delta(after_sent as i64 - before_sent as i64),
+            format_bytes(after_received),
+            format_delta(after_received as i64 - before_received as i64)
+        ),
+        String::new(),
+        format!("  {:<32} {:>12} {:>12}", "Process", "Sent", "Received"),
+    ];
+    for change in changes.iter().take(top) {
+        lines.push(format!(
+            "  {:<32} {:>12} {:>12}  {}",
+            change.process,
+            format_delta(change.sent_delta()),
+            format_delta(change.received_delta()),
+            change.status()
+        ).trim_end().to_string());
+    }
+    if changes.len() > top {
+        lines.push(format!("  ... {} more", changes.len() - top));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// `monitetoring diff before.json after.json`
+pub fn diff_files(before: &Path, after: &Path, top: usize) -> Result<String, String> {
+    let labels = (before.display().to_string(), after.display().to_string());
+    Ok(report(&load_snapshot(before)?, &load_snapshot(after)?, (&labels.0, &labels.1), top))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_processes_by_name_and_container() {
+        let snapshot = parse_snapshot(r#"[
+            {"pid": 1, "name": "nginx", "sent_bytes": 100, "received_bytes": 10, "container_name": "web", "tcp_bytes": 110},
+            {"pid": 2, "name": "nginx", "sent_bytes": 50, "received_bytes": 5, "container_name": "web"},
+            {"pid": 3, "name": "nginx", "sent_bytes": 1, "received_bytes": 1, "container_name": null}
+        ]"#).unwrap();
+        assert_eq!(snapshot.get("nginx [web]"), Some(&(150, 15)));
+        assert_eq!(snapshot.get("nginx"), Some(&(1, 1)));
+        assert!(parse_snapshot("{}").is_err());
+        let v2 = parse_snapshot(r#"{"schema": 2, "capture": {}, "processes": [
+            {"pid": 1, "name": "nginx", "sent_bytes": 100, "received_bytes": 10, "container_name": "web"}
+        ]}"#).unwrap();
+        assert_eq!(v2.get("nginx [web]"), Some(&(100, 10)));
+    }
+
+    #[test]
+    fn orders_by_growth() {
+        let before = Snapshot::from([("curl".to_string(), (100, 100)), ("sshd".to_string(), (500, 500))]);
+        let after = Snapshot::from([("curl".to_string(), (5000, 200)), ("rsync".to_string(), (900, 0))]);
+        let changes = compare(&before, &after);
+        let order: Vec<(&str, i64, &str)> = changes.iter()
+            .map(|change| (change.process.as_str(), change.sent_delta() + change.received_delta(), change.status()))
+            .collect();
+        assert_eq!(order, vec![("curl", 5000, ""), ("rsync", 900, "new"), ("sshd", -1000, "gone")]);
+        assert_eq!(format_delta(-2048), "-2.0 KB");
+        assert_eq!(format_delta(0), "0 B");
+    }
+}
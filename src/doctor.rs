@@ -0,0 +1,334 @@
+// `monitetoring doctor`: checks what monitoring needs on this machine, in the
+// order things usually go wrong when the table stays empty. It covers
+// privileges, whether the interface opens and sees packets, whether sockets
+// can be put down to processes, and whether container runtimes answer. Then
+// it measures how many packets per second the per-packet work gets through on
+// one core. Nothing is changed; each problem comes with what to do about it.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+use crate::capture::{self, CaptureSettings};
+use crate::parser;
+use crate::types::Connection;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Status {
+    Ok,
+    Info,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn symbol(self) -> &'static str {
+        match self {
+            Status::Ok => "✅",
+            Status::Info => "ℹ️ ",
+            Status::Warn => "⚠️ ",
+            Status::Fail => "❌",
+        }
+    }
+}
+
+pub struct Finding {
+    pub status: Status,
+    pub check: &'static str,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Finding {
+    fn new(status: Status, check: &'static str, detail: impl Into<String>) -> Self {
+        Finding { status, check, detail: detail.into(), hint: None }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Run every check on `iface` (the default device when not given), listening
+/// `listen` for packets; prints the report and returns the exit code (1 when
+/// something can't work)
+pub fn run(iface: Option<String>, listen: Duration, settings: CaptureSettings) -> i32 {
+    println!("🩺 monitetoring doctor ({} {})", std::env::consts::OS, env!("CARGO_PKG_VERSION"));
+    println!();
+    let mut findings = vec![privileges()];
+    findings.extend(capture_check(iface, listen, settings));
+    findings.push(sockets());
+    findings.extend(container_sockets());
+    findings.push(overhead(Duration::from_secs(1)));
+    let (text, code) = report(&findings);
+    print!("{}", text);
+    code
+}
+
+/// The findings as printed, and the exit code
+pub fn report(findings: &[Finding]) -> (String, i32) {
+    let width = findings.iter().map(|finding| finding.check.len()).max().unwrap_or(0);
+    let mut text = String::new();
+    for finding in findings {
+        text.push_str(&format!("{} {:width$}  {}\n", finding.status.symbol(), finding.check, finding.detail, width = width));
+        if let Some(hint) = &finding.hint {
+            text.push_str(&format!("   {:width$}  → {}\n", "", hint, width = width));
+        }
+    }
+    let failed = findings.iter().filter(|finding| finding.status == Status::Fail).count();
+    let warned = findings.iter().filter(|finding| finding.status == Status::Warn).count();
+    text.push('\n');
+    text.push_str(&match (failed, warned) {
+        (0, 0) => "✅ Everything monitetoring needs is in place\n".to_string(),
+        (0, warned) => format!("⚠️  Monitoring works, with {} warning(s) above\n", warned),
+        (failed, _) => format!("❌ {} problem(s) above keep monitoring from working\n", failed),
+    });
+    (text, if failed > 0 { 1 } else { 0 })
+}
+
+#[cfg(target_os = "linux")]
+fn privileges() -> Finding {
+    if unsafe { libc::geteuid() } == 0 {
+        Finding::new(Status::Ok, "Privileges", "running as root")
+    } else if crate::privileges::has_capture_capabilities() {
+        Finding::new(Status::Warn, "Privileges", "CAP_NET_RAW and CAP_NET_ADMIN, not root")
+            .hint("other users' processes can't be attributed or killed; run with sudo for those")
+    } else {
+        Finding::new(Status::Fail, "Privileges", "not root and no capture capabilities")
+            .hint("run with sudo, or once: monitetoring --setup-capabilities")
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn privileges() -> Finding {
+    if crate::process::control::is_elevated() {
+        Finding::new(Status::Ok, "Privileges", "running as Administrator")
+    } else {
+        Finding::new(Status::Warn, "Privileges", "not running as Administrator")
+            .hint("killing, limiting and blocking processes need an elevated prompt")
+    }
+}
+
+/// Open the interface the way monitoring does and count what arrives
+fn capture_check(iface: Option<String>, listen: Duration, settings: CaptureSettings) -> Vec<Finding> {
+    // Asking libpcap anything without it installed can bring the process down on Windows
+    let missing = crate::dependencies::DependencyChecker::check_dependencies();
+    if let Some(guide) = missing.first() {
+        return vec![Finding::new(Status::Fail, "Capture", format!("{} is not installed", guide.dependency.name))
+            .hint(guide.install_steps.first().copied().unwrap_or("install it and run doctor again"))];
+    }
+    let Some(iface) = iface.or_else(|| pcap::Device::lookup().ok().flatten().map(|device| device.name)) else {
+        return vec![Finding::new(Status::Fail, "Capture", "no interface to capture on")
+            .hint("name one with --iface")];
+    };
+    let iface = iface.as_str();
+    let mut cap = match capture::reopen(iface, settings) {
+        Ok(cap) => cap,
+        Err(e) => {
+            return vec![Finding::new(Status::Fail, "Capture", format!("can't open {}: {}", iface, e))
+                .hint("check the name with `ip link` (or --iface any), and the privileges above")];
+        }
+    };
+    let link = cap.get_datalink();
+    let link_name = link.get_name().unwrap_or_else(|_| format!("DLT {}", link.0));
+    let link_type = parser::LinkType::from_datalink(link.0);
+
+    let started = Instant::now();
+    let (mut packets, mut parsed) = (0u64, 0u64);
+    while started.elapsed() < listen {
+        match cap.next_packet() {
+            Ok(packet) => {
+                packets += 1;
+                if parser::parse(link_type, packet.data).is_some_and(|packet| packet.ports.is_some()) {
+                    parsed += 1;
+                }
+            }
+            Err(pcap::Error::TimeoutExpired) | Err(pcap::Error::NoMorePackets) => {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Err(e) => {
+                return vec![Finding::new(Status::Fail, "Capture", format!("{} stopped delivering packets: {}", iface, e))];
+            }
+        }
+    }
+    let dropped = cap.stats().map(|stats| stats.dropped + stats.if_dropped).unwrap_or(0);
+
+    let seen = format!("{}: {} packets in {:.0} s, link type {}", iface, packets, listen.as_secs_f64(), link_name);
+    let mut findings = vec![if packets == 0 {
+        Finding::new(Status::Warn, "Capture", seen)
+            .hint("opened fine but nothing arrived; generate some traffic, or pick the interface that carries it (--iface any)")
+    } else {
+        Finding::new(Status::Ok, "Capture", seen)
+    }];
+    if packets > 0 && parsed < packets / 2 {
+        findings.push(Finding::new(Status::Warn, "Parsing", format!("only {} of {} packets had TCP or UDP ports", parsed, packets))
+            .hint(if matches!(link_type, parser::LinkType::Other(_)) {
+                format!("the {} link type isn't understood; a concrete interface such as eth0 is", link_name)
+            } else {
+                "most traffic here is neither TCP nor UDP, or is cut short; check --snaplen".to_string()
+            }));
+    }
+    if dropped > 0 {
+        findings.push(Finding::new(Status::Warn, "Drops", format!("{} packets dropped while listening", dropped))
+            .hint("raise --buffer-size, or lower --snaplen to capture headers only"));
+    }
+    findings
+}
+
+/// One full socket-to-process scan, as the capture thread does on start
+fn sockets() -> Finding {
+    let mut inode_map = HashMap::new();
+    let mut conn_map = HashMap::new();
+    let stats = crate::process::ProcMapScanner::default().refresh(&mut inode_map, &mut conn_map, false);
+    let detail = format!(
+        "{} sockets, {} without a readable owner; a full scan of {} processes took {} ms",
+        stats.sockets, stats.unowned, stats.processes_scanned, stats.duration.as_millis()
+    );
+    if stats.sockets == 0 {
+        Finding::new(Status::Fail, "Sockets", "no TCP or UDP sockets listed")
+            .hint(if cfg!(target_os = "linux") { "/proc/net/tcp and /proc/net/udp must be readable (is /proc mounted?)" } else { "the connection tables can't be read" })
+    } else if stats.unowned > stats.sockets / 2 {
+        Finding::new(Status::Warn, "Sockets", detail)
+            .hint("traffic of processes whose sockets can't be read shows up as unattributed; run as root")
+    } else {
+        Finding::new(Status::Ok, "Sockets", detail)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn container_sockets() -> Vec<Finding> {
+    use std::io::{ErrorKind, Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let findings: Vec<Finding> = crate::containers::API_SOCKETS.iter()
+        .filter_map(|path| {
+            let mut stream = match UnixStream::connect(path) {
+                Ok(stream) => stream,
+                Err(e) if e.kind() == ErrorKind::NotFound => return None,
+                Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+                    return Some(Finding::new(Status::Warn, "Containers", format!("{}: permission denied", path))
+                        .hint("image and Compose names need access to it; run as root or join its group"));
+                }
+                Err(e) => return Some(Finding::new(Status::Warn, "Containers", format!("{}: {}", path, e))),
+            };
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+            let mut response = String::new();
+            let answered = write!(stream, "GET /_ping HTTP/1.0\r\nHost: localhost\r\n\r\n").is_ok()
+                && stream.read_to_string(&mut response).is_ok()
+                && response.split_whitespace().nth(1) == Some("200");
+            Some(if answered {
+                Finding::new(Status::Ok, "Containers", format!("{} answers", path))
+            } else {
+                Finding::new(Status::Warn, "Containers", format!("{} doesn't answer", path))
+                    .hint("is the daemon running?")
+            })
+        })
+        .collect();
+    if findings.is_empty() {
+        return vec![Finding::new(Status::Info, "Containers", "no Docker or Podman socket found")];
+    }
+    findings
+}
+
+#[cfg(target_os = "windows")]
+fn container_sockets() -> Vec<Finding> {
+    vec![Finding::new(Status::Info, "Containers", "container awareness is Linux only")]
+}
+
+/// Packets per second the per-packet work (parsing, finding the socket, looking
+/// for QUIC) gets through on one core, over synthetic traffic for `duration`
+fn overhead(duration: Duration) -> Finding {
+    let rate = packets_per_second(duration);
+    let detail = if rate >= 1_000_000.0 {
+        format!("~{:.1} million packets/s on one core", rate / 1_000_000.0)
+    } else {
+        format!("~{:.0} thousand packets/s on one core", rate / 1000.0)
+    };
+    // A busy gigabit link carries around 80,000 full-size packets a second
+    if rate < 100_000.0 {
+        Finding::new(Status::Warn, "Overhead", detail)
+            .hint("busy links may outpace the capture thread; --snaplen 128 and a narrower --iface help")
+    } else {
+        Finding::new(Status::Ok, "Overhead", detail)
+    }
+}
+
+fn packets_per_second(duration: Duration) -> f64 {
+    use etherparse::PacketBuilder;
+
+    let local = Ipv4Addr::new(192, 168, 1, 2);
+    let payload = [0u8; 1200];
+    let mut frames = Vec::new();
+    let mut conn_map: HashMap<Connection, u64> = HashMap::new();
+    for flow in 0..64u16 {
+        let remote = Ipv4Addr::new(203, 0, 113, flow as u8);
+        let port = 40000 + flow;
+        let mut frame = Vec::new();
+        let builder = PacketBuilder::ethernet2([2, 0, 0, 0, 0, 1], [2, 0, 0, 0, 0, 2]).ipv4(local.octets(), remote.octets(), 64);
+        let written = if flow % 4 == 0 {
+            builder.udp(port, 443).write(&mut frame, &payload)
+        } else {
+            builder.tcp(port, 443, 1, 65535).write(&mut frame, &payload)
+        };
+        if written.is_err() {
+            continue;
+        }
+        let protocol = if flow % 4 == 0 { 17 } else { 6 };
+        conn_map.insert(Connection {
+            source_port: port,
+            dest_port: 443,
+            source_ip: IpAddr::V4(local),
+            dest_ip: IpAddr::V4(remote),
+            protocol,
+        }, flow as u64 + 1);
+        frames.push(frame);
+    }
+    // As many unrelated sockets as a busy server has
+    for filler in 0..5000u32 {
+        conn_map.insert(Connection {
+            source_port: (filler % 60000) as u16,
+            dest_port: 8080,
+            source_ip: IpAddr::V4(local),
+            dest_ip: IpAddr::V4(Ipv4Addr::from(0x0a00_0000 + filler)),
+            protocol: 6,
+        }, 100_000 + filler as u64);
+    }
+
+    let mut quic = crate::quic::QuicFlows::default();
+    let started = Instant::now();
+    let mut packets = 0u64;
+    while started.elapsed() < duration {
+        for frame in &frames {
+            let packet = parser::parse(parser::LinkType::Ethernet, frame);
+            if let Some(conn) = packet.as_ref().and_then(Connection::from_packet)
+                && capture::lookup_socket(&conn_map, &conn).is_some()
+                && conn.protocol == 17 {
+                    std::hint::black_box(quic.classify(&conn, || packet.and_then(|packet| packet.payload)));
+                }
+            packets += 1;
+        }
+    }
+    packets as f64 / started.elapsed().as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fails_only_on_problems_that_stop_monitoring() {
+        let warned = [
+            Finding::new(Status::Ok, "Privileges", "running as root"),
+            Finding::new(Status::Warn, "Capture", "eth0: 0 packets").hint("generate some traffic"),
+        ];
+        let (text, code) = report(&warned);
+        assert_eq!(code, 0);
+        assert!(text.contains("✅ Privileges  running as root\n"));
+        assert!(text.contains("→ generate some traffic"));
+        assert!(text.contains("1 warning(s)"));
+
+        let failed = [Finding::new(Status::Fail, "Privileges", "not root")];
+        assert_eq!(report(&failed).1, 1);
+        assert!(packets_per_second(Duration::from_millis(20)) > 0.0);
+    }
+}
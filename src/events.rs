@@ -0,0 +1,112 @@
+// Session event log: every alert trigger, kill, custom command, capture, quota
+// breach, annotation and other notice, timestamped and kept after the on-screen message
+// fades. Shown in the Events view and exportable to CSV. Custom command results
+// keep what the command printed, for Enter in the Events view.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Write};
+use std::sync::Arc;
+use chrono::{DateTime, Local};
+use crate::action_queue::CommandOutput;
+
+/// Oldest entries are dropped beyond this many
+pub const MAX_EVENTS: usize = 10_000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum EventKind {
+    Alert,   // An alert fired
+    Kill,    // A process was killed, by an alert or by hand
+    Command, // A custom alert command ran
+    Capture, // Alert-triggered pcap recording
+    Quota,   // The data quota was exceeded
+    Anomaly, // Traffic far above a process's baseline
+    Mute,    // Alert muted or unmuted
+    Annotation, // A label the user dropped with `a`
+    Restart, // A process came back under a new PID
+    System,  // Packet drops, memory budget and other notices
+}
+
+impl EventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventKind::Alert => "Alert",
+            EventKind::Kill => "Kill",
+            EventKind::Command => "Command",
+            EventKind::Capture => "Capture",
+            EventKind::Quota => "Quota",
+            EventKind::Anomaly => "Anomaly",
+            EventKind::Mute => "Mute",
+            EventKind::Annotation => "Annotation",
+            EventKind::Restart => "Restart",
+            EventKind::System => "System",
+        }
+    }
+}
+
+pub struct Event {
+    pub time: DateTime<Local>,
+    pub kind: EventKind,
+    pub pid: Option<i32>,
+    pub message: String,
+    pub output: Option<Arc<CommandOutput>>,
+}
+
+/// Newest first, capped at `MAX_EVENTS`
+#[derive(Default)]
+pub struct EventLog {
+    entries: VecDeque<Event>,
+}
+
+impl EventLog {
+    pub fn push(&mut self, kind: EventKind, pid: Option<i32>, message: &str) {
+        self.record(kind, pid, message, None);
+    }
+
+    /// Log a custom command's result along with what it printed
+    pub fn push_output(&mut self, kind: EventKind, pid: Option<i32>, message: &str, output: CommandOutput) {
+        self.record(kind, pid, message, Some(Arc::new(output)));
+    }
+
+    fn record(&mut self, kind: EventKind, pid: Option<i32>, message: &str, output: Option<Arc<CommandOutput>>) {
+        // Notifications may span lines; the log keeps one line per event
+        let message = message.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>().join(" ");
+        self.entries.push_front(Event { time: Local::now(), kind, pid, message, output });
+        self.entries.truncate(MAX_EVENTS);
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Event> {
+        self.entries.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the log, oldest first, to `events_<timestamp>.csv` in the current
+    /// directory and return the file name
+    pub fn export_csv(&self) -> io::Result<String> {
+        let filename = format!("events_{}.csv", Local::now().format("%Y%m%d_%H%M%S"));
+        let mut file = File::create(&filename)?;
+        writeln!(file, "Time,Type,PID,Message")?;
+        for event in self.entries.iter().rev() {
+            writeln!(
+                file,
+                "{},{},{},\"{}\"",
+                event.time.format("%Y-%m-%d %H:%M:%S%.3f"),
+                event.kind.label(),
+                event.pid.map(|pid| pid.to_string()).unwrap_or_default(),
+                event.message.replace('"', "\"\"")
+            )?;
+        }
+        Ok(filename)
+    }
+}
@@ -0,0 +1,114 @@
+// Command-line restriction of monitoring to some processes (`--pid`,
+// `--process-name`, `--container`, or the process tree of `run`). Traffic of
+// any other process is dropped before it is accounted, so it never reaches
+// the table, the packet histories or the exports.
+
+use std::collections::HashSet;
+use regex::Regex;
+use crate::config::Cli;
+use crate::types::ProcessIdentifier;
+
+/// Longest parent chain followed; on Windows a recycled PID can make the chain loop
+const MAX_TREE_DEPTH: usize = 64;
+
+#[derive(Clone, Default)]
+pub struct ProcessFilter {
+    pid: Option<i32>,
+    name: Option<Regex>,
+    container: Option<String>,
+    tree: Option<ProcessTree>,
+}
+
+/// A process and its descendants, learned by walking up the parent PIDs of
+/// each process seen. Verdicts are cached, so a descendant stays a member
+/// after its parent exits.
+#[derive(Clone)]
+struct ProcessTree {
+    root: i32,
+    members: HashSet<i32>,
+    others: HashSet<i32>,
+}
+
+impl ProcessTree {
+    fn new(root: i32) -> Self {
+        ProcessTree { root, members: HashSet::from([root]), others: HashSet::new() }
+    }
+
+    fn contains(&mut self, pid: i32) -> bool {
+        let mut chain = Vec::new();
+        let mut current = pid;
+        let member = loop {
+            if self.members.contains(&current) {
+                break true;
+            }
+            if self.others.contains(&current) || chain.len() == MAX_TREE_DEPTH {
+                break false;
+            }
+            chain.push(current);
+            match crate::process::parent_pid(current) {
+                Some(parent) if parent > 0 && parent != current => current = parent,
+                Some(_) => break false,
+                // Exited before it could be placed; decide again if its PID shows up later
+                None => return false,
+            }
+        };
+        if member {
+            self.members.extend(chain);
+        } else {
+            self.others.extend(chain);
+        }
+        member
+    }
+}
+
+impl ProcessFilter {
+    pub fn from_cli(cli: &Cli) -> Result<Self, String> {
+        let name = cli.process_name.as_deref()
+            .map(|pattern| Regex::new(pattern).map_err(|e| format!("Invalid --process-name pattern: {}", e)))
+            .transpose()?;
+        if cli.container.is_some() && cfg!(windows) {
+            return Err("--container is only available on Linux".to_string());
+        }
+        Ok(ProcessFilter { pid: cli.pid, name, container: cli.container.clone(), tree: None })
+    }
+
+    /// Also require processes to be `root` or one of its descendants
+    pub fn follow_tree(&mut self, root: i32) {
+        self.tree = Some(ProcessTree::new(root));
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.pid.is_some() || self.name.is_some() || self.container.is_some() || self.tree.is_some()
+    }
+
+    /// Container names are only resolved in containers mode
+    pub fn needs_containers(&self) -> bool {
+        self.container.is_some()
+    }
+
+    /// Every given criterion has to match
+    pub fn matches(&mut self, process: &ProcessIdentifier) -> bool {
+        self.pid.is_none_or(|pid| pid == process.pid)
+            && self.name.as_ref().is_none_or(|name| name.is_match(&process.name))
+            && self.container.as_ref().is_none_or(|container| process.container_name.as_ref() == Some(container))
+            && self.tree.as_mut().is_none_or(|tree| tree.contains(process.pid))
+    }
+
+    /// Short description for the startup message and the table title, e.g. `pid 1234, name /^nginx/`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(pid) = self.pid {
+            parts.push(format!("pid {}", pid));
+        }
+        if let Some(name) = &self.name {
+            parts.push(format!("name /{}/", name.as_str()));
+        }
+        if let Some(container) = &self.container {
+            parts.push(format!("container {}", container));
+        }
+        if let Some(tree) = &self.tree {
+            parts.push(format!("process tree of pid {}", tree.root));
+        }
+        parts.join(", ")
+    }
+}
@@ -0,0 +1,208 @@
+// On-disk ring store for chart history older than the history window. Points
+// that fall out of a process's in-memory history are gathered into blocks of
+// `BLOCK_POINTS` and written one after another into a memory-mapped file of a
+// fixed size; once the file is full, writing starts over at its beginning and
+// the oldest blocks are overwritten. Only where each block lies is kept in
+// memory, so hours of history cost disk space rather than heap. Built with the
+// `zstd` feature, blocks are compressed before they are written.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use memmap2::MmapMut;
+
+/// Points (time, sent rate, received rate) gathered before a block is written
+const BLOCK_POINTS: usize = 256;
+
+const POINT_BYTES: usize = 24;
+
+/// (time, rate) samples, as charts take them
+type History = Vec<(f64, f64)>;
+
+/// Where one block lies in the file and what it covers
+struct Block {
+    pid: Option<i32>, // None once the process is forgotten
+    offset: usize,
+    len: usize,
+    compressed: bool,
+    from: f64,
+    to: f64,
+}
+
+pub struct HistoryStore {
+    map: MmapMut,
+    head: usize,                            // Where the next block goes
+    blocks: VecDeque<Block>,                // In the order they were written, oldest first
+    pending: HashMap<i32, Vec<[f64; 3]>>,   // Points not yet making up a full block
+}
+
+impl HistoryStore {
+    /// Create (or truncate) `path` and map `size` bytes of it
+    pub fn create(path: &Path, size: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.set_len(size)?;
+        // SAFETY: the file was just truncated for this store alone; nothing else is
+        // expected to change it while it is mapped
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        Ok(HistoryStore { map, head: 0, blocks: VecDeque::new(), pending: HashMap::new() })
+    }
+
+    /// Take the points that left `pid`'s in-memory history; `sent` and
+    /// `received` are sampled together, so they line up point by point
+    pub fn spill(&mut self, pid: i32, sent: &[(f64, f64)], received: &[(f64, f64)]) {
+        if sent.is_empty() {
+            return;
+        }
+        let mut pending = self.pending.remove(&pid).unwrap_or_default();
+        pending.extend(sent.iter().zip(received).map(|((time, sent), (_, received))| [*time, *sent, *received]));
+        let full = pending.len() / BLOCK_POINTS * BLOCK_POINTS;
+        for points in pending[..full].chunks(BLOCK_POINTS) {
+            self.write(pid, points);
+        }
+        pending.drain(..full);
+        self.pending.insert(pid, pending);
+    }
+
+    /// Points of `pid` from `from` to `to` (seconds since start), as (sent, received) histories
+    pub fn load(&self, pid: i32, from: f64, to: f64) -> (History, History) {
+        let stored = self.blocks.iter()
+            .filter(|block| block.pid == Some(pid) && block.to >= from && block.from <= to)
+            .flat_map(|block| self.read(block));
+        let pending = self.pending.get(&pid).into_iter().flatten().copied();
+        stored.chain(pending)
+            .filter(|[time, _, _]| (from..=to).contains(time))
+            .map(|[time, sent, received]| ((time, sent), (time, received)))
+            .unzip()
+    }
+
+    /// A process took over `from`'s history after a restart
+    pub fn rename(&mut self, from: i32, to: i32) {
+        for block in self.blocks.iter_mut().filter(|block| block.pid == Some(from)) {
+            block.pid = Some(to);
+        }
+        if let Some(points) = self.pending.remove(&from) {
+            self.pending.insert(to, points);
+        }
+    }
+
+    /// `pid` now belongs to another process; what was stored for it no longer applies
+    pub fn forget(&mut self, pid: i32) {
+        for block in self.blocks.iter_mut().filter(|block| block.pid == Some(pid)) {
+            block.pid = None;
+        }
+        self.pending.remove(&pid);
+    }
+
+    /// Oldest point still stored for `pid`
+    pub fn oldest(&self, pid: i32) -> Option<f64> {
+        let stored = self.blocks.iter().find(|block| block.pid == Some(pid)).map(|block| block.from);
+        stored.or_else(|| self.pending.get(&pid)?.first().map(|[time, _, _]| *time))
+    }
+
+    fn write(&mut self, pid: i32, points: &[[f64; 3]]) {
+        let raw: Vec<u8> = points.iter().flatten().flat_map(|value| value.to_le_bytes()).collect();
+        let (bytes, compressed) = compress(raw);
+        if bytes.len() > self.map.len() {
+            return;
+        }
+        // Start over at the beginning when the block doesn't fit before the end;
+        // the blocks still lying past the old head are the oldest, and go first
+        let mut offset = self.head;
+        if offset + bytes.len() > self.map.len() {
+            while self.blocks.front().is_some_and(|block| block.offset >= self.head) {
+                self.blocks.pop_front();
+            }
+            offset = 0;
+        }
+        let end = offset + bytes.len();
+        while self.blocks.front().is_some_and(|block| block.offset < end && block.offset + block.len > offset) {
+            self.blocks.pop_front();
+        }
+        self.map[offset..end].copy_from_slice(&bytes);
+        self.head = end;
+        self.blocks.push_back(Block {
+            pid: Some(pid),
+            offset,
+            len: bytes.len(),
+            compressed,
+            from: points[0][0],
+            to: points[points.len() - 1][0],
+        });
+    }
+
+    fn read(&self, block: &Block) -> Vec<[f64; 3]> {
+        let bytes = &self.map[block.offset..block.offset + block.len];
+        let Some(raw) = decompress(bytes, block.compressed) else { return Vec::new() };
+        raw.chunks_exact(POINT_BYTES)
+            .map(|point| {
+                let value = |at: usize| f64::from_le_bytes(point[at..at + 8].try_into().unwrap_or_default());
+                [value(0), value(8), value(16)]
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "zstd")]
+fn compress(raw: Vec<u8>) -> (Vec<u8>, bool) {
+    match zstd::bulk::compress(&raw, 3) {
+        Ok(bytes) if bytes.len() < raw.len() => (bytes, true),
+        _ => (raw, false),
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn compress(raw: Vec<u8>) -> (Vec<u8>, bool) {
+    (raw, false)
+}
+
+#[cfg(feature = "zstd")]
+fn decompress(bytes: &[u8], compressed: bool) -> Option<std::borrow::Cow<'_, [u8]>> {
+    if compressed {
+        zstd::bulk::decompress(bytes, BLOCK_POINTS * POINT_BYTES).ok().map(std::borrow::Cow::Owned)
+    } else {
+        Some(std::borrow::Cow::Borrowed(bytes))
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress(bytes: &[u8], compressed: bool) -> Option<std::borrow::Cow<'_, [u8]>> {
+    (!compressed).then_some(std::borrow::Cow::Borrowed(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(from: usize, to: usize) -> Vec<(f64, f64)> {
+        (from..to).map(|second| (second as f64, second as f64 * 10.0)).collect()
+    }
+
+    #[test]
+    fn keeps_the_newest_blocks_and_loads_a_range() {
+        let path = std::env::temp_dir().join(format!("monitetoring-history-{}.bin", std::process::id()));
+        // Room for two uncompressed blocks and a bit
+        let mut store = HistoryStore::create(&path, (BLOCK_POINTS * POINT_BYTES * 2 + 100) as u64).unwrap();
+        for block in 0..4 {
+            let spilled = points(block * BLOCK_POINTS, (block + 1) * BLOCK_POINTS);
+            store.spill(7, &spilled, &spilled);
+        }
+        store.spill(7, &points(1024, 1030), &points(1024, 1030));
+
+        // Uncompressed, only the last two blocks fit; the pending points are kept either way
+        if !cfg!(feature = "zstd") {
+            assert_eq!(store.oldest(7), Some((BLOCK_POINTS * 2) as f64));
+        }
+        let (sent, received) = store.load(7, 1000.0, 1027.0);
+        assert_eq!(sent.len(), 28);
+        assert_eq!(sent.first(), Some(&(1000.0, 10000.0)));
+        assert_eq!(sent, received);
+
+        store.rename(7, 8);
+        assert_eq!(store.load(7, 0.0, 2000.0).0.len(), 0);
+        assert_eq!(store.load(8, 1028.0, 2000.0).0.len(), 2);
+        store.forget(8);
+        assert_eq!(store.oldest(8), None);
+        let _ = std::fs::remove_file(path);
+    }
+}
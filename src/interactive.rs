@@ -1,11 +1,11 @@
-use std::io::{self, Write};
-use pcap::Device;
+use std::io;
 use crate::config::{SavedConfig, load_config, save_config, reset_config};
-use std::collections::HashMap;
+use crate::types::{InterfaceChoice, InterfacePicker};
 use std::time::{Duration, Instant};
 use std::thread;
 use std::sync::mpsc;
-use crossterm::event::{self, Event, KeyCode};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{backend::CrosstermBackend, Terminal};
 
 
 pub struct InteractiveConfig {
@@ -15,208 +15,78 @@ pub struct InteractiveConfig {
     pub show_total_columns: bool,
 }
 
-/// Helper struct for managing user input operations
-struct InputHandler;
-
-impl InputHandler {
-    /// Gets user input with automatic trimming
-    fn get_input() -> Result<String, io::Error> {
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        Ok(input.trim().to_string())
+/// How long each interface is sampled for, at most
+pub const MEASURE_DURATION_SECS: u64 = 5;
+/// Extra time for slow interface opens on Linux
+pub const OPEN_GRACE_SECS: u64 = 1;
+/// How often each measuring thread reports the bytes it has seen so far
+const REPORT_INTERVAL: Duration = Duration::from_millis(250);
+/// A measurement can settle early once it has run this long...
+const SETTLE_AFTER: Duration = Duration::from_secs(1);
+/// ...and one real interface has seen at least this much...
+const SETTLE_MIN_BYTES: u64 = 256 * 1024;
+/// ...and this many times as much as any other
+const SETTLE_LEAD: u64 = 10;
+
+/// Sample one interface, sending its running byte count every REPORT_INTERVAL.
+/// Stops early once nobody listens any more: the measurement settled, was
+/// skipped or its dialog closed
+fn measure_interface(name: &str, tx: &mpsc::Sender<(String, u64)>) {
+    // Skip interfaces that can't be opened, due to dependency issues or otherwise
+    let Ok(device) = crate::dependencies::DependencyChecker::device_from_name_with_dependency_check(name) else { return };
+    let Ok(cap) = crate::dependencies::DependencyChecker::capture_from_device_with_dependency_check(device) else { return };
+    // Short timeout so the reports go out on time on a quiet interface
+    let Ok(mut cap) = cap.timeout(100).open() else { return };
+
+    // A first report of zero shows the interface opened
+    if tx.send((name.to_string(), 0)).is_err() {
+        return;
     }
-
-    /// Prompts user with a question and handles yes/no responses
-    fn confirm_prompt(message: &str, default_yes: bool) -> Result<bool, io::Error> {
-        let default_text = if default_yes { "[Y/n]" } else { "[y/N]" };
-        
-        loop {
-            print!("{} {}: ", message, default_text);
-            io::stdout().flush()?;
-
-            let input = Self::get_input()?.to_lowercase();
-            
-            match input.as_str() {
-                "" => return Ok(default_yes),
-                "y" | "yes" => return Ok(true),
-                "n" | "no" => return Ok(false),
-                _ => {
-                    println!("❌ Please enter Y for yes or N for no.");
-                    println!();
-                }
-            }
+    let start = Instant::now();
+    let mut reported = start;
+    let mut total_bytes = 0u64;
+    while start.elapsed() < Duration::from_secs(MEASURE_DURATION_SECS) {
+        match cap.next_packet() {
+            Ok(packet) => total_bytes += packet.data.len() as u64,
+            // Timeout or error, continue sampling
+            Err(_) => thread::sleep(Duration::from_millis(10)),
         }
-    }
-
-    /// Prompts user for a numeric choice within a range
-    fn numeric_choice_prompt(prompt: &str, min: usize, max: usize) -> Result<Option<usize>, io::Error> {
-        loop {
-            print!("{}: ", prompt);
-            io::stdout().flush()?;
-
-            let input = Self::get_input()?;
-            
-            match input.parse::<usize>() {
-                Ok(0) if min == 0 => return Ok(None), // Quit option
-                Ok(n) if n >= min && n <= max => return Ok(Some(n)),
-                _ => {
-                    if min == 0 {
-                        println!("❌ Invalid selection. Please enter a number between {} and {} (or 0 to quit).", min, max);
-                    } else {
-                        println!("❌ Invalid selection. Please enter a number between {} and {}.", min, max);
-                    }
-                    println!();
-                }
-            }
-        }
-    }
-
-    /// Prompts user for a numeric choice with a default value when Enter is pressed
-    fn numeric_choice_prompt_with_default(prompt: &str, min: usize, max: usize, default: usize) -> Result<Option<usize>, io::Error> {
-        loop {
-            print!("{} [{}]: ", prompt, default);
-            io::stdout().flush()?;
-
-            let input = Self::get_input()?;
-            
-            if input.is_empty() {
-                // Return default when Enter is pressed without input
-                return Ok(Some(default));
-            }
-            
-            match input.parse::<usize>() {
-                Ok(0) if min == 0 => return Ok(None), // Quit option
-                Ok(n) if n >= min && n <= max => return Ok(Some(n)),
-                _ => {
-                    if min == 0 {
-                        println!("❌ Invalid selection. Please enter a number between {} and {} (or 0 to quit), or press Enter for default ({}).", min, max, default);
-                    } else {
-                        println!("❌ Invalid selection. Please enter a number between {} and {}, or press Enter for default ({}).", min, max, default);
-                    }
-                    println!();
-                }
+        if reported.elapsed() >= REPORT_INTERVAL {
+            reported = Instant::now();
+            if tx.send((name.to_string(), total_bytes)).is_err() {
+                return;
             }
         }
     }
+    let _ = tx.send((name.to_string(), total_bytes));
 }
 
-/// Helper struct for display formatting
-struct DisplayHelper;
-
-impl DisplayHelper {
-    fn print_header(title: &str, width: usize) {
-        println!("{}", title);
-        println!("{}", "=".repeat(width));
-        println!();
-    }
-
-    fn print_config_summary(interface: &str, json_mode: bool, containers_mode: bool, show_total_columns: bool) {
-        println!("📋 Configuration Summary:");
-        println!("   📡 Interface: {}", interface);
-        println!("   📊 Mode: {}", if json_mode { "JSON output" } else { "Interactive TUI" });
-        println!("   🐳 Container awareness: {}", if containers_mode { "Enabled" } else { "Disabled" });
-        println!("   📈 Show total columns: {}", if show_total_columns { "Yes" } else { "No" });
-        println!();
+/// Measure each interface concurrently, also used by the TUI's interface picker.
+/// Each thread opens its interface and streams (name, bytes so far) while it
+/// samples; the channel disconnects once every thread is done
+pub fn measure_traffic_in_background(names: Vec<String>) -> mpsc::Receiver<(String, u64)> {
+    let (tx, rx) = mpsc::channel();
+    for interface_name in names {
+        let tx = tx.clone();
+        thread::spawn(move || measure_interface(&interface_name, &tx));
     }
+    rx
 }
 
-/// Enhanced device information with validation
-struct NetworkInterface {
-    name: String,
-    description: Option<String>,
-    is_up: bool,
-    traffic_bytes: u64,  // Total bytes observed during measurement period
-}
-
-impl NetworkInterface {
-    fn from_device(device: Device) -> Self {
-        Self {
-            name: device.name,
-            description: device.desc,
-            is_up: device.flags.is_up(),
-            traffic_bytes: 0,
-        }
-    }
-
-    fn is_pseudo(&self) -> bool {
-        matches!(self.name.as_str(), "any") ||
-        self.name.starts_with("nfqueue") ||
-        self.name.starts_with("usbmon")
+/// Whether a measurement that has run for `elapsed` already tells which
+/// interface is busiest, so nobody has to wait out the full window
+pub fn measurement_settled(choices: &[InterfaceChoice], elapsed: Duration) -> bool {
+    if elapsed < SETTLE_AFTER {
+        return false;
     }
-
-    fn display_line(&self, index: usize, is_recommended: bool) -> String {
-        let status = if self.is_up { "🟢" } else { "🔴" };
-        let desc = self.description.as_deref().unwrap_or("No description");
-        let traffic_info = if self.traffic_bytes > 0 {
-            format!(" [{}]", format_bytes(self.traffic_bytes))
-        } else {
-            String::new()
-        };
-        let recommended = if is_recommended { " (recommended)" } else { "" };
-        format!("   {}. {} {}{}{} - {}", index + 1, status, self.name, traffic_info, recommended, desc)
-    }
-
-    fn measure_traffic(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Skip measurement for interfaces that are down
-        if !self.is_up {
-            return Ok(());
-        }
-
-        let device = match crate::dependencies::DependencyChecker::device_from_name_with_dependency_check(&self.name) {
-            Ok(device) => device,
-            Err(_) => return Ok(()), // Skip interfaces that can't be opened due to dependency issues
-        };
-        let cap = match crate::dependencies::DependencyChecker::capture_from_device_with_dependency_check(device) {
-            Ok(cap) => cap,
-            Err(_) => return Ok(()), // Skip interfaces that can't be opened due to dependency issues
-        };
-        let cap = cap.timeout(100)  // Short timeout for quick sampling
-            .open();
-        
-        let mut cap = match cap {
-            Ok(c) => c,
-            Err(_) => return Ok(()), // Skip interfaces we can't open
-        };
-
-        let start_time = Instant::now();
-        let mut total_bytes = 0u64;
-
-        // Measure for exactly 5 seconds
-        const MEASURE_DURATION_SECS: u64 = 5;
-        while start_time.elapsed() < Duration::from_secs(MEASURE_DURATION_SECS) {
-            match cap.next_packet() {
-                Ok(packet) => {
-                    total_bytes += packet.data.len() as u64;
-                }
-                Err(_) => {
-                    // Timeout or error, continue sampling
-                    thread::sleep(Duration::from_millis(10));
-                }
-            }
-        }
-
-        self.traffic_bytes = total_bytes;
-        Ok(())
-    }
-}
-
-// Helper function to format bytes (same as in ui/utils.rs)
-fn format_bytes(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-    const THRESHOLD: f64 = 1024.0;
-    
-    if bytes == 0 {
-        return "0 B".to_string();
-    }
-    
-    let bytes_f = bytes as f64;
-    let unit_index = (bytes_f.log(THRESHOLD).floor() as usize).min(UNITS.len() - 1);
-    let size = bytes_f / THRESHOLD.powi(unit_index as i32);
-    
-    if unit_index == 0 {
-        format!("{} {}", bytes, UNITS[unit_index])
-    } else {
-        format!("{:.1} {}", size, UNITS[unit_index])
+    let mut traffic: Vec<u64> = choices.iter()
+        .filter(|choice| !is_pseudo(&choice.name))
+        .filter_map(|choice| choice.traffic_bytes)
+        .collect();
+    traffic.sort_unstable_by(|a, b| b.cmp(a));
+    match traffic.as_slice() {
+        [leader, rest @ ..] => *leader >= SETTLE_MIN_BYTES && rest.first().is_none_or(|second| *leader >= second.saturating_mul(SETTLE_LEAD)),
+        [] => false,
     }
 }
 
@@ -271,58 +141,277 @@ fn handle_existing_config(saved: SavedConfig) -> Result<Option<InteractiveConfig
     }))
 }
 
-fn run_full_interactive_setup() -> Result<Option<InteractiveConfig>, io::Error> {
-    DisplayHelper::print_header("🚀 Welcome to Monitetoring - Interactive Setup", 50);
+/// The steps of the first-run setup wizard
+#[derive(Clone, Copy, PartialEq)]
+pub enum SetupStep {
+    Interface,
+    Options,
+}
+
+/// Checkboxes on the wizard's options step
+#[derive(Clone, Copy, PartialEq)]
+pub enum SetupOption {
+    JsonOutput,
+    Containers,
+    TotalColumns,
+    SaveSettings,
+}
 
-    // Step 1: Choose interface
-    let interface = choose_interface()?;
-    if interface.is_none() {
-        return Ok(None); // User chose to quit
+impl SetupOption {
+    pub const ALL: [SetupOption; 4] = [
+        SetupOption::JsonOutput,
+        SetupOption::Containers,
+        SetupOption::TotalColumns,
+        SetupOption::SaveSettings,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SetupOption::JsonOutput => "JSON output instead of the TUI",
+            SetupOption::Containers => "Container awareness",
+            SetupOption::TotalColumns => "Show total columns",
+            SetupOption::SaveSettings => "Save these settings",
+        }
     }
-    let interface = interface.unwrap();
 
-    // Step 2: Choose mode
-    let (json_mode, containers_mode) = choose_mode()?;
+    pub fn detail(self) -> &'static str {
+        match self {
+            SetupOption::JsonOutput => "A single 5-second capture printed as JSON, for automation",
+            SetupOption::Containers if !self.available() => "Not available on Windows",
+            SetupOption::Containers => "Identify and group processes by container (Docker, Podman, LXC, ...)",
+            SetupOption::TotalColumns => "Sent and received totals since monitoring started, in the process table",
+            SetupOption::SaveSettings => "Start straight away with them next time; change them later in the Settings view",
+        }
+    }
 
-    // Step 3: Choose display options
-    let show_total_columns = choose_display_options()?;
+    /// Container awareness reads cgroups, which Windows doesn't have
+    pub fn available(self) -> bool {
+        !(cfg!(windows) && self == SetupOption::Containers)
+    }
+}
 
-    // Step 4: Show summary and confirm
-    println!();
-    DisplayHelper::print_config_summary(&interface, json_mode, containers_mode, show_total_columns);
+enum WizardOutcome {
+    Continue,
+    Quit,
+    Start,
+}
 
-    // Step 4: Ask if user wants to save these settings
-    let save_settings = ask_save_settings()?;
+/// State of the first-run setup wizard
+pub struct SetupWizard {
+    pub step: SetupStep,
+    pub picker: InterfacePicker, // Interfaces and their traffic measurement, as in the TUI's dialog
+    pub confirm_down: bool,      // Enter was pressed once on an interface that is down
+    pub option_selected: usize,
+    pub json_mode: bool,
+    pub containers_mode: bool,
+    pub show_total_columns: bool,
+    pub save_settings: bool,
+    moved: bool, // The user picked an interface, so sorting by traffic keeps it selected
+}
 
-    // Step 5: Final confirmation
-    if !InputHandler::confirm_prompt("🔥 Start monitoring with these settings?", true)? {
-        println!("❌ Monitoring cancelled.");
+impl SetupWizard {
+    fn new(devices: Vec<pcap::Device>) -> Self {
+        let mut choices: Vec<InterfaceChoice> = devices.into_iter().map(|device| InterfaceChoice {
+            is_up: device.flags.is_up(),
+            name: device.name,
+            description: device.desc,
+            traffic_bytes: None,
+        }).collect();
+        sort_interfaces(&mut choices);
+        let measurement = measure_traffic_in_background(
+            choices.iter().filter(|choice| choice.is_up).map(|choice| choice.name.clone()).collect()
+        );
+        SetupWizard {
+            step: SetupStep::Interface,
+            picker: InterfacePicker {
+                choices,
+                selected: 0,
+                measurement: Some(measurement),
+                measure_started: Instant::now(),
+                measured_by: Instant::now() + Duration::from_secs(MEASURE_DURATION_SECS + OPEN_GRACE_SECS),
+            },
+            confirm_down: false,
+            option_selected: 0,
+            json_mode: false,
+            containers_mode: false,
+            show_total_columns: false,
+            save_settings: true,
+            moved: false,
+        }
+    }
+
+    pub fn is_checked(&self, option: SetupOption) -> bool {
+        match option {
+            SetupOption::JsonOutput => self.json_mode,
+            SetupOption::Containers => self.containers_mode,
+            SetupOption::TotalColumns => self.show_total_columns,
+            SetupOption::SaveSettings => self.save_settings,
+        }
+    }
+
+    fn toggle(&mut self, option: SetupOption) {
+        match option {
+            _ if !option.available() => {}
+            SetupOption::JsonOutput => self.json_mode = !self.json_mode,
+            SetupOption::Containers => self.containers_mode = !self.containers_mode,
+            SetupOption::TotalColumns => self.show_total_columns = !self.show_total_columns,
+            SetupOption::SaveSettings => self.save_settings = !self.save_settings,
+        }
+    }
+
+    pub fn measuring(&self) -> bool {
+        self.picker.measurement.is_some()
+    }
+
+    /// The busiest real interface that is up, once it saw any traffic
+    pub fn recommended(&self) -> Option<usize> {
+        self.picker.choices.iter().enumerate()
+            .filter(|(_, choice)| choice.is_up && !is_pseudo(&choice.name) && choice.traffic_bytes.unwrap_or(0) > 0)
+            .max_by_key(|(_, choice)| choice.traffic_bytes)
+            .map(|(i, _)| i)
+    }
+
+    fn poll(&mut self) {
+        if self.measuring() {
+            self.picker.poll_measurement();
+            if !self.measuring() {
+                self.finish_measurement();
+            }
+        }
+    }
+
+    /// Sort by traffic once the numbers are in, leaving the user's pick selected
+    fn finish_measurement(&mut self) {
+        self.picker.measurement = None;
+        let picked = self.picker.choices.get(self.picker.selected).map(|choice| choice.name.clone());
+        sort_interfaces(&mut self.picker.choices);
+        self.picker.selected = picked
+            .filter(|_| self.moved)
+            .and_then(|name| self.picker.choices.iter().position(|choice| choice.name == name))
+            .unwrap_or(0);
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> WizardOutcome {
+        match self.step {
+            SetupStep::Interface => match key {
+                KeyCode::Esc | KeyCode::Char('q') => return WizardOutcome::Quit,
+                KeyCode::Char('s') | KeyCode::Char('S') if self.measuring() => self.finish_measurement(),
+                KeyCode::Up if self.picker.selected > 0 => {
+                    self.picker.selected -= 1;
+                    self.moved = true;
+                    self.confirm_down = false;
+                }
+                KeyCode::Down if self.picker.selected + 1 < self.picker.choices.len() => {
+                    self.picker.selected += 1;
+                    self.moved = true;
+                    self.confirm_down = false;
+                }
+                KeyCode::Enter => {
+                    let is_up = self.picker.choices.get(self.picker.selected).is_some_and(|choice| choice.is_up);
+                    // A down interface takes a second Enter, after the warning
+                    if is_up || self.confirm_down {
+                        self.step = SetupStep::Options;
+                    }
+                    self.confirm_down = !self.confirm_down && !is_up;
+                }
+                _ => {}
+            },
+            SetupStep::Options => match key {
+                KeyCode::Esc => self.step = SetupStep::Interface,
+                KeyCode::Char('q') => return WizardOutcome::Quit,
+                KeyCode::Up if self.option_selected > 0 => self.option_selected -= 1,
+                KeyCode::Down if self.option_selected + 1 < SetupOption::ALL.len() => self.option_selected += 1,
+                KeyCode::Char(' ') => self.toggle(SetupOption::ALL[self.option_selected]),
+                KeyCode::Enter => return WizardOutcome::Start,
+                _ => {}
+            },
+        }
+        WizardOutcome::Continue
+    }
+}
+
+/// "any" and the monitoring-only devices come last and are never recommended
+fn is_pseudo(name: &str) -> bool {
+    name == "any" || name.starts_with("nfqueue") || name.starts_with("usbmon")
+}
+
+/// Real interfaces first, then those that are up, then the busiest
+fn sort_interfaces(choices: &mut [InterfaceChoice]) {
+    choices.sort_by(|a, b| {
+        is_pseudo(&a.name).cmp(&is_pseudo(&b.name))
+            .then(b.is_up.cmp(&a.is_up))
+            .then(b.traffic_bytes.unwrap_or(0).cmp(&a.traffic_bytes.unwrap_or(0)))
+    });
+}
+
+fn run_full_interactive_setup() -> Result<Option<InteractiveConfig>, io::Error> {
+    let devices = match crate::dependencies::DependencyChecker::list_devices_with_dependency_check() {
+        Ok(devices) => devices,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(None);
+        }
+    };
+    if devices.is_empty() {
+        eprintln!("❌ No network interfaces found.");
+        eprintln!("   Please check your network configuration.");
         return Ok(None);
     }
 
-    // Save configuration if user requested it
-    if save_settings {
-        save_user_config(&interface, json_mode, containers_mode, show_total_columns)?;
+    let mut wizard = SetupWizard::new(devices);
+    let started = {
+        let mut terminal = crate::ui::terminal::setup_terminal()?;
+        let _guard = crate::ui::terminal::TerminalGuard;
+        run_wizard(&mut terminal, &mut wizard)?
+    };
+    let Some(choice) = wizard.picker.choices.get(wizard.picker.selected).filter(|_| started) else {
+        println!("❌ Monitoring cancelled.");
+        return Ok(None);
+    };
+
+    let interface = choice.name.clone();
+    let containers_mode = wizard.containers_mode && SetupOption::Containers.available();
+    if wizard.save_settings {
+        save_user_config(&interface, wizard.json_mode, containers_mode, wizard.show_total_columns)?;
     }
 
     Ok(Some(InteractiveConfig {
         interface,
-        json_mode,
+        json_mode: wizard.json_mode,
         containers_mode,
-        show_total_columns,
+        show_total_columns: wizard.show_total_columns,
     }))
 }
 
+/// Draw the wizard until it is finished; false when the user quit
+fn run_wizard(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, wizard: &mut SetupWizard) -> Result<bool, io::Error> {
+    loop {
+        wizard.poll();
+        terminal.draw(|f| crate::ui::renderers::setup::render(f, wizard))?;
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press {
+                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    return Ok(false);
+                }
+                match wizard.handle_key(key.code) {
+                    WizardOutcome::Continue => {}
+                    WizardOutcome::Quit => return Ok(false),
+                    WizardOutcome::Start => return Ok(true),
+                }
+            }
+    }
+}
+
 fn save_user_config(interface: &str, json_mode: bool, containers_mode: bool, show_total_columns: bool) -> Result<(), io::Error> {
     let config = SavedConfig {
         interface: interface.to_string(),
         json_mode,
         containers_mode,
         show_total_columns,
-        alerts: vec![], // Initialize with no alerts
-        large_packet_threshold: 100_000,
-        frequent_connection_threshold: 20,
-        setup_offered: false, // Will be set to true when we offer automatic setup
+        ..Default::default()
     };
     
     match save_config(&config) {
@@ -340,221 +429,80 @@ fn save_user_config(interface: &str, json_mode: bool, containers_mode: bool, sho
     Ok(())
 }
 
-fn ask_save_settings() -> Result<bool, io::Error> {
-    println!("💾 Save these settings for future use?");
-    println!("   (Next time you run the program, you can use these settings quickly)");
-    println!();
-    
-    InputHandler::confirm_prompt("💾 Save settings?", true)
-}
-
-fn choose_interface() -> Result<Option<String>, io::Error> {
-    loop {
-        println!("🔌 Available Network Interfaces:");
-        
-        let devices = match crate::dependencies::DependencyChecker::list_devices_with_dependency_check() {
-            Ok(d) => d,
-            Err(e) => {
-                eprintln!("{}", e);
-                return Ok(None);
-            }
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if devices.is_empty() {
-            eprintln!("❌ No network interfaces found.");
-            eprintln!("   Please check your network configuration.");
-            return Ok(None);
-        }
-
-        let mut interfaces: Vec<NetworkInterface> = devices
-            .into_iter()
-            .map(NetworkInterface::from_device)
-            .collect();
-
-        // ===== TRAFFIC MEASUREMENT SECTION =====
-        // CRITICAL: This section measures network traffic on interfaces to help users
-        // identify the busiest interface. The implementation is carefully designed to:
-        // 1. Never block longer than MEASURE_DURATION_SECS + OPEN_GRACE_SECS (6s total)
-        // 2. Allow users to skip measurement with 'S' key
-        // 3. Handle slow interface opens that can take 100-500ms each on Linux
-        //
-        // DEVELOPER WARNING: Modifying this section can cause:
-        // - Interactive mode hanging for >10s when no traffic present
-        // - Traffic measurements returning zero due to timing issues
-        // - User interface becoming unresponsive
-        //
-        // Key timing considerations:
-        // - Each worker thread does: open interface (~100-500ms) + measure for 5s + send result
-        // - Main thread waits maximum 6s total (5s measurement + 1s grace for opens)
-        // - If interface opens are slow, results may arrive after main thread timeout
-        // - OPEN_GRACE_SECS compensates for typical interface open delays
-        
-        const MEASURE_DURATION_SECS: u64 = 5;
-        const OPEN_GRACE_SECS: u64 = 1; // Extra time for slow interface opens on Linux
-        let deadline = Instant::now() + Duration::from_secs(MEASURE_DURATION_SECS + OPEN_GRACE_SECS);
-        println!(
-            "📊 Measuring traffic on interfaces for {} seconds…",
-            MEASURE_DURATION_SECS
-        );
-        println!("   (Press [S] to skip and continue immediately)");
-        println!("   (This helps identify the busiest interface)");
-        println!();
-        
-        // Spawn worker threads to measure each interface concurrently
-        // Each thread: opens interface → samples for MEASURE_DURATION_SECS → sends result
-        let (tx, rx) = mpsc::channel();
-        for interface in &mut interfaces {
-            if interface.is_up {
-                let interface_name = interface.name.clone();
-                let tx = tx.clone();
-                thread::spawn(move || {
-                    let mut temp_interface = NetworkInterface {
-                        name: interface_name.clone(),
-                        description: None,
-                        is_up: true,
-                        traffic_bytes: 0,
-                    };
-                    let _ = temp_interface.measure_traffic();
-                    // Ignore send errors (main thread may timeout)
-                    let _ = tx.send((interface_name, temp_interface.traffic_bytes));
-                });
-            }
-        }
-
-        drop(tx); // Close sending side in main thread
-
-        // Collect results with hard deadline to prevent hanging
-        // Main thread waits max 6s regardless of worker thread status
-        let mut traffic_results: HashMap<String, u64> = HashMap::new();
-        loop {
-            // Allow user to skip measurement early with 's' key
-            // This provides immediate escape if user doesn't want to wait
-            if event::poll(Duration::from_millis(0)).unwrap_or(false)
-                && let Ok(Event::Key(k)) = event::read()
-                    && matches!(k.code, KeyCode::Char('s') | KeyCode::Char('S')) {
-                        println!("⏭️  Skipping traffic measurement…");
-                        break;
-                    }
-
-            let remaining = deadline.saturating_duration_since(Instant::now());
-            if remaining.is_zero() {
-                break;
-            }
-
-            match rx.recv_timeout(Duration::from_millis(50)) {
-                Ok((name, bytes)) => {
-                    traffic_results.insert(name, bytes);
-                }
-                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
-                    // Continue waiting until deadline or skip
-                }
-                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
-                    break;
-                }
-            }
-        }
-        
-        // Update interfaces with measured traffic
-        for interface in &mut interfaces {
-            if let Some(&traffic) = traffic_results.get(&interface.name) {
-                interface.traffic_bytes = traffic;
-            }
-        }
-        
-        // Sort interfaces: real interfaces first, pseudo ("any"/"nfqueue"/"usbmon") last,
-        // then by up/down status and finally by traffic bytes.
-        interfaces.sort_by(|a, b| {
-            use std::cmp::Ordering::*;
-            match (a.is_pseudo(), b.is_pseudo()) {
-                (false, true) => Less,
-                (true, false) => Greater,
-                _ => {
-                    match (a.is_up, b.is_up) {
-                        (true, false) => Less,
-                        (false, true) => Greater,
-                        _ => b.traffic_bytes.cmp(&a.traffic_bytes),
-                    }
-                }
-            }
-        });
-
-        // Display interfaces with enhanced information
-        for (i, interface) in interfaces.iter().enumerate() {
-            let is_recommended = i == 0 && interface.is_up && interface.traffic_bytes > 0 && !interface.is_pseudo();
-            println!("{}", interface.display_line(i, is_recommended));
-        }
-        
-        println!("   0. Quit");
-        println!();
-        println!(
-            "🟢 = Interface is up   🔴 = Interface is down   [traffic] = Bytes observed in {}s",
-            MEASURE_DURATION_SECS
-        );
-        println!();
-        
-        match InputHandler::numeric_choice_prompt("📡 Select interface (number)", 0, interfaces.len())? {
-            None => return Ok(None), // Quit
-            Some(n) => {
-                let selected_interface = &interfaces[n - 1];
-                
-                if !selected_interface.is_up {
-                    println!("⚠️  Warning: Selected interface '{}' appears to be down.", selected_interface.name);
-                    if !InputHandler::confirm_prompt("   Continue anyway?", false)? {
-                        println!();
-                        continue; // Go back to interface selection
-                    }
-                }
-                
-                return Ok(Some(selected_interface.name.clone()));
-            }
-        }
+    fn choice(name: &str, traffic_bytes: Option<u64>) -> InterfaceChoice {
+        InterfaceChoice { name: name.to_string(), description: None, is_up: true, traffic_bytes }
     }
-}
-
-fn choose_mode() -> Result<(bool, bool), io::Error> {
-    loop {
-        println!("📊 Choose Output Mode:");
-        println!("   1. Interactive TUI (recommended) - Real-time monitoring interface");
-        println!("   2. JSON output - Single 5-second capture for automation");
-        println!();
-        
-        let json_mode = match InputHandler::numeric_choice_prompt_with_default("📊 Select mode (1-2)", 1, 2, 1)? {
-            Some(1) => false,
-            Some(2) => true,
-            _ => {
-                println!("❌ Invalid selection. Please choose 1 or 2.");
-                println!();
-                continue;
-            }
-        };
 
-        // On Windows builds, skip the container awareness prompt entirely and disable the feature.
-        if cfg!(windows) {
-            println!();
-            println!("🐳 Container awareness is not available on Windows and will be disabled.");
-            println!();
-            return Ok((json_mode, false));
+    /// A wizard whose measurement has finished, without the background threads of `SetupWizard::new`
+    fn wizard(mut choices: Vec<InterfaceChoice>) -> SetupWizard {
+        sort_interfaces(&mut choices);
+        SetupWizard {
+            step: SetupStep::Interface,
+            picker: InterfacePicker {
+                choices,
+                selected: 0,
+                measurement: None,
+                measure_started: Instant::now(),
+                measured_by: Instant::now(),
+            },
+            confirm_down: false,
+            option_selected: 0,
+            json_mode: false,
+            containers_mode: false,
+            show_total_columns: false,
+            save_settings: true,
+            moved: false,
         }
+    }
 
-        // Ask about container awareness (Linux / macOS)
-        println!();
-        println!("🐳 Container Awareness:");
-        println!("   This feature identifies and groups processes by container");
-        println!("   (Docker, Podman, LXC, etc.)");
-        println!();
-
-        let containers_mode = InputHandler::confirm_prompt("🐳 Enable container awareness?", false)?;
+    #[test]
+    fn recommends_the_busiest_real_interface() {
+        assert_eq!(wizard(vec![choice("eth0", Some(0)), choice("any", Some(1024))]).recommended(), None);
+        let down = InterfaceChoice { is_up: false, ..choice("eth1", Some(50 * 1024)) };
+        let wizard = wizard(vec![choice("any", Some(9 * 1024)), down, choice("wlan0", Some(1024)), choice("eth0", Some(4 * 1024))]);
+        let names: Vec<&str> = wizard.picker.choices.iter().map(|choice| choice.name.as_str()).collect();
+        assert_eq!(names, ["eth0", "wlan0", "eth1", "any"]);
+        assert_eq!(wizard.recommended(), Some(0));
+    }
 
-        return Ok((json_mode, containers_mode));
+    #[test]
+    fn walks_through_the_steps() {
+        let down = InterfaceChoice { is_up: false, ..choice("eth1", None) };
+        let mut wizard = wizard(vec![choice("eth0", None), down]);
+        wizard.handle_key(KeyCode::Down);
+        // A down interface asks for a second Enter
+        wizard.handle_key(KeyCode::Enter);
+        assert!(wizard.step == SetupStep::Interface && wizard.confirm_down);
+        wizard.handle_key(KeyCode::Enter);
+        assert!(wizard.step == SetupStep::Options && !wizard.confirm_down);
+
+        wizard.handle_key(KeyCode::Char(' '));
+        wizard.handle_key(KeyCode::Down);
+        wizard.handle_key(KeyCode::Down);
+        wizard.handle_key(KeyCode::Char(' '));
+        assert!(wizard.json_mode && wizard.show_total_columns && wizard.save_settings);
+        assert!(matches!(wizard.handle_key(KeyCode::Enter), WizardOutcome::Start));
+
+        wizard.handle_key(KeyCode::Esc);
+        assert!(wizard.step == SetupStep::Interface);
+        assert_eq!(wizard.picker.selected, 1);
+        assert!(matches!(wizard.handle_key(KeyCode::Char('q')), WizardOutcome::Quit));
     }
-}
 
-fn choose_display_options() -> Result<bool, io::Error> {
-    println!();
-    println!("📈 Display Options:");
-    println!("   Show total bandwidth columns (Sent Total / Received Total) in process table?");
-    println!("   These columns show cumulative data usage since monitoring started.");
-    println!();
-    
-    InputHandler::confirm_prompt("📈 Show total columns?", false)
+    #[test]
+    fn settles_once_one_interface_clearly_leads() {
+        let second = Duration::from_secs(2);
+        let choices = vec![choice("eth0", Some(4 * 1024 * 1024)), choice("wlan0", Some(100 * 1024)), choice("any", Some(5 * 1024 * 1024))];
+        assert!(measurement_settled(&choices, second));
+        assert!(!measurement_settled(&choices, Duration::from_millis(500)));
+        // Close race, too little traffic, or nothing reported yet
+        assert!(!measurement_settled(&[choice("eth0", Some(4 * 1024 * 1024)), choice("wlan0", Some(1024 * 1024))], second));
+        assert!(!measurement_settled(&[choice("eth0", Some(10 * 1024)), choice("wlan0", None)], second));
+        assert!(!measurement_settled(&[choice("eth0", None)], second));
+        assert!(measurement_settled(&[choice("eth0", Some(1024 * 1024))], second));
+    }
 }
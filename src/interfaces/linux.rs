@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::Path;
+use crate::types::InterfaceStats;
+
+const SYS_CLASS_NET: &str = "/sys/class/net";
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_counter(dir: &Path, name: &str) -> u64 {
+    read_trimmed(&dir.join("statistics").join(name))
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Read link state, speed and counters for every interface in /sys/class/net
+pub fn read_interfaces() -> Vec<InterfaceStats> {
+    let Ok(entries) = fs::read_dir(SYS_CLASS_NET) else {
+        return Vec::new();
+    };
+
+    let mut interfaces: Vec<InterfaceStats> = entries
+        .flatten()
+        .map(|entry| {
+            let dir = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // Loopback and some virtual links report "unknown" while passing traffic
+            let is_up = matches!(read_trimmed(&dir.join("operstate")).as_deref(), Some("up") | Some("unknown"))
+                && read_trimmed(&dir.join("carrier")).as_deref() != Some("0");
+            // Reading `speed` fails with EINVAL for links without one; -1 means unknown
+            let speed_mbps = read_trimmed(&dir.join("speed"))
+                .and_then(|s| s.parse::<i64>().ok())
+                .filter(|speed| *speed > 0)
+                .map(|speed| speed as u64);
+
+            InterfaceStats {
+                name,
+                is_up,
+                speed_mbps,
+                rx_bytes: read_counter(&dir, "rx_bytes"),
+                tx_bytes: read_counter(&dir, "tx_bytes"),
+                rx_dropped: read_counter(&dir, "rx_dropped"),
+                tx_dropped: read_counter(&dir, "tx_dropped"),
+                rx_errors: read_counter(&dir, "rx_errors"),
+                tx_errors: read_counter(&dir, "tx_errors"),
+                rx_rate: 0,
+                tx_rate: 0,
+                wireless: None,
+            }
+        })
+        .collect();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
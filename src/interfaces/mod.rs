@@ -0,0 +1,17 @@
+// Per-NIC counters as reported by the operating system, used by the
+// Interfaces view to cross-check the per-process totals, and the link
+// quality of Wi-Fi interfaces.
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+pub use linux::*;
+#[cfg(target_os = "linux")]
+mod wireless;
+#[cfg(target_os = "linux")]
+pub use wireless::read_wireless;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+pub use windows::*;
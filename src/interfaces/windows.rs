@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+use windows_sys::Win32::Foundation::NO_ERROR;
+use windows_sys::Win32::NetworkManagement::IpHelper::{FreeMibTable, GetIfTable2, MIB_IF_ROW2, MIB_IF_TABLE2};
+use windows_sys::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+use crate::types::InterfaceStats;
+
+/// IF_TYPE_SOFTWARE_LOOPBACK
+const LOOPBACK_IF_TYPE: u32 = 24;
+
+fn wide_to_string(wide: &[u16]) -> String {
+    let len = wide.iter().position(|c| *c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..len])
+}
+
+/// Wi-Fi link quality comes from nl80211, which only Linux has
+pub fn read_wireless(_interfaces: &mut [InterfaceStats]) {}
+
+/// Read link state, speed and counters for every interface from the IP Helper API
+pub fn read_interfaces() -> Vec<InterfaceStats> {
+    let mut table: *mut MIB_IF_TABLE2 = std::ptr::null_mut();
+    // SAFETY: GetIfTable2 allocates the table and stores its address in `table`
+    if unsafe { GetIfTable2(&mut table) } != NO_ERROR || table.is_null() {
+        return Vec::new();
+    }
+
+    // SAFETY: on success `table` points to NumEntries rows laid out after the header
+    let rows: &[MIB_IF_ROW2] = unsafe {
+        std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize)
+    };
+
+    let mut seen = HashSet::new();
+    let mut interfaces = Vec::new();
+    for row in rows {
+        // Skip the filter/miniport shadows Windows lists for every physical adapter
+        let hardware = row.InterfaceAndOperStatusFlags._bitfield & 0x1 != 0;
+        if !hardware && row.Type != LOOPBACK_IF_TYPE {
+            continue;
+        }
+        let name = wide_to_string(&row.Alias);
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+
+        let link_speed = row.ReceiveLinkSpeed.max(row.TransmitLinkSpeed);
+        interfaces.push(InterfaceStats {
+            name,
+            is_up: row.OperStatus == IfOperStatusUp,
+            speed_mbps: (link_speed > 0 && link_speed != u64::MAX).then_some(link_speed / 1_000_000),
+            rx_bytes: row.InOctets,
+            tx_bytes: row.OutOctets,
+            rx_dropped: row.InDiscards,
+            tx_dropped: row.OutDiscards,
+            rx_errors: row.InErrors,
+            tx_errors: row.OutErrors,
+            rx_rate: 0,
+            tx_rate: 0,
+            wireless: None,
+        });
+    }
+
+    // SAFETY: `table` came from GetIfTable2 and is not used after this
+    unsafe { FreeMibTable(table as *const _) };
+
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
@@ -0,0 +1,151 @@
+// Wi-Fi link quality from nl80211, through the `iw` tool: the network an
+// interface is associated with, its signal, bitrates and how often frames had
+// to be retried. A weak or crowded link, not the processes, is often why
+// throughput drops. Without `iw`, /proc/net/wireless still gives the signal.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use crate::types::{InterfaceStats, WirelessLink};
+
+const SYS_CLASS_NET: &str = "/sys/class/net";
+const PROC_WIRELESS: &str = "/proc/net/wireless";
+
+fn is_wireless(name: &str) -> bool {
+    let dir = Path::new(SYS_CLASS_NET).join(name);
+    dir.join("wireless").exists() || dir.join("phy80211").exists()
+}
+
+fn iw(name: &str, command: &str) -> Option<String> {
+    let output = Command::new("iw").args(["dev", name, command]).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Fill in `wireless` for the Wi-Fi interfaces among `interfaces`
+pub fn read_wireless(interfaces: &mut [InterfaceStats]) {
+    for iface in interfaces.iter_mut().filter(|iface| is_wireless(&iface.name)) {
+        let mut link = match iw(&iface.name, "link") {
+            Some(output) => parse_link(&output),
+            None => WirelessLink {
+                signal_dbm: fs::read_to_string(PROC_WIRELESS).ok()
+                    .and_then(|content| parse_proc_signal(&content, &iface.name)),
+                ..Default::default()
+            },
+        };
+        if link.ssid.is_some()
+            && let Some(output) = iw(&iface.name, "station dump") {
+                parse_station(&output, &mut link);
+            }
+        iface.wireless = Some(link);
+    }
+}
+
+/// First number of a value such as "-52 dBm" or "866.7 MBit/s VHT-MCS 9"
+fn leading_number(value: &str) -> Option<f64> {
+    value.split_whitespace().next()?.parse().ok()
+}
+
+/// `iw dev <name> link`
+fn parse_link(output: &str) -> WirelessLink {
+    let mut link = WirelessLink::default();
+    if output.trim_start().starts_with("Not connected") {
+        return link;
+    }
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "SSID" => link.ssid = Some(value.to_string()),
+            "freq" => link.frequency_mhz = leading_number(value).map(|mhz| mhz as u32),
+            "signal" => link.signal_dbm = leading_number(value).map(|dbm| dbm as i32),
+            "rx bitrate" => link.rx_bitrate_mbps = leading_number(value),
+            "tx bitrate" => link.tx_bitrate_mbps = leading_number(value),
+            _ => {}
+        }
+    }
+    link
+}
+
+/// Transmit counters of the access point's entry in `iw dev <name> station dump`
+fn parse_station(output: &str, link: &mut WirelessLink) {
+    // A client has a single station, the access point; keep to the first one
+    for line in output.lines().skip(1).take_while(|line| !line.starts_with("Station ")) {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim().parse().ok();
+        match key {
+            "tx packets" => link.tx_packets = value,
+            "tx retries" => link.tx_retries = value,
+            "tx failed" => link.tx_failed = value,
+            _ => {}
+        }
+    }
+}
+
+/// Signal level of `name` from /proc/net/wireless (in dBm with current drivers)
+fn parse_proc_signal(content: &str, name: &str) -> Option<i32> {
+    content.lines().skip(2).find_map(|line| {
+        let (iface, fields) = line.trim().split_once(':')?;
+        if iface != name {
+            return None;
+        }
+        let level: f64 = fields.split_whitespace().nth(2)?.trim_end_matches('.').parse().ok()?;
+        Some(level as i32)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINK: &str = "Connected to aa:bb:cc:dd:ee:ff (on wlan0)
+\tSSID: Office 5G
+\tfreq: 5180.0
+\tRX: 81525392 bytes (63215 packets)
+\tTX: 4285173 bytes (21047 packets)
+\tsignal: -61 dBm
+\trx bitrate: 866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2
+\ttx bitrate: 650.0 MBit/s VHT-MCS 7 80MHz short GI VHT-NSS 2
+";
+
+    const STATION: &str = "Station aa:bb:cc:dd:ee:ff (on wlan0)
+\tinactive time:\t12 ms
+\ttx packets:\t21047
+\ttx retries:\t1893
+\ttx failed:\t4
+\tsignal:  \t-61 [-63, -65] dBm
+Station 11:22:33:44:55:66 (on wlan0)
+\ttx packets:\t9
+";
+
+    #[test]
+    fn parses_link() {
+        let link = parse_link(LINK);
+        assert_eq!(link.ssid.as_deref(), Some("Office 5G"));
+        assert_eq!(link.frequency_mhz, Some(5180));
+        assert_eq!(link.signal_dbm, Some(-61));
+        assert_eq!(link.rx_bitrate_mbps, Some(866.7));
+        assert_eq!(link.tx_bitrate_mbps, Some(650.0));
+        assert!(parse_link("Not connected.\n").ssid.is_none());
+    }
+
+    #[test]
+    fn parses_first_station() {
+        let mut link = WirelessLink::default();
+        parse_station(STATION, &mut link);
+        assert_eq!((link.tx_packets, link.tx_retries, link.tx_failed), (Some(21047), Some(1893), Some(4)));
+    }
+
+    #[test]
+    fn parses_proc_wireless() {
+        let content = "Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+ face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+ wlan0: 0000   49.  -61.  -256        0      0      0      7      0        0
+";
+        assert_eq!(parse_proc_signal(content, "wlan0"), Some(-61));
+        assert_eq!(parse_proc_signal(content, "wlan1"), None);
+    }
+}
@@ -0,0 +1,257 @@
+// Versioned `--json` output. Version 1 (the default) is the bare array of
+// processes consumers already parse. Version 2 wraps it in an object that
+// also describes the capture (interface, host, when and for how long) and
+// adds a protocol breakdown per process and, with `--json-connections`, the
+// connections each process used, built from its recorded packets.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::SystemTime;
+use serde::Serialize;
+use crate::delta::StatsDelta;
+use crate::types::{PacketDirection, PacketInfo, ProcessInfo, ProcessInfoJson};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum JsonSchema {
+    #[default]
+    V1,
+    V2,
+}
+
+impl FromStr for JsonSchema {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        match input.to_ascii_lowercase().trim_start_matches('v') {
+            "1" => Ok(JsonSchema::V1),
+            "2" => Ok(JsonSchema::V2),
+            _ => Err(format!("'{}' is not a JSON schema version (v1 or v2)", input)),
+        }
+    }
+}
+
+/// What was captured, where and when
+#[derive(Serialize)]
+pub struct CaptureMetadata {
+    pub interface: String,
+    pub host: String,
+    pub started: String,
+    pub ended: String,
+    pub duration_secs: f64,
+    /// `--filter`/`--pid` restriction, when there was one
+    pub filter: Option<String>,
+    pub containers: bool,
+    /// Byte counts come from TCP connection statistics, not captured packets (Windows without Npcap)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub estimated: bool,
+    /// What the processes' byte counts count: "wire" or "payload"
+    pub counting: crate::accounting::ByteCounting,
+    pub monitetoring_version: &'static str,
+}
+
+#[derive(Serialize, Default, Debug, PartialEq)]
+pub struct ProtocolBytes {
+    pub tcp: u64,
+    pub udp: u64,
+    pub quic: u64, // Part of `udp`
+    pub other: u64, // ICMP and anything else that isn't TCP or UDP
+    pub ipv4: u64,
+    pub ipv6: u64,
+}
+
+impl ProtocolBytes {
+    fn of(info: &ProcessInfo) -> Self {
+        let total = info.sent + info.received;
+        ProtocolBytes {
+            tcp: info.tcp_bytes,
+            udp: info.udp_bytes,
+            quic: info.quic_bytes,
+            other: total.saturating_sub(info.tcp_bytes + info.udp_bytes),
+            ipv4: info.ipv4_bytes,
+            ipv6: info.ipv6_bytes,
+        }
+    }
+}
+
+/// Traffic of one connection of a process, seen from the process's side
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ConnectionRecord {
+    pub protocol: &'static str,
+    pub local_address: IpAddr,
+    pub local_port: u16,
+    pub remote_address: IpAddr,
+    pub remote_port: u16,
+    pub sent_bytes: u64,
+    pub received_bytes: u64,
+    pub packets: u64,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+#[derive(Serialize)]
+pub struct ProcessRecord {
+    #[serde(flatten)]
+    pub process: ProcessInfoJson,
+    pub protocols: ProtocolBytes,
+    /// Bytes per category of the config's classification rules
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub categories: BTreeMap<String, u64>,
+    /// Packets that didn't fit the packet history; `connections` leaves their traffic out
+    pub packets_evicted: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connections: Option<Vec<ConnectionRecord>>,
+}
+
+#[derive(Serialize)]
+pub struct JsonDocument {
+    pub schema: u32,
+    pub capture: CaptureMetadata,
+    pub processes: Vec<ProcessRecord>,
+}
+
+fn protocol_name(protocol: u8) -> &'static str {
+    match protocol {
+        6 => "tcp",
+        17 => "udp",
+        1 | 58 => "icmp",
+        _ => "other",
+    }
+}
+
+fn format_timestamp(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(time).to_rfc3339()
+}
+
+/// Group packets into connections, the busiest first
+pub fn connections<'a>(packets: impl IntoIterator<Item = &'a PacketInfo>) -> Vec<ConnectionRecord> {
+    struct Totals {
+        sent: u64,
+        received: u64,
+        packets: u64,
+        first: SystemTime,
+        last: SystemTime,
+    }
+
+    let mut grouped: HashMap<(u8, IpAddr, u16, IpAddr, u16), Totals> = HashMap::new();
+    for packet in packets {
+        let key = match packet.direction {
+            PacketDirection::Sent => (packet.protocol, packet.src_ip, packet.src_port, packet.dst_ip, packet.dst_port),
+            PacketDirection::Received => (packet.protocol, packet.dst_ip, packet.dst_port, packet.src_ip, packet.src_port),
+        };
+        let totals = grouped.entry(key).or_insert(Totals {
+            sent: 0,
+            received: 0,
+            packets: 0,
+            first: packet.timestamp,
+            last: packet.timestamp,
+        });
+        match packet.direction {
+            PacketDirection::Sent => totals.sent += packet.size as u64,
+            PacketDirection::Received => totals.received += packet.size as u64,
+        }
+        totals.packets += 1;
+        totals.first = totals.first.min(packet.timestamp);
+        totals.last = totals.last.max(packet.timestamp);
+    }
+
+    let mut records: Vec<ConnectionRecord> = grouped.into_iter()
+        .map(|((protocol, local_address, local_port, remote_address, remote_port), totals)| ConnectionRecord {
+            protocol: protocol_name(protocol),
+            local_address,
+            local_port,
+            remote_address,
+            remote_port,
+            sent_bytes: totals.sent,
+            received_bytes: totals.received,
+            packets: totals.packets,
+            first_seen: format_timestamp(totals.first),
+            last_seen: format_timestamp(totals.last),
+        })
+        .collect();
+    records.sort_by(|a, b| {
+        (b.sent_bytes + b.received_bytes).cmp(&(a.sent_bytes + a.received_bytes))
+            .then_with(|| (a.remote_address, a.remote_port).cmp(&(b.remote_address, b.remote_port)))
+    });
+    records
+}
+
+/// The version 1 array: one entry per process, the busiest first
+pub fn processes_v1(stats: &StatsDelta) -> Vec<ProcessInfoJson> {
+    let mut items: Vec<ProcessInfoJson> = stats.iter()
+        .map(|(pid, update)| ProcessInfoJson::from((pid, &update.info)))
+        .collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.sent_bytes + item.received_bytes));
+    items
+}
+
+/// The version 2 document; the final update of a bounded capture carries every
+/// recorded packet, which is where connections come from
+pub fn document_v2(stats: &StatsDelta, capture: CaptureMetadata, with_connections: bool) -> JsonDocument {
+    let mut processes: Vec<ProcessRecord> = stats.iter()
+        .map(|(pid, update)| ProcessRecord {
+            process: ProcessInfoJson::from((pid, &update.info)),
+            protocols: ProtocolBytes::of(&update.info),
+            categories: update.info.category_bytes.iter().map(|(name, bytes)| (name.clone(), *bytes)).collect(),
+            packets_evicted: update.packets_evicted,
+            connections: with_connections.then(|| connections(&update.new_packets)),
+        })
+        .collect();
+    processes.sort_by_key(|record| std::cmp::Reverse(record.process.sent_bytes + record.process.received_bytes));
+    JsonDocument { schema: 2, capture, processes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn packet(direction: PacketDirection, remote: &str, remote_port: u16, size: usize, at: u64) -> PacketInfo {
+        let local: IpAddr = "10.0.0.2".parse().unwrap();
+        let remote: IpAddr = remote.parse().unwrap();
+        let (src_ip, src_port, dst_ip, dst_port) = match direction {
+            PacketDirection::Sent => (local, 40000, remote, remote_port),
+            PacketDirection::Received => (remote, remote_port, local, 40000),
+        };
+        PacketInfo {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(at),
+            direction,
+            protocol: 6,
+            src_ip,
+            src_port,
+            dst_ip,
+            dst_port,
+            size,
+            cached_ts: String::new(),
+            cached_src: String::new(),
+            cached_dst: String::new(),
+            cached_proto: String::new(),
+            cached_size: String::new(),
+        }
+    }
+
+    #[test]
+    fn groups_packets_by_connection() {
+        let packets = [
+            packet(PacketDirection::Sent, "93.184.216.34", 443, 100, 10),
+            packet(PacketDirection::Received, "93.184.216.34", 443, 1500, 11),
+            packet(PacketDirection::Sent, "1.1.1.1", 53, 60, 12),
+            packet(PacketDirection::Received, "93.184.216.34", 443, 1500, 9),
+        ];
+        let records = connections(&packets);
+        assert_eq!(records.len(), 2);
+        let https = &records[0];
+        assert_eq!((https.remote_port, https.local_port), (443, 40000));
+        assert_eq!((https.sent_bytes, https.received_bytes, https.packets), (100, 3000, 3));
+        assert_eq!(https.first_seen, format_timestamp(SystemTime::UNIX_EPOCH + Duration::from_secs(9)));
+        assert_eq!(https.last_seen, format_timestamp(SystemTime::UNIX_EPOCH + Duration::from_secs(11)));
+        assert_eq!((records[1].remote_port, records[1].sent_bytes), (53, 60));
+    }
+
+    #[test]
+    fn parses_schema_versions() {
+        assert_eq!("v2".parse::<JsonSchema>(), Ok(JsonSchema::V2));
+        assert_eq!("1".parse::<JsonSchema>(), Ok(JsonSchema::V1));
+        assert!("v3".parse::<JsonSchema>().is_err());
+    }
+}
@@ -0,0 +1,264 @@
+// Bandwidth limits for single processes, on Linux. A limited process moves
+// into a net_cls cgroup of its own, whose class ID tags the packets it sends;
+// a `cgroup` filter on an HTB qdisc then queues them in a class capped at the
+// chosen rate. Only outgoing traffic can be shaped this way. Limits stay in
+// place when monitetoring exits and are picked up again at the next start;
+// removing one (or the process exiting) takes the cgroup and class away, and
+// the qdisc goes with the interface's last limit.
+//
+// Each cgroup is named `<interface>.<minor>` under `monitetoring/`, the minor
+// being its tc class under the `6d6e:` qdisc.
+
+/// Where the limit lives: `tc` class `6d6e:<minor>` on `interface`
+#[derive(Clone, Debug, PartialEq)]
+pub struct BandwidthLimit {
+    pub interface: String,
+    pub minor: u16,
+    pub rate: u64, // Bytes per second the process may send
+}
+
+#[cfg(target_os = "linux")]
+const HTB_MAJOR: u16 = 0x6d6e;
+
+/// Where cgroup v1 mounts net_cls, alone or together with net_prio
+#[cfg(target_os = "linux")]
+const NET_CLS_MOUNTS: [&str; 3] = ["/sys/fs/cgroup/net_cls", "/sys/fs/cgroup/net_cls,net_prio", "/sys/fs/cgroup/net_prio,net_cls"];
+
+#[cfg(target_os = "linux")]
+const CGROUP_DIR: &str = "monitetoring";
+
+#[cfg(target_os = "linux")]
+fn tc(args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new("tc").args(args).output()
+        .map_err(|e| format!("can't run tc ({}); it is in iproute2", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!("tc {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn net_cls_root() -> Result<std::path::PathBuf, String> {
+    NET_CLS_MOUNTS.iter()
+        .map(std::path::Path::new)
+        .find(|mount| mount.join("net_cls.classid").exists())
+        .map(|mount| mount.to_path_buf())
+        .ok_or_else(|| "the net_cls cgroup controller isn't mounted (cgroup v2-only systems don't have it)".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn cgroup_path(root: &std::path::Path, limit: &BandwidthLimit) -> std::path::PathBuf {
+    root.join(CGROUP_DIR).join(format!("{}.{}", limit.interface, limit.minor))
+}
+
+#[cfg(target_os = "linux")]
+fn class_id(minor: u16) -> String {
+    format!("{:x}:{:x}", HTB_MAJOR, minor)
+}
+
+/// Interface of the default route, where limits go when capturing on `any`
+#[cfg(target_os = "linux")]
+pub fn default_interface() -> Option<String> {
+    parse_default_route(&std::fs::read_to_string("/proc/net/route").ok()?)
+}
+
+#[cfg(target_os = "linux")]
+fn parse_default_route(routes: &str) -> Option<String> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let (iface, destination) = (fields.next()?, fields.next()?);
+        (destination == "00000000").then(|| iface.to_string())
+    })
+}
+
+/// Rate of a tc class as `tc class show` prints it ("800Kbit", "1200bit"), in bytes per second
+#[cfg(target_os = "linux")]
+fn parse_tc_rate(rate: &str) -> Option<u64> {
+    let number_end = rate.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = rate.split_at(number_end);
+    let number: f64 = number.parse().ok()?;
+    let bits = match unit {
+        "bit" => number,
+        "Kbit" => number * 1e3,
+        "Mbit" => number * 1e6,
+        "Gbit" => number * 1e9,
+        "Tbit" => number * 1e12,
+        "bps" => number * 8.0,
+        _ => return None,
+    };
+    Some((bits / 8.0).round() as u64)
+}
+
+/// The HTB qdisc and the cgroup filter feeding it, added with an interface's first limit
+#[cfg(target_os = "linux")]
+fn ensure_qdisc(interface: &str) -> Result<(), String> {
+    let handle = format!("{:x}:", HTB_MAJOR);
+    if tc(&["qdisc", "show", "dev", interface])?.contains(&format!("qdisc htb {} root", handle)) {
+        return Ok(());
+    }
+    // Without a default class, traffic no cgroup claims passes unshaped
+    tc(&["qdisc", "add", "dev", interface, "root", "handle", &handle, "htb"])?;
+    tc(&["filter", "add", "dev", interface, "parent", &handle, "protocol", "all", "prio", "10", "handle", "1:", "cgroup"])?;
+    Ok(())
+}
+
+/// Delete the qdisc (and its filter) once no limit on `interface` is left
+#[cfg(target_os = "linux")]
+fn drop_unused_qdisc(root: &std::path::Path, interface: &str) {
+    let in_use = std::fs::read_dir(root.join(CGROUP_DIR)).is_ok_and(|entries| {
+        entries.flatten().any(|entry| {
+            entry.file_name().to_string_lossy().rsplit_once('.').is_some_and(|(name, _)| name == interface)
+        })
+    });
+    if !in_use {
+        let _ = tc(&["qdisc", "del", "dev", interface, "root", "handle", &format!("{:x}:", HTB_MAJOR)]);
+    }
+}
+
+/// Cap what `pid` sends on `interface` at `rate` bytes per second; changes the
+/// rate in place when `current` is the process's existing limit there
+#[cfg(target_os = "linux")]
+pub fn apply(pid: i32, interface: &str, rate: u64, current: Option<&BandwidthLimit>) -> Result<BandwidthLimit, String> {
+    let root = net_cls_root()?;
+    ensure_qdisc(interface)?;
+    let minor = match current {
+        Some(limit) if limit.interface == interface => limit.minor,
+        _ => {
+            let used: Vec<u16> = active_limits().values().map(|limit| limit.minor).collect();
+            (1..=u16::MAX).find(|minor| !used.contains(minor)).ok_or("no free tc class left")?
+        }
+    };
+    let limit = BandwidthLimit { interface: interface.to_string(), minor, rate };
+    // A new limit, rather than a rate change in place, is undone again if it can't be completed
+    let is_new = current.is_none_or(|previous| previous.interface != interface);
+    let rate_arg = format!("{}bps", rate.max(1));
+    let classed = tc(&["class", "replace", "dev", interface, "parent", &format!("{:x}:", HTB_MAJOR), "classid", &class_id(minor),
+        "htb", "rate", &rate_arg, "ceil", &rate_arg]);
+    if let Err(e) = classed {
+        if is_new {
+            release(&limit);
+        }
+        return Err(e);
+    }
+
+    let cgroup = cgroup_path(&root, &limit);
+    std::fs::create_dir_all(&cgroup).map_err(|e| format!("can't create {}: {}", cgroup.display(), e))?;
+    let classid = (u32::from(HTB_MAJOR) << 16) | u32::from(minor);
+    let moved = std::fs::write(cgroup.join("net_cls.classid"), classid.to_string())
+        .and_then(|_| std::fs::write(cgroup.join("cgroup.procs"), pid.to_string()));
+    if let Err(e) = moved {
+        if is_new {
+            release(&limit);
+        }
+        return Err(format!("can't move PID {} into {}: {}", pid, cgroup.display(), e));
+    }
+    if let Some(previous) = current.filter(|previous| previous.interface != interface) {
+        release(previous);
+    }
+    Ok(limit)
+}
+
+/// Lift the limit: the process (and any children it started since) goes back to the root cgroup
+#[cfg(target_os = "linux")]
+pub fn remove(limit: &BandwidthLimit) -> Result<(), String> {
+    let root = net_cls_root()?;
+    let cgroup = cgroup_path(&root, limit);
+    let procs = std::fs::read_to_string(cgroup.join("cgroup.procs")).unwrap_or_default();
+    for pid in procs.lines() {
+        // Processes that exited meanwhile can't be moved and don't need to be
+        let _ = std::fs::write(root.join("cgroup.procs"), pid);
+    }
+    release(limit);
+    if cgroup.exists() {
+        return Err(format!("{} still holds processes", cgroup.display()));
+    }
+    Ok(())
+}
+
+/// Delete the cgroup and class of a limit once no process is left in it, and
+/// the qdisc once no limit on the interface is left
+#[cfg(target_os = "linux")]
+pub fn release(limit: &BandwidthLimit) {
+    let Ok(root) = net_cls_root() else { return };
+    if std::fs::remove_dir(cgroup_path(&root, limit)).is_ok() {
+        let _ = tc(&["class", "del", "dev", &limit.interface, "classid", &class_id(limit.minor)]);
+    }
+    drop_unused_qdisc(&root, &limit.interface);
+}
+
+/// Limits left by earlier runs, by PID; empty ones are cleaned up on the way
+#[cfg(target_os = "linux")]
+pub fn active_limits() -> std::collections::HashMap<i32, BandwidthLimit> {
+    let mut limits = std::collections::HashMap::new();
+    let Ok(root) = net_cls_root() else { return limits };
+    let Ok(entries) = std::fs::read_dir(root.join(CGROUP_DIR)) else { return limits };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some((interface, minor)) = name.rsplit_once('.') else { continue };
+        let Ok(minor) = minor.parse::<u16>() else { continue };
+        let rate = tc(&["class", "show", "dev", interface, "classid", &class_id(minor)]).ok()
+            .and_then(|output| {
+                let mut words = output.split_whitespace();
+                words.find(|word| *word == "rate")?;
+                parse_tc_rate(words.next()?)
+            })
+            .unwrap_or(0);
+        let limit = BandwidthLimit { interface: interface.to_string(), minor, rate };
+        let procs = std::fs::read_to_string(entry.path().join("cgroup.procs")).unwrap_or_default();
+        let pids: Vec<i32> = procs.lines().filter_map(|pid| pid.trim().parse().ok()).collect();
+        if pids.is_empty() {
+            release(&limit);
+        }
+        for pid in pids {
+            limits.insert(pid, limit.clone());
+        }
+    }
+    limits
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn default_interface() -> Option<String> {
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn apply(_pid: i32, _interface: &str, _rate: u64, _current: Option<&BandwidthLimit>) -> Result<BandwidthLimit, String> {
+    Err("bandwidth limits are only available on Linux".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn remove(_limit: &BandwidthLimit) -> Result<(), String> {
+    Err("bandwidth limits are only available on Linux".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn release(_limit: &BandwidthLimit) {}
+
+#[cfg(not(target_os = "linux"))]
+pub fn active_limits() -> std::collections::HashMap<i32, BandwidthLimit> {
+    std::collections::HashMap::new()
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tc_rates() {
+        assert_eq!(parse_tc_rate("800Kbit"), Some(100_000));
+        assert_eq!(parse_tc_rate("1200bit"), Some(150));
+        assert_eq!(parse_tc_rate("1.5Mbit"), Some(187_500));
+        assert_eq!(parse_tc_rate("fast"), None);
+    }
+
+    #[test]
+    fn finds_default_route() {
+        let routes = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask
+docker0\t000011AC\t00000000\t0001\t0\t0\t0\t0000FFFF
+wlan0\t00000000\t0101A8C0\t0003\t0\t0\t600\t00000000
+";
+        assert_eq!(parse_default_route(routes), Some("wlan0".to_string()));
+        assert_eq!(parse_default_route("Iface\tDestination\n"), None);
+    }
+}
@@ -5,34 +5,80 @@ mod capture;
 mod ui;
 mod interactive;
 mod dependencies;
+mod stream;
+mod demo;
+mod interfaces;
+mod tcp_info;
+mod pcap_recorder;
+mod socket_states;
+mod tcp_quality;
+mod services;
+mod filter;
+mod run;
+mod aggregate;
+mod metrics;
+mod audit;
+mod protect;
+mod privileges;
+mod tunnel;
+mod schedule;
+mod summary;
+mod report;
+mod packet_history;
+mod delta;
+mod units;
+mod anomaly;
+mod cost;
+mod check;
+mod diff;
+mod json_output;
+mod limit;
+mod block;
+mod alerts;
+mod action_queue;
+mod events;
+mod notifications;
+mod containers;
+mod restarts;
+mod quic;
+mod classify;
+mod traffic_stats;
+mod history_store;
+mod doctor;
+mod accounting;
+mod parser;
+#[cfg(test)]
+mod replay;
+#[cfg(feature = "web")]
+mod web;
 
 use clap::Parser;
 
 use std::process::{exit, Command};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{Instant, Duration};
 use tokio::sync::mpsc;
-use crossterm::event::{self, Event};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use std::io;
 use std::thread;
-#[cfg(target_os = "linux")]
-use nix::sys::signal::{self, Signal};
-#[cfg(target_os = "linux")]
-use nix::unistd::Pid;
-#[cfg(target_os = "linux")]
-use nix::errno::Errno;
+use std::sync::atomic::Ordering;
 
 use config::{Cli, reset_config, load_config};
-use types::{App, ProcessInfo, Connection, AlertAction, PROCESS_CLEANUP_INTERVAL_SECS};
-use process::{refresh_proc_maps, cleanup_dead_processes};
-use capture::connection_from_packet;
-use ui::utils::format_bytes;
+use types::{Alert, App, ProcessInfo, AlertAction};
+use process::find_exited_processes;
+use units::format_rate;
 use interactive::{run_interactive_mode, validate_interface_exists};
 
-fn display_startup_info(iface: &str, is_json: bool, containers_enabled: bool) {
+fn display_startup_info(iface: &str, json_capture: Option<Duration>, containers_enabled: bool, check: bool) {
+    let is_json = json_capture.is_some();
     eprintln!("🚀 Starting monitetoring...");
     eprintln!("📡 Interface: {}", iface);
-    eprintln!("📊 Mode: {}", if is_json { "JSON output" } else { "Interactive TUI" });
+    let mode = match (is_json, check) {
+        (true, true) => "Threshold check",
+        (true, false) => "JSON output",
+        _ => "Interactive TUI",
+    };
+    eprintln!("📊 Mode: {}", mode);
     eprintln!("🐳 Container awareness: {}", if containers_enabled { "Enabled" } else { "Disabled" });
     if !is_json {
         eprintln!("⏱️  Preparing to capture network traffic... (Press 'q' to quit)");
@@ -42,8 +88,37 @@ fn display_startup_info(iface: &str, is_json: bool, containers_enabled: bool) {
         eprintln!("📊 Sorting: Higher bandwidth usage appears at the top");
         eprintln!();
     } else {
-        eprintln!("⏱️  Capturing for 5 seconds...");
+        eprintln!("⏱️  Capturing for {}...", ui::utils::format_countdown(json_capture.unwrap_or_default()));
+        eprintln!();
+    }
+}
+
+/// Print and write the end-of-session reports asked for on the command line.
+/// `alerts` is None where alerts aren't evaluated (JSON mode).
+fn finish_session(cli: &Cli, summary: &summary::SessionSummary, session: &report::SessionInfo, alerts: Option<&[summary::AlertEntry]>) {
+    let elapsed = (chrono::Local::now() - session.started).to_std().unwrap_or_default();
+    let heading = [
+        "Monitetoring session summary".to_string(),
+        format!("Interface:  {}", session.interface),
+        format!("Started:    {}", session.started.format("%Y-%m-%d %H:%M:%S")),
+        format!("Duration:   {}", ui::utils::format_countdown(elapsed)),
+    ];
+    let text = summary.report(&heading, alerts);
+    if cli.summary {
         eprintln!();
+        eprint!("{}", text);
+    }
+    if let Some(path) = &cli.summary_file {
+        match std::fs::write(path, &text) {
+            Ok(()) => eprintln!("📝 Summary written to {}", path.display()),
+            Err(e) => eprintln!("❌ Failed to write the summary to {}: {}", path.display(), e),
+        }
+    }
+    if let Some(path) = &cli.report {
+        match report::write(path, summary, session, alerts.unwrap_or_default()) {
+            Ok(()) => eprintln!("📝 Report written to {}", path.display()),
+            Err(e) => eprintln!("❌ Failed to write the report to {}: {}", path.display(), e),
+        }
     }
 }
 
@@ -71,86 +146,55 @@ fn show_interface_help() {
     eprintln!("📖 Use --help for more options");
 }
 
-fn execute_alert_action(action: &AlertAction, pid: i32, name: &str, current_sent: u64, current_received: u64, threshold: u64) -> (bool, Option<String>, Option<String>) {
-    match action {
+/// Carry out an alert's action. Kills and custom commands only get queued and
+/// report back through `queue`; for those the result is the execution log entry.
+fn execute_alert_action(alert: &Alert, pid: i32, stats: &ProcessInfo, anomaly: Option<anomaly::AnomalyScore>, pcap_tx: &std::sync::mpsc::Sender<pcap_recorder::PcapRequest>, queue: &mut action_queue::ActionQueue, fired: &str) -> (bool, Option<String>, Option<String>) {
+    let name = stats.name.as_str();
+    let ticket = || action_queue::Ticket {
+        pid,
+        name: name.to_string(),
+        alert: alert.clone(),
+        bytes: stats.sent + stats.received,
+        fired: fired.to_string(),
+    };
+    match &alert.action {
+        AlertAction::CapturePcap(secs) => {
+            // The capture thread owns the pcap handle, so it does the recording
+            let request = pcap_recorder::PcapRequest {
+                pid,
+                name: name.to_string(),
+                duration: Duration::from_secs(*secs),
+            };
+            match pcap_tx.send(request) {
+                Ok(()) => (false, Some(format!("📼 Recording {} (PID {}) to a pcap file for {}s", name, pid, secs)), None),
+                Err(_) => (false, Some(format!("❌ Cannot record {} (PID {}): no live capture running", name, pid)), None),
+            }
+        }
         AlertAction::SystemAlert => {
             // Just return a notification message, no process killing
-            (false, Some(format!("🚨 System Alert for {} (PID {}):\nExceeded bandwidth threshold", name, pid)), None)
+            let reason = if alert.on_anomaly {
+                "its bandwidth baseline"
+            } else if alert.max_new_connections.is_some() {
+                "connection rate threshold"
+            } else {
+                "bandwidth threshold"
+            };
+            (false, Some(format!("🚨 System Alert for {} (PID {}):\nExceeded {}", name, pid, reason)), None)
         }
+        // Blocking needs the whole app, so the alert loop does it before getting here
+        AlertAction::Block => (false, None, None),
         AlertAction::Kill => {
-            #[cfg(target_os = "linux")]
-            {
-                // First, send the kill signal
-                match signal::kill(Pid::from_raw(pid), Some(Signal::SIGKILL)) {
-                    Ok(_) => {
-                        // Signal sent, now verify process termination
-                    }
-                    Err(Errno::ESRCH) => {
-                        // Process already doesn't exist, which is a success in this context
-                        return (true, Some(format!("💀 Process {} (PID {}) was already gone", name, pid)), None);
-                    }
-                    Err(e) => {
-                        // Another error, like permissions
-                        return (false, Some(format!("❌ Failed to send kill signal to {} (PID {}): {}", name, pid, e)), None);
-                    }
-                }
-
-                // Poll for up to 2 seconds to see if the process terminates
-                let start = Instant::now();
-                while start.elapsed() < Duration::from_secs(2) {
-                    // Check if process exists by sending signal 0
-                    match signal::kill(Pid::from_raw(pid), None) {
-                        Ok(_) => {
-                            // Process still exists, wait a bit
-                            thread::sleep(Duration::from_millis(100));
-                        }
-                        Err(Errno::ESRCH) => {
-                            // Process does not exist, success!
-                            return (true, Some(format!("💀 Killed {} (PID {}) due to bandwidth limit", name, pid)), None);
-                        }
-                        Err(_) => {
-                            // Some other error, assume failure to check
-                            break;
-                        }
-                    }
-                }
-
-                // If the loop finishes and we haven't returned, the process is still alive
-                (false, Some(format!("❌ Failed to kill {} (PID {}): Process still running", name, pid)), None)
-            }
-            
-            #[cfg(target_os = "windows")]
-            {
-                // Use taskkill command on Windows
-                let output = Command::new("taskkill")
-                    .args(["/PID", &pid.to_string(), "/F"])
-                    .stdout(std::process::Stdio::null()) // Suppress stdout
-                    .stderr(std::process::Stdio::null()) // Suppress stderr
-                    .output();
-                
-                match output {
-                    Ok(result) if result.status.success() => {
-                        (true, Some(format!("💀 Killed {} (PID {}) due to bandwidth limit", name, pid)), None)
-                    }
-                    Ok(result) => {
-                        let _stderr_msg = String::from_utf8_lossy(&result.stderr);
-                        (false, Some(format!("❌ Failed to kill {} (PID {}): taskkill command failed", name, pid)), None)
-                    }
-                    Err(e) => {
-                        (false, Some(format!("❌ Failed to execute taskkill for {} (PID {}): {}", name, pid, e)), None)
-                    }
-                }
-            }
+            queue.kill(ticket());
+            (false, None, None)
         }
         AlertAction::CustomCommand(cmd) => {
-            let start_time = Instant::now();
-            let total_usage = current_sent + current_received;
+            let total_usage = stats.sent + stats.received;
+            let threshold = alert.threshold_bytes;
             
             // Create the execution log entry that shows immediately
             let execution_log_entry = format!(
-                "🔧 Executing custom command for {} (PID {}): {} | Usage: {} ({}% over threshold)",
-                name, pid, cmd, format_bytes(total_usage),
-                ((total_usage as f64 / threshold as f64 - 1.0) * 100.0) as u32
+                "🔧 Executing custom command for {} (PID {}): {} | Usage: {}",
+                name, pid, cmd, alert.usage_text(stats, anomaly)
             );
             
             #[cfg(target_os = "linux")]
@@ -170,69 +214,23 @@ fn execute_alert_action(action: &AlertAction, pid: i32, name: &str, current_sent
             command.env("MONITETORING_PID", pid.to_string())
                 .env("MONITETORING_PROCESS_NAME", name)
                 .env("MONITETORING_BANDWIDTH_EXCEEDED", "true")
-                .env("MONITETORING_SENT_BYTES", current_sent.to_string())
-                .env("MONITETORING_RECEIVED_BYTES", current_received.to_string())
+                .env("MONITETORING_SENT_BYTES", stats.sent.to_string())
+                .env("MONITETORING_RECEIVED_BYTES", stats.received.to_string())
                 .env("MONITETORING_TOTAL_BYTES", total_usage.to_string())
                 .env("MONITETORING_THRESHOLD_BYTES", threshold.to_string())
                 .env("MONITETORING_EXCESS_BYTES", (total_usage.saturating_sub(threshold)).to_string())
+                .env("MONITETORING_NEW_CONNECTIONS_PER_SEC", stats.new_connection_rate.to_string())
                 .env("MONITETORING_TIMESTAMP", chrono::Utc::now().to_rfc3339());
-            
-            // Use spawn() with timeout instead of status() for better control
-            match command.spawn() {
-                Ok(mut child) => {
-                    // Wait for the process with a timeout
-                    let timeout_duration = Duration::from_secs(30); // 30 second timeout
-                    let poll_start = Instant::now();
-                    
-                    loop {
-                        match child.try_wait() {
-                            Ok(Some(status)) => {
-                                let execution_time = start_time.elapsed();
-                                if status.success() {
-                                    return (false, Some(format!(
-                                        "✅ Custom command executed successfully for {} (PID {}) in {:.2}s:\nUsage: {} ({}% over threshold)", 
-                                        name, pid, execution_time.as_secs_f64(),
-                                        format_bytes(total_usage),
-                                        ((total_usage as f64 / threshold as f64 - 1.0) * 100.0) as u32
-                                    )), Some(execution_log_entry));
-                                } else {
-                                    return (false, Some(format!(
-                                        "❌ Custom command failed (exit code: {}) for {} (PID {}) after {:.2}s:\nUsage: {}", 
-                                        status.code().unwrap_or(-1), name, pid, 
-                                        execution_time.as_secs_f64(), format_bytes(total_usage)
-                                    )), Some(execution_log_entry));
-                                }
-                            }
-                            Ok(None) => {
-                                // Process is still running
-                                if poll_start.elapsed() > timeout_duration {
-                                    // Timeout reached, kill the child process
-                                    let _ = child.kill();
-                                    let _ = child.wait(); // Clean up zombie
-                                    return (false, Some(format!(
-                                        "⏰ Custom command timed out after {}s for {} (PID {}):\nUsage: {}", 
-                                        timeout_duration.as_secs(), name, pid, format_bytes(total_usage)
-                                    )), Some(execution_log_entry));
-                                }
-                                // Sleep briefly before checking again
-                                thread::sleep(Duration::from_millis(100));
-                            }
-                            Err(e) => {
-                                return (false, Some(format!(
-                                    "❌ Error waiting for custom command for {} (PID {}):\n{} | Usage: {}", 
-                                    name, pid, e, format_bytes(total_usage)
-                                )), Some(execution_log_entry));
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    (false, Some(format!(
-                        "❌ Failed to spawn custom command for {} (PID {}):\n{} | Usage: {}", 
-                        name, pid, e, format_bytes(total_usage)
-                    )), Some(execution_log_entry))
-                }
+            if let Some(limit) = alert.max_new_connections {
+                command.env("MONITETORING_CONNECTION_RATE_THRESHOLD", limit.to_string());
+            }
+            if let Some(score) = anomaly {
+                command.env("MONITETORING_BASELINE_BYTES_PER_SEC", (score.baseline as u64).to_string())
+                    .env("MONITETORING_ANOMALY_SIGMAS", format!("{:.1}", score.sigmas));
             }
+            
+            queue.run_command(ticket(), command, alert.usage_text(stats, anomaly));
+            (false, None, Some(execution_log_entry))
         }
     }
 }
@@ -277,6 +275,8 @@ fn check_privileges_and_provide_guidance() {
         eprintln!("      sudo ~/.cargo/bin/monitetoring --iface any");
         eprintln!();
         eprintln!("   3. Set capabilities (alternative to sudo):");
+        eprintln!("      monitetoring --setup-capabilities");
+        eprintln!("      or by hand:");
         if let Ok(current_exe) = env::current_exe() {
             eprintln!("      sudo setcap cap_net_raw,cap_net_admin=eip {}", current_exe.display());
         } else {
@@ -322,14 +322,8 @@ fn offer_automatic_setup() -> bool {
             } else {
                 // Create a minimal config just to track that we offered setup
                 let minimal_config = config::SavedConfig {
-                    interface: "any".to_string(),
-                    json_mode: false,
-                    containers_mode: false,
-                    show_total_columns: false,
-                    alerts: vec![],
-                    large_packet_threshold: 100_000,
-                    frequent_connection_threshold: 20,
                     setup_offered: true,
+                    ..Default::default()
                 };
                 let _ = config::save_config(&minimal_config);
             }
@@ -377,74 +371,117 @@ fn offer_automatic_setup() -> bool {
 
 #[tokio::main]
 async fn main() -> Result<(), io::Error> {
+    // Parsing arguments doesn't touch pcap, and --demo/--aggregate need to skip the checks below
+    let cli = Cli::parse();
+    if cli.setup_capabilities {
+        match privileges::setup_capabilities() {
+            Ok(exe) => {
+                println!("✅ {} can now capture packets without sudo", exe.display());
+                println!("   Rebuilding or reinstalling the binary removes the capabilities; run this again afterwards.");
+                println!("   Without root, traffic of other users' processes can't be attributed to them or killed.");
+            }
+            Err(e) => {
+                eprintln!("❌ Can't set up capabilities: {}", e);
+                exit(1);
+            }
+        }
+        return Ok(());
+    }
+    // Every mode formats numbers, the diff report included
+    let mut unit_style = load_config().map(|saved| saved.units).unwrap_or_default();
+    if let Some(bytes) = cli.units {
+        unit_style.bytes = bytes;
+    }
+    if cli.bit_rates {
+        unit_style.bit_rates = true;
+    }
+    units::set_style(unit_style);
+    // Comparing snapshots needs neither capture nor privileges
+    if let Some(config::Command::Diff { before, after, top }) = &cli.command {
+        match diff::diff_files(before, after, *top) {
+            Ok(report) => print!("{}", report),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                exit(1);
+            }
+        }
+        return Ok(());
+    }
+    if let Some(config::Command::Doctor { listen }) = &cli.command {
+        let saved = load_config();
+        let settings = capture::CaptureSettings {
+            snaplen: cli.snaplen.or(saved.as_ref().map(|c| c.snaplen.clamp(types::MIN_SNAPLEN, types::MAX_SNAPLEN))).unwrap_or(types::DEFAULT_SNAPLEN),
+            immediate_mode: cli.immediate || saved.as_ref().is_some_and(|c| c.immediate_mode),
+            buffer_mb: cli.buffer_size.or(saved.as_ref().and_then(|c| c.capture_buffer_mb)),
+        };
+        let iface = cli.iface.clone().or(saved.map(|c| c.interface));
+        exit(doctor::run(iface, std::time::Duration::from_secs(*listen), settings));
+    }
+    // Demo and aggregated processes don't come from local packet capture
+    let no_capture = cli.demo || !cli.aggregate.is_empty();
+
     // CRITICAL: Check dependencies FIRST, before any pcap functions are called
     // This prevents crashes when wpcap.dll/npcap is missing on Windows
     #[cfg(target_os = "windows")]
-    {
-        let missing_deps = dependencies::DependencyChecker::check_dependencies();
-        if !missing_deps.is_empty() {
-            println!();
-            dependencies::DependencyChecker::display_installation_guides(&missing_deps);
-            match dependencies::DependencyChecker::prompt_installation_action() {
-                Ok(true) => {
-                    // User chose to see guides and exit
-                    println!("👋 Run monitetoring again after installing the required dependencies!");
-                    exit(0);
-                }
-                Ok(false) => {
-                    // User chose to skip and continue anyway
-                    eprintln!("⚠️  Continuing without all dependencies - packet capture may not work properly.");
-                    eprintln!("   Some features may not work as expected.");
-                }
-                Err(e) => {
-                    eprintln!("Error handling user input: {}", e);
-                    exit(1);
-                }
+    let mut without_npcap = false;
+    #[cfg(target_os = "windows")]
+    if !no_capture && !cli.estats && !dependencies::DependencyChecker::check_dependencies().is_empty() {
+        // Walks through installing Npcap and checks again, so no restart is needed
+        match dependencies::DependencyChecker::npcap_wizard() {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!("ℹ️  Continuing without Npcap: falling back to estimated TCP counters (same as --estats).");
+                eprintln!();
+                without_npcap = true;
+            }
+            Err(e) => {
+                eprintln!("Error handling user input: {}", e);
+                exit(1);
             }
         }
     }
 
-    let cli = Cli::parse();
-    
     // Early setup offer for cargo-installed binaries (before privilege checks)
     #[cfg(target_os = "linux")]
     {
-        // Only offer setup if not running as root and not using --reset
+        // Only offer setup if not running as root and not using --reset, --demo or --aggregate
         let euid = unsafe { libc::geteuid() };
-        if euid != 0 && !cli.reset {
+        if euid != 0 && !cli.reset && !no_capture && !privileges::has_capture_capabilities() {
             offer_automatic_setup();
         }
     }
 
     let mut startup_warning: Option<String> = None;
 
-    // Check for root privileges on Linux
+    // Check for root privileges on Linux (demo mode generates its own traffic, aggregation reads other hosts')
     #[cfg(target_os = "linux")]
     {
-        if unsafe { libc::geteuid() } != 0 {
-            check_privileges_and_provide_guidance();
-            std::process::exit(1);
+        if unsafe { libc::geteuid() } != 0 && !no_capture {
+            if !privileges::has_capture_capabilities() {
+                check_privileges_and_provide_guidance();
+                std::process::exit(1);
+            }
+            // /proc only shows the sockets of other users' processes to root
+            startup_warning = Some("🔑 Running with capabilities instead of root: other users' processes can't be attributed or killed".to_string());
         }
     }
 
     // Check for administrator privileges on Windows
     #[cfg(target_os = "windows")]
-    {
-        use std::process::Command;
-        let output = Command::new("net").args(["session"]).output();
-        match output {
-            Ok(result) if result.status.success() => {
-                // Administrator – nothing to do
-            }
-            _ => {
-                // Not admin – store warning to propagate to UI later
-                startup_warning = Some("⚠️ Not running as Administrator. Some features may not work".to_string());
-            }
-        }
+    if !process::control::is_elevated() {
+        // Not admin – store warning to propagate to UI later
+        startup_warning = Some("⚠️ Not running as Administrator. Some features may not work".to_string());
     }
 
+    // Without Npcap, Windows can still estimate TCP traffic from connection statistics
+    #[cfg(target_os = "windows")]
+    let mut estats_mode = (cli.estats || without_npcap) && !no_capture;
+    #[cfg(not(target_os = "windows"))]
+    let estats_mode = false;
+
     // Check packet capture availability early (Windows needs Npcap)
-    if let Err(error_message) = process::check_packet_capture_available() {
+    if let Err(error_message) = process::check_packet_capture_available()
+        && !no_capture && !estats_mode {
         // Use the new dependency system for better user experience
         let missing_deps = dependencies::DependencyChecker::check_dependencies();
         if !missing_deps.is_empty() {
@@ -461,6 +498,12 @@ async fn main() -> Result<(), io::Error> {
                     eprintln!("⚠️  Continuing without all dependencies - packet capture may not work properly.");
                     eprintln!("Original error: {}", error_message);
                     eprintln!();
+                    #[cfg(target_os = "windows")]
+                    {
+                        eprintln!("ℹ️  Falling back to estimated TCP counters (same as --estats).");
+                        eprintln!();
+                        estats_mode = true;
+                    }
                 }
                 Err(e) => {
                     eprintln!("Error handling user input: {}", e);
@@ -492,9 +535,76 @@ async fn main() -> Result<(), io::Error> {
         return Ok(());
     }
 
+    let mut process_filter = match filter::ProcessFilter::from_cli(&cli) {
+        Ok(filter) => filter,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            exit(1);
+        }
+    };
+
+    let (run_command, run_as_root) = match &cli.command {
+        Some(config::Command::Run { command, as_root }) => (Some(command.clone()), *as_root),
+        _ => (None, false),
+    };
+    if run_command.is_some() && no_capture {
+        eprintln!("❌ run needs local traffic and can't be combined with --demo or --aggregate");
+        exit(1);
+    }
+    let aggregate_hosts = match aggregate::parse_hosts(&cli.aggregate) {
+        Ok(hosts) => hosts,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            exit(1);
+        }
+    };
+    let aggregate_mode = !aggregate_hosts.is_empty();
+    let check_thresholds = check::Thresholds { per_process: cli.max_process, total: cli.max_total };
+    if cli.check && check_thresholds.is_empty() {
+        eprintln!("❌ --check needs a threshold: --max-process, --max-total or both");
+        exit(1);
+    }
+    if run_command.is_some() && !cli.metrics.is_empty() {
+        eprintln!("❌ run prints its own summary and can't be combined with --metrics");
+        exit(1);
+    }
+    let metrics_sinks = match cli.metrics.iter().map(|spec| metrics::parse_sink(spec)).collect::<Result<Vec<_>, _>>() {
+        Ok(sinks) => sinks,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            exit(1);
+        }
+    };
+
     let was_from_cli = cli.iface.is_some();
     // Check if no arguments were provided - run interactive mode
-    let (mut iface, mut json_mode, mut containers_mode, mut show_total_columns) = if cli.iface.is_none() && !cli.json && !cli.containers {
+    // An agent is the headless collector streaming to an --aggregate instance
+    let stream_target = cli.stream.as_deref().map(stream::StreamTarget::parse)
+        .or_else(|| cli.agent.clone().map(stream::StreamTarget::Tcp));
+    #[cfg(feature = "web")]
+    let web_addr = cli.web.clone();
+    #[cfg(not(feature = "web"))]
+    let web_addr: Option<String> = None;
+    // Streaming and the web dashboard run headless, without the TUI
+    let headless = stream_target.is_some() || web_addr.is_some();
+    if cli.check && (headless || run_command.is_some() || aggregate_mode) {
+        eprintln!("❌ --check runs its own bounded capture and can't be combined with run, --aggregate, --stream, --agent or --web");
+        exit(1);
+    }
+    let demo_mode = cli.demo;
+    let (mut iface, mut json_mode, mut containers_mode, mut show_total_columns) = if demo_mode {
+        // No capture in demo mode, so there is no interface to choose
+        ("demo".to_string(), cli.json || cli.check, cli.containers, false)
+    } else if aggregate_mode {
+        // Each agent captures on its own interface
+        ("remote".to_string(), false, false, false)
+    } else if estats_mode {
+        // Connection statistics are system-wide, not per interface
+        ("estats".to_string(), cli.json || cli.check, false, false)
+    } else if run_command.is_some() {
+        // The summary is printed when the command exits; there is nothing to set up
+        (cli.iface.clone().unwrap_or_else(|| "any".to_string()), false, cli.containers, false)
+    } else if cli.iface.is_none() && !cli.json && !cli.check && !cli.containers && !headless {
         // No arguments provided, run interactive mode
         match run_interactive_mode()? {
             Some(config) => (config.interface, config.json_mode, config.containers_mode, config.show_total_columns),
@@ -503,9 +613,12 @@ async fn main() -> Result<(), io::Error> {
                 return Ok(());
             }
         }
-    } else if let Some(iface) = cli.iface {
+    } else if let Some(iface) = cli.iface.clone() {
         // Arguments provided, use them (default show_total_columns to false)
-        (iface, cli.json, cli.containers, false)
+        (iface, cli.json || cli.check, cli.containers, false)
+    } else if headless || cli.check {
+        // Headless modes and checks can't prompt, so fall back to all interfaces
+        ("any".to_string(), cli.check, cli.containers, false)
     } else {
         // Some arguments provided but no interface - show help
         show_interface_help();
@@ -515,7 +628,7 @@ async fn main() -> Result<(), io::Error> {
     // --- Interface validation: if the chosen interface no longer exists ---
     // (e.g. USB Ethernet dongle unplugged, VPN disconnected since last run),
     // re-run interactive setup so the user can pick a valid one.
-    if !validate_interface_exists(&iface) {
+    if !demo_mode && !aggregate_mode && !estats_mode && !validate_interface_exists(&iface) {
         if was_from_cli {
             eprintln!("⚠️  Specified interface '{}' is not available.", iface);
         } else {
@@ -538,14 +651,145 @@ async fn main() -> Result<(), io::Error> {
     }
 
     // Apply Windows-specific override (disable container awareness)
-    let containers_mode_effective = if cfg!(windows) { false } else { containers_mode };
+    let containers_mode_effective = if cfg!(windows) { false } else { containers_mode || process_filter.needs_containers() };
+    let filter_label = process_filter.is_active().then(|| process_filter.describe());
+    if aggregate_mode {
+        eprintln!("📡 Collecting from agents: {}", aggregate_hosts.iter().map(|(_, address)| address.as_str()).collect::<Vec<_>>().join(", "));
+    }
+    if let Some(label) = &filter_label {
+        eprintln!("🔍 Only monitoring processes matching: {}", label);
+    }
+
+    // Bounded runs: wait for the scheduled window, then stop at whichever limit comes first
+    let mut capture_limit = cli.duration;
+    if let Some(window) = cli.schedule {
+        let (wait, open_for) = window.next(chrono::Local::now().time());
+        if !wait.is_zero() {
+            eprintln!("⏳ Waiting {} for the capture window {}", ui::utils::format_countdown(wait), window);
+            tokio::time::sleep(wait).await;
+        }
+        capture_limit = Some(capture_limit.map_or(open_for, |limit| limit.min(open_for)));
+    }
+    let deadline = capture_limit.map(|limit| Instant::now() + limit);
+    // JSON mode prints once, at the end of the run
+    let json_capture = json_mode.then(|| capture_limit.unwrap_or(Duration::from_secs(5)));
+    let want_summary = cli.summary || cli.summary_file.is_some() || cli.report.is_some();
+    let session_start = chrono::Local::now();
+    // Only the TUI evaluates alerts, so only it has anything to audit
+    let audit_log = if cli.syslog && !json_mode && !headless && run_command.is_none() {
+        match audit::AuditLog::open() {
+            Ok(log) => {
+                eprintln!("📝 Recording alerts and kills in {}", log.describe());
+                Some(log)
+            }
+            Err(e) => {
+                eprintln!("❌ Can't write to the system log: {}", e);
+                exit(1);
+            }
+        }
+    } else {
+        None
+    };
+    if cli.dry_run && !json_mode && !headless && run_command.is_none() {
+        eprintln!("🧪 Dry run: alerts will only log the kills and commands they would run");
+    }
+    if cli.json_connections && cli.json_schema != Some(json_output::JsonSchema::V2) {
+        eprintln!("⚠️  --json-connections only applies to --json-schema v2 output; ignoring it");
+    }
+    let mut protected_entries = load_config().map_or_else(config::default_protected, |saved| saved.protected);
+    protected_entries.extend(cli.protect.iter().cloned());
+    let protected = match protect::ProtectedList::parse(&protected_entries) {
+        Ok(list) => list,
+        Err(e) => {
+            eprintln!("❌ Invalid protected process entry: {}", e);
+            exit(1);
+        }
+    };
+    let mut metrics_exporter = if metrics_sinks.is_empty() {
+        None
+    } else {
+        let interval = Duration::from_secs(cli.metrics_interval.unwrap_or(10));
+        for sink in &metrics_sinks {
+            eprintln!("📈 Exporting metrics to {} every {}", sink.describe(), ui::utils::format_countdown(interval));
+        }
+        Some(metrics::MetricsExporter::start(metrics_sinks, interval))
+    };
 
     // Now proceed with the monitoring logic using the determined configuration
     let (tx, mut rx) = mpsc::channel(100);
+    // PIDs whose histories the UI evicted to stay within the memory budget
+    let (evict_tx, evict_rx) = std::sync::mpsc::channel::<Vec<i32>>();
+    // libpcap counters (received / dropped), sent once per second
+    let (capture_stats_tx, capture_stats_rx) = std::sync::mpsc::channel::<types::CaptureStats>();
+    // Cost of each socket-to-process map refresh, for the overview's debug stats
+    let (scan_stats_tx, scan_stats_rx) = std::sync::mpsc::channel::<types::ScanStats>();
+    // Process-to-process traffic that stays on the host, sent once per second
+    let (local_flows_tx, local_flows_rx) = std::sync::mpsc::channel::<Vec<types::LocalFlow>>();
+    // Traffic per listening port, sent once per second
+    let (services_tx, services_rx) = std::sync::mpsc::channel::<Vec<types::ServiceTraffic>>();
+    // Connection state of the --aggregate hosts, sent whenever it changes
+    let (remote_hosts_tx, remote_hosts_rx) = std::sync::mpsc::channel::<Vec<types::RemoteHost>>();
+    // Alert-triggered pcap recordings: requests to the capture thread, completion messages back
+    let (pcap_tx, pcap_rx) = std::sync::mpsc::channel::<pcap_recorder::PcapRequest>();
+    let (pcap_done_tx, pcap_done_rx) = std::sync::mpsc::channel::<String>();
+    // Capture lost and regained, and the interface the user wants it moved to
+    let (capture_events_tx, capture_events_rx) = std::sync::mpsc::channel::<types::CaptureEvent>();
+    let (capture_control_tx, capture_control_rx) = std::sync::mpsc::channel::<String>();
+
+    // Capture handle tuning: CLI flags override the saved config
+    let saved_capture = load_config();
+    let snaplen = cli.snaplen
+        .or(saved_capture.as_ref().map(|c| c.snaplen.clamp(types::MIN_SNAPLEN, types::MAX_SNAPLEN)))
+        .unwrap_or(types::DEFAULT_SNAPLEN);
+    let capture_settings = capture::CaptureSettings {
+        snaplen,
+        immediate_mode: cli.immediate || saved_capture.as_ref().is_some_and(|c| c.immediate_mode),
+        buffer_mb: cli.buffer_size.or(saved_capture.as_ref().and_then(|c| c.capture_buffer_mb)),
+    };
+    let packet_budget = packet_history::PacketBudget {
+        max_packets: cli.packet_budget.map(|n| n as usize)
+            .or(saved_capture.as_ref().map(|c| c.packet_budget))
+            .unwrap_or(types::DEFAULT_PACKET_BUDGET),
+        max_bytes: cli.packet_budget_mb.map(|mb| mb as usize)
+            .or(saved_capture.as_ref().map(|c| c.packet_budget_mb))
+            .unwrap_or(types::DEFAULT_PACKET_BUDGET_MB) * 1024 * 1024,
+    };
+
+    // `run` starts its command once capture is up and then only follows its process tree
+    let (mut capture_handshake, command_handshake) = run_command.as_ref().map(|_| run::handshake()).unzip();
 
     // Spawn packet capture thread
     let iface_clone = iface.clone();
-    thread::spawn(move || {
+    let drop_privileges = cli.drop_privileges && !no_capture && !estats_mode;
+    // Without the TUI's banner, losing the capture is reported on stderr
+    let announce_capture = headless || json_mode || run_command.is_some();
+    let mark_tunnels = !cli.count_tunnel_traffic;
+    let byte_counting = cli.count_bytes
+        .or(saved_capture.as_ref().map(|c| c.count_bytes))
+        .unwrap_or_default();
+    // Protocol and interface counters of the capture, for the Overview, the stream and the exporters
+    let traffic_shared = traffic_stats::SharedTraffic::default();
+    let capture_traffic = traffic_shared.clone();
+    let classification = saved_capture.as_ref().map(|c| c.classification.clone()).unwrap_or_default();
+    let capture_thread = thread::spawn(move || {
+        if demo_mode {
+            demo::run(tx, local_flows_tx, services_tx, &mut process_filter, containers_mode_effective, json_capture);
+            return;
+        }
+        if aggregate_mode {
+            aggregate::run(tx, remote_hosts_tx, aggregate_hosts, &mut process_filter);
+            return;
+        }
+        #[cfg(target_os = "windows")]
+        if estats_mode {
+            if let Some(handshake) = capture_handshake.take()
+                && !handshake.wait(&mut process_filter) {
+                    return;
+                }
+            process::estats::run(tx, &mut process_filter, json_capture);
+            return;
+        }
+
         let main_device = match dependencies::DependencyChecker::device_from_name_with_dependency_check(&iface_clone) {
             Ok(device) => device,
             Err(e) => {
@@ -577,24 +821,8 @@ async fn main() -> Result<(), io::Error> {
             }
         };
 
-        let cap = if iface_clone != "any" {
-            cap.promisc(true)
-        } else {
-            cap
-        };
-
-        let mut cap = match cap.timeout(100).open() {
-            Ok(c) => {
-                // Enable non-blocking mode so next_packet() returns quickly when no traffic
-                
-                match c.setnonblock() {
-                    Ok(nonblock_cap) => nonblock_cap,
-                    Err(e) => {
-                        eprintln!("❌ Failed to set non-blocking mode on capture: {}", e);
-                        exit(1);
-                    }
-                }
-            },
+        let mut cap = match capture::activate(cap, &iface_clone, capture_settings) {
+            Ok(cap) => cap,
             Err(e) => {
                 eprintln!("❌ Error opening packet capture: {}", e);
                 eprintln!();
@@ -615,198 +843,273 @@ async fn main() -> Result<(), io::Error> {
             }
         };
 
-        // NOTE [Linux -i any]: If the selected interface is the pseudo "any"
-        // interface, the kernel often delivers Linux cooked capture frames
-        // (SLL/SLL2). Proper parsing is datalink-dependent. For a future
-        // improvement, read the datalink via `cap.get_datalink()` here after
-        // activation and pass that information down so `connection_from_packet`
-        // can choose an exact parser instead of heuristic offsets.
+        // Past this point a capture handle can't be opened any more: with
+        // --drop-privileges, a lost capture isn't reconnected and the
+        // interface can't be switched
+        if drop_privileges {
+            match privileges::drop_privileges() {
+                Ok(dropped) => {
+                    if announce_capture {
+                        eprintln!("🔒 Dropped privileges: {}", dropped);
+                    }
+                    let _ = capture_events_tx.send(types::CaptureEvent::PrivilegesDropped(dropped));
+                }
+                Err(e) => {
+                    let error = format!("❌ Can't drop privileges: {}", e);
+                    if announce_capture {
+                        eprintln!("{}", error);
+                        exit(1);
+                    }
+                    // The TUI holds the terminal; it says so once it has restored it and lifted its blocks
+                    let _ = capture_events_tx.send(types::CaptureEvent::Failed(error));
+                    return;
+                }
+            }
+        }
+
+        if let Some(handshake) = capture_handshake.take()
+            && !handshake.wait(&mut process_filter) {
+                return;
+            }
+        let mut capture_iface = iface_clone;
+        let mut traffic = traffic_stats::TrafficCounter::new(&capture_iface, capture_traffic);
+        // How frames are laid out, e.g. Linux cooked capture on the "any" device
+        let mut link = parser::LinkType::from_datalink(cap.get_datalink().0);
 
-        let mut bandwidth_map: HashMap<i32, ProcessInfo> = HashMap::new();
-        let mut previous_bandwidth_map: HashMap<i32, ProcessInfo> = HashMap::new();
+        // Problems with the classification rules were reported when the UI started
+        let (classifier, _) = classify::Classifier::new(&classification);
+        let mut attribution = accounting::Attribution::new(process_filter, classifier, byte_counting, mark_tunnels);
+        // What the consumer has already seen, so each send only carries changes
+        let mut tracker = delta::DeltaTracker::default();
+        let mut previous_local_bytes: HashMap<(i32, i32), u64> = HashMap::new();
+        let mut rates = accounting::RateCalculator::default();
         let mut last_map_refresh = Instant::now();
         let mut last_send = Instant::now();
         let mut last_rate_calc = Instant::now();
-        let (mut inode_map, mut conn_map) = refresh_proc_maps(containers_mode_effective);
+        let mut last_budget_check = Instant::now();
+        let mut recorder = pcap_recorder::PcapRecorder::default();
+        let mut socket_owners = socket_states::SocketOwners::default();
+        let mut tcp_quality = tcp_quality::TcpQualityTracker::default();
+        let mut socket_scanner = process::NewSocketScanner::default();
+        let mut map_scanner = process::ProcMapScanner::default();
+        let mut inode_map = HashMap::new();
+        let mut conn_map = HashMap::new();
+        socket_scanner.start_refresh();
+        let _ = scan_stats_tx.send(map_scanner.refresh(&mut inode_map, &mut conn_map, containers_mode_effective));
+        attribution.services.refresh_listeners(&conn_map, &inode_map);
         
         let capture_start = Instant::now();
 
         loop {
             // In JSON mode, check timeout at the beginning of each loop iteration
-            if json_mode && capture_start.elapsed() > Duration::from_secs(5) {
-                let _ = tx.blocking_send(bandwidth_map.clone());
+            if let Some(limit) = json_capture
+                && capture_start.elapsed() > limit {
+                tracker.touch_all(&attribution.processes);
+                let _ = tx.blocking_send(tracker.build(&attribution.processes));
                 break;
             }
 
+            traffic.tick();
+
             // Refresh process maps every 2 seconds
             if last_map_refresh.elapsed() > Duration::from_secs(2) {
-                (inode_map, conn_map) = refresh_proc_maps(containers_mode_effective);
+                socket_scanner.start_refresh();
+                let _ = scan_stats_tx.send(map_scanner.refresh(&mut inode_map, &mut conn_map, containers_mode_effective));
+                attribution.services.refresh_listeners(&conn_map, &inode_map);
+                let sockets = process::read_tcp_sockets(&inode_map);
+                let states = socket_owners.count(&sockets);
+                let qualities = tcp_quality.update(&sockets);
+                for (pid, stats) in attribution.processes.iter_mut() {
+                    let current = states.get(pid).copied().unwrap_or_default();
+                    if stats.socket_states != current {
+                        stats.socket_states = current;
+                        tracker.touch(*pid);
+                    }
+                    let quality = qualities.get(pid).copied().unwrap_or_else(|| types::TcpQuality {
+                        retransmits: tcp_quality.retransmits(*pid),
+                        ..Default::default()
+                    });
+                    if stats.tcp_quality != quality {
+                        stats.tcp_quality = quality;
+                        tracker.touch(*pid);
+                    }
+                }
+                // Processes that exited, once the consumer has their last counts, are dropped
+                // here, as are local flows with an exited end; the consumer keeps its own copy,
+                // and a reused PID starts from zero
+                let flow_pids = attribution.local_flows.keys().flat_map(|&(from, to)| [from, to]);
+                let exited: HashSet<i32> = attribution.processes.keys().copied()
+                    .chain(flow_pids)
+                    .filter(|pid| !tracker.is_pending(*pid) && !process::is_process_alive(*pid))
+                    .collect();
+                for pid in exited {
+                    attribution.forget(pid);
+                    tracker.forget(pid);
+                    rates.forget(pid);
+                }
+                previous_local_bytes.retain(|key, _| attribution.local_flows.contains_key(key));
                 last_map_refresh = Instant::now();
             }
 
             // Try to get a packet (with timeout)
+            let mut lost = None;
             match cap.next_packet() {
                 Ok(packet) => {
-                    if let Some(conn) = connection_from_packet(packet.data) {
-                        // Check both directions of the connection
-                        let reverse_conn = Connection {
-                            source_port: conn.dest_port,
-                            dest_port: conn.source_port,
-                            source_ip: conn.dest_ip,
-                            dest_ip: conn.source_ip,
-                            protocol: conn.protocol,
-                        };
-                        
-                        let (matched_conn, found_inode) = if let Some(inode) = conn_map.get(&conn) {
-                            (conn, *inode)
-                        } else if let Some(inode) = conn_map.get(&reverse_conn) {
-                            (reverse_conn, *inode)
-                        } else {
-                            continue;
-                        };
-                        
-                        if let Some(proc_identifier) = inode_map.get(&found_inode) {
-                            // Skip if this process is known to be dead (avoids constant re-adding)
-                            // Note: We'll refresh this cache periodically in the main loop
-                            let pid = proc_identifier.pid;
-                            
-                            let stats = bandwidth_map.entry(pid).or_insert(ProcessInfo {
-                                name: proc_identifier.name.clone(),
-                                sent: 0,
-                                received: 0,
-                                sent_rate: 0,
-                                received_rate: 0,
-                                container_name: proc_identifier.container_name.clone(),
-                                user_name: proc_identifier.user_name.clone(),
-                                has_alert: false, // Default value
-                                sent_history: Vec::new(),
-                                received_history: Vec::new(),
-                                packet_history: std::collections::VecDeque::new(),
-                            });
-                            
-                            // Determine direction based on which connection matched
-                            if matched_conn == conn {
-                                // Original packet direction: process is sending data (outbound)
-                                stats.sent += packet.data.len() as u64;
-                                // Record individual packet information for history view
-                                {
-                                    use crate::types::{PacketInfo, PacketDirection, MAX_PACKET_HISTORY};
-                                    let ts_now = std::time::SystemTime::now();
-                                    let dt: chrono::DateTime<chrono::Local> = ts_now.into();
-                                    let cached_ts = dt.format("%H:%M:%S%.3f").to_string();
-                                    let cached_src = format!("{}:{}", conn.source_ip, conn.source_port);
-                                    let cached_dst = format!("{}:{}", conn.dest_ip, conn.dest_port);
-                                    let cached_proto = match conn.protocol {
-                                        6 => "TCP".to_string(),
-                                        17 => "UDP".to_string(),
-                                        1 => "ICMP".to_string(),
-                                        other => other.to_string(),
-                                    };
-                                    let cached_size = format_bytes(packet.data.len() as u64);
-
-                                    let pinfo = PacketInfo {
-                                        timestamp: ts_now,
-                                        direction: PacketDirection::Sent,
-                                        protocol: conn.protocol,
-                                        src_ip: conn.source_ip,
-                                        src_port: conn.source_port,
-                                        dst_ip: conn.dest_ip,
-                                        dst_port: conn.dest_port,
-                                        size: packet.data.len(),
-                                        cached_ts,
-                                        cached_src,
-                                        cached_dst,
-                                        cached_proto,
-                                        cached_size,
-                                    };
-                                    if stats.packet_history.len() >= MAX_PACKET_HISTORY {
-                                        stats.packet_history.pop_front();
-                                    }
-                                    stats.packet_history.push_back(pinfo);
-                                }
-                            } else {
-                                // Reverse connection matched: process is receiving data (inbound)  
-                                stats.received += packet.data.len() as u64;
-                                {
-                                    use crate::types::{PacketInfo, PacketDirection, MAX_PACKET_HISTORY};
-                                    let ts_now = std::time::SystemTime::now();
-                                    let dt: chrono::DateTime<chrono::Local> = ts_now.into();
-                                    let cached_ts = dt.format("%H:%M:%S%.3f").to_string();
-                                    let cached_src = format!("{}:{}", conn.dest_ip, conn.dest_port);
-                                    let cached_dst = format!("{}:{}", conn.source_ip, conn.source_port);
-                                    let cached_proto = match conn.protocol {
-                                        6 => "TCP".to_string(),
-                                        17 => "UDP".to_string(),
-                                        1 => "ICMP".to_string(),
-                                        other => other.to_string(),
-                                    };
-                                    let cached_size = format_bytes(packet.data.len() as u64);
-
-                                    let pinfo = PacketInfo {
-                                        timestamp: ts_now,
-                                        direction: PacketDirection::Received,
-                                        protocol: conn.protocol,
-                                        src_ip: conn.dest_ip,
-                                        src_port: conn.dest_port,
-                                        dst_ip: conn.source_ip,
-                                        dst_port: conn.source_port,
-                                        size: packet.data.len(),
-                                        cached_ts,
-                                        cached_src,
-                                        cached_dst,
-                                        cached_proto,
-                                        cached_size,
-                                    };
-                                    if stats.packet_history.len() >= MAX_PACKET_HISTORY {
-                                        stats.packet_history.pop_front();
-                                    }
-                                    stats.packet_history.push_back(pinfo);
-                                }
-                            }
+                    // `header.len` is the length on the wire; `data` is cut short by the snaplen
+                    let parsed = parser::parse(link, packet.data);
+                    let attributed = attribution.packet(
+                        parsed, packet.header.len as u64, &mut conn_map, &mut inode_map, &mut traffic,
+                        |conn, inode_map, conn_map| socket_scanner.resolve(conn, inode_map, conn_map, containers_mode_effective),
+                    );
+                    if let Some(pid) = attributed {
+                        tracker.touch(pid);
+                        if !recorder.is_empty() {
+                            recorder.record(pid, &packet);
                         }
                     }
                 }
-                Err(_) => {
-                    // Timeout or other error, continue
+                Err(pcap::Error::TimeoutExpired | pcap::Error::NoMorePackets) => {
                     // Small sleep to prevent busy waiting when no packets are available
                     std::thread::sleep(Duration::from_millis(1));
                 }
+                // Anything else means the interface went away (unplugged, VPN reconnect)
+                Err(e) => lost = Some(e.to_string()),
+            }
+
+            if let Some(error) = lost {
+                // Nothing is being measured, so don't leave the last rates on screen
+                for stats in attribution.processes.values_mut() {
+                    stats.sent_rate = 0;
+                    stats.received_rate = 0;
+                    stats.new_connection_rate = 0;
+                    stats.loopback_sent_rate = 0;
+                    stats.loopback_received_rate = 0;
+                }
+                tracker.touch_all(&attribution.processes);
+                if json_capture.is_some() {
+                    // A bounded capture reports what it got instead of waiting for the interface
+                    eprintln!("⚠️  Capture on {} lost: {}", capture_iface, error);
+                    let _ = tx.blocking_send(tracker.build(&attribution.processes));
+                    break;
+                }
+                if tx.try_send(tracker.build(&attribution.processes)).is_ok() {
+                    tracker.delivered(&attribution.processes);
+                }
+                if drop_privileges {
+                    if announce_capture {
+                        eprintln!("❌ Capture on {} lost: {}; {}", capture_iface, error, capture::REOPEN_AFTER_DROP);
+                    }
+                    let _ = capture_events_tx.send(types::CaptureEvent::Ended { iface: capture_iface.clone(), error });
+                    break;
+                }
+                let recovered = capture::recover(
+                    &capture_iface, error, capture_settings, &capture_events_tx,
+                    &capture_control_rx, announce_capture, || tx.is_closed(),
+                );
+                let Some((reopened, iface)) = recovered else {
+                    break;
+                };
+                cap = reopened;
+                link = parser::LinkType::from_datalink(cap.get_datalink().0);
+                capture_iface = iface;
+                traffic.set_interface(&capture_iface);
+                // Rates restart from here rather than averaging over the outage
+                rates.restart(&attribution.processes);
+                last_rate_calc = Instant::now();
+                continue;
             }
 
             // Calculate rates every second
             if last_rate_calc.elapsed() > Duration::from_secs(1) {
                 let rate_interval = last_rate_calc.elapsed().as_secs_f64();
-                
-                for (pid, current_stats) in bandwidth_map.iter_mut() {
-                    if let Some(prev_stats) = previous_bandwidth_map.get(pid) {
-                        let sent_diff = current_stats.sent.saturating_sub(prev_stats.sent);
-                        let received_diff = current_stats.received.saturating_sub(prev_stats.received);
-                        
-                        current_stats.sent_rate = (sent_diff as f64 / rate_interval) as u64;
-                        current_stats.received_rate = (received_diff as f64 / rate_interval) as u64;
-                    } else {
-                        // First measurement, rate is total divided by time since start
-                        let elapsed = capture_start.elapsed().as_secs_f64();
-                        if elapsed > 0.0 {
-                            current_stats.sent_rate = (current_stats.sent as f64 / elapsed) as u64;
-                            current_stats.received_rate = (current_stats.received as f64 / elapsed) as u64;
-                        }
-                    }
+                rates.update(&mut attribution.processes, rate_interval, capture_start.elapsed().as_secs_f64());
+
+                // Rates changed for every process, idle ones included
+                tracker.touch_all(&attribution.processes);
+
+                for (key, flow) in attribution.local_flows.iter_mut() {
+                    let previous = previous_local_bytes.insert(*key, flow.bytes).unwrap_or(0);
+                    flow.rate = (flow.bytes.saturating_sub(previous) as f64 / rate_interval) as u64;
                 }
-                
-                // Store current state for next rate calculation
-                previous_bandwidth_map = bandwidth_map.clone();
+                let _ = local_flows_tx.send(attribution.local_flows.values().cloned().collect());
+                let _ = services_tx.send(attribution.services.snapshot(rate_interval));
+
                 last_rate_calc = Instant::now();
+
+                // Report capture drops; the receiver only exists in TUI mode
+                if let Ok(stat) = cap.stats() {
+                    traffic.set_drops(stat.dropped as u64, stat.if_dropped as u64);
+                    let _ = capture_stats_tx.send(types::CaptureStats {
+                        received: stat.received as u64,
+                        dropped: stat.dropped as u64,
+                        if_dropped: stat.if_dropped as u64,
+                    });
+                }
+            }
+
+            // Drop packet histories the UI evicted to stay within the memory budget
+            while let Ok(pids) = evict_rx.try_recv() {
+                for pid in pids {
+                    if let Some(stats) = attribution.processes.get_mut(&pid) {
+                        stats.packet_history.clear();
+                        tracker.touch(pid);
+                    }
+                }
+            }
+
+            // The user moved the capture to another interface; counters and history carry over
+            while let Ok(target) = capture_control_rx.try_recv() {
+                if target == capture_iface {
+                    continue;
+                }
+                let reopened = if drop_privileges {
+                    Err(capture::REOPEN_AFTER_DROP.to_string())
+                } else {
+                    capture::reopen(&target, capture_settings)
+                };
+                match reopened {
+                    Ok(reopened) => {
+                        cap = reopened;
+                        link = parser::LinkType::from_datalink(cap.get_datalink().0);
+                        capture_iface = target.clone();
+                        traffic.set_interface(&capture_iface);
+                        let _ = capture_events_tx.send(types::CaptureEvent::Switched(target));
+                        rates.restart(&attribution.processes);
+                        last_rate_calc = Instant::now();
+                    }
+                    Err(error) => {
+                        let _ = capture_events_tx.send(types::CaptureEvent::SwitchFailed { iface: target, error });
+                    }
+                }
+            }
+
+            // Start and finish alert-triggered recordings
+            while let Ok(request) = pcap_rx.try_recv() {
+                if let Some(message) = recorder.start(&cap, request) {
+                    let _ = pcap_done_tx.send(message);
+                }
+            }
+            if !recorder.is_empty() {
+                for message in recorder.finish_expired() {
+                    let _ = pcap_done_tx.send(message);
+                }
+            }
+
+            // Keep the packet histories of all processes within the global budget
+            if last_budget_check.elapsed() > Duration::from_millis(100) {
+                packet_budget.enforce(&mut attribution.processes);
+                last_budget_check = Instant::now();
             }
 
             // Send data to the UI thread more frequently for a smoother experience
             if !json_mode && last_send.elapsed() > Duration::from_millis(100) {
-                match tx.try_send(bandwidth_map.clone()) {
+                match tx.try_send(tracker.build(&attribution.processes)) {
                     Ok(_) => {
-                last_send = Instant::now();
+                        tracker.delivered(&attribution.processes);
+                        last_send = Instant::now();
                     }
                     Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
-                        // Channel full – drop the update, UI will catch up and we send a fresh snapshot later
+                        // Channel full – drop the update; its changes stay pending and go out with the next one
                     }
                     Err(_) => {
                         // Receiver gone; exit capture loop
@@ -815,50 +1118,262 @@ async fn main() -> Result<(), io::Error> {
                 }
             }
         }
+
+        // The UI is shutting down: flush recordings still in progress
+        for message in recorder.finish_all() {
+            let _ = pcap_done_tx.send(message);
+        }
     });
 
-    if json_mode {
-        display_startup_info(&iface, true, containers_mode_effective);
+    if let (Some(command), Some(handshake)) = (run_command, command_handshake) {
+        drop(capture_stats_rx);
+        drop(scan_stats_rx);
+        drop(local_flows_rx);
+        drop(services_rx);
+        drop(remote_hosts_rx);
+        drop(pcap_done_rx);
+        drop(capture_events_rx);
+        drop(capture_control_tx);
+        eprintln!("🚀 Capturing on interface {}; starting {}", iface, command.join(" "));
+        let child = match run::spawn(handshake, &command, run_as_root) {
+            Ok(child) => child,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                exit(1);
+            }
+        };
+        exit(run::monitor(rx, child, &command).await);
+    } else if headless {
+        // Only the TUI reads these; without a receiver the capture thread's sends are no-ops
+        drop(capture_stats_rx);
+        drop(scan_stats_rx);
+        drop(local_flows_rx);
+        drop(services_rx);
+        drop(remote_hosts_rx);
+        drop(pcap_done_rx);
+        drop(capture_events_rx);
+        drop(capture_control_tx);
+        eprintln!("🚀 Capturing on interface {} (headless)", iface);
+        let (frames, _) = tokio::sync::broadcast::channel(stream::CLIENT_BUFFER_FRAMES);
+        let reader_gone = match stream_target {
+            Some(target) => stream::start(target, &frames).await?,
+            None => None,
+        };
+        #[cfg(feature = "web")]
+        if let Some(addr) = web_addr {
+            web::start(&addr, &frames).await?;
+        }
+        if let Some(mut exporter) = metrics_exporter.take() {
+            let mut frames_rx = frames.subscribe();
+            tokio::spawn(async move {
+                loop {
+                    match frames_rx.recv().await {
+                        Ok(frame) if exporter.due() => exporter.offer(frame),
+                        Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                    for message in exporter.messages() {
+                        eprintln!("{}", message);
+                    }
+                }
+            });
+        }
+        let local_pids = !demo_mode && !aggregate_mode;
+        match deadline {
+            Some(deadline) => {
+                let _ = tokio::time::timeout_at(deadline.into(), stream::run(rx, frames, traffic_shared, local_pids, reader_gone)).await;
+            }
+            None => stream::run(rx, frames, traffic_shared, local_pids, reader_gone).await,
+        }
+    } else if json_mode {
+        drop(capture_stats_rx);
+        drop(scan_stats_rx);
+        drop(local_flows_rx);
+        drop(services_rx);
+        drop(remote_hosts_rx);
+        drop(pcap_done_rx);
+        drop(capture_events_rx);
+        drop(capture_control_tx);
+        display_startup_info(&iface, json_capture, containers_mode_effective, cli.check);
         
         if let Some(final_stats) = rx.recv().await {
-            // Convert to an array of objects that include pid to match README
-            let mut items: Vec<crate::types::ProcessInfoJson> = final_stats
-                .iter()
-                .map(|(pid, info)| crate::types::ProcessInfoJson::from((pid, info)))
-                .collect();
-            // Stable order: sort by total bytes desc
-            items.sort_by_key(|b| std::cmp::Reverse(b.sent_bytes + b.received_bytes));
-
-            if let Ok(json_output) = serde_json::to_string_pretty(&items) {
+            if want_summary {
+                // The final update carries every process with all its recorded packets
+                let mut summary = summary::SessionSummary::new();
+                summary.observe(&final_stats);
+                let session = report::SessionInfo { interface: &iface, started: session_start, filter: filter_label.as_deref() };
+                finish_session(&cli, &summary, &session, None);
+            }
+            let output = match cli.json_schema.unwrap_or_default() {
+                json_output::JsonSchema::V1 => serde_json::to_string_pretty(&json_output::processes_v1(&final_stats)),
+                json_output::JsonSchema::V2 => {
+                    let ended = chrono::Local::now();
+                    let capture = json_output::CaptureMetadata {
+                        interface: iface.clone(),
+                        host: metrics::hostname(),
+                        started: session_start.to_rfc3339(),
+                        ended: ended.to_rfc3339(),
+                        duration_secs: (ended - session_start).num_milliseconds() as f64 / 1000.0,
+                        filter: filter_label.clone(),
+                        containers: containers_mode_effective,
+                        estimated: estats_mode,
+                        counting: byte_counting,
+                        monitetoring_version: env!("CARGO_PKG_VERSION"),
+                    };
+                    serde_json::to_string_pretty(&json_output::document_v2(&final_stats, capture, cli.json_connections))
+                }
+            };
+            if cli.check {
+                let elapsed = (chrono::Local::now() - session_start).to_std().unwrap_or_default();
+                let (report, code) = check::evaluate(&check_thresholds, &final_stats, elapsed);
+                // With --json too, stdout stays valid JSON
+                if cli.json {
+                    if let Ok(json_output) = output {
+                        println!("{}", json_output);
+                    }
+                    eprintln!("{}", report);
+                } else {
+                    println!("{}", report);
+                }
+                exit(code);
+            }
+            if let Ok(json_output) = output {
                 println!("{}", json_output);
             }
         }
     } else {
-        display_startup_info(&iface, false, containers_mode_effective);
+        display_startup_info(&iface, None, containers_mode_effective, false);
         
         // Small delay to let user read the information
         std::thread::sleep(std::time::Duration::from_millis(1500));
         
         // Start TUI
         let mut app = App::new(containers_mode_effective, show_total_columns);
+        app.demo_mode = demo_mode;
+        app.traffic_source = traffic_shared;
+        app.dry_run = cli.dry_run;
+        app.protected = protected;
+        app.allow_protected_kills = cli.allow_protected_kills;
+        app.audit = audit_log;
+        if aggregate_mode {
+            app.remote_hosts = Some(Vec::new());
+        }
+        app.estimated = estats_mode;
+        app.byte_counting = byte_counting;
+        app.capture_iface = Some(iface.clone());
+        if !app.foreign_pids() {
+            app.limits = limit::active_limits();
+            block::clear_leftovers();
+        }
+        if !no_capture && !estats_mode {
+            app.capture_control = Some(capture_control_tx);
+        }
+        app.process_filter = filter_label;
+        app.stop_at = deadline;
+        if estats_mode {
+            // Enabling per-connection statistics needs Administrator, so this replaces that warning
+            startup_warning = Some(if startup_warning.is_some() {
+                "⚠️ Estimated TCP-only counters: not running as Administrator, so most connections will read as 0".to_string()
+            } else {
+                "⚠️ No packet capture: showing estimated TCP-only counters from connection statistics".to_string()
+            });
+        }
         if let Some(warning) = startup_warning.take() {
-            app.kill_notification = Some(warning);
-            app.kill_notification_time = Some(Instant::now());
+            app.notice(warning);
         }
         if let Some(saved_config) = load_config() {
+            // The minimums the Settings view steps down to
+            app.memory_budget = saved_config.memory_budget_mb.max(32).saturating_mul(1024 * 1024);
+            app.history_window_secs = saved_config.history_window_secs.max(60);
+            app.exited_retention_secs = saved_config.exited_retention_secs;
+            app.cleanup_interval_secs = saved_config.cleanup_interval_secs.max(1);
+            app.idle_after_secs = saved_config.idle_after_secs;
+            // The range the Settings view steps through; 0 would sample on every tick
+            app.sample_interval_ms = saved_config.sample_interval_ms.clamp(100, 10_000);
+            app.refresh_interval_ms = saved_config.refresh_interval_ms.max(20);
+            app.total_quota_threshold = saved_config.quota_mb.max(1) * 1024 * 1024;
+            app.theme = saved_config.theme;
+            let highlight_problems = app.theme.compile_highlights();
+            if !highlight_problems.is_empty() {
+                app.notice(format!("⚠️ Ignored highlight rules: {}", highlight_problems.join(", ")));
+            }
+            app.anomalies.settings = saved_config.anomaly;
+            app.cost = saved_config.cost;
+            app.pinned = saved_config.pinned.into_iter().collect();
+            let (classifier, problems) = classify::Classifier::new(&saved_config.classification);
+            app.categories = classifier.categories().to_vec();
+            if !problems.is_empty() {
+                app.notice(format!("⚠️ Ignored classification rules: {}", problems.join(", ")));
+            }
+            let (keymap, problems) = ui::keymap::Keymap::from_config(&saved_config.keys);
+            app.keymap = keymap;
+            if !problems.is_empty() {
+                app.notice(format!("⚠️ Ignored key bindings: {}", problems.join(", ")));
+            }
             for alert in saved_config.alerts {
-                app.alerts.insert(alert.process_pid, alert);
+                match alert.process_name.clone() {
+                    Some(name) => {
+                        app.alert_rules.insert(name, alert);
+                    }
+                    None => {
+                        app.alerts.insert(alert.process_pid, alert);
+                    }
+                }
             }
         }
+        if let Some(budget_mb) = cli.memory_budget {
+            app.memory_budget = budget_mb.saturating_mul(1024 * 1024);
+        }
+        if let Some(window) = cli.history_window {
+            app.history_window_secs = window;
+        }
+        if let Some(path) = &cli.history_store {
+            let size = cli.history_store_size.unwrap_or(64) * 1024 * 1024;
+            match history_store::HistoryStore::create(path, size) {
+                Ok(store) => app.history_store = Some(store),
+                Err(e) => app.notice(format!("⚠️ Could not open the history store {}: {}", path.display(), e)),
+            }
+        }
+        if let Some(interval) = cli.sample_interval {
+            app.sample_interval_ms = interval;
+        }
+        if let Some(secs) = cli.keep_exited {
+            app.exited_retention_secs = secs;
+        }
+        if let Some(secs) = cli.cleanup_interval {
+            app.cleanup_interval_secs = secs;
+        }
+        if let Some(secs) = cli.idle_after {
+            app.idle_after_secs = secs;
+        }
+        if let Some(price) = cli.cost_per_gb {
+            app.cost.per_gb = Some(price);
+        }
+        if let Some(currency) = &cli.currency {
+            app.cost.currency = currency.clone();
+        }
+        if let Some(billed) = cli.cost_billed {
+            app.cost.billed = billed;
+        }
+        if let Some(budget) = cli.cost_budget {
+            app.cost.budget = Some(budget);
+        }
+        app.show_debug_overlay = cli.debug_ui;
+        ui::install_panic_hook();
+        let shutdown = ui::shutdown_on_signal();
         let mut terminal = ui::setup_terminal()?;
+        let terminal_guard = ui::TerminalGuard;
 
-        let tick_rate = Duration::from_millis(100);
         let mut last_tick = Instant::now();
         let mut last_cleanup = Instant::now();
+        let mut session_summary = want_summary.then(summary::SessionSummary::new);
+        let mut action_queue = action_queue::ActionQueue::new();
         
         loop {
             // --- Draw UI ---
+            let frame_start = Instant::now();
             ui::render_ui(&mut app, &mut terminal)?;
+            app.render_profile.record_frame(frame_start, frame_start.elapsed());
             
             // Reset force redraw flag after rendering
             if app.force_redraw {
@@ -866,90 +1381,235 @@ async fn main() -> Result<(), io::Error> {
             }
 
             // --- Input Handling ---
+            // Wait for input until the next tick, then take everything queued up so a slow
+            // frame never leaves keys waiting for later frames
+            // The Settings view can change the refresh interval while running
+            let tick_rate = Duration::from_millis(app.refresh_interval_ms);
             let timeout = tick_rate
                 .checked_sub(last_tick.elapsed())
                 .unwrap_or_else(|| Duration::from_secs(0));
 
-            if crossterm::event::poll(timeout)?
-                && let Event::Key(event) = event::read()?
-                    && event.kind == crossterm::event::KeyEventKind::Press
-                        && ui::input::handle_key_event(&mut app, event.code) {
-                            break; // Exit condition
+            let mut keys = Vec::new();
+            let mut wait = timeout;
+            while crossterm::event::poll(wait)? {
+                if let Event::Key(event) = event::read()?
+                    && event.kind == crossterm::event::KeyEventKind::Press {
+                        // Raw mode turns Ctrl+C into a key press instead of SIGINT
+                        if event.code == KeyCode::Char('c') && event.modifiers.contains(KeyModifiers::CONTROL) {
+                            shutdown.store(true, Ordering::Relaxed);
                         }
+                        keys.push(event.code);
+                    }
+                wait = Duration::ZERO;
+            }
+            if shutdown.load(Ordering::Relaxed) || ui::input::handle_key_events(&mut app, &keys) {
+                break; // Exit condition
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break; // End of a --duration/--schedule run
+            }
             
             // --- Tick-based updates ---
             if last_tick.elapsed() >= tick_rate {
-                // Drain all pending messages and only keep the most recent to avoid backlog lag
-                let mut latest_stats = None;
-                while let Ok(stats) = rx.try_recv() {
-                    latest_stats = Some(stats);
+                // Drain all pending updates; each one only carries what changed since the previous
+                let backlog = rx.len();
+                let apply_start = Instant::now();
+                let mut updates = Vec::new();
+                while let Ok(delta) = rx.try_recv() {
+                    updates.push(delta);
                 }
 
-                if let Some(new_stats) = latest_stats {
-                    let now = app.start_time.elapsed().as_secs_f64();
-                    for (pid, new_info) in new_stats {
+                if let Some(stats) = capture_stats_rx.try_iter().last() {
+                    app.update_capture_stats(stats);
+                }
+                if let Some(stats) = scan_stats_rx.try_iter().last() {
+                    app.scan_stats = Some(stats);
+                }
+                if let Some(flows) = local_flows_rx.try_iter().last() {
+                    app.local_flows = flows;
+                }
+                if let Some(services) = services_rx.try_iter().last() {
+                    app.services = services;
+                }
+                if let Some(hosts) = remote_hosts_rx.try_iter().last() {
+                    app.update_remote_hosts(hosts);
+                }
+                for event in capture_events_rx.try_iter() {
+                    app.apply_capture_event(event);
+                }
+                if app.capture_failure.is_some() {
+                    break;
+                }
+                if let Some(picker) = app.interface_picker.as_mut() {
+                    picker.poll_measurement();
+                }
+                for message in pcap_done_rx.try_iter() {
+                    app.notify(events::EventKind::Capture, None, message.clone());
+                    app.command_execution_log.push_front((Instant::now(), message));
+                    if app.command_execution_log.len() > 10 {
+                        app.command_execution_log.pop_back();
+                    }
+                }
+
+                let sample_due = !updates.is_empty() && app.history_sample_due();
+                let window_secs = app.history_window_secs as f64;
+                let now = app.start_time.elapsed().as_secs_f64();
+                let history_points = app.history_points();
+                // Packets are stamped as the capture thread counts them, so the newest one
+                // tells how far behind the UI is
+                let capture_lag = updates.iter()
+                    .flat_map(|delta| delta.values())
+                    .filter_map(|update| update.new_packets.last())
+                    .map(|packet| packet.timestamp)
+                    .max()
+                    .map(|newest| newest.elapsed().unwrap_or_default());
+                for delta in updates {
+                    if let Some(summary) = &mut session_summary {
+                        summary.observe(&delta);
+                    }
+                    for (pid, update) in delta {
                         // Ignore stats for processes that are known to be killed or dead
-                        if !process::should_track_process(pid, &app.killed_processes, &app.dead_processes_cache) {
+                        // Demo and remote PIDs aren't local, so only the killed list applies to them
+                        let tracked = if app.foreign_pids() {
+                            !app.killed_processes.contains(&pid)
+                        } else {
+                            process::should_track_process(pid, &app.killed_processes, &app.dead_processes_cache)
+                        };
+                        if !tracked {
                             continue;
                         }
+                        // Exited processes still on screen keep getting updates; only a live
+                        // process means the PID was reused, and that one starts over
+                        if app.exited_processes.contains_key(&pid) {
+                            if app.foreign_pids() || !process::is_process_alive(pid) {
+                                continue;
+                            }
+                            app.exited_processes.remove(&pid);
+                            app.stats.remove(&pid);
+                        }
+                        let arrived = !app.stats.contains_key(&pid);
                         let entry = app.stats.entry(pid).or_insert_with(|| {
                             // If process is new, create a new ProcessInfo for it
-                            let mut pi = new_info.clone();
-                            // Allocate enough space for ~5 minutes of data for history vectors
-                            pi.sent_history = Vec::with_capacity(3_100);
-                            pi.received_history = Vec::with_capacity(3_100);
+                            let mut pi = update.info.clone();
+                            // Allocate enough space for a full history window
+                            pi.sent_history = Vec::with_capacity(history_points + 1);
+                            pi.received_history = Vec::with_capacity(history_points + 1);
                             pi
                         });
 
                         // Update stats from the capture thread
-                        entry.sent = new_info.sent;
-                        entry.received = new_info.received;
-                        entry.sent_rate = new_info.sent_rate;
-                        entry.received_rate = new_info.received_rate;
-                        entry.name = new_info.name;
-                        entry.packet_history = new_info.packet_history;
-
-                        // Update the per-process history for the chart
-                        entry.sent_history.push((now, entry.sent_rate as f64));
-                        entry.received_history.push((now, entry.received_rate as f64));
-
-                        // Trim history vectors to prevent them from growing indefinitely
-                        // Keep at most ~5 minutes (≈3 000 points) of history to match the chart window
-                        if entry.sent_history.len() > 3_000 {
-                            entry.sent_history.remove(0);
+                        update.apply_to(entry);
+                        if arrived {
+                            if let Some(store) = &mut app.history_store {
+                                store.forget(pid);
+                            }
+                            app.note_arrival(pid);
                         }
-                        if entry.received_history.len() > 3_000 {
-                            entry.received_history.remove(0);
+                    }
+                }
+                app.render_profile.record_tick(backlog, apply_start.elapsed(), capture_lag);
+
+                // Update the per-process history for the chart, trimmed to the
+                // history window and downsampled once it gets too long
+                if sample_due {
+                    for (pid, entry) in app.stats.iter_mut() {
+                        let sent = types::push_history_point(&mut entry.sent_history, (now, entry.sent_rate as f64), window_secs);
+                        let received = types::push_history_point(&mut entry.received_history, (now, entry.received_rate as f64), window_secs);
+                        // Samples leaving the window go to the history store, if there is one
+                        if let Some(store) = &mut app.history_store {
+                            store.spill(*pid, &sent, &received);
                         }
                     }
                 }
 
                 // Update data for other UI components that depend on the new stats
                 app.update_system_stats();
-                let now = app.start_time.elapsed().as_secs_f64();
-                let rates: Vec<(i32, f64, f64)> = app.stats.iter()
-                    .map(|(pid, info)| (*pid, info.sent_rate as f64, info.received_rate as f64))
-                    .collect();
-                app.system_bandwidth_history.push((now, rates));
-                // Cap system-wide history to ~5 minutes, too (3 000 × 100 ms)
-                if app.system_bandwidth_history.len() > 3_000 {
-                    app.system_bandwidth_history.remove(0);
+                for pid in app.anomalies.observe(&app.stats) {
+                    if app.anomalies.settings.enabled
+                        && let (Some(info), Some(score)) = (app.stats.get(&pid), app.anomalies.score(pid)) {
+                            let message = format!(
+                                "📈 Unusual traffic from {} (PID {}): {} against a baseline of {}",
+                                info.name, pid, format_rate(score.current as u64), format_rate(score.baseline as u64)
+                            );
+                            app.notify(events::EventKind::Anomaly, Some(pid), message);
+                            app.last_alert_pid = None;
+                        }
+                }
+                app.record_snapshot();
+                if let Some(exporter) = &mut metrics_exporter {
+                    if exporter.due() {
+                        exporter.offer(std::sync::Arc::new(stream::StreamFrame::from_stats(&app.stats, &app.traffic)));
+                    }
+                    for message in exporter.messages() {
+                        app.notify(events::EventKind::System, None, message);
+                    }
+                }
+                if app.interface_poll_due() {
+                    let mut nics = interfaces::read_interfaces();
+                    // `iw` runs per Wi-Fi interface, so only while a view shows the link quality
+                    if app.mode == types::AppMode::Interfaces || (app.mode == types::AppMode::Normal && app.bandwidth_mode) {
+                        interfaces::read_wireless(&mut nics);
+                    }
+                    app.update_interfaces(nics);
+                }
+                // Process metadata for the detail pane is read once per selection
+                if app.mode == types::AppMode::PacketDetails
+                    && let Some(pid) = app.selected_process
+                    && app.process_details.as_ref().is_none_or(|details| details.pid != pid) {
+                        // Demo and remote PIDs aren't local, so /proc would describe some unrelated process
+                        app.process_details = Some(if app.foreign_pids() {
+                            types::ProcessDetails { pid, ..Default::default() }
+                        } else {
+                            process::read_process_details(pid)
+                        });
+                    }
+                if let Some(view) = app.tcp_info.as_mut()
+                    && let Some((local, remote)) = view.connection
+                    && view.poll_due() {
+                        view.info = Some(tcp_info::read_tcp_info(local, remote));
+                        view.last_poll = Some(Instant::now());
+                    }
+                if !app.foreign_pids()
+                    && let Some(view) = app.connections.as_mut()
+                    && view.poll_due() {
+                        view.sockets = Some(process::read_process_sockets(view.pid));
+                        view.last_poll = Some(Instant::now());
+                    }
+                if sample_due {
+                    app.last_history_sample = Instant::now();
+                    let now = app.start_time.elapsed().as_secs_f64();
+                    let rates: Vec<(i32, f64, f64)> = app.stats.iter()
+                        .map(|(pid, info)| (*pid, info.sent_rate as f64, info.received_rate as f64))
+                        .collect();
+                    // Cap system-wide history to the same window, thinning the oldest half when too long
+                    types::push_system_history_point(&mut app.system_bandwidth_history, (now, rates), window_secs);
                 }
                 ui::update_chart_datasets(&mut app);
 
-                // Cleanup alerts that have been displayed for more than 5 seconds
-                if let Some(time) = app.last_alert_message_time
-                    && time.elapsed() > Duration::from_secs(5) {
-                        app.last_alert_message = None;
-                        app.last_alert_message_time = None;
-                    }
+                // Keep histories within the memory budget, evicting the idlest processes first
+                let evicted = app.enforce_memory_budget();
+                if !evicted.is_empty() {
+                    let _ = evict_tx.send(evicted);
+                }
 
-                // Cleanup kill notifications that have been displayed for more than 5 seconds
-                if let Some(time) = app.kill_notification_time
-                    && time.elapsed() > Duration::from_secs(5) {
-                        app.kill_notification = None;
-                        app.kill_notification_time = None;
+                // Images, Compose projects and pods of containers seen for the first time
+                if app.containers_mode {
+                    for (pid, info) in &app.stats {
+                        if let Some(container) = &info.container_name {
+                            app.containers.request(container, *pid);
+                        }
                     }
+                    app.containers.poll();
+                }
+
+                // Notifications expire one by one, errors last
+                app.notifications.expire(Instant::now());
+                if app.notifications.is_empty() {
+                    app.last_alert_pid = None;
+                }
+
+                // Graceful kills whose process exited or whose grace period is over
+                ui::input::check_pending_kills(&mut app);
 
                 // Cleanup settings notifications that have been displayed for more than 5 seconds
                 if let Some(time) = app.settings_notification_time
@@ -975,36 +1635,145 @@ async fn main() -> Result<(), io::Error> {
                     }
                 }
 
-                // Check for triggered alerts
-                let mut triggered_alerts = Vec::new();
-            for (pid, alert) in &app.alerts {
-                if let Some(stats) = app.stats.get(pid) {
-                        let total_usage = stats.sent + stats.received;
-                        if total_usage > alert.threshold_bytes {
-                            // Check cooldown
-                            let should_trigger = if let Some(last_triggered) = app.alert_cooldowns.get(pid) {
-                                last_triggered.elapsed() > Duration::from_secs(60) // 1 minute cooldown
-                            } else {
-                                true
+                // Kills and custom commands queued by earlier alerts
+                for done in action_queue.finished() {
+                    let ticket = &done.ticket;
+                    if let Some(audit) = &app.audit {
+                        let result = if done.was_killed {
+                            "killed"
+                        } else if done.message.starts_with('❌') {
+                            "failed"
+                        } else {
+                            "done"
                         };
-                        
-                        if should_trigger {
-                                triggered_alerts.push((*pid, alert.clone()));
-                                app.alert_cooldowns.insert(*pid, Instant::now());
-                            }
+                        audit.record(&audit::AuditEvent {
+                            event: "alert",
+                            pid: ticket.pid,
+                            name: &ticket.name,
+                            bytes: ticket.bytes,
+                            threshold: Some(audit::threshold_text(&ticket.alert)),
+                            action: audit::action_name(&ticket.alert.action),
+                            result,
+                            message: &ticket.fired,
+                        });
+                    }
+                    let kind = match ticket.alert.action {
+                        AlertAction::Kill => events::EventKind::Kill,
+                        _ => events::EventKind::Command,
+                    };
+                    match done.output {
+                        Some(output) => {
+                            app.events.push_output(kind, Some(ticket.pid), &done.message, output);
+                            app.alert_notice(ticket.pid, &ticket.alert, done.message.clone());
                         }
+                        None => app.notify_alert(kind, ticket.pid, &ticket.alert, done.message.clone()),
+                    }
+                    app.last_alert_pid = (!done.was_killed).then_some(ticket.pid);
+                    if done.was_killed {
+                        app.note_departure(ticket.pid);
+                        app.killed_processes.insert(ticket.pid);
+                        app.stats.remove(&ticket.pid);
                     }
                 }
-                
-                for (pid, alert) in triggered_alerts {
+
+                // Check for triggered alerts, skipping muted ones
+                app.expire_alert_mutes();
+                for (pid, alert) in alerts::due_alerts(&mut app) {
+                    let protected = app.protected_reason(pid).filter(|_| matches!(alert.action, AlertAction::Kill | AlertAction::Block));
+                    let dry_run = (app.dry_run || alert.dry_run)
+                        && matches!(alert.action, AlertAction::Kill | AlertAction::CustomCommand(_) | AlertAction::Block);
+                    // Blocking needs the whole app, so it happens before the process's stats are borrowed
+                    let blocked = (matches!(alert.action, AlertAction::Block) && protected.is_none() && !dry_run
+                        && app.stats.contains_key(&pid))
+                        .then(|| app.block_process(pid));
                     if let Some(stats) = app.stats.get(&pid) {
-                        let (was_killed, message, execution_log) = execute_alert_action(
-                            &alert.action, pid, &stats.name, stats.sent, stats.received, alert.threshold_bytes
+                        let fired = format!(
+                            "🔔 Alert fired for {} (PID {}): {}",
+                            stats.name, pid, alert.usage_text(stats, app.anomalies.score(pid))
                         );
+                        let mut queued = false;
+                        let (was_killed, message, execution_log) = if let Some(reason) = &protected {
+                            let verb = if matches!(alert.action, AlertAction::Block) { "block" } else { "kill" };
+                            (false, Some(format!("🛡️ Didn't {} {} (PID {}): protected by {}", verb, stats.name, pid, reason)), None)
+                        } else if let Some(result) = blocked {
+                            (false, Some(result.unwrap_or_else(|refusal| refusal)), None)
+                        } else if dry_run {
+                            // Validating thresholds: say what would have happened and leave the process alone
+                            let usage = alert.usage_text(stats, app.anomalies.score(pid));
+                            let action = match &alert.action {
+                                AlertAction::CustomCommand(cmd) => format!("run `{}` for", cmd),
+                                AlertAction::Block => "block the network of".to_string(),
+                                _ => "kill".to_string(),
+                            };
+                            (false, Some(format!("🧪 Dry run: would {} {} (PID {}) | Usage: {}", action, stats.name, pid, usage)), None)
+                        } else if app.foreign_pids()
+                            && matches!(alert.action, AlertAction::Kill | AlertAction::CustomCommand(_) | AlertAction::CapturePcap(_)) {
+                            // Never signal, record or hand a user command a PID that isn't one of
+                            // this machine's: a demo PID can be a real process's, and a remote
+                            // process's agent has to act on it
+                            match (&alert.action, app.demo_mode) {
+                                (AlertAction::Kill, true) => {
+                                    (true, Some(format!("💀 Killed {} (PID {}) due to bandwidth limit (demo)", stats.name, pid)), None)
+                                }
+                                (AlertAction::CustomCommand(cmd), true) => {
+                                    (false, Some(format!("🔧 Would run `{}` for {} (PID {}) (demo)", cmd, stats.name, pid)), None)
+                                }
+                                (_, true) => (false, Some(format!("📼 Would record {} (PID {}) to a pcap file (demo)", stats.name, pid)), None),
+                                (action, false) => {
+                                    let verb = match action {
+                                        AlertAction::Kill => "kill",
+                                        AlertAction::CustomCommand(_) => "run the command for",
+                                        _ => "record",
+                                    };
+                                    (false, Some(format!("❌ Can't {} {} (PID {}): it runs on a remote host", verb, stats.name, pid)), None)
+                                }
+                            }
+                        } else {
+                            queued = matches!(alert.action, AlertAction::Kill | AlertAction::CustomCommand(_));
+                            execute_alert_action(
+                                &alert, pid, stats, app.anomalies.score(pid), &pcap_tx, &mut action_queue, &fired
+                            )
+                        };
                         
+                        // Queued actions are audited once they finish
+                        if let Some(audit) = &app.audit
+                            && !queued {
+                            // Failed actions are the ones reported with ❌
+                            let result = if protected.is_some() {
+                                "protected"
+                            } else if dry_run {
+                                "dry-run"
+                            } else if was_killed {
+                                "killed"
+                            } else if message.as_ref().is_some_and(|msg| msg.starts_with('❌')) {
+                                "failed"
+                            } else {
+                                "done"
+                            };
+                            audit.record(&audit::AuditEvent {
+                                event: "alert",
+                                pid,
+                                name: &stats.name,
+                                bytes: stats.sent + stats.received,
+                                threshold: Some(audit::threshold_text(&alert)),
+                                action: audit::action_name(&alert.action),
+                                result,
+                                message: &fired,
+                            });
+                        }
+                        app.events.push(events::EventKind::Alert, Some(pid), &fired);
+                        if let Some(log_entry) = &execution_log {
+                            app.events.push(events::EventKind::Command, Some(pid), log_entry);
+                        }
                         if let Some(msg) = message {
-                            app.last_alert_message = Some(msg);
-                            app.last_alert_message_time = Some(Instant::now());
+                            let kind = match alert.action {
+                                AlertAction::Kill => events::EventKind::Kill,
+                                AlertAction::CustomCommand(_) => events::EventKind::Command,
+                                AlertAction::CapturePcap(_) => events::EventKind::Capture,
+                                AlertAction::SystemAlert | AlertAction::Block => events::EventKind::Alert,
+                            };
+                            app.notify_alert(kind, pid, &alert, msg);
+                            app.last_alert_pid = (!was_killed).then_some(pid);
                         }
                         if let Some(log_entry) = execution_log {
                             app.command_execution_log.push_front((Instant::now(), log_entry));
@@ -1014,21 +1783,17 @@ async fn main() -> Result<(), io::Error> {
                         }
 
                         if was_killed {
+                            app.note_departure(pid);
                             app.killed_processes.insert(pid);
                             app.stats.remove(&pid);
                         }
                     }
                 }
 
-                // Periodic cleanup of dead processes
-                if last_cleanup.elapsed() >= Duration::from_secs(PROCESS_CLEANUP_INTERVAL_SECS) {
-                    let removed_pids = cleanup_dead_processes(&mut app.stats, &app.killed_processes);
-                    for pid in removed_pids {
-                        app.dead_processes_cache.insert(pid);
-                        if app.selected_process == Some(pid) {
-                            app.selected_process = None;
-                        }
-                    }
+                // Periodic cleanup of dead processes, keeping them around for the retention time
+                if !app.foreign_pids() && last_cleanup.elapsed() >= Duration::from_secs(app.cleanup_interval_secs) {
+                    let exited = find_exited_processes(&app.stats, &app.killed_processes);
+                    app.retire_exited_processes(exited);
                     last_cleanup = Instant::now();
                 }
 
@@ -1036,7 +1801,46 @@ async fn main() -> Result<(), io::Error> {
             }
         }
         
-        ui::restore_terminal(&mut terminal)?;
+        drop(terminal_guard);
+        app.unblock_all();
+
+        // Stopping the UI stops the capture thread, which flushes any pcap
+        // recordings still running; give it a moment before exiting
+        drop(rx);
+        drop(pcap_tx);
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while (!capture_thread.is_finished() || action_queue.pending() > 0) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+            for done in action_queue.finished() {
+                eprintln!("{}", done.message);
+            }
+        }
+        for message in pcap_done_rx.try_iter() {
+            eprintln!("{}", message);
+        }
+        if action_queue.pending() > 0 {
+            eprintln!("⚠️  {} alert actions were still running and were left to finish on their own", action_queue.pending());
+        }
+        if let Some(error) = &app.capture_failure {
+            eprintln!("{}", error);
+            exit(1);
+        }
+
+        if let Some(summary) = session_summary {
+            // What fired and what the user marked, oldest first
+            let mut alerts: Vec<summary::AlertEntry> = app.events.iter()
+                .filter(|event| matches!(event.kind, events::EventKind::Alert | events::EventKind::Kill | events::EventKind::Quota | events::EventKind::Anomaly | events::EventKind::Annotation))
+                .map(|event| summary::AlertEntry {
+                    time: event.time.format("%H:%M:%S").to_string(),
+                    seconds: (event.time - session_start).num_seconds().max(0) as u32,
+                    kind: event.kind.label(),
+                    message: event.message.clone(),
+                })
+                .collect();
+            alerts.reverse();
+            let session = report::SessionInfo { interface: &iface, started: session_start, filter: app.process_filter.as_deref() };
+            finish_session(&cli, &summary, &session, Some(&alerts));
+        }
     }
     Ok(())
 }
@@ -0,0 +1,132 @@
+// InfluxDB line protocol over HTTP. Works with the 1.x `/write?db=…` and the
+// 2.x `/api/v2/write?org=…&bucket=…` endpoints; 2.x wants the API token from
+// INFLUX_TOKEN.
+
+use super::{HttpEndpoint, MetricsSink};
+use crate::stream::StreamFrame;
+
+pub struct InfluxSink {
+    endpoint: HttpEndpoint,
+    host: String,
+    token: Option<String>,
+}
+
+impl InfluxSink {
+    pub fn new(endpoint: HttpEndpoint, host: String) -> Self {
+        let token = std::env::var("INFLUX_TOKEN").ok().filter(|token| !token.is_empty());
+        InfluxSink { endpoint, host, token }
+    }
+}
+
+/// Escape a tag value; empty values aren't allowed, so those tags are left out
+fn tag(key: &str, value: Option<&str>) -> String {
+    match value.filter(|value| !value.is_empty()) {
+        Some(value) => {
+            let mut escaped = String::with_capacity(value.len() + 8);
+            for c in value.chars() {
+                match c {
+                    ',' | '=' | ' ' | '\\' => {
+                        escaped.push('\\');
+                        escaped.push(c);
+                    }
+                    '\n' | '\r' => escaped.push(' '),
+                    c => escaped.push(c),
+                }
+            }
+            format!(",{}={}", key, escaped)
+        }
+        None => String::new(),
+    }
+}
+
+/// One point per process and one for all of them, plus the capture's own counts
+/// per protocol and interface when there is a capture; nanosecond timestamps
+fn lines(frame: &StreamFrame, host: &str) -> String {
+    let timestamp = frame.timestamp_ms as u128 * 1_000_000;
+    let host = tag("host", Some(host));
+    let mut body = String::new();
+    for process in &frame.processes {
+        body.push_str(&format!(
+            "monitetoring_process{}{}{}{}{} sent_bytes={}i,received_bytes={}i,sent_rate={}i,received_rate={}i,connections={}i {}\n",
+            host,
+            tag("pid", Some(&process.pid.to_string())),
+            tag("name", Some(&process.name)),
+            tag("user", process.user_name.as_deref()),
+            tag("container", process.container_name.as_deref()),
+            process.sent_bytes,
+            process.received_bytes,
+            process.sent_rate_bytes,
+            process.received_rate_bytes,
+            process.connection_count,
+            timestamp,
+        ));
+    }
+    let totals = &frame.totals;
+    body.push_str(&format!(
+        "monitetoring_total{} sent_bytes={}i,received_bytes={}i,sent_rate={}i,received_rate={}i,processes={}i {}\n",
+        host,
+        totals.sent_bytes,
+        totals.received_bytes,
+        totals.sent_rate_bytes,
+        totals.received_rate_bytes,
+        frame.processes.len(),
+        timestamp,
+    ));
+    if let Some(traffic) = &frame.traffic {
+        for (name, stats) in traffic.protocols() {
+            body.push_str(&format!(
+                "monitetoring_protocol{}{} packets={}i,bytes={}i,packet_rate={}i,rate={}i,unattributed={}i {}\n",
+                host,
+                tag("protocol", Some(name)),
+                stats.total.packets,
+                stats.total.bytes,
+                stats.per_second.packets,
+                stats.per_second.bytes,
+                stats.unattributed,
+                timestamp,
+            ));
+        }
+        for interface in &traffic.interfaces {
+            body.push_str(&format!(
+                "monitetoring_interface{}{} packets={}i,bytes={}i,packet_rate={}i,rate={}i,unparsed={}i,kernel_dropped={}i,interface_dropped={}i {}\n",
+                host,
+                tag("interface", Some(&interface.name)),
+                interface.total.packets,
+                interface.total.bytes,
+                interface.per_second.packets,
+                interface.per_second.bytes,
+                interface.unparsed,
+                interface.kernel_dropped,
+                interface.interface_dropped,
+                timestamp,
+            ));
+        }
+    }
+    body
+}
+
+impl MetricsSink for InfluxSink {
+    fn describe(&self) -> String {
+        format!("InfluxDB at {}", self.endpoint.url)
+    }
+
+    fn export(&mut self, frame: &StreamFrame) -> Result<(), String> {
+        let headers: Vec<(String, String)> = self.token.iter()
+            .map(|token| ("Authorization".to_string(), format!("Token {}", token)))
+            .collect();
+        self.endpoint.post("text/plain; charset=utf-8", &headers, lines(frame, &self.host).as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_tags() {
+        assert_eq!(tag("name", Some("Web Content")), ",name=Web\\ Content");
+        assert_eq!(tag("name", Some("a,b=c")), ",name=a\\,b\\=c");
+        assert_eq!(tag("user", Some("")), "");
+        assert_eq!(tag("user", None), "");
+    }
+}
@@ -0,0 +1,187 @@
+// Metrics export to time-series backends (`--metrics influx=URL`,
+// `--metrics otlp=URL`). Every sink gets the same snapshot frames as the
+// stream and the web dashboard, once per `--metrics-interval`, and runs on a
+// thread of its own so a slow or unreachable backend never holds up capture
+// or the UI.
+
+mod influx;
+mod otlp;
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::sync::mpsc as std_mpsc;
+use std::time::{Duration, Instant};
+use crate::stream::StreamFrame;
+
+/// Connect, write and read timeout of one push
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest response read back from a backend (only the status line matters)
+const MAX_RESPONSE_BYTES: u64 = 16 * 1024;
+
+/// A backend snapshots are pushed to
+pub trait MetricsSink: Send {
+    /// Shown in messages about the sink, e.g. "InfluxDB at http://db:8086/api/v2/write"
+    fn describe(&self) -> String;
+
+    /// Push one snapshot
+    fn export(&mut self, frame: &StreamFrame) -> Result<(), String>;
+}
+
+/// Build a sink from a `--metrics` value such as `influx=http://localhost:8086/write?db=net`
+pub fn parse_sink(spec: &str) -> Result<Box<dyn MetricsSink>, String> {
+    let (kind, url) = spec.split_once('=')
+        .ok_or_else(|| format!("'{}' is not a metrics sink; use influx=URL or otlp=URL", spec))?;
+    let endpoint = HttpEndpoint::parse(url)?;
+    match kind.trim().to_lowercase().as_str() {
+        "influx" | "influxdb" => Ok(Box::new(influx::InfluxSink::new(endpoint, hostname()))),
+        "otlp" | "otel" => Ok(Box::new(otlp::OtlpSink::new(endpoint, hostname())?)),
+        other => Err(format!("Unknown metrics sink '{}' (use influx or otlp)", other)),
+    }
+}
+
+/// Name of this machine, tagged on every point
+pub fn hostname() -> String {
+    #[cfg(target_os = "linux")]
+    let name = std::fs::read_to_string("/proc/sys/kernel/hostname").ok();
+    #[cfg(not(target_os = "linux"))]
+    let name = std::env::var("COMPUTERNAME").ok();
+    name.map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// A plain-HTTP URL snapshots are POSTed to
+pub struct HttpEndpoint {
+    url: String,
+    authority: String, // host:port
+    path: String,      // Path and query
+}
+
+impl HttpEndpoint {
+    fn parse(url: &str) -> Result<Self, String> {
+        let url = url.trim();
+        if url.starts_with("https://") {
+            return Err(format!("{}: https isn't supported; push to a local collector or proxy over http", url));
+        }
+        let rest = url.strip_prefix("http://")
+            .ok_or_else(|| format!("'{}' is not an http:// URL", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], &rest[slash..]),
+            None => (rest, "/"),
+        };
+        if authority.is_empty() {
+            return Err(format!("'{}' has no host", url));
+        }
+        // Without a port, plain HTTP's
+        let has_port = authority.rsplit_once(':').is_some_and(|(_, port)| !port.contains(']'));
+        let authority = if has_port { authority.to_string() } else { format!("{}:80", authority) };
+        Ok(HttpEndpoint { url: url.to_string(), authority, path: path.to_string() })
+    }
+
+    /// POST `body` and fail unless the backend answers with a 2xx status
+    fn post(&self, content_type: &str, headers: &[(String, String)], body: &[u8]) -> Result<(), String> {
+        let address = self.authority.to_socket_addrs()
+            .map_err(|e| format!("can't resolve {}: {}", self.authority, e))?
+            .next()
+            .ok_or_else(|| format!("can't resolve {}", self.authority))?;
+        let mut socket = TcpStream::connect_timeout(&address, HTTP_TIMEOUT).map_err(|e| e.to_string())?;
+        socket.set_read_timeout(Some(HTTP_TIMEOUT)).map_err(|e| e.to_string())?;
+        socket.set_write_timeout(Some(HTTP_TIMEOUT)).map_err(|e| e.to_string())?;
+
+        let mut head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.path, self.authority, content_type, body.len()
+        );
+        for (name, value) in headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        socket.write_all(head.as_bytes()).and_then(|_| socket.write_all(body)).map_err(|e| e.to_string())?;
+
+        let mut response = String::new();
+        let _ = socket.take(MAX_RESPONSE_BYTES).read_to_string(&mut response);
+        let status = response.lines().next().unwrap_or_default();
+        match status.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            Some(_) => {
+                // The body usually says what was wrong with the data
+                let detail = response.split_once("\r\n\r\n").map_or("", |(_, body)| body.trim());
+                let detail: String = detail.chars().take(200).collect();
+                Err(format!("{} {}", status, detail).trim().to_string())
+            }
+            None => Err("no HTTP response".to_string()),
+        }
+    }
+}
+
+/// Feeds the sinks' thread with a snapshot once per interval
+pub struct MetricsExporter {
+    interval: Duration,
+    last_export: Option<Instant>,
+    frames: std_mpsc::SyncSender<Arc<StreamFrame>>,
+    messages: std_mpsc::Receiver<String>,
+}
+
+impl MetricsExporter {
+    /// Start pushing to `sinks`; nothing is sent until the first `offer`
+    pub fn start(mut sinks: Vec<Box<dyn MetricsSink>>, interval: Duration) -> Self {
+        // One pending snapshot; when the sinks fall behind, newer ones are skipped
+        let (frames_tx, frames_rx) = std_mpsc::sync_channel::<Arc<StreamFrame>>(1);
+        let (messages_tx, messages_rx) = std_mpsc::channel();
+        std::thread::spawn(move || {
+            let mut failing = vec![false; sinks.len()];
+            for frame in frames_rx {
+                for (sink, failing) in sinks.iter_mut().zip(failing.iter_mut()) {
+                    // Report a sink going down and coming back, not every failed push
+                    let message = match sink.export(&frame) {
+                        Err(e) if !*failing => Some(format!("⚠️ Metrics export to {} failed: {}", sink.describe(), e)),
+                        Ok(()) if *failing => Some(format!("✅ Metrics export to {} works again", sink.describe())),
+                        _ => None,
+                    };
+                    if let Some(message) = message {
+                        *failing = !*failing;
+                        let _ = messages_tx.send(message);
+                    }
+                }
+            }
+        });
+        MetricsExporter { interval, last_export: None, frames: frames_tx, messages: messages_rx }
+    }
+
+    /// Whether the next snapshot should be exported
+    pub fn due(&self) -> bool {
+        self.last_export.is_none_or(|last| last.elapsed() >= self.interval)
+    }
+
+    /// Hand a snapshot to the sinks
+    pub fn offer(&mut self, frame: Arc<StreamFrame>) {
+        self.last_export = Some(Instant::now());
+        let _ = self.frames.try_send(frame);
+    }
+
+    /// Sinks that failed or recovered since the last call
+    pub fn messages(&self) -> Vec<String> {
+        self.messages.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_endpoints() {
+        let endpoint = HttpEndpoint::parse("http://db:8086/api/v2/write?org=o&bucket=b").unwrap();
+        assert_eq!(endpoint.authority, "db:8086");
+        assert_eq!(endpoint.path, "/api/v2/write?org=o&bucket=b");
+        let endpoint = HttpEndpoint::parse("http://collector").unwrap();
+        assert_eq!(endpoint.authority, "collector:80");
+        assert_eq!(endpoint.path, "/");
+        assert_eq!(HttpEndpoint::parse("http://[::1]/v1/metrics").unwrap().authority, "[::1]:80");
+        assert!(HttpEndpoint::parse("https://db:8086/write").is_err());
+        assert!(HttpEndpoint::parse("db:8086").is_err());
+        assert!(parse_sink("prometheus=http://db:9090/").is_err());
+    }
+}
@@ -0,0 +1,188 @@
+// OpenTelemetry metrics over OTLP/HTTP with the JSON encoding, e.g. to a
+// collector's `http://host:4318/v1/metrics`. Extra headers (API keys) come
+// from OTEL_EXPORTER_OTLP_HEADERS, as with other OpenTelemetry exporters.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde_json::{Value, json};
+use super::{HttpEndpoint, MetricsSink};
+use crate::stream::StreamFrame;
+
+pub struct OtlpSink {
+    endpoint: HttpEndpoint,
+    host: String,
+    headers: Vec<(String, String)>,
+    start_nanos: String, // Counters are cumulative since monitoring started
+}
+
+impl OtlpSink {
+    pub fn new(endpoint: HttpEndpoint, host: String) -> Result<Self, String> {
+        let headers = match std::env::var("OTEL_EXPORTER_OTLP_HEADERS") {
+            Ok(list) => parse_headers(&list)?,
+            Err(_) => Vec::new(),
+        };
+        let start = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        Ok(OtlpSink { endpoint, host, headers, start_nanos: start.to_string() })
+    }
+
+    fn body(&self, frame: &StreamFrame) -> Value {
+        let time = (frame.timestamp_ms as u128 * 1_000_000).to_string();
+        let point = |attributes: Vec<Value>, value: u64| json!({
+            "attributes": attributes,
+            "startTimeUnixNano": self.start_nanos,
+            "timeUnixNano": time,
+            "asInt": value.to_string(),
+        });
+        let direction = |value: &str| string_attribute("network.io.direction", value);
+
+        let mut io_points = Vec::new();
+        let mut rate_points = Vec::new();
+        for process in &frame.processes {
+            let mut attributes = vec![
+                json!({"key": "process.pid", "value": {"intValue": process.pid.to_string()}}),
+                string_attribute("process.executable.name", &process.name),
+            ];
+            if let Some(user) = &process.user_name {
+                attributes.push(string_attribute("process.owner", user));
+            }
+            if let Some(container) = &process.container_name {
+                attributes.push(string_attribute("container.name", container));
+            }
+            let with_direction = |value: &str| {
+                let mut attributes = attributes.clone();
+                attributes.push(direction(value));
+                attributes
+            };
+            io_points.push(point(with_direction("transmit"), process.sent_bytes));
+            io_points.push(point(with_direction("receive"), process.received_bytes));
+            rate_points.push(point(with_direction("transmit"), process.sent_rate_bytes));
+            rate_points.push(point(with_direction("receive"), process.received_rate_bytes));
+        }
+        let totals = &frame.totals;
+        let cumulative = |points: Vec<Value>| json!({"aggregationTemporality": 2, "isMonotonic": true, "dataPoints": points});
+        let gauge = |points: Vec<Value>| json!({"dataPoints": points});
+
+        let mut metrics = vec![
+            json!({"name": "process.network.io", "unit": "By", "sum": cumulative(io_points)}),
+            json!({"name": "process.network.rate", "unit": "By/s", "gauge": gauge(rate_points)}),
+            json!({"name": "monitetoring.network.io", "unit": "By", "sum": cumulative(vec![
+                point(vec![direction("transmit")], totals.sent_bytes),
+                point(vec![direction("receive")], totals.received_bytes),
+            ])}),
+            json!({"name": "monitetoring.network.rate", "unit": "By/s", "gauge": gauge(vec![
+                point(vec![direction("transmit")], totals.sent_rate_bytes),
+                point(vec![direction("receive")], totals.received_rate_bytes),
+            ])}),
+            json!({"name": "monitetoring.processes", "unit": "{process}", "gauge": gauge(vec![
+                point(Vec::new(), frame.processes.len() as u64),
+            ])}),
+        ];
+        if let Some(traffic) = &frame.traffic {
+            let protocol = |name: &str| vec![string_attribute("network.protocol.name", name)];
+            let interface = |name: &str| vec![string_attribute("network.interface.name", name)];
+            let protocols = traffic.protocols();
+            let by_protocol = |value: fn(&crate::traffic_stats::ProtocolStats) -> u64| {
+                protocols.iter().map(|(name, stats)| point(protocol(name), value(stats))).collect::<Vec<_>>()
+            };
+            let by_interface = |value: fn(&crate::traffic_stats::InterfaceStats) -> u64| {
+                traffic.interfaces.iter().map(|stats| point(interface(&stats.name), value(stats))).collect::<Vec<_>>()
+            };
+            let dropped: Vec<Value> = traffic.interfaces.iter().flat_map(|stats| {
+                let by = |by: &str| {
+                    let mut attributes = interface(&stats.name);
+                    attributes.push(string_attribute("monitetoring.dropped_by", by));
+                    attributes
+                };
+                [point(by("kernel"), stats.kernel_dropped), point(by("interface"), stats.interface_dropped)]
+            }).collect();
+            metrics.extend([
+                json!({"name": "monitetoring.protocol.io", "unit": "By", "sum": cumulative(by_protocol(|stats| stats.total.bytes))}),
+                json!({"name": "monitetoring.protocol.packets", "unit": "{packet}", "sum": cumulative(by_protocol(|stats| stats.total.packets))}),
+                json!({"name": "monitetoring.protocol.rate", "unit": "By/s", "gauge": gauge(by_protocol(|stats| stats.per_second.bytes))}),
+                json!({"name": "monitetoring.protocol.unattributed", "unit": "{packet}", "sum": cumulative(by_protocol(|stats| stats.unattributed))}),
+                json!({"name": "monitetoring.interface.io", "unit": "By", "sum": cumulative(by_interface(|stats| stats.total.bytes))}),
+                json!({"name": "monitetoring.interface.packets", "unit": "{packet}", "sum": cumulative(by_interface(|stats| stats.total.packets))}),
+                json!({"name": "monitetoring.interface.rate", "unit": "By/s", "gauge": gauge(by_interface(|stats| stats.per_second.bytes))}),
+                json!({"name": "monitetoring.interface.unparsed", "unit": "{packet}", "sum": cumulative(by_interface(|stats| stats.unparsed))}),
+                json!({"name": "monitetoring.interface.dropped", "unit": "{packet}", "sum": cumulative(dropped)}),
+            ]);
+        }
+
+        json!({
+            "resourceMetrics": [{
+                "resource": {"attributes": [
+                    string_attribute("service.name", "monitetoring"),
+                    string_attribute("service.version", env!("CARGO_PKG_VERSION")),
+                    string_attribute("host.name", &self.host),
+                ]},
+                "scopeMetrics": [{
+                    "scope": {"name": "monitetoring", "version": env!("CARGO_PKG_VERSION")},
+                    "metrics": metrics,
+                }],
+            }],
+        })
+    }
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({"key": key, "value": {"stringValue": value}})
+}
+
+/// Parse `key=value,key2=value2` (values may be percent-encoded, as the spec allows)
+fn parse_headers(list: &str) -> Result<Vec<(String, String)>, String> {
+    list.split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (key, value) = entry.split_once('=')
+                .ok_or_else(|| format!("OTEL_EXPORTER_OTLP_HEADERS: '{}' is not key=value", entry.trim()))?;
+            Ok((key.trim().to_string(), percent_decode(value.trim())))
+        })
+        .collect()
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+impl MetricsSink for OtlpSink {
+    fn describe(&self) -> String {
+        format!("OTLP at {}", self.endpoint.url)
+    }
+
+    fn export(&mut self, frame: &StreamFrame) -> Result<(), String> {
+        let body = serde_json::to_vec(&self.body(frame)).map_err(|e| e.to_string())?;
+        self.endpoint.post("application/json", &self.headers, &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_headers() {
+        assert_eq!(
+            parse_headers("api-key=abc%20def, x-team=net").unwrap(),
+            vec![("api-key".to_string(), "abc def".to_string()), ("x-team".to_string(), "net".to_string())]
+        );
+        assert!(parse_headers("").unwrap().is_empty());
+        assert!(parse_headers("broken").is_err());
+    }
+}
@@ -0,0 +1,238 @@
+// The main view's notification stack. Alerts, kills, exports and the other
+// one-line results of an action pile up, newest first, instead of replacing
+// each other, and each one expires on its own: errors stay longest. `x`
+// dismisses the one at the top of the box, `X` all of them, and `[`/`]`
+// scroll when there are more than fit.
+//
+// A flapping process shouldn't bury everything else, so a message that only
+// differs from one already shown in its numbers (PIDs, byte counts) replaces
+// it with an "×N" count, an alert can limit how often it notifies at all, and
+// do-not-disturb holds back everything but errors. The Events view still
+// logs each one.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Notifications kept at most; the oldest go first
+const MAX_NOTIFICATIONS: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Messages say how they went with their leading emoji
+    pub fn of(message: &str) -> Severity {
+        const ERROR: [&str; 1] = ["❌"];
+        const WARNING: [&str; 5] = ["⚠️", "⏰", "🚨", "📈", "🛡️"];
+        const SUCCESS: [&str; 3] = ["✅", "💀", "⛔"];
+        let starts = |prefixes: &[&str]| prefixes.iter().any(|prefix| message.starts_with(prefix));
+        if starts(&ERROR) {
+            Severity::Error
+        } else if starts(&WARNING) {
+            Severity::Warning
+        } else if starts(&SUCCESS) {
+            Severity::Success
+        } else {
+            Severity::Info
+        }
+    }
+
+    fn lifetime(self) -> Duration {
+        Duration::from_secs(match self {
+            Severity::Info | Severity::Success => 5,
+            Severity::Warning => 8,
+            Severity::Error => 15,
+        })
+    }
+}
+
+pub struct Notification {
+    pub message: String,
+    pub severity: Severity,
+    pub created: Instant,
+    pub count: u32, // Repeats collapsed into this one, itself included
+    expires: Instant,
+    key: String,
+}
+
+#[derive(Default)]
+pub struct NotificationStack {
+    items: VecDeque<Notification>, // Newest first
+    scroll: usize,                 // Index of the notification at the top of the box
+    do_not_disturb: bool,
+    held_back: usize,                    // Dropped by do-not-disturb since it was turned on
+    last_shown: HashMap<String, Instant>, // Per throttled source
+}
+
+/// The message with every number blanked out, so repeats of it match
+fn collapse_key(message: &str) -> String {
+    let mut key = String::with_capacity(message.len());
+    let mut in_number = false;
+    for c in message.chars() {
+        if c.is_ascii_digit() || (in_number && c == '.') {
+            if !in_number {
+                key.push('#');
+            }
+            in_number = true;
+        } else {
+            in_number = false;
+            key.push(c);
+        }
+    }
+    key
+}
+
+impl NotificationStack {
+    pub fn push(&mut self, message: String) {
+        let severity = Severity::of(&message);
+        if self.do_not_disturb && severity != Severity::Error {
+            self.held_back += 1;
+            return;
+        }
+        let now = Instant::now();
+        let key = collapse_key(&message);
+        // A repeat takes the place of the earlier one, moved to the top with the newer text
+        let repeat = self.items.iter().position(|notification| notification.key == key);
+        let count = repeat.and_then(|index| self.items.remove(index)).map_or(1, |earlier| earlier.count + 1);
+        self.items.push_front(Notification { message, severity, created: now, count, expires: now + severity.lifetime(), key });
+        self.items.truncate(MAX_NOTIFICATIONS);
+        // Keep showing what was on screen when scrolled away from the newest
+        if self.scroll > 0 && repeat.is_none_or(|index| index >= self.scroll) {
+            self.scroll = (self.scroll + 1).min(self.items.len() - 1);
+        }
+    }
+
+    /// Push at most once per `every` for `source`; a message held back in
+    /// between still counts towards the matching notification's ×N
+    pub fn push_throttled(&mut self, source: &str, every: Duration, message: String) {
+        let now = Instant::now();
+        if self.last_shown.get(source).is_some_and(|shown| now.duration_since(*shown) < every) {
+            let key = collapse_key(&message);
+            if let Some(notification) = self.items.iter_mut().find(|notification| notification.key == key) {
+                notification.count += 1;
+            }
+            return;
+        }
+        self.last_shown.insert(source.to_string(), now);
+        self.push(message);
+    }
+
+    /// Turn do-not-disturb on or off, returning how many notifications it held back
+    pub fn set_do_not_disturb(&mut self, on: bool) -> usize {
+        self.do_not_disturb = on;
+        std::mem::take(&mut self.held_back)
+    }
+
+    pub fn do_not_disturb(&self) -> bool {
+        self.do_not_disturb
+    }
+
+    pub fn held_back(&self) -> usize {
+        self.held_back
+    }
+
+    /// Drop notifications whose time is up
+    pub fn expire(&mut self, now: Instant) {
+        self.items.retain(|notification| notification.expires > now);
+        self.clamp_scroll();
+    }
+
+    /// Dismiss the notification at the top of the box
+    pub fn dismiss(&mut self) {
+        if self.scroll < self.items.len() {
+            self.items.remove(self.scroll);
+        }
+        self.clamp_scroll();
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.scroll = 0;
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = (self.scroll + 1).min(self.items.len().saturating_sub(1));
+    }
+
+    fn clamp_scroll(&mut self) {
+        self.scroll = self.scroll.min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Up to `count` notifications from the scroll position on
+    pub fn visible(&self, count: usize) -> impl Iterator<Item = &Notification> {
+        self.items.iter().skip(self.scroll).take(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacks_dismisses_and_expires() {
+        let mut stack = NotificationStack::default();
+        stack.push("🔔 Alert fired for curl".to_string());
+        stack.push("❌ Failed to kill curl".to_string());
+        stack.push("✅ Exported 40 packets".to_string());
+        let messages = |stack: &NotificationStack| stack.visible(10).map(|n| n.message.clone()).collect::<Vec<_>>();
+        assert_eq!(messages(&stack)[0], "✅ Exported 40 packets");
+        assert_eq!(stack.visible(10).map(|n| n.severity).collect::<Vec<_>>(), vec![Severity::Success, Severity::Error, Severity::Info]);
+
+        stack.scroll_down();
+        stack.dismiss();
+        assert_eq!(messages(&stack), vec!["🔔 Alert fired for curl"]);
+        stack.scroll_up();
+        assert_eq!(messages(&stack), vec!["✅ Exported 40 packets", "🔔 Alert fired for curl"]);
+
+        // The error outlives the others
+        stack.push("❌ Export failed".to_string());
+        stack.expire(Instant::now() + Duration::from_secs(10));
+        assert_eq!(messages(&stack), vec!["❌ Export failed"]);
+        assert_eq!(stack.scroll(), 0);
+    }
+
+    #[test]
+    fn collapses_repeats_throttles_and_holds_back() {
+        let mut stack = NotificationStack::default();
+        stack.push("💀 Killed miner (PID 4121) due to bandwidth limit".to_string());
+        stack.push("✅ Exported 40 packets".to_string());
+        stack.push("💀 Killed miner (PID 4187) due to bandwidth limit".to_string());
+        let top = stack.visible(1).next().unwrap();
+        assert_eq!((top.message.as_str(), top.count), ("💀 Killed miner (PID 4187) due to bandwidth limit", 2));
+        assert_eq!(stack.len(), 2);
+
+        let every = Duration::from_secs(300);
+        stack.push_throttled("miner", every, "🔔 Alert fired for miner (PID 4187): 12 MB".to_string());
+        stack.push_throttled("miner", every, "🔔 Alert fired for miner (PID 4187): 14 MB".to_string());
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.visible(1).next().unwrap().count, 2);
+        assert!(stack.visible(1).next().unwrap().message.ends_with("12 MB"));
+
+        stack.set_do_not_disturb(true);
+        stack.push("🔔 Alert fired for curl".to_string());
+        stack.push("❌ Failed to kill curl".to_string());
+        assert_eq!(stack.len(), 4);
+        assert_eq!(stack.set_do_not_disturb(false), 1);
+    }
+}
@@ -0,0 +1,255 @@
+// Bounded per-process packet history plus the global budget that keeps the
+// sum of all histories in check on busy hosts.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime};
+use serde::Serialize;
+use crate::types::{PacketInfo, ProcessInfo, MAX_PACKET_HISTORY};
+
+/// Upper bounds of the packet size histogram's buckets; the last one holds
+/// everything above a standard Ethernet MTU (jumbo frames, offloaded segments)
+pub const SIZE_BUCKETS: [(usize, &str); 7] = [
+    (64, "≤64"),
+    (128, "≤128"),
+    (256, "≤256"),
+    (512, "≤512"),
+    (1024, "≤1K"),
+    (1514, "≤1.5K"),
+    (usize::MAX, ">1.5K"),
+];
+
+/// Estimated memory held by one recorded packet, including its cached strings
+fn packet_footprint(packet: &PacketInfo) -> usize {
+    std::mem::size_of::<PacketInfo>()
+        + packet.cached_ts.capacity()
+        + packet.cached_src.capacity()
+        + packet.cached_dst.capacity()
+        + packet.cached_proto.capacity()
+        + packet.cached_size.capacity()
+}
+
+/// Ring buffer of a process's most recent packets, oldest first
+#[derive(Clone, Default, Serialize)]
+pub struct PacketHistory {
+    packets: VecDeque<PacketInfo>,
+    bytes: usize,
+    evicted: u64, // Packets dropped to stay within the per-process cap or the global budget
+}
+
+impl PacketHistory {
+    /// Record a packet, overwriting the oldest one once `MAX_PACKET_HISTORY` is reached
+    pub fn push(&mut self, packet: PacketInfo) {
+        if self.packets.len() >= MAX_PACKET_HISTORY {
+            self.evict_oldest(1);
+        }
+        self.bytes += packet_footprint(&packet);
+        self.packets.push_back(packet);
+    }
+
+    /// Drop up to `count` of the oldest packets, returning how many were dropped
+    pub fn evict_oldest(&mut self, count: usize) -> usize {
+        let count = count.min(self.packets.len());
+        for packet in self.packets.drain(..count) {
+            self.bytes -= packet_footprint(&packet);
+        }
+        self.evicted += count as u64;
+        count
+    }
+
+    /// Drop every packet (counted as evicted) and release the buffer
+    pub fn clear(&mut self) {
+        self.evicted += self.packets.len() as u64;
+        self.packets = VecDeque::new();
+        self.bytes = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&PacketInfo> {
+        self.packets.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PacketInfo> {
+        self.packets.iter()
+    }
+
+    /// How many of the held packets fall into each of `SIZE_BUCKETS`
+    pub fn size_histogram(&self) -> [u64; SIZE_BUCKETS.len()] {
+        let mut counts = [0; SIZE_BUCKETS.len()];
+        for packet in &self.packets {
+            let bucket = SIZE_BUCKETS.iter().position(|(limit, _)| packet.size <= *limit).unwrap_or(SIZE_BUCKETS.len() - 1);
+            counts[bucket] += 1;
+        }
+        counts
+    }
+
+    /// Packets per second recorded over the `window` before `now`
+    pub fn packet_rate(&self, now: SystemTime, window: Duration) -> f64 {
+        let since = now.checked_sub(window).unwrap_or(SystemTime::UNIX_EPOCH);
+        let recent = self.packets.iter().rev().take_while(|packet| packet.timestamp >= since).count();
+        recent as f64 / window.as_secs_f64()
+    }
+
+    /// Estimated memory held by the recorded packets
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Packets dropped from this history since the process was first seen
+    pub fn evicted(&self) -> u64 {
+        self.evicted
+    }
+
+    /// Packets ever recorded, including the evicted ones; a position marker for `recorded_since`
+    pub fn recorded(&self) -> u64 {
+        self.evicted + self.packets.len() as u64
+    }
+
+    /// Packets recorded after the `recorded()` marker `mark` that are still held
+    pub fn recorded_since(&self, mark: u64) -> impl Iterator<Item = &PacketInfo> {
+        let newer = self.recorded().saturating_sub(mark).min(self.packets.len() as u64) as usize;
+        self.packets.range(self.packets.len() - newer..)
+    }
+
+    /// Evict the oldest packets until as many have been evicted as in a mirrored history
+    pub fn sync_evictions(&mut self, evicted: u64) {
+        let behind = evicted.saturating_sub(self.evicted) as usize;
+        if behind > 0 {
+            self.evict_oldest(behind);
+        }
+    }
+}
+
+impl std::ops::Index<usize> for PacketHistory {
+    type Output = PacketInfo;
+
+    fn index(&self, index: usize) -> &PacketInfo {
+        &self.packets[index]
+    }
+}
+
+/// Global cap on the packets held across all per-process histories
+#[derive(Clone, Copy)]
+pub struct PacketBudget {
+    pub max_packets: usize,
+    pub max_bytes: usize,
+}
+
+impl PacketBudget {
+    /// Once either limit is exceeded, trim the oldest packets of the largest
+    /// histories until everything fits in 90% of the budget. Returns the number
+    /// of packets evicted.
+    pub fn enforce(&self, map: &mut HashMap<i32, ProcessInfo>) -> usize {
+        let mut packets: usize = map.values().map(|p| p.packet_history.len()).sum();
+        let mut bytes: usize = map.values().map(|p| p.packet_history.bytes()).sum();
+        if packets <= self.max_packets && bytes <= self.max_bytes {
+            return 0;
+        }
+
+        let target_packets = self.max_packets / 10 * 9;
+        let target_bytes = self.max_bytes / 10 * 9;
+        let mut evicted = 0;
+        while packets > target_packets || bytes > target_bytes {
+            let Some(history) = map
+                .values_mut()
+                .map(|p| &mut p.packet_history)
+                .filter(|h| !h.is_empty())
+                .max_by_key(|h| h.bytes())
+            else {
+                break;
+            };
+            // Take an eighth of the biggest history at a time so the others get a turn
+            let before = history.bytes();
+            let count = history.evict_oldest((history.len() / 8).max(1));
+            packets -= count;
+            bytes -= before - history.bytes();
+            evicted += count;
+        }
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PacketDirection, ProcessIdentifier};
+
+    fn packet() -> PacketInfo {
+        let ip = "10.0.0.2".parse().unwrap();
+        PacketInfo {
+            timestamp: SystemTime::UNIX_EPOCH,
+            direction: PacketDirection::Sent,
+            protocol: 6,
+            src_ip: ip,
+            src_port: 40000,
+            dst_ip: ip,
+            dst_port: 443,
+            size: 60,
+            cached_ts: String::new(),
+            cached_src: String::new(),
+            cached_dst: String::new(),
+            cached_proto: String::new(),
+            cached_size: String::new(),
+        }
+    }
+
+    /// Processes holding these many packets each, keyed by their position
+    fn processes(lengths: &[usize]) -> HashMap<i32, ProcessInfo> {
+        lengths.iter().enumerate().map(|(pid, &len)| {
+            let pid = pid as i32;
+            let identifier = ProcessIdentifier { pid, name: "curl".to_string(), container_name: None, user_name: None, unit: None };
+            let mut info = ProcessInfo::new(&identifier);
+            for _ in 0..len {
+                info.packet_history.push(packet());
+            }
+            (pid, info)
+        }).collect()
+    }
+
+    fn lengths(map: &HashMap<i32, ProcessInfo>) -> Vec<usize> {
+        (0..map.len() as i32).map(|pid| map[&pid].packet_history.len()).collect()
+    }
+
+    #[test]
+    fn leaves_histories_within_budget_alone() {
+        let mut map = processes(&[60, 40]);
+        let budget = PacketBudget { max_packets: 100, max_bytes: usize::MAX };
+        assert_eq!(budget.enforce(&mut map), 0);
+        assert_eq!(lengths(&map), [60, 40]);
+    }
+
+    #[test]
+    fn trims_the_largest_history_an_eighth_at_a_time_to_ninety_percent() {
+        let mut map = processes(&[80, 40]);
+        let budget = PacketBudget { max_packets: 100, max_bytes: usize::MAX };
+        // 80 → 70 → 62 → 55 → 49, when 89 packets are left of the 90 allowed
+        assert_eq!(budget.enforce(&mut map), 31);
+        assert_eq!(lengths(&map), [49, 40]);
+        assert_eq!(map[&0].packet_history.evicted(), 31);
+    }
+
+    #[test]
+    fn enforces_the_byte_limit() {
+        let footprint = packet_footprint(&packet());
+        let mut map = processes(&[60]);
+        let budget = PacketBudget { max_packets: usize::MAX, max_bytes: 50 * footprint };
+        // 60 → 53 → 47 → 42 packets, within 45 packets' worth of bytes
+        assert_eq!(budget.enforce(&mut map), 18);
+        assert_eq!(map[&0].packet_history.bytes(), 42 * footprint);
+    }
+
+    #[test]
+    fn evicts_small_histories_one_packet_at_a_time() {
+        let mut map = processes(&[6, 0]);
+        let budget = PacketBudget { max_packets: 4, max_bytes: usize::MAX };
+        // 90% of 4 packets rounds down to none
+        assert_eq!(budget.enforce(&mut map), 6);
+        assert_eq!(lengths(&map), [0, 0]);
+    }
+}
@@ -0,0 +1,496 @@
+// Packet parsing for the capture path: from a captured frame of a given link
+// type down to the IP addresses, the transport protocol, the TCP/UDP ports
+// and the transport payload. Every header is checked against the bytes that
+// were actually captured before it is read, so truncated (small --snaplen)
+// and malformed frames come back as less information or None, never as a
+// panic or a read past the end. VLAN tags (802.1Q, and QinQ stacks of them)
+// and PPPoE sessions are looked through, and so are GRE and VXLAN tunnels: a packet carried in
+// one comes back as the inner packet, so overlay and trunk traffic is
+// attributed to the flows inside rather than to the tunnel endpoints. Only std
+// is used, which lets the fuzz targets in fuzz/ include this file as it is.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// How a capture handle frames its packets, from its datalink (DLT) number
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LinkType {
+    Ethernet,
+    /// IP packets without any link-layer header
+    Raw,
+    /// Linux cooked capture, the `any` device on older libpcap
+    LinuxSll,
+    /// Linux cooked capture v2, the `any` device on libpcap 1.10 and later
+    LinuxSll2,
+    /// BSD loopback, e.g. lo0 on macOS and Npcap's loopback adapter
+    Loopback,
+    /// PPP, with or without HDLC-like framing, e.g. ppp0 on the BSDs
+    Ppp,
+    /// PPPoE sessions without their Ethernet header
+    PppOverEthernet,
+    /// Nothing IP can be read from
+    Other(i32),
+}
+
+impl LinkType {
+    pub fn from_datalink(dlt: i32) -> Self {
+        match dlt {
+            1 => LinkType::Ethernet,
+            12 | 14 | 101 | 228 | 229 => LinkType::Raw,
+            113 => LinkType::LinuxSll,
+            276 => LinkType::LinuxSll2,
+            0 | 108 => LinkType::Loopback,
+            9 | 50 => LinkType::Ppp,
+            51 => LinkType::PppOverEthernet,
+            other => LinkType::Other(other),
+        }
+    }
+}
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+/// 802.1ad service tag, the outer tag of QinQ; 0x9100 and 0x9200 predate it
+const ETHERTYPE_QINQ: [u16; 3] = [0x88a8, 0x9100, 0x9200];
+const ETHERTYPE_PPPOE_SESSION: u16 = 0x8864;
+/// GRE carrying whole Ethernet frames (gretap)
+const ETHERTYPE_TRANSPARENT_BRIDGING: u16 = 0x6558;
+
+const IP_PROTOCOL_GRE: u8 = 47;
+const VXLAN_PORT: u16 = 4789;
+
+/// VLAN tags looked through before giving up on a frame
+const MAX_VLAN_TAGS: usize = 4;
+
+/// Tunnels inside tunnels opened before the packet is taken as it is
+const MAX_ENCAPSULATION: u8 = 4;
+
+/// IPv6 extension headers skipped before giving up on finding the transport
+const MAX_EXTENSION_HEADERS: usize = 8;
+
+/// What the capture path needs from a packet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Packet<'a> {
+    pub source_ip: IpAddr,
+    pub dest_ip: IpAddr,
+    /// IP protocol number of the transport, after any IPv6 extension headers
+    pub protocol: u8,
+    /// (source, destination) of TCP and UDP packets; None for other protocols,
+    /// later IP fragments and transport headers cut short
+    pub ports: Option<(u16, u16)>,
+    /// TCP or UDP payload, possibly cut short by the snaplen
+    pub payload: Option<&'a [u8]>,
+    /// Length of the TCP or UDP payload as the headers declare it, whether or
+    /// not all of it was captured
+    pub payload_len: Option<usize>,
+}
+
+/// IP, transport and ports of a captured frame; None when no IP header can be read
+pub fn parse(link: LinkType, frame: &[u8]) -> Option<Packet<'_>> {
+    match link {
+        LinkType::Ethernet => {
+            let ethertype = u16_at(frame, 12)?;
+            ethernet_payload(ethertype, frame.get(14..)?, 0)
+        }
+        LinkType::Raw => ip(frame, 0),
+        // Packet type (2), ARPHRD type (2), address length (2), address (8), protocol (2)
+        LinkType::LinuxSll => ethernet_payload(u16_at(frame, 14)?, frame.get(16..)?, 0),
+        // Protocol (2), reserved (2), interface index (4), ARPHRD type (2),
+        // packet type (1), address length (1), address (8)
+        LinkType::LinuxSll2 => ethernet_payload(u16_at(frame, 0)?, frame.get(20..)?, 0),
+        // The address family is in the capturing host's byte order and differs
+        // between systems; the IP version tells as much
+        LinkType::Loopback => ip(frame.get(4..)?, 0),
+        LinkType::Ppp => ppp(frame, 0),
+        LinkType::PppOverEthernet => pppoe(frame, 0),
+        LinkType::Other(_) => None,
+    }
+}
+
+fn u16_at(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*data.get(at)?, *data.get(at.checked_add(1)?)?]))
+}
+
+/// What follows an EtherType, looking through VLAN tags; `depth` counts the
+/// tunnels the packet was found in
+fn ethernet_payload(mut ethertype: u16, mut data: &[u8], depth: u8) -> Option<Packet<'_>> {
+    for _ in 0..=MAX_VLAN_TAGS {
+        match ethertype {
+            ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => return ip(data, depth),
+            ETHERTYPE_PPPOE_SESSION => return pppoe(data, depth),
+            // Tag control (2), then the EtherType of what the tag carries
+            ETHERTYPE_VLAN => {}
+            tag if ETHERTYPE_QINQ.contains(&tag) => {}
+            _ => return None,
+        }
+        ethertype = u16_at(data, 2)?;
+        data = data.get(4..)?;
+    }
+    None
+}
+
+/// An Ethernet frame carried in a tunnel
+fn inner_frame(frame: &[u8], depth: u8) -> Option<Packet<'_>> {
+    ethernet_payload(u16_at(frame, 12)?, frame.get(14..)?, depth)
+}
+
+/// A PPPoE session packet: version and type (1, both 1), code (1, 0 for
+/// session data), session ID (2), length (2), then a PPP packet
+fn pppoe(data: &[u8], depth: u8) -> Option<Packet<'_>> {
+    if data.get(..2)? != [0x11, 0x00] {
+        return None;
+    }
+    let end = (6 + u16_at(data, 4)? as usize).min(data.len());
+    ppp(data.get(6..end)?, depth)
+}
+
+/// A PPP packet: the protocol (1 byte when compressed, which leaves it odd,
+/// otherwise 2), then what it carries
+fn ppp(data: &[u8], depth: u8) -> Option<Packet<'_>> {
+    // Address and control bytes of HDLC-like framing, when they weren't left out
+    let data = data.strip_prefix(&[0xff, 0x03]).unwrap_or(data);
+    let (protocol, rest) = if data.first()? & 1 == 1 {
+        (*data.first()? as u16, data.get(1..)?)
+    } else {
+        (u16_at(data, 0)?, data.get(2..)?)
+    };
+    match protocol {
+        0x0021 | 0x0057 => ip(rest, depth),
+        _ => None,
+    }
+}
+
+fn ip(data: &[u8], depth: u8) -> Option<Packet<'_>> {
+    match data.first()? >> 4 {
+        4 => ipv4(data, depth),
+        6 => ipv6(data, depth),
+        _ => None,
+    }
+}
+
+fn ipv4(data: &[u8], depth: u8) -> Option<Packet<'_>> {
+    let header = data.get(..20)?;
+    let header_len = (header[0] & 0x0f) as usize * 4;
+    let total_len = u16_at(header, 2)? as usize;
+    if header_len < 20 || data.len() < header_len {
+        return None;
+    }
+    // Segmentation offload hands over packets with a total length of 0
+    let end = if total_len == 0 { data.len() } else { total_len.min(data.len()) };
+    if end < header_len {
+        return None;
+    }
+    let source_ip = IpAddr::V4(Ipv4Addr::new(header[12], header[13], header[14], header[15]));
+    let dest_ip = IpAddr::V4(Ipv4Addr::new(header[16], header[17], header[18], header[19]));
+    let protocol = header[9];
+    // Only the first fragment carries the transport header
+    let later_fragment = u16_at(header, 6)? & 0x1fff != 0;
+    if later_fragment {
+        return Some(Packet { source_ip, dest_ip, protocol, ports: None, payload: None, payload_len: None });
+    }
+    let segment = &data[header_len..end];
+    let declared = if total_len == 0 { segment.len() } else { total_len - header_len };
+    let Transport { ports, payload, payload_len } = match tunneled(protocol, segment, depth) {
+        Some(Some(inner)) => return Some(inner),
+        Some(None) => Transport { ports: transport(protocol, segment, declared).ports, ..Transport::default() },
+        None => transport(protocol, segment, declared),
+    };
+    Some(Packet { source_ip, dest_ip, protocol, ports, payload, payload_len })
+}
+
+/// Some(inner packet) when `segment` is a GRE packet or a VXLAN datagram,
+/// None when it isn't one. Which it is only depends on the outer headers: once
+/// a packet is taken for a tunnel, an inner packet that can't be read leaves
+/// the tunnel's endpoints without a payload rather than falling back to the
+/// outer datagram's, so cutting a packet shorter never makes more of it readable
+fn tunneled(protocol: u8, segment: &[u8], depth: u8) -> Option<Option<Packet<'_>>> {
+    if depth >= MAX_ENCAPSULATION {
+        return None;
+    }
+    match protocol {
+        IP_PROTOCOL_GRE => Some(gre(segment, depth + 1)),
+        17 => match transport(protocol, segment, segment.len()) {
+            Transport { ports: Some((_, VXLAN_PORT)), payload: Some(payload), .. } => Some(vxlan(payload, depth + 1)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn gre(segment: &[u8], depth: u8) -> Option<Packet<'_>> {
+    // Flags and version (2), protocol (2), then a checksum (4), key (4) and
+    // sequence number (4) as the flags say; version 1 is PPTP's own
+    let flags = u16_at(segment, 0)?;
+    if flags & 0x0007 != 0 {
+        return None;
+    }
+    let header_len = 4 + [0x8000, 0x2000, 0x1000].iter().filter(|flag| flags & **flag != 0).count() * 4;
+    let inner = segment.get(header_len..)?;
+    match u16_at(segment, 2)? {
+        ETHERTYPE_TRANSPARENT_BRIDGING => inner_frame(inner, depth),
+        ethertype => ethernet_payload(ethertype, inner, depth),
+    }
+}
+
+fn vxlan(payload: &[u8], depth: u8) -> Option<Packet<'_>> {
+    // Flags (1, the I bit says the network identifier is valid), reserved (3),
+    // network identifier (3), reserved (1)
+    if payload.first()? & 0x08 == 0 {
+        return None;
+    }
+    inner_frame(payload.get(8..)?, depth)
+}
+
+fn ipv6(data: &[u8], depth: u8) -> Option<Packet<'_>> {
+    let header = data.get(..40)?;
+    let payload_len = u16_at(header, 4)? as usize;
+    let source: [u8; 16] = header[8..24].try_into().ok()?;
+    let dest: [u8; 16] = header[24..40].try_into().ok()?;
+    // A payload length of 0 means a jumbogram (or offload); take what was captured
+    let end = if payload_len == 0 { data.len() } else { (40 + payload_len).min(data.len()) };
+
+    let (protocol, transport) = match upper_layer(data, header[6]) {
+        Some((protocol, at, false)) => {
+            let segment = data.get(at..end).unwrap_or_default();
+            let declared = if payload_len == 0 { segment.len() } else { (40 + payload_len).saturating_sub(at) };
+            match tunneled(protocol, segment, depth) {
+                Some(Some(inner)) => return Some(inner),
+                Some(None) => (protocol, Transport { ports: transport(protocol, segment, declared).ports, ..Transport::default() }),
+                None => (protocol, transport(protocol, segment, declared)),
+            }
+        }
+        Some((protocol, _, true)) => (protocol, Transport::default()),
+        // Extension headers cut short or too many of them
+        None => (header[6], Transport::default()),
+    };
+    Some(Packet {
+        source_ip: IpAddr::V6(Ipv6Addr::from(source)),
+        dest_ip: IpAddr::V6(Ipv6Addr::from(dest)),
+        protocol,
+        ports: transport.ports,
+        payload: transport.payload,
+        payload_len: transport.payload_len,
+    })
+}
+
+/// Protocol and offset of what follows the IPv6 extension headers, and whether
+/// the packet is a later fragment, which carries no transport header
+fn upper_layer(data: &[u8], mut protocol: u8) -> Option<(u8, usize, bool)> {
+    let mut at = 40;
+    let mut later_fragment = false;
+    for _ in 0..MAX_EXTENSION_HEADERS {
+        let header_len = match protocol {
+            // Hop-by-hop options, routing, destination options: length in 8-byte units, not counting the first
+            0 | 43 | 60 => (*data.get(at + 1)? as usize + 1) * 8,
+            // Fragment: fixed size, offset in the upper 13 bits
+            44 => {
+                later_fragment |= u16_at(data, at + 2)? >> 3 != 0;
+                8
+            }
+            // Authentication header: length in 4-byte units, not counting the first two
+            51 => (*data.get(at + 1)? as usize + 2) * 4,
+            _ => return Some((protocol, at, later_fragment)),
+        };
+        protocol = *data.get(at)?;
+        at += header_len;
+    }
+    None
+}
+
+/// What a TCP or UDP header tells
+#[derive(Default)]
+struct Transport<'a> {
+    ports: Option<(u16, u16)>,
+    payload: Option<&'a [u8]>,
+    payload_len: Option<usize>,
+}
+
+/// Ports and payload of a TCP or UDP segment, of which `segment` was captured
+/// and the IP header declares `declared` bytes; nothing unless the whole fixed
+/// header was captured
+fn transport(protocol: u8, segment: &[u8], declared: usize) -> Transport<'_> {
+    let ports = || Some((u16_at(segment, 0)?, u16_at(segment, 2)?));
+    match protocol {
+        6 if segment.len() >= 20 => {
+            let header_len = (segment[12] >> 4) as usize * 4;
+            if header_len < 20 {
+                return Transport::default();
+            }
+            Transport { ports: ports(), payload: segment.get(header_len..), payload_len: declared.checked_sub(header_len) }
+        }
+        17 if segment.len() >= 8 => {
+            // Length 0 again means offload or a jumbogram
+            let udp_len = match u16_at(segment, 4).unwrap_or(0) as usize {
+                0 => declared,
+                udp_len => udp_len,
+            };
+            let end = udp_len.min(segment.len());
+            if end < 8 {
+                return Transport::default();
+            }
+            Transport { ports: ports(), payload: Some(&segment[8..end]), payload_len: Some(udp_len - 8) }
+        }
+        _ => Transport::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use etherparse::PacketBuilder;
+
+    fn tcp_frame(payload: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        PacketBuilder::ethernet2([2, 0, 0, 0, 0, 1], [2, 0, 0, 0, 0, 2])
+            .ipv4([192, 168, 1, 10], [93, 184, 216, 34], 64)
+            .tcp(50000, 443, 1, 65535)
+            .write(&mut frame, payload)
+            .unwrap();
+        frame
+    }
+
+    fn udp6_ip(payload: &[u8]) -> Vec<u8> {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv6([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1], [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2], 64)
+            .udp(5353, 5353)
+            .write(&mut packet, payload)
+            .unwrap();
+        packet
+    }
+
+    #[test]
+    fn reads_ethernet_vlan_and_truncated_frames() {
+        let frame = tcp_frame(b"hello");
+        let packet = parse(LinkType::Ethernet, &frame).unwrap();
+        assert_eq!(packet.source_ip, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)));
+        assert_eq!((packet.protocol, packet.ports, packet.payload), (6, Some((50000, 443)), Some(&b"hello"[..])));
+
+        // An 802.1Q tag between the MAC addresses and the EtherType
+        let tagged = [&frame[..12], &[0x81, 0x00, 0x00, 0x2a], &frame[12..]].concat();
+        assert_eq!(parse(LinkType::Ethernet, &tagged).unwrap().ports, Some((50000, 443)));
+
+        // Cut short by the snaplen: the ports are there, the rest of the payload isn't
+        let truncated = parse(LinkType::Ethernet, &frame[..56]).unwrap();
+        assert_eq!((truncated.payload, truncated.payload_len), (Some(&b"he"[..]), Some(5)));
+        // Cut inside the TCP header: the addresses still count, the ports don't
+        let cut = parse(LinkType::Ethernet, &frame[..40]).unwrap();
+        assert_eq!((cut.protocol, cut.ports), (6, None));
+        for len in 0..frame.len() {
+            let _ = parse(LinkType::Ethernet, &frame[..len]);
+        }
+    }
+
+    #[test]
+    fn reads_cooked_captures_of_the_any_device() {
+        let packet = udp6_ip(b"query");
+        let mut sll = vec![0, 0, 0, 1, 0, 6, 2, 0, 0, 0, 0, 1, 0, 0, 0x86, 0xdd];
+        sll.extend_from_slice(&packet);
+        let mut sll2 = vec![0x86, 0xdd, 0, 0, 0, 0, 0, 2, 0, 1, 0, 6, 2, 0, 0, 0, 0, 1, 0, 0];
+        sll2.extend_from_slice(&packet);
+        for (link, frame) in [(LinkType::LinuxSll, &sll), (LinkType::LinuxSll2, &sll2), (LinkType::Raw, &packet)] {
+            let parsed = parse(link, frame).unwrap();
+            assert_eq!((parsed.protocol, parsed.ports, parsed.payload), (17, Some((5353, 5353)), Some(&b"query"[..])));
+        }
+        // Read as Ethernet, the cooked header is garbage rather than a guess
+        assert_eq!(parse(LinkType::Ethernet, &sll).map(|packet| packet.ports), None);
+    }
+
+    #[test]
+    fn looks_through_qinq_tags_and_tunnels() {
+        let frame = tcp_frame(b"hello");
+        let inner = |packet: Option<Packet>| packet.map(|packet| (packet.source_ip, packet.ports, packet.payload.map(<[u8]>::to_vec)));
+        let expected = inner(parse(LinkType::Ethernet, &frame));
+
+        // 802.1ad service tag outside an 802.1Q customer tag
+        let qinq = [&frame[..12], &[0x88, 0xa8, 0x00, 0x64, 0x81, 0x00, 0x00, 0x2a], &frame[12..]].concat();
+        assert_eq!(inner(parse(LinkType::Ethernet, &qinq)), expected);
+
+        // GRE with a key, carrying the IPv4 packet
+        let mut gre = Vec::new();
+        PacketBuilder::ethernet2([2, 0, 0, 0, 0, 3], [2, 0, 0, 0, 0, 4])
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2], 64)
+            .write(&mut gre, IP_PROTOCOL_GRE.into(), &[&[0x20, 0x00, 0x08, 0x00, 0, 0, 0, 7][..], &frame[14..]].concat())
+            .unwrap();
+        assert_eq!(inner(parse(LinkType::Ethernet, &gre)), expected);
+
+        // VXLAN carrying the whole Ethernet frame
+        let mut vxlan = Vec::new();
+        PacketBuilder::ethernet2([2, 0, 0, 0, 0, 3], [2, 0, 0, 0, 0, 4])
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2], 64)
+            .udp(49152, VXLAN_PORT)
+            .write(&mut vxlan, &[&[0x08, 0, 0, 0, 0, 0, 42, 0][..], &frame].concat())
+            .unwrap();
+        assert_eq!(inner(parse(LinkType::Ethernet, &vxlan)), expected);
+
+        // A VXLAN datagram whose frame was cut short is the tunnel's, without a payload
+        let broken = &vxlan[..vxlan.len() - frame.len() + 10];
+        assert_eq!(inner(parse(LinkType::Ethernet, broken)), Some((IpAddr::from([10, 0, 0, 1]), Some((49152, VXLAN_PORT)), None)));
+    }
+
+    #[test]
+    fn tunnels_cut_short_stay_tunnels() {
+        // A bare inner IPv4 header, without a transport header, in VXLAN and in GRE
+        let mut bare = Vec::new();
+        PacketBuilder::ipv4([192, 168, 0, 1], [192, 168, 0, 2], 64)
+            .write(&mut bare, 253.into(), &[])
+            .unwrap();
+        let bare_frame = [&[2, 0, 0, 0, 0, 1, 2, 0, 0, 0, 0, 2, 0x08, 0x00][..], &bare].concat();
+        let mut vxlan = Vec::new();
+        PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 64)
+            .udp(49152, VXLAN_PORT)
+            .write(&mut vxlan, &[&[0x08, 0, 0, 0, 0, 0, 42, 0][..], &bare_frame].concat())
+            .unwrap();
+        let mut gre = Vec::new();
+        PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 64)
+            .write(&mut gre, IP_PROTOCOL_GRE.into(), &[&[0x00, 0x00, 0x08, 0x00][..], &bare].concat())
+            .unwrap();
+
+        for packet in [&vxlan, &gre] {
+            let full = parse(LinkType::Raw, packet).unwrap();
+            assert_eq!((full.source_ip, full.payload), (IpAddr::from([192, 168, 0, 1]), None));
+            // What the parse_ip fuzz target checks: the shorter packet doesn't read as more
+            let shorter = parse(LinkType::Raw, &packet[..packet.len() - 1]).unwrap();
+            assert_eq!((shorter.source_ip, shorter.payload), (IpAddr::from([10, 0, 0, 1]), None));
+        }
+    }
+
+    #[test]
+    fn reads_ppp_and_pppoe_sessions() {
+        let frame = tcp_frame(b"hello");
+        let packet = &frame[14..];
+        let expected = parse(LinkType::Raw, packet).map(|packet| packet.ports);
+
+        // PPPoE session on Ethernet, and as DLT_PPP_ETHER without the Ethernet header
+        let length = (packet.len() as u16 + 2).to_be_bytes();
+        let session = [&[0x11, 0x00, 0x12, 0x34, length[0], length[1], 0x00, 0x21][..], packet].concat();
+        let pppoe = [&frame[..12], &[0x88, 0x64], &session].concat();
+        assert_eq!(parse(LinkType::Ethernet, &pppoe).map(|packet| packet.ports), expected);
+        assert_eq!(parse(LinkType::PppOverEthernet, &session).map(|packet| packet.ports), expected);
+
+        // PPP with HDLC-like framing, and with a compressed protocol field
+        let framed = [&[0xff, 0x03, 0x00, 0x21][..], packet].concat();
+        let compressed = [&[0x21][..], packet].concat();
+        for ppp in [&framed, &compressed] {
+            assert_eq!(parse(LinkType::Ppp, ppp).map(|packet| packet.ports), expected);
+        }
+        // Link control rather than IP
+        assert_eq!(parse(LinkType::Ppp, &[0xff, 0x03, 0xc0, 0x21, 1, 1, 0, 4]), None);
+    }
+
+    #[test]
+    fn skips_ipv6_extension_headers_and_later_fragments() {
+        let packet = udp6_ip(b"x");
+        // Hop-by-hop options (8 bytes) between the IPv6 header and UDP
+        let mut extended = packet[..40].to_vec();
+        extended[6] = 0;
+        extended[5] += 8;
+        extended.extend_from_slice(&[17, 0, 1, 4, 0, 0, 0, 0]);
+        extended.extend_from_slice(&packet[40..]);
+        assert_eq!(parse(LinkType::Raw, &extended).unwrap().ports, Some((5353, 5353)));
+
+        let frame = tcp_frame(b"hello");
+        let mut fragment = frame.clone();
+        fragment[14 + 7] = 0x10; // Fragment offset 16
+        let parsed = parse(LinkType::Ethernet, &fragment).unwrap();
+        assert_eq!((parsed.protocol, parsed.ports), (6, None));
+    }
+}
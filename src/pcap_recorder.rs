@@ -0,0 +1,102 @@
+// Alert-triggered packet captures. When a `CapturePcap` alert fires, the UI asks
+// the capture thread to write the offending process's packets to a pcap file
+// for a while; the thread reports the path once the recording is complete.
+
+use std::time::{Duration, Instant};
+use pcap::{Activated, Capture, Packet, Savefile};
+
+/// Default recording length when the alert editor's duration field is left empty
+pub const DEFAULT_PCAP_DURATION_SECS: u64 = 30;
+
+/// Ask the capture thread to record one process
+pub struct PcapRequest {
+    pub pid: i32,
+    pub name: String,
+    pub duration: Duration,
+}
+
+struct Recording {
+    pid: i32,
+    name: String,
+    path: String,
+    savefile: Savefile,
+    until: Instant,
+    packets: u64,
+}
+
+/// Recordings in progress, owned by the capture thread
+#[derive(Default)]
+pub struct PcapRecorder {
+    recordings: Vec<Recording>,
+}
+
+impl PcapRecorder {
+    /// Open a pcap file in the current directory for the requested process.
+    /// Returns a message for the UI when the recording can't start.
+    pub fn start<T: Activated + ?Sized>(&mut self, cap: &Capture<T>, request: PcapRequest) -> Option<String> {
+        if self.recordings.iter().any(|r| r.pid == request.pid) {
+            return Some(format!("📼 Already recording {} (PID {})", request.name, request.pid));
+        }
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let path = format!("capture_{}_{}_{}.pcap", request.name.replace(' ', "_"), request.pid, timestamp);
+        match cap.savefile(&path) {
+            Ok(savefile) => {
+                self.recordings.push(Recording {
+                    pid: request.pid,
+                    name: request.name,
+                    path,
+                    savefile,
+                    until: Instant::now() + request.duration,
+                    packets: 0,
+                });
+                None
+            }
+            Err(e) => Some(format!("❌ Could not create {} for {} (PID {}): {}", path, request.name, request.pid, e)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recordings.is_empty()
+    }
+
+    /// Write a packet attributed to `pid` to its recording, if there is one
+    pub fn record(&mut self, pid: i32, packet: &Packet) {
+        if let Some(recording) = self.recordings.iter_mut().find(|r| r.pid == pid) {
+            recording.savefile.write(packet);
+            recording.packets += 1;
+        }
+    }
+
+    /// Close recordings whose time is up, returning a message with each file's path
+    pub fn finish_expired(&mut self) -> Vec<String> {
+        let now = Instant::now();
+        self.finish_where(|r| r.until <= now)
+    }
+
+    /// Close every recording early so nothing is lost on shutdown
+    pub fn finish_all(&mut self) -> Vec<String> {
+        self.finish_where(|_| true)
+    }
+
+    fn finish_where(&mut self, done: impl Fn(&Recording) -> bool) -> Vec<String> {
+        let (done, running) = std::mem::take(&mut self.recordings)
+            .into_iter()
+            .partition(|r| done(r));
+        self.recordings = running;
+
+        done.into_iter()
+            .map(|mut recording: Recording| {
+                let path = std::env::current_dir()
+                    .map(|dir| dir.join(&recording.path).display().to_string())
+                    .unwrap_or(recording.path);
+                match recording.savefile.flush() {
+                    Ok(()) => format!(
+                        "📼 Recorded {} packets of {} (PID {}) to {}",
+                        recording.packets, recording.name, recording.pid, path
+                    ),
+                    Err(e) => format!("❌ Failed to finish {}: {}", path, e),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,116 @@
+// Running without full root on Linux. `--setup-capabilities` gives the binary
+// CAP_NET_RAW and CAP_NET_ADMIN, which is all packet capture needs, so it can
+// be started by a normal user. `--drop-privileges` gives up what is left once
+// the capture handle is open: a sudo session switches back to the invoking
+// user, a capability-enabled run clears the capabilities of the capture thread,
+// the one that parses packets off the wire.
+
+use std::path::PathBuf;
+
+/// Capabilities written to the binary
+#[cfg(target_os = "linux")]
+const CAPTURE_CAPABILITIES: &str = "cap_net_raw,cap_net_admin=eip";
+
+#[cfg(target_os = "linux")]
+const CAP_NET_ADMIN: u32 = 12;
+#[cfg(target_os = "linux")]
+const CAP_NET_RAW: u32 = 13;
+
+/// Whether this process may capture without being root
+#[cfg(target_os = "linux")]
+pub fn has_capture_capabilities() -> bool {
+    let Ok(status) = std::fs::read_to_string("/proc/self/status") else {
+        return false;
+    };
+    status.lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))
+        .and_then(|mask| u64::from_str_radix(mask.trim(), 16).ok())
+        .is_some_and(|mask| mask & (1 << CAP_NET_RAW) != 0 && mask & (1 << CAP_NET_ADMIN) != 0)
+}
+
+/// Set the capture capabilities on the running binary, through sudo unless already root
+#[cfg(target_os = "linux")]
+pub fn setup_capabilities() -> Result<PathBuf, String> {
+    use std::process::Command;
+
+    let exe = std::env::current_exe()
+        .and_then(|exe| exe.canonicalize())
+        .map_err(|e| format!("can't find the monitetoring binary: {}", e))?;
+    let mut command = if unsafe { libc::geteuid() } == 0 {
+        Command::new("setcap")
+    } else {
+        let mut sudo = Command::new("sudo");
+        sudo.arg("setcap");
+        sudo
+    };
+    let status = command.arg(CAPTURE_CAPABILITIES).arg(&exe).status()
+        .map_err(|e| format!("can't run setcap ({}); it is in libcap2-bin or libcap", e))?;
+    if !status.success() {
+        return Err(format!("setcap failed on {} ({})", exe.display(), status));
+    }
+
+    // Mounts with nosuid, and some filesystems, silently ignore file capabilities
+    let granted = Command::new("getcap").arg(&exe).output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains("cap_net_admin"))
+        .unwrap_or(true);
+    if !granted {
+        return Err(format!("setcap ran, but {} has no capabilities afterwards (nosuid mount?)", exe.display()));
+    }
+    Ok(exe)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn setup_capabilities() -> Result<PathBuf, String> {
+    Err("--setup-capabilities is only available on Linux; on Windows run as Administrator".to_string())
+}
+
+/// Give up the privileges capture needed to open its handle; describes what was dropped
+#[cfg(target_os = "linux")]
+pub fn drop_privileges() -> Result<String, String> {
+    if unsafe { libc::geteuid() } == 0 {
+        // Back to whoever ran sudo; glibc applies this to every thread
+        let id = |name: &str| std::env::var(name).ok().and_then(|id| id.parse::<u32>().ok()).filter(|id| *id != 0);
+        let (Some(uid), Some(gid)) = (id("SUDO_UID"), id("SUDO_GID")) else {
+            return Err("running as root outside sudo, so there is no user to switch to".to_string());
+        };
+        let switched = unsafe {
+            libc::setgroups(1, &gid) == 0 && libc::setgid(gid) == 0 && libc::setuid(uid) == 0
+        };
+        if !switched {
+            return Err(format!("can't switch to UID {}: {}", uid, std::io::Error::last_os_error()));
+        }
+        // setuid applies to the whole process, not only to the capture thread
+        return Ok(format!(
+            "switched to UID {} after opening the capture; other users' processes are no longer attributed \
+             or killed, and limiting or blocking a process no longer works",
+            uid
+        ));
+    }
+
+    // capset only touches the calling thread: the capture thread parses the packets
+    #[repr(C)]
+    struct CapHeader {
+        version: u32,
+        pid: i32,
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct CapData {
+        effective: u32,
+        permitted: u32,
+        inheritable: u32,
+    }
+    const CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+    let mut header = CapHeader { version: CAPABILITY_VERSION_3, pid: 0 };
+    let data = [CapData { effective: 0, permitted: 0, inheritable: 0 }; 2];
+    let result = unsafe { libc::syscall(libc::SYS_capset, &mut header as *mut CapHeader, data.as_ptr()) };
+    if result != 0 {
+        return Err(format!("can't clear capabilities: {}", std::io::Error::last_os_error()));
+    }
+    Ok("cleared the capture thread's capabilities after opening the capture".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn drop_privileges() -> Result<String, String> {
+    Err("--drop-privileges is only available on Linux".to_string())
+}
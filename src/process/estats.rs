@@ -0,0 +1,230 @@
+// Fallback for Windows hosts without a packet driver: per-process byte counts
+// from the TCP extended statistics (GetPerTcpConnectionEStats) instead of
+// captured packets. Only established TCP connections are seen, sampled once
+// per second, so the numbers are estimates: traffic of connections that open
+// and close between samples, and all UDP, is missed.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use windows_sys::Win32::Foundation::NO_ERROR;
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    GetExtendedTcpTable, GetPerTcp6ConnectionEStats, GetPerTcpConnectionEStats,
+    SetPerTcp6ConnectionEStats, SetPerTcpConnectionEStats,
+    MIB_TCP6ROW, MIB_TCP6ROW_OWNER_PID, MIB_TCP6TABLE_OWNER_PID, MIB_TCPROW_LH, MIB_TCPROW_LH_0,
+    MIB_TCPROW_OWNER_PID, MIB_TCPTABLE_OWNER_PID, MIB_TCP_STATE_ESTAB,
+    TCP_ESTATS_DATA_ROD_v0, TCP_ESTATS_DATA_RW_v0, TCP_TABLE_OWNER_PID_ALL, TcpConnectionEstatsData,
+};
+use windows_sys::Win32::Networking::WinSock::{AF_INET, AF_INET6, IN6_ADDR, IN6_ADDR_0};
+use crate::delta::{DeltaTracker, StatsDelta};
+use crate::filter::ProcessFilter;
+use crate::types::{Connection, ProcessIdentifier, ProcessInfo};
+use super::windows::{fetch_table, port_from, refresh_proc_maps, table_rows};
+
+/// Row identifying a connection to the EStats API
+#[derive(Clone, Copy)]
+enum TcpRow {
+    V4(MIB_TCPROW_LH),
+    V6(MIB_TCP6ROW),
+}
+
+impl TcpRow {
+    /// Turn on data counters for the connection; needs Administrator rights
+    fn enable_collection(&self) -> bool {
+        let rw = TCP_ESTATS_DATA_RW_v0 { EnableCollection: true };
+        let rw_ptr = &rw as *const TCP_ESTATS_DATA_RW_v0 as *const u8;
+        let rw_size = std::mem::size_of::<TCP_ESTATS_DATA_RW_v0>() as u32;
+        // SAFETY: the row and the read/write struct outlive the call, sizes match the structs
+        let status = unsafe {
+            match self {
+                TcpRow::V4(row) => SetPerTcpConnectionEStats(row, TcpConnectionEstatsData, rw_ptr, 0, rw_size, 0),
+                TcpRow::V6(row) => SetPerTcp6ConnectionEStats(row, TcpConnectionEstatsData, rw_ptr, 0, rw_size, 0),
+            }
+        };
+        status == NO_ERROR
+    }
+
+    /// Payload bytes (out, in) since collection was enabled
+    fn data_bytes(&self) -> Option<(u64, u64)> {
+        let mut rod = TCP_ESTATS_DATA_ROD_v0::default();
+        let rod_ptr = &mut rod as *mut TCP_ESTATS_DATA_ROD_v0 as *mut u8;
+        let rod_size = std::mem::size_of::<TCP_ESTATS_DATA_ROD_v0>() as u32;
+        let null = std::ptr::null_mut();
+        // SAFETY: only the read-only dynamic struct is requested, and it is sized to match
+        let status = unsafe {
+            match self {
+                TcpRow::V4(row) => GetPerTcpConnectionEStats(row, TcpConnectionEstatsData, null, 0, 0, null, 0, 0, rod_ptr, 0, rod_size),
+                TcpRow::V6(row) => GetPerTcp6ConnectionEStats(row, TcpConnectionEstatsData, null, 0, 0, null, 0, 0, rod_ptr, 0, rod_size),
+            }
+        };
+        (status == NO_ERROR).then_some((rod.DataBytesOut, rod.DataBytesIn))
+    }
+}
+
+/// Established TCP connections with their owning PID and EStats row
+fn established_connections() -> Vec<(Connection, i32, TcpRow)> {
+    let mut connections = Vec::new();
+
+    // SAFETY (both calls): buffer pointer and size come from `fetch_table`
+    if let Some(buffer) = fetch_table(|ptr, size| unsafe {
+        GetExtendedTcpTable(ptr, size, 0, AF_INET as u32, TCP_TABLE_OWNER_PID_ALL, 0)
+    }) {
+        for row in table_rows::<MIB_TCPROW_OWNER_PID>(&buffer, std::mem::offset_of!(MIB_TCPTABLE_OWNER_PID, table)) {
+            if row.dwState != MIB_TCP_STATE_ESTAB as u32 {
+                continue;
+            }
+            let conn = Connection {
+                source_ip: IpAddr::V4(Ipv4Addr::from(row.dwLocalAddr.to_ne_bytes())),
+                dest_ip: IpAddr::V4(Ipv4Addr::from(row.dwRemoteAddr.to_ne_bytes())),
+                source_port: port_from(row.dwLocalPort),
+                dest_port: port_from(row.dwRemotePort),
+                protocol: 6, // TCP
+            };
+            let estats_row = MIB_TCPROW_LH {
+                Anonymous: MIB_TCPROW_LH_0 { dwState: row.dwState },
+                dwLocalAddr: row.dwLocalAddr,
+                dwLocalPort: row.dwLocalPort,
+                dwRemoteAddr: row.dwRemoteAddr,
+                dwRemotePort: row.dwRemotePort,
+            };
+            connections.push((conn, row.dwOwningPid as i32, TcpRow::V4(estats_row)));
+        }
+    }
+
+    if let Some(buffer) = fetch_table(|ptr, size| unsafe {
+        GetExtendedTcpTable(ptr, size, 0, AF_INET6 as u32, TCP_TABLE_OWNER_PID_ALL, 0)
+    }) {
+        for row in table_rows::<MIB_TCP6ROW_OWNER_PID>(&buffer, std::mem::offset_of!(MIB_TCP6TABLE_OWNER_PID, table)) {
+            if row.dwState != MIB_TCP_STATE_ESTAB as u32 {
+                continue;
+            }
+            let conn = Connection {
+                source_ip: IpAddr::V6(Ipv6Addr::from(row.ucLocalAddr)),
+                dest_ip: IpAddr::V6(Ipv6Addr::from(row.ucRemoteAddr)),
+                source_port: port_from(row.dwLocalPort),
+                dest_port: port_from(row.dwRemotePort),
+                protocol: 6, // TCP
+            };
+            let estats_row = MIB_TCP6ROW {
+                State: MIB_TCP_STATE_ESTAB,
+                LocalAddr: IN6_ADDR { u: IN6_ADDR_0 { Byte: row.ucLocalAddr } },
+                dwLocalScopeId: row.dwLocalScopeId,
+                dwLocalPort: row.dwLocalPort,
+                RemoteAddr: IN6_ADDR { u: IN6_ADDR_0 { Byte: row.ucRemoteAddr } },
+                dwRemoteScopeId: row.dwRemoteScopeId,
+                dwRemotePort: row.dwRemotePort,
+            };
+            connections.push((conn.normalized(), row.dwOwningPid as i32, TcpRow::V6(estats_row)));
+        }
+    }
+
+    connections
+}
+
+/// Producer loop used instead of packet capture: samples every established TCP
+/// connection once per second and attributes the byte deltas to its process.
+/// In JSON mode, `json_capture` is how long to collect before the single output.
+pub fn run(tx: mpsc::Sender<StatsDelta>, filter: &mut ProcessFilter, json_capture: Option<Duration>) {
+    let mut bandwidth_map: HashMap<i32, ProcessInfo> = HashMap::new();
+    let mut tracker = DeltaTracker::default();
+    // Last (out, in) reading per connection; None when collection couldn't be enabled
+    let mut previous: HashMap<Connection, Option<(u64, u64)>> = HashMap::new();
+    let mut identifiers: HashMap<i32, ProcessIdentifier> = HashMap::new();
+    let mut last_identifier_refresh: Option<Instant> = None;
+    let start = Instant::now();
+    let tick = Duration::from_secs(1);
+
+    loop {
+        if last_identifier_refresh.is_none_or(|last| last.elapsed() > Duration::from_secs(2)) {
+            let (inode_map, _) = refresh_proc_maps(false);
+            identifiers = inode_map.into_values().map(|identifier| (identifier.pid, identifier)).collect();
+            last_identifier_refresh = Some(Instant::now());
+        }
+
+        let mut seen = HashMap::new();
+        let mut connection_counts: HashMap<i32, usize> = HashMap::new();
+        let mut new_connections: HashMap<i32, u64> = HashMap::new();
+        let mut interval_bytes: HashMap<i32, (u64, u64)> = HashMap::new();
+        for (conn, pid, row) in established_connections() {
+            // PID 0 is the System Idle Process
+            if pid == 0 {
+                continue;
+            }
+            let identifier = identifiers.get(&pid).cloned().unwrap_or_else(|| ProcessIdentifier {
+                pid,
+                name: "???".to_string(),
+                container_name: None,
+                user_name: None,
+                unit: None,
+            });
+            // Checked before collection is enabled, so filtered-out connections cost nothing
+            if !filter.matches(&identifier) {
+                continue;
+            }
+            let reading = match previous.get(&conn) {
+                Some(None) => None, // Collection was refused for this connection earlier
+                Some(Some(_)) => row.data_bytes(),
+                None => row.enable_collection().then(|| row.data_bytes()).flatten(),
+            };
+            seen.insert(conn, reading);
+            *connection_counts.entry(pid).or_default() += 1;
+            // Everything is new on the first sample, so only count from the second one
+            if !previous.is_empty() && !previous.contains_key(&conn) {
+                *new_connections.entry(pid).or_default() += 1;
+            }
+
+            let Some((out_bytes, in_bytes)) = reading else { continue };
+            // The first reading of a new connection counts in full
+            let (prev_out, prev_in) = previous.get(&conn).copied().flatten().unwrap_or((0, 0));
+            let sent = out_bytes.saturating_sub(prev_out);
+            let received = in_bytes.saturating_sub(prev_in);
+            if sent == 0 && received == 0 && !bandwidth_map.contains_key(&pid) {
+                continue;
+            }
+            let stats = bandwidth_map.entry(pid).or_insert_with(|| ProcessInfo::new(&identifier));
+            stats.sent += sent;
+            stats.received += received;
+            stats.tcp_bytes += sent + received;
+            stats.count_port(conn.source_port, conn.dest_port, sent + received);
+            match conn.source_ip {
+                IpAddr::V4(_) => stats.ipv4_bytes += sent + received,
+                IpAddr::V6(_) => stats.ipv6_bytes += sent + received,
+            }
+            if sent + received > 0 {
+                stats.last_seen = std::time::SystemTime::now();
+            }
+            let bytes = interval_bytes.entry(pid).or_default();
+            bytes.0 += sent;
+            bytes.1 += received;
+        }
+        previous = seen;
+
+        let interval = tick.as_secs_f64();
+        for (pid, stats) in bandwidth_map.iter_mut() {
+            let (sent, received) = interval_bytes.get(pid).copied().unwrap_or((0, 0));
+            stats.sent_rate = (sent as f64 / interval) as u64;
+            stats.received_rate = (received as f64 / interval) as u64;
+            stats.connection_count = connection_counts.get(pid).copied().unwrap_or(0);
+            stats.new_connection_rate = (new_connections.get(pid).copied().unwrap_or(0) as f64 / interval) as u64;
+        }
+        tracker.touch_all(&bandwidth_map);
+
+        if let Some(limit) = json_capture {
+            if start.elapsed() > limit {
+                let _ = tx.blocking_send(tracker.build(&bandwidth_map));
+                return;
+            }
+        } else {
+            match tx.try_send(tracker.build(&bandwidth_map)) {
+                Ok(_) => tracker.delivered(&bandwidth_map),
+                // Unsent changes stay pending for the next update
+                Err(mpsc::error::TrySendError::Full(_)) => {}
+                // Receiver gone; the UI has exited
+                Err(_) => return,
+            }
+        }
+
+        std::thread::sleep(tick);
+    }
+}
@@ -1,5 +1,7 @@
-use std::collections::HashMap;
-use crate::types::{Connection, ProcessIdentifier, ProcessInfo};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use crate::types::{Connection, ProcessDetails, ProcessIdentifier, ProcessInfo, ScanStats, SocketDetails, TcpSocket, TcpSocketState};
+use super::sock_diag::{self, DiagSocket};
 
 /// Check if packet capture is available (always available on Linux)
 pub fn check_packet_capture_available() -> Result<(), String> {
@@ -12,8 +14,21 @@ pub fn extract_container_name(pid: i32) -> Option<String> {
     
     if let Ok(cgroup_content) = std::fs::read_to_string(&cgroup_path) {
         for line in cgroup_content.lines() {
+            // Look for Kubernetes containers, whatever runs them (typically in the format:
+            // 0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod<uid>.slice/cri-containerd-container_id.scope)
+            if line.contains("kubepods") {
+                let scope = line.rsplit('/').next().unwrap_or(line);
+                let container_id = ["cri-containerd-", "crio-", "docker-"]
+                    .iter()
+                    .find_map(|prefix| scope.strip_prefix(prefix))
+                    .unwrap_or(scope)
+                    .trim_end_matches(".scope");
+                if container_id.len() >= 12 && container_id.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Some(format!("k8s:{}", &container_id[..12]));
+                }
+            }
             // Look for Docker containers (typically in the format: 0::/docker/container_id)
-            if line.contains("/docker/") {
+            else if line.contains("/docker/") {
                 if let Some(docker_part) = line.split("/docker/").nth(1) {
                     let container_id = docker_part.trim();
                     if container_id.len() >= 12 {
@@ -59,6 +74,22 @@ pub fn extract_container_name(pid: i32) -> Option<String> {
     None
 }
 
+/// The systemd unit a process runs in, e.g. `nginx.service` or `session-2.scope`
+pub fn extract_systemd_unit(pid: i32) -> Option<String> {
+    systemd_unit(&std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?)
+}
+
+/// The innermost service or scope in the systemd cgroup path: a user service
+/// like `user@1000.service/app.slice/dbus.service` counts as `dbus.service`
+fn systemd_unit(cgroup: &str) -> Option<String> {
+    // The unified hierarchy (cgroup v2, "0::/...") or v1's systemd controller
+    let path = cgroup.lines()
+        .find_map(|line| line.strip_prefix("0::").or_else(|| line.split_once(":name=systemd:").map(|(_, path)| path)))?;
+    path.rsplit('/')
+        .find(|segment| segment.ends_with(".service") || segment.ends_with(".scope"))
+        .map(str::to_string)
+}
+
 pub fn extract_user_name(pid: i32) -> Option<String> {
     // Read /proc/[PID]/status to get UID information
     let status_path = format!("/proc/{}/status", pid);
@@ -97,93 +128,359 @@ fn get_username_from_uid(uid: u32) -> Option<String> {
     Some(uid.to_string())
 }
 
-pub fn refresh_proc_maps(containers_mode: bool) -> (HashMap<u64, ProcessIdentifier>, HashMap<Connection, u64>) {
-    let mut inode_to_pid_map: HashMap<u64, ProcessIdentifier> = HashMap::new();
-    let mut connection_to_inode_map: HashMap<Connection, u64> = HashMap::new();
+/// Name, container and user of a process, as shown in the table
+fn process_identifier(p: &procfs::process::Process, containers_mode: bool) -> ProcessIdentifier {
+    ProcessIdentifier {
+        pid: p.pid,
+        name: p.stat().map_or_else(|_| "???".to_string(), |s| s.comm),
+        container_name: if containers_mode { extract_container_name(p.pid) } else { None },
+        user_name: extract_user_name(p.pid),
+        unit: extract_systemd_unit(p.pid),
+    }
+}
 
-    if let Ok(all_procs) = procfs::process::all_processes() {
-        for p in all_procs.flatten() {
-            let name = p.stat().map_or_else(|_| "???".to_string(), |s| s.comm);
-            let container_name = if containers_mode {
-                extract_container_name(p.pid)
-            } else {
-                None
-            };
-            let user_name = extract_user_name(p.pid);
-            
-            if let Ok(fds) = p.fd() {
-                for fd in fds {
-                    if let Ok(fd_info) = fd
-                        && let procfs::process::FDTarget::Socket(inode) = fd_info.target {
-                            inode_to_pid_map.insert(inode, ProcessIdentifier { 
-                                pid: p.pid, 
-                                name: name.clone(),
-                                container_name: container_name.clone(),
-                                user_name: user_name.clone(),
-                            });
-                        }
-                }
+/// Inodes of the sockets a process has open
+fn socket_inodes(p: &procfs::process::Process) -> Vec<u64> {
+    let Ok(fds) = p.fd() else {
+        return Vec::new();
+    };
+    fds.flatten()
+        .filter_map(|fd_info| match fd_info.target {
+            procfs::process::FDTarget::Socket(inode) => Some(inode),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every socket of one protocol (6 = TCP, 17 = UDP), IPv4 and IPv6 alike,
+/// from sock_diag. Falls back to the /proc/net text tables (without TCP_INFO)
+/// when the kernel lacks the inet_diag module. Dual-stack sockets list IPv4
+/// peers as v4-mapped addresses; `normalized()` turns those back into IPv4 so
+/// they match the captured packets.
+fn socket_table(protocol: u8, with_tcp_info: bool) -> Vec<DiagSocket> {
+    if let Ok(sockets) = sock_diag::dump(protocol, with_tcp_info) {
+        return sockets;
+    }
+    let entries: Vec<(std::net::SocketAddr, std::net::SocketAddr, u8, u32, u32, u64)> = if protocol == 6 {
+        procfs::net::tcp().unwrap_or_default()
+            .into_iter()
+            .chain(procfs::net::tcp6().unwrap_or_default())
+            .map(|e| (e.local_address, e.remote_address, e.state.to_u8(), e.rx_queue, e.tx_queue, e.inode))
+            .collect()
+    } else {
+        procfs::net::udp().unwrap_or_default()
+            .into_iter()
+            .chain(procfs::net::udp6().unwrap_or_default())
+            .map(|e| (e.local_address, e.remote_address, e.state as u8, e.rx_queue, e.tx_queue, e.inode))
+            .collect()
+    };
+    entries
+        .into_iter()
+        .map(|(local, remote, state, recv_queue, send_queue, inode)| DiagSocket {
+            conn: Connection {
+                source_ip: local.ip(),
+                dest_ip: remote.ip(),
+                source_port: local.port(),
+                dest_port: remote.port(),
+                protocol,
+            }.normalized(),
+            state,
+            inode,
+            recv_queue,
+            send_queue,
+            tcp_info: None,
+        })
+        .collect()
+}
+
+/// Every socket of one protocol with its inode
+fn socket_entries(protocol: u8) -> Vec<(Connection, u64)> {
+    socket_table(protocol, false)
+        .into_iter()
+        .map(|socket| (socket.conn, socket.inode))
+        .collect()
+}
+
+/// Refreshes after which every process is scanned again, to pick up processes
+/// that exec'd into another name and sockets handed from one process to another
+const FULL_SCAN_EVERY: u32 = 15;
+
+/// Keeps the socket-to-process maps up to date without reading the fd
+/// directory of every process on each refresh. The socket tables are re-read
+/// and diffed against the previous read; closed sockets and exited processes
+/// are dropped, and new sockets are searched for first in processes started
+/// since the last refresh, then in processes already known to own sockets, and
+/// only if still unowned in all the others.
+#[derive(Default)]
+pub struct ProcMapScanner {
+    table_inodes: HashSet<u64>,                   // Socket inodes listed in /proc/net at the last refresh
+    identifiers: HashMap<i32, ProcessIdentifier>, // Every process scanned so far
+    socket_owners: HashSet<i32>,                  // Scanned processes that own a TCP or UDP socket
+    unowned: HashSet<u64>,                        // Not found in any readable process; left alone until the next full scan
+    refreshes: u32,
+}
+
+impl ProcMapScanner {
+    /// Bring both maps up to date and report what it cost
+    pub fn refresh(
+        &mut self,
+        inode_map: &mut HashMap<u64, ProcessIdentifier>,
+        conn_map: &mut HashMap<Connection, u64>,
+        containers_mode: bool,
+    ) -> ScanStats {
+        let started = Instant::now();
+        let periodic_full = self.refreshes.is_multiple_of(FULL_SCAN_EVERY);
+        self.refreshes = self.refreshes.wrapping_add(1);
+
+        *conn_map = socket_entries(6).into_iter().chain(socket_entries(17)).collect();
+        // Inode 0 belongs to sockets without an owner (e.g. TIME_WAIT)
+        let table_inodes: HashSet<u64> = conn_map.values().copied().filter(|&inode| inode != 0).collect();
+        let added = table_inodes.difference(&self.table_inodes).count();
+        let removed = self.table_inodes.difference(&table_inodes).count();
+        self.table_inodes = table_inodes;
+
+        let running = running_pids();
+        let mut processes_scanned = 0;
+        let full = if periodic_full {
+            inode_map.clear();
+            self.identifiers.clear();
+            self.socket_owners.clear();
+            self.unowned.clear();
+            processes_scanned += self.scan(&running, inode_map, containers_mode);
+            true
+        } else {
+            inode_map.retain(|inode, _| self.table_inodes.contains(inode));
+            self.unowned.retain(|inode| self.table_inodes.contains(inode));
+            self.identifiers.retain(|pid, _| running.contains(pid));
+            self.socket_owners.retain(|pid| running.contains(pid));
+
+            let new_pids: HashSet<i32> = running.iter().copied().filter(|pid| !self.identifiers.contains_key(pid)).collect();
+            let owners: HashSet<i32> = self.socket_owners.clone();
+            let mut fallback = false;
+            if self.has_pending(inode_map) {
+                processes_scanned += self.scan(&new_pids, inode_map, containers_mode);
+            }
+            if self.has_pending(inode_map) {
+                processes_scanned += self.scan(&owners, inode_map, containers_mode);
+            }
+            if self.has_pending(inode_map) {
+                let rest: HashSet<i32> = running.iter().copied()
+                    .filter(|pid| !new_pids.contains(pid) && !owners.contains(pid))
+                    .collect();
+                processes_scanned += self.scan(&rest, inode_map, containers_mode);
+                fallback = true;
             }
+            fallback
+        };
+        if full {
+            // Whatever is still unowned belongs to nothing we can read
+            let unowned: Vec<u64> = self.table_inodes.iter().copied().filter(|inode| !inode_map.contains_key(inode)).collect();
+            self.unowned.extend(unowned);
         }
-    }
 
-    // TCP connections
-    if let Ok(tcp) = procfs::net::tcp() {
-        for entry in tcp {
-            let conn = Connection {
-                source_ip: entry.local_address.ip(),
-                dest_ip: entry.remote_address.ip(),
-                source_port: entry.local_address.port(),
-                dest_port: entry.remote_address.port(),
-                protocol: 6, // TCP
-            };
-            connection_to_inode_map.insert(conn, entry.inode);
+        ScanStats {
+            duration: started.elapsed(),
+            sockets: conn_map.len(),
+            added,
+            removed,
+            processes_scanned,
+            unowned: self.unowned.len(),
+            full,
         }
     }
 
-    // UDP connections
-    if let Ok(udp) = procfs::net::udp() {
-        for entry in udp {
-            let conn = Connection {
-                source_ip: entry.local_address.ip(),
-                dest_ip: entry.remote_address.ip(),
-                source_port: entry.local_address.port(),
-                dest_port: entry.remote_address.port(),
-                protocol: 17, // UDP
-            };
-            connection_to_inode_map.insert(conn, entry.inode);
-        }
+    /// Whether a socket in the tables has no known owner and hasn't been given up on
+    fn has_pending(&self, inode_map: &HashMap<u64, ProcessIdentifier>) -> bool {
+        self.table_inodes.iter().any(|inode| !inode_map.contains_key(inode) && !self.unowned.contains(inode))
     }
-    
-    // TCP6 connections
-    if let Ok(tcp6) = procfs::net::tcp6() {
-        for entry in tcp6 {
-            let conn = Connection {
-                source_ip: entry.local_address.ip(),
-                dest_ip: entry.remote_address.ip(),
-                source_port: entry.local_address.port(),
-                dest_port: entry.remote_address.port(),
-                protocol: 6, // TCP
-            };
-            connection_to_inode_map.insert(conn, entry.inode);
+
+    /// Read the fd directories of `pids` and attribute the listed sockets found
+    /// there. Returns how many processes were read.
+    fn scan(&mut self, pids: &HashSet<i32>, inode_map: &mut HashMap<u64, ProcessIdentifier>, containers_mode: bool) -> usize {
+        let mut scanned = 0;
+        for &pid in pids {
+            let Ok(p) = procfs::process::Process::new(pid) else { continue };
+            scanned += 1;
+            let identifier = self.identifiers.entry(pid).or_insert_with(|| process_identifier(&p, containers_mode));
+            let mut owns_sockets = false;
+            for inode in socket_inodes(&p) {
+                if self.table_inodes.contains(&inode) {
+                    owns_sockets = true;
+                    // A socket shared after fork stays with the process it was first seen in
+                    inode_map.entry(inode).or_insert_with(|| identifier.clone());
+                }
+            }
+            if owns_sockets {
+                self.socket_owners.insert(pid);
+            } else {
+                self.socket_owners.remove(&pid);
+            }
         }
+        scanned
+    }
+}
+
+/// PIDs currently listed in /proc, without reading anything about them
+fn running_pids() -> HashSet<i32> {
+    std::fs::read_dir("/proc")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Shortest gap between two targeted scans, so unknown traffic can't keep /proc busy
+const SCAN_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Attributes traffic of sockets that appeared after the last refresh of the
+/// process maps. A process that lives for less than the refresh interval
+/// would otherwise never be seen: on a lookup miss, the socket table of the
+/// packet's protocol is re-read, and only processes started since the last
+/// refresh have their fds searched for the new inodes.
+#[derive(Default)]
+pub struct NewSocketScanner {
+    known_pids: HashSet<i32>,
+    unresolved: HashSet<Connection>, // Still unknown after a scan; retried after the next refresh
+    last_scan: Option<Instant>,
+}
+
+impl NewSocketScanner {
+    /// Call right before each refresh of the maps
+    pub fn start_refresh(&mut self) {
+        self.known_pids = running_pids();
+        self.unresolved.clear();
     }
 
-    // UDP6 connections
-    if let Ok(udp6) = procfs::net::udp6() {
-        for entry in udp6 {
-            let conn = Connection {
-                source_ip: entry.local_address.ip(),
-                dest_ip: entry.remote_address.ip(),
-                source_port: entry.local_address.port(),
-                dest_port: entry.remote_address.port(),
-                protocol: 17, // UDP
-            };
-            connection_to_inode_map.insert(conn, entry.inode);
+    /// Try to attribute a connection the maps don't know. Returns true when it
+    /// can now be looked up.
+    pub fn resolve(
+        &mut self,
+        conn: &Connection,
+        inode_map: &mut HashMap<u64, ProcessIdentifier>,
+        conn_map: &mut HashMap<Connection, u64>,
+        containers_mode: bool,
+    ) -> bool {
+        if self.unresolved.contains(conn) || self.last_scan.is_some_and(|last| last.elapsed() < SCAN_INTERVAL) {
+            return false;
+        }
+        self.last_scan = Some(Instant::now());
+
+        // Sockets nobody owns yet, including ones opened during the last full refresh
+        let mut new_inodes = HashSet::new();
+        for (entry, inode) in socket_entries(conn.protocol) {
+            conn_map.insert(entry, inode);
+            if !inode_map.contains_key(&inode) {
+                new_inodes.insert(inode);
+            }
+        }
+        // Inode 0 belongs to sockets without an owner (e.g. TIME_WAIT)
+        new_inodes.remove(&0);
+        if !new_inodes.is_empty() {
+            for pid in running_pids().difference(&self.known_pids) {
+                let Ok(p) = procfs::process::Process::new(*pid) else { continue };
+                let inodes = socket_inodes(&p);
+                if inodes.iter().any(|inode| new_inodes.contains(inode)) {
+                    let identifier = process_identifier(&p, containers_mode);
+                    for inode in inodes {
+                        inode_map.insert(inode, identifier.clone());
+                    }
+                }
+            }
+        }
+
+        let resolved = crate::capture::lookup_socket(conn_map, conn)
+            .is_some_and(|(_, inode)| inode_map.contains_key(&inode));
+        if !resolved {
+            self.unresolved.insert(*conn);
         }
+        resolved
+    }
+}
+
+fn socket_state(state: &procfs::net::TcpState) -> TcpSocketState {
+    use procfs::net::TcpState;
+    match state {
+        TcpState::Established => TcpSocketState::Established,
+        TcpState::SynSent => TcpSocketState::SynSent,
+        TcpState::SynRecv | TcpState::NewSynRecv => TcpSocketState::SynRecv,
+        TcpState::FinWait1 | TcpState::FinWait2 => TcpSocketState::FinWait,
+        TcpState::TimeWait => TcpSocketState::TimeWait,
+        TcpState::Close => TcpSocketState::Close,
+        TcpState::CloseWait => TcpSocketState::CloseWait,
+        TcpState::LastAck => TcpSocketState::LastAck,
+        TcpState::Listen => TcpSocketState::Listen,
+        TcpState::Closing => TcpSocketState::Closing,
     }
+}
+
+/// Kernel TCP state number, as sock_diag and /proc/net report it
+fn tcp_state(state: u8) -> Option<TcpSocketState> {
+    procfs::net::TcpState::from_u8(state).map(|state| socket_state(&state))
+}
 
-    (inode_to_pid_map, connection_to_inode_map)
+/// Every TCP socket with its state, owning PID and TCP_INFO counters.
+/// TIME_WAIT sockets are detached from their process (inode 0), so their
+/// owner is `None`.
+pub fn read_tcp_sockets(inode_map: &HashMap<u64, ProcessIdentifier>) -> Vec<TcpSocket> {
+    socket_table(6, true)
+        .into_iter()
+        .filter_map(|socket| Some(TcpSocket {
+            conn: socket.conn,
+            state: tcp_state(socket.state)?,
+            owner: inode_map.get(&socket.inode).map(|identifier| identifier.pid),
+            sample: socket.tcp_info,
+        }))
+        .collect()
+}
+
+/// Every TCP and UDP socket a process has open, with queue sizes and, for TCP,
+/// round-trip time and retransmits
+pub fn read_process_sockets(pid: i32) -> Result<Vec<SocketDetails>, String> {
+    let process = procfs::process::Process::new(pid).map_err(|e| format!("Cannot read process {}: {}", pid, e))?;
+    let inodes: HashSet<u64> = socket_inodes(&process).into_iter().collect();
+    let mut sockets: Vec<SocketDetails> = socket_table(6, true)
+        .into_iter()
+        .chain(socket_table(17, false))
+        .filter(|socket| inodes.contains(&socket.inode))
+        .map(|socket| SocketDetails {
+            protocol: socket.conn.protocol,
+            local: std::net::SocketAddr::new(socket.conn.source_ip, socket.conn.source_port),
+            remote: std::net::SocketAddr::new(socket.conn.dest_ip, socket.conn.dest_port),
+            state: if socket.conn.protocol == 6 { tcp_state(socket.state) } else { None },
+            recv_queue: Some(socket.recv_queue),
+            send_queue: Some(socket.send_queue),
+            rtt_ms: socket.tcp_info.map(|info| info.rtt_us as f64 / 1000.0),
+            retrans: socket.tcp_info.map(|info| info.total_retrans),
+        })
+        .collect();
+    sockets.sort_by_key(|socket| (socket.protocol, socket.local.port(), socket.remote));
+    Ok(sockets)
+}
+
+/// Read the command line, executable, working directory, start time and open
+/// socket count of a process; fields the process hides from us are left empty
+pub fn read_process_details(pid: i32) -> ProcessDetails {
+    use procfs::WithCurrentSystemInfo;
+
+    let mut details = ProcessDetails { pid, ..Default::default() };
+    let Ok(process) = procfs::process::Process::new(pid) else {
+        return details;
+    };
+    details.cmdline = process.cmdline().ok()
+        .filter(|args| !args.is_empty())
+        .map(|args| args.join(" "));
+    details.exe = process.exe().ok().map(|path| path.display().to_string());
+    details.cwd = process.cwd().ok().map(|path| path.display().to_string());
+    details.start_time = process.stat().ok().and_then(|stat| stat.starttime().get().ok());
+    details.socket_count = process.fd().ok().map(|fds| {
+        fds.flatten()
+            .filter(|fd| matches!(fd.target, procfs::process::FDTarget::Socket(_)))
+            .count()
+    });
+    details
 }
 
 /// Check if a process with the given PID is still alive
@@ -193,46 +490,50 @@ pub fn is_process_alive(pid: i32) -> bool {
     std::fs::metadata(&stat_path).is_ok()
 }
 
-/// Clean up dead processes from the stats HashMap
-/// Returns a vector of PIDs that were removed
-pub fn cleanup_dead_processes(stats: &mut HashMap<i32, ProcessInfo>, killed_processes: &std::collections::HashSet<i32>) -> Vec<i32> {
-    let mut removed_pids = Vec::new();
-    
-    // Collect PIDs to remove (processes that are dead and not in killed_processes)
-    let pids_to_remove: Vec<i32> = stats.keys()
+/// Parent PID of a running process, 0 for the top of the tree; None once it has exited
+pub fn parent_pid(pid: i32) -> Option<i32> {
+    let stat = procfs::process::Process::new(pid).ok()?.stat().ok()?;
+    Some(stat.ppid)
+}
+
+/// PIDs in the stats HashMap whose process has exited; the caller decides
+/// how long to keep showing them
+pub fn find_exited_processes(stats: &HashMap<i32, ProcessInfo>, killed_processes: &std::collections::HashSet<i32>) -> Vec<i32> {
+    stats.keys()
         .filter(|&pid| {
-            // Don't remove processes that were intentionally killed by the tool
+            // Processes that were intentionally killed by the tool are handled separately
             if killed_processes.contains(pid) {
                 return false;
             }
-            // Remove if the process is no longer alive
             !is_process_alive(*pid)
         })
         .cloned()
-        .collect();
-    
-    // Remove dead processes and track what was removed
-    for pid in pids_to_remove {
-        if stats.remove(&pid).is_some() {
-            removed_pids.push(pid);
-        }
-    }
-    
-    removed_pids
+        .collect()
 }
 
-/// Validate if a process should be tracked (alive and not in exclusion sets)
-pub fn should_track_process(pid: i32, killed_processes: &std::collections::HashSet<i32>, dead_processes_cache: &std::collections::HashSet<i32>) -> bool {
-    // Don't track killed processes
-    if killed_processes.contains(&pid) {
-        return false;
-    }
-    
-    // Don't track processes we know are dead
-    if dead_processes_cache.contains(&pid) {
-        return false;
+/// Validate if a process should be tracked (not in exclusion sets). A process
+/// that already exited is still tracked until the periodic cleanup has seen it,
+/// so the traffic of short-lived processes shows up.
+pub fn should_track_process(pid: i32, killed_processes: &HashSet<i32>, dead_processes_cache: &HashSet<i32>) -> bool {
+    !killed_processes.contains(&pid) && !dead_processes_cache.contains(&pid)
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_innermost_systemd_unit() {
+        assert_eq!(systemd_unit("0::/system.slice/nginx.service\n").as_deref(), Some("nginx.service"));
+        assert_eq!(
+            systemd_unit("0::/user.slice/user-1000.slice/user@1000.service/app.slice/dbus.service").as_deref(),
+            Some("dbus.service")
+        );
+        assert_eq!(
+            systemd_unit("12:pids:/user.slice\n1:name=systemd:/user.slice/user-1000.slice/session-2.scope\n").as_deref(),
+            Some("session-2.scope")
+        );
+        // Kernel threads and processes outside any unit
+        assert_eq!(systemd_unit("0::/\n"), None);
+        assert_eq!(systemd_unit("0::/user.slice\n"), None);
     }
-    
-    // Final check: is the process actually alive?
-    is_process_alive(pid)
-} 
\ No newline at end of file
+}
@@ -2,11 +2,15 @@
 mod linux;
 #[cfg(target_os = "linux")]
 pub use linux::*;
+#[cfg(target_os = "linux")]
+mod sock_diag;
 
 #[cfg(target_os = "windows")]
 mod windows;
 #[cfg(target_os = "windows")]
 pub use windows::*;
+#[cfg(target_os = "windows")]
+pub mod estats;
 
 #[cfg(not(any(target_os = "linux", target_os = "windows")))]
 compile_error!("This platform is not supported yet"); 
\ No newline at end of file
@@ -0,0 +1,317 @@
+// Socket enumeration over NETLINK_SOCK_DIAG (inet_diag): one binary dump per
+// address family and protocol instead of parsing the /proc/net text tables,
+// which also carries the queue sizes and TCP_INFO those tables lack.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use crate::types::{Connection, TcpSample};
+
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+const INET_DIAG_INFO: u16 = 2;
+const HEADER_LEN: usize = std::mem::size_of::<libc::nlmsghdr>();
+const DIAG_MSG_LEN: usize = 72; // struct inet_diag_msg
+const RECV_BUFFER: usize = 64 * 1024;
+
+#[repr(C)]
+struct InetDiagSockId {
+    sport: [u8; 2], // Network byte order
+    dport: [u8; 2],
+    src: [u8; 16],  // IPv4 addresses use the first 4 bytes
+    dst: [u8; 16],
+    interface: u32,
+    cookie: [u32; 2],
+}
+
+#[repr(C)]
+struct InetDiagReqV2 {
+    family: u8,
+    protocol: u8,
+    ext: u8, // Bitmask of extra attributes wanted, 1 << (attribute - 1)
+    pad: u8,
+    states: u32, // Bitmask of TCP states to dump
+    id: InetDiagSockId,
+}
+
+#[repr(C)]
+struct DumpRequest {
+    header: libc::nlmsghdr,
+    body: InetDiagReqV2,
+}
+
+/// One socket as reported by the kernel
+pub struct DiagSocket {
+    pub conn: Connection, // Normalized, like the /proc/net entries
+    pub state: u8,        // Kernel TCP state number (1 = ESTABLISHED ... 12 = NEW_SYN_RECV)
+    pub inode: u64,
+    pub recv_queue: u32,  // Unread bytes; pending connections for listeners
+    pub send_queue: u32,  // Unacknowledged bytes; backlog size for listeners
+    pub tcp_info: Option<TcpSample>,
+}
+
+/// Every socket of one protocol (6 = TCP, 17 = UDP), IPv4 and IPv6. TCP_INFO is
+/// only requested when `with_tcp_info` is set, since it makes the dump larger.
+pub fn dump(protocol: u8, with_tcp_info: bool) -> io::Result<Vec<DiagSocket>> {
+    let mut sockets = dump_family(libc::AF_INET as u8, protocol, with_tcp_info)?;
+    sockets.extend(dump_family(libc::AF_INET6 as u8, protocol, with_tcp_info)?);
+    Ok(sockets)
+}
+
+fn dump_family(family: u8, protocol: u8, with_tcp_info: bool) -> io::Result<Vec<DiagSocket>> {
+    // SAFETY: plain socket creation; the descriptor is owned right away so it is closed on every path
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_SOCK_DIAG) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let request = DumpRequest {
+        header: libc::nlmsghdr {
+            nlmsg_len: std::mem::size_of::<DumpRequest>() as u32,
+            nlmsg_type: SOCK_DIAG_BY_FAMILY,
+            nlmsg_flags: (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16,
+            nlmsg_seq: 1,
+            nlmsg_pid: 0,
+        },
+        body: InetDiagReqV2 {
+            family,
+            protocol,
+            ext: if with_tcp_info { 1 << (INET_DIAG_INFO - 1) } else { 0 },
+            pad: 0,
+            states: u32::MAX,
+            id: InetDiagSockId { sport: [0; 2], dport: [0; 2], src: [0; 16], dst: [0; 16], interface: 0, cookie: [0; 2] },
+        },
+    };
+    // SAFETY: an all-zero sockaddr_nl addresses the kernel
+    let mut kernel: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    kernel.nl_family = libc::AF_NETLINK as u16;
+    // SAFETY: request and address are valid for the lengths given
+    let sent = unsafe {
+        libc::sendto(
+            socket.as_raw_fd(),
+            (&request as *const DumpRequest).cast(),
+            std::mem::size_of::<DumpRequest>(),
+            0,
+            (&kernel as *const libc::sockaddr_nl).cast(),
+            std::mem::size_of::<libc::sockaddr_nl>() as u32,
+        )
+    };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut sockets = Vec::new();
+    let mut buffer = vec![0u8; RECV_BUFFER];
+    loop {
+        // SAFETY: the buffer is valid for its whole length
+        let received = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr().cast(), buffer.len(), 0) };
+        if received < 0 {
+            let error = io::Error::last_os_error();
+            if error.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(error);
+        }
+        if received == 0 {
+            return Ok(sockets);
+        }
+        if parse_messages(&buffer[..received as usize], protocol, &mut sockets)? {
+            return Ok(sockets);
+        }
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_ne_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Netlink messages and attributes are padded to 4 bytes
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// Collect the sockets of one received datagram. Returns true once the dump is complete.
+fn parse_messages(data: &[u8], protocol: u8, sockets: &mut Vec<DiagSocket>) -> io::Result<bool> {
+    let mut offset = 0;
+    while let (Some(len), Some(kind)) = (u32_at(data, offset), u16_at(data, offset + 4)) {
+        let len = len as usize;
+        if len < HEADER_LEN || offset + len > data.len() {
+            break;
+        }
+        match kind as i32 {
+            libc::NLMSG_DONE => return Ok(true),
+            libc::NLMSG_ERROR => {
+                let code = u32_at(data, offset + HEADER_LEN).unwrap_or(0) as i32;
+                return Err(io::Error::from_raw_os_error(-code));
+            }
+            _ => {
+                if let Some(socket) = parse_socket(&data[offset + HEADER_LEN..offset + len], protocol) {
+                    sockets.push(socket);
+                }
+            }
+        }
+        offset += align(len);
+    }
+    Ok(false)
+}
+
+/// Decode one inet_diag_msg and its attributes
+fn parse_socket(message: &[u8], protocol: u8) -> Option<DiagSocket> {
+    if message.len() < DIAG_MSG_LEN {
+        return None;
+    }
+    let family = message[0];
+    let address = |offset: usize| -> Option<IpAddr> {
+        if family == libc::AF_INET as u8 {
+            let bytes: [u8; 4] = message.get(offset..offset + 4)?.try_into().ok()?;
+            Some(IpAddr::V4(Ipv4Addr::from(bytes)))
+        } else {
+            let bytes: [u8; 16] = message.get(offset..offset + 16)?.try_into().ok()?;
+            Some(IpAddr::V6(Ipv6Addr::from(bytes)))
+        }
+    };
+    let conn = Connection {
+        source_ip: address(8)?,
+        dest_ip: address(24)?,
+        source_port: u16::from_be_bytes([message[4], message[5]]),
+        dest_port: u16::from_be_bytes([message[6], message[7]]),
+        protocol,
+    };
+
+    let mut tcp_info = None;
+    let mut offset = DIAG_MSG_LEN;
+    while let (Some(len), Some(kind)) = (u16_at(message, offset), u16_at(message, offset + 2)) {
+        let len = len as usize;
+        if len < 4 || offset + len > message.len() {
+            break;
+        }
+        if kind == INET_DIAG_INFO {
+            tcp_info = parse_tcp_info(&message[offset + 4..offset + len]);
+        }
+        offset += align(len);
+    }
+
+    Some(DiagSocket {
+        conn: conn.normalized(),
+        state: message[1],
+        inode: u32_at(message, 68)? as u64,
+        recv_queue: u32_at(message, 56)?,
+        send_queue: u32_at(message, 60)?,
+        tcp_info,
+    })
+}
+
+/// Read struct tcp_info by offset; older kernels send a shorter struct, so
+/// only the fields that have been there from the start are required
+fn parse_tcp_info(info: &[u8]) -> Option<TcpSample> {
+    Some(TcpSample {
+        rtt_us: u32_at(info, 68)?,
+        total_retrans: u32_at(info, 100)?,
+        segs_out: u32_at(info, 136).unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(kind: u16, body: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(((HEADER_LEN + body.len()) as u32).to_ne_bytes());
+        data.extend(kind.to_ne_bytes());
+        data.extend([0; HEADER_LEN - 6]);
+        data.extend(body);
+        data.resize(align(data.len()), 0);
+        data
+    }
+
+    fn diag_msg(family: u8, source: &[u8], dest: &[u8], info: Option<&[u8]>) -> Vec<u8> {
+        let mut body = vec![0; DIAG_MSG_LEN];
+        body[0] = family;
+        body[1] = 1; // ESTABLISHED
+        body[4..6].copy_from_slice(&443u16.to_be_bytes());
+        body[6..8].copy_from_slice(&51000u16.to_be_bytes());
+        body[8..8 + source.len()].copy_from_slice(source);
+        body[24..24 + dest.len()].copy_from_slice(dest);
+        body[56..60].copy_from_slice(&7u32.to_ne_bytes());
+        body[60..64].copy_from_slice(&1500u32.to_ne_bytes());
+        body[68..72].copy_from_slice(&4242u32.to_ne_bytes());
+        if let Some(info) = info {
+            body.extend(((4 + info.len()) as u16).to_ne_bytes());
+            body.extend(INET_DIAG_INFO.to_ne_bytes());
+            body.extend(info);
+        }
+        body
+    }
+
+    fn tcp_info(len: usize) -> Vec<u8> {
+        let mut info = vec![0; len];
+        for (offset, value) in [(68, 2500u32), (100, 3), (136, 90)] {
+            if offset + 4 <= len {
+                info[offset..offset + 4].copy_from_slice(&value.to_ne_bytes());
+            }
+        }
+        info
+    }
+
+    #[test]
+    fn parses_a_dump() {
+        let mut data = message(SOCK_DIAG_BY_FAMILY, &diag_msg(libc::AF_INET as u8, &[10, 0, 0, 2], &[93, 184, 216, 34], Some(&tcp_info(232))));
+        data.extend(message(libc::NLMSG_DONE as u16, &[0; 4]));
+        let mut sockets = Vec::new();
+        assert!(parse_messages(&data, 6, &mut sockets).unwrap());
+
+        assert_eq!(sockets.len(), 1);
+        let socket = &sockets[0];
+        assert_eq!(socket.conn, Connection {
+            source_ip: "10.0.0.2".parse().unwrap(),
+            dest_ip: "93.184.216.34".parse().unwrap(),
+            source_port: 443,
+            dest_port: 51000,
+            protocol: 6,
+        });
+        assert_eq!((socket.state, socket.inode, socket.recv_queue, socket.send_queue), (1, 4242, 7, 1500));
+        let info = socket.tcp_info.unwrap();
+        assert_eq!((info.rtt_us, info.total_retrans, info.segs_out), (2500, 3, 90));
+    }
+
+    #[test]
+    fn unmaps_ipv6_and_tolerates_old_tcp_info() {
+        let mapped: Ipv6Addr = "::ffff:192.168.1.5".parse().unwrap();
+        let remote: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        // A pre-4.2 kernel's tcp_info stops before tcpi_segs_out
+        let data = message(SOCK_DIAG_BY_FAMILY, &diag_msg(libc::AF_INET6 as u8, &mapped.octets(), &remote.octets(), Some(&tcp_info(104))));
+        let mut sockets = Vec::new();
+        assert!(!parse_messages(&data, 6, &mut sockets).unwrap());
+
+        assert_eq!(sockets[0].conn.source_ip, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 5)));
+        assert_eq!(sockets[0].conn.dest_ip, IpAddr::V6(remote));
+        let info = sockets[0].tcp_info.unwrap();
+        assert_eq!((info.rtt_us, info.total_retrans, info.segs_out), (2500, 3, 0));
+
+        let too_short = message(SOCK_DIAG_BY_FAMILY, &diag_msg(libc::AF_INET6 as u8, &mapped.octets(), &remote.octets(), Some(&tcp_info(80))));
+        let mut sockets = Vec::new();
+        parse_messages(&too_short, 6, &mut sockets).unwrap();
+        assert!(sockets[0].tcp_info.is_none());
+    }
+
+    #[test]
+    fn reports_errors_and_skips_truncated_messages() {
+        let data = message(libc::NLMSG_ERROR as u16, &(-libc::EPERM).to_ne_bytes());
+        let error = parse_messages(&data, 6, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(libc::EPERM));
+
+        let mut data = message(SOCK_DIAG_BY_FAMILY, &diag_msg(libc::AF_INET as u8, &[10, 0, 0, 2], &[10, 0, 0, 1], None));
+        let complete = data.len();
+        data.truncate(complete - 8);
+        let mut sockets = Vec::new();
+        assert!(!parse_messages(&data, 17, &mut sockets).unwrap());
+        assert!(sockets.is_empty());
+        // A diag message shorter than inet_diag_msg is dropped, not misread
+        assert!(parse_socket(&[0; DIAG_MSG_LEN - 1], 6).is_none());
+    }
+}
@@ -1,6 +1,19 @@
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
-use crate::types::{Connection, ProcessIdentifier, ProcessInfo};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use std::ffi::c_void;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use windows_sys::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, NO_ERROR};
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    GetExtendedTcpTable, GetExtendedUdpTable,
+    MIB_TCP6ROW_OWNER_PID, MIB_TCP6TABLE_OWNER_PID, MIB_TCPROW_OWNER_PID, MIB_TCPTABLE_OWNER_PID,
+    MIB_UDP6ROW_OWNER_PID, MIB_UDP6TABLE_OWNER_PID, MIB_UDPROW_OWNER_PID, MIB_UDPTABLE_OWNER_PID,
+    TCP_TABLE_OWNER_PID_ALL, UDP_TABLE_OWNER_PID,
+};
+use windows_sys::Win32::Networking::WinSock::{AF_INET, AF_INET6};
+use crate::types::{Connection, ProcessDetails, ProcessIdentifier, ProcessInfo, ScanStats, SocketDetails, TcpSocket, TcpSocketState};
+
+pub mod control;
+mod identity;
 
 /// Check if Npcap or WinPcap is installed and available
 pub fn check_packet_capture_available() -> Result<(), String> {
@@ -33,8 +46,7 @@ pub fn check_packet_capture_available() -> Result<(), String> {
         }
     }
     
-    Err(format!(
-        "❌ Packet capture driver not found!\n\n\
+    Err("❌ Packet capture driver not found!\n\n\
         Monitetoring requires Npcap or WinPcap to capture network packets.\n\n\
         Please install Npcap from: https://npcap.com/\n\
         \n\
@@ -43,128 +55,383 @@ pub fn check_packet_capture_available() -> Result<(), String> {
         2. Run this tool as Administrator\n\
         \n\
         Note: Npcap is free and safe - it's the standard packet capture\n\
-        library used by Wireshark and many other network tools."
-    ))
+        library used by Wireshark and many other network tools.".to_string())
 }
 
-// Synthetic inode counter for Windows (since Windows doesn't have socket inodes)
-static SYNTHETIC_INODE: AtomicU64 = AtomicU64::new(1);
-
-pub fn extract_container_name(_pid: i32) -> Option<String> {
-    // Container awareness is not supported on Windows yet
+/// Call one of the `GetExtended*Table` functions, growing the buffer until the table fits.
+/// The buffer is `u64`-backed so the row structs inside it are properly aligned.
+pub(super) fn fetch_table(fetch: impl Fn(*mut c_void, *mut u32) -> u32) -> Option<Vec<u64>> {
+    let mut size: u32 = 0;
+    let mut buffer: Vec<u64> = Vec::new();
+    // Connections can appear between the size query and the real call, so retry a few times
+    for _ in 0..5 {
+        match fetch(buffer.as_mut_ptr() as *mut c_void, &mut size) {
+            NO_ERROR => return Some(buffer),
+            ERROR_INSUFFICIENT_BUFFER => buffer.resize((size as usize).div_ceil(8), 0),
+            _ => return None,
+        }
+    }
     None
 }
 
-pub fn extract_user_name(_pid: i32) -> Option<String> {
-    // Username extraction not implemented yet for Windows
-    // Could be added later using Windows APIs or sysinfo
-    None
+/// Copy the rows out of a `MIB_*TABLE_OWNER_PID` buffer: a `u32` entry count
+/// followed by the row array starting at `rows_offset`
+pub(super) fn table_rows<Row: Copy>(buffer: &[u64], rows_offset: usize) -> Vec<Row> {
+    let bytes = std::mem::size_of_val(buffer);
+    if bytes < rows_offset {
+        return Vec::new();
+    }
+    // SAFETY: the buffer holds at least the header, and the entry count is clamped
+    // to the rows that actually fit in the buffer
+    unsafe {
+        let base = buffer.as_ptr() as *const u8;
+        let count = *(base as *const u32) as usize;
+        let count = count.min((bytes - rows_offset) / std::mem::size_of::<Row>());
+        std::slice::from_raw_parts(base.add(rows_offset) as *const Row, count).to_vec()
+    }
+}
+
+/// Port fields hold a network-order u16 in their low bytes
+pub(super) fn port_from(raw: u32) -> u16 {
+    u16::from_be(raw as u16)
+}
+
+/// All TCP and UDP sockets (IPv4 and IPv6) with their owning PIDs, via the IP Helper API
+fn socket_table() -> Vec<(Connection, u32)> {
+    let mut sockets = Vec::new();
+
+    // SAFETY (all calls below): buffer pointer and size come from `fetch_table`,
+    // which sizes the buffer according to what the API reports
+    if let Some(buffer) = fetch_table(|ptr, size| unsafe {
+        GetExtendedTcpTable(ptr, size, 0, AF_INET as u32, TCP_TABLE_OWNER_PID_ALL, 0)
+    }) {
+        for row in table_rows::<MIB_TCPROW_OWNER_PID>(&buffer, std::mem::offset_of!(MIB_TCPTABLE_OWNER_PID, table)) {
+            sockets.push((Connection {
+                source_ip: IpAddr::V4(Ipv4Addr::from(row.dwLocalAddr.to_ne_bytes())),
+                dest_ip: IpAddr::V4(Ipv4Addr::from(row.dwRemoteAddr.to_ne_bytes())),
+                source_port: port_from(row.dwLocalPort),
+                dest_port: port_from(row.dwRemotePort),
+                protocol: 6, // TCP
+            }, row.dwOwningPid));
+        }
+    }
+
+    if let Some(buffer) = fetch_table(|ptr, size| unsafe {
+        GetExtendedTcpTable(ptr, size, 0, AF_INET6 as u32, TCP_TABLE_OWNER_PID_ALL, 0)
+    }) {
+        for row in table_rows::<MIB_TCP6ROW_OWNER_PID>(&buffer, std::mem::offset_of!(MIB_TCP6TABLE_OWNER_PID, table)) {
+            sockets.push((Connection {
+                source_ip: IpAddr::V6(Ipv6Addr::from(row.ucLocalAddr)),
+                dest_ip: IpAddr::V6(Ipv6Addr::from(row.ucRemoteAddr)),
+                source_port: port_from(row.dwLocalPort),
+                dest_port: port_from(row.dwRemotePort),
+                protocol: 6, // TCP
+            }, row.dwOwningPid));
+        }
+    }
+
+    // UDP sockets have no remote end, matching how unconnected sockets appear on Linux
+    if let Some(buffer) = fetch_table(|ptr, size| unsafe {
+        GetExtendedUdpTable(ptr, size, 0, AF_INET as u32, UDP_TABLE_OWNER_PID, 0)
+    }) {
+        for row in table_rows::<MIB_UDPROW_OWNER_PID>(&buffer, std::mem::offset_of!(MIB_UDPTABLE_OWNER_PID, table)) {
+            sockets.push((Connection {
+                source_ip: IpAddr::V4(Ipv4Addr::from(row.dwLocalAddr.to_ne_bytes())),
+                dest_ip: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                source_port: port_from(row.dwLocalPort),
+                dest_port: 0,
+                protocol: 17, // UDP
+            }, row.dwOwningPid));
+        }
+    }
+
+    if let Some(buffer) = fetch_table(|ptr, size| unsafe {
+        GetExtendedUdpTable(ptr, size, 0, AF_INET6 as u32, UDP_TABLE_OWNER_PID, 0)
+    }) {
+        for row in table_rows::<MIB_UDP6ROW_OWNER_PID>(&buffer, std::mem::offset_of!(MIB_UDP6TABLE_OWNER_PID, table)) {
+            sockets.push((Connection {
+                source_ip: IpAddr::V6(Ipv6Addr::from(row.ucLocalAddr)),
+                dest_ip: IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+                source_port: port_from(row.dwLocalPort),
+                dest_port: 0,
+                protocol: 17, // UDP
+            }, row.dwOwningPid));
+        }
+    }
+
+    sockets
+}
+
+/// MIB_TCP_STATE values reported in `dwState`
+fn socket_state(state: u32) -> Option<TcpSocketState> {
+    match state {
+        1 => Some(TcpSocketState::Close),
+        2 => Some(TcpSocketState::Listen),
+        3 => Some(TcpSocketState::SynSent),
+        4 => Some(TcpSocketState::SynRecv),
+        5 => Some(TcpSocketState::Established),
+        6 | 7 => Some(TcpSocketState::FinWait),
+        8 => Some(TcpSocketState::CloseWait),
+        9 => Some(TcpSocketState::Closing),
+        10 => Some(TcpSocketState::LastAck),
+        11 => Some(TcpSocketState::TimeWait),
+        _ => None, // 12 is DELETE_TCB, a socket being torn down
+    }
+}
+
+/// Every TCP socket with its state and owning PID. TIME_WAIT sockets are
+/// reported as owned by PID 0, so their owner is `None`.
+pub fn read_tcp_sockets(_inode_map: &HashMap<u64, ProcessIdentifier>) -> Vec<TcpSocket> {
+    let mut sockets = Vec::new();
+
+    // SAFETY (both calls): buffer pointer and size come from `fetch_table`
+    if let Some(buffer) = fetch_table(|ptr, size| unsafe {
+        GetExtendedTcpTable(ptr, size, 0, AF_INET as u32, TCP_TABLE_OWNER_PID_ALL, 0)
+    }) {
+        for row in table_rows::<MIB_TCPROW_OWNER_PID>(&buffer, std::mem::offset_of!(MIB_TCPTABLE_OWNER_PID, table)) {
+            let Some(state) = socket_state(row.dwState) else { continue };
+            sockets.push(TcpSocket {
+                conn: Connection {
+                    source_ip: IpAddr::V4(Ipv4Addr::from(row.dwLocalAddr.to_ne_bytes())),
+                    dest_ip: IpAddr::V4(Ipv4Addr::from(row.dwRemoteAddr.to_ne_bytes())),
+                    source_port: port_from(row.dwLocalPort),
+                    dest_port: port_from(row.dwRemotePort),
+                    protocol: 6, // TCP
+                },
+                state,
+                owner: (row.dwOwningPid != 0).then_some(row.dwOwningPid as i32),
+                sample: None,
+            });
+        }
+    }
+
+    if let Some(buffer) = fetch_table(|ptr, size| unsafe {
+        GetExtendedTcpTable(ptr, size, 0, AF_INET6 as u32, TCP_TABLE_OWNER_PID_ALL, 0)
+    }) {
+        for row in table_rows::<MIB_TCP6ROW_OWNER_PID>(&buffer, std::mem::offset_of!(MIB_TCP6TABLE_OWNER_PID, table)) {
+            let Some(state) = socket_state(row.dwState) else { continue };
+            sockets.push(TcpSocket {
+                conn: Connection {
+                    source_ip: IpAddr::V6(Ipv6Addr::from(row.ucLocalAddr)),
+                    dest_ip: IpAddr::V6(Ipv6Addr::from(row.ucRemoteAddr)),
+                    source_port: port_from(row.dwLocalPort),
+                    dest_port: port_from(row.dwRemotePort),
+                    protocol: 6, // TCP
+                }.normalized(),
+                state,
+                owner: (row.dwOwningPid != 0).then_some(row.dwOwningPid as i32),
+                sample: None,
+            });
+        }
+    }
+
+    sockets
+}
+
+/// Every TCP and UDP socket a process has open. The IP Helper tables carry no
+/// queue sizes, and RTT needs per-connection extended statistics, so only the
+/// addresses and TCP state are filled in.
+pub fn read_process_sockets(pid: i32) -> Result<Vec<SocketDetails>, String> {
+    let owner = pid as u32;
+    let details = |conn: Connection, state: Option<TcpSocketState>| SocketDetails {
+        protocol: conn.protocol,
+        local: std::net::SocketAddr::new(conn.source_ip, conn.source_port),
+        remote: std::net::SocketAddr::new(conn.dest_ip, conn.dest_port),
+        state,
+        recv_queue: None,
+        send_queue: None,
+        rtt_ms: None,
+        retrans: None,
+    };
+    let mut sockets: Vec<SocketDetails> = read_tcp_sockets(&HashMap::new())
+        .into_iter()
+        .filter(|socket| socket.owner == Some(pid))
+        .map(|socket| details(socket.conn, Some(socket.state)))
+        .chain(socket_table().into_iter()
+            .filter(|(conn, socket_owner)| conn.protocol == 17 && *socket_owner == owner)
+            .map(|(conn, _)| details(conn.normalized(), None)))
+        .collect();
+    sockets.sort_by_key(|socket| (socket.protocol, socket.local.port(), socket.remote));
+    Ok(sockets)
 }
 
 pub fn refresh_proc_maps(_containers_mode: bool) -> (HashMap<u64, ProcessIdentifier>, HashMap<Connection, u64>) {
     let mut inode_to_pid_map: HashMap<u64, ProcessIdentifier> = HashMap::new();
     let mut connection_to_inode_map: HashMap<Connection, u64> = HashMap::new();
 
-    // Use netstat2 to get socket information with associated PIDs
-    let af_flags = netstat2::AddressFamilyFlags::IPV4 | netstat2::AddressFamilyFlags::IPV6;
-    let proto_flags = netstat2::ProtocolFlags::TCP | netstat2::ProtocolFlags::UDP;
-    
-    if let Ok(sockets_info) = netstat2::get_sockets_info(af_flags, proto_flags) {
-        let mut sys = sysinfo::System::new_all();
-        sys.refresh_processes();
-        
-        for socket_info in sockets_info {
-            // Generate a synthetic inode for this socket
-            let synthetic_inode = SYNTHETIC_INODE.fetch_add(1, Ordering::SeqCst);
-            
-            // Process each PID associated with this socket
-            for &pid in &socket_info.associated_pids {
-                let name = sys.process(sysinfo::Pid::from(pid as usize))
-                    .map(|p| p.name().to_string())
-                    .unwrap_or_else(|| "???".to_string());
-                
-                let process_identifier = ProcessIdentifier {
-                    pid: pid as i32,
-                    name,
-                    container_name: None, // Windows doesn't support container detection yet
-                    user_name: None,      // Windows user detection not implemented yet
-                };
-                
-                inode_to_pid_map.insert(synthetic_inode, process_identifier);
-            }
-            
-            // Create Connection from socket info
-            let connection = match socket_info.protocol_socket_info {
-                netstat2::ProtocolSocketInfo::Tcp(tcp_info) => Connection {
-                    source_ip: tcp_info.local_addr,
-                    dest_ip: tcp_info.remote_addr,
-                    source_port: tcp_info.local_port,
-                    dest_port: tcp_info.remote_port,
-                    protocol: 6, // TCP
-                },
-                netstat2::ProtocolSocketInfo::Udp(udp_info) => Connection {
-                    source_ip: udp_info.local_addr,
-                    dest_ip: std::net::IpAddr::V4(std::net::Ipv4Addr::new(0, 0, 0, 0)), // UDP doesn't have remote addr
-                    source_port: udp_info.local_port,
-                    dest_port: 0, // UDP doesn't have remote port
-                    protocol: 17, // UDP
-                },
-            };
-            
-            connection_to_inode_map.insert(connection, synthetic_inode);
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes_specifics(sysinfo::ProcessesToUpdate::All, true, sysinfo::ProcessRefreshKind::nothing());
+    // Most processes own several sockets; their token is read once per refresh
+    let mut users: HashMap<u32, Option<String>> = HashMap::new();
+
+    // Windows has no socket inodes, so each socket gets a synthetic one per refresh
+    for (synthetic_inode, (connection, pid)) in (1u64..).zip(socket_table()) {
+        // PID 0 is the System Idle Process; sockets in TIME_WAIT report it
+        if pid == 0 {
+            continue;
         }
+        let process = sys.process(sysinfo::Pid::from(pid as usize));
+        let name = process
+            .map(|p| p.name().to_string_lossy().into_owned())
+            .unwrap_or_else(|| "???".to_string());
+        let user_name = users.entry(pid).or_insert_with(|| identity::process_user(pid as i32)).clone();
+
+        inode_to_pid_map.insert(synthetic_inode, ProcessIdentifier {
+            pid: pid as i32,
+            name,
+            container_name: None, // Windows doesn't support container detection yet
+            user_name,
+            unit: None,
+        });
+        connection_to_inode_map.insert(connection.normalized(), synthetic_inode);
     }
 
     (inode_to_pid_map, connection_to_inode_map)
 }
 
+/// Keeps the socket-to-process maps up to date. The Windows socket tables carry
+/// the owning PID, so every refresh is a full one and costs a single read of
+/// the tables and the process list.
+#[derive(Default)]
+pub struct ProcMapScanner {
+    sockets: HashSet<Connection>, // Connections listed at the last refresh
+}
+
+impl ProcMapScanner {
+    /// Bring both maps up to date and report what it cost
+    pub fn refresh(
+        &mut self,
+        inode_map: &mut HashMap<u64, ProcessIdentifier>,
+        conn_map: &mut HashMap<Connection, u64>,
+        containers_mode: bool,
+    ) -> ScanStats {
+        let started = Instant::now();
+        (*inode_map, *conn_map) = refresh_proc_maps(containers_mode);
+        let sockets: HashSet<Connection> = conn_map.keys().copied().collect();
+        let stats = ScanStats {
+            duration: started.elapsed(),
+            sockets: sockets.len(),
+            added: sockets.difference(&self.sockets).count(),
+            removed: self.sockets.difference(&sockets).count(),
+            processes_scanned: inode_map.values().map(|identifier| identifier.pid).collect::<HashSet<_>>().len(),
+            unowned: 0,
+            full: true,
+        };
+        self.sockets = sockets;
+        stats
+    }
+}
+
+/// Shortest gap between two out-of-turn refreshes, so unknown traffic can't keep the tables busy
+const SCAN_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Attributes traffic of sockets that appeared after the last refresh of the
+/// process maps, so processes that live for less than the refresh interval
+/// are still seen. The socket tables already carry the owning PID, so a miss
+/// simply refreshes the maps early.
+#[derive(Default)]
+pub struct NewSocketScanner {
+    unresolved: HashSet<Connection>, // Still unknown after a refresh; retried after the next one
+    last_scan: Option<Instant>,
+}
+
+impl NewSocketScanner {
+    /// Call right before each refresh of the maps
+    pub fn start_refresh(&mut self) {
+        self.unresolved.clear();
+    }
+
+    /// Try to attribute a connection the maps don't know. Returns true when it
+    /// can now be looked up.
+    pub fn resolve(
+        &mut self,
+        conn: &Connection,
+        inode_map: &mut HashMap<u64, ProcessIdentifier>,
+        conn_map: &mut HashMap<Connection, u64>,
+        containers_mode: bool,
+    ) -> bool {
+        if self.unresolved.contains(conn) || self.last_scan.is_some_and(|last| last.elapsed() < SCAN_INTERVAL) {
+            return false;
+        }
+        self.last_scan = Some(Instant::now());
+        (*inode_map, *conn_map) = refresh_proc_maps(containers_mode);
+
+        let resolved = crate::capture::lookup_socket(conn_map, conn)
+            .is_some_and(|(_, inode)| inode_map.contains_key(&inode));
+        if !resolved {
+            self.unresolved.insert(*conn);
+        }
+        resolved
+    }
+}
+
+/// Read the command line, executable, working directory, start time and open
+/// socket count of a process; fields the process hides from us are left empty
+pub fn read_process_details(pid: i32) -> ProcessDetails {
+    let mut details = ProcessDetails { pid, ..Default::default() };
+    let target = sysinfo::Pid::from(pid as usize);
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::Some(&[target]),
+        true,
+        sysinfo::ProcessRefreshKind::nothing()
+            .with_cmd(sysinfo::UpdateKind::Always)
+            .with_exe(sysinfo::UpdateKind::Always)
+            .with_cwd(sysinfo::UpdateKind::Always),
+    );
+    let Some(process) = sys.process(target) else {
+        details.exe = identity::process_image_path(pid);
+        return details;
+    };
+    let cmd = process.cmd();
+    details.cmdline = (!cmd.is_empty()).then(|| {
+        cmd.iter().map(|arg| arg.to_string_lossy()).collect::<Vec<_>>().join(" ")
+    });
+    // sysinfo needs more access than the image name does, e.g. for elevated processes
+    details.exe = process.exe().map(|path| path.display().to_string())
+        .or_else(|| identity::process_image_path(pid));
+    details.cwd = process.cwd().map(|path| path.display().to_string());
+    details.start_time = chrono::DateTime::from_timestamp(process.start_time() as i64, 0)
+        .map(|time| time.with_timezone(&chrono::Local));
+    details.socket_count = Some(socket_table().iter().filter(|(_, owner)| *owner == pid as u32).count());
+    details
+}
+
 /// Check if a process with the given PID is still alive
 pub fn is_process_alive(pid: i32) -> bool {
     let mut sys = sysinfo::System::new();
-    sys.refresh_processes();
-    sys.process(sysinfo::Pid::from(pid as usize)).is_some()
+    let target = sysinfo::Pid::from(pid as usize);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[target]), true);
+    sys.process(target).is_some()
 }
 
-/// Clean up dead processes from the stats HashMap
-/// Returns a vector of PIDs that were removed
-pub fn cleanup_dead_processes(stats: &mut HashMap<i32, ProcessInfo>, killed_processes: &std::collections::HashSet<i32>) -> Vec<i32> {
-    let mut removed_pids = Vec::new();
-    
-    // Collect PIDs to remove (processes that are dead and not in killed_processes)
-    let pids_to_remove: Vec<i32> = stats.keys()
+/// Parent PID of a running process, 0 for the top of the tree; None once it has exited
+pub fn parent_pid(pid: i32) -> Option<i32> {
+    let mut sys = sysinfo::System::new();
+    let target = sysinfo::Pid::from(pid as usize);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[target]), true);
+    let process = sys.process(target)?;
+    Some(process.parent().map_or(0, |parent| parent.as_u32() as i32))
+}
+
+/// PIDs in the stats HashMap whose process has exited; the caller decides
+/// how long to keep showing them
+pub fn find_exited_processes(stats: &HashMap<i32, ProcessInfo>, killed_processes: &std::collections::HashSet<i32>) -> Vec<i32> {
+    stats.keys()
         .filter(|&pid| {
-            // Don't remove processes that were intentionally killed by the tool
+            // Processes that were intentionally killed by the tool are handled separately
             if killed_processes.contains(pid) {
                 return false;
             }
-            // Remove if the process is no longer alive
             !is_process_alive(*pid)
         })
         .cloned()
-        .collect();
-    
-    // Remove dead processes and track what was removed
-    for pid in pids_to_remove {
-        if stats.remove(&pid).is_some() {
-            removed_pids.push(pid);
-        }
-    }
-    
-    removed_pids
+        .collect()
 }
 
-/// Validate if a process should be tracked (alive and not in exclusion sets)
-pub fn should_track_process(pid: i32, killed_processes: &std::collections::HashSet<i32>, dead_processes_cache: &std::collections::HashSet<i32>) -> bool {
-    // Don't track killed processes
-    if killed_processes.contains(&pid) {
-        return false;
-    }
-    
-    // Don't track processes we know are dead
-    if dead_processes_cache.contains(&pid) {
-        return false;
-    }
-    
-    // Final check: is the process actually alive?
-    is_process_alive(pid)
+/// Validate if a process should be tracked (not in exclusion sets). A process
+/// that already exited is still tracked until the periodic cleanup has seen it,
+/// so the traffic of short-lived processes shows up.
+pub fn should_track_process(pid: i32, killed_processes: &HashSet<i32>, dead_processes_cache: &HashSet<i32>) -> bool {
+    !killed_processes.contains(&pid) && !dead_processes_cache.contains(&pid)
 } 
\ No newline at end of file
@@ -0,0 +1,138 @@
+// Ending processes through the Win32 API rather than `taskkill`: no child
+// process per kill, and failures come back as error codes that can be told
+// apart, so "already gone" counts as done and "access denied" can say whether
+// running elevated would help.
+
+use std::fmt;
+use std::io;
+use std::time::Duration;
+use windows_sys::Win32::Foundation::{
+    CloseHandle, GetLastError, ERROR_ACCESS_DENIED, ERROR_INVALID_PARAMETER, HANDLE, HWND, LPARAM, WAIT_OBJECT_0,
+};
+use windows_sys::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+use windows_sys::Win32::System::Threading::{
+    GetCurrentProcess, OpenProcess, OpenProcessToken, TerminateProcess, WaitForSingleObject, PROCESS_SYNCHRONIZE,
+    PROCESS_TERMINATE,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{EnumWindows, GetWindowThreadProcessId, PostMessageW, WM_CLOSE};
+
+/// Exit code of processes ended with `terminate`, as `taskkill /F` uses
+const TERMINATED_EXIT_CODE: u32 = 1;
+
+#[derive(Debug, PartialEq)]
+pub enum ControlError {
+    /// No process has this PID (any more)
+    NotFound,
+    /// Windows refused; `elevated` tells whether monitetoring already runs as Administrator
+    AccessDenied { elevated: bool },
+    /// `request_close` found no top-level window to send WM_CLOSE to
+    NoWindow,
+    /// The process was told to exit but is still running after the wait
+    StillRunning,
+    Os(u32),
+}
+
+impl fmt::Display for ControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlError::NotFound => write!(f, "no such process"),
+            ControlError::AccessDenied { elevated: false } => write!(f, "access denied; run monitetoring as Administrator"),
+            ControlError::AccessDenied { elevated: true } => {
+                write!(f, "access denied even as Administrator (a protected or system process)")
+            }
+            ControlError::NoWindow => write!(f, "it has no window to close; kill it instead"),
+            ControlError::StillRunning => write!(f, "still running"),
+            ControlError::Os(code) => write!(f, "{} (error {})", io::Error::from_raw_os_error(*code as i32), code),
+        }
+    }
+}
+
+fn last_error() -> ControlError {
+    match unsafe { GetLastError() } {
+        // OpenProcess reports a PID nobody has as an invalid parameter
+        ERROR_INVALID_PARAMETER => ControlError::NotFound,
+        ERROR_ACCESS_DENIED => ControlError::AccessDenied { elevated: is_elevated() },
+        code => ControlError::Os(code),
+    }
+}
+
+/// A process handle that is closed when dropped
+struct ProcessHandle(HANDLE);
+
+impl ProcessHandle {
+    fn open(pid: i32, access: u32) -> Result<Self, ControlError> {
+        let handle = unsafe { OpenProcess(access, 0, pid as u32) };
+        if handle.is_null() {
+            return Err(last_error());
+        }
+        Ok(ProcessHandle(handle))
+    }
+}
+
+impl Drop for ProcessHandle {
+    fn drop(&mut self) {
+        unsafe { CloseHandle(self.0) };
+    }
+}
+
+/// Whether monitetoring runs with an elevated (Administrator) token
+pub fn is_elevated() -> bool {
+    let mut token: HANDLE = std::ptr::null_mut();
+    if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) } == 0 {
+        return false;
+    }
+    let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+    let mut returned = 0u32;
+    let ok = unsafe {
+        GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut TOKEN_ELEVATION as *mut _,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        )
+    };
+    unsafe { CloseHandle(token) };
+    ok != 0 && elevation.TokenIsElevated != 0
+}
+
+/// End `pid` at once, like `taskkill /F`, and wait up to `wait` for it to be gone
+pub fn terminate(pid: i32, wait: Duration) -> Result<(), ControlError> {
+    let process = ProcessHandle::open(pid, PROCESS_TERMINATE | PROCESS_SYNCHRONIZE)?;
+    if unsafe { TerminateProcess(process.0, TERMINATED_EXIT_CODE) } == 0 {
+        return Err(last_error());
+    }
+    // Termination finishes asynchronously; the handle is signalled once it has
+    match unsafe { WaitForSingleObject(process.0, wait.as_millis().min(u32::MAX as u128) as u32) } {
+        WAIT_OBJECT_0 => Ok(()),
+        _ => Err(ControlError::StillRunning),
+    }
+}
+
+struct CloseRequest {
+    pid: u32,
+    posted: usize,
+}
+
+unsafe extern "system" fn post_close(window: HWND, param: LPARAM) -> windows_sys::core::BOOL {
+    let request = unsafe { &mut *(param as *mut CloseRequest) };
+    let mut owner = 0u32;
+    unsafe { GetWindowThreadProcessId(window, &mut owner) };
+    if owner == request.pid && unsafe { PostMessageW(window, WM_CLOSE, 0, 0) } != 0 {
+        request.posted += 1;
+    }
+    1 // Keep enumerating
+}
+
+/// Ask `pid` to exit by closing its top-level windows, like `taskkill` without /F;
+/// the closest Windows has to SIGTERM. Console and background processes have no window
+pub fn request_close(pid: i32) -> Result<(), ControlError> {
+    // Fails the same way `terminate` would when the process is gone or out of reach
+    drop(ProcessHandle::open(pid, PROCESS_TERMINATE)?);
+    let mut request = CloseRequest { pid: pid as u32, posted: 0 };
+    unsafe { EnumWindows(Some(post_close), &mut request as *mut CloseRequest as LPARAM) };
+    if request.posted == 0 {
+        return Err(ControlError::NoWindow);
+    }
+    Ok(())
+}
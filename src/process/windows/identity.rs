@@ -0,0 +1,83 @@
+// Who runs a process and from where, read from its access token and image.
+// sysinfo only resolves users listed on this machine, which leaves SYSTEM, the
+// service accounts and domain users blank; LookupAccountSidW knows them all.
+
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+use windows_sys::Win32::Security::{GetTokenInformation, LookupAccountSidW, TokenUser, TOKEN_QUERY, TOKEN_USER};
+use windows_sys::Win32::System::Threading::{
+    OpenProcess, OpenProcessToken, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+
+/// A handle that is closed when dropped
+struct OwnedHandle(HANDLE);
+
+impl Drop for OwnedHandle {
+    fn drop(&mut self) {
+        unsafe { CloseHandle(self.0) };
+    }
+}
+
+/// Limited query rights are granted for far more processes than full ones,
+/// including elevated processes seen from an unelevated monitetoring
+fn open_process(pid: i32) -> Option<OwnedHandle> {
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid as u32) };
+    (!handle.is_null()).then_some(OwnedHandle(handle))
+}
+
+fn utf16_to_string(buffer: &[u16], len: u32) -> String {
+    String::from_utf16_lossy(&buffer[..(len as usize).min(buffer.len())])
+}
+
+/// Account name of the SID in a `TOKEN_USER`, e.g. "alice", "SYSTEM" or "NETWORK SERVICE"
+fn account_name(sid: *mut std::ffi::c_void) -> Option<String> {
+    let mut name = [0u16; 256];
+    let mut domain = [0u16; 256];
+    let (mut name_len, mut domain_len) = (name.len() as u32, domain.len() as u32);
+    let mut kind = 0;
+    let found = unsafe {
+        LookupAccountSidW(
+            std::ptr::null(),
+            sid,
+            name.as_mut_ptr(),
+            &mut name_len,
+            domain.as_mut_ptr(),
+            &mut domain_len,
+            &mut kind,
+        )
+    } != 0;
+    // The user column shows bare names, as it does for Linux users
+    found.then(|| utf16_to_string(&name, name_len))
+}
+
+/// Name of the account a process runs as; None when its token can't be opened
+pub fn process_user(pid: i32) -> Option<String> {
+    let process = open_process(pid)?;
+    let mut token: HANDLE = std::ptr::null_mut();
+    if unsafe { OpenProcessToken(process.0, TOKEN_QUERY, &mut token) } == 0 {
+        return None;
+    }
+    let token = OwnedHandle(token);
+
+    // TOKEN_USER is followed by the SID it points to, so ask for the size first
+    let mut size = 0u32;
+    unsafe { GetTokenInformation(token.0, TokenUser, std::ptr::null_mut(), 0, &mut size) };
+    if size == 0 {
+        return None;
+    }
+    // u64 elements keep the buffer aligned for TOKEN_USER
+    let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+    if unsafe { GetTokenInformation(token.0, TokenUser, buffer.as_mut_ptr().cast(), size, &mut size) } == 0 {
+        return None;
+    }
+    let user = unsafe { &*(buffer.as_ptr() as *const TOKEN_USER) };
+    account_name(user.User.Sid)
+}
+
+/// Full path of a process's executable, e.g. "C:\Windows\System32\svchost.exe"
+pub fn process_image_path(pid: i32) -> Option<String> {
+    let process = open_process(pid)?;
+    let mut path = [0u16; 1024];
+    let mut len = path.len() as u32;
+    let ok = unsafe { QueryFullProcessImageNameW(process.0, PROCESS_NAME_WIN32, path.as_mut_ptr(), &mut len) };
+    (ok != 0).then(|| utf16_to_string(&path, len))
+}
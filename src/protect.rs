@@ -0,0 +1,111 @@
+// Protected processes: a denylist monitetoring refuses to kill, by hand or
+// from an alert. Entries come from the `protected` list in the config file
+// (defaulting to the processes a machine can't do without) plus `--protect`,
+// and `--allow-protected-kills` turns the check off for a session.
+//
+// An entry is a process name (`sshd`), `pid:<n>` or `user:<name>`.
+
+/// Written to a new config file; covers init, remote access and the Windows session processes
+pub const DEFAULT_PROTECTED: [&str; 11] = [
+    "pid:1",
+    "init",
+    "systemd",
+    "sshd",
+    "smss.exe",
+    "csrss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "services.exe",
+    "lsass.exe",
+    "svchost.exe",
+];
+
+#[derive(Debug, PartialEq)]
+enum Rule {
+    Name(String),
+    Pid(i32),
+    User(String),
+}
+
+#[derive(Default)]
+pub struct ProtectedList {
+    rules: Vec<Rule>,
+}
+
+impl ProtectedList {
+    pub fn parse(entries: &[String]) -> Result<Self, String> {
+        let rules = entries.iter()
+            .map(|entry| entry.trim())
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                if let Some(pid) = entry.strip_prefix("pid:") {
+                    pid.trim().parse().map(Rule::Pid).map_err(|_| format!("'{}' is not a valid PID", entry))
+                } else if let Some(user) = entry.strip_prefix("user:") {
+                    Ok(Rule::User(user.trim().to_string()))
+                } else {
+                    Ok(Rule::Name(entry.to_string()))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ProtectedList { rules })
+    }
+
+    /// The entry that protects this process, e.g. "name sshd" or "user root"
+    pub fn reason(&self, pid: i32, name: &str, user: Option<&str>) -> Option<String> {
+        self.rules.iter().find_map(|rule| match rule {
+            Rule::Pid(protected) if *protected == pid => Some(format!("PID {}", pid)),
+            // Windows names differ in case from one tool to the next
+            Rule::Name(protected) if protected.eq_ignore_ascii_case(name) => Some(format!("name {}", protected)),
+            Rule::User(protected) if user == Some(protected.as_str()) => Some(format!("user {}", protected)),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(entries: &[&str]) -> ProtectedList {
+        ProtectedList::parse(&entries.iter().map(ToString::to_string).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn parses_entries() {
+        assert_eq!(list(&["sshd", " pid:1 ", "user:root", ""]).rules, vec![
+            Rule::Name("sshd".to_string()),
+            Rule::Pid(1),
+            Rule::User("root".to_string()),
+        ]);
+        assert!(ProtectedList::parse(&["pid:init".to_string()]).is_err());
+    }
+
+    #[test]
+    fn matches_processes() {
+        let protected = list(&["sshd", "pid:1", "user:postgres"]);
+        assert_eq!(protected.reason(1, "systemd", Some("root")), Some("PID 1".to_string()));
+        assert_eq!(protected.reason(812, "SSHD", None), Some("name sshd".to_string()));
+        assert_eq!(protected.reason(900, "postgres", Some("postgres")), Some("user postgres".to_string()));
+        assert_eq!(protected.reason(4242, "curl", Some("alice")), None);
+    }
+
+    #[test]
+    fn protected_processes_are_not_limited() {
+        use crate::types::{App, ProcessIdentifier, ProcessInfo};
+        let mut app = App::new(false, false);
+        app.demo_mode = true;
+        app.protected = list(&["sshd"]);
+        for (pid, name) in [(812, "sshd"), (4242, "curl")] {
+            let identifier = ProcessIdentifier { pid, name: name.to_string(), container_name: None, user_name: None, unit: None };
+            app.stats.insert(pid, ProcessInfo::new(&identifier));
+        }
+        app.limit_process(812, 1000);
+        app.limit_process(4242, 1000);
+        assert!(!app.limits.contains_key(&812));
+        assert_eq!(app.limits.get(&4242).map(|limit| limit.rate), Some(1000));
+
+        app.allow_protected_kills = true;
+        app.limit_process(812, 1000);
+        assert!(app.limits.contains_key(&812));
+    }
+}
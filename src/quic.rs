@@ -0,0 +1,95 @@
+// QUIC (HTTP/3) recognition. Browsers move much of their traffic over QUIC,
+// which on the wire is just UDP to port 443. The handshake packets carry a
+// long header naming the QUIC version, which is telling enough to go on; a
+// flow that showed one is remembered, so the short-header packets that make
+// up the rest of the connection are labeled too.
+
+use std::collections::HashSet;
+use crate::types::Connection;
+
+/// Shown instead of "UDP" for packets recognised as QUIC
+pub const LABEL: &str = "QUIC/HTTP3";
+
+/// Flows remembered before the oldest knowledge is dropped and relearned
+const MAX_FLOWS: usize = 4096;
+
+const PORT: u16 = 443;
+
+/// Versions a long header may name: version negotiation (0), QUIC 1, QUIC 2
+/// and the IETF drafts (0xff0000xx)
+fn is_known_version(version: u32) -> bool {
+    matches!(version, 0 | 0x0000_0001 | 0x6b33_43cf) || version >> 8 == 0x00ff_0000
+}
+
+/// Whether a UDP payload starts with a QUIC long header
+pub fn is_long_header(payload: &[u8]) -> bool {
+    let Some((&first, rest)) = payload.split_first() else { return false };
+    let Some(version) = rest.get(..4) else { return false };
+    let version = u32::from_be_bytes([version[0], version[1], version[2], version[3]]);
+    // Version negotiation leaves the fixed bit (0x40) to chance
+    first & 0x80 != 0 && (first & 0x40 != 0 || version == 0) && is_known_version(version)
+}
+
+/// Whether a UDP payload could be a QUIC short header packet
+fn is_short_header(payload: &[u8]) -> bool {
+    payload.first().is_some_and(|first| first & 0xc0 == 0x40)
+}
+
+/// UDP flows on port 443 that started with a QUIC handshake
+#[derive(Default)]
+pub struct QuicFlows {
+    flows: HashSet<Connection>,
+}
+
+impl QuicFlows {
+    /// Whether a packet of `flow` (oriented local -> remote) is QUIC; `payload`
+    /// is looked at only for UDP on port 443
+    pub fn classify<'a>(&mut self, flow: &Connection, payload: impl FnOnce() -> Option<&'a [u8]>) -> bool {
+        if flow.protocol != 17 || (flow.source_port != PORT && flow.dest_port != PORT) {
+            return false;
+        }
+        let Some(payload) = payload() else { return false };
+        if is_long_header(payload) {
+            if self.flows.len() >= MAX_FLOWS && !self.flows.contains(flow) {
+                self.flows.clear();
+            }
+            self.flows.insert(*flow);
+            return true;
+        }
+        is_short_header(payload) && self.flows.contains(flow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(dest_port: u16) -> Connection {
+        Connection {
+            source_port: 50000,
+            dest_port,
+            source_ip: "192.168.1.2".parse().unwrap(),
+            dest_ip: "142.250.1.1".parse().unwrap(),
+            protocol: 17,
+        }
+    }
+
+    #[test]
+    fn recognizes_handshakes_then_the_rest_of_the_flow() {
+        let initial = [0xc3, 0x00, 0x00, 0x00, 0x01, 0x08];
+        let short = [0x41, 0x9a, 0x10];
+        let mut flows = QuicFlows::default();
+
+        // Short headers alone could be anything
+        assert!(!flows.classify(&flow(443), || Some(&short[..])));
+        assert!(flows.classify(&flow(443), || Some(&initial[..])));
+        assert!(flows.classify(&flow(443), || Some(&short[..])));
+
+        // Same bytes on another port, or with an unknown version, are plain UDP
+        assert!(!flows.classify(&flow(8443), || Some(&initial[..])));
+        assert!(!flows.classify(&flow(443), || Some(&[0xc3, 0x12, 0x34, 0x56, 0x78][..])));
+        assert!(is_long_header(&[0xc0, 0xff, 0x00, 0x00, 0x1d]));
+        assert!(is_long_header(&[0x80, 0x00, 0x00, 0x00, 0x00]));
+        assert!(!is_long_header(&[0xc0, 0x00]));
+    }
+}
@@ -0,0 +1,421 @@
+// Test-only capture backend: packets come from the pcap fixtures in
+// tests/fixtures instead of a live interface, and the socket and process
+// tables from `MockProc` instead of /proc. `Replay` hands them to
+// `Attribution::packet`, the capture thread's own per-packet step, then
+// computes rates with the packets' timestamps as the clock, so attribution,
+// rates and alerts can be tested end to end.
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use crate::accounting::{Attribution, ByteCounting, RateCalculator};
+use crate::classify::Classifier;
+use crate::filter::ProcessFilter;
+use crate::parser::{self, LinkType};
+use crate::traffic_stats::{SharedTraffic, TrafficCounter};
+use crate::types::{Connection, ProcessIdentifier};
+
+const PCAP_MAGIC: u32 = 0xa1b2_c3d4;
+const LINKTYPE_ETHERNET: u32 = 1;
+
+/// One captured packet
+pub struct Frame {
+    /// Seconds since the epoch
+    pub time: f64,
+    /// Length on the wire; `data` may be cut short by the snaplen
+    pub wire_len: u32,
+    pub data: Vec<u8>,
+}
+
+impl Frame {
+    pub fn new(time: f64, data: Vec<u8>) -> Self {
+        Frame { time, wire_len: data.len() as u32, data }
+    }
+}
+
+/// Write Ethernet frames as a classic (microsecond) pcap file
+pub fn write_pcap(path: &Path, frames: &[Frame]) -> io::Result<()> {
+    let mut out = PCAP_MAGIC.to_le_bytes().to_vec();
+    out.extend_from_slice(&[2, 0, 4, 0]); // Version 2.4
+    for value in [0, 0, 65535, LINKTYPE_ETHERNET] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    for frame in frames {
+        let micros = (frame.time * 1e6).round() as u64;
+        for value in [(micros / 1_000_000) as u32, (micros % 1_000_000) as u32, frame.data.len() as u32, frame.wire_len] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        out.extend_from_slice(&frame.data);
+    }
+    std::fs::File::create(path)?.write_all(&out)
+}
+
+/// Frames of a classic pcap file with Ethernet link type, in either byte order
+pub fn read_pcap(path: &Path) -> io::Result<Vec<Frame>> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)?.read_to_end(&mut bytes)?;
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let magic = bytes.get(0..4).ok_or_else(|| invalid("no pcap header"))?;
+    let little_endian = match u32::from_le_bytes(magic.try_into().unwrap_or_default()) {
+        PCAP_MAGIC => true,
+        magic if magic.swap_bytes() == PCAP_MAGIC => false,
+        _ => return Err(invalid("not a microsecond pcap file")),
+    };
+    let u32_at = |at: usize| -> io::Result<u32> {
+        let field: [u8; 4] = bytes.get(at..at + 4).and_then(|field| field.try_into().ok()).ok_or_else(|| invalid("truncated pcap file"))?;
+        Ok(if little_endian { u32::from_le_bytes(field) } else { u32::from_be_bytes(field) })
+    };
+    if u32_at(20)? != LINKTYPE_ETHERNET {
+        return Err(invalid("only Ethernet captures can be replayed"));
+    }
+    let mut frames = Vec::new();
+    let mut at = 24;
+    while at < bytes.len() {
+        let (seconds, micros, captured, wire_len) = (u32_at(at)?, u32_at(at + 4)?, u32_at(at + 8)? as usize, u32_at(at + 12)?);
+        let data = bytes.get(at + 16..at + 16 + captured).ok_or_else(|| invalid("truncated pcap record"))?;
+        frames.push(Frame { time: seconds as f64 + micros as f64 / 1e6, wire_len, data: data.to_vec() });
+        at += 16 + captured;
+    }
+    Ok(frames)
+}
+
+/// Socket and process tables as /proc would show them
+#[derive(Default)]
+pub struct MockProc {
+    sockets: Vec<(Connection, u64)>,
+    owners: HashMap<u64, ProcessIdentifier>,
+}
+
+impl MockProc {
+    /// Sockets from a table in the format of /proc/net/tcp, udp, tcp6 or udp6
+    /// (header line included); `protocol` is 6 or 17
+    pub fn net(mut self, protocol: u8, table: &str) -> Self {
+        for line in table.lines().skip(1) {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() < 10 {
+                continue;
+            }
+            let (Some((source_ip, source_port)), Some((dest_ip, dest_port)), Ok(inode)) =
+                (proc_address(columns[1]), proc_address(columns[2]), columns[9].parse::<u64>()) else {
+                    continue;
+                };
+            let conn = Connection { source_port, dest_port, source_ip, dest_ip, protocol }.normalized();
+            self.sockets.push((conn, inode));
+        }
+        self
+    }
+
+    /// A process and the socket inodes its /proc/<pid>/fd links point to
+    pub fn process(mut self, pid: i32, name: &str, inodes: &[u64]) -> Self {
+        let identifier = ProcessIdentifier { pid, name: name.to_string(), container_name: None, user_name: None, unit: None };
+        for inode in inodes {
+            self.owners.insert(*inode, identifier.clone());
+        }
+        self
+    }
+}
+
+/// "0100007F:0050" -> 127.0.0.1:80; addresses are written as the kernel's
+/// 32-bit words in host (little-endian) order, ports as plain hex
+fn proc_address(column: &str) -> Option<(IpAddr, u16)> {
+    let (address, port) = column.split_once(':')?;
+    let words: Option<Vec<[u8; 4]>> = (0..address.len() / 8)
+        .map(|word| address.get(word * 8..word * 8 + 8).and_then(|hex| u32::from_str_radix(hex, 16).ok()).map(u32::to_le_bytes))
+        .collect();
+    let bytes = words?.concat();
+    let ip = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+        16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+        _ => return None,
+    };
+    Some((ip, u16::from_str_radix(port, 16).ok()?))
+}
+
+/// The capture thread's per-packet work, driven by recorded packets
+pub struct Replay {
+    conn_map: HashMap<Connection, u64>,
+    inode_map: HashMap<u64, ProcessIdentifier>,
+    /// What the capture loop keeps, the processes' counters included
+    pub attribution: Attribution,
+    /// Packets no process's socket matched
+    pub unattributed: u64,
+    traffic: TrafficCounter,
+    rates: RateCalculator,
+    started: Option<f64>,
+    last_rate_calc: f64,
+}
+
+impl Replay {
+    pub fn new(proc: MockProc) -> Self {
+        Replay {
+            conn_map: proc.sockets.into_iter().collect(),
+            inode_map: proc.owners,
+            attribution: Attribution::new(ProcessFilter::default(), Classifier::new(&[]).0, ByteCounting::default(), true),
+            unattributed: 0,
+            traffic: TrafficCounter::new("replay", SharedTraffic::default()),
+            rates: RateCalculator::default(),
+            started: None,
+            last_rate_calc: 0.0,
+        }
+    }
+
+    /// Calculate rates once a second of packet time has passed since the last
+    /// time, as the capture loop does
+    pub fn advance_to(&mut self, time: f64) {
+        let started = *self.started.get_or_insert_with(|| {
+            self.last_rate_calc = time;
+            time
+        });
+        if time - self.last_rate_calc >= 1.0 {
+            self.rates.update(&mut self.attribution.processes, time - self.last_rate_calc, time - started);
+            self.last_rate_calc = time;
+        }
+    }
+
+    pub fn feed(&mut self, frame: &Frame) {
+        self.advance_to(frame.time);
+        let parsed = parser::parse(LinkType::Ethernet, &frame.data);
+        // The socket tables are all there is; nothing is opened behind their back
+        let attributed = self.attribution.packet(
+            parsed, frame.wire_len as u64, &mut self.conn_map, &mut self.inode_map, &mut self.traffic,
+            |_, _, _| false,
+        );
+        if attributed.is_none() {
+            self.unattributed += 1;
+        }
+    }
+
+    /// Replay a pcap fixture from start to end
+    pub fn run(proc: MockProc, path: &Path) -> io::Result<Self> {
+        let mut replay = Replay::new(proc);
+        for frame in read_pcap(path)? {
+            replay.feed(&frame);
+        }
+        Ok(replay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use etherparse::PacketBuilder;
+    use crate::types::{Alert, AlertAction, App};
+
+    const CURL: i32 = 100;
+    const AVAHI: i32 = 200;
+    const SCANNER: i32 = 300;
+
+    // curl connected to 93.184.216.34:443, a socket whose process already
+    // exited, a UDP socket on *:5353 and one on *:40000
+    const NET_TCP: &str = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0A01A8C0:C350 22D8B85D:01BB 01 00000000:00000000 00:00000000 00000000  1000        0 1001 1 0000000000000000 20 4 30 10 -1
+   1: 0A01A8C0:C351 22D8B85D:01BB 01 00000000:00000000 00:00000000 00000000  1000        0 1002 1 0000000000000000 20 4 30 10 -1
+";
+    const NET_UDP: &str = "\
+   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  100: 00000000:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000   104        0 2001 2 0000000000000000 0
+  200: 00000000:9C40 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 3001 2 0000000000000000 0
+";
+
+    fn proc() -> MockProc {
+        MockProc::default()
+            .net(6, NET_TCP)
+            .net(17, NET_UDP)
+            .process(CURL, "curl", &[1001])
+            .process(AVAHI, "avahi-daemon", &[2001])
+            .process(SCANNER, "scanner", &[3001])
+    }
+
+    fn tcp(time: f64, from: ([u8; 4], u16), to: ([u8; 4], u16), payload: usize) -> Frame {
+        let mut data = Vec::new();
+        PacketBuilder::ethernet2([2, 0, 0, 0, 0, 1], [2, 0, 0, 0, 0, 2])
+            .ipv4(from.0, to.0, 64)
+            .tcp(from.1, to.1, 1, 65535)
+            .write(&mut data, &vec![0; payload])
+            .unwrap();
+        Frame::new(time, data)
+    }
+
+    fn udp(time: f64, from: ([u8; 4], u16), to: ([u8; 4], u16), payload: usize) -> Frame {
+        let mut data = Vec::new();
+        PacketBuilder::ethernet2([2, 0, 0, 0, 0, 1], [2, 0, 0, 0, 0, 2])
+            .ipv4(from.0, to.0, 64)
+            .udp(from.1, to.1)
+            .write(&mut data, &vec![0; payload])
+            .unwrap();
+        Frame::new(time, data)
+    }
+
+    const HOST: [u8; 4] = [192, 168, 1, 10];
+    const SERVER: [u8; 4] = [93, 184, 216, 34];
+
+    /// Only the headers captured, as with a small --snaplen; the wire length stays
+    fn headers_only(mut frame: Frame) -> Frame {
+        frame.data.truncate(64);
+        frame
+    }
+
+    /// The frames of each fixture in tests/fixtures, which `write_fixtures` writes
+    fn fixture_frames(name: &str) -> Vec<Frame> {
+        match name {
+            "attribution" => vec![
+                tcp(0.0, (HOST, 50000), (SERVER, 443), 500),
+                tcp(0.1, (SERVER, 443), (HOST, 50000), 1400),
+                tcp(0.2, (SERVER, 443), (HOST, 50000), 1400),
+                // The socket's process is gone, and nobody has this one
+                tcp(0.3, (HOST, 50001), (SERVER, 443), 100),
+                tcp(0.4, (HOST, 50002), ([1, 1, 1, 1], 443), 100),
+                // An unconnected socket on the wildcard address receives from anyone
+                udp(0.5, ([192, 168, 1, 20], 5000), (HOST, 5353), 200),
+            ],
+            // Ten 1000-byte frames a second for two seconds, then thirty
+            "rates" => (0..4).flat_map(|second| {
+                let count = if second < 2 { 10 } else { 30 };
+                (0..count).map(move |packet| {
+                    let time = 1000.0 + second as f64 + packet as f64 / count as f64;
+                    headers_only(tcp(time, (HOST, 50000), (SERVER, 443), 946))
+                })
+            }).collect(),
+            "alerts" => (0..20).flat_map(|packet| {
+                let time = packet as f64 / 10.0;
+                [
+                    tcp(time, (SERVER, 443), (HOST, 50000), 1000),
+                    // One new host a packet, from a socket bound to *:40000
+                    udp(time, (HOST, 40000), ([10, 0, 0, packet + 1], 80), 40),
+                    udp(time, ([192, 168, 1, 20], 5000), (HOST, 5353), 40),
+                ].map(headers_only)
+            }).collect(),
+            _ => unreachable!("no fixture {}", name),
+        }
+    }
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(format!("{}.pcap", name))
+    }
+
+    /// Regenerate the fixtures after changing `fixture_frames`:
+    /// `cargo test write_fixtures -- --ignored`
+    #[test]
+    #[ignore]
+    fn write_fixtures() {
+        for name in ["attribution", "rates", "alerts"] {
+            write_pcap(&fixture(name), &fixture_frames(name)).unwrap();
+        }
+    }
+
+    #[test]
+    fn fixtures_match_their_frames() {
+        for name in ["attribution", "rates", "alerts"] {
+            let (read, expected) = (read_pcap(&fixture(name)).unwrap(), fixture_frames(name));
+            assert_eq!(read.len(), expected.len(), "{}", name);
+            for (read, expected) in read.iter().zip(&expected) {
+                // pcap keeps microseconds
+                assert!((read.time - expected.time).abs() < 1e-6, "{}", name);
+                assert_eq!((read.wire_len, &read.data), (expected.wire_len, &expected.data), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        let truncated = headers_only(tcp(1.5, (HOST, 50000), (SERVER, 443), 1400));
+        let path = std::env::temp_dir().join(format!("monitetoring-roundtrip-{}.pcap", std::process::id()));
+        write_pcap(&path, &[tcp(1.0, (HOST, 50000), (SERVER, 443), 10), truncated]).unwrap();
+        let frames = read_pcap(&path).unwrap();
+        let _ = std::fs::remove_file(path);
+        assert_eq!(frames.len(), 2);
+        assert_eq!((frames[1].time, frames[1].data.len(), frames[1].wire_len), (1.5, 64, 1454));
+        assert_eq!(proc_address("0100007F:0050"), Some((IpAddr::V4(Ipv4Addr::LOCALHOST), 80)));
+    }
+
+    #[test]
+    fn attributes_packets_to_the_socket_owner() {
+        let frames = read_pcap(&fixture("attribution")).unwrap();
+        let replay = Replay::run(proc(), &fixture("attribution")).unwrap();
+        let processes = &replay.attribution.processes;
+
+        let curl = &processes[&CURL];
+        assert_eq!(curl.sent, frames[0].wire_len as u64);
+        assert_eq!(curl.received, (frames[1].wire_len + frames[2].wire_len) as u64);
+        assert_eq!((curl.tcp_bytes, curl.udp_bytes), (curl.sent + curl.received, 0));
+        assert_eq!(curl.connection_count, 1);
+        assert_eq!(curl.host_contacts.iter().map(|contact| contact.host).collect::<Vec<_>>(), [IpAddr::from(SERVER)]);
+        assert_eq!(curl.packet_history.len(), 3);
+
+        let avahi = &processes[&AVAHI];
+        assert_eq!((avahi.sent, avahi.received), (0, frames[5].wire_len as u64));
+        assert_eq!(replay.unattributed, 2);
+        assert!(!processes.contains_key(&SCANNER));
+    }
+
+    #[test]
+    fn payload_counting_leaves_out_the_headers() {
+        let mut replay = Replay::new(proc());
+        replay.attribution.counting = ByteCounting::Payload;
+        let frames = read_pcap(&fixture("attribution")).unwrap();
+        for frame in &frames {
+            replay.feed(frame);
+        }
+
+        let curl = &replay.attribution.processes[&CURL];
+        assert_eq!((curl.sent, curl.received, curl.tcp_bytes), (500, 2800, 3300));
+        // The packet history still shows the frames as captured
+        assert_eq!(curl.packet_history.get(0).map(|packet| packet.size), Some(frames[0].data.len()));
+        assert_eq!(replay.attribution.processes[&AVAHI].received, 200);
+    }
+
+    #[test]
+    fn rates_follow_the_packet_timestamps() {
+        let mut replay = Replay::new(proc());
+        let mut rates = Vec::new();
+        let frames = read_pcap(&fixture("rates")).unwrap();
+        for second in 0..4 {
+            let until = 1000.0 + second as f64 + 1.0;
+            for frame in frames.iter().filter(|frame| (until - 1.0..until).contains(&frame.time)) {
+                replay.feed(frame);
+            }
+            replay.advance_to(until);
+            rates.push(replay.attribution.processes[&CURL].sent_rate);
+        }
+        // Only the headers were captured; the rates count the 1000 bytes on the wire
+        assert_eq!(rates, [10_000, 10_000, 30_000, 30_000]);
+        assert_eq!(replay.attribution.processes[&CURL].new_connection_rate, 0);
+    }
+
+    fn alert(pid: i32, threshold_bytes: u64, max_new_connections: Option<u64>) -> Alert {
+        Alert {
+            process_pid: pid,
+            threshold_bytes,
+            action: AlertAction::SystemAlert,
+            max_new_connections,
+            on_anomaly: false,
+            cooldown_secs: 60,
+            rearm_below_percent: None,
+            max_triggers: None,
+            notify_every_secs: None,
+            dry_run: false,
+            process_name: None,
+        }
+    }
+
+    #[test]
+    fn alerts_fire_on_replayed_traffic() {
+        let mut replay = Replay::run(proc(), &fixture("alerts")).unwrap();
+        replay.advance_to(2.0);
+        assert_eq!(replay.attribution.processes[&SCANNER].connection_count, 20);
+
+        let mut app = App::new(false, false);
+        app.stats = replay.attribution.processes.iter().map(|(pid, info)| (*pid, info.summary())).collect();
+        app.alerts.insert(CURL, alert(CURL, 10_000, None));
+        app.alerts.insert(SCANNER, alert(SCANNER, u64::MAX, Some(5)));
+        app.alerts.insert(AVAHI, alert(AVAHI, 10_000, None));
+
+        let mut fired: Vec<i32> = crate::alerts::due_alerts(&mut app).into_iter().map(|(pid, _)| pid).collect();
+        fired.sort();
+        assert_eq!(fired, [CURL, SCANNER]);
+        // Within the cooldown nothing fires again
+        assert!(crate::alerts::due_alerts(&mut app).is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+// Standalone HTML report of a session (`--report out.html`): the session
+// summary serialized into a static page whose inline script draws the charts
+// and tables, so it opens anywhere without a server or network access.
+
+use std::io;
+use std::path::Path;
+use serde::Serialize;
+use crate::summary::{AlertEntry, Destination, RateSample, SessionSummary};
+
+const TEMPLATE: &str = include_str!("report.html");
+const PLACEHOLDER: &str = "/*REPORT_DATA*/null";
+const CHARTED_PROCESSES: usize = 8;
+const TOP_HOSTS: usize = 25;
+
+/// Where and when the session ran
+pub struct SessionInfo<'a> {
+    pub interface: &'a str,
+    pub started: chrono::DateTime<chrono::Local>,
+    pub filter: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct ReportProcess<'a> {
+    pid: i32,
+    name: &'a str,
+    sent: u64,
+    received: u64,
+    samples: &'a [RateSample], // Empty for processes past the charted ones, to keep the file small
+}
+
+#[derive(Serialize)]
+struct ReportData<'a> {
+    interface: &'a str,
+    started: String,
+    duration_secs: i64,
+    filter: Option<&'a str>,
+    sent: u64,
+    received: u64,
+    peak_sent_rate: u64,
+    peak_received_rate: u64,
+    timeline: &'a [RateSample],
+    processes: Vec<ReportProcess<'a>>,
+    destinations: Vec<Destination>,
+    alerts: &'a [AlertEntry],
+}
+
+/// Write the report for a finished session
+pub fn write(path: &Path, summary: &SessionSummary, session: &SessionInfo, alerts: &[AlertEntry]) -> io::Result<()> {
+    let (sent, received) = summary.totals();
+    let (peak_sent_rate, peak_received_rate) = summary.peak_rates();
+    let data = ReportData {
+        interface: session.interface,
+        started: session.started.format("%Y-%m-%d %H:%M:%S").to_string(),
+        duration_secs: (chrono::Local::now() - session.started).num_seconds(),
+        filter: session.filter,
+        sent,
+        received,
+        peak_sent_rate,
+        peak_received_rate,
+        timeline: summary.timeline(),
+        processes: summary.processes().into_iter().enumerate().map(|(rank, process)| ReportProcess {
+            pid: process.pid,
+            name: &process.name,
+            sent: process.sent,
+            received: process.received,
+            samples: if rank < CHARTED_PROCESSES { &process.samples } else { &[] },
+        }).collect(),
+        destinations: summary.top_destinations(TOP_HOSTS),
+        alerts,
+    };
+    // A process name containing "</script>" must not end the script early
+    let json = serde_json::to_string(&data)?.replace("</", "<\\/");
+    std::fs::write(path, TEMPLATE.replacen(PLACEHOLDER, &json, 1))
+}
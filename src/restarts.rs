@@ -0,0 +1,159 @@
+// Recognising a process that came back under a new PID, e.g. a service
+// restarted by systemd after an alert killed it. A process is known by its
+// executable and a hash of its command line, read when it first shows up.
+// When one exits or is killed, what it had (totals, chart history, alert
+// state) is kept for a while; a newcomer with the same identity takes it over,
+// unless another process with that identity is still running, which makes it
+// a sibling (another worker of a pool) rather than a restart.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime};
+use crate::alerts::AlertState;
+use crate::types::Alert;
+
+/// How long a departed process waits for its successor
+const REMEMBER_FOR: Duration = Duration::from_secs(15 * 60);
+
+/// Shown on a process that took over from an earlier PID
+#[derive(Clone, Debug, PartialEq)]
+pub struct Restart {
+    pub previous_pid: i32,
+    pub count: u32,    // Restarts this session, this one included
+    pub sent: u64,     // Carried over from before, added to what the capture counts
+    pub received: u64,
+}
+
+/// What a process leaves behind for its successor
+pub struct Departed {
+    pub pid: i32,
+    pub name: String,
+    pub sent: u64,
+    pub received: u64,
+    pub sent_history: Vec<(f64, f64)>,
+    pub received_history: Vec<(f64, f64)>,
+    pub first_seen: SystemTime,
+    pub restarts: u32,
+    pub alert: Option<Alert>,
+    pub alert_state: Option<AlertState>,
+    pub muted_until: Option<Instant>,
+    left: Instant,
+}
+
+impl Departed {
+    pub fn new(
+        pid: i32,
+        info: &crate::types::ProcessInfo,
+        alert: Option<Alert>,
+        alert_state: Option<AlertState>,
+        muted_until: Option<Instant>,
+    ) -> Self {
+        Departed {
+            pid,
+            name: info.name.clone(),
+            sent: info.sent,
+            received: info.received,
+            sent_history: info.sent_history.clone(),
+            received_history: info.received_history.clone(),
+            first_seen: info.first_seen,
+            restarts: info.restart.as_ref().map_or(0, |restart| restart.count),
+            alert,
+            alert_state,
+            muted_until,
+            left: Instant::now(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RestartTracker {
+    identities: HashMap<i32, u64>,    // Processes seen alive, by PID
+    departed: HashMap<u64, Departed>, // By identity, newest departure only
+}
+
+impl RestartTracker {
+    /// Record a process that just showed up; returns what its predecessor left
+    /// if it is a restart
+    pub fn arrived(&mut self, pid: i32, identity: u64) -> Option<Departed> {
+        self.departed.retain(|_, departed| departed.left.elapsed() < REMEMBER_FOR);
+        let sibling_running = self.identities.values().any(|known| *known == identity);
+        self.identities.insert(pid, identity);
+        if sibling_running {
+            return None;
+        }
+        self.departed.remove(&identity)
+    }
+
+    /// Whether `pid` was seen arriving and hasn't departed yet
+    pub fn knows(&self, pid: i32) -> bool {
+        self.identities.contains_key(&pid)
+    }
+
+    /// Keep what an exited or killed process had, for a restart to take over
+    pub fn depart(&mut self, departed: Departed) {
+        if let Some(identity) = self.identities.remove(&departed.pid) {
+            self.departed.insert(identity, departed);
+        }
+    }
+}
+
+/// Executable and command line of a running process, hashed
+#[cfg(target_os = "linux")]
+pub fn identity(pid: i32) -> Option<u64> {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let exe = std::fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    // An upgraded binary shows up as "(deleted)" for the processes still running the old one
+    let exe = exe.to_string_lossy();
+    let mut hasher = DefaultHasher::new();
+    exe.trim_end_matches(" (deleted)").hash(&mut hasher);
+    cmdline.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn identity(_pid: i32) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn departed(pid: i32, sent: u64) -> Departed {
+        Departed {
+            pid,
+            name: "nginx".to_string(),
+            sent,
+            received: 0,
+            sent_history: Vec::new(),
+            received_history: Vec::new(),
+            first_seen: SystemTime::now(),
+            restarts: 0,
+            alert: None,
+            alert_state: None,
+            muted_until: None,
+            left: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn a_newcomer_takes_over_only_without_a_running_sibling() {
+        let mut tracker = RestartTracker::default();
+        assert!(tracker.arrived(100, 7).is_none());
+        assert!(tracker.arrived(101, 7).is_none());
+
+        // One worker of two goes: its replacement is a sibling, not a restart
+        tracker.depart(departed(100, 5000));
+        assert!(tracker.arrived(102, 7).is_none());
+
+        // Once all are gone, the next one carries on from the last to leave
+        tracker.depart(departed(101, 6000));
+        tracker.depart(departed(102, 7000));
+        let before = tracker.arrived(200, 7).expect("a restart");
+        assert_eq!((before.pid, before.sent), (102, 7000));
+        assert!(tracker.knows(200));
+        assert!(tracker.arrived(300, 8).is_none());
+    }
+}
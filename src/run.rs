@@ -0,0 +1,128 @@
+// `monitetoring run -- <command>`: start a command once capture is running,
+// account only its process tree and print a summary when it exits, the way
+// time(1) does for CPU time.
+
+use std::sync::mpsc as std_mpsc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use crate::delta::StatsDelta;
+use crate::filter::ProcessFilter;
+use crate::summary::SessionSummary;
+
+/// How long packets and rates keep arriving after the command exited
+const DRAIN_TIME: Duration = Duration::from_millis(1500);
+
+/// Capture thread's side of the start-up handshake
+pub struct CaptureHandshake {
+    ready: std_mpsc::Sender<()>,
+    root: std_mpsc::Receiver<i32>,
+}
+
+/// The command's side of the start-up handshake
+pub struct CommandHandshake {
+    ready: std_mpsc::Receiver<()>,
+    root: std_mpsc::Sender<i32>,
+}
+
+pub fn handshake() -> (CaptureHandshake, CommandHandshake) {
+    let (ready_tx, ready_rx) = std_mpsc::channel();
+    let (root_tx, root_rx) = std_mpsc::channel();
+    (
+        CaptureHandshake { ready: ready_tx, root: root_rx },
+        CommandHandshake { ready: ready_rx, root: root_tx },
+    )
+}
+
+impl CaptureHandshake {
+    /// Report that packets are being captured, then restrict the filter to the
+    /// command's process tree. Returns false if the command never started.
+    pub fn wait(self, filter: &mut ProcessFilter) -> bool {
+        let _ = self.ready.send(());
+        match self.root.recv() {
+            Ok(pid) => {
+                filter.follow_tree(pid);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Start the command once capture is running, so none of its traffic is missed.
+/// Under sudo it runs as the user who ran sudo unless `as_root` is set.
+pub fn spawn(handshake: CommandHandshake, command: &[String], as_root: bool) -> Result<tokio::process::Child, String> {
+    handshake.ready.recv().map_err(|_| "Packet capture did not start".to_string())?;
+    let mut process = tokio::process::Command::new(&command[0]);
+    process.args(&command[1..]);
+    #[cfg(target_os = "linux")]
+    if let Some((uid, gid)) = sudo_user().filter(|_| !as_root) {
+        process.uid(uid).gid(gid);
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = as_root;
+    let child = process.spawn().map_err(|e| format!("Failed to start {}: {}", command[0], e))?;
+    if let Some(pid) = child.id() {
+        let _ = handshake.root.send(pid as i32);
+    }
+    Ok(child)
+}
+
+/// The user who ran sudo, when monitetoring runs as root through it
+#[cfg(target_os = "linux")]
+fn sudo_user() -> Option<(u32, u32)> {
+    if unsafe { libc::geteuid() } != 0 {
+        return None;
+    }
+    let id = |name: &str| std::env::var(name).ok().and_then(|id| id.parse::<u32>().ok()).filter(|id| *id != 0);
+    Some((id("SUDO_UID")?, id("SUDO_GID")?))
+}
+
+/// How the command ended, and the exit code to pass on
+fn describe_exit(status: std::process::ExitStatus) -> (String, i32) {
+    if let Some(code) = status.code() {
+        return (code.to_string(), code);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return (format!("killed by signal {}", signal), 128 + signal);
+        }
+    }
+    (status.to_string(), 1)
+}
+
+/// Collect updates until the command exits, print the summary and return the
+/// command's exit code. Ctrl+C reaches the command too; monitoring carries on
+/// until it has exited.
+pub async fn monitor(mut rx: mpsc::Receiver<StatsDelta>, mut child: tokio::process::Child, command: &[String]) -> i32 {
+    let start = Instant::now();
+    let mut summary = SessionSummary::new();
+    let status = loop {
+        tokio::select! {
+            status = child.wait() => break status,
+            Some(delta) = rx.recv() => summary.observe(&delta),
+            _ = tokio::signal::ctrl_c() => {}
+        }
+    };
+    let elapsed = start.elapsed();
+
+    // The last packets and rate update are still on their way
+    let deadline = tokio::time::Instant::now() + DRAIN_TIME;
+    while let Ok(Some(delta)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+        summary.observe(&delta);
+    }
+
+    let (status, code) = match status {
+        Ok(status) => describe_exit(status),
+        Err(e) => (format!("unknown ({})", e), 1),
+    };
+    let heading = [
+        format!("Network usage of: {}", command.join(" ")),
+        format!("Exit status: {}", status),
+        format!("Duration:    {:.1} s", elapsed.as_secs_f64()),
+    ];
+    eprintln!();
+    eprint!("{}", summary.report(&heading, None));
+    code
+}
@@ -0,0 +1,146 @@
+// Bounded runs for unattended measurements: `--duration` stops monitoring
+// after a while, `--schedule` waits for a daily time window (local time) and
+// stops when it closes. Either way the run ends the way quitting would, with
+// the JSON output or summary written on the way out.
+
+use std::str::FromStr;
+use std::time::Duration;
+use chrono::{NaiveTime, Timelike};
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// Parse a duration such as "90" (seconds), "45s", "10m", "2h" or "1h30m"
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err("Enter a duration, e.g. 10m".to_string());
+    }
+    if let Ok(secs) = input.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => DAY_SECS as u64,
+            _ => return Err(format!("Unknown unit '{}' in '{}' (use s, m, h or d)", c, input)),
+        };
+        let value: u64 = number.parse().map_err(|_| format!("'{}' is not a duration, e.g. 1h30m", input))?;
+        total = value.checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| format!("'{}' is too long", input))?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        return Err(format!("'{}' needs a unit after {}, e.g. {}m", input, number, number));
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// A daily time window such as "02:00-03:00"; "23:00-01:00" runs past midnight
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CaptureWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl FromStr for CaptureWindow {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        let (start, end) = input.split_once('-')
+            .ok_or_else(|| format!("'{}' is not a time window, e.g. 02:00-03:00", input))?;
+        let time = |text: &str| NaiveTime::parse_from_str(text.trim(), "%H:%M")
+            .map_err(|_| format!("'{}' is not a time of day (HH:MM)", text.trim()));
+        let window = CaptureWindow { start: time(start)?, end: time(end)? };
+        if window.start == window.end {
+            return Err(format!("The window '{}' is empty", input));
+        }
+        Ok(window)
+    }
+}
+
+impl CaptureWindow {
+    /// How long until the window opens (zero when `now` is inside it) and how
+    /// long it then stays open
+    pub fn next(&self, now: NaiveTime) -> (Duration, Duration) {
+        let secs = |time: NaiveTime| time.num_seconds_from_midnight() as i64;
+        let (start, end, now) = (secs(self.start), secs(self.end), secs(now));
+        let open_for = |from: i64| Duration::from_secs((end - from).rem_euclid(DAY_SECS) as u64);
+        let inside = if start < end {
+            (start..end).contains(&now)
+        } else {
+            now >= start || now < end
+        };
+        if inside {
+            (Duration::ZERO, open_for(now))
+        } else {
+            (Duration::from_secs((start - now).rem_euclid(DAY_SECS) as u64), open_for(start))
+        }
+    }
+}
+
+impl std::fmt::Display for CaptureWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start.format("%H:%M"), self.end.format("%H:%M"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("45s"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(10 * MINUTE)));
+        assert_eq!(parse_duration("2H"), Ok(Duration::from_secs(2 * HOUR)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(HOUR + 30 * MINUTE)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("99999999999999999d").is_err());
+        assert!(parse_duration("18446744073709551615s1s").is_err());
+    }
+
+    #[test]
+    fn parses_windows() {
+        let window: CaptureWindow = "02:00-03:30".parse().unwrap();
+        assert_eq!(window.to_string(), "02:00-03:30");
+        assert!("02:00".parse::<CaptureWindow>().is_err());
+        assert!("25:00-03:00".parse::<CaptureWindow>().is_err());
+        assert!("02:00-02:00".parse::<CaptureWindow>().is_err());
+    }
+
+    #[test]
+    fn waits_for_the_next_window() {
+        let window: CaptureWindow = "02:00-03:00".parse().unwrap();
+        assert_eq!(window.next(at(1, 0)), (Duration::from_secs(HOUR), Duration::from_secs(HOUR)));
+        assert_eq!(window.next(at(2, 15)), (Duration::ZERO, Duration::from_secs(45 * MINUTE)));
+        assert_eq!(window.next(at(3, 0)), (Duration::from_secs(23 * HOUR), Duration::from_secs(HOUR)));
+    }
+
+    #[test]
+    fn windows_run_past_midnight() {
+        let window: CaptureWindow = "23:00-01:00".parse().unwrap();
+        assert_eq!(window.next(at(23, 30)), (Duration::ZERO, Duration::from_secs(90 * MINUTE)));
+        assert_eq!(window.next(at(0, 30)), (Duration::ZERO, Duration::from_secs(30 * MINUTE)));
+        assert_eq!(window.next(at(12, 0)), (Duration::from_secs(11 * HOUR), Duration::from_secs(2 * HOUR)));
+    }
+}
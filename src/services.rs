@@ -0,0 +1,75 @@
+// Inbound traffic grouped by local listening port. Servers often hand
+// accepted connections to worker processes (nginx workers, postgres
+// backends), so per-process numbers scatter one service over many rows;
+// here every connection on a listening port counts towards the process that
+// owns the listener.
+
+use std::collections::{HashMap, HashSet};
+use crate::types::{Connection, ProcessIdentifier, ServiceTraffic};
+
+/// UDP sockets bound below this port are taken as services; above it they are
+/// usually the ephemeral ports of clients (Linux allocates from 32768 up)
+const UDP_SERVICE_PORT_LIMIT: u16 = 32_768;
+
+#[derive(Default)]
+pub struct ServiceTracker {
+    listeners: HashMap<(u8, u16), ProcessIdentifier>, // (protocol, port) -> owner of the listening socket
+    services: HashMap<(u8, u16), ServiceTraffic>,
+    connections: HashMap<(u8, u16), HashSet<Connection>>,
+    workers: HashMap<(u8, u16), HashSet<i32>>,
+    previous: HashMap<(u8, u16), (u64, u64)>, // (sent, received) at the last rate calculation
+}
+
+impl ServiceTracker {
+    /// Pick the listening sockets out of freshly refreshed maps. TCP listeners
+    /// and unconnected UDP sockets have no remote end.
+    pub fn refresh_listeners(&mut self, conn_map: &HashMap<Connection, u64>, inode_map: &HashMap<u64, ProcessIdentifier>) {
+        self.listeners.clear();
+        for (conn, inode) in conn_map {
+            if conn.dest_port != 0 || !conn.dest_ip.is_unspecified() {
+                continue;
+            }
+            if conn.protocol == 17 && conn.source_port >= UDP_SERVICE_PORT_LIMIT {
+                continue;
+            }
+            if let Some(owner) = inode_map.get(inode) {
+                self.listeners.entry((conn.protocol, conn.source_port)).or_insert_with(|| owner.clone());
+            }
+        }
+    }
+
+    /// Count a packet of a connection oriented local -> remote, handled by `pid`
+    pub fn record(&mut self, conn: &Connection, outbound: bool, bytes: u64, pid: i32) {
+        let key = (conn.protocol, conn.source_port);
+        let Some(owner) = self.listeners.get(&key) else { return };
+        let service = self.services.entry(key).or_insert_with(|| ServiceTraffic::new(key.0, key.1, owner));
+        // A restarted server keeps its port; show whoever listens now
+        if service.pid != owner.pid {
+            service.pid = owner.pid;
+            service.name = owner.name.clone();
+        }
+        if outbound {
+            service.sent += bytes;
+        } else {
+            service.received += bytes;
+        }
+        let connections = self.connections.entry(key).or_default();
+        if connections.insert(*conn) {
+            service.connections = connections.len();
+        }
+        let workers = self.workers.entry(key).or_default();
+        if workers.insert(pid) {
+            service.workers = workers.len();
+        }
+    }
+
+    /// Update the rates over the last `interval` seconds and return every service seen
+    pub fn snapshot(&mut self, interval: f64) -> Vec<ServiceTraffic> {
+        for (key, service) in self.services.iter_mut() {
+            let (sent, received) = self.previous.insert(*key, (service.sent, service.received)).unwrap_or((0, 0));
+            service.sent_rate = (service.sent.saturating_sub(sent) as f64 / interval) as u64;
+            service.received_rate = (service.received.saturating_sub(received) as f64 / interval) as u64;
+        }
+        self.services.values().cloned().collect()
+    }
+}
@@ -0,0 +1,37 @@
+// Per-process TCP socket state breakdown. Sockets in TIME_WAIT no longer
+// belong to any process, so they are attributed to whoever owned the same
+// connection on an earlier read, or to the process listening on their port.
+
+use std::collections::HashMap;
+use crate::types::{Connection, SocketStates, TcpSocket, TcpSocketState};
+
+/// Connection owners from the previous read, kept across refreshes
+#[derive(Default)]
+pub struct SocketOwners {
+    owners: HashMap<Connection, i32>,
+}
+
+impl SocketOwners {
+    /// Count the sockets of every process, remembering owners for the next read
+    pub fn count(&mut self, sockets: &[TcpSocket]) -> HashMap<i32, SocketStates> {
+        // Server-side TIME_WAIT sockets keep the local port of the listener
+        let listeners: HashMap<u16, i32> = sockets
+            .iter()
+            .filter(|socket| socket.state == TcpSocketState::Listen)
+            .filter_map(|socket| Some((socket.conn.source_port, socket.owner?)))
+            .collect();
+
+        let mut owners = HashMap::new();
+        let mut counts: HashMap<i32, SocketStates> = HashMap::new();
+        for socket in sockets {
+            let owner = socket.owner
+                .or_else(|| self.owners.get(&socket.conn).copied())
+                .or_else(|| listeners.get(&socket.conn.source_port).copied());
+            let Some(pid) = owner else { continue };
+            owners.insert(socket.conn, pid);
+            counts.entry(pid).or_default().add(socket.state);
+        }
+        self.owners = owners;
+        counts
+    }
+}
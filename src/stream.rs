@@ -0,0 +1,213 @@
+// Binary snapshot stream for external frontends.
+//
+// Every update received from the capture thread is folded into the latest
+// snapshot, which is encoded as one frame:
+// a 4-byte big-endian length followed by a MessagePack map (`StreamFrame`,
+// field names included). Frames go to stdout or to every client connected
+// to a TCP listener; the web dashboard consumes the same frames as JSON.
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use crate::delta::StatsDelta;
+use crate::traffic_stats::{self, SharedTraffic, TrafficSnapshot};
+use crate::types::{ProcessInfo, ProcessInfoJson};
+
+/// Bumped whenever a field is removed or changes meaning
+pub const STREAM_PROTOCOL_VERSION: u8 = 1;
+
+/// Port `--agent` listens on and `--aggregate` connects to unless told otherwise
+pub const DEFAULT_AGENT_PORT: u16 = 7878;
+
+/// Frames buffered per consumer before it starts skipping snapshots
+pub const CLIENT_BUFFER_FRAMES: usize = 16;
+
+/// Where the snapshot stream is written
+pub enum StreamTarget {
+    Stdout,
+    Tcp(String),
+}
+
+impl StreamTarget {
+    /// Parse the `--stream` argument: `-`/`stdout`, or a TCP listen address
+    pub fn parse(value: &str) -> StreamTarget {
+        match value {
+            "-" | "stdout" => StreamTarget::Stdout,
+            addr => StreamTarget::Tcp(addr.strip_prefix("tcp://").unwrap_or(addr).to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct StreamTotals {
+    pub sent_bytes: u64,
+    pub received_bytes: u64,
+    pub sent_rate_bytes: u64,
+    pub received_rate_bytes: u64,
+}
+
+/// One per-tick snapshot of the process table
+#[derive(Serialize)]
+pub struct StreamFrame {
+    pub version: u8,
+    pub timestamp_ms: u64,
+    pub totals: StreamTotals,
+    pub processes: Vec<ProcessInfoJson>,
+    /// Everything the capture saw, by protocol and interface; left out without a capture
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub traffic: Option<TrafficSnapshot>,
+}
+
+impl StreamFrame {
+    pub fn from_stats(stats: &HashMap<i32, ProcessInfo>, traffic: &TrafficSnapshot) -> Self {
+        let mut processes: Vec<ProcessInfoJson> = stats
+            .iter()
+            .map(|(pid, info)| ProcessInfoJson::from((pid, info)))
+            .collect();
+        processes.sort_by_key(|p| p.pid);
+
+        // VPN daemons are left out, their traffic is already counted inside the tunnel
+        let counted = || processes.iter().filter(|p| !p.tunnel);
+        let totals = StreamTotals {
+            sent_bytes: counted().map(|p| p.sent_bytes).sum(),
+            received_bytes: counted().map(|p| p.received_bytes).sum(),
+            sent_rate_bytes: counted().map(|p| p.sent_rate_bytes).sum(),
+            received_rate_bytes: counted().map(|p| p.received_rate_bytes).sum(),
+        };
+
+        StreamFrame {
+            version: STREAM_PROTOCOL_VERSION,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            totals,
+            processes,
+            traffic: (!traffic.is_empty()).then(|| traffic.clone()),
+        }
+    }
+
+    /// Encode as a length-prefixed MessagePack frame
+    pub fn encode(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+        let body = rmp_serde::to_vec_named(self)?;
+        let mut frame = Vec::with_capacity(body.len() + 4);
+        frame.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&body);
+        Ok(frame)
+    }
+}
+
+/// Fan-out of snapshot frames to every consumer (stream clients, web dashboard)
+pub type FrameSender = broadcast::Sender<Arc<StreamFrame>>;
+
+/// Start writing frames from `frames` to the stream target. For stdout, the
+/// returned receiver resolves once nothing reads the frames any more, which
+/// ends `run`.
+pub async fn start(target: StreamTarget, frames: &FrameSender) -> io::Result<Option<oneshot::Receiver<()>>> {
+    match target {
+        StreamTarget::Stdout => {
+            let mut frames = frames.subscribe();
+            let (reader_gone_tx, reader_gone) = oneshot::channel();
+            tokio::spawn(async move {
+                let mut stdout = tokio::io::stdout();
+                loop {
+                    let frame = match frames.recv().await {
+                        Ok(frame) => frame,
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    };
+                    let Ok(bytes) = frame.encode() else { continue };
+                    if stdout.write_all(&bytes).await.is_err() || stdout.flush().await.is_err() {
+                        // Reader went away (e.g. `| head`), nothing left to stream to
+                        break;
+                    }
+                }
+                let _ = reader_gone_tx.send(());
+            });
+            Ok(Some(reader_gone))
+        }
+        StreamTarget::Tcp(addr) => {
+            let listener = TcpListener::bind(&addr).await?;
+            let local = listener.local_addr()?;
+            eprintln!("📡 Streaming snapshots on tcp://{}", local);
+            if !local.ip().is_loopback() {
+                eprintln!("⚠️  The stream has no authentication: anyone who can reach {} sees every process, user and remote host", local);
+            }
+
+            let accept_tx = frames.clone();
+            tokio::spawn(async move {
+                while let Ok((mut socket, peer)) = listener.accept().await {
+                    let mut frames = accept_tx.subscribe();
+                    eprintln!("🔌 Stream client connected: {}", peer);
+                    tokio::spawn(async move {
+                        loop {
+                            match frames.recv().await {
+                                Ok(frame) => {
+                                    let Ok(bytes) = frame.encode() else { continue };
+                                    if socket.write_all(&bytes).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                // Slow client: skip the frames it missed and carry on
+                                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                                Err(broadcast::error::RecvError::Closed) => break,
+                            }
+                        }
+                        eprintln!("🔌 Stream client disconnected: {}", peer);
+                    });
+                }
+            });
+            Ok(None)
+        }
+    }
+}
+
+/// How often exited processes are dropped from the snapshot, like the capture thread does
+const PRUNE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Resolves when `reader_gone` does; never without one
+async fn reader_left(reader_gone: &mut Option<oneshot::Receiver<()>>) {
+    match reader_gone {
+        Some(receiver) => {
+            let _ = receiver.await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Turn updates from the capture thread into full snapshot frames until capture
+/// stops, or until the stdout reader from `start` has gone away.
+/// With `local_pids`, processes that exited leave the frames once the producer forgot
+/// them; demo and remote PIDs aren't this machine's to check.
+pub async fn run(
+    mut rx: mpsc::Receiver<StatsDelta>,
+    frames: FrameSender,
+    traffic: SharedTraffic,
+    local_pids: bool,
+    mut reader_gone: Option<oneshot::Receiver<()>>,
+) {
+    // Frames only need the counters, so packet histories are never mirrored here
+    let mut stats: HashMap<i32, ProcessInfo> = HashMap::new();
+    let mut last_prune = Instant::now();
+    loop {
+        let delta = tokio::select! {
+            delta = rx.recv() => delta,
+            _ = reader_left(&mut reader_gone) => None,
+        };
+        let Some(delta) = delta else { break };
+        for (pid, update) in delta {
+            stats.insert(pid, update.info);
+        }
+        if local_pids && last_prune.elapsed() >= PRUNE_INTERVAL {
+            stats.retain(|pid, _| crate::process::is_process_alive(*pid));
+            last_prune = Instant::now();
+        }
+        // No subscribers is fine; frames are simply dropped
+        let _ = frames.send(Arc::new(StreamFrame::from_stats(&stats, &traffic_stats::latest(&traffic))));
+    }
+}
@@ -0,0 +1,215 @@
+// End-of-session report: totals, peak rates, the busiest processes and
+// destinations and what fired. Built from the same updates the consumers
+// receive, so processes that exited during the session still count.
+// Destinations come from the recorded packets, so traffic the packet budget
+// evicted before it was delivered is missing from them.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use crate::delta::StatsDelta;
+use crate::types::PacketDirection;
+use crate::units::{format_bytes, format_rate};
+
+const TOP_PROCESSES: usize = 10;
+const TOP_DESTINATIONS: usize = 10;
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One rate sample: (seconds since the start, sent bytes/s, received bytes/s)
+pub type RateSample = (u32, u64, u64);
+
+/// Latest counters of one process, plus its rates over the session
+pub struct ProcessTotals {
+    pub pid: i32,
+    pub name: String,
+    pub sent: u64,
+    pub received: u64,
+    sent_rate: u64,
+    received_rate: u64,
+    tunnel: bool, // A VPN daemon, left out of the totals
+    pub samples: Vec<RateSample>, // Only seconds with traffic
+}
+
+/// Bytes exchanged with one remote endpoint
+#[derive(Serialize)]
+pub struct Destination {
+    pub host: IpAddr,
+    pub port: u16,
+    pub protocol: &'static str,
+    pub bytes: u64,
+}
+
+/// An alert, kill, quota breach, anomaly or annotation of the session
+#[derive(Serialize)]
+pub struct AlertEntry {
+    pub time: String, // HH:MM:SS
+    pub seconds: u32, // Since the start of the session, to place it on the charts
+    pub kind: &'static str,
+    pub message: String,
+}
+
+pub struct SessionSummary {
+    start: Instant,
+    last_sample: Option<Instant>,
+    processes: HashMap<i32, ProcessTotals>,
+    peak_sent_rate: u64, // Highest combined rate of all processes, bytes per second
+    peak_received_rate: u64,
+    timeline: Vec<RateSample>, // Combined rates of all processes
+    destinations: HashMap<(IpAddr, u16, u8), u64>, // (remote host, port, protocol) -> bytes both ways
+}
+
+fn protocol_name(protocol: u8) -> &'static str {
+    match protocol {
+        6 => "TCP",
+        17 => "UDP",
+        _ => "IP",
+    }
+}
+
+impl SessionSummary {
+    pub fn new() -> Self {
+        SessionSummary {
+            start: Instant::now(),
+            last_sample: None,
+            processes: HashMap::new(),
+            peak_sent_rate: 0,
+            peak_received_rate: 0,
+            timeline: Vec::new(),
+            destinations: HashMap::new(),
+        }
+    }
+
+    /// Fold in one update from the capture thread
+    pub fn observe(&mut self, delta: &StatsDelta) {
+        for (pid, update) in delta {
+            for packet in &update.new_packets {
+                let (host, port) = match packet.direction {
+                    PacketDirection::Sent => (packet.dst_ip, packet.dst_port),
+                    PacketDirection::Received => (packet.src_ip, packet.src_port),
+                };
+                *self.destinations.entry((host, port, packet.protocol)).or_default() += packet.size as u64;
+            }
+            let totals = self.processes.entry(*pid).or_insert_with(|| ProcessTotals {
+                pid: *pid,
+                name: String::new(),
+                sent: 0,
+                received: 0,
+                sent_rate: 0,
+                received_rate: 0,
+                tunnel: false,
+                samples: Vec::new(),
+            });
+            totals.name.clone_from(&update.info.name);
+            totals.sent = update.info.sent;
+            totals.received = update.info.received;
+            totals.sent_rate = update.info.sent_rate;
+            totals.received_rate = update.info.received_rate;
+            totals.tunnel = update.info.tunnel;
+        }
+        // Rates are recalculated once per second, so the latest ones of every process add up
+        let sent_rate = self.counted().map(|p| p.sent_rate).sum();
+        let received_rate = self.counted().map(|p| p.received_rate).sum();
+        self.peak_sent_rate = self.peak_sent_rate.max(sent_rate);
+        self.peak_received_rate = self.peak_received_rate.max(received_rate);
+
+        if self.last_sample.is_none_or(|last| last.elapsed() >= SAMPLE_INTERVAL) {
+            self.last_sample = Some(Instant::now());
+            let seconds = self.start.elapsed().as_secs() as u32;
+            self.timeline.push((seconds, sent_rate, received_rate));
+            for process in self.processes.values_mut() {
+                if process.sent_rate > 0 || process.received_rate > 0 {
+                    process.samples.push((seconds, process.sent_rate, process.received_rate));
+                }
+            }
+        }
+    }
+
+    /// Processes whose traffic counts towards the totals: all but VPN daemons,
+    /// whose traffic is the tunnelled copy of the others'
+    fn counted(&self) -> impl Iterator<Item = &ProcessTotals> {
+        self.processes.values().filter(|p| !p.tunnel)
+    }
+
+    pub fn totals(&self) -> (u64, u64) {
+        let sent = self.counted().map(|p| p.sent).sum();
+        let received = self.counted().map(|p| p.received).sum();
+        (sent, received)
+    }
+
+    pub fn peak_rates(&self) -> (u64, u64) {
+        (self.peak_sent_rate, self.peak_received_rate)
+    }
+
+    pub fn timeline(&self) -> &[RateSample] {
+        &self.timeline
+    }
+
+    /// Every process seen, most traffic first
+    pub fn processes(&self) -> Vec<&ProcessTotals> {
+        let mut processes: Vec<&ProcessTotals> = self.processes.values().collect();
+        processes.sort_by_key(|p| (std::cmp::Reverse(p.sent + p.received), p.pid));
+        processes
+    }
+
+    /// The `limit` remote endpoints with the most traffic
+    pub fn top_destinations(&self, limit: usize) -> Vec<Destination> {
+        let mut destinations: Vec<(&(IpAddr, u16, u8), &u64)> = self.destinations.iter().collect();
+        destinations.sort_by_key(|(key, bytes)| (std::cmp::Reverse(**bytes), **key));
+        destinations
+            .into_iter()
+            .take(limit)
+            .map(|((host, port, protocol), bytes)| Destination {
+                host: *host,
+                port: *port,
+                protocol: protocol_name(*protocol),
+                bytes: *bytes,
+            })
+            .collect()
+    }
+
+    /// Plain-text report below the given heading lines. The alerts section is
+    /// left out when `alerts` is None.
+    pub fn report(&self, heading: &[String], alerts: Option<&[AlertEntry]>) -> String {
+        let mut lines: Vec<String> = heading.to_vec();
+        let (sent, received) = self.totals();
+        lines.push(String::new());
+        lines.push(format!("Total:      {} sent, {} received", format_bytes(sent), format_bytes(received)));
+        lines.push(format!(
+            "Peak rate:  {} sent, {} received",
+            format_rate(self.peak_sent_rate), format_rate(self.peak_received_rate)
+        ));
+
+        let processes = self.processes();
+        lines.push(String::new());
+        lines.push(format!("Top processes ({} seen):", processes.len()));
+        for process in processes.into_iter().take(TOP_PROCESSES) {
+            lines.push(format!(
+                "  {:<24} {:>8}  {:>10} sent  {:>10} received",
+                process.name, process.pid, format_bytes(process.sent), format_bytes(process.received)
+            ));
+        }
+
+        let destinations = self.top_destinations(TOP_DESTINATIONS);
+        lines.push(String::new());
+        lines.push("Top destinations:".to_string());
+        if destinations.is_empty() {
+            lines.push("  none recorded".to_string());
+        }
+        for destination in destinations {
+            let address = SocketAddr::new(destination.host, destination.port).to_string();
+            lines.push(format!("  {:<46} {:<4} {:>10}", address, destination.protocol, format_bytes(destination.bytes)));
+        }
+
+        if let Some(alerts) = alerts {
+            lines.push(String::new());
+            lines.push(format!("Alerts ({}):", alerts.len()));
+            if alerts.is_empty() {
+                lines.push("  none triggered".to_string());
+            }
+            lines.extend(alerts.iter().map(|alert| format!("  {}  {}", alert.time, alert.message)));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,98 @@
+use std::net::SocketAddr;
+use std::process::Command;
+use crate::types::TcpCongestionInfo;
+
+/// Bare option words `ss -i` prints before the congestion control algorithm
+const SOCKET_FLAGS: [&str; 5] = ["ts", "sack", "ecn", "ecnseen", "fastopen"];
+
+/// Query the kernel's TCP_INFO for one socket through `ss -ti`, which reads it
+/// over sock_diag and knows how to print every field
+pub fn read_tcp_info(local: SocketAddr, remote: SocketAddr) -> Result<TcpCongestionInfo, String> {
+    let output = Command::new("ss")
+        .args(["-tinH", "src", &local.to_string(), "dst", &remote.to_string()])
+        .output()
+        .map_err(|e| format!("Could not run ss (part of iproute2): {}", e))?;
+    if !output.status.success() {
+        return Err(format!("ss failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    // One line with the socket addresses, followed by an indented line of TCP_INFO fields
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .find(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty())
+        .map(parse_info_line)
+        .ok_or_else(|| "Socket not found; the connection may have closed".to_string())
+}
+
+/// Pick the congestion control fields out of an `ss -i` info line
+fn parse_info_line(line: &str) -> TcpCongestionInfo {
+    let mut info = TcpCongestionInfo::default();
+    let mut tokens = line.split_whitespace();
+    while let Some(token) = tokens.next() {
+        match token.split_once(':') {
+            Some(("cwnd", value)) => info.cwnd = value.parse().ok(),
+            Some(("ssthresh", value)) => info.ssthresh = value.parse().ok(),
+            Some(("mss", value)) => info.mss = value.parse().ok(),
+            Some(("rtt", value)) => info.rtt_ms = value.split('/').next().and_then(|rtt| rtt.parse().ok()),
+            Some(("retrans", value)) => info.retrans = Some(value.to_string()),
+            Some(_) => {}
+            None => match token {
+                "send" => info.send_rate = tokens.next().map(str::to_string),
+                "pacing_rate" => info.pacing_rate = tokens.next().map(str::to_string),
+                "delivery_rate" => info.delivery_rate = tokens.next().map(str::to_string),
+                // The algorithm is the first bare word after the option flags
+                name if info.algorithm.is_none()
+                    && !SOCKET_FLAGS.contains(&name)
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+                {
+                    info.algorithm = Some(name.to_string());
+                }
+                _ => {}
+            },
+        }
+    }
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_loopback_connection() {
+        let line = "\t cubic wscale:7,7 rto:204 rtt:0.025/0.012 ato:40 mss:32768 pmtu:65535 rcvmss:536 advmss:65483 \
+            cwnd:10 bytes_sent:86 bytes_acked:87 bytes_received:85 segs_out:4 segs_in:3 data_segs_out:1 data_segs_in:1 \
+            send 104857600000bps lastsnd:1234 lastrcv:1234 lastack:1234 pacing_rate 209715200000bps \
+            delivery_rate 13107200000bps delivered:2 app_limited busy:1ms rcv_space:65483 minrtt:0.025 snd_wnd:65536";
+        let info = parse_info_line(line);
+        assert_eq!(info.algorithm.as_deref(), Some("cubic"));
+        assert_eq!((info.cwnd, info.ssthresh, info.mss), (Some(10), None, Some(32768)));
+        assert_eq!(info.rtt_ms, Some(0.025));
+        assert_eq!(info.send_rate.as_deref(), Some("104857600000bps"));
+        assert_eq!(info.pacing_rate.as_deref(), Some("209715200000bps"));
+        assert_eq!(info.delivery_rate.as_deref(), Some("13107200000bps"));
+        assert_eq!(info.retrans, None);
+    }
+
+    #[test]
+    fn skips_option_flags_before_the_algorithm() {
+        let line = "\t ts sack ecn bbr wscale:9,7 rto:232 rtt:31.5/4.25 ato:40 mss:1448 pmtu:1500 rcvmss:1448 \
+            advmss:1448 cwnd:42 ssthresh:28 bytes_sent:1843200 bytes_retrans:7240 send 15.4Mbps pacing_rate 18.5Mbps \
+            delivery_rate 12.1Mbps delivered:1230 busy:4120ms retrans:0/5 reordering:4";
+        let info = parse_info_line(line);
+        assert_eq!(info.algorithm.as_deref(), Some("bbr"));
+        assert_eq!((info.cwnd, info.ssthresh, info.mss), (Some(42), Some(28), Some(1448)));
+        assert_eq!(info.rtt_ms, Some(31.5));
+        assert_eq!(info.send_rate.as_deref(), Some("15.4Mbps"));
+        assert_eq!(info.retrans.as_deref(), Some("0/5"));
+    }
+
+    #[test]
+    fn leaves_missing_fields_unset() {
+        let info = parse_info_line("\t wscale:7,7 rto:1000 mss:524");
+        assert_eq!(info.algorithm, None);
+        assert_eq!((info.cwnd, info.mss), (None, Some(524)));
+        assert_eq!((info.rtt_ms, info.send_rate), (None, None));
+    }
+}
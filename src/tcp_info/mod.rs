@@ -0,0 +1,12 @@
+// Congestion control state of a single TCP connection (algorithm, cwnd,
+// pacing rate), shown for the top packet row in Packet Details.
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+pub use linux::*;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+pub use windows::*;
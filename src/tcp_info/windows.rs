@@ -0,0 +1,8 @@
+use std::net::SocketAddr;
+use crate::types::TcpCongestionInfo;
+
+/// Windows only exposes these through extended TCP statistics that must be
+/// enabled per connection beforehand, so there is nothing to read
+pub fn read_tcp_info(_local: SocketAddr, _remote: SocketAddr) -> Result<TcpCongestionInfo, String> {
+    Err("TCP congestion info is only available on Linux".to_string())
+}
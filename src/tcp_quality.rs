@@ -0,0 +1,84 @@
+// Per-process TCP loss and latency from the TCP_INFO counters of each
+// connection (Linux). RTTs are averaged over a process's established
+// connections; retransmits are counted as the growth of each connection's
+// lifetime counter between two refreshes, so the totals start at zero when
+// monitoring starts. Connections that open and close between two refreshes
+// are not seen.
+
+use std::collections::HashMap;
+use crate::types::{Connection, TcpQuality, TcpSample, TcpSocket, TcpSocketState};
+
+#[derive(Default)]
+pub struct TcpQualityTracker {
+    previous: HashMap<Connection, TcpSample>,
+    retransmits: HashMap<i32, u64>, // Kept while a process has no connections, so its total doesn't restart
+    seeded: bool, // Connections seen on the first read only count retransmits from then on
+}
+
+#[derive(Default)]
+struct Totals {
+    rtt_sum_ms: f64,
+    rtt_count: u32,
+    max_rtt_ms: Option<f64>,
+    retransmitted: u64, // Since the previous refresh
+    sent: u64,
+}
+
+impl TcpQualityTracker {
+    /// Fold a fresh read of the socket tables into per-process metrics. Only
+    /// processes owning a TCP socket with counters get an entry; see
+    /// `retransmits` for the others.
+    pub fn update(&mut self, sockets: &[TcpSocket]) -> HashMap<i32, TcpQuality> {
+        let mut totals: HashMap<i32, Totals> = HashMap::new();
+        let mut current = HashMap::new();
+        for socket in sockets {
+            let (Some(pid), Some(sample)) = (socket.owner, socket.sample) else { continue };
+            current.insert(socket.conn, sample);
+            let entry = totals.entry(pid).or_default();
+
+            // Listening sockets and connections still in the handshake have no RTT yet
+            if socket.state == TcpSocketState::Established && sample.rtt_us > 0 {
+                let rtt_ms = sample.rtt_us as f64 / 1000.0;
+                entry.rtt_sum_ms += rtt_ms;
+                entry.rtt_count += 1;
+                entry.max_rtt_ms = Some(entry.max_rtt_ms.map_or(rtt_ms, |max| max.max(rtt_ms)));
+            }
+
+            let (retransmitted, sent) = match self.previous.get(&socket.conn) {
+                Some(previous) => (
+                    sample.total_retrans.saturating_sub(previous.total_retrans),
+                    sample.segs_out.saturating_sub(previous.segs_out),
+                ),
+                None if self.seeded => (sample.total_retrans, sample.segs_out),
+                None => (0, 0),
+            };
+            entry.retransmitted += retransmitted as u64;
+            entry.sent += sent as u64;
+        }
+        self.previous = current;
+        self.seeded = true;
+        // Only processes that ever retransmitted need their total remembered
+        self.retransmits.retain(|pid, count| *count > 0 || totals.contains_key(pid));
+
+        totals
+            .into_iter()
+            .map(|(pid, totals)| {
+                let retransmits = self.retransmits.entry(pid).or_default();
+                *retransmits += totals.retransmitted;
+                let quality = TcpQuality {
+                    avg_rtt_ms: (totals.rtt_count > 0).then(|| totals.rtt_sum_ms / totals.rtt_count as f64),
+                    max_rtt_ms: totals.max_rtt_ms,
+                    retransmits: *retransmits,
+                    retransmit_percent: (totals.sent > 0)
+                        .then(|| totals.retransmitted as f64 * 100.0 / totals.sent as f64),
+                };
+                (pid, quality)
+            })
+            .collect()
+    }
+
+    /// Retransmits counted so far for a process without measured connections
+    pub fn retransmits(&self, pid: i32) -> u64 {
+        self.retransmits.get(&pid).copied().unwrap_or(0)
+    }
+}
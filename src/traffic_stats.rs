@@ -0,0 +1,237 @@
+// Capture-wide traffic statistics, counted in the capture thread from every
+// packet, whether or not a process could be found for it. Packets go into the
+// current one-second bucket; once the second is over, the bucket becomes the
+// per-second rates and a snapshot is published through a shared handle that
+// the TUI, the snapshot stream and the metrics exporters read.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+
+const BUCKET: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+    Quic, // Recognised QUIC, counted apart from the rest of UDP
+    Icmp,
+    Other,
+}
+
+impl Protocol {
+    /// From the IP protocol number
+    pub fn from_number(protocol: u8) -> Self {
+        match protocol {
+            6 => Protocol::Tcp,
+            17 => Protocol::Udp,
+            1 | 58 => Protocol::Icmp,
+            _ => Protocol::Other,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct Counters {
+    pub packets: u64,
+    pub bytes: u64,
+}
+
+impl Counters {
+    fn add(&mut self, bytes: u64) {
+        self.packets += 1;
+        self.bytes += bytes;
+    }
+
+    /// Counts over `elapsed`, as a per-second rate
+    fn per_second(self, elapsed: Duration) -> Counters {
+        let secs = elapsed.as_secs_f64().max(BUCKET.as_secs_f64());
+        Counters {
+            packets: (self.packets as f64 / secs).round() as u64,
+            bytes: (self.bytes as f64 / secs).round() as u64,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct ProtocolStats {
+    pub total: Counters,
+    pub per_second: Counters,
+    /// Packets no process's socket matched
+    pub unattributed: u64,
+}
+
+#[derive(Serialize, Clone, Default, Debug, PartialEq)]
+pub struct InterfaceStats {
+    pub name: String,
+    pub total: Counters,
+    pub per_second: Counters,
+    /// Packets no IP header could be read from
+    pub unparsed: u64,
+    /// Dropped by the kernel and by the interface, as libpcap reports them
+    pub kernel_dropped: u64,
+    pub interface_dropped: u64,
+}
+
+#[derive(Serialize, Clone, Default, Debug, PartialEq)]
+pub struct TrafficSnapshot {
+    pub tcp: ProtocolStats,
+    pub udp: ProtocolStats,
+    pub quic: ProtocolStats,
+    pub icmp: ProtocolStats,
+    pub other: ProtocolStats,
+    /// Every interface captured on this session, the current one last
+    pub interfaces: Vec<InterfaceStats>,
+}
+
+impl TrafficSnapshot {
+    /// Nothing was captured, e.g. in demo mode or without packet capture
+    pub fn is_empty(&self) -> bool {
+        self.interfaces.is_empty()
+    }
+
+    /// Each protocol with the name exporters label it by
+    pub fn protocols(&self) -> [(&'static str, &ProtocolStats); 5] {
+        [("tcp", &self.tcp), ("udp", &self.udp), ("quic", &self.quic), ("icmp", &self.icmp), ("other", &self.other)]
+    }
+
+    fn protocol_mut(&mut self, protocol: Protocol) -> &mut ProtocolStats {
+        match protocol {
+            Protocol::Tcp => &mut self.tcp,
+            Protocol::Udp => &mut self.udp,
+            Protocol::Quic => &mut self.quic,
+            Protocol::Icmp => &mut self.icmp,
+            Protocol::Other => &mut self.other,
+        }
+    }
+}
+
+/// The latest snapshot, shared between the capture thread and its readers
+pub type SharedTraffic = Arc<Mutex<TrafficSnapshot>>;
+
+/// Latest published snapshot
+pub fn latest(shared: &SharedTraffic) -> TrafficSnapshot {
+    shared.lock().map(|snapshot| snapshot.clone()).unwrap_or_default()
+}
+
+/// Counting side, owned by the capture thread
+pub struct TrafficCounter {
+    snapshot: TrafficSnapshot,
+    bucket: [Counters; 5], // By protocol, in `Protocol` order
+    interface_bucket: Counters,
+    bucket_started: Instant,
+    shared: SharedTraffic,
+}
+
+impl TrafficCounter {
+    pub fn new(interface: &str, shared: SharedTraffic) -> Self {
+        let mut counter = TrafficCounter {
+            snapshot: TrafficSnapshot::default(),
+            bucket: [Counters::default(); 5],
+            interface_bucket: Counters::default(),
+            bucket_started: Instant::now(),
+            shared,
+        };
+        counter.set_interface(interface);
+        counter
+    }
+
+    fn interface(&mut self) -> &mut InterfaceStats {
+        self.snapshot.interfaces.last_mut().expect("an interface is set on creation")
+    }
+
+    /// Capture moved to `interface`; counts from here on go to it
+    pub fn set_interface(&mut self, interface: &str) {
+        let interfaces = &mut self.snapshot.interfaces;
+        if let Some(known) = interfaces.iter().position(|stats| stats.name == interface) {
+            let stats = interfaces.remove(known);
+            interfaces.push(stats);
+        } else {
+            interfaces.push(InterfaceStats { name: interface.to_string(), ..Default::default() });
+        }
+        for stats in interfaces.iter_mut() {
+            stats.per_second = Counters::default();
+        }
+        self.interface_bucket = Counters::default();
+    }
+
+    /// A packet of `protocol`, and whether a process was found for it
+    pub fn count(&mut self, protocol: Protocol, bytes: u64, attributed: bool) {
+        self.bucket[protocol as usize].add(bytes);
+        self.interface_bucket.add(bytes);
+        let stats = self.snapshot.protocol_mut(protocol);
+        stats.total.add(bytes);
+        if !attributed {
+            stats.unattributed += 1;
+        }
+        self.interface().total.add(bytes);
+    }
+
+    /// A packet no IP header could be read from
+    pub fn count_unparsed(&mut self, bytes: u64) {
+        self.interface_bucket.add(bytes);
+        let interface = self.interface();
+        interface.total.add(bytes);
+        interface.unparsed += 1;
+    }
+
+    pub fn set_drops(&mut self, kernel: u64, interface: u64) {
+        let stats = self.interface();
+        stats.kernel_dropped = kernel;
+        stats.interface_dropped = interface;
+    }
+
+    /// Close the bucket once its second is over and publish the snapshot
+    pub fn tick(&mut self) {
+        let elapsed = self.bucket_started.elapsed();
+        if elapsed < BUCKET {
+            return;
+        }
+        for (index, protocol) in [Protocol::Tcp, Protocol::Udp, Protocol::Quic, Protocol::Icmp, Protocol::Other].into_iter().enumerate() {
+            self.snapshot.protocol_mut(protocol).per_second = self.bucket[index].per_second(elapsed);
+        }
+        let per_second = self.interface_bucket.per_second(elapsed);
+        self.interface().per_second = per_second;
+        self.bucket = [Counters::default(); 5];
+        self.interface_bucket = Counters::default();
+        self.bucket_started = Instant::now();
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.clone_from(&self.snapshot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_per_protocol_and_interface() {
+        let shared = SharedTraffic::default();
+        let mut counter = TrafficCounter::new("eth0", shared.clone());
+        counter.count(Protocol::Tcp, 1500, true);
+        counter.count(Protocol::Tcp, 500, false);
+        counter.count(Protocol::from_number(58), 100, false);
+        counter.count_unparsed(60);
+        counter.bucket_started -= Duration::from_secs(2);
+        counter.tick();
+
+        let snapshot = latest(&shared);
+        assert_eq!(snapshot.tcp.total, Counters { packets: 2, bytes: 2000 });
+        assert_eq!(snapshot.tcp.per_second, Counters { packets: 1, bytes: 1000 });
+        assert_eq!((snapshot.tcp.unattributed, snapshot.icmp.unattributed), (1, 1));
+        assert_eq!(snapshot.interfaces[0].total, Counters { packets: 4, bytes: 2160 });
+        assert_eq!(snapshot.interfaces[0].unparsed, 1);
+
+        // Moving the capture starts a new interface and keeps the old one's totals
+        counter.set_interface("wlan0");
+        counter.count(Protocol::Udp, 200, true);
+        counter.bucket_started -= Duration::from_secs(1);
+        counter.tick();
+        let snapshot = latest(&shared);
+        let names: Vec<&str> = snapshot.interfaces.iter().map(|stats| stats.name.as_str()).collect();
+        assert_eq!(names, ["eth0", "wlan0"]);
+        assert_eq!(snapshot.interfaces[1].per_second.bytes, 200);
+        assert_eq!(snapshot.tcp.per_second, Counters::default());
+    }
+}
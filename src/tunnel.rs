@@ -0,0 +1,75 @@
+// VPN and tunnel awareness. Traffic that goes through a VPN is seen twice
+// when capturing on `any` (or on both the physical NIC and the tunnel): once
+// in the clear on the tunnel interface, attributed to the applications, and
+// once encrypted on the physical interface, attributed to the VPN daemon that
+// owns the outer socket. The daemon is marked as a tunnel carrier and left out
+// of the totals, so they add up to what the applications used.
+
+/// Interface name prefixes of tunnels: WireGuard, OpenVPN/tun-tap, PPP, IPsec
+/// and the mesh VPNs; macOS-style utun shows up through some userspace VPNs
+const TUNNEL_INTERFACE_PREFIXES: [&str; 9] = [
+    "tun", "tap", "wg", "ppp", "ipsec", "utun", "tailscale", "nordlynx", "proton",
+];
+
+/// Words in Windows adapter names and descriptions that mark a tunnel adapter
+const TUNNEL_ADAPTER_WORDS: [&str; 7] = [
+    "wireguard", "wintun", "tap-windows", "openvpn", "tailscale", "zerotier", "vpn",
+];
+
+/// VPN daemons whose own traffic is the encrypted copy of someone else's
+const CARRIER_PROCESSES: [&str; 14] = [
+    "openvpn", "wireguard-go", "wireguard", "tailscaled", "openconnect", "vpnc", "charon",
+    "zerotier-one", "nordvpnd", "protonvpn", "expressvpnd", "mullvad-daemon", "wg-quick", "sstpc",
+];
+
+/// UDP ports of WireGuard, OpenVPN, Tailscale and IPsec (IKE and NAT traversal)
+const TUNNEL_UDP_PORTS: [u16; 5] = [51820, 1194, 41641, 500, 4500];
+
+/// Whether an interface is a tunnel rather than a physical link
+pub fn is_tunnel_interface(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    TUNNEL_INTERFACE_PREFIXES.iter().any(|prefix| {
+        lower.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '_' || c == '.'))
+    })
+        // ZeroTier names its interfaces after the network ID, e.g. ztks57kc
+        || (lower.starts_with("zt") && lower.len() == 10)
+        || TUNNEL_ADAPTER_WORDS.iter().any(|word| lower.contains(word))
+}
+
+/// Whether a process is a VPN daemon, by name (".exe" and case don't matter)
+pub fn is_carrier_process(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    let name = lower.strip_suffix(".exe").unwrap_or(&lower);
+    CARRIER_PROCESSES.contains(&name)
+}
+
+/// Whether a connection on these ports looks like a tunnel's outer packets
+pub fn is_tunnel_port(protocol: u8, local_port: u16, remote_port: u16) -> bool {
+    protocol == 17 && (TUNNEL_UDP_PORTS.contains(&local_port) || TUNNEL_UDP_PORTS.contains(&remote_port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_tunnel_interfaces() {
+        for name in ["tun0", "wg0", "ztks57kc2x", "wg-home", "ppp0", "tailscale0", "utun3", "NordLynx", "WireGuard Tunnel", "OpenVPN Wintun"] {
+            assert!(is_tunnel_interface(name), "{}", name);
+        }
+        for name in ["eth0", "wlan0", "enp3s0", "lo", "Ethernet", "Wi-Fi", "tapir0"] {
+            assert!(!is_tunnel_interface(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn recognizes_carriers() {
+        assert!(is_carrier_process("openvpn"));
+        assert!(is_carrier_process("OpenVPN.exe"));
+        assert!(!is_carrier_process("firefox"));
+        assert!(is_tunnel_port(17, 40000, 51820));
+        assert!(!is_tunnel_port(6, 40000, 1194));
+        assert!(!is_tunnel_port(17, 40000, 53));
+    }
+}
@@ -1,15 +1,70 @@
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::time::Instant;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 use ratatui::style::Color;
 use crate::ui::renderers::packet_details::cache::PacketRenderCacheItem;
+use crate::packet_history::PacketHistory;
+use crate::ui::profile::{MemoryBreakdown, RenderProfile};
+use crate::units::{format_bytes, format_rate};
+use crate::anomaly::{AnomalyDetector, AnomalyScore};
+use crate::events::{EventKind, EventLog};
+use crate::notifications::NotificationStack;
 
 // Process cleanup configuration
-pub const PROCESS_CLEANUP_INTERVAL_SECS: u64 = 5; // Check for dead processes every 5 seconds
+pub const DEFAULT_CLEANUP_INTERVAL_SECS: u64 = 5; // Check for dead processes every 5 seconds
+
+/// Seconds without traffic after which `z` hides a process
+pub const DEFAULT_IDLE_AFTER_SECS: u64 = 30;
 
 /// Maximum number of packets kept per process for the packet history view
 pub const MAX_PACKET_HISTORY: usize = 5_000;
 
+/// Default cap on packets held across all processes' packet histories
+pub const DEFAULT_PACKET_BUDGET: usize = 200_000;
+
+/// Default cap on the memory held by all packet histories, in megabytes
+pub const DEFAULT_PACKET_BUDGET_MB: usize = 64;
+
+/// Number of one-second table snapshots kept for time-travel playback (~5 minutes)
+pub const MAX_SNAPSHOT_HISTORY: usize = 300;
+
+/// Default span of chart history kept per process, in seconds
+pub const DEFAULT_HISTORY_WINDOW_SECS: u64 = 300;
+
+/// Default interval between chart history samples, in milliseconds
+pub const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 100;
+
+/// Upper bound on points per history series; older points are downsampled beyond this
+pub const MAX_HISTORY_POINTS: usize = 3_000;
+
+/// Default memory budget for chart/packet histories, in megabytes
+pub const DEFAULT_MEMORY_BUDGET_MB: u64 = 256;
+
+/// Default time between UI refreshes, in milliseconds
+pub const DEFAULT_REFRESH_INTERVAL_MS: u64 = 100;
+
+/// Default data quota shown in the overview, in megabytes
+pub const DEFAULT_QUOTA_MB: u64 = 1024;
+
+/// Default bytes captured per packet; enough for any header plus payload
+pub const DEFAULT_SNAPLEN: i32 = 65_535;
+
+/// Snaplen limits `--snaplen` enforces; a saved one is clamped to them
+pub const MIN_SNAPLEN: i32 = 64;
+pub const MAX_SNAPLEN: i32 = 262_144;
+
+/// Minimum spacing between packet drop warnings in the TUI
+pub const DROP_WARNING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Distinct remote hosts remembered per process for the first-contact audit trail
+pub const MAX_HOST_CONTACTS: usize = 1_000;
+
+/// Ports counted per process; traffic on further ports isn't broken down
+pub const MAX_TRACKED_PORTS: usize = 64;
+
+/// Most matches listed in the quick-jump prompt
+pub const MAX_JUMP_MATCHES: usize = 8;
+
 /// Direction of a packet relative to the monitored process
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum PacketDirection {
@@ -70,25 +125,7 @@ pub struct PacketFilter {
     pub direction: Option<PacketDirection>, // Sent or Received
     pub search_term: Option<String>,        // Raw input string (for redisplay)
     pub search_regex: Option<regex::Regex>, // Compiled regex when provided
-}
-
-fn format_bytes(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-    const THRESHOLD: f64 = 1024.0;
-    
-    if bytes == 0 {
-        return "0 B".to_string();
-    }
-    
-    let bytes_f = bytes as f64;
-    let unit_index = (bytes_f.log(THRESHOLD).floor() as usize).min(UNITS.len() - 1);
-    let size = bytes_f / THRESHOLD.powi(unit_index as i32);
-    
-    if unit_index == 0 {
-        format!("{} {}", bytes, UNITS[unit_index])
-    } else {
-        format!("{:.1} {}", size, UNITS[unit_index])
-    }
+    pub port: Option<u16>,                  // Source or destination port
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
@@ -100,6 +137,149 @@ pub struct Connection {
     pub protocol: u8,
 }
 
+impl Connection {
+    /// The TCP or UDP connection a parsed packet belongs to, as captured
+    pub fn from_packet(packet: &crate::parser::Packet) -> Option<Connection> {
+        let (source_port, dest_port) = packet.ports?;
+        Some(Connection {
+            source_port,
+            dest_port,
+            source_ip: packet.source_ip,
+            dest_ip: packet.dest_ip,
+            protocol: packet.protocol,
+        }.normalized())
+    }
+
+    /// Same connection with IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) turned into
+    /// plain IPv4, so dual-stack sockets match the IPv4 packets they actually carry
+    pub fn normalized(self) -> Connection {
+        fn unmap(ip: std::net::IpAddr) -> std::net::IpAddr {
+            match ip {
+                std::net::IpAddr::V6(v6) => v6.to_ipv4_mapped().map(std::net::IpAddr::V4).unwrap_or(ip),
+                v4 => v4,
+            }
+        }
+        Connection {
+            source_ip: unmap(self.source_ip),
+            dest_ip: unmap(self.dest_ip),
+            ..self
+        }
+    }
+}
+
+/// When a process first exchanged traffic with a remote host
+#[derive(Clone, Serialize)]
+pub struct HostContact {
+    pub host: std::net::IpAddr,
+    pub port: u16,    // Remote port of that first connection
+    pub protocol: u8,
+    pub first_seen: std::time::SystemTime,
+}
+
+/// TCP socket state, as listed in the kernel socket tables
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TcpSocketState {
+    Established,
+    SynSent,
+    SynRecv,
+    FinWait,
+    TimeWait,
+    Close,
+    CloseWait,
+    LastAck,
+    Listen,
+    Closing,
+}
+
+impl TcpSocketState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TcpSocketState::Established => "ESTABLISHED",
+            TcpSocketState::SynSent => "SYN_SENT",
+            TcpSocketState::SynRecv => "SYN_RECV",
+            TcpSocketState::FinWait => "FIN_WAIT",
+            TcpSocketState::TimeWait => "TIME_WAIT",
+            TcpSocketState::Close => "CLOSE",
+            TcpSocketState::CloseWait => "CLOSE_WAIT",
+            TcpSocketState::LastAck => "LAST_ACK",
+            TcpSocketState::Listen => "LISTEN",
+            TcpSocketState::Closing => "CLOSING",
+        }
+    }
+}
+
+/// TCP_INFO counters of one connection (Linux)
+#[derive(Clone, Copy, Default)]
+pub struct TcpSample {
+    pub rtt_us: u32,        // Smoothed round-trip time
+    pub total_retrans: u32, // Segments retransmitted over the connection's lifetime
+    pub segs_out: u32,      // Segments sent, retransmissions included; 0 on kernels older than 4.2
+}
+
+/// One TCP socket from the kernel tables
+pub struct TcpSocket {
+    pub conn: Connection,
+    pub state: TcpSocketState,
+    pub owner: Option<i32>,
+    pub sample: Option<TcpSample>, // Linux only
+}
+
+/// Loss and latency of a process's TCP connections, so a slow process can be
+/// told apart from one that simply sends little
+#[derive(Clone, Copy, Default, PartialEq, Serialize)]
+pub struct TcpQuality {
+    pub avg_rtt_ms: Option<f64>,         // Mean smoothed RTT of its established connections
+    pub max_rtt_ms: Option<f64>,
+    pub retransmits: u64,                // Segments retransmitted since monitoring started
+    pub retransmit_percent: Option<f64>, // Share of the segments sent since the previous refresh
+}
+
+/// Retransmitted share of sent segments at which a process is shown in red
+pub const RETRANSMIT_WARNING_PERCENT: f64 = 2.0;
+
+/// Half-open (SYN_SENT/SYN_RECV) sockets at which a process gets flagged
+pub const HALF_OPEN_WARNING: u32 = 100;
+/// TIME_WAIT sockets at which a process gets flagged
+pub const TIME_WAIT_WARNING: u32 = 1_000;
+/// CLOSE_WAIT sockets (peer closed, process never did) at which a process gets flagged
+pub const CLOSE_WAIT_WARNING: u32 = 100;
+
+/// A process's TCP sockets by state
+#[derive(Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SocketStates {
+    pub established: u32,
+    pub listen: u32,
+    pub half_open: u32,  // SYN_SENT and SYN_RECV
+    pub time_wait: u32,
+    pub close_wait: u32,
+    pub closing: u32,    // FIN_WAIT, CLOSING, LAST_ACK and CLOSE
+}
+
+impl SocketStates {
+    pub fn add(&mut self, state: TcpSocketState) {
+        match state {
+            TcpSocketState::Established => self.established += 1,
+            TcpSocketState::Listen => self.listen += 1,
+            TcpSocketState::SynSent | TcpSocketState::SynRecv => self.half_open += 1,
+            TcpSocketState::TimeWait => self.time_wait += 1,
+            TcpSocketState::CloseWait => self.close_wait += 1,
+            TcpSocketState::FinWait | TcpSocketState::Closing | TcpSocketState::LastAck | TcpSocketState::Close => self.closing += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.established + self.listen + self.half_open + self.time_wait + self.close_wait + self.closing
+    }
+
+    /// Whether the process holds enough half-open, TIME_WAIT or CLOSE_WAIT
+    /// sockets to suggest a connection leak or a flood
+    pub fn needs_attention(&self) -> bool {
+        self.half_open >= HALF_OPEN_WARNING
+            || self.time_wait >= TIME_WAIT_WARNING
+            || self.close_wait >= CLOSE_WAIT_WARNING
+    }
+}
+
 #[derive(Clone, Serialize)]
 pub struct ProcessInfo {
     pub name: String,
@@ -109,11 +289,292 @@ pub struct ProcessInfo {
     pub received_rate: u64,  // bytes per second
     pub container_name: Option<String>,
     pub user_name: Option<String>,
+    pub unit: Option<String>, // systemd unit, e.g. nginx.service (Linux)
     pub has_alert: bool,
     pub sent_history: Vec<(f64, f64)>,
     pub received_history: Vec<(f64, f64)>,
     /// Bounded history of individual packets (headers only)
-    pub packet_history: std::collections::VecDeque<PacketInfo>,
+    pub packet_history: PacketHistory,
+    pub tcp_bytes: u64,
+    pub udp_bytes: u64,
+    /// Part of the UDP bytes recognised as QUIC (HTTP/3)
+    pub quic_bytes: u64,
+    /// Bytes carried over IPv4 (including v4-mapped traffic on dual-stack sockets) and IPv6
+    pub ipv4_bytes: u64,
+    pub ipv6_bytes: u64,
+    /// Number of distinct connections observed for this process
+    pub connection_count: usize,
+    /// Distinct connections first seen during the last second
+    pub new_connection_rate: u64,
+    /// First contact with each distinct remote host, oldest first (bounded)
+    pub host_contacts: Vec<HostContact>,
+    /// TCP sockets by state, refreshed with the socket tables
+    pub socket_states: SocketStates,
+    /// RTT and retransmits of its TCP connections, refreshed with the socket tables
+    pub tcp_quality: TcpQuality,
+    /// A VPN daemon: its traffic is the encrypted copy of other processes', so totals leave it out
+    pub tunnel: bool,
+    /// Part of sent/received exchanged over loopback or with another local socket
+    pub loopback_sent: u64,
+    pub loopback_received: u64,
+    pub loopback_sent_rate: u64,
+    pub loopback_received_rate: u64,
+    pub first_seen: std::time::SystemTime,
+    pub last_seen: std::time::SystemTime,
+    /// Set when this process took over from an earlier PID of the same program
+    #[serde(skip)]
+    pub restart: Option<crate::restarts::Restart>,
+    /// TCP and UDP bytes by service port (bounded by MAX_TRACKED_PORTS)
+    #[serde(skip)]
+    pub port_bytes: HashMap<u16, u64>,
+    /// Bytes by category of the config's classification rules
+    #[serde(skip)]
+    pub category_bytes: HashMap<String, u64>,
+}
+
+impl ProcessInfo {
+    /// Create an empty entry for a process that was just attributed traffic
+    pub fn new(identifier: &ProcessIdentifier) -> Self {
+        let now = std::time::SystemTime::now();
+        ProcessInfo {
+            name: identifier.name.clone(),
+            sent: 0,
+            received: 0,
+            sent_rate: 0,
+            received_rate: 0,
+            container_name: identifier.container_name.clone(),
+            user_name: identifier.user_name.clone(),
+            unit: identifier.unit.clone(),
+            has_alert: false,
+            sent_history: Vec::new(),
+            received_history: Vec::new(),
+            packet_history: PacketHistory::default(),
+            tcp_bytes: 0,
+            udp_bytes: 0,
+            quic_bytes: 0,
+            ipv4_bytes: 0,
+            ipv6_bytes: 0,
+            connection_count: 0,
+            new_connection_rate: 0,
+            host_contacts: Vec::new(),
+            socket_states: SocketStates::default(),
+            tcp_quality: TcpQuality::default(),
+            tunnel: false,
+            loopback_sent: 0,
+            loopback_received: 0,
+            loopback_sent_rate: 0,
+            loopback_received_rate: 0,
+            first_seen: now,
+            last_seen: now,
+            restart: None,
+            port_bytes: HashMap::new(),
+            category_bytes: HashMap::new(),
+        }
+    }
+}
+
+impl ProcessInfo {
+    /// Copy of the counters and metadata without the (large) chart and packet histories
+    pub fn summary(&self) -> ProcessInfo {
+        ProcessInfo {
+            name: self.name.clone(),
+            sent: self.sent,
+            received: self.received,
+            sent_rate: self.sent_rate,
+            received_rate: self.received_rate,
+            container_name: self.container_name.clone(),
+            user_name: self.user_name.clone(),
+            unit: self.unit.clone(),
+            has_alert: self.has_alert,
+            sent_history: Vec::new(),
+            received_history: Vec::new(),
+            packet_history: PacketHistory::default(),
+            tcp_bytes: self.tcp_bytes,
+            udp_bytes: self.udp_bytes,
+            quic_bytes: self.quic_bytes,
+            ipv4_bytes: self.ipv4_bytes,
+            ipv6_bytes: self.ipv6_bytes,
+            connection_count: self.connection_count,
+            new_connection_rate: self.new_connection_rate,
+            host_contacts: Vec::new(),
+            socket_states: self.socket_states,
+            tcp_quality: self.tcp_quality,
+            tunnel: self.tunnel,
+            loopback_sent: self.loopback_sent,
+            loopback_received: self.loopback_received,
+            loopback_sent_rate: self.loopback_sent_rate,
+            loopback_received_rate: self.loopback_received_rate,
+            first_seen: self.first_seen,
+            last_seen: self.last_seen,
+            restart: self.restart.clone(),
+            port_bytes: self.port_bytes.clone(),
+            category_bytes: self.category_bytes.clone(),
+        }
+    }
+
+    /// Count TCP or UDP bytes towards the connection's service port: the lower
+    /// of its two ports, which is the server's for all but peer-to-peer traffic
+    pub fn count_port(&mut self, local_port: u16, remote_port: u16, bytes: u64) {
+        let port = local_port.min(remote_port);
+        if self.port_bytes.len() < MAX_TRACKED_PORTS || self.port_bytes.contains_key(&port) {
+            *self.port_bytes.entry(port).or_default() += bytes;
+        }
+    }
+
+    /// The port carrying the most bytes, with its share of the counted ones
+    pub fn top_port(&self) -> Option<(u16, f64)> {
+        let total: u64 = self.port_bytes.values().sum();
+        let (port, bytes) = self.port_bytes.iter().max_by_key(|(port, bytes)| (**bytes, std::cmp::Reverse(**port)))?;
+        (total > 0).then(|| (*port, *bytes as f64 / total as f64))
+    }
+
+    pub fn count_category(&mut self, category: &str, bytes: u64) {
+        match self.category_bytes.get_mut(category) {
+            Some(counted) => *counted += bytes,
+            None => {
+                self.category_bytes.insert(category.to_string(), bytes);
+            }
+        }
+    }
+
+    /// Categories this process's traffic fell into, most bytes first
+    pub fn categories(&self) -> Vec<(&str, u64)> {
+        let mut categories: Vec<(&str, u64)> = self.category_bytes.iter().map(|(name, bytes)| (name.as_str(), *bytes)).collect();
+        categories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        categories
+    }
+
+    /// Counters as the process table shows them under `scope`
+    pub fn scoped(&self, scope: TrafficScope) -> Traffic {
+        match scope {
+            TrafficScope::All => Traffic {
+                sent: self.sent,
+                received: self.received,
+                sent_rate: self.sent_rate,
+                received_rate: self.received_rate,
+            },
+            TrafficScope::External => Traffic {
+                sent: self.sent.saturating_sub(self.loopback_sent),
+                received: self.received.saturating_sub(self.loopback_received),
+                sent_rate: self.sent_rate.saturating_sub(self.loopback_sent_rate),
+                received_rate: self.received_rate.saturating_sub(self.loopback_received_rate),
+            },
+            TrafficScope::Loopback => Traffic {
+                sent: self.loopback_sent,
+                received: self.loopback_received,
+                sent_rate: self.loopback_sent_rate,
+                received_rate: self.loopback_received_rate,
+            },
+        }
+    }
+
+    /// Estimated memory held by the chart and packet histories, in bytes
+    pub fn history_bytes(&self) -> usize {
+        self.chart_history_bytes() + self.packet_history_bytes()
+    }
+
+    pub fn chart_history_bytes(&self) -> usize {
+        (self.sent_history.capacity() + self.received_history.capacity()) * std::mem::size_of::<(f64, f64)>()
+    }
+
+    pub fn packet_history_bytes(&self) -> usize {
+        // The capture thread keeps its own copy of the packet history
+        self.packet_history.bytes() * 2
+    }
+
+    /// Drop the chart and packet histories, keeping the counters
+    pub fn evict_histories(&mut self) {
+        self.sent_history = Vec::new();
+        self.received_history = Vec::new();
+        self.packet_history.clear();
+    }
+}
+
+/// Append a chart sample, dropping points older than the history window and
+/// halving the resolution of the oldest half once `MAX_HISTORY_POINTS` is exceeded;
+/// returns the dropped points
+pub fn push_history_point(history: &mut Vec<(f64, f64)>, point: (f64, f64), window_secs: f64) -> Vec<(f64, f64)> {
+    history.push(point);
+
+    let cutoff = point.0 - window_secs;
+    let expired = history.partition_point(|(t, _)| *t < cutoff);
+    let dropped: Vec<(f64, f64)> = history.drain(..expired).collect();
+
+    if history.len() > MAX_HISTORY_POINTS {
+        let half = history.len() / 2;
+        let merged: Vec<(f64, f64)> = history[..half]
+            .chunks(2)
+            .map(|pair| {
+                let n = pair.len() as f64;
+                (
+                    pair.iter().map(|(t, _)| t).sum::<f64>() / n,
+                    pair.iter().map(|(_, v)| v).sum::<f64>() / n,
+                )
+            })
+            .collect();
+        history.splice(..half, merged);
+    }
+    dropped
+}
+
+/// A sample of the system-wide history: (timestamp, [(pid, sent_rate, received_rate)])
+pub type SystemSample = (f64, Vec<(i32, f64, f64)>);
+
+/// `push_history_point` for the system-wide history: the oldest half is thinned the
+/// same way, averaging each pair's timestamps and every process's rates
+pub fn push_system_history_point(history: &mut Vec<SystemSample>, point: SystemSample, window_secs: f64) {
+    let cutoff = point.0 - window_secs;
+    history.push(point);
+    let expired = history.partition_point(|(t, _)| *t < cutoff);
+    history.drain(..expired);
+
+    if history.len() > MAX_HISTORY_POINTS {
+        let half = history.len() / 2;
+        let merged: Vec<SystemSample> = history[..half]
+            .chunks(2)
+            .map(|pair| {
+                let n = pair.len() as f64;
+                // A process missing from one sample had no rate then
+                let mut rates: Vec<(i32, f64, f64)> = Vec::new();
+                for (pid, sent, received) in pair.iter().flat_map(|(_, rates)| rates) {
+                    match rates.iter_mut().find(|(p, _, _)| p == pid) {
+                        Some(rate) => {
+                            rate.1 += sent;
+                            rate.2 += received;
+                        }
+                        None => rates.push((*pid, *sent, *received)),
+                    }
+                }
+                for rate in &mut rates {
+                    rate.1 /= n;
+                    rate.2 /= n;
+                }
+                (pair.iter().map(|(t, _)| t).sum::<f64>() / n, rates)
+            })
+            .collect();
+        history.splice(..half, merged);
+    }
+}
+
+/// Samples of the selected process for a chart scrolled back past the history
+/// window, read back from the history store
+pub struct RewoundHistory {
+    pub pid: i32,
+    pub sent: Vec<(f64, f64)>,
+    pub received: Vec<(f64, f64)>,
+}
+
+/// Point-in-time copy of the process table, recorded for playback
+#[derive(Clone)]
+pub struct StatsSnapshot {
+    pub time: f64, // seconds since app start
+    pub stats: HashMap<i32, ProcessInfo>,
+}
+
+/// Format a wall-clock timestamp as RFC 3339 for JSON consumers
+fn format_timestamp(ts: std::time::SystemTime) -> String {
+    let dt: chrono::DateTime<chrono::Local> = ts.into();
+    dt.to_rfc3339()
 }
 
 #[derive(Clone, Serialize)]
@@ -130,6 +591,21 @@ pub struct ProcessInfoFormatted {
     pub received_rate_formatted: String,
     pub container_name: Option<String>,
     pub user_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub tcp_bytes: u64,
+    pub udp_bytes: u64,
+    pub quic_bytes: u64,
+    pub connection_count: usize,
+    pub new_connection_rate: u64,
+    pub socket_states: SocketStates,
+    pub tcp_quality: TcpQuality,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub tunnel: bool,
+    pub loopback_sent_bytes: u64,
+    pub loopback_received_bytes: u64,
+    pub first_seen: String,
+    pub last_seen: String,
 }
 
 impl From<&ProcessInfo> for ProcessInfoFormatted {
@@ -139,13 +615,26 @@ impl From<&ProcessInfo> for ProcessInfoFormatted {
             sent_bytes: info.sent,
             sent_formatted: format_bytes(info.sent),
             sent_rate_bytes: info.sent_rate,
-            sent_rate_formatted: format!("{}/s", format_bytes(info.sent_rate)),
+            sent_rate_formatted: format_rate(info.sent_rate),
             received_bytes: info.received,
             received_formatted: format_bytes(info.received),
             received_rate_bytes: info.received_rate,
-            received_rate_formatted: format!("{}/s", format_bytes(info.received_rate)),
+            received_rate_formatted: format_rate(info.received_rate),
             container_name: info.container_name.clone(),
             user_name: info.user_name.clone(),
+            unit: info.unit.clone(),
+            tcp_bytes: info.tcp_bytes,
+            udp_bytes: info.udp_bytes,
+            quic_bytes: info.quic_bytes,
+            connection_count: info.connection_count,
+            new_connection_rate: info.new_connection_rate,
+            socket_states: info.socket_states,
+            tcp_quality: info.tcp_quality,
+            tunnel: info.tunnel,
+            loopback_sent_bytes: info.loopback_sent,
+            loopback_received_bytes: info.loopback_received,
+            first_seen: format_timestamp(info.first_seen),
+            last_seen: format_timestamp(info.last_seen),
         }
     }
 }
@@ -165,6 +654,21 @@ pub struct ProcessInfoJson {
     pub received_rate_formatted: String,
     pub container_name: Option<String>,
     pub user_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub tcp_bytes: u64,
+    pub udp_bytes: u64,
+    pub quic_bytes: u64,
+    pub connection_count: usize,
+    pub new_connection_rate: u64,
+    pub socket_states: SocketStates,
+    pub tcp_quality: TcpQuality,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub tunnel: bool,
+    pub loopback_sent_bytes: u64,
+    pub loopback_received_bytes: u64,
+    pub first_seen: String,
+    pub last_seen: String,
 }
 
 impl From<(&i32, &ProcessInfo)> for ProcessInfoJson {
@@ -175,13 +679,26 @@ impl From<(&i32, &ProcessInfo)> for ProcessInfoJson {
             sent_bytes: info.sent,
             sent_formatted: format_bytes(info.sent),
             sent_rate_bytes: info.sent_rate,
-            sent_rate_formatted: format!("{}/s", format_bytes(info.sent_rate)),
+            sent_rate_formatted: format_rate(info.sent_rate),
             received_bytes: info.received,
             received_formatted: format_bytes(info.received),
             received_rate_bytes: info.received_rate,
-            received_rate_formatted: format!("{}/s", format_bytes(info.received_rate)),
+            received_rate_formatted: format_rate(info.received_rate),
             container_name: info.container_name.clone(),
             user_name: info.user_name.clone(),
+            unit: info.unit.clone(),
+            tcp_bytes: info.tcp_bytes,
+            udp_bytes: info.udp_bytes,
+            quic_bytes: info.quic_bytes,
+            connection_count: info.connection_count,
+            new_connection_rate: info.new_connection_rate,
+            socket_states: info.socket_states,
+            tcp_quality: info.tcp_quality,
+            tunnel: info.tunnel,
+            loopback_sent_bytes: info.loopback_sent,
+            loopback_received_bytes: info.loopback_received,
+            first_seen: format_timestamp(info.first_seen),
+            last_seen: format_timestamp(info.last_seen),
         }
     }
 }
@@ -192,15 +709,114 @@ pub struct ProcessIdentifier {
     pub name: String,
     pub container_name: Option<String>,
     pub user_name: Option<String>,
+    pub unit: Option<String>,
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub enum SortDirection {
     Asc,
     Desc,
 }
 
-#[derive(PartialEq)]
+impl SortDirection {
+    pub fn toggled(self) -> Self {
+        match self {
+            SortDirection::Asc => SortDirection::Desc,
+            SortDirection::Desc => SortDirection::Asc,
+        }
+    }
+
+    pub fn indicator(self) -> &'static str {
+        match self {
+            SortDirection::Asc => " ▲",
+            SortDirection::Desc => " ▼",
+        }
+    }
+}
+
+/// Which traffic the process table and totals count, cycled with `l`
+#[derive(PartialEq, Clone, Copy, Default)]
+pub enum TrafficScope {
+    #[default]
+    All,
+    External, // Loopback and local-socket traffic left out
+    Loopback, // Only that
+}
+
+impl TrafficScope {
+    pub fn next(self) -> Self {
+        match self {
+            TrafficScope::All => TrafficScope::External,
+            TrafficScope::External => TrafficScope::Loopback,
+            TrafficScope::Loopback => TrafficScope::All,
+        }
+    }
+
+    /// Shown in the table title; nothing for the default
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            TrafficScope::All => None,
+            TrafficScope::External => Some("external traffic only"),
+            TrafficScope::Loopback => Some("loopback traffic only"),
+        }
+    }
+}
+
+/// What the process table folds its rows by, cycled with `g`
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GroupBy {
+    Unit, // systemd unit, so a service's workers count as one
+    Container,
+    Image,
+    ComposeProject,
+    Pod,
+}
+
+impl GroupBy {
+    /// The next grouping, `None` after the last; the container ones need container awareness
+    pub fn next(current: Option<GroupBy>, containers_mode: bool) -> Option<GroupBy> {
+        match current {
+            None => Some(GroupBy::Unit),
+            Some(GroupBy::Unit) if !containers_mode => None,
+            Some(GroupBy::Unit) => Some(GroupBy::Container),
+            Some(GroupBy::Container) => Some(GroupBy::Image),
+            Some(GroupBy::Image) => Some(GroupBy::ComposeProject),
+            Some(GroupBy::ComposeProject) => Some(GroupBy::Pod),
+            Some(GroupBy::Pod) => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupBy::Unit => "Unit",
+            GroupBy::Container => "Container",
+            GroupBy::Image => "Image",
+            GroupBy::ComposeProject => "Compose project",
+            GroupBy::Pod => "Pod",
+        }
+    }
+}
+
+/// One row of the grouped process table
+pub struct ProcessGroup {
+    pub key: String,
+    pub processes: usize,
+    pub sent: u64,
+    pub received: u64,
+    pub sent_rate: u64,
+    pub received_rate: u64,
+}
+
+/// A process's counters within a `TrafficScope`
+#[derive(Clone, Copy)]
+pub struct Traffic {
+    pub sent: u64,
+    pub received: u64,
+    pub sent_rate: u64,
+    pub received_rate: u64,
+}
+
+#[derive(PartialEq, Clone, Copy)]
 pub enum SortColumn {
     Pid,
     Name,
@@ -212,6 +828,54 @@ pub enum SortColumn {
     User,
 }
 
+impl SortColumn {
+    /// Columns offered by the sort menu, in display order
+    pub fn menu_columns(containers_mode: bool) -> Vec<SortColumn> {
+        let mut columns = vec![
+            SortColumn::Pid,
+            SortColumn::Name,
+            SortColumn::User,
+            SortColumn::SentRate,
+            SortColumn::Sent,
+            SortColumn::ReceivedRate,
+            SortColumn::Received,
+        ];
+        if containers_mode {
+            columns.push(SortColumn::Container);
+        }
+        columns
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortColumn::Pid => "PID",
+            SortColumn::Name => "Name",
+            SortColumn::Sent => "Sent (total)",
+            SortColumn::SentRate => "Sent/s",
+            SortColumn::Received => "Received (total)",
+            SortColumn::ReceivedRate => "Received/s",
+            SortColumn::Container => "Container",
+            SortColumn::User => "User",
+        }
+    }
+
+    /// Compare two processes by this column (ascending)
+    pub fn compare(self, a: (&i32, &ProcessInfo), b: (&i32, &ProcessInfo), scope: TrafficScope) -> std::cmp::Ordering {
+        let ((a_pid, a_info), (b_pid, b_info)) = (a, b);
+        let (a_traffic, b_traffic) = (a_info.scoped(scope), b_info.scoped(scope));
+        match self {
+            SortColumn::Pid => a_pid.cmp(b_pid),
+            SortColumn::Name => a_info.name.cmp(&b_info.name),
+            SortColumn::Sent => a_traffic.sent.cmp(&b_traffic.sent),
+            SortColumn::SentRate => a_traffic.sent_rate.cmp(&b_traffic.sent_rate),
+            SortColumn::Received => a_traffic.received.cmp(&b_traffic.received),
+            SortColumn::ReceivedRate => a_traffic.received_rate.cmp(&b_traffic.received_rate),
+            SortColumn::Container => a_info.container_name.cmp(&b_info.container_name),
+            SortColumn::User => a_info.user_name.cmp(&b_info.user_name),
+        }
+    }
+}
+
 #[derive(PartialEq, Clone, Copy)]
 pub enum AppMode {
     Normal,
@@ -219,11 +883,34 @@ pub enum AppMode {
     SystemOverview,
     Settings,
     PacketDetails, // NEW - per-process packet list view
+    Interfaces, // Per-NIC counters reported by the OS
+    LocalTraffic, // Inter-process traffic matrix over loopback/local sockets
+    Services, // Inbound traffic grouped by local listening port
+    Events, // Session log of alerts, kills, commands and quota breaches
 }
 
+#[derive(PartialEq, Clone, Copy)]
 pub enum EditingField {
     Threshold,
     Command,
+    Cooldown,
+    Rearm,
+    MaxTriggers,
+    NotifyEvery,
+}
+
+impl EditingField {
+    /// Next field in Tab order
+    pub fn next(self) -> Self {
+        match self {
+            EditingField::Threshold => EditingField::Command,
+            EditingField::Command => EditingField::Cooldown,
+            EditingField::Cooldown => EditingField::Rearm,
+            EditingField::Rearm => EditingField::MaxTriggers,
+            EditingField::MaxTriggers => EditingField::NotifyEvery,
+            EditingField::NotifyEvery => EditingField::Threshold,
+        }
+    }
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -255,6 +942,7 @@ pub struct PacketCacheMeta {
     pub sort_column: PacketSortColumn,
     pub sort_direction: PacketSortDirection,
     pub history_len: usize,
+    pub history_evicted: u64, // Changes whenever the ring buffer overwrites packets
 }
 
 pub struct App {
@@ -262,28 +950,49 @@ pub struct App {
     pub stats: HashMap<i32, ProcessInfo>,
     pub sort_by: SortColumn,
     pub sort_direction: SortDirection,
+    pub secondary_sort: Option<(SortColumn, SortDirection)>, // Tie-breaker chosen in the sort menu
+    pub show_sort_menu: bool,
+    pub sort_menu_selected: usize,
     pub containers_mode: bool,
     pub show_total_columns: bool,
+    pub traffic_scope: TrafficScope, // Loopback traffic counted, left out or shown alone
     pub alerts: HashMap<i32, Alert>,
+    pub alert_rules: HashMap<String, Alert>, // Alerts by process name, saved to the config and applied to every process of that name
     pub selected_process: Option<i32>,
     pub show_action_panel: bool,
     pub selected_action: usize,
     pub mode: AppMode,
     pub alert_input: String,
+    pub alert_input_error: Option<String>, // Why the threshold couldn't be parsed
+    pub anomalies: AnomalyDetector,
     pub command_input: String,
+    pub cooldown_input: String,
+    pub rearm_input: String,
+    pub max_triggers_input: String,
+    pub notify_every_input: String,
+    pub dry_run_input: bool,
     pub selected_alert_action: usize,
     pub current_editing_field: EditingField,
     pub killed_processes: HashSet<i32>,
-    pub alert_cooldowns: HashMap<i32, Instant>,
-    pub last_alert_message: Option<String>,
-    pub last_alert_message_time: Option<Instant>, // Track when the alert message was set
-    pub kill_notification: Option<String>, // Kill success notification
-    pub kill_notification_time: Option<Instant>, // When kill notification was set
+    pub alert_states: HashMap<i32, crate::alerts::AlertState>,
+    pub alert_mutes: HashMap<i32, Instant>, // PID -> when the alert mute expires
+    pub last_alert_pid: Option<i32>, // Process whose alert produced the current notification
+    pub show_mute_menu: bool,
+    pub mute_menu_selected: usize,
+    pub mute_menu_pid: Option<i32>,
+    pub notifications: NotificationStack, // Alerts, kills, exports and other results, newest first
+    pub audit: Option<crate::audit::AuditLog>, // --syslog: alerts and kills also go to the system log
     pub dead_processes_cache: HashSet<i32>, // Cache of known dead processes to avoid re-checking
+    pub exited_processes: HashMap<i32, Instant>, // Exited but still shown, with when the exit was noticed
+    pub restarts: crate::restarts::RestartTracker,
+    pub exited_retention_secs: u64, // How long exited processes stay in the table; 0 removes them right away
+    pub cleanup_interval_secs: u64, // How often to look for exited processes
+    pub hide_idle: bool, // `z`: leave processes without recent traffic out of the table
+    pub idle_after_secs: u64, // How long a process must go without traffic to count as idle
     pub command_execution_log: VecDeque<(Instant, String)>, // Timestamped execution log
     pub bandwidth_mode: bool,
     #[allow(clippy::type_complexity)]
-    pub system_bandwidth_history: Vec<(f64, Vec<(i32, f64, f64)>)>, // (timestamp, [(pid, sent_rate, received_rate)])
+    pub system_bandwidth_history: Vec<SystemSample>,
     pub chart_type: ChartType,
     #[allow(clippy::type_complexity)]
     pub chart_datasets: Vec<(String, Vec<(f64, f64)>, ratatui::style::Color)>,
@@ -292,9 +1001,13 @@ pub struct App {
     // System Overview Dashboard fields
     pub system_stats: SystemStats,
     pub system_stats_prev: SystemStats,
+    pub traffic: crate::traffic_stats::TrafficSnapshot, // As the capture thread last counted it
+    pub traffic_source: crate::traffic_stats::SharedTraffic,
     pub total_quota_threshold: u64,
     pub threshold_exceeded: bool,
     pub threshold_exceeded_time: Option<Instant>,
+    pub cost: crate::cost::CostSettings,
+    pub cost_budget_exceeded: bool,
     pub system_alerts: HashSet<i32>, // PIDs with system alerts that should blink
     pub alert_scroll_offset: usize, // Scroll offset for alert progress bars
     // Performance optimization
@@ -315,6 +1028,12 @@ pub struct App {
     pub packet_search_input: String,       // Current search input buffer
     // Last measured visible rows in packet table (set during render)
     pub packet_visible_rows: usize,
+    // First-contact audit trail subview of the packet details
+    pub show_host_contacts: bool,
+    pub host_contact_scroll_offset: usize,
+    pub tcp_info: Option<TcpInfoView>, // Some while the TCP info popup is open
+    pub connections: Option<ConnectionsView>, // Some while the connections popup is open
+    pub process_details: Option<ProcessDetails>, // Detail pane of the selected process, read on selection
     // Enhanced export notification system
     pub export_notification_state: NotificationState, // Enhanced state management
     pub export_notification_time: Option<Instant>, // When export notification was set
@@ -324,6 +1043,93 @@ pub struct App {
     pub packet_cache: Vec<usize>, // indices into packet_history after filtering & sorting
     pub packet_cache_meta: Option<PacketCacheMeta>,
     pub packet_render_cache: Vec<PacketRenderCacheItem>, // NEW: pre-computed render info
+    // Time-travel playback
+    pub snapshot_history: VecDeque<StatsSnapshot>,
+    pub last_snapshot: Instant,
+    pub playback_offset: Option<usize>, // Snapshots back from the newest; None = live view
+    // Memory budget
+    pub memory_budget: u64, // Bytes allowed for histories before the idlest ones are evicted
+    pub evicted_processes: HashSet<i32>, // PIDs whose histories were dropped to stay within budget
+    // History sampling
+    pub history_window_secs: u64, // Span of chart history kept and shown
+    pub sample_interval_ms: u64, // Interval between chart history samples
+    pub refresh_interval_ms: u64, // Interval between UI ticks, which take in new data and redraw
+    pub theme: crate::ui::theme::Theme,
+    pub keymap: crate::ui::keymap::Keymap,
+    pub last_history_sample: Instant,
+    pub history_store: Option<crate::history_store::HistoryStore>, // --history-store
+    pub chart_rewound_to: Option<f64>, // End of the chart when scrolled back with `<`; None = live
+    pub rewound_history: Option<RewoundHistory>,
+    pub protected: crate::protect::ProtectedList, // Processes kills are refused for
+    pub allow_protected_kills: bool, // --allow-protected-kills
+    pub capture_loss: Option<CaptureLoss>, // Shown as a banner until the capture is back
+    pub capture_failure: Option<String>, // The capture thread gave up before capturing; the UI exits with this
+    pub capture_control: Option<std::sync::mpsc::Sender<String>>, // Asks the capture thread to capture on another interface
+    pub interface_picker: Option<InterfacePicker>, // Interface dialog, while it is open
+    pub kill_confirm: Option<i32>, // Action panel is asking whether to kill this PID
+    pub marked: BTreeSet<i32>, // Rows marked with Space; the action panel then acts on all of them
+    pub bulk_kill_confirm: bool, // Action panel is asking whether to kill every marked process
+    pub limits: HashMap<i32, crate::limit::BandwidthLimit>, // Processes whose sending is capped with tc
+    pub limit_input: Option<String>, // Some while the limit prompt is open, for the selected process
+    pub port_input: Option<String>,  // Some while the port filter prompt is open
+    pub port_filter: Option<u16>,    // `P`: only processes with traffic on this port
+    pub blocks: HashMap<i32, crate::block::NetworkBlock>, // Processes cut off from the network, lifted on exit
+    pub pending_kills: Vec<PendingKill>, // Sent SIGTERM, waiting to escalate to SIGKILL
+    pub demo_mode: bool, // Simulated traffic; actions must never touch real processes
+    pub dry_run: bool, // --dry-run: alerts log their kills and custom commands instead of running them
+    pub remote_hosts: Option<Vec<RemoteHost>>, // --aggregate: the table shows other machines' processes
+    pub estimated: bool, // Byte counts come from TCP connection statistics, not captured packets
+    pub byte_counting: crate::accounting::ByteCounting, // What the processes' byte counts count
+    pub process_filter: Option<String>, // Description of the --pid/--process-name/--container restriction
+    pub stop_at: Option<Instant>, // End of a --duration/--schedule run
+    // Interfaces view
+    pub interfaces: Vec<InterfaceStats>,
+    pub last_interface_poll: Option<Instant>,
+    pub capture_iface: Option<String>, // Interface pcap is capturing on, highlighted in the view
+    pub interface_scroll_offset: usize,
+    // Local traffic view
+    pub local_flows: Vec<LocalFlow>,
+    pub local_flow_scroll_offset: usize,
+    // Services view
+    pub services: Vec<ServiceTraffic>,
+    pub service_scroll_offset: usize,
+    // Events view
+    pub events: EventLog,
+    pub event_scroll_offset: usize, // The event at the top is the selected one
+    pub event_output: Option<OutputView>,
+    // Capture statistics
+    pub capture_stats: Option<CaptureStats>,
+    pub last_drop_warning: Option<Instant>,
+    // Socket-to-process map refreshes (`D` in the overview)
+    pub scan_stats: Option<ScanStats>,
+    pub show_scan_stats: bool,
+    // Frame timings and buffer sizes (F12 or `--debug-ui`)
+    pub render_profile: RenderProfile,
+    pub show_debug_overlay: bool,
+    // Quick-jump (`:` followed by a PID or name prefix)
+    pub jump_input: Option<String>, // Some while the jump prompt is open
+    pub jump_selected: usize,
+    pub jump_return_mode: Option<AppMode>, // View to go back to on Esc after jumping
+    // Annotations (`a` followed by a label)
+    pub annotations: Vec<Annotation>,
+    pub annotation_input: Option<String>, // Some while the annotation prompt is open
+    // Before/after comparison (`B` marks the baseline, `b` shows the changes since)
+    pub baseline: Option<Baseline>,
+    pub show_baseline_diff: bool,
+    pub baseline_scroll: usize,
+    // Process names pinned to the top of the table (`f`), saved in the config
+    pub pinned: BTreeSet<String>,
+    // Categories of the config's classification rules, in config order
+    pub categories: Vec<String>,
+    // Help overlay (`?`)
+    pub show_help: bool,
+    pub help_scroll: usize,
+    // Container images, Compose projects and pods, looked up in the background
+    pub containers: crate::containers::ContainerResolver,
+    // Grouped table (`g`) and the group it was narrowed to with Enter
+    pub group_by: Option<GroupBy>,
+    pub group_selected: usize,
+    pub group_filter: Option<(GroupBy, String)>,
 }
 
 impl App {
@@ -333,24 +1139,45 @@ impl App {
             stats: HashMap::new(),
             sort_by: SortColumn::Pid,
             sort_direction: SortDirection::Asc,
+            secondary_sort: None,
+            show_sort_menu: false,
+            sort_menu_selected: 0,
             containers_mode,
             show_total_columns,
+            traffic_scope: TrafficScope::default(),
             alerts: HashMap::new(),
+            alert_rules: HashMap::new(),
             selected_process: None,
             show_action_panel: false,
             selected_action: 0,
             mode: AppMode::Normal,
             alert_input: String::new(),
+            alert_input_error: None,
+            anomalies: AnomalyDetector::default(),
             command_input: String::new(),
+            cooldown_input: String::new(),
+            rearm_input: String::new(),
+            max_triggers_input: String::new(),
+            notify_every_input: String::new(),
+            dry_run_input: false,
             selected_alert_action: 0,
             current_editing_field: EditingField::Threshold,
             killed_processes: HashSet::new(),
-            alert_cooldowns: HashMap::new(),
-            last_alert_message: None,
-            last_alert_message_time: None, // Track when the alert message was set
-            kill_notification: None, // Kill success notification
-            kill_notification_time: None, // When kill notification was set
+            alert_states: HashMap::new(),
+            alert_mutes: HashMap::new(),
+            last_alert_pid: None,
+            show_mute_menu: false,
+            mute_menu_selected: 0,
+            mute_menu_pid: None,
+            notifications: NotificationStack::default(),
+            audit: None,
             dead_processes_cache: HashSet::new(), // Cache of known dead processes to avoid re-checking
+            exited_processes: HashMap::new(),
+            restarts: crate::restarts::RestartTracker::default(),
+            exited_retention_secs: 0,
+            cleanup_interval_secs: DEFAULT_CLEANUP_INTERVAL_SECS,
+            hide_idle: false,
+            idle_after_secs: DEFAULT_IDLE_AFTER_SECS,
             command_execution_log: VecDeque::new(),
             bandwidth_mode: false,
             system_bandwidth_history: Vec::new(),
@@ -361,9 +1188,13 @@ impl App {
             // System Overview Dashboard fields
             system_stats: SystemStats::new(),
             system_stats_prev: SystemStats::new(),
-            total_quota_threshold: 1024 * 1024 * 1024, // Default 1 GB total quota
+            traffic: crate::traffic_stats::TrafficSnapshot::default(),
+            traffic_source: crate::traffic_stats::SharedTraffic::default(),
+            total_quota_threshold: DEFAULT_QUOTA_MB * 1024 * 1024,
             threshold_exceeded: false,
             threshold_exceeded_time: None,
+            cost: crate::cost::CostSettings::default(),
+            cost_budget_exceeded: false,
             system_alerts: HashSet::new(),
             alert_scroll_offset: 0,
             // Performance optimization
@@ -383,6 +1214,11 @@ impl App {
             packet_search_mode: false,
             packet_search_input: String::new(),
             packet_visible_rows: 0,
+            show_host_contacts: false,
+            host_contact_scroll_offset: 0,
+            tcp_info: None,
+            connections: None,
+            process_details: None,
             // Enhanced export notification system
             export_notification_state: NotificationState::None, // Enhanced state management
             export_notification_time: None,
@@ -392,135 +1228,1643 @@ impl App {
             packet_cache: Vec::new(),
             packet_cache_meta: None,
             packet_render_cache: Vec::new(),
+            // Time-travel playback
+            snapshot_history: VecDeque::with_capacity(MAX_SNAPSHOT_HISTORY),
+            last_snapshot: Instant::now(),
+            playback_offset: None,
+            // Memory budget
+            memory_budget: DEFAULT_MEMORY_BUDGET_MB * 1024 * 1024,
+            evicted_processes: HashSet::new(),
+            // History sampling
+            history_window_secs: DEFAULT_HISTORY_WINDOW_SECS,
+            sample_interval_ms: DEFAULT_SAMPLE_INTERVAL_MS,
+            refresh_interval_ms: DEFAULT_REFRESH_INTERVAL_MS,
+            theme: crate::ui::theme::Theme::default(),
+            keymap: crate::ui::keymap::Keymap::default(),
+            last_history_sample: Instant::now(),
+            history_store: None,
+            chart_rewound_to: None,
+            rewound_history: None,
+            protected: crate::protect::ProtectedList::default(),
+            allow_protected_kills: false,
+            capture_loss: None,
+            capture_failure: None,
+            capture_control: None,
+            interface_picker: None,
+            kill_confirm: None,
+            marked: BTreeSet::new(),
+            bulk_kill_confirm: false,
+            limits: HashMap::new(),
+            limit_input: None,
+            port_input: None,
+            port_filter: None,
+            blocks: HashMap::new(),
+            pending_kills: Vec::new(),
+            demo_mode: false,
+            dry_run: false,
+            remote_hosts: None,
+            estimated: false,
+            byte_counting: crate::accounting::ByteCounting::default(),
+            process_filter: None,
+            stop_at: None,
+            // Interfaces view
+            interfaces: Vec::new(),
+            last_interface_poll: None,
+            capture_iface: None,
+            interface_scroll_offset: 0,
+            // Local traffic view
+            local_flows: Vec::new(),
+            local_flow_scroll_offset: 0,
+            // Services view
+            services: Vec::new(),
+            service_scroll_offset: 0,
+            // Events view
+            events: EventLog::default(),
+            event_scroll_offset: 0,
+            event_output: None,
+            // Capture statistics
+            capture_stats: None,
+            scan_stats: None,
+            show_scan_stats: false,
+            last_drop_warning: None,
+            render_profile: RenderProfile::default(),
+            show_debug_overlay: false,
+            // Quick-jump
+            jump_input: None,
+            jump_selected: 0,
+            jump_return_mode: None,
+            // Annotations
+            annotations: Vec::new(),
+            annotation_input: None,
+            baseline: None,
+            show_baseline_diff: false,
+            baseline_scroll: 0,
+            pinned: BTreeSet::new(),
+            categories: Vec::new(),
+            // Help overlay
+            show_help: false,
+            help_scroll: 0,
+            containers: crate::containers::ContainerResolver::new(),
+            group_by: None,
+            group_selected: 0,
+            group_filter: None,
         }
     }
 
-    pub fn totals(&self) -> (u64, u64, u64, u64) {
-        let mut total_sent = 0u64;
-        let mut total_received = 0u64;
-        let mut total_sent_rate = 0u64;
-        let mut total_received_rate = 0u64;
-        
-        for info in self.stats.values() {
-            total_sent += info.sent;
-            total_received += info.received;
-            total_sent_rate += info.sent_rate;
-            total_received_rate += info.received_rate;
-        }
-        
-        (total_sent, total_received, total_sent_rate, total_received_rate)
+    /// Number of points a full history window needs at the current sample interval
+    pub fn history_points(&self) -> usize {
+        let points = self.history_window_secs * 1000 / self.sample_interval_ms.max(1);
+        (points as usize).min(MAX_HISTORY_POINTS)
     }
 
-    pub fn sorted_stats(&self) -> Vec<(&i32, &ProcessInfo)> {
-        let mut sorted: Vec<_> = self.stats.iter().collect();
-        match self.sort_by {
-            SortColumn::Pid => sorted.sort_by_key(|(pid, _)| *pid),
-            SortColumn::Name => sorted.sort_by_key(|(_, info)| &info.name),
-            SortColumn::Sent => sorted.sort_by_key(|(_, info)| info.sent),
-            SortColumn::SentRate => sorted.sort_by_key(|(_, info)| info.sent_rate),
-            SortColumn::Received => sorted.sort_by_key(|(_, info)| info.received),
-            SortColumn::ReceivedRate => sorted.sort_by_key(|(_, info)| info.received_rate),
-            SortColumn::Container => sorted.sort_by_key(|(_, info)| &info.container_name),
-            SortColumn::User => sorted.sort_by_key(|(_, info)| &info.user_name),
-        }
+    /// Whether enough time has passed to record the next chart sample
+    pub fn history_sample_due(&self) -> bool {
+        self.last_history_sample.elapsed() >= std::time::Duration::from_millis(self.sample_interval_ms)
+    }
 
-        if self.sort_direction == SortDirection::Desc {
-            sorted.reverse();
+    /// Human-readable history window, e.g. "5 min" or "1 h"
+    pub fn history_window_label(&self) -> String {
+        let secs = self.history_window_secs;
+        if secs >= 3600 && secs.is_multiple_of(3600) {
+            format!("{} h", secs / 3600)
+        } else if secs >= 60 && secs.is_multiple_of(60) {
+            format!("{} min", secs / 60)
+        } else {
+            format!("{} s", secs)
         }
+    }
 
-        sorted
+    /// Estimated memory used by the chart and packet histories, which `memory_budget` limits
+    pub fn memory_usage(&self) -> usize {
+        self.memory_breakdown().evictable()
     }
 
-    pub fn update_system_stats(&mut self) {
-        // Store previous stats for rate calculation
-        self.system_stats_prev = self.system_stats.clone();
-        
-        // Reset current stats
-        self.system_stats = SystemStats::new();
-        
-        // Aggregate protocol statistics from all processes
-        for process_info in self.stats.values() {
-            // For now, we'll estimate protocol breakdown (this should be collected from packet capture)
-            // TCP is typically the majority of traffic, UDP is less, ICMP minimal
-            let total_rate = process_info.sent_rate + process_info.received_rate;
-            
-            // Rough estimation - in real implementation this would come from packet analysis
-            self.system_stats.tcp_rate += (total_rate as f64 * 0.8) as u64;
-            self.system_stats.udp_rate += (total_rate as f64 * 0.15) as u64;
-            self.system_stats.icmp_rate += (total_rate as f64 * 0.01) as u64;
-            self.system_stats.other_rate += (total_rate as f64 * 0.04) as u64;
-            
-            let total_bytes = process_info.sent + process_info.received;
-            self.system_stats.tcp_bytes += (total_bytes as f64 * 0.8) as u64;
-            self.system_stats.udp_bytes += (total_bytes as f64 * 0.15) as u64;
-            self.system_stats.icmp_bytes += (total_bytes as f64 * 0.01) as u64;
-            self.system_stats.other_bytes += (total_bytes as f64 * 0.04) as u64;
-            
-            // Estimate packet counts (rough average packet sizes)
-            // TCP: ~1400 bytes, UDP: ~512 bytes, ICMP: ~64 bytes, Other: ~800 bytes
-            self.system_stats.tcp_packets += (total_bytes as f64 * 0.8 / 1400.0) as u64;
-            self.system_stats.udp_packets += (total_bytes as f64 * 0.15 / 512.0) as u64;
-            self.system_stats.icmp_packets += (total_bytes as f64 * 0.01 / 64.0) as u64;
-            self.system_stats.other_packets += (total_bytes as f64 * 0.04 / 800.0) as u64;
+    pub fn memory_breakdown(&self) -> MemoryBreakdown {
+        MemoryBreakdown {
+            charts: self.stats.values().map(ProcessInfo::chart_history_bytes).sum(),
+            packets: self.stats.values().map(ProcessInfo::packet_history_bytes).sum(),
+            snapshots: self.snapshot_history.iter()
+                .map(|snapshot| snapshot.stats.len() * std::mem::size_of::<ProcessInfo>())
+                .sum(),
+            system: self.system_bandwidth_history.iter()
+                .map(|(_, rates)| std::mem::size_of::<f64>() + rates.capacity() * std::mem::size_of::<(i32, f64, f64)>())
+                .sum(),
         }
-        
-        // Update last non-zero system stats for display persistence
-        let total_current_rate = self.system_stats.tcp_rate + self.system_stats.udp_rate + 
-                                self.system_stats.icmp_rate + self.system_stats.other_rate;
-        if total_current_rate > 0 {
-            self.last_nonzero_system_stats = self.system_stats.clone();
+    }
+
+    /// Evict histories of the least-recently-active processes until usage is back
+    /// under 90% of the budget. Counters are kept, and playback and system history
+    /// don't count since they can't be evicted. Returns the evicted PIDs so the
+    /// capture thread can drop its copies too.
+    pub fn enforce_memory_budget(&mut self) -> Vec<i32> {
+        let budget = self.memory_budget as usize;
+        let mut usage = self.memory_usage();
+        if usage <= budget {
+            return Vec::new();
         }
-        
-        // Check if quota threshold is exceeded and update system alerts
-        let total_bytes = self.system_stats.total_bytes();
-        if total_bytes > self.total_quota_threshold {
-            if !self.threshold_exceeded {
-                self.threshold_exceeded = true;
-                self.threshold_exceeded_time = Some(std::time::Instant::now());
+        let target = budget / 10 * 9;
+
+        let mut candidates: Vec<(i32, std::time::SystemTime)> = self.stats.iter()
+            .filter(|(pid, info)| Some(**pid) != self.selected_process && info.history_bytes() > 0)
+            .map(|(pid, info)| (*pid, info.last_seen))
+            .collect();
+        candidates.sort_by_key(|(_, last_seen)| *last_seen);
+
+        let mut evicted = Vec::new();
+        for (pid, _) in candidates {
+            if usage <= target {
+                break;
             }
-            
-            // Check for system alerts that should trigger
-            for (pid, alert) in &self.alerts {
-                if let AlertAction::SystemAlert = alert.action
-                    && let Some(process_info) = self.stats.get(pid) {
-                        let process_bytes = process_info.sent + process_info.received;
-                        if process_bytes > alert.threshold_bytes {
-                            self.system_alerts.insert(*pid);
-                        }
-                    }
+            if let Some(info) = self.stats.get_mut(&pid) {
+                usage = usage.saturating_sub(info.history_bytes());
+                info.evict_histories();
+                self.evicted_processes.insert(pid);
+                evicted.push(pid);
             }
-        } else {
-            // Reset threshold exceeded state if we're below 80% of quota
-            if total_bytes <= (self.total_quota_threshold as f64 * 0.8) as u64 {
-                self.threshold_exceeded = false;
-                self.threshold_exceeded_time = None;
+        }
+
+        if !evicted.is_empty() {
+            let names: Vec<String> = evicted.iter()
+                .take(3)
+                .filter_map(|pid| self.stats.get(pid).map(|info| format!("{} ({})", info.name, pid)))
+                .collect();
+            let more = if evicted.len() > 3 { format!(" +{} more", evicted.len() - 3) } else { String::new() };
+            self.notify(EventKind::System, None, format!(
+                "🧹 Memory budget reached: evicted history of {}{}",
+                names.join(", "),
+                more
+            ));
+            self.last_alert_pid = None;
+        }
+        evicted
+    }
+
+    /// Take a fresh read of the pcap counters, warning when new drops show up
+    pub fn update_capture_stats(&mut self, stats: CaptureStats) {
+        let previous = self.capture_stats.replace(stats).unwrap_or_default();
+        let new_drops = stats.dropped.saturating_sub(previous.dropped)
+            + stats.if_dropped.saturating_sub(previous.if_dropped);
+        if new_drops == 0 {
+            return;
+        }
+
+        // Avoid re-raising the same warning every second under sustained load
+        if self.last_drop_warning.is_some_and(|last| last.elapsed() < DROP_WARNING_INTERVAL) {
+            return;
+        }
+        self.last_drop_warning = Some(Instant::now());
+        self.notify(EventKind::System, None, format!(
+            "⚠️ Packet drops: {} packets dropped by the capture ({} total); per-process numbers are undercounted. Consider a larger capture buffer (--buffer-size)",
+            new_drops,
+            stats.dropped + stats.if_dropped
+        ));
+        self.last_alert_pid = None;
+    }
+
+    /// Take the latest connection state of the --aggregate hosts, noting hosts
+    /// that went away or came back
+    pub fn update_remote_hosts(&mut self, hosts: Vec<RemoteHost>) {
+        let previous = self.remote_hosts.replace(hosts).unwrap_or_default();
+        let Some(hosts) = &self.remote_hosts else { return };
+        let mut messages = Vec::new();
+        for (host, before) in hosts.iter().zip(&previous) {
+            match (before.connected, host.connected) {
+                (false, true) => messages.push(format!("🔌 Connected to {} ({})", host.label, host.address)),
+                (true, false) => messages.push(format!(
+                    "⚠️ Lost {} ({}): {}; its processes keep their totals until it is back",
+                    host.label, host.address, host.error.as_deref().unwrap_or("disconnected")
+                )),
+                _ => {}
             }
         }
+        for message in messages {
+            self.notify(EventKind::System, None, message);
+        }
     }
-}
 
-#[derive(Clone, Serialize, Deserialize)]
-pub enum AlertAction {
-    Kill,
-    CustomCommand(String),
-    SystemAlert, // New system-wide alert that just blinks/highlights
-}
+    /// Move the process selection `delta` rows down (negative: up) the sorted table,
+    /// stopping at either end. With nothing selected, Down picks the first row and
+    /// Up the last.
+    pub fn move_selection(&mut self, delta: isize) {
+        let sorted_pids: Vec<i32> = self.sorted_stats().iter().map(|(pid, _)| **pid).collect();
+        if sorted_pids.is_empty() {
+            return;
+        }
+        let current_index = self.selected_process
+            .map(|pid| sorted_pids.iter().position(|p| *p == pid));
+        let index = match current_index {
+            Some(Some(index)) => index.saturating_add_signed(delta).min(sorted_pids.len() - 1),
+            // Selected process no longer listed: keep the selection as is
+            Some(None) => return,
+            None if delta > 0 => (delta as usize - 1).min(sorted_pids.len() - 1),
+            None => sorted_pids.len().saturating_sub(delta.unsigned_abs()),
+        };
+        self.selected_process = Some(sorted_pids[index]);
+        if self.chart_rewound_to.is_some() {
+            self.load_rewound_history();
+        }
+    }
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct Alert {
-    pub process_pid: i32,
-    pub threshold_bytes: u64,
-    pub action: AlertAction,
-}
+    /// Scroll the chart `windows` history windows back in time (forward when
+    /// negative); scrolling forward past the present returns to the live chart
+    pub fn rewind_chart(&mut self, windows: i64) {
+        let Some(pid) = self.selected_process else {
+            self.notice("⚠️ Select a process to scroll its chart back".to_string());
+            return;
+        };
+        let Some(store) = &self.history_store else {
+            self.notice("⚠️ Chart history older than the window is only kept with --history-store".to_string());
+            return;
+        };
+        let now = self.start_time.elapsed().as_secs_f64();
+        let window = self.history_window_secs as f64;
+        let oldest = store.oldest(pid)
+            .or_else(|| self.stats.get(&pid)?.sent_history.first().map(|(time, _)| *time))
+            .unwrap_or(now);
+        let end = self.chart_rewound_to.unwrap_or(now) - windows as f64 * window;
+        if end >= now {
+            self.chart_rewound_to = None;
+            self.rewound_history = None;
+            return;
+        }
+        // Stop once the chart starts at the oldest sample kept
+        let end = end.max((oldest + window).min(now));
+        if self.chart_rewound_to == Some(end) {
+            self.notice("ℹ️ That's the oldest chart history kept for this process".to_string());
+            return;
+        }
+        self.chart_rewound_to = Some(end);
+        self.chart_type = ChartType::ProcessLines;
+        self.load_rewound_history();
+    }
 
-#[derive(Clone)]
-pub struct SystemStats {
-    pub tcp_bytes: u64,
-    pub tcp_rate: u64,
-    pub tcp_packets: u64,
-    pub udp_bytes: u64,
-    pub udp_rate: u64,
+    /// Read the selected process's samples for the rewound chart, from the
+    /// store and what is still in memory
+    fn load_rewound_history(&mut self) {
+        let (Some(end), Some(pid), Some(store)) = (self.chart_rewound_to, self.selected_process, &self.history_store) else {
+            self.rewound_history = None;
+            return;
+        };
+        let start = end - self.history_window_secs as f64;
+        let (mut sent, mut received) = store.load(pid, start, end);
+        if let Some(info) = self.stats.get(&pid) {
+            let in_view = |(time, _): &&(f64, f64)| (start..=end).contains(time);
+            sent.extend(info.sent_history.iter().filter(in_view));
+            received.extend(info.received_history.iter().filter(in_view));
+        }
+        self.rewound_history = Some(RewoundHistory { pid, sent, received });
+    }
+
+    /// Short label for a chart scrolled back in time, `None` when live
+    pub fn rewound_label(&self) -> Option<String> {
+        let end = self.chart_rewound_to?;
+        let ago = (self.start_time.elapsed().as_secs_f64() - end) as u64;
+        Some(format!("⏪ CHART {} ago (</> scroll, Esc: live)", crate::ui::utils::format_countdown(Duration::from_secs(ago))))
+    }
+
+    /// Processes matching the quick-jump query, in table order: PID prefix for
+    /// numeric queries (exact PID first), otherwise case-insensitive name prefix
+    pub fn jump_matches(&self) -> Vec<i32> {
+        let query = self.jump_input.as_deref().unwrap_or("").trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<i32> = if query.chars().all(|c| c.is_ascii_digit()) {
+            self.sorted_stats()
+                .iter()
+                .map(|(pid, _)| **pid)
+                .filter(|pid| pid.to_string().starts_with(&query))
+                .collect()
+        } else {
+            self.sorted_stats()
+                .iter()
+                .filter(|(_, info)| info.name.to_lowercase().starts_with(&query))
+                .map(|(pid, _)| **pid)
+                .collect()
+        };
+        if let Some(exact) = matches.iter().position(|pid| pid.to_string() == query) {
+            let pid = matches.remove(exact);
+            matches.insert(0, pid);
+        }
+        matches.truncate(MAX_JUMP_MATCHES);
+        matches
+    }
+
+    /// Mark the current moment on the charts and in the event log
+    pub fn annotate(&mut self, label: &str) {
+        let label = label.trim();
+        if label.is_empty() {
+            return;
+        }
+        self.annotations.push(Annotation {
+            at: self.start_time.elapsed().as_secs_f64(),
+            label: label.to_string(),
+        });
+        self.notify(EventKind::Annotation, None, format!("📌 {}", label));
+    }
+
+    /// Remember the current counters to compare later ones with
+    pub fn mark_baseline(&mut self) {
+        let marked = chrono::Local::now();
+        self.baseline = Some(Baseline { marked, snapshot: crate::diff::snapshot_of(&self.stats) });
+        self.notify(EventKind::System, None, format!("📍 Baseline marked at {}; b shows what changed since", marked.format("%H:%M:%S")));
+    }
+
+    /// Select `pid` in the process table, switching to the main view when the
+    /// current one has no process table; Esc there returns to where we came from
+    pub fn jump_to_process(&mut self, pid: i32) {
+        self.selected_process = Some(pid);
+        self.show_action_panel = false;
+        self.show_sort_menu = false;
+        self.show_mute_menu = false;
+        self.playback_offset = None;
+        match self.mode {
+            AppMode::Normal | AppMode::PacketDetails => {
+                self.packet_scroll_offset = 0;
+            }
+            previous => {
+                self.jump_return_mode = Some(previous);
+                self.mode = AppMode::Normal;
+                self.bandwidth_mode = false;
+            }
+        }
+    }
+
+    /// Whether the interface counters are due for another read (once per second)
+    pub fn interface_poll_due(&self) -> bool {
+        self.last_interface_poll
+            .is_none_or(|last| last.elapsed() >= Duration::from_secs(1))
+    }
+
+    /// Replace the interface list with a fresh read, deriving rates from the previous one
+    pub fn update_interfaces(&mut self, mut interfaces: Vec<InterfaceStats>) {
+        let elapsed = self.last_interface_poll.map(|last| last.elapsed().as_secs_f64());
+        self.last_interface_poll = Some(Instant::now());
+
+        if let Some(elapsed) = elapsed.filter(|secs| *secs > 0.0) {
+            for current in &mut interfaces {
+                if let Some(previous) = self.interfaces.iter().find(|i| i.name == current.name) {
+                    // Counters can reset when a link is re-created; treat that as no traffic
+                    current.rx_rate = (current.rx_bytes.saturating_sub(previous.rx_bytes) as f64 / elapsed) as u64;
+                    current.tx_rate = (current.tx_bytes.saturating_sub(previous.tx_bytes) as f64 / elapsed) as u64;
+                }
+            }
+        }
+        for current in &mut interfaces {
+            let previous = self.interfaces.iter()
+                .find(|i| i.name == current.name)
+                .and_then(|i| i.wireless.as_ref());
+            if let Some(link) = current.wireless.as_mut() {
+                link.retry_percent = retry_percent(link, previous);
+            }
+        }
+        self.interfaces = interfaces;
+    }
+
+    /// The table's PIDs are not processes of this machine (demo or remote ones),
+    /// so nothing may signal them or read their details from the system
+    pub fn foreign_pids(&self) -> bool {
+        self.demo_mode || self.remote_hosts.is_some()
+    }
+
+    /// Track the capture thread's reports; losing and regaining the capture are logged once
+    pub fn apply_capture_event(&mut self, event: CaptureEvent) {
+        match event {
+            CaptureEvent::Lost(loss) => {
+                if self.capture_loss.is_none() {
+                    let message = format!("⚠️ Capture on {} lost: {}; reconnecting", loss.iface, loss.error);
+                    self.events.push(EventKind::System, None, &message);
+                }
+                self.capture_loss = Some(loss);
+            }
+            CaptureEvent::Restored(iface) => {
+                let message = format!("✅ Capturing on {} again", iface);
+                self.capture_loss = None;
+                self.capture_iface = Some(iface);
+                self.notify(EventKind::System, None, message);
+            }
+            CaptureEvent::Switched(iface) => {
+                let message = format!("🔌 Now capturing on {}; history so far is kept", iface);
+                self.capture_iface = Some(iface);
+                self.notify(EventKind::System, None, message);
+            }
+            CaptureEvent::SwitchFailed { iface, error } => {
+                let message = format!("❌ Can't capture on {}: {}; staying on {}", iface, error,
+                    self.capture_iface.as_deref().unwrap_or("the current interface"));
+                self.notify(EventKind::System, None, message);
+            }
+            CaptureEvent::PrivilegesDropped(dropped) => {
+                self.notify(EventKind::System, None, format!("🔒 Dropped privileges: {}", dropped));
+            }
+            CaptureEvent::Ended { iface, error } => {
+                let message = format!("❌ Capture on {} lost: {}; {}", iface, error, crate::capture::REOPEN_AFTER_DROP);
+                self.capture_loss = None;
+                self.notify(EventKind::System, None, message);
+            }
+            CaptureEvent::Failed(error) => {
+                self.capture_failure = Some(error);
+            }
+        }
+    }
+
+    /// The protected-list entry that forbids killing, throttling or blocking `pid`,
+    /// unless --allow-protected-kills is set
+    pub fn protected_reason(&self, pid: i32) -> Option<String> {
+        if self.allow_protected_kills {
+            return None;
+        }
+        let (name, user) = self.stats.get(&pid)
+            .map_or(("", None), |info| (info.name.as_str(), info.user_name.as_deref()));
+        self.protected.reason(pid, name, user)
+    }
+
+    /// Show a notification and keep it in the event log
+    pub fn notify(&mut self, kind: EventKind, pid: Option<i32>, message: String) {
+        self.events.push(kind, pid, &message);
+        self.notifications.push(message);
+    }
+
+    /// Log an alert's result and show it, as often as the alert lets it notify;
+    /// alerts saved by name share one limit across restarts of the process
+    pub fn notify_alert(&mut self, kind: EventKind, pid: i32, alert: &Alert, message: String) {
+        self.events.push(kind, Some(pid), &message);
+        self.alert_notice(pid, alert, message);
+    }
+
+    /// Show an alert's result without logging it, as often as the alert lets it notify
+    pub fn alert_notice(&mut self, pid: i32, alert: &Alert, message: String) {
+        match alert.notify_every_secs {
+            Some(secs) => {
+                let source = alert.process_name.clone().unwrap_or_else(|| pid.to_string());
+                self.notifications.push_throttled(&source, Duration::from_secs(secs), message);
+            }
+            None => self.notifications.push(message),
+        }
+    }
+
+    /// Show the result of an action without logging it as an event
+    pub fn notice(&mut self, message: String) {
+        self.notifications.push(message);
+    }
+
+    /// Time left on an active alert mute for `pid`
+    pub fn mute_remaining(&self, pid: i32) -> Option<Duration> {
+        self.alert_mutes
+            .get(&pid)
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    pub fn is_alert_muted(&self, pid: i32) -> bool {
+        self.mute_remaining(pid).is_some()
+    }
+
+    /// Suppress actions and notifications for the alert on `pid`
+    pub fn mute_alert(&mut self, pid: i32, duration: MuteDuration) {
+        self.alert_mutes.insert(pid, Instant::now() + duration.duration());
+        self.system_alerts.remove(&pid);
+        let name = self.stats.get(&pid).map(|info| info.name.clone()).unwrap_or_default();
+        self.notify(EventKind::Mute, Some(pid), format!(
+            "🔇 Alert muted: {} (PID {}) for {}",
+            name,
+            pid,
+            duration.label().to_lowercase()
+        ));
+        self.last_alert_pid = Some(pid);
+    }
+
+    pub fn unmute_alert(&mut self, pid: i32) {
+        if self.alert_mutes.remove(&pid).is_some() {
+            let name = self.stats.get(&pid).map(|info| info.name.clone()).unwrap_or_default();
+            self.notify(EventKind::Mute, Some(pid), format!("🔔 Alert unmuted: {} (PID {})", name, pid));
+            self.last_alert_pid = Some(pid);
+        }
+    }
+
+    /// Mark newly exited processes, keeping their final totals on screen with
+    /// zero rates, and drop those kept longer than the retention time.
+    /// Returns the PIDs removed for good.
+    pub fn retire_exited_processes(&mut self, exited: Vec<i32>) -> Vec<i32> {
+        let now = Instant::now();
+        for pid in exited {
+            if !self.exited_processes.contains_key(&pid) {
+                self.note_departure(pid);
+            }
+            // Its rules and cgroup are of no use anymore, and a reused PID mustn't inherit them
+            if let Some(block) = self.blocks.remove(&pid)
+                && !self.demo_mode {
+                    let _ = crate::block::unblock(pid, &block);
+                }
+            if let Some(info) = self.stats.get_mut(&pid) {
+                info.sent_rate = 0;
+                info.received_rate = 0;
+                info.new_connection_rate = 0;
+            }
+            self.exited_processes.entry(pid).or_insert(now);
+        }
+
+        let retention = Duration::from_secs(self.exited_retention_secs);
+        let expired: Vec<i32> = self.exited_processes
+            .iter()
+            .filter(|(_, since)| since.elapsed() >= retention)
+            .map(|(pid, _)| *pid)
+            .collect();
+        for pid in &expired {
+            self.exited_processes.remove(pid);
+            self.evicted_processes.remove(pid);
+            self.stats.remove(pid);
+            if let Some(limit) = self.limits.remove(pid)
+                && !self.demo_mode {
+                    crate::limit::release(&limit);
+                }
+            self.dead_processes_cache.insert(*pid);
+            if self.selected_process == Some(*pid) {
+                self.selected_process = None;
+            }
+        }
+        expired
+    }
+
+    /// Keep what `pid` had for a restart of it to take over; call while it is still in the table
+    pub fn note_departure(&mut self, pid: i32) {
+        if !self.restarts.knows(pid) {
+            return;
+        }
+        let Some(info) = self.stats.get(&pid) else { return };
+        let departed = crate::restarts::Departed::new(
+            pid,
+            info,
+            self.alerts.get(&pid).cloned(),
+            self.alert_states.remove(&pid),
+            self.alert_mutes.get(&pid).copied(),
+        );
+        self.restarts.depart(departed);
+    }
+
+    /// Look up a process that just entered the table; a restart of one that
+    /// exited or was killed takes over its totals, chart history and alert
+    pub fn note_arrival(&mut self, pid: i32) {
+        if self.foreign_pids() {
+            return;
+        }
+        let Some(identity) = crate::restarts::identity(pid) else { return };
+        let Some(before) = self.restarts.arrived(pid, identity) else { return };
+        let Some(info) = self.stats.get_mut(&pid) else { return };
+
+        info.sent += before.sent;
+        info.received += before.received;
+        if let Some(store) = &mut self.history_store {
+            store.rename(before.pid, pid);
+        }
+        info.sent_history.splice(0..0, before.sent_history);
+        info.received_history.splice(0..0, before.received_history);
+        info.first_seen = before.first_seen;
+        info.restart = Some(crate::restarts::Restart {
+            previous_pid: before.pid,
+            count: before.restarts + 1,
+            sent: before.sent,
+            received: before.received,
+        });
+        let count = before.restarts + 1;
+
+        if let Some(alert) = before.alert
+            && !self.alerts.contains_key(&pid) {
+                self.alerts.insert(pid, Alert { process_pid: pid, ..alert });
+            }
+        if self.system_alerts.remove(&before.pid) {
+            self.system_alerts.insert(pid);
+        }
+        if let Some(state) = before.alert_state {
+            self.alert_states.insert(pid, state);
+        }
+        if let Some(until) = before.muted_until.filter(|until| *until > Instant::now()) {
+            self.alert_mutes.insert(pid, until);
+        }
+        // The exited row lives on in this one
+        if self.exited_processes.remove(&before.pid).is_some() {
+            self.stats.remove(&before.pid);
+            self.dead_processes_cache.insert(before.pid);
+        }
+        if self.selected_process == Some(before.pid) {
+            self.selected_process = Some(pid);
+        }
+        let times = if count == 1 { String::new() } else { format!(" ({} restarts)", count) };
+        self.notify(EventKind::Restart, Some(pid), format!(
+            "↻ {} restarted as PID {} (was {}){}; its totals, history and alert carry on",
+            before.name, pid, before.pid, times
+        ));
+    }
+
+    pub fn is_exited(&self, pid: i32) -> bool {
+        self.exited_processes.contains_key(&pid)
+    }
+
+    /// Drop mutes that have run out so the alert fires normally again
+    pub fn expire_alert_mutes(&mut self) {
+        let alerts = &self.alerts;
+        self.alert_mutes.retain(|pid, _| alerts.contains_key(pid));
+        let expired: Vec<i32> = self.alert_mutes
+            .keys()
+            .copied()
+            .filter(|pid| !self.is_alert_muted(*pid))
+            .collect();
+        for pid in expired {
+            self.unmute_alert(pid);
+        }
+    }
+
+    /// Record a snapshot of the process table at most once per second
+    pub fn record_snapshot(&mut self) {
+        if self.last_snapshot.elapsed() < std::time::Duration::from_secs(1) {
+            return;
+        }
+        self.last_snapshot = Instant::now();
+
+        let snapshot = StatsSnapshot {
+            time: self.start_time.elapsed().as_secs_f64(),
+            stats: self.stats.iter().map(|(pid, info)| (*pid, info.summary())).collect(),
+        };
+        if self.snapshot_history.len() >= MAX_SNAPSHOT_HISTORY {
+            self.snapshot_history.pop_front();
+        }
+        self.snapshot_history.push_back(snapshot);
+
+        // Keep playback anchored to the same moment while new snapshots arrive
+        if let Some(offset) = self.playback_offset {
+            self.playback_offset = Some((offset + 1).min(self.snapshot_history.len() - 1));
+        }
+    }
+
+    /// Snapshot currently shown in playback, if any
+    pub fn playback_snapshot(&self) -> Option<&StatsSnapshot> {
+        let offset = self.playback_offset?;
+        let index = self.snapshot_history.len().checked_sub(offset + 1)?;
+        self.snapshot_history.get(index)
+    }
+
+    /// Step one snapshot back in time
+    pub fn scrub_back(&mut self) {
+        if self.snapshot_history.is_empty() {
+            return;
+        }
+        let max_offset = self.snapshot_history.len() - 1;
+        self.playback_offset = Some(match self.playback_offset {
+            None => 0,
+            Some(offset) => (offset + 1).min(max_offset),
+        });
+    }
+
+    /// Step one snapshot forward; stepping past the newest returns to the live view
+    pub fn scrub_forward(&mut self) {
+        self.playback_offset = match self.playback_offset {
+            Some(0) | None => None,
+            Some(offset) => Some(offset - 1),
+        };
+    }
+
+    /// Process table to display: the playback snapshot when scrubbing, live stats otherwise
+    pub fn visible_stats(&self) -> &HashMap<i32, ProcessInfo> {
+        match self.playback_snapshot() {
+            Some(snapshot) => &snapshot.stats,
+            None => &self.stats,
+        }
+    }
+
+    /// Time (seconds since start) the charts should end at
+    pub fn view_time(&self) -> f64 {
+        match self.playback_snapshot() {
+            Some(snapshot) => snapshot.time,
+            None => self.chart_rewound_to.unwrap_or_else(|| self.start_time.elapsed().as_secs_f64()),
+        }
+    }
+
+    /// Short label describing the playback position, `None` when live
+    pub fn playback_label(&self) -> Option<String> {
+        let snapshot = self.playback_snapshot()?;
+        let ago = self.start_time.elapsed().as_secs_f64() - snapshot.time;
+        Some(format!("⏪ PLAYBACK {:.0}s ago (←/→ scrub, Esc: live)", ago))
+    }
+
+    pub fn totals(&self) -> (u64, u64, u64, u64) {
+        let mut total_sent = 0u64;
+        let mut total_received = 0u64;
+        let mut total_sent_rate = 0u64;
+        let mut total_received_rate = 0u64;
+        
+        // VPN daemons carry traffic already counted for the processes inside the tunnel
+        for info in self.visible_stats().values().filter(|info| !info.tunnel) {
+            let traffic = info.scoped(self.traffic_scope);
+            total_sent += traffic.sent;
+            total_received += traffic.received;
+            total_sent_rate += traffic.sent_rate;
+            total_received_rate += traffic.received_rate;
+        }
+        
+        (total_sent, total_received, total_sent_rate, total_received_rate)
+    }
+
+    /// Estimated cost of a process's traffic; loopback traffic never reaches the metered link
+    pub fn process_cost(&self, info: &ProcessInfo) -> f64 {
+        let traffic = info.scoped(TrafficScope::External);
+        self.cost.cost(traffic.sent, traffic.received)
+    }
+
+    /// Estimated cost so far and per hour at the current rate, whatever the table's scope
+    pub fn estimated_cost(&self) -> (f64, f64) {
+        let mut total = 0.0;
+        let mut per_second = 0.0;
+        for info in self.visible_stats().values().filter(|info| !info.tunnel) {
+            let traffic = info.scoped(TrafficScope::External);
+            total += self.cost.cost(traffic.sent, traffic.received);
+            per_second += self.cost.cost(traffic.sent_rate, traffic.received_rate);
+        }
+        (total, per_second * 3600.0)
+    }
+
+    /// Bytes and number of processes per classification category, in config order
+    pub fn category_totals(&self) -> Vec<(&str, u64, usize)> {
+        self.categories.iter().map(|category| {
+            let (bytes, processes) = self.visible_stats().values()
+                .filter(|info| !info.tunnel)
+                .filter_map(|info| info.category_bytes.get(category))
+                .fold((0, 0), |(bytes, processes), counted| (bytes + counted, processes + 1));
+            (category.as_str(), bytes, processes)
+        }).collect()
+    }
+
+    /// Actions the panel offers for `pid`, in the order it lists them
+    pub fn process_actions(&self, pid: i32) -> Vec<&'static str> {
+        let mut actions = vec!["Kill", "Edit", "Details"];
+        if self.alerts.contains_key(&pid) {
+            actions.extend(["Remove", "Mute"]);
+        }
+        actions.push("Limit");
+        if self.limits.contains_key(&pid) {
+            actions.push("Unlimit");
+        }
+        actions.push(if self.blocks.contains_key(&pid) { "Unblock" } else { "Block" });
+        actions
+    }
+
+    /// Cap what `pid` sends at `rate` bytes per second, or change its cap
+    pub fn limit_process(&mut self, pid: i32, rate: u64) {
+        let name = self.stats.get(&pid).map_or_else(|| format!("PID {}", pid), |info| info.name.clone());
+        if let Some(reason) = self.protected_reason(pid) {
+            let message = format!("🛡️ Didn't limit {} (PID {}): protected by {}", name, pid, reason);
+            self.notify(EventKind::System, Some(pid), message);
+            return;
+        }
+        let result = if self.remote_hosts.is_some() {
+            Err("processes of other machines can't be limited from here".to_string())
+        } else if self.demo_mode {
+            // Demo PIDs are made up; only pretend
+            Ok(crate::limit::BandwidthLimit { interface: "demo0".to_string(), minor: 0, rate })
+        } else {
+            let interface = self.capture_iface.clone()
+                .filter(|iface| iface != "any")
+                .or_else(crate::limit::default_interface);
+            match interface {
+                Some(interface) => crate::limit::apply(pid, &interface, rate, self.limits.get(&pid)),
+                None => Err("no interface to shape: capture on one with -i".to_string()),
+            }
+        };
+        let message = match result {
+            Ok(limit) => {
+                let message = format!("🚦 Limited {} (PID {}) to sending {} on {}", name, pid, format_rate(rate), limit.interface);
+                self.limits.insert(pid, limit);
+                message
+            }
+            Err(e) => format!("❌ Can't limit {} (PID {}): {}", name, pid, e),
+        };
+        self.notify(EventKind::System, Some(pid), message);
+    }
+
+    pub fn unlimit_process(&mut self, pid: i32) {
+        let Some(limit) = self.limits.remove(&pid) else { return };
+        let name = self.stats.get(&pid).map_or_else(|| format!("PID {}", pid), |info| info.name.clone());
+        let result = if self.demo_mode { Ok(()) } else { crate::limit::remove(&limit) };
+        let message = match result {
+            Ok(()) => format!("🚦 Removed the bandwidth limit of {} (PID {})", name, pid),
+            Err(e) => format!("❌ Can't fully remove the limit of {} (PID {}): {}", name, pid, e),
+        };
+        self.notify(EventKind::System, Some(pid), message);
+    }
+
+    /// Cut `pid` off the network; says why not when it can't be
+    pub fn block_process(&mut self, pid: i32) -> Result<String, String> {
+        let info = self.stats.get(&pid).ok_or_else(|| format!("PID {} is gone", pid))?;
+        let name = info.name.clone();
+        if let Some(reason) = self.protected_reason(pid) {
+            return Err(format!("🛡️ Didn't block {} (PID {}): protected by {}", name, pid, reason));
+        }
+        if self.remote_hosts.is_some() {
+            return Err(format!("❌ Can't block {} (PID {}): it runs on a remote host", name, pid));
+        }
+        if self.blocks.contains_key(&pid) {
+            // Blocking again would save the block group as the one to return to, and add duplicate rules
+            return Ok(format!("⛔ {} (PID {}) is already blocked", name, pid));
+        }
+        let block = if self.demo_mode {
+            // Demo PIDs are made up; only pretend
+            crate::block::NetworkBlock {
+                firewall: crate::block::Firewall::Nftables,
+                target: crate::block::BlockTarget::Endpoints(Vec::new()),
+                original_cgroup: None,
+            }
+        } else {
+            let endpoints: Vec<std::net::IpAddr> = info.host_contacts.iter()
+                .map(|contact| contact.host)
+                .filter(|host| !host.is_loopback() && !host.is_unspecified())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            crate::block::block(pid, &endpoints)
+                .map_err(|e| format!("❌ Can't block {} (PID {}): {}", name, pid, e))?
+        };
+        let message = format!("⛔ Blocked {} of {} (PID {}) until unblocked or monitetoring exits", block.describe(), name, pid);
+        self.blocks.insert(pid, block);
+        Ok(message)
+    }
+
+    pub fn unblock_process(&mut self, pid: i32) {
+        let Some(block) = self.blocks.remove(&pid) else { return };
+        let name = self.stats.get(&pid).map_or_else(|| format!("PID {}", pid), |info| info.name.clone());
+        let result = if self.demo_mode { Ok(()) } else { crate::block::unblock(pid, &block) };
+        let message = match result {
+            Ok(()) => format!("✅ Unblocked {} (PID {})", name, pid),
+            Err(e) => format!("❌ Can't fully unblock {} (PID {}): {}", name, pid, e),
+        };
+        self.notify(EventKind::System, Some(pid), message);
+    }
+
+    /// Lift every block, when monitetoring exits
+    pub fn unblock_all(&mut self) {
+        for (pid, block) in self.blocks.drain() {
+            if !self.demo_mode {
+                let _ = crate::block::unblock(pid, &block);
+            }
+        }
+    }
+
+    pub fn toggle_mark(&mut self, pid: i32) {
+        if !self.marked.remove(&pid) {
+            self.marked.insert(pid);
+        }
+    }
+
+    /// Marked processes that are still listed, in table order
+    pub fn marked_pids(&self) -> Vec<i32> {
+        self.sorted_stats().iter()
+            .map(|(pid, _)| **pid)
+            .filter(|pid| self.marked.contains(pid))
+            .collect()
+    }
+
+    pub fn is_pinned(&self, info: &ProcessInfo) -> bool {
+        self.pinned.contains(&info.name)
+    }
+
+    /// Set the alert of `pid`, and of every other process of its name: a rule
+    /// for the name replaces the alerts its previous version left on them
+    pub fn set_alert(&mut self, pid: i32, alert: Alert) {
+        if let Some(name) = &alert.process_name {
+            let replaced: Vec<i32> = self.alerts.iter()
+                .filter(|(_, existing)| existing.process_name.as_ref() == Some(name))
+                .map(|(pid, _)| *pid)
+                .collect();
+            for pid in replaced {
+                self.alerts.remove(&pid);
+                self.alert_states.remove(&pid);
+            }
+            self.alert_rules.insert(name.clone(), alert.clone());
+        }
+        self.alerts.insert(pid, alert);
+        // A saved alert starts over: fresh cooldown, armed, no triggers counted
+        self.alert_states.remove(&pid);
+    }
+
+    /// Remove the alert of `pid`, and its rule with the alerts of the other processes of that name
+    pub fn remove_alert(&mut self, pid: i32) {
+        let Some(alert) = self.alerts.remove(&pid) else { return };
+        self.system_alerts.remove(&pid);
+        self.alert_mutes.remove(&pid);
+        if let Some(name) = alert.process_name {
+            self.alert_rules.remove(&name);
+            self.alerts.retain(|_, other| other.process_name.as_ref() != Some(&name));
+        }
+    }
+
+    /// Give processes that appeared since the last tick the alert of their name
+    pub fn attach_alert_rules(&mut self) {
+        if self.alert_rules.is_empty() {
+            return;
+        }
+        for (pid, info) in &self.stats {
+            if self.alerts.contains_key(pid) || self.is_exited(*pid) {
+                continue;
+            }
+            if let Some(rule) = self.alert_rules.get(&info.name) {
+                self.alerts.insert(*pid, Alert { process_pid: *pid, ..rule.clone() });
+            }
+        }
+    }
+
+    /// Write the alert rules to the config file, creating it if needed, with a
+    /// notification saying whether that worked
+    pub fn save_alerts(&mut self) {
+        let count = self.alert_rules.len();
+        // Demo and remote processes aren't local; a rule for their names could act on real ones next time
+        if self.foreign_pids() {
+            self.notice("💾 Alerts not saved: this session only (demo/remote processes)".to_string());
+            return;
+        }
+        let mut alerts: Vec<Alert> = self.alert_rules.values().cloned().collect();
+        alerts.sort_by(|a, b| a.process_name.cmp(&b.process_name));
+        // Alerts from configs written before alerts had names are kept by PID
+        alerts.extend(self.alerts.values().filter(|alert| alert.process_name.is_none()).cloned());
+        let saved = self.save_to_config(|config| config.alerts = alerts);
+        self.notice(match saved {
+            Ok(()) => format!("💾 Saved {} alert{} to the config", count, if count == 1 { "" } else { "s" }),
+            Err(e) => format!("💾 Alerts not saved: this session only ({})", e),
+        });
+    }
+
+    /// Pin or unpin every process named like `pid`, saving the pins to the config file
+    pub fn toggle_pin(&mut self, pid: i32) {
+        let Some(name) = self.stats.get(&pid).map(|info| info.name.clone()) else {
+            return;
+        };
+        let pinned = if self.pinned.remove(&name) {
+            false
+        } else {
+            self.pinned.insert(name.clone());
+            true
+        };
+        let pins = self.pinned.iter().cloned().collect();
+        let saved = self.save_to_config(|config| config.pinned = pins);
+        let mut message = if pinned {
+            format!("📌 Pinned {} to the top of the table", name)
+        } else {
+            format!("📌 Unpinned {}", name)
+        };
+        if let Err(e) = saved {
+            message.push_str(&format!(" (this session only: {})", e));
+        }
+        self.notice(message);
+    }
+
+    /// Change the saved config the way Settings does: created from this session
+    /// when there is none yet, left alone when it doesn't parse
+    fn save_to_config(&self, change: impl FnOnce(&mut crate::config::SavedConfig)) -> Result<(), String> {
+        let (mut config, _) = crate::ui::settings_fields::editable_config(self)?;
+        change(&mut config);
+        crate::config::save_config(&config).map_err(|e| format!("can't save the config: {}", e))
+    }
+
+    /// No traffic right now and none for `idle_after_secs`
+    pub fn is_idle(&self, info: &ProcessInfo) -> bool {
+        info.sent_rate == 0
+            && info.received_rate == 0
+            && info.last_seen.elapsed().is_ok_and(|quiet| quiet.as_secs() >= self.idle_after_secs)
+    }
+
+    /// Whether `z` currently keeps this process out of the table; pinned, selected and marked ones always stay
+    pub fn is_hidden_idle(&self, pid: i32, info: &ProcessInfo) -> bool {
+        self.hide_idle
+            && self.is_idle(info)
+            && !self.is_pinned(info)
+            && self.selected_process != Some(pid)
+            && !self.marked.contains(&pid)
+    }
+
+    /// The group `info` falls in when the table is grouped by `by`
+    pub fn group_key(&self, by: GroupBy, info: &ProcessInfo) -> String {
+        let container = info.container_name.as_ref();
+        let meta = container.and_then(|container| self.containers.get(container));
+        let key = match (by, container, meta) {
+            (GroupBy::Unit, _, _) => return info.unit.clone().unwrap_or_else(|| "(no unit)".to_string()),
+            (_, None, _) => return "host".to_string(),
+            (GroupBy::Container, container, _) => container.cloned(),
+            (_, _, None) => return "(looking up)".to_string(),
+            (GroupBy::Image, _, Some(meta)) => meta.image.clone(),
+            (GroupBy::ComposeProject, _, Some(meta)) => meta.compose_project.clone(),
+            (GroupBy::Pod, _, Some(meta)) => meta.pod_name(),
+        };
+        key.unwrap_or_else(|| "(none)".to_string())
+    }
+
+    /// Processes in the table: idle ones hidden with `z`, those outside the chosen group
+    /// and those without traffic on the filtered port left out
+    fn listed_stats(&self) -> impl Iterator<Item = (&i32, &ProcessInfo)> {
+        self.visible_stats().iter()
+            .filter(|(pid, info)| !self.is_hidden_idle(**pid, info))
+            .filter(|(_, info)| self.port_filter.is_none_or(|port| info.port_bytes.contains_key(&port)))
+            .filter(|(_, info)| self.group_filter.as_ref().is_none_or(|(by, key)| self.group_key(*by, info) == *key))
+    }
+
+    /// The table's processes folded by `group_by`, busiest group first
+    pub fn grouped_stats(&self) -> Vec<ProcessGroup> {
+        let Some(by) = self.group_by else { return Vec::new() };
+        let mut groups: HashMap<String, ProcessGroup> = HashMap::new();
+        for (_, info) in self.listed_stats() {
+            let key = self.group_key(by, info);
+            let traffic = info.scoped(self.traffic_scope);
+            let group = groups.entry(key.clone()).or_insert_with(|| ProcessGroup {
+                key, processes: 0, sent: 0, received: 0, sent_rate: 0, received_rate: 0,
+            });
+            group.processes += 1;
+            group.sent += traffic.sent;
+            group.received += traffic.received;
+            group.sent_rate += traffic.sent_rate;
+            group.received_rate += traffic.received_rate;
+        }
+        let mut groups: Vec<ProcessGroup> = groups.into_values().collect();
+        groups.sort_by(|a, b| (b.sent_rate + b.received_rate).cmp(&(a.sent_rate + a.received_rate))
+            .then_with(|| (b.sent + b.received).cmp(&(a.sent + a.received)))
+            .then_with(|| a.key.cmp(&b.key)));
+        groups
+    }
+
+    pub fn sorted_stats(&self) -> Vec<(&i32, &ProcessInfo)> {
+        let mut sorted: Vec<_> = self.listed_stats().collect();
+        let directed = |column: SortColumn, direction: SortDirection, a: (&i32, &ProcessInfo), b: (&i32, &ProcessInfo)| {
+            let ordering = column.compare(a, b, self.traffic_scope);
+            if direction == SortDirection::Desc { ordering.reverse() } else { ordering }
+        };
+
+        sorted.sort_by(|a, b| {
+            // Pinned processes form their own section above the rest, sorted the same way
+            self.is_pinned(b.1).cmp(&self.is_pinned(a.1))
+                .then_with(|| directed(self.sort_by, self.sort_direction, *a, *b))
+                .then_with(|| match self.secondary_sort {
+                    Some((column, direction)) => directed(column, direction, *a, *b),
+                    None => std::cmp::Ordering::Equal,
+                })
+                // Fall back to PID so rows with equal keys don't jump around between frames
+                .then_with(|| a.0.cmp(b.0))
+        });
+
+        sorted
+    }
+
+    pub fn update_system_stats(&mut self) {
+        // Store previous stats for rate calculation
+        self.system_stats_prev = self.system_stats.clone();
+
+        // What the processes account for; the quota goes by this either way
+        let estimated = self.estimated_system_stats();
+        let total_bytes = estimated.total_bytes();
+
+        // The capture thread's own counts cover every packet, whether or not a
+        // process was found for it; without a capture (demo mode, extended
+        // stats, remote agents) the processes' traffic is all there is
+        self.traffic = crate::traffic_stats::latest(&self.traffic_source);
+        self.system_stats = if self.traffic.is_empty() {
+            estimated
+        } else {
+            SystemStats::from_traffic(&self.traffic)
+        };
+        
+        // Update last non-zero system stats for display persistence
+        let total_current_rate = self.system_stats.tcp_rate + self.system_stats.udp_rate + self.system_stats.quic_rate +
+                                self.system_stats.icmp_rate + self.system_stats.other_rate;
+        if total_current_rate > 0 {
+            self.last_nonzero_system_stats = self.system_stats.clone();
+        }
+        
+        // Check if quota threshold is exceeded and update system alerts
+        if total_bytes > self.total_quota_threshold {
+            if !self.threshold_exceeded {
+                self.threshold_exceeded = true;
+                self.threshold_exceeded_time = Some(std::time::Instant::now());
+                self.events.push(EventKind::Quota, None, &format!(
+                    "📊 Data quota of {} exceeded: {} used",
+                    format_bytes(self.total_quota_threshold),
+                    format_bytes(total_bytes)
+                ));
+            }
+            
+            // Check for system alerts that should trigger
+            for (pid, alert) in &self.alerts {
+                if let AlertAction::SystemAlert = alert.action
+                    && !self.is_alert_muted(*pid)
+                    && let Some(process_info) = self.stats.get(pid)
+                    && alert.is_exceeded(process_info, self.anomalies.score(*pid)) {
+                        self.system_alerts.insert(*pid);
+                    }
+            }
+        } else {
+            // Reset threshold exceeded state if we're below 80% of quota
+            if total_bytes <= (self.total_quota_threshold as f64 * 0.8) as u64 {
+                self.threshold_exceeded = false;
+                self.threshold_exceeded_time = None;
+            }
+        }
+
+        if let Some(budget) = self.cost.budget.filter(|_| self.cost.is_enabled()) {
+            let (cost, _) = self.estimated_cost();
+            if cost > budget && !self.cost_budget_exceeded {
+                self.cost_budget_exceeded = true;
+                let message = format!(
+                    "💰 Cost budget of {} exceeded: {} estimated",
+                    self.cost.format(budget),
+                    self.cost.format(cost)
+                );
+                self.notify(EventKind::Quota, None, message);
+            } else if cost <= budget * 0.8 {
+                self.cost_budget_exceeded = false;
+            }
+        }
+    }
+
+    /// Protocol breakdown from the processes' own traffic, split by what the
+    /// capture counted per protocol for each of them or by a rough estimate
+    fn estimated_system_stats(&self) -> SystemStats {
+        let mut stats = SystemStats::new();
+        for process_info in self.stats.values() {
+            let total_rate = process_info.sent_rate + process_info.received_rate;
+            let total_bytes = process_info.sent + process_info.received;
+
+            // Shares of TCP, UDP other than QUIC, QUIC, ICMP and the rest. Processes the
+            // capture counted per protocol are split by those counts (ICMP goes with the
+            // rest, as sockets don't see it); for the others, a rough estimate
+            let counted = process_info.tcp_bytes + process_info.udp_bytes;
+            let [tcp, udp, quic, icmp, other] = if counted > 0 && total_bytes > 0 {
+                let share = |bytes: u64| (bytes as f64 / total_bytes as f64).min(1.0);
+                let tcp = share(process_info.tcp_bytes);
+                let quic = share(process_info.quic_bytes);
+                let udp = share(process_info.udp_bytes.saturating_sub(process_info.quic_bytes));
+                [tcp, udp, quic, 0.0, (1.0 - tcp - udp - quic).max(0.0)]
+            } else {
+                // TCP is typically the majority of traffic, UDP is less, ICMP minimal
+                [0.8, 0.15, 0.0, 0.01, 0.04]
+            };
+
+            stats.tcp_rate += (total_rate as f64 * tcp) as u64;
+            stats.udp_rate += (total_rate as f64 * udp) as u64;
+            stats.quic_rate += (total_rate as f64 * quic) as u64;
+            stats.icmp_rate += (total_rate as f64 * icmp) as u64;
+            stats.other_rate += (total_rate as f64 * other) as u64;
+
+            stats.tcp_bytes += (total_bytes as f64 * tcp) as u64;
+            stats.udp_bytes += (total_bytes as f64 * udp) as u64;
+            stats.quic_bytes += (total_bytes as f64 * quic) as u64;
+            stats.icmp_bytes += (total_bytes as f64 * icmp) as u64;
+            stats.other_bytes += (total_bytes as f64 * other) as u64;
+
+            // Estimate packet counts (rough average packet sizes)
+            // TCP: ~1400 bytes, UDP: ~512 bytes, QUIC: ~1200 bytes, ICMP: ~64 bytes, Other: ~800 bytes
+            stats.tcp_packets += (total_bytes as f64 * tcp / 1400.0) as u64;
+            stats.udp_packets += (total_bytes as f64 * udp / 512.0) as u64;
+            stats.quic_packets += (total_bytes as f64 * quic / 1200.0) as u64;
+            stats.icmp_packets += (total_bytes as f64 * icmp / 64.0) as u64;
+            stats.other_packets += (total_bytes as f64 * other / 800.0) as u64;
+        }
+        stats
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum AlertAction {
+    Kill,
+    CustomCommand(String),
+    SystemAlert, // New system-wide alert that just blinks/highlights
+    CapturePcap(u64), // Record the process's packets to a pcap file for this many seconds
+    Block, // Drop the process's network traffic with a firewall rule, leaving it running
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub process_pid: i32,
+    pub threshold_bytes: u64,
+    pub action: AlertAction,
+    /// Fire on new connections per second instead of total bytes (scanners, crawlers, retry loops)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_new_connections: Option<u64>,
+    /// Fire when the process's bandwidth is far above its learned baseline instead
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub on_anomaly: bool,
+    /// Minimum time between two firings
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// After firing, wait until usage drops below this share (percent) of the threshold before firing again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rearm_below_percent: Option<u8>,
+    /// Stop firing after this many triggers in a session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_triggers: Option<u32>,
+    /// Show at most one notification per this many seconds; firings in between are only logged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_every_secs: Option<u64>,
+    /// Only log what a kill or custom command would have done
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+    /// Name of the process the alert was set on. Alerts saved with a name apply
+    /// to every process of that name, so they survive the PID changing on restart
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_name: Option<String>,
+}
+
+fn default_cooldown_secs() -> u64 {
+    crate::alerts::DEFAULT_COOLDOWN_SECS
+}
+
+impl Alert {
+    /// Whether the process is past this alert's limit; `anomaly` is its current
+    /// baseline score, if the baseline has finished learning
+    pub fn is_exceeded(&self, info: &ProcessInfo, anomaly: Option<AnomalyScore>) -> bool {
+        if self.on_anomaly {
+            return anomaly.is_some_and(|score| score.is_anomalous());
+        }
+        match self.max_new_connections {
+            Some(limit) => info.new_connection_rate > limit,
+            None => info.sent + info.received > self.threshold_bytes,
+        }
+    }
+
+    /// How far the process is towards the limit, 1.0 and above when exceeded
+    pub fn progress(&self, info: &ProcessInfo, anomaly: Option<AnomalyScore>) -> f64 {
+        if self.on_anomaly {
+            return anomaly.map_or(0.0, |score| score.sigmas / score.threshold);
+        }
+        match self.max_new_connections {
+            Some(limit) => info.new_connection_rate as f64 / limit.max(1) as f64,
+            None => (info.sent + info.received) as f64 / self.threshold_bytes.max(1) as f64,
+        }
+    }
+
+    /// The limit as typed in the alert editor
+    pub fn threshold_input(&self) -> String {
+        if self.on_anomaly {
+            return "anomaly".to_string();
+        }
+        match self.max_new_connections {
+            Some(limit) => format!("{} conn/s", limit),
+            None => format_bytes(self.threshold_bytes),
+        }
+    }
+
+    /// Current usage against the limit, for notifications
+    pub fn usage_text(&self, info: &ProcessInfo, anomaly: Option<AnomalyScore>) -> String {
+        if self.on_anomaly {
+            return match anomaly {
+                Some(score) => format!(
+                    "{}, {:.1}σ above its baseline of {}",
+                    format_rate(score.current as u64), score.sigmas, format_rate(score.baseline as u64)
+                ),
+                None => "baseline still learning".to_string(),
+            };
+        }
+        let over = ((self.progress(info, anomaly) - 1.0) * 100.0).max(0.0) as u32;
+        match self.max_new_connections {
+            Some(_) => format!("{} new connections/s ({}% over threshold)", info.new_connection_rate, over),
+            None => format!("{} ({}% over threshold)", format_bytes(info.sent + info.received), over),
+        }
+    }
+}
+
+/// Counters reported by libpcap for the capture handle
+#[derive(Clone, Copy, Default)]
+pub struct CaptureStats {
+    pub received: u64,
+    pub dropped: u64,    // Dropped by the kernel because the capture buffer was full
+    pub if_dropped: u64, // Dropped by the interface or its driver
+}
+
+/// The live capture failed and the capture thread is trying to get it back
+#[derive(Clone)]
+pub struct CaptureLoss {
+    pub iface: String,
+    pub error: String,
+    pub attempt: u32,       // Reconnect attempts so far
+    pub retry_at: Instant,  // When the next one starts
+}
+
+/// What a custom command printed, opened from its event
+pub struct OutputView {
+    pub title: String,
+    pub message: String, // The event's line: how the command went
+    pub output: std::sync::Arc<crate::action_queue::CommandOutput>,
+    pub scroll: usize,
+}
+
+/// One row of the interface picker
+pub struct InterfaceChoice {
+    pub name: String,
+    pub description: Option<String>,
+    pub is_up: bool,
+    pub traffic_bytes: Option<u64>, // Seen during the measurement, once it reported
+}
+
+/// Dialog for moving the capture to another interface
+pub struct InterfacePicker {
+    pub choices: Vec<InterfaceChoice>,
+    pub selected: usize,
+    pub measurement: Option<std::sync::mpsc::Receiver<(String, u64)>>, // Running byte counts, until every interface is done
+    pub measure_started: Instant,
+    pub measured_by: Instant, // When the measurement gives up on slow interfaces
+}
+
+impl InterfacePicker {
+    /// Fill in the traffic counts that have arrived; stops listening at the
+    /// deadline or as soon as the busiest interface is clear
+    pub fn poll_measurement(&mut self) {
+        let Some(measurement) = &self.measurement else { return };
+        loop {
+            match measurement.try_recv() {
+                Ok((name, bytes)) => {
+                    if let Some(choice) = self.choices.iter_mut().find(|choice| choice.name == name) {
+                        choice.traffic_bytes = Some(bytes);
+                    }
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    let now = Instant::now();
+                    if now >= self.measured_by
+                        || crate::interactive::measurement_settled(&self.choices, now.duration_since(self.measure_started)) {
+                        self.measurement = None;
+                    }
+                    return;
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.measurement = None;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// What the capture thread reports about the capture itself
+pub enum CaptureEvent {
+    Lost(CaptureLoss),
+    Restored(String), // Capturing again, on this interface
+    Switched(String), // Moved to this interface at the user's request
+    SwitchFailed { iface: String, error: String }, // Still capturing on the previous one
+    Ended { iface: String, error: String }, // Lost for good: privileges to reopen it were dropped
+    PrivilegesDropped(String), // --drop-privileges took effect, and what it gave up
+    Failed(String), // The capture thread stopped before capturing, e.g. privileges couldn't be dropped
+}
+
+/// A manual kill that started with SIGTERM and turns into SIGKILL once
+/// `escalate_at` passes, unless the process exits or the user cancels it
+pub struct PendingKill {
+    pub pid: i32,
+    pub name: String,
+    pub escalate_at: Instant,
+}
+
+/// Connection state of one `--aggregate` host
+#[derive(Clone)]
+pub struct RemoteHost {
+    pub label: String,
+    pub address: String,
+    pub connected: bool,
+    pub error: Option<String>, // Why the last connection attempt failed or ended
+}
+
+/// Cost of one refresh of the socket-to-process maps, for the debug stats
+#[derive(Clone, Copy, Default)]
+pub struct ScanStats {
+    pub duration: Duration,
+    pub sockets: usize,           // TCP and UDP sockets listed by the system
+    pub added: usize,             // Sockets that appeared since the previous refresh
+    pub removed: usize,           // Sockets that closed since the previous refresh
+    pub processes_scanned: usize, // Processes whose open sockets were read
+    pub unowned: usize,           // Sockets no readable process owns
+    pub full: bool,               // Every process was scanned
+}
+
+/// Traffic sent from one local process to another without leaving the host
+#[derive(Clone)]
+pub struct LocalFlow {
+    pub from_pid: i32,
+    pub from_name: String,
+    pub to_pid: i32,
+    pub to_name: String,
+    pub bytes: u64,
+    pub rate: u64, // bytes per second
+}
+
+impl LocalFlow {
+    pub fn new(from: &ProcessIdentifier, to: &ProcessIdentifier) -> Self {
+        LocalFlow {
+            from_pid: from.pid,
+            from_name: from.name.clone(),
+            to_pid: to.pid,
+            to_name: to.name.clone(),
+            bytes: 0,
+            rate: 0,
+        }
+    }
+}
+
+/// Traffic of one listening port, whichever process handled each connection
+#[derive(Clone)]
+pub struct ServiceTraffic {
+    pub protocol: u8,
+    pub port: u16,
+    pub pid: i32, // Owner of the listening socket
+    pub name: String,
+    pub sent: u64,
+    pub received: u64,
+    pub sent_rate: u64, // bytes per second
+    pub received_rate: u64,
+    pub connections: usize, // Distinct client connections seen
+    pub workers: usize,     // Distinct processes that handled them
+}
+
+impl ServiceTraffic {
+    pub fn new(protocol: u8, port: u16, owner: &ProcessIdentifier) -> Self {
+        ServiceTraffic {
+            protocol,
+            port,
+            pid: owner.pid,
+            name: owner.name.clone(),
+            sent: 0,
+            received: 0,
+            sent_rate: 0,
+            received_rate: 0,
+            connections: 0,
+            workers: 0,
+        }
+    }
+}
+
+/// Per-process counters saved with `B`
+pub struct Baseline {
+    pub marked: chrono::DateTime<chrono::Local>,
+    pub snapshot: crate::diff::Snapshot,
+}
+
+/// A label dropped with `a`, drawn as a vertical marker on the bandwidth charts
+#[derive(Clone)]
+pub struct Annotation {
+    pub at: f64, // Seconds since start_time, the charts' time axis
+    pub label: String,
+}
+
+/// Link state and counters for one network interface
+#[derive(Clone)]
+pub struct InterfaceStats {
+    pub name: String,
+    pub is_up: bool,
+    pub speed_mbps: Option<u64>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_rate: u64, // bytes/s since the previous read
+    pub tx_rate: u64,
+    pub wireless: Option<WirelessLink>, // Wi-Fi interfaces, read only while a view shows it
+}
+
+/// Association and link quality of a Wi-Fi interface
+#[derive(Clone, Default)]
+pub struct WirelessLink {
+    pub ssid: Option<String>, // None while not associated
+    pub frequency_mhz: Option<u32>,
+    pub signal_dbm: Option<i32>,
+    pub rx_bitrate_mbps: Option<f64>,
+    pub tx_bitrate_mbps: Option<f64>,
+    pub tx_packets: Option<u64>,
+    pub tx_retries: Option<u64>,
+    pub tx_failed: Option<u64>,
+    pub retry_percent: Option<f64>, // Retries per transmitted frame since the previous read
+}
+
+/// Retries per transmitted frame since `previous`, or since association without one
+fn retry_percent(link: &WirelessLink, previous: Option<&WirelessLink>) -> Option<f64> {
+    let (packets, retries) = (link.tx_packets?, link.tx_retries?);
+    let (packets, retries) = match previous.and_then(|previous| previous.tx_packets.zip(previous.tx_retries)) {
+        // A reassociation restarts the counters
+        Some((old_packets, old_retries)) if packets >= old_packets && retries >= old_retries => {
+            (packets - old_packets, retries - old_retries)
+        }
+        _ => (packets, retries),
+    };
+    (packets > 0).then(|| retries as f64 / packets as f64 * 100.0)
+}
+
+/// Metadata of a process beyond its name, read on demand for the detail pane
+#[derive(Clone, Default)]
+pub struct ProcessDetails {
+    pub pid: i32,
+    pub cmdline: Option<String>,
+    pub exe: Option<String>,
+    pub cwd: Option<String>,
+    pub start_time: Option<chrono::DateTime<chrono::Local>>,
+    pub socket_count: Option<usize>,
+}
+
+/// Congestion control state of one TCP socket, as reported by the kernel
+#[derive(Clone, Default)]
+pub struct TcpCongestionInfo {
+    pub algorithm: Option<String>, // e.g. cubic, bbr
+    pub cwnd: Option<u32>,         // Congestion window, in segments
+    pub ssthresh: Option<u32>,     // Unset until the first loss
+    pub mss: Option<u32>,
+    pub rtt_ms: Option<f64>,
+    pub send_rate: Option<String>, // cwnd * mss / rtt, formatted by the kernel tools
+    pub pacing_rate: Option<String>,
+    pub delivery_rate: Option<String>,
+    pub retrans: Option<String>, // Unacknowledged/total retransmits
+}
+
+/// TCP info popup in Packet Details: the connection it follows and its latest read
+pub struct TcpInfoView {
+    pub connection: Option<(std::net::SocketAddr, std::net::SocketAddr)>, // (local, remote); None without a TCP packet in view
+    pub info: Option<Result<TcpCongestionInfo, String>>, // None until the first read
+    pub last_poll: Option<Instant>,
+}
+
+impl TcpInfoView {
+    /// Whether the connection is due for another read (once per second)
+    pub fn poll_due(&self) -> bool {
+        self.connection.is_some()
+            && self.last_poll.is_none_or(|last| last.elapsed() >= Duration::from_secs(1))
+    }
+}
+
+/// One socket of a process, as listed in the connections popup
+#[derive(Clone)]
+pub struct SocketDetails {
+    pub protocol: u8,
+    pub local: std::net::SocketAddr,
+    pub remote: std::net::SocketAddr,
+    pub state: Option<TcpSocketState>, // TCP only
+    pub recv_queue: Option<u32>,       // Bytes the process hasn't read yet (pending connections for listeners)
+    pub send_queue: Option<u32>,       // Bytes the peer hasn't acknowledged yet
+    pub rtt_ms: Option<f64>,           // Smoothed round-trip time, TCP only
+    pub retrans: Option<u32>,          // Retransmitted segments over the connection's lifetime
+}
+
+/// Connections popup in Packet Details: every socket of the selected process
+pub struct ConnectionsView {
+    pub pid: i32,
+    pub sockets: Option<Result<Vec<SocketDetails>, String>>, // None until the first read
+    pub last_poll: Option<Instant>,
+    pub scroll: usize,
+}
+
+impl ConnectionsView {
+    /// Whether the socket list is due for another read (once per second)
+    pub fn poll_due(&self) -> bool {
+        self.last_poll.is_none_or(|last| last.elapsed() >= Duration::from_secs(1))
+    }
+}
+
+/// How long a firing alert is silenced from the mute menu
+#[derive(Clone, Copy, PartialEq)]
+pub enum MuteDuration {
+    ThirtyMinutes,
+    TwoHours,
+    Today,
+}
+
+impl MuteDuration {
+    pub const ALL: [MuteDuration; 3] = [MuteDuration::ThirtyMinutes, MuteDuration::TwoHours, MuteDuration::Today];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MuteDuration::ThirtyMinutes => "30 minutes",
+            MuteDuration::TwoHours => "2 hours",
+            MuteDuration::Today => "Rest of today",
+        }
+    }
+
+    /// Time from now until the mute should expire
+    pub fn duration(self) -> Duration {
+        match self {
+            MuteDuration::ThirtyMinutes => Duration::from_secs(30 * 60),
+            MuteDuration::TwoHours => Duration::from_secs(2 * 60 * 60),
+            MuteDuration::Today => {
+                // Until local midnight
+                let now = chrono::Local::now().naive_local();
+                now.date()
+                    .succ_opt()
+                    .and_then(|tomorrow| tomorrow.and_hms_opt(0, 0, 0))
+                    .and_then(|midnight| (midnight - now).to_std().ok())
+                    .unwrap_or(Duration::from_secs(24 * 60 * 60))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SystemStats {
+    pub tcp_bytes: u64,
+    pub tcp_rate: u64,
+    pub tcp_packets: u64,
+    pub udp_bytes: u64,
+    pub udp_rate: u64,
     pub udp_packets: u64,
+    // QUIC is counted apart from the rest of UDP
+    pub quic_bytes: u64,
+    pub quic_rate: u64,
+    pub quic_packets: u64,
     pub icmp_bytes: u64,
     pub icmp_rate: u64,
     pub icmp_packets: u64,
@@ -538,6 +2882,9 @@ impl SystemStats {
             udp_bytes: 0,
             udp_rate: 0,
             udp_packets: 0,
+            quic_bytes: 0,
+            quic_rate: 0,
+            quic_packets: 0,
             icmp_bytes: 0,
             icmp_rate: 0,
             icmp_packets: 0,
@@ -548,6 +2895,27 @@ impl SystemStats {
     }
 
     pub fn total_bytes(&self) -> u64 {
-        self.tcp_bytes + self.udp_bytes + self.icmp_bytes + self.other_bytes
+        self.tcp_bytes + self.udp_bytes + self.quic_bytes + self.icmp_bytes + self.other_bytes
+    }
+
+    /// From the capture thread's counts: totals and packets so far, rates over the last second
+    pub fn from_traffic(traffic: &crate::traffic_stats::TrafficSnapshot) -> Self {
+        SystemStats {
+            tcp_bytes: traffic.tcp.total.bytes,
+            tcp_rate: traffic.tcp.per_second.bytes,
+            tcp_packets: traffic.tcp.total.packets,
+            udp_bytes: traffic.udp.total.bytes,
+            udp_rate: traffic.udp.per_second.bytes,
+            udp_packets: traffic.udp.total.packets,
+            quic_bytes: traffic.quic.total.bytes,
+            quic_rate: traffic.quic.per_second.bytes,
+            quic_packets: traffic.quic.total.packets,
+            icmp_bytes: traffic.icmp.total.bytes,
+            icmp_rate: traffic.icmp.per_second.bytes,
+            icmp_packets: traffic.icmp.total.packets,
+            other_bytes: traffic.other.total.bytes,
+            other_rate: traffic.other.per_second.bytes,
+            other_packets: traffic.other.total.packets,
+        }
     }
 }
\ No newline at end of file
@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use ratatui::{
     widgets::{Chart, Dataset, Axis, GraphType, Block, Borders},
     style::{Style, Color},
@@ -6,7 +5,13 @@ use ratatui::{
     Frame,
 };
 use crate::types::{App, ChartType, MetricsMode};
-use crate::ui::utils::format_bytes;
+use crate::units::format_rate;
+
+/// Name, (time, rate) points and color of one process in the stacked chart
+type Band = (String, Vec<(f64, f64)>, Color);
+
+/// Annotation labels listed under a chart; older ones keep their marker only
+const LISTED_ANNOTATIONS: usize = 4;
 
 /// Optimized chart rendering with caching and reduced allocations
 pub fn render_charts(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
@@ -26,11 +31,16 @@ pub fn render_charts(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
 fn render_process_lines_chart_data(app: &App) -> (Vec<Dataset<'_>>, f64, String) {
     if let Some(pid) = app.selected_process {
         if let Some(process_info) = app.stats.get(&pid) {
+            // Scrolled back past the window, the samples come from the history store
+            let (sent_history, received_history) = match &app.rewound_history {
+                Some(rewound) if rewound.pid == pid && app.chart_rewound_to.is_some() => (&rewound.sent, &rewound.received),
+                _ => (&process_info.sent_history, &process_info.received_history),
+            };
             // Pre-calculate max value more efficiently
-            let sent_max = process_info.sent_history.iter()
+            let sent_max = sent_history.iter()
                 .map(|(_, v)| *v)
                 .fold(0f64, f64::max);
-            let received_max = process_info.received_history.iter()
+            let received_max = received_history.iter()
                 .map(|(_, v)| *v)
                 .fold(0f64, f64::max);
             
@@ -43,30 +53,35 @@ fn render_process_lines_chart_data(app: &App) -> (Vec<Dataset<'_>>, f64, String)
                     .marker(ratatui::symbols::Marker::Braille)
                     .style(Style::default().fg(Color::Cyan))
                     .graph_type(GraphType::Line)
-                    .data(&process_info.sent_history),
+                    .data(sent_history),
                 Dataset::default()
                     .name("Received")
                     .marker(ratatui::symbols::Marker::Braille)
                     .style(Style::default().fg(Color::Magenta))
                     .graph_type(GraphType::Line)
-                    .data(&process_info.received_history),
+                    .data(received_history),
             ];
-            (datasets, y_max, format!("Process {} Bandwidth (last 5 min)", pid))
+            let title = match app.rewound_label() {
+                Some(label) => format!("Process {} Bandwidth ({}) – {}", pid, app.history_window_label(), label),
+                None => format!("Process {} Bandwidth (last {})", pid, app.history_window_label()),
+            };
+            (datasets, y_max, title)
         } else {
-            (Vec::new(), 1f64, "Process Bandwidth (last 5 min)".to_string())
+            (Vec::new(), 1f64, format!("Process Bandwidth (last {})", app.history_window_label()))
         }
     } else {
-        (Vec::new(), 1f64, "Process Bandwidth (last 5 min)".to_string())
+        (Vec::new(), 1f64, format!("Process Bandwidth (last {})", app.history_window_label()))
     }
 }
 
 /// Optimized system stacked chart data generation with pre-built datasets
 fn render_system_stacked_chart_data(app: &App, area: ratatui::layout::Rect) -> (Vec<Dataset<'_>>, f64, String) {
     if app.chart_datasets.is_empty() {
-        return (Vec::new(), 1f64, "System Bandwidth Stack (last 5 min)".to_string());
+        return (Vec::new(), 1f64, format!("System Bandwidth Stack (last {})", app.history_window_label()));
     }
 
-    // Use pre-built datasets from app with optimized name truncation
+    // Bands are stored top first and drawn as filled bars, so each lower band
+    // paints over the ones above it and only its own slice stays visible
     let datasets: Vec<Dataset> = app.chart_datasets.iter()
         .map(|(name, data, color)| {
             let display_name = get_display_name(name, area.width);
@@ -74,15 +89,15 @@ fn render_system_stacked_chart_data(app: &App, area: ratatui::layout::Rect) -> (
                 .name(display_name)
                 .marker(ratatui::symbols::Marker::Braille)
                 .style(Style::default().fg(*color))
-                .graph_type(GraphType::Line)
+                .graph_type(GraphType::Bar)
                 .data(data)
         })
         .collect();
 
-    // Pre-calculate y_max more efficiently
-    let max_stack = app.chart_datasets.iter()
-        .flat_map(|(_, data, _)| data.iter().map(|(_, y)| *y))
-        .fold(1f64, f64::max);
+    // The top band is the running total, so it holds the peak
+    let max_stack = app.chart_datasets.first()
+        .map(|(_, data, _)| data.iter().map(|(_, y)| *y).fold(1f64, f64::max))
+        .unwrap_or(1f64);
 
     let y_max = max_stack * 1.2;
     let title = get_chart_title(app.metrics_mode, area.width);
@@ -125,20 +140,45 @@ fn render_optimized_chart(
     chart_title: String,
     app: &App
 ) {
-    let now = app.start_time.elapsed().as_secs_f64();
-    let x_min = if now > 300.0 { now - 300.0 } else { 0.0 };
+    let now = app.view_time();
+    let window = app.history_window_secs as f64;
+    let x_min = if now > window { now - window } else { 0.0 };
     
     let x_axis = Axis::default()
         .title("Time (s)")
         .style(Style::default().fg(Color::Gray))
         .bounds([x_min, now]);
 
+    // Annotations in view become vertical lines, numbered in the list under the chart
+    let annotations: Vec<(usize, &crate::types::Annotation)> = app.annotations.iter()
+        .enumerate()
+        .filter(|(_, annotation)| annotation.at >= x_min && annotation.at <= now)
+        .map(|(index, annotation)| (index + 1, annotation))
+        .collect();
+    let markers: Vec<[(f64, f64); 2]> = annotations.iter()
+        .map(|(_, annotation)| [(annotation.at, 0.0), (annotation.at, y_max)])
+        .collect();
+    let mut datasets = datasets;
+    datasets.extend(markers.iter().map(|points| {
+        Dataset::default()
+            .marker(ratatui::symbols::Marker::Braille)
+            .style(Style::default().fg(Color::LightYellow))
+            .graph_type(GraphType::Line)
+            .data(points)
+    }));
+    let annotation_list: Vec<String> = annotations.iter()
+        .rev()
+        .take(LISTED_ANNOTATIONS)
+        .rev()
+        .map(|(number, annotation)| format!(" 📌{} {} ({:.0}s) ", number, annotation.label, annotation.at))
+        .collect();
+
     // Optimized y-axis label generation
     let num_labels = if area.height < 12 { 3 } else { 5 };
     let y_labels: Vec<Span> = (0..num_labels)
         .map(|i| {
             let val = y_max * i as f64 / (num_labels - 1) as f64;
-            Span::raw(format!("{}/s", format_bytes(val as u64)))
+            Span::raw(format_rate(val as u64))
         })
         .collect();
 
@@ -148,12 +188,14 @@ fn render_optimized_chart(
         .labels(y_labels)
         .bounds([0.0, y_max]);
 
+    let mut block = Block::default()
+        .title(chart_title)
+        .borders(Borders::ALL);
+    if !annotation_list.is_empty() {
+        block = block.title_bottom(Span::styled(annotation_list.concat(), Style::default().fg(Color::LightYellow)));
+    }
     let chart = Chart::new(datasets)
-        .block(
-            Block::default()
-                .title(chart_title)
-                .borders(Borders::ALL),
-        )
+        .block(block)
         .x_axis(x_axis)
         .y_axis(y_axis);
 
@@ -243,32 +285,28 @@ pub fn update_chart_datasets(app: &mut App) {
         })
         .collect();
 
-    process_scores.sort_by_key(|b| std::cmp::Reverse(b.1));
-    
-    let top_pids: HashSet<i32> = process_scores.into_iter()
+    // Busiest process at the bottom of the stack; PID breaks ties so bands don't swap places
+    process_scores.sort_by_key(|&(pid, score)| (std::cmp::Reverse(score), pid));
+    let top_pids: Vec<i32> = process_scores.into_iter()
         .take(5)
         .map(|(pid, _)| pid)
         .collect();
 
-    let mut new_datasets = Vec::new();
-    
     // Palette for assigning new colors to processes
     const COLORS: &[Color] = &[
         Color::Cyan, Color::Magenta, Color::Green, Color::Yellow, Color::Blue,
         Color::LightRed, Color::LightGreen, Color::LightBlue,
     ];
 
-    for (pid, info) in &app.stats {
-        if !top_pids.contains(pid) {
-            continue;
-        }
-
+    let mut bands = Vec::new();
+    for pid in &top_pids {
+        let Some(info) = app.stats.get(pid) else { continue };
         let len = app.process_colors.len();
         let color = *app.process_colors.entry(*pid).or_insert_with(|| {
             COLORS[len % COLORS.len()]
         });
         
-        let data = match app.metrics_mode {
+        let data: Vec<(f64, f64)> = match app.metrics_mode {
             MetricsMode::Combined => {
                 info.sent_history.iter().zip(&info.received_history)
                     .map(|((t, s), (_, r))| (*t, *s + *r))
@@ -278,8 +316,42 @@ pub fn update_chart_datasets(app: &mut App) {
             MetricsMode::ReceiveOnly => info.received_history.clone(),
         };
 
-        new_datasets.push((info.name.clone(), data, color));
+        bands.push((info.name.clone(), data, color));
     }
-    
-    app.chart_datasets = new_datasets;
+
+    app.chart_datasets = stack_bands(bands);
+}
+
+/// Rate of a series at time `t`: its latest sample at or before `t`, or 0 before it started
+fn value_at(series: &[(f64, f64)], t: f64) -> f64 {
+    match series.partition_point(|(x, _)| *x <= t) {
+        0 => 0.0,
+        index => series[index - 1].1,
+    }
+}
+
+/// Turn per-process series (bottom band first) into cumulative bands on a
+/// shared time axis, returned top band first for drawing. Histories are
+/// downsampled independently, so their timestamps don't always line up.
+fn stack_bands(bands: Vec<Band>) -> Vec<Band> {
+    let mut times: Vec<f64> = bands.iter()
+        .flat_map(|(_, data, _)| data.iter().map(|(t, _)| *t))
+        .collect();
+    times.sort_by(f64::total_cmp);
+    times.dedup();
+
+    let mut running = vec![0f64; times.len()];
+    let mut stacked: Vec<_> = bands.into_iter()
+        .map(|(name, data, color)| {
+            let cumulative = times.iter().zip(running.iter_mut())
+                .map(|(t, total)| {
+                    *total += value_at(&data, *t);
+                    (*t, *total)
+                })
+                .collect();
+            (name, cumulative, color)
+        })
+        .collect();
+    stacked.reverse();
+    stacked
 } 
\ No newline at end of file
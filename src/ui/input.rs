@@ -1,118 +1,689 @@
 use crossterm::event::KeyCode;
-use crate::types::{Alert, AlertAction, App, AppMode, SortColumn, SortDirection, EditingField, ChartType, MetricsMode};
-use crate::ui::utils::{parse_input_to_bytes, format_bytes};
+use crate::types::{Alert, AlertAction, App, AppMode, GroupBy, InterfaceChoice, InterfacePicker, MuteDuration, PendingKill, SortColumn, SortDirection, EditingField, ChartType, MetricsMode};
+use crate::ui::settings_fields::{self, Setting};
+use crate::ui::keymap::KeyContext;
+use crate::units::{format_bytes, parse_bytes, parse_connection_rate};
+use crate::events::EventKind;
 
 #[cfg(target_os = "linux")]
 use nix::sys::signal::{self, Signal};
 #[cfg(target_os = "linux")]
 use nix::unistd::Pid;
 
+/// How long a graceful kill waits after SIGTERM before sending SIGKILL
+const KILL_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Handle a batch of queued key presses. Runs of the same navigation key are
+/// coalesced into one selection move so the process list is sorted once per
+/// batch rather than once per key. Returns true when the app should quit.
+pub fn handle_key_events(app: &mut App, keys: &[KeyCode]) -> bool {
+    let mut i = 0;
+    while i < keys.len() {
+        let key = keys[i];
+        let run = keys[i..].iter().take_while(|k| **k == key).count();
+        i += run;
+
+        if matches!(key, KeyCode::Up | KeyCode::Down) && run > 1 && selection_keys_move_process(app) {
+            let steps = run as isize;
+            app.move_selection(if key == KeyCode::Down { steps } else { -steps });
+            continue;
+        }
+        for _ in 0..run {
+            if handle_key_event(app, key) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Whether Up/Down currently move the process selection (main and bandwidth views)
+fn selection_keys_move_process(app: &App) -> bool {
+    app.mode == AppMode::Normal
+        && app.jump_input.is_none()
+        && app.annotation_input.is_none()
+        && app.interface_picker.is_none()
+        && !app.show_help
+        && !app.show_sort_menu
+        && !app.show_mute_menu
+        && !app.show_action_panel
+        && !app.show_baseline_diff
+        && app.group_by.is_none()
+        && app.limit_input.is_none()
+        && app.port_input.is_none()
+}
+
+/// Where remapped keys apply; `None` while typing text or choosing from a popup
+fn key_context(app: &App) -> Option<KeyContext> {
+    match app.mode {
+        _ if app.jump_input.is_some() || app.annotation_input.is_some() || app.interface_picker.is_some()
+            || app.limit_input.is_some() || app.port_input.is_some() => None,
+        AppMode::EditingAlert => None,
+        AppMode::Normal if app.show_sort_menu || app.show_mute_menu || app.show_action_panel || app.show_baseline_diff => None,
+        AppMode::Normal => Some(KeyContext::Main),
+        AppMode::PacketDetails if app.packet_search_mode => None,
+        AppMode::PacketDetails => Some(KeyContext::PacketDetails),
+        AppMode::SystemOverview | AppMode::Settings | AppMode::Interfaces | AppMode::LocalTraffic | AppMode::Services | AppMode::Events => Some(KeyContext::Other),
+    }
+}
+
 /// Handle keyboard input events for all application modes
 pub fn handle_key_event(app: &mut App, key: crossterm::event::KeyCode) -> bool {
+    // The limit prompt takes every key while it is open, digits and units alike
+    if app.limit_input.is_some() {
+        return handle_limit_keys(app, key);
+    }
+    if app.port_input.is_some() {
+        return handle_port_keys(app, key);
+    }
+
+    // Undo for a graceful kill works until its grace period ends, before any
+    // remapping, and takes precedence over sorting by user; the packet details
+    // keep `u` for their UDP filter
+    if key == KeyCode::Char('u') && !app.pending_kills.is_empty() && app.jump_input.is_none()
+        && !matches!(app.mode, AppMode::EditingAlert | AppMode::PacketDetails) {
+        cancel_pending_kill(app);
+        return false;
+    }
+
+    // Remapped keys are turned back into the defaults the handlers below match on
+    let key = match key_context(app) {
+        Some(context) => app.keymap.translate(context, key),
+        None => key,
+    };
+
+    // The debug overlay toggles from any view, prompts included
+    if key == KeyCode::F(12) {
+        app.show_debug_overlay = !app.show_debug_overlay;
+        return false;
+    }
+
+    // Quick-jump prompt takes over input while open, from any mode
+    if app.jump_input.is_some() {
+        return handle_jump_keys(app, key);
+    }
+    if key == KeyCode::Char(':') && app.mode != AppMode::EditingAlert && !app.packet_search_mode {
+        app.jump_input = Some(String::new());
+        app.jump_selected = 0;
+        app.show_help = false;
+        return false;
+    }
+
+    // Annotations can be dropped from any view, e.g. right after starting a backup
+    if app.annotation_input.is_some() {
+        return handle_annotation_keys(app, key);
+    }
+    if key == KeyCode::Char('a') && app.mode != AppMode::EditingAlert && !app.packet_search_mode {
+        app.annotation_input = Some(String::new());
+        app.show_help = false;
+        return false;
+    }
+
+    // The capture can be moved to another interface from any view
+    if app.interface_picker.is_some() {
+        return handle_interface_picker_keys(app, key);
+    }
+    if key == KeyCode::Char('I') && app.capture_control.is_some()
+        && app.mode != AppMode::EditingAlert && !app.packet_search_mode {
+        open_interface_picker(app);
+        return false;
+    }
+
+    // Help overlay sits on top of the current view until closed
+    if app.show_help {
+        return handle_help_keys(app, key);
+    }
+    if key == KeyCode::Char('?') && app.mode != AppMode::EditingAlert && !app.packet_search_mode {
+        app.show_help = true;
+        app.help_scroll = 0;
+        return false;
+    }
+
     match app.mode {
         AppMode::EditingAlert => handle_alert_editing_keys(app, key),
         AppMode::Normal => handle_normal_mode_keys(app, key),
         AppMode::SystemOverview => handle_overview_mode_keys(app, key),
         AppMode::Settings => handle_settings_mode_keys(app, key),
         AppMode::PacketDetails => handle_packet_details_mode_keys(app, key),
+        AppMode::Interfaces => handle_interfaces_mode_keys(app, key),
+        AppMode::LocalTraffic => handle_local_traffic_mode_keys(app, key),
+        AppMode::Services => handle_services_mode_keys(app, key),
+        AppMode::Events => handle_events_mode_keys(app, key),
     }
 }
 
-/// Handle key events in alert editing mode
-fn handle_alert_editing_keys(app: &mut App, key: KeyCode) -> bool {
+/// Open the TCP info popup for the first TCP packet from the top of the packet list down
+fn open_tcp_info(app: &mut App) {
+    let history = app.selected_process
+        .and_then(|pid| app.stats.get(&pid))
+        .map(|info| &info.packet_history);
+    let connection = history.and_then(|history| {
+        app.packet_cache
+            .iter()
+            .skip(app.packet_scroll_offset)
+            .filter_map(|index| history.get(*index))
+            .find(|packet| packet.protocol == 6)
+            .map(|packet| {
+                let src = std::net::SocketAddr::new(packet.src_ip, packet.src_port);
+                let dst = std::net::SocketAddr::new(packet.dst_ip, packet.dst_port);
+                match packet.direction {
+                    crate::types::PacketDirection::Sent => (src, dst),
+                    crate::types::PacketDirection::Received => (dst, src),
+                }
+            })
+    });
+    // Demo and remote connections aren't local sockets, so there is no socket to ask about
+    let info = app.foreign_pids().then(|| Err(foreign_message(app)));
+    app.tcp_info = Some(crate::types::TcpInfoView {
+        connection: connection.filter(|_| !app.foreign_pids()),
+        info,
+        last_poll: None,
+    });
+}
+
+/// Why local socket details are unavailable for the table's processes
+fn foreign_message(app: &App) -> String {
+    if app.demo_mode {
+        "Not available in demo mode".to_string()
+    } else {
+        "Not available for remote hosts".to_string()
+    }
+}
+
+/// Open the connections popup for the selected process
+fn open_connections(app: &mut App) {
+    let Some(pid) = app.selected_process else { return };
+    // Demo and remote PIDs aren't local, so their sockets would be some unrelated process's
+    let sockets = app.foreign_pids().then(|| Err(foreign_message(app)));
+    app.connections = Some(crate::types::ConnectionsView {
+        pid,
+        sockets,
+        last_poll: None,
+        scroll: 0,
+    });
+}
+
+/// Handle key events while the help overlay is open
+fn handle_help_keys(app: &mut App, key: KeyCode) -> bool {
+    let max_scroll = crate::ui::renderers::help::line_count(app).saturating_sub(1);
+    match key {
+        KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => {
+            app.show_help = false;
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.help_scroll = app.help_scroll.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.help_scroll = (app.help_scroll + 1).min(max_scroll);
+        }
+        KeyCode::PageUp => {
+            app.help_scroll = app.help_scroll.saturating_sub(10);
+        }
+        KeyCode::PageDown => {
+            app.help_scroll = (app.help_scroll + 10).min(max_scroll);
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle key events while the quick-jump prompt is open
+fn handle_jump_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Esc => {
+            app.jump_input = None;
+        }
+        KeyCode::Char(c) => {
+            if let Some(input) = app.jump_input.as_mut() {
+                input.push(c);
+            }
+            app.jump_selected = 0;
+        }
+        KeyCode::Backspace => {
+            if let Some(input) = app.jump_input.as_mut() {
+                input.pop();
+            }
+            app.jump_selected = 0;
+        }
+        KeyCode::Up
+            if app.jump_selected > 0 => {
+                app.jump_selected -= 1;
+            }
+        KeyCode::Down | KeyCode::Tab
+            if app.jump_selected + 1 < app.jump_matches().len() => {
+                app.jump_selected += 1;
+            }
+        KeyCode::Enter => {
+            if let Some(pid) = app.jump_matches().get(app.jump_selected).copied() {
+                app.jump_to_process(pid);
+                app.jump_input = None;
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle key events while the annotation prompt is open
+fn handle_annotation_keys(app: &mut App, key: KeyCode) -> bool {
     match key {
+        KeyCode::Esc => {
+            app.annotation_input = None;
+        }
         KeyCode::Char(c) => {
-            match app.current_editing_field {
-                EditingField::Threshold => app.alert_input.push(c),
-                EditingField::Command => app.command_input.push(c),
+            if let Some(input) = app.annotation_input.as_mut() {
+                input.push(c);
             }
         }
         KeyCode::Backspace => {
-            match app.current_editing_field {
-                EditingField::Threshold => { app.alert_input.pop(); },
-                EditingField::Command => { app.command_input.pop(); },
+            if let Some(input) = app.annotation_input.as_mut() {
+                input.pop();
+            }
+        }
+        KeyCode::Enter => {
+            if let Some(label) = app.annotation_input.take() {
+                app.annotate(&label);
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Offer every capture device pcap knows about, starting at the current one,
+/// and measure their traffic the way interactive mode does
+fn open_interface_picker(app: &mut App) {
+    let choices: Vec<InterfaceChoice> = pcap::Device::list()
+        .map(|devices| devices.into_iter().map(|device| InterfaceChoice {
+            is_up: device.flags.is_up(),
+            name: device.name,
+            description: device.desc,
+            traffic_bytes: None,
+        }).collect())
+        .unwrap_or_default();
+    let selected = app.capture_iface.as_ref()
+        .and_then(|current| choices.iter().position(|choice| &choice.name == current))
+        .unwrap_or(0);
+    let measurement = crate::interactive::measure_traffic_in_background(
+        choices.iter().filter(|choice| choice.is_up).map(|choice| choice.name.clone()).collect()
+    );
+    let window = crate::interactive::MEASURE_DURATION_SECS + crate::interactive::OPEN_GRACE_SECS;
+    app.interface_picker = Some(InterfacePicker {
+        choices,
+        selected,
+        measurement: Some(measurement),
+        measure_started: std::time::Instant::now(),
+        measured_by: std::time::Instant::now() + std::time::Duration::from_secs(window),
+    });
+    app.show_help = false;
+}
+
+fn handle_interface_picker_keys(app: &mut App, key: KeyCode) -> bool {
+    let Some(picker) = app.interface_picker.as_mut() else { return false };
+    match key {
+        KeyCode::Esc => app.interface_picker = None,
+        KeyCode::Up if picker.selected > 0 => picker.selected -= 1,
+        KeyCode::Down | KeyCode::Tab if picker.selected + 1 < picker.choices.len() => picker.selected += 1,
+        KeyCode::Enter => {
+            let name = picker.choices.get(picker.selected).map(|choice| choice.name.clone());
+            // Picked from the Settings view, it is also the interface for the next start
+            if app.mode == AppMode::Settings && let Some(name) = &name {
+                crate::ui::settings_fields::save_interface(app, name);
+            }
+            if let Some(name) = name.filter(|name| app.capture_control.is_some() && app.capture_iface.as_ref() != Some(name)) {
+                let message = match app.capture_control.as_ref().map(|control| control.send(name.clone())) {
+                    Some(Ok(())) => format!("🔌 Trying to capture on {}", name),
+                    _ => "❌ The capture thread has stopped; restart monitetoring".to_string(),
+                };
+                app.notify(EventKind::System, None, message);
             }
+            app.interface_picker = None;
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle key events in alert editing mode
+fn handle_alert_editing_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char(c) => {
+            editing_input(app).push(c);
+            app.alert_input_error = None;
+        }
+        KeyCode::Backspace => {
+            editing_input(app).pop();
+            app.alert_input_error = None;
         }
         KeyCode::Esc => {
             app.mode = AppMode::Normal;
-            app.alert_input.clear();
-            app.command_input.clear();
+            clear_alert_inputs(app);
         }
         KeyCode::Up
             if app.selected_alert_action > 0 => {
                 app.selected_alert_action -= 1;
             }
         KeyCode::Down
-            if app.selected_alert_action < 2 => {
+            if app.selected_alert_action < 4 => {
                 app.selected_alert_action += 1;
             }
         KeyCode::Tab => {
-            app.current_editing_field = match app.current_editing_field {
-                EditingField::Threshold => EditingField::Command,
-                EditingField::Command => EditingField::Threshold,
-            };
+            app.current_editing_field = app.current_editing_field.next();
+        }
+        KeyCode::Left | KeyCode::Right => {
+            app.dry_run_input = !app.dry_run_input;
         }
         KeyCode::Enter => {
-            if let Some(pid) = app.selected_process {
-                let (threshold, action) = match app.selected_alert_action {
-                    0 => {
-                        // Kill Process - just parse threshold
-                        (parse_input_to_bytes(&app.alert_input), AlertAction::Kill)
-                    },
+            // With rows marked, every marked process gets the same alert
+            let targets: Vec<i32> = if app.marked.is_empty() {
+                app.selected_process.into_iter().collect()
+            } else {
+                app.marked_pids()
+            };
+            if !targets.is_empty() {
+                // "anomaly", a connection rate ("100 conn/s") or a byte size; stay in the editor on typos
+                let on_anomaly = app.alert_input.trim().eq_ignore_ascii_case("anomaly");
+                let max_new_connections = parse_connection_rate(&app.alert_input);
+                let threshold = if on_anomaly || max_new_connections.is_some() {
+                    0
+                } else {
+                    match parse_bytes(&app.alert_input) {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            app.alert_input_error = Some(e);
+                            app.current_editing_field = EditingField::Threshold;
+                            return false;
+                        }
+                    }
+                };
+                let action = match app.selected_alert_action {
+                    0 => AlertAction::Kill,
                     1 => {
-                        // Custom Command - parse "threshold,command" format
-                        let threshold = parse_input_to_bytes(&app.alert_input);
+                        // Custom Command - an empty command falls back to killing the process
                         let command = app.command_input.trim().to_string();
                         if command.is_empty() {
-                            (threshold, AlertAction::Kill)
+                            AlertAction::Kill
                         } else {
-                            (threshold, AlertAction::CustomCommand(command))
+                            AlertAction::CustomCommand(command)
                         }
                     },
-                    2 => {
-                        // Alert - just parse threshold
-                        (parse_input_to_bytes(&app.alert_input), AlertAction::SystemAlert)
+                    2 => AlertAction::SystemAlert,
+                    3 => {
+                        // Capture Pcap - the command field holds the duration in seconds
+                        let secs = app.command_input.trim().parse().ok().filter(|secs| *secs > 0)
+                            .unwrap_or(crate::pcap_recorder::DEFAULT_PCAP_DURATION_SECS);
+                        AlertAction::CapturePcap(secs)
                     },
-                    _ => (1024 * 1024, AlertAction::Kill),
+                    4 => AlertAction::Block,
+                    _ => AlertAction::Kill,
                 };
-                
-                let new_alert = Alert {
-                    process_pid: pid,
-                    threshold_bytes: threshold,
-                    action: action.clone(),
+                let (cooldown_secs, rearm_below_percent, max_triggers, notify_every_secs) = match parse_trigger_limits(app) {
+                    Ok(limits) => limits,
+                    Err((field, e)) => {
+                        app.alert_input_error = Some(e);
+                        app.current_editing_field = field;
+                        return false;
+                    }
                 };
-                app.alerts.insert(pid, new_alert);
                 
-                // Add to system alerts if it's a system alert
-                if let AlertAction::SystemAlert = action {
-                    app.system_alerts.insert(pid);
+                for &pid in &targets {
+                    let new_alert = Alert {
+                        process_pid: pid,
+                        threshold_bytes: threshold,
+                        action: action.clone(),
+                        max_new_connections,
+                        on_anomaly,
+                        cooldown_secs,
+                        rearm_below_percent,
+                        max_triggers,
+                        notify_every_secs,
+                        dry_run: app.dry_run_input,
+                        process_name: app.stats.get(&pid).map(|info| info.name.clone()),
+                    };
+                    app.set_alert(pid, new_alert);
+
+                    // Add to system alerts if it's a system alert
+                    if let AlertAction::SystemAlert = action {
+                        app.system_alerts.insert(pid);
+                    }
                 }
+                if !app.marked.is_empty() {
+                    app.marked.clear();
+                    app.notice(format!("🔔 Set the same alert on {} marked processes", targets.len()));
+                }
+                app.save_alerts();
             }
             app.mode = AppMode::Normal;
-            app.alert_input.clear();
-            app.command_input.clear();
+            clear_alert_inputs(app);
         }
         _ => {}
     }
     false
 }
 
+/// Text of the alert editor field that has focus
+fn editing_input(app: &mut App) -> &mut String {
+    match app.current_editing_field {
+        EditingField::Threshold => &mut app.alert_input,
+        EditingField::Command => &mut app.command_input,
+        EditingField::Cooldown => &mut app.cooldown_input,
+        EditingField::Rearm => &mut app.rearm_input,
+        EditingField::MaxTriggers => &mut app.max_triggers_input,
+        EditingField::NotifyEvery => &mut app.notify_every_input,
+    }
+}
+
+fn clear_alert_inputs(app: &mut App) {
+    app.alert_input.clear();
+    app.command_input.clear();
+    app.cooldown_input.clear();
+    app.rearm_input.clear();
+    app.max_triggers_input.clear();
+    app.notify_every_input.clear();
+    app.dry_run_input = false;
+    app.alert_input_error = None;
+}
+
+/// Cooldown seconds, re-arm percentage, trigger limit and seconds between notifications
+type TriggerLimits = (u64, Option<u8>, Option<u32>, Option<u64>);
+
+/// Cooldown, re-arm level, trigger limit and notification limit from the editor; empty fields take the defaults.
+/// On a typo, returns the field to focus with the message to show.
+fn parse_trigger_limits(app: &App) -> Result<TriggerLimits, (EditingField, String)> {
+    let cooldown = app.cooldown_input.trim().trim_end_matches('s').trim();
+    let cooldown_secs = if cooldown.is_empty() {
+        crate::alerts::DEFAULT_COOLDOWN_SECS
+    } else {
+        cooldown.parse().map_err(|_| (EditingField::Cooldown, format!("Cooldown '{}' is not a number of seconds", cooldown)))?
+    };
+
+    let rearm = app.rearm_input.trim().trim_end_matches('%').trim();
+    let rearm_below_percent = if rearm.is_empty() {
+        None
+    } else {
+        match rearm.parse::<u8>() {
+            Ok(percent) if (1..=100).contains(&percent) => Some(percent),
+            _ => return Err((EditingField::Rearm, format!("Re-arm level '{}' must be a percentage from 1 to 100", rearm))),
+        }
+    };
+
+    let max = app.max_triggers_input.trim();
+    let max_triggers = if max.is_empty() {
+        None
+    } else {
+        match max.parse::<u32>() {
+            Ok(count) if count > 0 => Some(count),
+            _ => return Err((EditingField::MaxTriggers, format!("Max triggers '{}' must be a whole number above 0", max))),
+        }
+    };
+
+    let every = app.notify_every_input.trim().trim_end_matches('s').trim();
+    let notify_every_secs = if every.is_empty() {
+        None
+    } else {
+        match every.parse::<u64>() {
+            Ok(secs) if secs > 0 => Some(secs),
+            _ => return Err((EditingField::NotifyEvery, format!("Notify every '{}' must be a number of seconds above 0", every))),
+        }
+    };
+
+    Ok((cooldown_secs, rearm_below_percent, max_triggers, notify_every_secs))
+}
+
 /// Handle key events in normal mode
 fn handle_normal_mode_keys(app: &mut App, key: KeyCode) -> bool {
-    if app.show_action_panel {
+    if app.show_sort_menu {
+        handle_sort_menu_keys(app, key)
+    } else if app.show_mute_menu {
+        handle_mute_menu_keys(app, key)
+    } else if app.show_action_panel {
         handle_action_panel_keys(app, key)
+    } else if app.show_baseline_diff {
+        handle_baseline_diff_keys(app, key)
+    } else if app.group_by.is_some() {
+        handle_group_table_keys(app, key)
     } else {
         handle_main_view_keys(app, key)
     }
 }
 
+/// Handle key events while the table is grouped; keys it doesn't use work as in the main view
+fn handle_group_table_keys(app: &mut App, key: KeyCode) -> bool {
+    let groups = app.grouped_stats();
+    match key {
+        KeyCode::Esc => app.group_by = None,
+        KeyCode::Up => app.group_selected = app.group_selected.saturating_sub(1),
+        KeyCode::Down => app.group_selected = (app.group_selected + 1).min(groups.len().saturating_sub(1)),
+        KeyCode::Enter => {
+            // Narrow the process table down to the selected group
+            if let (Some(by), Some(group)) = (app.group_by, groups.get(app.group_selected)) {
+                app.group_filter = Some((by, group.key.clone()));
+                app.group_by = None;
+            }
+        }
+        _ => return handle_main_view_keys(app, key),
+    }
+    false
+}
+
+/// Handle key events while the changes since the baseline are shown
+fn handle_baseline_diff_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char('q') => return true,
+        KeyCode::Esc | KeyCode::Char('b') => {
+            app.show_baseline_diff = false;
+        }
+        KeyCode::Char('B') => {
+            app.mark_baseline();
+            app.baseline_scroll = 0;
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.baseline_scroll = app.baseline_scroll.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.baseline_scroll += 1; // Clamped while rendering
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle key events when the sort menu popup is shown
+fn handle_sort_menu_keys(app: &mut App, key: KeyCode) -> bool {
+    let columns = SortColumn::menu_columns(app.containers_mode);
+    let selected = columns[app.sort_menu_selected.min(columns.len() - 1)];
+
+    match key {
+        KeyCode::Esc | KeyCode::Char('S') => {
+            app.show_sort_menu = false;
+        }
+        KeyCode::Up
+            if app.sort_menu_selected > 0 => {
+                app.sort_menu_selected -= 1;
+            }
+        KeyCode::Down
+            if app.sort_menu_selected < columns.len() - 1 => {
+                app.sort_menu_selected += 1;
+            }
+        KeyCode::Enter | KeyCode::Char('1') => {
+            // Primary key; selecting the current primary again flips its direction
+            if app.sort_by == selected {
+                app.sort_direction = app.sort_direction.toggled();
+            } else {
+                app.sort_by = selected;
+            }
+            if matches!(app.secondary_sort, Some((column, _)) if column == selected) {
+                app.secondary_sort = None;
+            }
+        }
+        KeyCode::Char('2') | KeyCode::Char(' ') => {
+            // Secondary key; selecting it again flips its direction
+            app.secondary_sort = match app.secondary_sort {
+                _ if app.sort_by == selected => None,
+                Some((column, direction)) if column == selected => Some((column, direction.toggled())),
+                _ => Some((selected, SortDirection::Desc)),
+            };
+        }
+        KeyCode::Char('x') => {
+            app.secondary_sort = None;
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Open the mute menu for the alert on `pid`
+fn open_mute_menu(app: &mut App, pid: i32) {
+    app.show_mute_menu = true;
+    app.mute_menu_pid = Some(pid);
+    app.mute_menu_selected = 0;
+}
+
+/// Handle key events when the alert mute menu popup is shown
+fn handle_mute_menu_keys(app: &mut App, key: KeyCode) -> bool {
+    let Some(pid) = app.mute_menu_pid else {
+        app.show_mute_menu = false;
+        return false;
+    };
+    // Durations, plus "Unmute" while a mute is active
+    let num_options = MuteDuration::ALL.len() + usize::from(app.is_alert_muted(pid));
+
+    match key {
+        KeyCode::Esc => {
+            app.show_mute_menu = false;
+            app.mute_menu_pid = None;
+        }
+        KeyCode::Up
+            if app.mute_menu_selected > 0 => {
+                app.mute_menu_selected -= 1;
+            }
+        KeyCode::Down
+            if app.mute_menu_selected < num_options - 1 => {
+                app.mute_menu_selected += 1;
+            }
+        KeyCode::Enter => {
+            match MuteDuration::ALL.get(app.mute_menu_selected) {
+                Some(duration) => app.mute_alert(pid, *duration),
+                None => app.unmute_alert(pid),
+            }
+            app.show_mute_menu = false;
+            app.mute_menu_pid = None;
+        }
+        _ => {}
+    }
+    false
+}
+
 /// Handle key events when action panel is shown
 fn handle_action_panel_keys(app: &mut App, key: KeyCode) -> bool {
-    let mut num_actions = 3; // Kill, Edit, Details
-    if let Some(pid) = app.selected_process
-        && app.alerts.contains_key(&pid) {
-            num_actions = 4; // Add Remove option
-        }
+    if let Some(pid) = app.kill_confirm {
+        return handle_kill_confirm_keys(app, pid, key);
+    }
+    if !app.marked.is_empty() {
+        return handle_bulk_action_keys(app, key);
+    }
+    let actions = app.selected_process.map(|pid| app.process_actions(pid)).unwrap_or_default();
+    let num_actions = actions.len().max(1);
 
     match key {
         KeyCode::Esc => {
@@ -129,91 +700,76 @@ fn handle_action_panel_keys(app: &mut App, key: KeyCode) -> bool {
             }
         KeyCode::Enter => {
             if let Some(pid) = app.selected_process {
-                let has_alert = app.alerts.contains_key(&pid);
-                let action_str = match app.selected_action {
-                    0 => "Kill",
-                    1 => "Edit",
-                    2 => "Details",
-                    3 if has_alert => "Remove",
-                    _ => "",
-                };
+                let action_str = actions.get(app.selected_action).copied().unwrap_or("");
 
                 match action_str {
-                    "Kill" => {
-                        let kill_success = {
-                            #[cfg(target_os = "linux")]
-                            {
-                                signal::kill(Pid::from_raw(pid), Some(Signal::SIGKILL)).is_ok()
-                            }
-                            
-                            #[cfg(target_os = "windows")]
-                            {
-                                use std::process::Command;
-                                let output = Command::new("taskkill")
-                                    .args(["/PID", &pid.to_string(), "/F"])
-                                    .stdout(std::process::Stdio::null()) // Suppress stdout
-                                    .stderr(std::process::Stdio::null()) // Suppress stderr
-                                    .output();
-                                
-                                match output {
-                                    Ok(result) => result.status.success(),
-                                    Err(_) => false,
-                                }
-                            }
-                        };
-                        
-                        if kill_success {
-                            let process_name = app.stats.get(&pid)
-                                .map(|info| info.name.clone())
-                                .unwrap_or_else(|| format!("PID {}", pid));
-                            
-                            app.kill_notification = Some(format!("✅ Successfully killed {} (PID {})", process_name, pid));
-                            app.kill_notification_time = Some(std::time::Instant::now());
-                            
-                            // Remove process immediately from stats and alerts
-                            app.stats.remove(&pid);
-                            app.alerts.remove(&pid);
-                            
-                            app.killed_processes.insert(pid);
-                            app.selected_process = None;
-                        } else {
-                            app.kill_notification = Some(format!("❌ Failed to kill process (PID {})", pid));
-                            app.kill_notification_time = Some(std::time::Instant::now());
+                    "Kill" => match app.protected_reason(pid) {
+                        Some(reason) => refuse_protected_kill(app, pid, &reason),
+                        None => {
+                            // Ask first; the panel stays open showing the prompt
+                            app.kill_confirm = Some(pid);
+                            return false;
                         }
-                    }
+                    },
                     "Edit" => {
                         app.mode = AppMode::EditingAlert;
+                        app.alert_input_error = None;
                         if let Some(alert) = app.alerts.get(&pid) {
+                            app.cooldown_input = alert.cooldown_secs.to_string();
+                            app.rearm_input = alert.rearm_below_percent.map(|p| p.to_string()).unwrap_or_default();
+                            app.max_triggers_input = alert.max_triggers.map(|n| n.to_string()).unwrap_or_default();
+                            app.notify_every_input = alert.notify_every_secs.map(|secs| secs.to_string()).unwrap_or_default();
+                            app.dry_run_input = alert.dry_run;
                             app.selected_alert_action = match &alert.action {
                                 AlertAction::Kill => {
-                                    app.alert_input = format_bytes(alert.threshold_bytes);
+                                    app.alert_input = alert.threshold_input();
                                     0
                                 },
                                 AlertAction::CustomCommand(cmd) => {
-                                    app.alert_input = format_bytes(alert.threshold_bytes);
+                                    app.alert_input = alert.threshold_input();
                                     app.command_input = cmd.clone();
                                     1
                                 },
                                 AlertAction::SystemAlert => {
-                                    app.alert_input = format_bytes(alert.threshold_bytes);
+                                    app.alert_input = alert.threshold_input();
                                     2
                                 },
+                                AlertAction::CapturePcap(secs) => {
+                                    app.alert_input = alert.threshold_input();
+                                    app.command_input = secs.to_string();
+                                    3
+                                },
+                                AlertAction::Block => {
+                                    app.alert_input = alert.threshold_input();
+                                    4
+                                },
                             };
                         } else {
-                            app.alert_input.clear();
+                            clear_alert_inputs(app);
                             app.selected_alert_action = 0;
                         }
-                        app.command_input.clear();
                     }
                     "Details" => {
                         app.mode = AppMode::PacketDetails;
                         app.packet_scroll_offset = 0;
                         app.packet_filter = None;
+                        app.show_host_contacts = false;
+                        app.process_details = None;
                     }
                     "Remove" => {
-                        app.alerts.remove(&pid);
-                        app.system_alerts.remove(&pid);
+                        app.remove_alert(pid);
+                        app.save_alerts();
                     }
+                    "Mute" => open_mute_menu(app, pid),
+                    "Limit" => {
+                        app.limit_input = Some(app.limits.get(&pid).map(|limit| format_bytes(limit.rate)).unwrap_or_default());
+                    }
+                    "Unlimit" => app.unlimit_process(pid),
+                    "Block" => {
+                        let message = app.block_process(pid).unwrap_or_else(|refusal| refusal);
+                        app.notify(EventKind::System, Some(pid), message);
+                    }
+                    "Unblock" => app.unblock_process(pid),
                     _ => {}
                 }
             }
@@ -225,19 +781,418 @@ fn handle_action_panel_keys(app: &mut App, key: KeyCode) -> bool {
     false
 }
 
+/// Handle key events while the bandwidth limit prompt is open
+fn handle_limit_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Esc => app.limit_input = None,
+        KeyCode::Char(c) => {
+            if let Some(input) = app.limit_input.as_mut() {
+                input.push(c);
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(input) = app.limit_input.as_mut() {
+                input.pop();
+            }
+        }
+        KeyCode::Enter => {
+            let input = app.limit_input.as_deref().unwrap_or("");
+            // "500KB/s" reads the same as "500KB"
+            let input = input.trim().strip_suffix("/s").unwrap_or(input);
+            match parse_bytes(input) {
+                Ok(rate) if rate > 0 => {
+                    app.limit_input = None;
+                    if let Some(pid) = app.selected_process {
+                        app.limit_process(pid, rate);
+                    }
+                }
+                Ok(_) => {
+                    app.notice("⚠️ A limit must be above 0; remove it from the action panel instead".to_string());
+                }
+                Err(e) => {
+                    app.notice(format!("⚠️ {}", e));
+                }
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Port filter prompt, for the process table or the packet list depending on the view
+fn handle_port_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Esc => app.port_input = None,
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            if let Some(input) = app.port_input.as_mut() {
+                input.push(c);
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(input) = app.port_input.as_mut() {
+                input.pop();
+            }
+        }
+        KeyCode::Enter => {
+            let input = app.port_input.as_deref().unwrap_or("").trim();
+            // Empty shows everything again
+            let port = match input {
+                "" => None,
+                input => match input.parse::<u16>() {
+                    Ok(port) if port > 0 => Some(port),
+                    _ => {
+                        app.notice(format!("⚠️ '{}' is not a port from 1 to 65535", input));
+                        return false;
+                    }
+                },
+            };
+            app.port_input = None;
+            if app.mode == AppMode::PacketDetails {
+                match app.packet_filter.as_mut() {
+                    Some(filter) => filter.port = port,
+                    None => {
+                        app.packet_filter = port.map(|port| crate::types::PacketFilter {
+                            protocol: None,
+                            direction: None,
+                            search_term: None,
+                            search_regex: None,
+                            port: Some(port),
+                        });
+                    }
+                }
+                app.packet_scroll_offset = 0;
+            } else {
+                app.port_filter = port;
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Action panel while rows are marked: the actions apply to every marked process
+fn handle_bulk_action_keys(app: &mut App, key: KeyCode) -> bool {
+    if app.bulk_kill_confirm {
+        return handle_bulk_kill_confirm_keys(app, key);
+    }
+    const NUM_BULK_ACTIONS: usize = 4; // Kill, Alert, Export, Clear marks
+    match key {
+        KeyCode::Esc => {
+            app.show_action_panel = false;
+            app.selected_action = 0;
+        }
+        KeyCode::Up | KeyCode::Left
+            if app.selected_action > 0 => {
+                app.selected_action -= 1;
+            }
+        KeyCode::Down | KeyCode::Right
+            if app.selected_action < NUM_BULK_ACTIONS - 1 => {
+                app.selected_action += 1;
+            }
+        KeyCode::Enter => {
+            match app.selected_action {
+                0 => {
+                    // Ask first; the panel stays open showing the prompt
+                    app.bulk_kill_confirm = true;
+                    return false;
+                }
+                1 => {
+                    app.mode = AppMode::EditingAlert;
+                    app.alert_input_error = None;
+                    clear_alert_inputs(app);
+                    app.selected_alert_action = 0;
+                }
+                2 => {
+                    let pids = app.marked_pids();
+                    if let Err(e) = crate::ui::renderers::packet_details::export_combined_packets_to_csv(app, &pids) {
+                        app.notice(format!("❌ Export failed: {}", e));
+                    }
+                }
+                3 => app.marked.clear(),
+                _ => {}
+            }
+            app.show_action_panel = false;
+            app.selected_action = 0;
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle key events while the action panel asks whether to kill every marked process
+fn handle_bulk_kill_confirm_keys(app: &mut App, key: KeyCode) -> bool {
+    let graceful = match key {
+        KeyCode::Enter | KeyCode::Char('y') => false,
+        KeyCode::Char('t') => true,
+        KeyCode::Esc | KeyCode::Char('n') => {
+            app.bulk_kill_confirm = false;
+            return false;
+        }
+        _ => return false,
+    };
+    let pids = app.marked_pids();
+    let mut protected = 0;
+    for &pid in &pids {
+        if let Some(reason) = app.protected_reason(pid) {
+            refuse_protected_kill(app, pid, &reason);
+            protected += 1;
+        } else if graceful {
+            terminate_gracefully(app, pid);
+        } else {
+            kill_now(app, pid);
+        }
+    }
+    let attempted = pids.len() - protected;
+    let mut message = if graceful && !app.foreign_pids() {
+        let waiting = app.pending_kills.iter().filter(|kill| pids.contains(&kill.pid)).count();
+        format!(
+            "⏳ Sent SIGTERM to {} of {} marked processes; SIGKILL in {}s unless they exit, u cancels the latest",
+            waiting, attempted, KILL_GRACE_PERIOD.as_secs()
+        )
+    } else {
+        let killed = pids.iter().filter(|pid| app.killed_processes.contains(pid)).count();
+        format!("💀 Killed {} of {} marked processes", killed, attempted)
+    };
+    if protected > 0 {
+        message.push_str(&format!(" ({} protected, left running)", protected));
+    }
+    app.notice(message);
+    app.marked.clear();
+    app.bulk_kill_confirm = false;
+    app.show_action_panel = false;
+    app.selected_action = 0;
+    false
+}
+
+/// Handle key events while the action panel asks whether to kill `pid`
+fn handle_kill_confirm_keys(app: &mut App, pid: i32, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Enter | KeyCode::Char('y') => kill_now(app, pid),
+        KeyCode::Char('t') => terminate_gracefully(app, pid),
+        KeyCode::Esc | KeyCode::Char('n') => {}
+        _ => return false,
+    }
+    app.kill_confirm = None;
+    app.show_action_panel = false;
+    app.selected_action = 0;
+    false
+}
+
+/// Send SIGKILL (`force`) or SIGTERM to a local process; Err says why it couldn't be delivered
+fn signal_process(pid: i32, force: bool) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        let signal = if force { Signal::SIGKILL } else { Signal::SIGTERM };
+        signal::kill(Pid::from_raw(pid), Some(signal)).map_err(|e| e.desc().to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // Closing its windows is the closest Windows has to SIGTERM
+        use crate::process::control;
+        let result = if force {
+            control::terminate(pid, std::time::Duration::from_secs(2))
+        } else {
+            control::request_close(pid)
+        };
+        result.map_err(|e| e.to_string())
+    }
+}
+
+/// Kill a process right away
+fn kill_now(app: &mut App, pid: i32) {
+    let result = if app.demo_mode {
+        // Demo PIDs are made up; never signal a real process
+        Ok(())
+    } else if app.remote_hosts.is_some() {
+        // Remote PIDs belong to another machine
+        Err("it runs on another machine".to_string())
+    } else {
+        signal_process(pid, true)
+    };
+    finish_manual_kill(app, pid, result);
+}
+
+/// Ask a process to exit and kill it if it is still running after the grace period
+fn terminate_gracefully(app: &mut App, pid: i32) {
+    if app.foreign_pids() {
+        // Nothing to wait for: demo processes die at once and remote ones can't be signalled
+        return kill_now(app, pid);
+    }
+    if let Err(e) = signal_process(pid, false) {
+        return finish_manual_kill(app, pid, Err(e));
+    }
+    let name = app.stats.get(&pid)
+        .map(|info| info.name.clone())
+        .unwrap_or_else(|| format!("PID {}", pid));
+    let message = format!(
+        "⏳ Sent SIGTERM to {} (PID {}); SIGKILL in {}s unless it exits, press u to cancel",
+        name, pid, KILL_GRACE_PERIOD.as_secs()
+    );
+    app.events.push(EventKind::Kill, Some(pid), &message);
+    app.notice(message);
+    app.pending_kills.retain(|pending| pending.pid != pid);
+    app.pending_kills.push(PendingKill { pid, name, escalate_at: std::time::Instant::now() + KILL_GRACE_PERIOD });
+}
+
+/// Called every tick: report processes that exited after SIGTERM and
+/// escalate to SIGKILL for the ones whose grace period is over
+pub fn check_pending_kills(app: &mut App) {
+    let now = std::time::Instant::now();
+    let pending = std::mem::take(&mut app.pending_kills);
+    for kill in pending {
+        if !crate::process::is_process_alive(kill.pid) {
+            finish_manual_kill(app, kill.pid, Ok(()));
+        } else if now >= kill.escalate_at {
+            let result = signal_process(kill.pid, true);
+            finish_manual_kill(app, kill.pid, result);
+        } else {
+            app.pending_kills.push(kill);
+        }
+    }
+}
+
+/// Cancel the SIGKILL of the latest graceful kill; the process keeps its SIGTERM
+fn cancel_pending_kill(app: &mut App) {
+    if let Some(kill) = app.pending_kills.pop() {
+        let message = format!("↩️ Won't SIGKILL {} (PID {}); it was only sent SIGTERM", kill.name, kill.pid);
+        app.events.push(EventKind::Kill, Some(kill.pid), &message);
+        app.notice(message);
+    }
+}
+
+/// Report a manual kill of a protected process that was not attempted
+fn refuse_protected_kill(app: &mut App, pid: i32, reason: &str) {
+    let name = app.stats.get(&pid).map_or("", |info| info.name.as_str());
+    if let Some(audit) = &app.audit {
+        audit.record(&crate::audit::AuditEvent {
+            event: "kill",
+            pid,
+            name,
+            bytes: app.stats.get(&pid).map_or(0, |info| info.sent + info.received),
+            threshold: None,
+            action: "kill",
+            result: "protected",
+            message: &format!("Refused to kill protected {} (PID {}) by hand", name, pid),
+        });
+    }
+    let message = format!(
+        "🛡️ {} (PID {}) is protected by {}; start with --allow-protected-kills to kill it",
+        name, pid, reason
+    );
+    app.events.push(EventKind::Kill, Some(pid), &message);
+    app.notice(message);
+}
+
+/// Audit, report and forget a process killed by hand
+fn finish_manual_kill(app: &mut App, pid: i32, result: Result<(), String>) {
+    let kill_success = result.is_ok();
+    if let Some(audit) = &app.audit {
+        let (name, bytes) = app.stats.get(&pid)
+            .map_or(("", 0), |info| (info.name.as_str(), info.sent + info.received));
+        audit.record(&crate::audit::AuditEvent {
+            event: "kill",
+            pid,
+            name,
+            bytes,
+            threshold: None,
+            action: "kill",
+            result: if kill_success { "killed" } else { "failed" },
+            message: &format!("{} {} (PID {}) by hand", if kill_success { "Killed" } else { "Failed to kill" }, name, pid),
+        });
+    }
+    if kill_success {
+        let process_name = app.stats.get(&pid)
+            .map(|info| info.name.clone())
+            .unwrap_or_else(|| format!("PID {}", pid));
+
+        let message = format!("✅ Successfully killed {} (PID {})", process_name, pid);
+        app.events.push(EventKind::Kill, Some(pid), &message);
+        app.notice(message);
+
+        // Remove process immediately from stats and alerts
+        app.note_departure(pid);
+        app.stats.remove(&pid);
+        app.alerts.remove(&pid);
+
+        app.killed_processes.insert(pid);
+        if app.selected_process == Some(pid) {
+            app.selected_process = None;
+        }
+    } else if let Err(reason) = result {
+        let message = format!("❌ Failed to kill process (PID {}): {}", pid, reason);
+        app.events.push(EventKind::Kill, Some(pid), &message);
+        app.notice(message);
+    }
+}
+
 /// Handle key events in main view (normal mode without action panel)
 fn handle_main_view_keys(app: &mut App, key: KeyCode) -> bool {
     match key {
         KeyCode::Char('q') => return true,
+        KeyCode::Esc
+            // Leave playback and return to the live view
+            if app.playback_offset.is_some() => {
+                app.playback_offset = None;
+            }
+        KeyCode::Esc
+            // Leave the scrolled-back chart
+            if app.chart_rewound_to.is_some() => {
+                app.chart_rewound_to = None;
+                app.rewound_history = None;
+            }
+        KeyCode::Esc
+            // Go back to the view a quick-jump came from
+            if app.jump_return_mode.is_some() => {
+                if let Some(mode) = app.jump_return_mode.take() {
+                    app.mode = mode;
+                }
+            }
         KeyCode::Esc
             // Dismiss notification boxes when Esc is pressed
-            if (app.last_alert_message.is_some() || !app.command_execution_log.is_empty() || app.kill_notification.is_some()) => {
-                app.last_alert_message = None;
-                app.last_alert_message_time = None;
-                app.kill_notification = None;
-                app.kill_notification_time = None;
+            if (!app.notifications.is_empty() || !app.command_execution_log.is_empty()) => {
+                app.notifications.clear();
+                app.last_alert_pid = None;
                 app.command_execution_log.clear();
             },
+        KeyCode::Char('x') => app.notifications.dismiss(),
+        KeyCode::Char('X') => {
+            app.notifications.clear();
+            app.last_alert_pid = None;
+        }
+        KeyCode::Char('[') => app.notifications.scroll_up(),
+        KeyCode::Char(']') => app.notifications.scroll_down(),
+        KeyCode::Char('N') => {
+            if app.notifications.do_not_disturb() {
+                let held = app.notifications.set_do_not_disturb(false);
+                app.notice(match held {
+                    0 => "🔔 Do not disturb is off".to_string(),
+                    held => format!("🔔 Do not disturb is off; {} notifications were held back (see the Events view)", held),
+                });
+            } else {
+                app.notifications.clear();
+                app.notifications.set_do_not_disturb(true);
+            }
+        }
+        KeyCode::Char('g') if cfg!(target_os = "linux") => {
+            app.group_by = GroupBy::next(app.group_by, app.containers_mode);
+            app.group_selected = 0;
+        }
+        KeyCode::Char('G') => app.group_filter = None,
+        KeyCode::Esc
+            if !app.marked.is_empty() => {
+                app.marked.clear();
+            }
+        KeyCode::Char('M') => {
+            // Mute the alert from the notification on screen, else the selected process's alert
+            let target = app.last_alert_pid
+                .filter(|_| !app.notifications.is_empty())
+                .or(app.selected_process)
+                .filter(|pid| app.alerts.contains_key(pid));
+            if let Some(pid) = target {
+                open_mute_menu(app, pid);
+            }
+        }
         KeyCode::Char('p') => app.sort_by = SortColumn::Pid,
         KeyCode::Char('n') => app.sort_by = SortColumn::Name,
         KeyCode::Char('u') => app.sort_by = SortColumn::User,
@@ -271,6 +1226,32 @@ fn handle_main_view_keys(app: &mut App, key: KeyCode) -> bool {
             if app.containers_mode => {
                 app.sort_by = SortColumn::Container;
             }
+        KeyCode::Char('l') => app.traffic_scope = app.traffic_scope.next(),
+        KeyCode::Char('P') => app.port_input = Some(app.port_filter.map(|port| port.to_string()).unwrap_or_default()),
+        KeyCode::Char('B') => app.mark_baseline(),
+        KeyCode::Char('b') => {
+            if app.baseline.is_some() {
+                app.show_baseline_diff = true;
+                app.baseline_scroll = 0;
+            } else {
+                app.notice("ℹ️ Press B to mark a baseline first".to_string());
+            }
+        }
+        KeyCode::Char('z') => {
+            app.hide_idle = !app.hide_idle;
+            app.notice(if app.hide_idle {
+                format!("💤 Hiding processes idle for {}s; z shows them again", app.idle_after_secs)
+            } else {
+                "💤 Showing idle processes".to_string()
+            });
+        }
+        KeyCode::Char('f') => {
+            if let Some(pid) = app.selected_process {
+                app.toggle_pin(pid);
+            } else {
+                app.notice("ℹ️ Select a process to pin it".to_string());
+            }
+        }
         KeyCode::Char('d') => {
             app.sort_direction = if app.sort_direction == SortDirection::Asc {
                 SortDirection::Desc
@@ -278,8 +1259,16 @@ fn handle_main_view_keys(app: &mut App, key: KeyCode) -> bool {
                 SortDirection::Asc
             };
         }
+        KeyCode::Char('S') => {
+            app.show_sort_menu = true;
+            app.sort_menu_selected = SortColumn::menu_columns(app.containers_mode)
+                .iter()
+                .position(|column| *column == app.sort_by)
+                .unwrap_or(0);
+        }
         KeyCode::Tab => {
-            // Cycle through modes: Main -> Bandwidth -> Overview -> Settings -> Main
+            app.jump_return_mode = None;
+            // Cycle through modes: Main -> Bandwidth -> Overview -> Interfaces -> Local -> Services -> Events -> Settings -> Main
             if app.mode == AppMode::Settings {
                 // Currently in settings mode, go back to normal
                 app.mode = AppMode::Normal;
@@ -327,33 +1316,26 @@ fn handle_main_view_keys(app: &mut App, key: KeyCode) -> bool {
                 // Force chart update when changing metrics mode
                 crate::ui::charts::update_chart_datasets(app);
             }
-        KeyCode::Down => {
-            let sorted_pids: Vec<i32> = app.sorted_stats().iter().map(|(pid, _)| **pid).collect();
-            if let Some(current_pid) = app.selected_process {
-                if let Some(current_index) = sorted_pids.iter().position(|p| *p == current_pid)
-                    && current_index < sorted_pids.len() - 1 {
-                        app.selected_process = Some(sorted_pids[current_index + 1]);
-                    }
-            } else if !sorted_pids.is_empty() {
-                app.selected_process = Some(sorted_pids[0]);
-            }
-        }
-        KeyCode::Up => {
-            let sorted_pids: Vec<i32> = app.sorted_stats().iter().map(|(pid, _)| **pid).collect();
-            if let Some(current_pid) = app.selected_process {
-                if let Some(current_index) = sorted_pids.iter().position(|p| *p == current_pid)
-                    && current_index > 0 {
-                        app.selected_process = Some(sorted_pids[current_index - 1]);
-                    }
-            } else if !sorted_pids.is_empty() {
-                app.selected_process = Some(sorted_pids[sorted_pids.len() - 1]);
+        KeyCode::Down => app.move_selection(1),
+        KeyCode::Up => app.move_selection(-1),
+        KeyCode::Char(' ') => {
+            // Mark the selected row and move on, so a run of rows is marked by holding Space
+            if let Some(pid) = app.selected_process {
+                app.toggle_mark(pid);
+                app.move_selection(1);
             }
         }
         KeyCode::Enter
-            if app.selected_process.is_some() => {
+            if app.selected_process.is_some() || !app.marked.is_empty() => {
                 app.show_action_panel = true;
                 app.selected_action = 0;
             }
+        // Time-travel playback through recorded snapshots
+        KeyCode::Left => app.scrub_back(),
+        KeyCode::Right => app.scrub_forward(),
+        // Chart history older than the window, from the history store
+        KeyCode::Char('<') => app.rewind_chart(1),
+        KeyCode::Char('>') => app.rewind_chart(-1),
         _ => {}
     }
     false
@@ -363,17 +1345,24 @@ fn handle_main_view_keys(app: &mut App, key: KeyCode) -> bool {
 fn handle_overview_mode_keys(app: &mut App, key: KeyCode) -> bool {
     match key {
         KeyCode::Char('q') => return true,
+        KeyCode::Char('D') => {
+            app.show_scan_stats = !app.show_scan_stats;
+        }
+        KeyCode::Esc if app.show_scan_stats => {
+            app.show_scan_stats = false;
+        }
         KeyCode::Esc => {
             app.mode = AppMode::Normal;
         }
         KeyCode::Tab => {
-            // Cycle from SystemOverview to Settings mode
-            app.mode = AppMode::Settings;
+            // Cycle from SystemOverview to Interfaces mode
+            app.mode = AppMode::Interfaces;
         }
         KeyCode::Char('r') => {
             // Reset threshold exceeded state
             app.threshold_exceeded = false;
             app.threshold_exceeded_time = None;
+            app.cost_budget_exceeded = false;
         }
         KeyCode::Char('+') | KeyCode::Char('=') => {
             // Increase quota by 100MB
@@ -401,69 +1390,164 @@ fn handle_overview_mode_keys(app: &mut App, key: KeyCode) -> bool {
     false
 }
 
-/// Handle key events in settings mode
-fn handle_settings_mode_keys(app: &mut App, key: KeyCode) -> bool {
+/// Handle key events in interfaces mode
+fn handle_interfaces_mode_keys(app: &mut App, key: KeyCode) -> bool {
     match key {
-        KeyCode::Char('q') => return true, // Quit application
+        KeyCode::Char('q') => return true,
         KeyCode::Esc => {
-            // Go back to main mode
             app.mode = AppMode::Normal;
         }
         KeyCode::Tab => {
-            // Cycle from Settings back to Main mode
+            // Cycle from Interfaces to Local traffic mode
+            app.mode = AppMode::LocalTraffic;
+        }
+        KeyCode::Up
+            if app.interface_scroll_offset > 0 => {
+                app.interface_scroll_offset -= 1;
+            }
+        KeyCode::Down
+            if app.interface_scroll_offset + 1 < app.interfaces.len() => {
+                app.interface_scroll_offset += 1;
+            }
+        _ => {}
+    }
+    false
+}
+
+fn handle_local_traffic_mode_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char('q') => return true,
+        KeyCode::Esc => {
             app.mode = AppMode::Normal;
         }
+        KeyCode::Tab => {
+            // Cycle from Local traffic to Services mode
+            app.mode = AppMode::Services;
+        }
         KeyCode::Up
-            if app.settings_selected_option > 0 => {
-                app.settings_selected_option -= 1;
+            if app.local_flow_scroll_offset > 0 => {
+                app.local_flow_scroll_offset -= 1;
             }
         KeyCode::Down
-            // We have 2 settings for now
-            if app.settings_selected_option < 1 => {
-                app.settings_selected_option += 1;
-            }
-        KeyCode::Left => {
-            if let Some(mut config) = crate::config::load_config() {
-                match app.settings_selected_option {
-                    0 => {
-                        config.large_packet_threshold = config.large_packet_threshold.saturating_sub(1000);
-                    }
-                    1 => {
-                        config.frequent_connection_threshold = config.frequent_connection_threshold.saturating_sub(1);
-                    }
-                    _ => {}
-                }
-                if crate::config::save_config(&config).is_ok() {
-                    app.settings_notification = Some("✅ Setting updated.".to_string());
-                } else {
-                    app.settings_notification = Some("❌ Failed to save setting.".to_string());
-                }
-                app.settings_notification_time = Some(std::time::Instant::now());
-            } else {
-                app.settings_notification = Some("ℹ️ No saved configuration to update.".to_string());
-                app.settings_notification_time = Some(std::time::Instant::now());
+            if app.local_flow_scroll_offset + 1 < app.local_flows.len() => {
+                app.local_flow_scroll_offset += 1;
             }
+        _ => {}
+    }
+    false
+}
+
+fn handle_services_mode_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char('q') => return true,
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
         }
-        KeyCode::Right => {
-            if let Some(mut config) = crate::config::load_config() {
-                match app.settings_selected_option {
-                    0 => {
-                        config.large_packet_threshold = config.large_packet_threshold.saturating_add(1000);
-                    }
-                    1 => {
-                        config.frequent_connection_threshold = config.frequent_connection_threshold.saturating_add(1);
-                    }
-                    _ => {}
-                }
-                 if crate::config::save_config(&config).is_ok() {
-                    app.settings_notification = Some("✅ Setting updated.".to_string());
-                } else {
-                    app.settings_notification = Some("❌ Failed to save setting.".to_string());
+        KeyCode::Tab => {
+            // Cycle from Services to Events mode
+            app.mode = AppMode::Events;
+        }
+        KeyCode::Up
+            if app.service_scroll_offset > 0 => {
+                app.service_scroll_offset -= 1;
+            }
+        KeyCode::Down
+            if app.service_scroll_offset + 1 < app.services.len() => {
+                app.service_scroll_offset += 1;
+            }
+        _ => {}
+    }
+    false
+}
+
+/// Handle key events in the events view
+fn handle_events_mode_keys(app: &mut App, key: KeyCode) -> bool {
+    if app.event_output.is_some() {
+        return handle_event_output_keys(app, key);
+    }
+    let last = app.events.len().saturating_sub(1);
+    match key {
+        KeyCode::Char('q') => return true,
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Tab => {
+            // Cycle from Events to Settings mode
+            app.mode = AppMode::Settings;
+        }
+        KeyCode::Up => app.event_scroll_offset = app.event_scroll_offset.saturating_sub(1),
+        KeyCode::Down => app.event_scroll_offset = (app.event_scroll_offset + 1).min(last),
+        KeyCode::PageUp => app.event_scroll_offset = app.event_scroll_offset.saturating_sub(10),
+        KeyCode::PageDown => app.event_scroll_offset = (app.event_scroll_offset + 10).min(last),
+        KeyCode::Home => app.event_scroll_offset = 0,
+        KeyCode::Enter => {
+            if let Some(event) = app.events.get(app.event_scroll_offset) && let Some(output) = &event.output {
+                app.event_output = Some(crate::types::OutputView {
+                    title: format!("Command output at {}", event.time.format("%H:%M:%S")),
+                    message: event.message.clone(),
+                    output: output.clone(),
+                    scroll: 0,
+                });
+            }
+        }
+        KeyCode::Char('e') if !app.events.is_empty() => {
+            let message = match app.events.export_csv() {
+                Ok(filename) => {
+                    let dir = std::env::current_dir()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_else(|_| "current directory".to_string());
+                    format!("✓ Successfully exported {} events to file '{}' in {}", app.events.len(), filename, dir)
                 }
-                app.settings_notification_time = Some(std::time::Instant::now());
-            } else {
-                app.settings_notification = Some("ℹ️ No saved configuration to update.".to_string());
-                app.settings_notification_time = Some(std::time::Instant::now());
+                Err(e) => format!("❌ Export failed: {}", e),
+            };
+            app.export_notification_state = crate::types::NotificationState::Active(message);
+            app.export_notification_time = Some(std::time::Instant::now());
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle key events while a custom command's output is open over the events view
+fn handle_event_output_keys(app: &mut App, key: KeyCode) -> bool {
+    let Some(view) = app.event_output.as_mut() else { return false };
+    let last = view.output.stdout.lines().count() + view.output.stderr.lines().count();
+    match key {
+        KeyCode::Char('q') => return true,
+        KeyCode::Esc | KeyCode::Enter => app.event_output = None,
+        KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+        KeyCode::Down => view.scroll = (view.scroll + 1).min(last),
+        KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(10),
+        KeyCode::PageDown => view.scroll = (view.scroll + 10).min(last),
+        KeyCode::Home => view.scroll = 0,
+        _ => {}
+    }
+    false
+}
+
+/// Handle key events in settings mode
+fn handle_settings_mode_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char('q') => return true, // Quit application
+        KeyCode::Esc => {
+            // Go back to main mode
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Tab => {
+            // Cycle from Settings back to Main mode
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Up if app.settings_selected_option > 0 => {
+            app.settings_selected_option -= 1;
+        }
+        KeyCode::Down if app.settings_selected_option + 1 < settings_fields::count() => {
+            app.settings_selected_option += 1;
+        }
+        KeyCode::Left | KeyCode::Right | KeyCode::Enter => {
+            match settings_fields::nth(app.settings_selected_option) {
+                Some(Setting::Interface) => open_interface_picker(app),
+                Some(setting) if key != KeyCode::Enter => settings_fields::change(app, setting, key == KeyCode::Right),
+                _ => {}
             }
         }
         KeyCode::Char('r') => {
@@ -498,6 +1582,27 @@ fn handle_packet_details_mode_keys(app: &mut App, key: KeyCode) -> bool {
         // return handle_packet_search_keys(app, key);
     }
 
+    if app.show_host_contacts {
+        handle_host_contacts_keys(app, key);
+        return false;
+    }
+    if app.tcp_info.is_some() {
+        if matches!(key, KeyCode::Char('T') | KeyCode::Char('q') | KeyCode::Esc) {
+            app.tcp_info = None;
+        }
+        return false;
+    }
+    if let Some(view) = app.connections.as_mut() {
+        let count = view.sockets.as_ref().and_then(|sockets| sockets.as_ref().ok()).map_or(0, Vec::len);
+        match key {
+            KeyCode::Char('C') | KeyCode::Char('q') | KeyCode::Esc => app.connections = None,
+            KeyCode::Up | KeyCode::Char('k') => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') if view.scroll + 1 < count => view.scroll += 1,
+            _ => {}
+        }
+        return false;
+    }
+
     match key {
         KeyCode::Char('q') | KeyCode::Esc => {
             app.mode = AppMode::Normal;
@@ -556,6 +1661,7 @@ fn handle_packet_details_mode_keys(app: &mut App, key: KeyCode) -> bool {
                     direction: None,
                     search_term: None,
                     search_regex: None,
+                    port: None,
                 });
             }
             // Reset scroll when filtering changes
@@ -575,6 +1681,7 @@ fn handle_packet_details_mode_keys(app: &mut App, key: KeyCode) -> bool {
                     direction: Some(crate::types::PacketDirection::Sent),
                     search_term: None,
                     search_regex: None,
+                    port: None,
                 });
             }
             // Reset scroll when filtering changes
@@ -595,6 +1702,7 @@ fn handle_packet_details_mode_keys(app: &mut App, key: KeyCode) -> bool {
                     direction: None,
                     search_term: None,
                     search_regex: None,
+                    port: None,
                 });
             }
             app.packet_scroll_offset = 0;
@@ -609,6 +1717,7 @@ fn handle_packet_details_mode_keys(app: &mut App, key: KeyCode) -> bool {
                     direction: None,
                     search_term: None,
                     search_regex: None,
+                    port: None,
                 });
             }
             app.packet_scroll_offset = 0;
@@ -627,10 +1736,26 @@ fn handle_packet_details_mode_keys(app: &mut App, key: KeyCode) -> bool {
                     direction: Some(crate::types::PacketDirection::Sent),
                     search_term: None,
                     search_regex: None,
+                    port: None,
                 });
             }
             app.packet_scroll_offset = 0;
         }
+        Char('h') => {
+            // Switch to the first-contact audit trail
+            app.show_host_contacts = true;
+            app.host_contact_scroll_offset = 0;
+        }
+        Char('T') => {
+            open_tcp_info(app);
+        }
+        Char('P') => {
+            let port = app.packet_filter.as_ref().and_then(|filter| filter.port);
+            app.port_input = Some(port.map(|port| port.to_string()).unwrap_or_default());
+        }
+        Char('C') => {
+            open_connections(app);
+        }
         Char('/') => {
             // Enter search mode
             app.packet_search_mode = true;
@@ -750,4 +1875,115 @@ fn handle_packet_details_mode_keys(app: &mut App, key: KeyCode) -> bool {
         _ => {}
     }
     false
-} 
\ No newline at end of file
+}
+
+/// Keys for the first-contact audit trail shown inside the packet details view
+fn handle_host_contacts_keys(app: &mut App, key: KeyCode) {
+    let num_hosts = app
+        .selected_process
+        .and_then(|pid| app.stats.get(&pid))
+        .map(|p| p.host_contacts.len())
+        .unwrap_or(0);
+
+    match key {
+        KeyCode::Char('h') | KeyCode::Char('q') | KeyCode::Esc => {
+            app.show_host_contacts = false;
+            app.force_redraw = true;
+        }
+        KeyCode::Down | KeyCode::Char('j')
+            if app.host_contact_scroll_offset + 1 < num_hosts => {
+                app.host_contact_scroll_offset += 1;
+            }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.host_contact_scroll_offset = app.host_contact_scroll_offset.saturating_sub(1);
+        }
+        KeyCode::PageUp => {
+            let page = app.packet_visible_rows.max(1);
+            app.host_contact_scroll_offset = app.host_contact_scroll_offset.saturating_sub(page);
+        }
+        KeyCode::PageDown => {
+            let page = app.packet_visible_rows.max(1);
+            app.host_contact_scroll_offset = (app.host_contact_scroll_offset + page).min(num_hosts.saturating_sub(1));
+        }
+        KeyCode::Char('e') => {
+            if let Some(pid) = app.selected_process
+                && let Some(process_info) = app.stats.get(&pid).cloned()
+                && let Err(e) = crate::ui::renderers::packet_details::export_host_contacts_to_csv(app, &process_info) {
+                    app.export_notification_state = crate::types::NotificationState::Active(format!("❌ Export failed: {}", e));
+                    app.export_notification_time = Some(std::time::Instant::now());
+                }
+        }
+        _ => {}
+    }
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ProcessIdentifier, ProcessInfo};
+
+    /// Processes 1 to `count`, listed by ascending PID
+    fn app_with_processes(count: i32) -> App {
+        let mut app = App::new(false, false);
+        app.sort_by = SortColumn::Pid;
+        app.sort_direction = SortDirection::Asc;
+        for pid in 1..=count {
+            let identifier = ProcessIdentifier { pid, name: format!("proc{}", pid), container_name: None, user_name: None, unit: None };
+            app.stats.insert(pid, ProcessInfo::new(&identifier));
+        }
+        app
+    }
+
+    #[test]
+    fn selection_stops_at_both_ends() {
+        let mut app = app_with_processes(5);
+        app.move_selection(1);
+        assert_eq!(app.selected_process, Some(1));
+        app.move_selection(-3);
+        assert_eq!(app.selected_process, Some(1));
+        app.move_selection(10);
+        assert_eq!(app.selected_process, Some(5));
+
+        // With nothing selected, Down counts from the top and Up from the bottom
+        app.selected_process = None;
+        app.move_selection(3);
+        assert_eq!(app.selected_process, Some(3));
+        app.selected_process = None;
+        app.move_selection(-2);
+        assert_eq!(app.selected_process, Some(4));
+        app.selected_process = None;
+        app.move_selection(-9);
+        assert_eq!(app.selected_process, Some(1));
+
+        // A selection that is no longer listed stays as it is
+        app.selected_process = Some(42);
+        app.move_selection(1);
+        assert_eq!(app.selected_process, Some(42));
+    }
+
+    #[test]
+    fn coalesces_runs_of_arrow_keys() {
+        let mut app = app_with_processes(5);
+        let keys = [KeyCode::Down, KeyCode::Down, KeyCode::Down, KeyCode::Up, KeyCode::Down, KeyCode::Down];
+        assert!(!handle_key_events(&mut app, &keys));
+        // Down ×3 from no selection, then up one and down two
+        assert_eq!(app.selected_process, Some(4));
+        assert!(!handle_key_events(&mut app, &[KeyCode::Down; 8]));
+        assert_eq!(app.selected_process, Some(5));
+        assert!(!handle_key_events(&mut app, &[KeyCode::Up; 3]));
+        assert_eq!(app.selected_process, Some(2));
+        // Keys after a run are still handled, a quit included
+        assert!(handle_key_events(&mut app, &[KeyCode::Up, KeyCode::Up, KeyCode::Char('q')]));
+        assert_eq!(app.selected_process, Some(1));
+    }
+
+    #[test]
+    fn runs_go_to_an_open_overlay_instead() {
+        let mut app = app_with_processes(5);
+        app.selected_process = Some(3);
+        app.show_help = true;
+        handle_key_events(&mut app, &[KeyCode::Down; 4]);
+        assert_eq!(app.selected_process, Some(3));
+        assert!(app.help_scroll > 0);
+    }
+}
@@ -0,0 +1,240 @@
+// User key remapping, loaded from the `keys` section of the config file.
+//
+// Handlers keep matching on the default keys: a remapped key is translated back
+// to the default key of its action before dispatch, and the default key of a
+// remapped action is swallowed so it no longer triggers it.
+
+use std::collections::HashMap;
+use crossterm::event::KeyCode;
+
+/// Where a key press is interpreted; each action only applies in some of them
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    Main,          // Process table, no popup open
+    PacketDetails, // Packet list, not typing a search
+    Other,         // Overview, Interfaces, Local traffic, Services, Events, Settings
+}
+
+/// Remappable actions
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    NextMode,
+    SortPid,
+    SortName,
+    SortUser,
+    SortSent,
+    SortReceived,
+    SortContainer,
+    ReverseSort,
+    SortMenu,
+    Search,
+    Export,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [
+        Action::Quit,
+        Action::NextMode,
+        Action::SortPid,
+        Action::SortName,
+        Action::SortUser,
+        Action::SortSent,
+        Action::SortReceived,
+        Action::SortContainer,
+        Action::ReverseSort,
+        Action::SortMenu,
+        Action::Search,
+        Action::Export,
+    ];
+
+    /// Name used in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::NextMode => "next_mode",
+            Action::SortPid => "sort_pid",
+            Action::SortName => "sort_name",
+            Action::SortUser => "sort_user",
+            Action::SortSent => "sort_sent",
+            Action::SortReceived => "sort_received",
+            Action::SortContainer => "sort_container",
+            Action::ReverseSort => "reverse_sort",
+            Action::SortMenu => "sort_menu",
+            Action::Search => "search",
+            Action::Export => "export",
+        }
+    }
+
+    /// The key the input handlers match on
+    pub fn default_key(self) -> KeyCode {
+        match self {
+            Action::Quit => KeyCode::Char('q'),
+            Action::NextMode => KeyCode::Tab,
+            Action::SortPid => KeyCode::Char('p'),
+            Action::SortName => KeyCode::Char('n'),
+            Action::SortUser => KeyCode::Char('u'),
+            Action::SortSent => KeyCode::Char('s'),
+            Action::SortReceived => KeyCode::Char('r'),
+            Action::SortContainer => KeyCode::Char('c'),
+            Action::ReverseSort => KeyCode::Char('d'),
+            Action::SortMenu => KeyCode::Char('S'),
+            Action::Search => KeyCode::Char('/'),
+            Action::Export => KeyCode::Char('e'),
+        }
+    }
+
+    fn applies_in(self, context: KeyContext) -> bool {
+        match self {
+            Action::Quit | Action::NextMode => context != KeyContext::PacketDetails,
+            Action::Search | Action::Export => context == KeyContext::PacketDetails,
+            _ => context == KeyContext::Main,
+        }
+    }
+}
+
+/// Parse a key name from the config: a single character, `Tab`, `Esc`, `Enter`,
+/// `Backspace`, `Space` or `F1`-`F12`
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    match name.to_ascii_lowercase().as_str() {
+        "tab" => Some(KeyCode::Tab),
+        "esc" | "escape" => Some(KeyCode::Esc),
+        "enter" => Some(KeyCode::Enter),
+        "backspace" => Some(KeyCode::Backspace),
+        "space" => Some(KeyCode::Char(' ')),
+        f => f.strip_prefix('f')?.parse().ok().filter(|n| (1..=12).contains(n)).map(KeyCode::F),
+    }
+}
+
+/// Human-readable name of a key, the inverse of `parse_key`
+fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Keymap {
+    bindings: HashMap<Action, KeyCode>, // Only actions moved away from their default key
+}
+
+impl Keymap {
+    /// Build the keymap from the config's `keys` section (action name -> key name).
+    /// Unknown actions and keys are skipped and reported.
+    pub fn from_config(keys: &HashMap<String, String>) -> (Keymap, Vec<String>) {
+        let mut keymap = Keymap::default();
+        let mut problems = Vec::new();
+        for (action_name, key_name) in keys {
+            let Some(action) = Action::ALL.iter().copied().find(|a| a.name() == action_name) else {
+                problems.push(format!("unknown action '{}'", action_name));
+                continue;
+            };
+            let Some(key) = parse_key(key_name) else {
+                problems.push(format!("unknown key '{}' for {}", key_name, action_name));
+                continue;
+            };
+            if key != action.default_key() {
+                keymap.bindings.insert(action, key);
+            }
+        }
+        problems.sort();
+        (keymap, problems)
+    }
+
+    /// Key currently bound to an action
+    pub fn key_for(&self, action: Action) -> KeyCode {
+        self.bindings.get(&action).copied().unwrap_or(action.default_key())
+    }
+
+    /// Name of the key bound to an action, for on-screen hints
+    pub fn label(&self, action: Action) -> String {
+        key_label(self.key_for(action))
+    }
+
+    /// Map a pressed key to the default key the handlers expect. Keys left
+    /// behind by a remapped action become `KeyCode::Null`.
+    pub fn translate(&self, context: KeyContext, key: KeyCode) -> KeyCode {
+        if self.bindings.is_empty() {
+            return key;
+        }
+        let mut actions = Action::ALL.iter().copied().filter(|a| a.applies_in(context));
+        // Explicit bindings win over defaults they collide with
+        let bound = actions.clone()
+            .find(|a| self.bindings.get(a) == Some(&key))
+            .or_else(|| actions.clone().find(|a| !self.bindings.contains_key(a) && a.default_key() == key));
+        match bound {
+            Some(action) => action.default_key(),
+            None if actions.any(|a| a.default_key() == key) => KeyCode::Null,
+            None => key,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries.iter().map(|(action, key)| (action.to_string(), key.to_string())).collect()
+    }
+
+    #[test]
+    fn parses_key_names() {
+        assert_eq!(parse_key("x"), Some(KeyCode::Char('x')));
+        assert_eq!(parse_key("Q"), Some(KeyCode::Char('Q')));
+        assert_eq!(parse_key("TAB"), Some(KeyCode::Tab));
+        assert_eq!(parse_key("escape"), Some(KeyCode::Esc));
+        assert_eq!(parse_key("Space"), Some(KeyCode::Char(' ')));
+        assert_eq!(parse_key("F12"), Some(KeyCode::F(12)));
+        assert_eq!(parse_key("f13"), None);
+        assert_eq!(parse_key("F0"), None);
+        assert_eq!(parse_key("ctrl-x"), None);
+        assert_eq!(parse_key(""), None);
+        for key in [KeyCode::Char(' '), KeyCode::Char('/'), KeyCode::Tab, KeyCode::Backspace, KeyCode::F(5)] {
+            assert_eq!(parse_key(&key_label(key)), Some(key));
+        }
+    }
+
+    #[test]
+    fn reports_unknown_actions_and_keys() {
+        let (keymap, problems) = Keymap::from_config(&keys(&[
+            ("quit", "x"),
+            ("explode", "b"),
+            ("export", "Ctrl+E"),
+            ("sort_pid", "p"), // Its default key: nothing to remap
+        ]));
+        assert_eq!(problems, ["unknown action 'explode'", "unknown key 'Ctrl+E' for export"]);
+        assert_eq!(keymap.key_for(Action::Quit), KeyCode::Char('x'));
+        assert_eq!(keymap.label(Action::Export), "e");
+        assert!(!keymap.bindings.contains_key(&Action::SortPid));
+    }
+
+    #[test]
+    fn translates_remapped_keys_to_the_defaults() {
+        let (keymap, _) = Keymap::from_config(&keys(&[("quit", "x"), ("sort_sent", "r"), ("search", "f")]));
+        // The new key acts like the default one, which no longer does anything
+        assert_eq!(keymap.translate(KeyContext::Main, KeyCode::Char('x')), KeyCode::Char('q'));
+        assert_eq!(keymap.translate(KeyContext::Main, KeyCode::Char('q')), KeyCode::Null);
+        // A binding wins over the default key it collides with
+        assert_eq!(keymap.translate(KeyContext::Main, KeyCode::Char('r')), KeyCode::Char('s'));
+        assert_eq!(keymap.translate(KeyContext::Main, KeyCode::Char('s')), KeyCode::Null);
+        // Bindings only apply where their action does
+        assert_eq!(keymap.translate(KeyContext::PacketDetails, KeyCode::Char('f')), KeyCode::Char('/'));
+        assert_eq!(keymap.translate(KeyContext::Main, KeyCode::Char('f')), KeyCode::Char('f'));
+        assert_eq!(keymap.translate(KeyContext::PacketDetails, KeyCode::Char('x')), KeyCode::Char('x'));
+        // Keys no action uses pass through
+        assert_eq!(keymap.translate(KeyContext::Main, KeyCode::Down), KeyCode::Down);
+        assert_eq!(Keymap::default().translate(KeyContext::Main, KeyCode::Char('q')), KeyCode::Char('q'));
+    }
+}
@@ -4,13 +4,17 @@ pub mod input;
 pub mod charts;
 pub mod renderers;
 pub mod widgets;
+pub mod theme;
+pub mod keymap;
+pub mod profile;
+pub mod settings_fields;
 
 use std::io;
 use ratatui::{backend::CrosstermBackend, Terminal};
 use crate::types::{App, AppMode};
 
 // Re-export the main public functions
-pub use terminal::{setup_terminal, restore_terminal};
+pub use terminal::{setup_terminal, install_panic_hook, shutdown_on_signal, TerminalGuard};
 pub use charts::update_chart_datasets;
 
 /// Main UI rendering function that delegates to specific mode renderers
@@ -53,11 +57,46 @@ pub fn render_ui(app: &mut App, terminal: &mut Terminal<CrosstermBackend<io::Std
                 } else {
                     renderers::normal::render(f, app);
                 }
+                if app.show_sort_menu {
+                    renderers::normal::render_sort_menu(f, app);
+                }
+                if app.show_mute_menu {
+                    renderers::normal::render_mute_menu(f, app);
+                }
+                if app.show_baseline_diff {
+                    renderers::normal::render_baseline_diff(f, app);
+                }
             }
             AppMode::EditingAlert => renderers::alert::render(f, app),
             AppMode::SystemOverview => renderers::overview::render(f, app),
             AppMode::Settings => renderers::settings::render(f, app),
             AppMode::PacketDetails => renderers::packet_details::render(f, app),
+            AppMode::Interfaces => renderers::interfaces::render(f, app),
+            AppMode::LocalTraffic => renderers::local_traffic::render(f, app),
+            AppMode::Services => renderers::services::render(f, app),
+            AppMode::Events => renderers::events::render(f, app),
+        }
+        renderers::capture_banner::render(f, app);
+        if app.jump_input.is_some() {
+            renderers::jump::render(f, app);
+        }
+        if app.annotation_input.is_some() {
+            renderers::annotation::render(f, app);
+        }
+        if app.limit_input.is_some() {
+            renderers::limit::render(f, app);
+        }
+        if app.port_input.is_some() {
+            renderers::port::render(f, app);
+        }
+        if app.interface_picker.is_some() {
+            renderers::interface_picker::render(f, app);
+        }
+        if app.show_help {
+            renderers::help::render(f, app);
+        }
+        if app.show_debug_overlay {
+            renderers::debug_overlay::render(f, app);
         }
     })?;
     Ok(())
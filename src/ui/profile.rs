@@ -0,0 +1,112 @@
+// Numbers behind the debug overlay (F12 or `--debug-ui`): how long frames take
+// to draw, how far the UI trails the capture thread and how much the history
+// buffers hold. Frames are kept for the last FRAME_WINDOW draws, enough to
+// tell a steady 30 ms from the odd 300 ms stall.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const FRAME_WINDOW: usize = 120;
+
+#[derive(Default)]
+pub struct RenderProfile {
+    frames: VecDeque<(Instant, Duration)>, // When each recent frame started and how long it took to draw
+    pub backlog: usize,                    // Updates waiting in the channel at the last tick
+    pub backlog_peak: usize,
+    pub apply_time: Duration,              // Time the last tick spent folding updates into the stats
+    pub capture_lag: Option<Duration>,     // Age of the newest packet when its update was applied
+}
+
+impl RenderProfile {
+    pub fn record_frame(&mut self, started: Instant, took: Duration) {
+        if self.frames.len() == FRAME_WINDOW {
+            self.frames.pop_front();
+        }
+        self.frames.push_back((started, took));
+    }
+
+    pub fn record_tick(&mut self, backlog: usize, apply_time: Duration, capture_lag: Option<Duration>) {
+        self.backlog = backlog;
+        self.backlog_peak = self.backlog_peak.max(backlog);
+        self.apply_time = apply_time;
+        if capture_lag.is_some() {
+            self.capture_lag = capture_lag;
+        }
+    }
+
+    pub fn last_frame(&self) -> Option<Duration> {
+        self.frames.back().map(|(_, took)| *took)
+    }
+
+    pub fn average_frame(&self) -> Option<Duration> {
+        let count = u32::try_from(self.frames.len()).ok().filter(|count| *count > 0)?;
+        Some(self.frames.iter().map(|(_, took)| *took).sum::<Duration>() / count)
+    }
+
+    pub fn max_frame(&self) -> Option<Duration> {
+        self.frames.iter().map(|(_, took)| *took).max()
+    }
+
+    /// Draw time 95% of recent frames stayed under
+    pub fn p95_frame(&self) -> Option<Duration> {
+        let mut times: Vec<Duration> = self.frames.iter().map(|(_, took)| *took).collect();
+        times.sort();
+        let index = (times.len() * 95).div_ceil(100).checked_sub(1)?;
+        times.get(index).copied()
+    }
+
+    /// Frames drawn per second over the recent window
+    pub fn fps(&self) -> Option<f64> {
+        let (first, _) = self.frames.front()?;
+        let (last, _) = self.frames.back()?;
+        let span = last.duration_since(*first).as_secs_f64();
+        (span > 0.0).then(|| (self.frames.len() - 1) as f64 / span)
+    }
+}
+
+/// Estimated bytes held by each kind of history; only the per-process ones count
+/// against `--memory-budget`, since only those can be evicted
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct MemoryBreakdown {
+    pub charts: usize,
+    pub packets: usize,
+    pub snapshots: usize, // Playback
+    pub system: usize,    // System-wide bandwidth history
+}
+
+impl MemoryBreakdown {
+    pub fn total(&self) -> usize {
+        self.charts + self.packets + self.snapshots + self.system
+    }
+
+    /// Chart and packet histories, which eviction can free
+    pub fn evictable(&self) -> usize {
+        self.charts + self.packets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarises_recent_frames() {
+        let mut profile = RenderProfile::default();
+        assert_eq!((profile.average_frame(), profile.p95_frame(), profile.fps()), (None, None, None));
+        let start = Instant::now();
+        for i in 0..200u64 {
+            let took = Duration::from_millis(if i % 20 == 0 { 100 } else { 10 });
+            profile.record_frame(start + Duration::from_millis(i * 100), took);
+        }
+        // Only the last FRAME_WINDOW frames count: 6 slow ones among 120
+        assert_eq!(profile.max_frame(), Some(Duration::from_millis(100)));
+        assert_eq!(profile.p95_frame(), Some(Duration::from_millis(10)));
+        assert_eq!(profile.average_frame(), Some(Duration::from_micros(14_500)));
+        assert!((profile.fps().unwrap() - 10.0).abs() < 1e-9);
+
+        profile.record_tick(40, Duration::from_millis(2), Some(Duration::from_millis(300)));
+        profile.record_tick(3, Duration::from_millis(1), None);
+        assert_eq!((profile.backlog, profile.backlog_peak), (3, 40));
+        assert_eq!(profile.capture_lag, Some(Duration::from_millis(300)));
+    }
+}
@@ -13,22 +13,53 @@ pub fn render(f: &mut Frame, app: &App) {
         Constraint::Length(3), // Title
         Constraint::Length(3), // Threshold Input
         Constraint::Length(3), // Command Input
+        Constraint::Length(3), // Cooldown, re-arm, trigger and notification limits
         Constraint::Min(0),    // Actions
     ])
     .margin(2)
     .split(f.area());
+    let limit_chunks = Layout::horizontal([Constraint::Ratio(1, 4); 4]).split(chunks[3]);
 
     render_title(f, app, chunks[0]);
     render_threshold_input(f, app, chunks[1]);
     render_command_input(f, app, chunks[2]);
-    render_cursor(f, app, &chunks);
-    render_actions(f, app, chunks[3]);
+    render_limit_inputs(f, app, &limit_chunks);
+    render_cursor(f, app, &chunks, &limit_chunks);
+    render_actions(f, app, chunks[4]);
+}
+
+/// Field title, replaced by the parse error while that field has focus
+fn field_title<'a>(app: &App, field: EditingField, title: &'a str) -> Line<'a> {
+    match &app.alert_input_error {
+        Some(error) if app.current_editing_field == field => {
+            Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red)))
+        }
+        _ => Line::from(title),
+    }
+}
+
+/// Render a single-line input field
+fn render_input(f: &mut Frame, text: &str, title: Line, area: ratatui::layout::Rect) {
+    let input = Paragraph::new(text)
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+        );
+    f.render_widget(input, area);
 }
 
 /// Render the title section
 fn render_title(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let title_text = if let Some(pid) = app.selected_process {
-        format!("Editing Alert for PID: {}", pid)
+    let title_text = if !app.marked.is_empty() {
+        format!("Editing Alert for {} marked processes", app.marked_pids().len())
+    } else if let Some(pid) = app.selected_process {
+        match app.stats.get(&pid) {
+            // Saved by name, so it also covers the process's namesakes and outlives its PID
+            Some(info) => format!("Editing Alert for PID: {} (saved for every {} process)", pid, info.name),
+            None => format!("Editing Alert for PID: {}", pid),
+        }
     } else {
         "Editing Alert".to_string()
     };
@@ -39,44 +70,49 @@ fn render_title(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
 
 /// Render the threshold input field
 fn render_threshold_input(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let threshold_input = Paragraph::new(app.alert_input.as_str())
-        .style(Style::default().fg(Color::Yellow))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Threshold (e.g., 10MB, 2GB)")
-        );
-    f.render_widget(threshold_input, area);
+    let title = field_title(
+        app,
+        EditingField::Threshold,
+        "Threshold (e.g., 10MB, 1,5 GB, 100 Mbit, 100 conn/s for new connections, or 'anomaly')",
+    );
+    render_input(f, &app.alert_input, title, area);
 }
 
 /// Render the command input field
 fn render_command_input(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let command_input = Paragraph::new(app.command_input.as_str())
-        .style(Style::default().fg(Color::Yellow))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Command (leave empty to kill process)")
-        );
-    f.render_widget(command_input, area);
+    let title = if app.selected_alert_action == 3 {
+        "Capture duration in seconds (default 30)"
+    } else {
+        "Command (leave empty to kill process)"
+    };
+    render_input(f, &app.command_input, Line::from(title), area);
+}
+
+/// Render the cooldown, re-arm, trigger limit and notification limit fields side by side
+fn render_limit_inputs(f: &mut Frame, app: &App, areas: &[ratatui::layout::Rect]) {
+    render_input(f, &app.cooldown_input, field_title(app, EditingField::Cooldown, "Cooldown s (default 60)"), areas[0]);
+    render_input(f, &app.rearm_input, field_title(app, EditingField::Rearm, "Re-arm below % (empty: off)"), areas[1]);
+    render_input(f, &app.max_triggers_input, field_title(app, EditingField::MaxTriggers, "Max triggers (empty: no limit)"), areas[2]);
+    render_input(f, &app.notify_every_input, field_title(app, EditingField::NotifyEvery, "Notify every s (empty: always)"), areas[3]);
 }
 
 /// Set cursor position based on the currently editing field
-fn render_cursor(f: &mut Frame, app: &App, chunks: &[ratatui::layout::Rect]) {
-    match app.current_editing_field {
-        EditingField::Threshold => {
-            f.set_cursor_position((chunks[1].x + app.alert_input.len() as u16 + 1, chunks[1].y + 1));
-        }
-        EditingField::Command => {
-            f.set_cursor_position((chunks[2].x + app.command_input.len() as u16 + 1, chunks[2].y + 1));
-        }
-    }
+fn render_cursor(f: &mut Frame, app: &App, chunks: &[ratatui::layout::Rect], limit_chunks: &[ratatui::layout::Rect]) {
+    let (area, text) = match app.current_editing_field {
+        EditingField::Threshold => (chunks[1], &app.alert_input),
+        EditingField::Command => (chunks[2], &app.command_input),
+        EditingField::Cooldown => (limit_chunks[0], &app.cooldown_input),
+        EditingField::Rearm => (limit_chunks[1], &app.rearm_input),
+        EditingField::MaxTriggers => (limit_chunks[2], &app.max_triggers_input),
+        EditingField::NotifyEvery => (limit_chunks[3], &app.notify_every_input),
+    };
+    f.set_cursor_position((area.x + text.len() as u16 + 1, area.y + 1));
 }
 
 /// Render the action selection section
 fn render_actions(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let actions = ["Kill Process", "Custom Command", "Alert"];
-    let action_lines: Vec<Line> = actions
+    let actions = ["Kill Process", "Custom Command", "Alert", "Capture Pcap", "Block Network"];
+    let mut action_lines: Vec<Line> = actions
         .iter()
         .enumerate()
         .map(|(i, action)| {
@@ -91,7 +127,17 @@ fn render_actions(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         })
         .collect();
 
+    action_lines.push(Line::from(""));
+    action_lines.push(if app.dry_run_input || app.dry_run {
+        Line::from(Span::styled(
+            if app.dry_run_input { "Dry run: on, kills and commands are only logged" } else { "Dry run: on for every alert (--dry-run)" },
+            Style::default().fg(Color::Yellow),
+        ))
+    } else {
+        Line::from("Dry run: off")
+    });
+
     let actions_widget = Paragraph::new(Text::from(action_lines))
-        .block(Block::default().borders(Borders::ALL).title("Action (use Tab to switch fields)"));
+        .block(Block::default().borders(Borders::ALL).title("Action (Tab to switch fields, ←/→ for dry run)"));
     f.render_widget(actions_widget, area);
 } 
\ No newline at end of file
@@ -0,0 +1,37 @@
+use ratatui::{
+    widgets::{Block, Borders, Clear, Paragraph},
+    style::{Style, Color},
+    text::{Line, Span, Text},
+    Frame
+};
+use crate::types::App;
+
+/// Render the annotation prompt on top of whatever view is active
+pub fn render(f: &mut Frame, app: &App) {
+    let Some(input) = app.annotation_input.as_deref() else { return };
+    let area = crate::ui::utils::centered_rect(50, 20, f.area());
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("📌 ", Style::default().fg(Color::Yellow)),
+            Span::raw(input),
+            Span::styled("_", Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Marks this moment on the bandwidth charts and in the event log",
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(Span::styled("Enter: add | Esc: cancel", Style::default().fg(Color::Gray))),
+    ];
+
+    let prompt = Paragraph::new(Text::from(lines))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Annotate")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
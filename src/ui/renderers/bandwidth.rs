@@ -2,10 +2,12 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Table, Row, Cell, TableState},
     layout::{Layout, Constraint},
     style::{Style, Color, Modifier},
+    text::{Line, Span},
     Frame
 };
 use crate::types::{App, SortColumn, SortDirection, ChartType};
-use crate::ui::{utils::format_bytes, charts::render_charts};
+use crate::ui::charts::render_charts;
+use crate::units::{format_bytes, format_rate};
 
 /// Render the bandwidth mode view with responsive chart display
 pub fn render(f: &mut Frame, app: &App) {
@@ -70,9 +72,9 @@ fn render_title(f: &mut Frame, area: ratatui::layout::Rect, terminal_height: u16
     } else {
         // Full navigation text
         if app.chart_type == ChartType::SystemStacked {
-            "q: quit | Tab: switch mode | t: chart type | m: traffic | Enter: actions"
+            "q: quit | Tab: switch mode | t: chart type | m: traffic | Enter: actions | ?: help"
         } else {
-            "q: quit | Tab: switch mode | t: chart type | ↑/↓: select | Enter: actions"
+            "q: quit | Tab: switch mode | t: chart type | ↑/↓: select | Enter: actions | ?: help"
         }
     };
     
@@ -81,6 +83,15 @@ fn render_title(f: &mut Frame, area: ratatui::layout::Rect, terminal_height: u16
     } else {
         "Monitetoring – Bandwidth View"
     };
+    let title_text = match app.playback_label() {
+        Some(label) => format!("{} – {}", title_text, label),
+        None => title_text.to_string(),
+    };
+    let title_text = if app.estimated {
+        format!("{} – estimated", title_text)
+    } else {
+        title_text
+    };
     
     let title = Paragraph::new(navigation_text)
         .block(Block::default().title(title_text).borders(Borders::ALL));
@@ -114,7 +125,7 @@ fn render_top5_processes_table(f: &mut Frame, app: &App, area: ratatui::layout::
     // Get top 5 processes from chart datasets and their current stats
     let rows: Vec<Row> = app.chart_datasets.iter().take(5).map(|(name, _, color)| {
         // Find the process stats by name
-        let (sent_rate, received_rate) = app.stats.iter()
+        let (sent_rate, received_rate) = app.visible_stats().iter()
             .find(|(_, info)| info.name == *name)
             .map(|(_, info)| (info.sent_rate, info.received_rate))
             .unwrap_or((0, 0));
@@ -133,16 +144,16 @@ fn render_top5_processes_table(f: &mut Frame, app: &App, area: ratatui::layout::
             vec![
                 Cell::from("●").style(Style::default().fg(*color).add_modifier(Modifier::BOLD)),
                 Cell::from(display_name).style(Style::default().fg(*color)),
-                Cell::from(format!("{}/s", format_bytes(sent_rate))),
-                Cell::from(format!("{}/s", format_bytes(received_rate))),
+                Cell::from(format_rate(sent_rate)),
+                Cell::from(format_rate(received_rate)),
             ]
         } else {
             vec![
                 Cell::from("●").style(Style::default().fg(*color).add_modifier(Modifier::BOLD)),
                 Cell::from(display_name).style(Style::default().fg(*color)),
-                Cell::from(format!("{}/s", format_bytes(sent_rate))),
-                Cell::from(format!("{}/s", format_bytes(received_rate))),
-                Cell::from(format!("{}/s", format_bytes(total_rate))),
+                Cell::from(format_rate(sent_rate)),
+                Cell::from(format_rate(received_rate)),
+                Cell::from(format_rate(total_rate)),
             ]
         };
         
@@ -228,16 +239,16 @@ fn render_compact_process_table(f: &mut Frame, app: &App, area: ratatui::layout:
             vec![
                 Cell::from(pid.to_string()),
                 Cell::from(display_name),
-                Cell::from(format!("{}/s", format_bytes(data.sent_rate))),
-                Cell::from(format!("{}/s", format_bytes(data.received_rate))),
+                Cell::from(format_rate(data.sent_rate)),
+                Cell::from(format_rate(data.received_rate)),
                 Cell::from(truncate_string(data.container_name.as_ref().unwrap_or(&"host".to_string()), 8)),
             ]
         } else {
             vec![
                 Cell::from(pid.to_string()),
                 Cell::from(display_name),
-                Cell::from(format!("{}/s", format_bytes(data.sent_rate))),
-                Cell::from(format!("{}/s", format_bytes(data.received_rate))),
+                Cell::from(format_rate(data.sent_rate)),
+                Cell::from(format_rate(data.received_rate)),
             ]
         };
         Row::new(cells).style(style)
@@ -288,6 +299,30 @@ fn truncate_string(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Signal and retries of the Wi-Fi link being captured on (every associated one for `any`)
+fn wireless_spans(app: &App) -> Vec<Span<'static>> {
+    let captured = |name: &str| matches!(app.capture_iface.as_deref(), Some("any") | None) || app.capture_iface.as_deref() == Some(name);
+    let mut spans = Vec::new();
+    for iface in app.interfaces.iter().filter(|iface| captured(&iface.name)) {
+        let Some(link) = iface.wireless.as_ref().filter(|link| link.ssid.is_some()) else {
+            continue;
+        };
+        spans.push(Span::raw(format!(" | 📶 {} ", iface.name)));
+        if let Some(dbm) = link.signal_dbm {
+            let (bars, _, color) = super::interfaces::signal_rating(dbm);
+            spans.push(Span::styled(format!("{} {} dBm", bars.trim_end(), dbm), Style::default().fg(color)));
+        }
+        if let Some(mbps) = link.tx_bitrate_mbps {
+            spans.push(Span::raw(format!(" {:.0} Mb/s", mbps)));
+        }
+        if let Some(percent) = link.retry_percent {
+            let color = if percent >= super::interfaces::HIGH_RETRY_PERCENT { Color::Yellow } else { Color::Reset };
+            spans.push(Span::styled(format!(" {:.0}% retries", percent), Style::default().fg(color)));
+        }
+    }
+    spans
+}
+
 /// Render the totals bar (responsive)
 fn render_totals_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let (total_sent, total_received, total_sent_rate, total_received_rate) = app.totals();
@@ -295,24 +330,28 @@ fn render_totals_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let totals_text = if area.width < 80 {
         // Compact format for narrow terminals
         format!(
-            "📊 ↑{}/s ({}) ↓{}/s ({})",
-            format_bytes(total_sent_rate),
+            "📊 ↑{} ({}) ↓{} ({})",
+            format_rate(total_sent_rate),
             format_bytes(total_sent),
-            format_bytes(total_received_rate),
+            format_rate(total_received_rate),
             format_bytes(total_received)
         )
     } else {
         // Full format for wide terminals
         format!(
-            "📊 TOTALS: Sent {}/s ({} total) | Received {}/s ({} total)",
-            format_bytes(total_sent_rate),
+            "📊 TOTALS: Sent {} ({} total) | Received {} ({} total)",
+            format_rate(total_sent_rate),
             format_bytes(total_sent),
-            format_bytes(total_received_rate),
+            format_rate(total_received_rate),
             format_bytes(total_received)
         )
     };
     
-    let totals = Paragraph::new(totals_text)
+    let mut spans = vec![Span::raw(totals_text)];
+    if area.width >= 80 {
+        spans.extend(wireless_spans(app));
+    }
+    let totals = Paragraph::new(Line::from(spans))
         .block(Block::default().borders(Borders::ALL).title("Network Totals"));
     f.render_widget(totals, area);
 } 
\ No newline at end of file
@@ -0,0 +1,37 @@
+use std::time::Instant;
+use ratatui::{
+    layout::Rect,
+    widgets::{Block, Borders, Paragraph},
+    style::{Style, Color, Modifier},
+    Frame
+};
+use crate::types::App;
+
+/// Warn across the top of every view while the capture is down, so the
+/// frozen numbers underneath aren't mistaken for live ones
+pub fn render(f: &mut Frame, app: &App) {
+    let Some(loss) = &app.capture_loss else { return };
+    let area = f.area();
+    if area.height < 3 {
+        return;
+    }
+    let area = Rect { height: 3, ..area };
+
+    let next = loss.retry_at.saturating_duration_since(Instant::now());
+    let mut text = format!(
+        "⚠️ Capture on {} lost: {} | reconnecting (attempt {}, next in {})",
+        loss.iface,
+        loss.error,
+        loss.attempt,
+        crate::ui::utils::format_countdown(next)
+    );
+    if app.capture_control.is_some() {
+        text.push_str(" | I: pick another interface");
+    }
+
+    let banner = Paragraph::new(text)
+        .style(Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD))
+        .block(Block::default().borders(Borders::ALL).title("Capture Lost"));
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(banner, area);
+}
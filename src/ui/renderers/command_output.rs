@@ -0,0 +1,48 @@
+use ratatui::{
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    style::{Style, Color, Modifier},
+    text::{Line, Span},
+    Frame
+};
+use crate::action_queue::OUTPUT_LIMIT;
+use crate::types::App;
+use crate::units::format_bytes;
+
+/// Render a custom command's stdout and stderr over the events view
+pub fn render(f: &mut Frame, app: &App) {
+    let Some(view) = app.event_output.as_ref() else { return };
+    let area = crate::ui::utils::centered_rect(80, 80, f.area());
+    let dim = Style::default().fg(Color::Gray);
+
+    let mut lines = vec![Line::from(view.message.as_str()), Line::from("")];
+    let streams = [("stdout", &view.output.stdout, Color::Cyan), ("stderr", &view.output.stderr, Color::Red)];
+    for (name, text, color) in streams {
+        if text.is_empty() {
+            continue;
+        }
+        lines.push(Line::from(Span::styled(name, Style::default().fg(color).add_modifier(Modifier::BOLD))));
+        lines.extend(text.lines().map(Line::from));
+        lines.push(Line::from(""));
+    }
+    if view.output.is_empty() {
+        lines.push(Line::from(Span::styled("The command printed nothing", dim)));
+    }
+    if view.output.truncated {
+        lines.push(Line::from(Span::styled(
+            format!("… only the first {} of each stream were kept", format_bytes(OUTPUT_LIMIT as u64)),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+
+    let output = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((view.scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(view.title.as_str())
+                .title_bottom(Line::from(Span::styled(" ↑/↓ PgUp/PgDn: scroll | Esc/Enter: close ", dim)))
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(output, area);
+}
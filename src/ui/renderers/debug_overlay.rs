@@ -0,0 +1,57 @@
+use std::time::Duration;
+use ratatui::{
+    layout::Rect,
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
+    style::{Style, Color},
+    Frame
+};
+use crate::types::App;
+use crate::units::format_bytes;
+
+fn millis(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => format!("{:.1} ms", duration.as_secs_f64() * 1000.0),
+        None => "-".to_string(),
+    }
+}
+
+/// Frame timings and buffer sizes in the top-right corner, over whatever view is open
+pub fn render(f: &mut Frame, app: &App) {
+    let profile = &app.render_profile;
+    let memory = app.memory_breakdown();
+
+    let field = |label: &'static str, value: String| Line::from(vec![
+        Span::styled(format!(" {:<11}", label), Style::default().fg(Color::Cyan)),
+        Span::raw(value),
+    ]);
+    let lines = vec![
+        field("Frame", format!("{} (avg {})", millis(profile.last_frame()), millis(profile.average_frame()))),
+        field("p95 / max", format!("{} / {}", millis(profile.p95_frame()), millis(profile.max_frame()))),
+        field("FPS", profile.fps().map_or("-".to_string(), |fps| format!("{:.1}", fps))),
+        field("Backlog", format!("{} updates (peak {})", profile.backlog, profile.backlog_peak)),
+        field("Apply", millis(Some(profile.apply_time))),
+        field("Capture lag", millis(profile.capture_lag)),
+        field("Charts", format_bytes(memory.charts as u64)),
+        field("Packets", format_bytes(memory.packets as u64)),
+        field("Playback", format_bytes(memory.snapshots as u64)),
+        field("System", format_bytes(memory.system as u64)),
+        field("Total", format_bytes(memory.total() as u64)),
+        field("Budget", format!("{} of {}", format_bytes(memory.evictable() as u64), format_bytes(app.memory_budget))),
+    ];
+
+    let screen = f.area();
+    let width = 40.min(screen.width);
+    let height = (lines.len() as u16 + 2).min(screen.height);
+    let area = Rect { x: screen.x + screen.width - width, y: screen.y, width, height };
+
+    let overlay = Paragraph::new(Text::from(lines))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Debug (F12)")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(overlay, area);
+}
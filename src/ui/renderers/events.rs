@@ -0,0 +1,100 @@
+use ratatui::{
+    widgets::{Block, Borders, Paragraph, Table, Row, Cell, Wrap},
+    layout::{Layout, Constraint},
+    style::{Style, Color, Modifier},
+    text::{Line, Span},
+    Frame
+};
+use crate::events::EventKind;
+use crate::types::{App, NotificationState};
+
+/// Render the session event log, newest first
+pub fn render(f: &mut Frame, app: &App) {
+    let main_chunks = Layout::vertical([
+        Constraint::Length(3),  // Title (header with navigation)
+        Constraint::Min(0),     // Event table
+        Constraint::Length(3),  // Export status
+    ])
+    .margin(1)
+    .split(f.area());
+
+    render_title(f, main_chunks[0]);
+    render_event_table(f, app, main_chunks[1]);
+    render_export_status(f, app, main_chunks[2]);
+    crate::ui::renderers::command_output::render(f, app);
+}
+
+/// Render the title header with navigation inside
+fn render_title(f: &mut Frame, area: ratatui::layout::Rect) {
+    let block = Block::default().title("Event Log").borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let nav_text = "q: quit | Tab: switch mode | ↑/↓ PgUp/PgDn: scroll | Home: newest | Enter: command output | e: export CSV | Esc: back to main | ?: help";
+    f.render_widget(Paragraph::new(nav_text), inner);
+}
+
+fn kind_color(kind: EventKind) -> Color {
+    match kind {
+        EventKind::Alert => Color::Yellow,
+        EventKind::Kill => Color::Red,
+        EventKind::Command => Color::Cyan,
+        EventKind::Capture => Color::Blue,
+        EventKind::Quota => Color::LightRed,
+        EventKind::Anomaly => Color::LightMagenta,
+        EventKind::Mute => Color::DarkGray,
+        EventKind::Annotation => Color::LightYellow,
+        EventKind::Restart => Color::LightCyan,
+        EventKind::System => Color::Gray,
+    }
+}
+
+/// Render the table of logged events
+fn render_event_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let block = Block::default()
+        .title(format!("Events this session ({})", app.events.len()))
+        .borders(Borders::ALL);
+    if app.events.is_empty() {
+        let text = Paragraph::new("No events yet. Alert triggers, kills, custom commands, captures and quota breaches are listed here.")
+            .wrap(Wrap { trim: true })
+            .block(block);
+        f.render_widget(text, area);
+        return;
+    }
+
+    let header = Row::new(["Time", "Type", "PID", "Message"].map(|h| {
+        Cell::from(h).style(Style::default().fg(Color::Red))
+    }));
+    // The top row is the selected one, whose command output Enter opens
+    let rows = app.events.iter().skip(app.event_scroll_offset).enumerate().map(|(i, event)| {
+        let mut message = vec![Span::raw(event.message.clone())];
+        if event.output.is_some() {
+            message.push(Span::styled("  ⏎ output", Style::default().fg(Color::DarkGray)));
+        }
+        let row = Row::new(vec![
+            Cell::from(event.time.format("%H:%M:%S").to_string()),
+            Cell::from(event.kind.label()).style(Style::default().fg(kind_color(event.kind))),
+            Cell::from(event.pid.map(|pid| pid.to_string()).unwrap_or_default()),
+            Cell::from(Line::from(message)),
+        ]);
+        if i == 0 { row.style(Style::default().add_modifier(Modifier::REVERSED)) } else { row }
+    });
+    let widths = [
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Min(20),
+    ];
+    let table = Table::new(rows, widths).header(header).block(block);
+    f.render_widget(table, area);
+}
+
+/// Render the result of the last export
+fn render_export_status(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    if let NotificationState::Active(message) = &app.export_notification_state {
+        let status = Paragraph::new(message.as_str())
+            .style(Style::default().fg(Color::Green))
+            .block(Block::default().title("Export Status").borders(Borders::ALL).border_style(Style::default().fg(Color::Green)));
+        f.render_widget(status, area);
+    }
+}
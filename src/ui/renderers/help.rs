@@ -0,0 +1,185 @@
+use ratatui::{
+    widgets::{Block, Borders, Clear, Paragraph},
+    style::{Style, Color, Modifier},
+    text::{Line, Span, Text},
+    Frame
+};
+use crate::types::{App, AppMode};
+use crate::ui::keymap::Action;
+
+type HelpSection = (&'static str, Vec<(String, &'static str)>);
+
+fn entries(items: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+    items.iter().map(|(key, text)| (key.to_string(), *text)).collect()
+}
+
+/// Keys of the view the overlay was opened from
+fn current_view_section(app: &App) -> HelpSection {
+    let keys = &app.keymap;
+    match app.mode {
+        AppMode::Normal if app.bandwidth_mode => ("Bandwidth view", entries(&[
+            ("t", "Toggle chart type (process lines / system stacked)"),
+            ("m", "Toggle metrics (combined / sent only / received only)"),
+            ("↑/↓", "Select process"),
+            ("Enter", "Actions for the selected process"),
+        ])),
+        AppMode::Normal => {
+            let mut items = vec![
+                (keys.label(Action::SortPid), "Sort by PID"),
+                (keys.label(Action::SortName), "Sort by process name"),
+                (keys.label(Action::SortUser), "Sort by user"),
+                (keys.label(Action::SortSent), "Sort by sent (rate or total)"),
+                (keys.label(Action::SortReceived), "Sort by received (rate or total)"),
+            ];
+            if app.containers_mode {
+                items.push((keys.label(Action::SortContainer), "Sort by container"));
+            }
+            if cfg!(target_os = "linux") {
+                items.push(("g".to_string(), if app.containers_mode {
+                    "Group by systemd unit / container / image / Compose project / pod / off; Enter shows a group"
+                } else {
+                    "Group by systemd unit / off; Enter shows a group"
+                }));
+                items.push(("G".to_string(), "Show every group again after Enter narrowed the table"));
+            }
+            items.extend([
+                (keys.label(Action::ReverseSort), "Flip sort direction"),
+                (keys.label(Action::SortMenu), "Sort menu (primary and secondary column)"),
+                ("l".to_string(), "Count all traffic / external only / loopback only"),
+                ("B".to_string(), "Mark a baseline of the counters"),
+                ("b".to_string(), "Show changes since the baseline"),
+                ("↑/↓".to_string(), "Select process"),
+                ("Space".to_string(), "Mark/unmark the selected row for bulk actions (Esc clears)"),
+                ("f".to_string(), "Pin/unpin the selected process's name to the top"),
+                ("z".to_string(), "Hide/show processes without recent traffic"),
+                ("Enter".to_string(), "Actions: kill (asks first), alert, packet details, mute, limit, block; on all marked rows if any"),
+                ("u".to_string(), "During a SIGTERM grace period: cancel the SIGKILL"),
+                ("M".to_string(), "Mute the alert behind the notification or selection"),
+                ("x / X".to_string(), "Dismiss the top notification / all of them"),
+                ("[ / ]".to_string(), "Scroll the notifications"),
+                ("N".to_string(), "Do not disturb: hold back all notifications but errors"),
+                ("P".to_string(), "Only list processes with traffic on a port"),
+                ("←/→".to_string(), "Scrub through the last 5 minutes of snapshots"),
+                ("< / >".to_string(), "Scroll the selected process's chart back/forward by a window (--history-store)"),
+                ("Esc".to_string(), "Back to live view or chart / previous view after a jump"),
+            ]);
+            ("Main view", items)
+        }
+        AppMode::PacketDetails => ("Packet details", vec![
+            ("↑/↓ PgUp/PgDn".to_string(), "Scroll packets"),
+            ("1-8".to_string(), "Sort by column (again to flip)"),
+            ("t / u / i".to_string(), "Filter TCP/UDP cycle, UDP, ICMP"),
+            ("r / s".to_string(), "Filter by direction"),
+            ("P".to_string(), "Filter by port, source or destination"),
+            (keys.label(Action::Search), "Search by address or port"),
+            ("c".to_string(), "Clear filters"),
+            (keys.label(Action::Export), "Export packets (or first contacts) to CSV"),
+            ("h".to_string(), "Toggle first-contact audit trail"),
+            ("T".to_string(), "Congestion control info for the top TCP row (Linux)"),
+            ("C".to_string(), "Connections of the process with queue sizes, RTT and retransmits"),
+            ("q / Esc".to_string(), "Back to main view"),
+        ]),
+        AppMode::SystemOverview => ("Overview", entries(&[
+            ("+ / -", "Adjust data quota by 100 MB"),
+            ("r", "Reset quota exceeded state"),
+            ("D", "Debug stats: cost of the last process map refresh"),
+            ("Esc", "Back to main view"),
+        ])),
+        AppMode::Settings => ("Settings", entries(&[
+            ("↑/↓", "Choose setting"),
+            ("←/→", "Adjust setting, saved to the config"),
+            ("Enter", "Pick the interface from a list"),
+            ("r", "Remove saved configuration"),
+            ("Esc", "Back to main view"),
+        ])),
+        AppMode::Interfaces => ("Interfaces", entries(&[
+            ("↑/↓", "Scroll interfaces"),
+            ("Esc", "Back to main view"),
+        ])),
+        AppMode::LocalTraffic => ("Local traffic", entries(&[
+            ("↑/↓", "Scroll flows"),
+            ("Esc", "Back to main view"),
+        ])),
+        AppMode::Services => ("Services", entries(&[
+            ("↑/↓", "Scroll services"),
+            ("Esc", "Back to main view"),
+        ])),
+        AppMode::Events => ("Events", entries(&[
+            ("↑/↓ PgUp/PgDn", "Scroll events"),
+            ("Home", "Back to the newest event"),
+            ("Enter", "Show what the custom command of the top event printed"),
+            ("e", "Export the event log to CSV"),
+            ("Esc", "Back to main view"),
+        ])),
+        AppMode::EditingAlert => ("Alert editor", entries(&[
+            ("↑/↓", "Choose action"),
+            ("←/→", "Toggle dry run (kills and commands are only logged)"),
+            ("Tab", "Next field (threshold, command, cooldown, re-arm, max triggers, notify every)"),
+            ("Enter", "Save alert"),
+            ("Esc", "Cancel"),
+        ])),
+    }
+}
+
+/// Every help line for the current view, followed by the global keys and modes
+fn help_lines(app: &App) -> Vec<Line<'static>> {
+    let keys = &app.keymap;
+    let sections = [
+        current_view_section(app),
+        ("Everywhere", vec![
+            (keys.label(Action::Quit), "Quit"),
+            (keys.label(Action::NextMode), "Next view"),
+            (":".to_string(), "Jump to a process by PID or name"),
+            ("a".to_string(), "Annotate this moment on the charts"),
+            ("?".to_string(), "Toggle this help"),
+            ("I".to_string(), "Capture on another interface"),
+            ("F12".to_string(), "Toggle the debug overlay (frame times, backlog, memory)"),
+        ]),
+        ("Views (cycled with Tab)", entries(&[
+            ("Main", "Process table with sorting, alerts and actions"),
+            ("Bandwidth", "Per-process or stacked bandwidth charts"),
+            ("Overview", "Quota, protocol breakdown and capture statistics"),
+            ("Interfaces", "Per-NIC counters next to process totals"),
+            ("Local", "Traffic between local processes"),
+            ("Services", "Inbound traffic per listening port"),
+            ("Events", "Log of alerts, kills, commands and quota breaches"),
+            ("Settings", "Edit every saved setting"),
+        ])),
+    ];
+
+    let mut lines = Vec::new();
+    for (title, items) in sections {
+        lines.push(Line::from(Span::styled(
+            title,
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )));
+        for (key, text) in items {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<14}", key), Style::default().fg(Color::Cyan)),
+                Span::raw(text),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+    lines
+}
+
+/// Number of lines in the help overlay, used to bound scrolling
+pub fn line_count(app: &App) -> usize {
+    help_lines(app).len()
+}
+
+/// Render the help overlay on top of the current view
+pub fn render(f: &mut Frame, app: &App) {
+    let area = crate::ui::utils::centered_rect(70, 80, f.area());
+    let help = Paragraph::new(Text::from(help_lines(app)))
+        .scroll((app.help_scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Help (↑/↓: scroll | ?/Esc: close)")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(help, area);
+}
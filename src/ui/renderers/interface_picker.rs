@@ -0,0 +1,72 @@
+use ratatui::{
+    widgets::{Block, Borders, Paragraph},
+    style::{Style, Color, Modifier},
+    text::{Line, Span, Text},
+    Frame
+};
+use crate::types::App;
+use crate::units::format_bytes;
+
+/// Render the dialog for moving the capture to another interface
+pub fn render(f: &mut Frame, app: &App) {
+    let Some(picker) = &app.interface_picker else { return };
+    let area = crate::ui::utils::centered_rect(70, 60, f.area());
+    let current = app.capture_iface.as_deref();
+
+    let measuring = picker.measurement.is_some();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            if measuring {
+                format!("📊 Measuring traffic for up to {} seconds…", crate::interactive::MEASURE_DURATION_SECS)
+            } else {
+                "[traffic] = bytes observed while measuring".to_string()
+            },
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(""),
+    ];
+    if picker.choices.is_empty() {
+        lines.push(Line::from(Span::styled("No interfaces found", Style::default().fg(Color::Red))));
+    }
+    for (i, choice) in picker.choices.iter().enumerate() {
+        let status = if choice.is_up { "🟢" } else { "🔴" };
+        let traffic = match choice.traffic_bytes {
+            Some(bytes) => format!(" [{}]", format_bytes(bytes)),
+            None if measuring && choice.is_up => " […]".to_string(),
+            None => String::new(),
+        };
+        let marker = if Some(choice.name.as_str()) == current { " (current)" } else { "" };
+        let text = format!(
+            "{} {}{}{} - {}",
+            status,
+            choice.name,
+            traffic,
+            marker,
+            choice.description.as_deref().unwrap_or("No description")
+        );
+        if i == picker.selected {
+            lines.push(Line::from(Span::styled(
+                format!("> {}", text),
+                Style::default().add_modifier(Modifier::BOLD).fg(Color::Cyan),
+            )));
+        } else {
+            lines.push(Line::from(format!("  {}", text)));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Enter: capture here (counters and history are kept) | ↑/↓: choose | Esc: cancel",
+        Style::default().fg(Color::Gray),
+    )));
+
+    let dialog = Paragraph::new(Text::from(lines))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Capture Interface")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(dialog, area);
+}
@@ -0,0 +1,246 @@
+use ratatui::{
+    widgets::{Block, Borders, Paragraph, Table, Row, Cell},
+    layout::{Layout, Constraint},
+    style::{Style, Color, Modifier},
+    text::{Line, Span},
+    Frame
+};
+use crate::types::{App, InterfaceStats, WirelessLink};
+use crate::units::{format_bytes, format_rate};
+
+/// Render the interfaces view with per-NIC counters
+pub fn render(f: &mut Frame, app: &App) {
+    let wireless_count = app.interfaces.iter().filter(|iface| iface.wireless.is_some()).count();
+    let wireless_height = if wireless_count > 0 { wireless_count as u16 + 3 } else { 0 };
+    let main_chunks = Layout::vertical([
+        Constraint::Length(3),  // Title (header with navigation)
+        Constraint::Min(0),     // Interface table
+        Constraint::Length(wireless_height), // Wi-Fi link quality
+        Constraint::Length(4),  // Process totals vs NIC comparison
+    ])
+    .margin(1)
+    .split(f.area());
+
+    render_title(f, main_chunks[0]);
+    render_interface_table(f, app, main_chunks[1]);
+    if wireless_count > 0 {
+        render_wireless(f, app, main_chunks[2]);
+    }
+    render_comparison(f, app, main_chunks[3]);
+}
+
+/// Render the title header with navigation inside
+fn render_title(f: &mut Frame, area: ratatui::layout::Rect) {
+    let block = Block::default().title("Network Interfaces").borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let nav_text = "q: quit | Tab: switch mode | ↑/↓: scroll | Esc: back to main | ?: help";
+    f.render_widget(Paragraph::new(nav_text), inner);
+}
+
+fn format_speed(speed_mbps: Option<u64>) -> String {
+    match speed_mbps {
+        Some(mbps) if mbps >= 1000 && mbps.is_multiple_of(1000) => format!("{} Gb/s", mbps / 1000),
+        Some(mbps) => format!("{} Mb/s", mbps),
+        None => "-".to_string(),
+    }
+}
+
+/// Signal bars and a rating for a Wi-Fi signal level
+pub fn signal_rating(dbm: i32) -> (&'static str, &'static str, Color) {
+    match dbm {
+        -55.. => ("▂▄▆█", "excellent", Color::Green),
+        -67.. => ("▂▄▆ ", "good", Color::Green),
+        -75.. => ("▂▄  ", "fair", Color::Yellow),
+        _ => ("▂   ", "poor", Color::Red),
+    }
+}
+
+/// Retry share at which a link counts as struggling
+pub const HIGH_RETRY_PERCENT: f64 = 10.0;
+
+fn format_band(frequency_mhz: u32) -> String {
+    let band = match frequency_mhz {
+        ..3000 => "2.4 GHz",
+        3000..5925 => "5 GHz",
+        _ => "6 GHz",
+    };
+    format!("{} ({} MHz)", band, frequency_mhz)
+}
+
+fn format_bitrate(mbps: Option<f64>) -> String {
+    mbps.map_or("-".to_string(), |mbps| format!("{:.0}", mbps))
+}
+
+/// Render SSID, signal, bitrates and retries of the Wi-Fi interfaces
+fn render_wireless(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let header = Row::new(["Interface", "SSID", "Signal", "Band", "Rate rx/tx (Mb/s)", "Retries", "Failed"]
+        .iter()
+        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red))));
+
+    let rows = app.interfaces.iter().filter_map(|iface| {
+        let link: &WirelessLink = iface.wireless.as_ref()?;
+        let Some(ssid) = &link.ssid else {
+            return Some(Row::new(vec![
+                Cell::from(iface.name.clone()),
+                Cell::from("not connected"),
+            ]).style(Style::default().fg(Color::DarkGray)));
+        };
+        let signal = match link.signal_dbm {
+            Some(dbm) => {
+                let (bars, rating, color) = signal_rating(dbm);
+                Cell::from(format!("{} {} dBm {}", bars, dbm, rating)).style(Style::default().fg(color))
+            }
+            None => Cell::from("-"),
+        };
+        let retries = match link.retry_percent {
+            Some(percent) => {
+                let color = if percent >= HIGH_RETRY_PERCENT { Color::Yellow } else { Color::Reset };
+                Cell::from(format!("{:.1}%", percent)).style(Style::default().fg(color))
+            }
+            None => Cell::from("-"),
+        };
+        Some(Row::new(vec![
+            Cell::from(iface.name.clone()),
+            Cell::from(ssid.clone()),
+            signal,
+            Cell::from(link.frequency_mhz.map_or("-".to_string(), format_band)),
+            Cell::from(format!("{} / {}", format_bitrate(link.rx_bitrate_mbps), format_bitrate(link.tx_bitrate_mbps))),
+            retries,
+            Cell::from(link.tx_failed.map_or("-".to_string(), |failed| failed.to_string())),
+        ]))
+    });
+
+    let widths = [
+        Constraint::Percentage(12),
+        Constraint::Percentage(18),
+        Constraint::Percentage(22),
+        Constraint::Percentage(16),
+        Constraint::Percentage(14),
+        Constraint::Percentage(9),
+        Constraint::Percentage(9),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().title("Wi-Fi Link Quality").borders(Borders::ALL));
+    f.render_widget(table, area);
+}
+
+/// Render the table of interfaces and their counters
+fn render_interface_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let header_cells = ["Interface", "State", "Speed", "Rx/s", "Tx/s", "Rx Total", "Tx Total", "Drops (rx/tx)", "Errors (rx/tx)"]
+        .iter()
+        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
+    let header = Row::new(header_cells);
+
+    let rows = app.interfaces.iter().skip(app.interface_scroll_offset).map(|iface| {
+        let mut style = Style::default();
+        if !iface.is_up {
+            style = style.fg(Color::DarkGray);
+        }
+        if app.capture_iface.as_deref() == Some(iface.name.as_str()) {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+
+        let drops = iface.rx_dropped + iface.tx_dropped;
+        let drop_style = if drops > 0 { Style::default().fg(Color::Yellow) } else { Style::default() };
+        let errors = iface.rx_errors + iface.tx_errors;
+        let error_style = if errors > 0 { Style::default().fg(Color::Red) } else { Style::default() };
+
+        let name = if crate::tunnel::is_tunnel_interface(&iface.name) {
+            format!("{} (tunnel)", iface.name)
+        } else {
+            iface.name.clone()
+        };
+        Row::new(vec![
+            Cell::from(name),
+            Cell::from(if iface.is_up { "up" } else { "down" }),
+            Cell::from(format_speed(iface.speed_mbps)),
+            Cell::from(format_rate(iface.rx_rate)),
+            Cell::from(format_rate(iface.tx_rate)),
+            Cell::from(format_bytes(iface.rx_bytes)),
+            Cell::from(format_bytes(iface.tx_bytes)),
+            Cell::from(format!("{}/{}", iface.rx_dropped, iface.tx_dropped)).style(drop_style),
+            Cell::from(format!("{}/{}", iface.rx_errors, iface.tx_errors)).style(error_style),
+        ])
+        .style(style)
+    });
+
+    let widths = [
+        Constraint::Percentage(16),
+        Constraint::Percentage(7),
+        Constraint::Percentage(9),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+        Constraint::Percentage(11),
+        Constraint::Percentage(12),
+        Constraint::Percentage(12),
+    ];
+
+    let title = match &app.capture_iface {
+        Some(name) => format!("Interfaces (capturing on {})", name),
+        None => "Interfaces".to_string(),
+    };
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(table, area);
+}
+
+/// Interfaces whose counters should match what pcap sees. With `any`, tunnels
+/// are left out: what they carry also crosses a physical link, encrypted
+fn captured_interfaces(app: &App) -> Vec<&InterfaceStats> {
+    match app.capture_iface.as_deref() {
+        Some("any") | None => app.interfaces.iter()
+            .filter(|iface| iface.is_up && !crate::tunnel::is_tunnel_interface(&iface.name))
+            .collect(),
+        Some(name) => app.interfaces.iter().filter(|iface| iface.name == name).collect(),
+    }
+}
+
+/// Render the per-process totals next to what the NIC reports
+fn render_comparison(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let (_, _, process_sent_rate, process_received_rate) = app.totals();
+    let nics = captured_interfaces(app);
+    let nic_tx_rate: u64 = nics.iter().map(|iface| iface.tx_rate).sum();
+    let nic_rx_rate: u64 = nics.iter().map(|iface| iface.rx_rate).sum();
+
+    let coverage = |process: u64, nic: u64| {
+        if nic == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.0}%", process as f64 / nic as f64 * 100.0)
+        }
+    };
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Processes: ", Style::default().fg(Color::Gray)),
+            Span::raw(format!(
+                "↑ {}  ↓ {}",
+                format_rate(process_sent_rate),
+                format_rate(process_received_rate)
+            )),
+            Span::styled("   NIC: ", Style::default().fg(Color::Gray)),
+            Span::raw(format!("↑ {}  ↓ {}", format_rate(nic_tx_rate), format_rate(nic_rx_rate))),
+        ]),
+        Line::from(vec![
+            Span::styled("Attributed: ", Style::default().fg(Color::Gray)),
+            Span::raw(format!(
+                "↑ {}  ↓ {}",
+                coverage(process_sent_rate, nic_tx_rate),
+                coverage(process_received_rate, nic_rx_rate)
+            )),
+            Span::styled(
+                "   (unattributed traffic and link-layer overhead explain small gaps)",
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().title("Process Totals vs NIC Counters").borders(Borders::ALL));
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,64 @@
+use ratatui::{
+    widgets::{Block, Borders, Paragraph},
+    style::{Style, Color, Modifier},
+    text::{Line, Span, Text},
+    Frame
+};
+use crate::types::App;
+use crate::units::format_rate;
+
+/// Render the quick-jump prompt on top of whatever view is active
+pub fn render(f: &mut Frame, app: &App) {
+    let Some(input) = app.jump_input.as_deref() else { return };
+    let area = crate::ui::utils::centered_rect(50, 40, f.area());
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(":", Style::default().fg(Color::Yellow)),
+            Span::raw(input),
+            Span::styled("_", Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(""),
+    ];
+
+    let matches = app.jump_matches();
+    if input.trim().is_empty() {
+        lines.push(Line::from(Span::styled("Type a PID or process name prefix", Style::default().fg(Color::Gray))));
+    } else if matches.is_empty() {
+        lines.push(Line::from(Span::styled("No matching process", Style::default().fg(Color::Red))));
+    }
+    for (i, pid) in matches.iter().enumerate() {
+        let Some(info) = app.stats.get(pid) else { continue };
+        let text = format!(
+            "{:>7}  {}  ({} ↑ {} ↓)",
+            pid,
+            info.name,
+            format_rate(info.sent_rate),
+            format_rate(info.received_rate)
+        );
+        if i == app.jump_selected {
+            lines.push(Line::from(Span::styled(
+                format!("> {}", text),
+                Style::default().add_modifier(Modifier::BOLD).fg(Color::Cyan),
+            )));
+        } else {
+            lines.push(Line::from(format!("  {}", text)));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Enter: jump | ↑/↓: choose | Esc: cancel",
+        Style::default().fg(Color::Gray),
+    )));
+
+    let prompt = Paragraph::new(Text::from(lines))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Jump to Process")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(prompt, area);
+}
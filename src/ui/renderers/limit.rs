@@ -0,0 +1,42 @@
+use ratatui::{
+    widgets::{Block, Borders, Clear, Paragraph},
+    style::{Style, Color},
+    text::{Line, Span, Text},
+    Frame
+};
+use crate::types::App;
+
+/// Render the bandwidth limit prompt for the selected process
+pub fn render(f: &mut Frame, app: &App) {
+    let Some(input) = app.limit_input.as_deref() else { return };
+    let area = crate::ui::utils::centered_rect(50, 20, f.area());
+    let name = app.selected_process
+        .and_then(|pid| app.stats.get(&pid).map(|info| format!("{} (PID {})", info.name, pid)))
+        .unwrap_or_else(|| "the selected process".to_string());
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("🚦 ", Style::default().fg(Color::Yellow)),
+            Span::raw(input),
+            Span::styled("_", Style::default().fg(Color::Yellow)),
+            Span::styled(" /s", Style::default().fg(Color::Gray)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("Caps what {} sends, e.g. 500KB or 2 Mbit; it stays after exiting", name),
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(Span::styled("Enter: apply | Esc: cancel", Style::default().fg(Color::Gray))),
+    ];
+
+    let prompt = Paragraph::new(Text::from(lines))
+        .wrap(ratatui::widgets::Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Limit Bandwidth")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use ratatui::{
+    widgets::{Block, Borders, Paragraph, Table, Row, Cell},
+    layout::{Layout, Constraint},
+    style::{Style, Color, Modifier},
+    Frame
+};
+use crate::types::{App, LocalFlow};
+use crate::units::{format_bytes, format_rate};
+
+/// Most processes shown as rows/columns of the matrix
+const MAX_MATRIX_PROCESSES: usize = 8;
+
+const NAME_WIDTH: u16 = 18;
+const CELL_WIDTH: u16 = 11;
+
+/// Render the inter-process traffic view: a sender x receiver matrix plus the flow list
+pub fn render(f: &mut Frame, app: &App) {
+    let main_chunks = Layout::vertical([
+        Constraint::Length(3),       // Title (header with navigation)
+        Constraint::Percentage(55),  // Matrix
+        Constraint::Min(0),          // Flow list
+    ])
+    .margin(1)
+    .split(f.area());
+
+    render_title(f, main_chunks[0]);
+    render_matrix(f, app, main_chunks[1]);
+    render_flow_list(f, app, main_chunks[2]);
+}
+
+/// Render the title header with navigation inside
+fn render_title(f: &mut Frame, area: ratatui::layout::Rect) {
+    let block = Block::default().title("Local Traffic (loopback and local sockets)").borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let nav_text = "q: quit | Tab: switch mode | ↑/↓: scroll flows | Esc: back to main | ?: help";
+    f.render_widget(Paragraph::new(nav_text), inner);
+}
+
+fn short_name(name: &str, pid: i32, width: usize) -> String {
+    let label = format!("{} ({})", name, pid);
+    if label.chars().count() <= width {
+        label
+    } else {
+        let mut short: String = label.chars().take(width.saturating_sub(1)).collect();
+        short.push('…');
+        short
+    }
+}
+
+/// Processes with the most local traffic (sent plus received), busiest first
+fn busiest_processes(flows: &[LocalFlow], limit: usize) -> Vec<(i32, String)> {
+    let mut volume: HashMap<i32, (u64, &str)> = HashMap::new();
+    for flow in flows {
+        volume.entry(flow.from_pid).or_insert((0, &flow.from_name)).0 += flow.bytes;
+        volume.entry(flow.to_pid).or_insert((0, &flow.to_name)).0 += flow.bytes;
+    }
+    let mut processes: Vec<(i32, u64, &str)> = volume.into_iter().map(|(pid, (bytes, name))| (pid, bytes, name)).collect();
+    processes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    processes.into_iter().take(limit).map(|(pid, _, name)| (pid, name.to_string())).collect()
+}
+
+/// Cell background by volume relative to the busiest pair
+fn cell_style(bytes: u64, max: u64) -> Style {
+    if bytes == 0 || max == 0 {
+        return Style::default().fg(Color::DarkGray);
+    }
+    let share = bytes as f64 / max as f64;
+    let bg = if share >= 0.5 {
+        Color::Indexed(124)
+    } else if share >= 0.15 {
+        Color::Indexed(88)
+    } else {
+        Color::Indexed(52)
+    };
+    Style::default().bg(bg)
+}
+
+/// Render the sender (rows) x receiver (columns) byte matrix
+fn render_matrix(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let block = Block::default().title("Bytes sent: row → column").borders(Borders::ALL);
+    if app.local_flows.is_empty() {
+        let text = Paragraph::new("No traffic between local processes yet.")
+            .style(Style::default().fg(Color::Yellow))
+            .block(block);
+        f.render_widget(text, area);
+        return;
+    }
+
+    let fit = (area.width.saturating_sub(2 + NAME_WIDTH) / CELL_WIDTH).max(1) as usize;
+    let processes = busiest_processes(&app.local_flows, MAX_MATRIX_PROCESSES.min(fit));
+    let bytes: HashMap<(i32, i32), u64> = app.local_flows
+        .iter()
+        .map(|flow| ((flow.from_pid, flow.to_pid), flow.bytes))
+        .collect();
+    let max = bytes.values().copied().max().unwrap_or(0);
+
+    let mut header_cells = vec![Cell::from("from \\ to")];
+    header_cells.extend(processes.iter().map(|(pid, name)| {
+        Cell::from(short_name(name, *pid, CELL_WIDTH as usize - 1)).style(Style::default().fg(Color::Red))
+    }));
+    let header = Row::new(header_cells);
+
+    let rows = processes.iter().map(|(from_pid, from_name)| {
+        let mut cells = vec![
+            Cell::from(short_name(from_name, *from_pid, NAME_WIDTH as usize - 1))
+                .style(Style::default().fg(Color::Red)),
+        ];
+        cells.extend(processes.iter().map(|(to_pid, _)| {
+            let value = bytes.get(&(*from_pid, *to_pid)).copied().unwrap_or(0);
+            let text = if value == 0 { "·".to_string() } else { format_bytes(value) };
+            Cell::from(text).style(cell_style(value, max))
+        }));
+        Row::new(cells)
+    });
+
+    let mut widths = vec![Constraint::Length(NAME_WIDTH)];
+    widths.extend(std::iter::repeat_n(Constraint::Length(CELL_WIDTH), processes.len()));
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(block);
+    f.render_widget(table, area);
+}
+
+/// Render every process pair, largest volume first
+fn render_flow_list(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let header = Row::new(["From", "To", "Total", "Rate"].map(|h| {
+        Cell::from(h).style(Style::default().fg(Color::Red))
+    }));
+
+    let mut flows: Vec<&LocalFlow> = app.local_flows.iter().collect();
+    flows.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.from_pid.cmp(&b.from_pid)).then(a.to_pid.cmp(&b.to_pid)));
+
+    let rows = flows.iter().skip(app.local_flow_scroll_offset).map(|flow| {
+        let style = if flow.rate > 0 { Style::default().add_modifier(Modifier::BOLD) } else { Style::default() };
+        Row::new(vec![
+            Cell::from(format!("{} ({})", flow.from_name, flow.from_pid)),
+            Cell::from(format!("{} ({})", flow.to_name, flow.to_pid)),
+            Cell::from(format_bytes(flow.bytes)),
+            Cell::from(format_rate(flow.rate)),
+        ])
+        .style(style)
+    });
+
+    let widths = [
+        Constraint::Percentage(35),
+        Constraint::Percentage(35),
+        Constraint::Percentage(15),
+        Constraint::Percentage(15),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().title(format!("Flows ({})", flows.len())).borders(Borders::ALL));
+    f.render_widget(table, area);
+}
@@ -3,4 +3,18 @@ pub mod bandwidth;
 pub mod alert;
 pub mod overview; 
 pub mod settings; 
+pub mod interfaces;
+pub mod local_traffic;
+pub mod services;
+pub mod events;
+pub mod command_output;
+pub mod jump;
+pub mod annotation;
+pub mod limit;
+pub mod port;
+pub mod capture_banner;
+pub mod interface_picker;
+pub mod setup;
+pub mod help;
+pub mod debug_overlay;
 pub mod packet_details; 
\ No newline at end of file
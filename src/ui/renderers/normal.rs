@@ -6,25 +6,18 @@ use ratatui::{
     Frame
 };
 use crate::types::{App, SortColumn, SortDirection};
-use crate::ui::{utils::format_bytes, charts::render_charts};
+use crate::ui::{utils::format_countdown, charts::render_charts, keymap::Action};
+use crate::units::{format_bytes, format_rate};
 
 /// Render the normal mode view
 pub fn render(f: &mut Frame, app: &App) {
     let terminal_height = f.area().height;
     let is_cramped = terminal_height < 25; // Detect if we're in a cramped terminal (raised threshold)
     
-    // Check if we have messages to show
-    let recent_log_entry = app.command_execution_log.back();
-    let has_messages = app.last_alert_message.is_some() || recent_log_entry.is_some() || app.kill_notification.is_some();
-    
-    // Determine number of footer message boxes
-    let footer_boxes = {
-        let mut n = 0;
-        if app.last_alert_message.is_some() { n += 1; }
-        if app.command_execution_log.back().is_some() { n += 1; }
-        n
-    };
-    let footer_height = if footer_boxes == 0 { 0 } else { 3 * footer_boxes };
+    // Check if we have messages to show: the latest command run and the notification stack
+    let log_height = if app.command_execution_log.back().is_some() { 3 } else { 0 };
+    let footer_height = log_height + notification_stack_height(app, is_cramped);
+    let has_messages = footer_height > 0;
 
     // Adaptive layout based on whether we have messages to show and if action panel is active
     let main_chunks = if has_messages {
@@ -32,12 +25,8 @@ pub fn render(f: &mut Frame, app: &App) {
         Layout::vertical([
             Constraint::Length(3),  // Navigation
             Constraint::Min(0),     // Main content (Table + Action Panel)
-            if app.show_action_panel {
-                Constraint::Length(0)
-            } else {
-                if app.kill_notification.is_some() { Constraint::Length(6) } else { Constraint::Length(3) }
-            },
-            Constraint::Length(footer_height as u16), // Footer height based on messages
+            if app.show_action_panel { Constraint::Length(0) } else { Constraint::Length(3) },
+            Constraint::Length(footer_height), // Footer height based on messages
         ])
         .margin(1)
         .split(f.area())
@@ -45,23 +34,30 @@ pub fn render(f: &mut Frame, app: &App) {
         Layout::vertical([
             Constraint::Length(3),  // Navigation
             Constraint::Min(0),     // Main content
-            if app.show_action_panel {
-                Constraint::Length(0)
-            } else {
-                if app.kill_notification.is_some() { Constraint::Length(6) } else { Constraint::Length(3) }
-            },
+            if app.show_action_panel { Constraint::Length(0) } else { Constraint::Length(3) },
         ])
         .margin(1)
         .split(f.area())
     };
 
-    let navigation_text = if app.containers_mode {
-        "q: quit | Tab: switch mode | p/n/u/s/r/c: sort | d: direction | ↑/↓: select | Enter: actions"
+    let navigation_text = navigation_text(app);
+    let mut title_text = if app.estimated {
+        "Monitetoring – estimated (TCP counters, no capture)".to_string()
     } else {
-        "q: quit | Tab: switch mode | p/n/u/s/r: sort | d: direction | ↑/↓: select | Enter: actions"
+        "Monitetoring".to_string()
     };
+    // Wire bytes are what every other counter shows; only the other mode is pointed out
+    if app.byte_counting != crate::accounting::ByteCounting::Wire {
+        title_text.push_str(&format!(" – counting {}", app.byte_counting.label()));
+    }
+    if app.notifications.do_not_disturb() {
+        title_text.push_str(&match app.notifications.held_back() {
+            0 => " – 🔕 do not disturb (N: off)".to_string(),
+            held => format!(" – 🔕 do not disturb, {} held back (N: off)", held),
+        });
+    }
     let title = Paragraph::new(navigation_text)
-        .block(Block::default().title("Monitetoring").borders(Borders::ALL));
+        .block(Block::default().title(title_text).borders(Borders::ALL));
     f.render_widget(title, main_chunks[0]);
 
     // When bandwidth_mode is inactive, use full width for table; otherwise split for potential side chart
@@ -96,7 +92,11 @@ pub fn render(f: &mut Frame, app: &App) {
     })
     .split(content_chunks[0]);
 
-    render_process_table(f, app, table_chunks[0]);
+    if app.group_by.is_some() {
+        render_group_table(f, app, table_chunks[0]);
+    } else {
+        render_process_table(f, app, table_chunks[0]);
+    }
 
     if app.show_action_panel {
         render_action_panel(f, app, table_chunks[1], is_cramped);
@@ -112,13 +112,9 @@ pub fn render(f: &mut Frame, app: &App) {
     }
     
     // Only render footer if we have messages to show
-    if has_messages
-        && footer_height > 0 {
-            let footer_index = if !app.show_action_panel { 3 } else { 2 };
-            if footer_index < main_chunks.len() {
-                render_footer(f, app, main_chunks[footer_index]);
-            }
-        }
+    if has_messages {
+        render_footer(f, app, main_chunks[3]);
+    }
 }
 
 /// Render the process table (Linux / Unix-like builds)
@@ -138,6 +134,34 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         }
     };
     let mut header_titles: Vec<String> = header_titles_str.iter().map(|s| s.to_string()).collect();
+    let show_unit = area.width >= UNIT_MIN_TABLE_WIDTH;
+    if show_unit {
+        header_titles.push("Unit".to_string());
+    }
+    let show_port = area.width >= PORT_MIN_TABLE_WIDTH;
+    if show_port {
+        header_titles.push(PORT_TITLE.to_string());
+    }
+    let show_category = show_category_column(app, area.width);
+    if show_category {
+        header_titles.push(CATEGORY_TITLE.to_string());
+    }
+    let show_quality = area.width >= QUALITY_MIN_TABLE_WIDTH;
+    if show_quality {
+        header_titles.extend(["RTT".to_string(), "Retrans".to_string()]);
+    }
+    let show_loopback = show_loopback_column(app, area.width);
+    if show_loopback {
+        header_titles.push(LOOPBACK_TITLE.to_string());
+    }
+    let show_limit = !app.limits.is_empty();
+    if show_limit {
+        header_titles.push(LIMIT_TITLE.to_string());
+    }
+    let show_trend = area.width >= TREND_MIN_TABLE_WIDTH;
+    if show_trend {
+        header_titles.push(TREND_TITLE.to_string());
+    }
 
     let sort_indicator = if app.sort_direction == SortDirection::Asc { " ▲" } else { " ▼" };
     match app.sort_by {
@@ -182,62 +206,82 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let header = Row::new(header_cells);
 
     let sorted_stats = app.sorted_stats();
-    let rows = sorted_stats.iter().map(|(pid, data)| {
-        let mut style = Style::default();
-        if app.alerts.contains_key(pid) {
-            style = style.bg(Color::Yellow).fg(Color::Black);
-        }
-        if app.selected_process == Some(**pid) {
-            style = style.add_modifier(Modifier::BOLD);
-        }
+    let total_rate = total_rate(app, &sorted_stats);
+    let now = app.start_time.elapsed().as_secs_f64();
+    let pinned = pinned_count(app, &sorted_stats);
+    let rows = sorted_stats.iter().enumerate().map(|(index, (pid, data))| {
+        let style = process_row_style(app, **pid, data, total_rate)
+            .patch(pinned_section_style(index, pinned, sorted_stats.len()));
+        let traffic = data.scoped(app.traffic_scope);
 
-        let cells = if app.show_total_columns {
+        let mut cells = if app.show_total_columns {
             if app.containers_mode {
                 vec![
                     Cell::from(pid.to_string()),
-                    Cell::from(data.name.clone()),
+                    Cell::from(process_name_label(app, **pid, data)),
                     Cell::from(data.user_name.as_ref().unwrap_or(&"unknown".to_string()).clone()),
-                    Cell::from(format!("{}/s", format_bytes(data.sent_rate))),
-                    Cell::from(format_bytes(data.sent)),
-                    Cell::from(format!("{}/s", format_bytes(data.received_rate))),
-                    Cell::from(format_bytes(data.received)),
-                    Cell::from(data.container_name.as_ref().unwrap_or(&"host".to_string()).clone()),
+                    Cell::from(format_rate(traffic.sent_rate)),
+                    Cell::from(format_bytes(traffic.sent)),
+                    Cell::from(format_rate(traffic.received_rate)),
+                    Cell::from(format_bytes(traffic.received)),
+                    Cell::from(container_label(app, data)),
                 ]
             } else {
                 vec![
                     Cell::from(pid.to_string()),
-                    Cell::from(data.name.clone()),
+                    Cell::from(process_name_label(app, **pid, data)),
                     Cell::from(data.user_name.as_ref().unwrap_or(&"unknown".to_string()).clone()),
-                    Cell::from(format!("{}/s", format_bytes(data.sent_rate))),
-                    Cell::from(format_bytes(data.sent)),
-                    Cell::from(format!("{}/s", format_bytes(data.received_rate))),
-                    Cell::from(format_bytes(data.received)),
+                    Cell::from(format_rate(traffic.sent_rate)),
+                    Cell::from(format_bytes(traffic.sent)),
+                    Cell::from(format_rate(traffic.received_rate)),
+                    Cell::from(format_bytes(traffic.received)),
                 ]
             }
         } else {
             if app.containers_mode {
                 vec![
                     Cell::from(pid.to_string()),
-                    Cell::from(data.name.clone()),
+                    Cell::from(process_name_label(app, **pid, data)),
                     Cell::from(data.user_name.as_ref().unwrap_or(&"unknown".to_string()).clone()),
-                    Cell::from(format!("{}/s", format_bytes(data.sent_rate))),
-                    Cell::from(format!("{}/s", format_bytes(data.received_rate))),
-                    Cell::from(data.container_name.as_ref().unwrap_or(&"host".to_string()).clone()),
+                    Cell::from(format_rate(traffic.sent_rate)),
+                    Cell::from(format_rate(traffic.received_rate)),
+                    Cell::from(container_label(app, data)),
                 ]
             } else {
                 vec![
                     Cell::from(pid.to_string()),
-                    Cell::from(data.name.clone()),
+                    Cell::from(process_name_label(app, **pid, data)),
                     Cell::from(data.user_name.as_ref().unwrap_or(&"unknown".to_string()).clone()),
-                    Cell::from(format!("{}/s", format_bytes(data.sent_rate))),
-                    Cell::from(format!("{}/s", format_bytes(data.received_rate))),
+                    Cell::from(format_rate(traffic.sent_rate)),
+                    Cell::from(format_rate(traffic.received_rate)),
                 ]
             }
         };
+        if show_unit {
+            cells.push(Cell::from(data.unit.clone().unwrap_or_default()));
+        }
+        if show_port {
+            cells.push(Cell::from(top_port_label(data)));
+        }
+        if show_category {
+            cells.push(Cell::from(category_label(data)));
+        }
+        if show_quality {
+            cells.extend(quality_cells(&data.tcp_quality));
+        }
+        if show_loopback {
+            cells.push(Cell::from(format_rate(data.loopback_sent_rate + data.loopback_received_rate)));
+        }
+        if show_limit {
+            cells.push(Cell::from(app.limits.get(pid).map(|limit| format_rate(limit.rate)).unwrap_or_default()));
+        }
+        if show_trend {
+            cells.push(Cell::from(trend_sparkline(data, now)));
+        }
         Row::new(cells).style(style)
     });
 
-    let widths = if app.show_total_columns {
+    let mut widths = if app.show_total_columns {
         if app.containers_mode {
             [
                 Constraint::Percentage(8),   // PID
@@ -249,7 +293,7 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
                 Constraint::Percentage(12),  // (R)Tot
                 Constraint::Percentage(18),  // Container
             ]
-            .as_slice()
+            .to_vec()
         } else {
             [
                 Constraint::Percentage(12),  // PID
@@ -260,7 +304,7 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
                 Constraint::Percentage(15),  // Recv/s
                 Constraint::Percentage(15),  // (R)Tot
             ]
-            .as_slice()
+            .to_vec()
         }
     } else {
         if app.containers_mode {
@@ -272,7 +316,7 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
                 Constraint::Percentage(20),  // Recv/s
                 Constraint::Percentage(15),  // Container
             ]
-            .as_slice()
+            .to_vec()
         } else {
             [
                 Constraint::Percentage(15),  // PID
@@ -281,12 +325,45 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
                 Constraint::Percentage(17),  // Sent/s
                 Constraint::Percentage(18),  // Recv/s
             ]
-            .as_slice()
+            .to_vec()
         }
     };
+    if show_unit {
+        widths.push(Constraint::Length(22));
+    }
+    if show_port {
+        widths.push(Constraint::Length(PORT_WIDTH));
+    }
+    if show_category {
+        widths.push(Constraint::Length(CATEGORY_WIDTH));
+    }
+    if show_quality {
+        widths.extend([Constraint::Length(9), Constraint::Length(14)]);
+    }
+    if show_loopback {
+        widths.push(Constraint::Length(12));
+    }
+    if show_limit {
+        widths.push(Constraint::Length(12));
+    }
+    if show_trend {
+        widths.push(Constraint::Length(TREND_WIDTH as u16));
+    }
+    // Pinned aggregate row with the sum of every numeric column shown
+    let mut footer_cells = vec![
+        Cell::from("Σ"),
+        Cell::from(format!("{} processes", sorted_stats.len())),
+        Cell::from(""),
+    ];
+    footer_cells.extend(summary_cells(app, &sorted_stats));
+    if app.containers_mode {
+        footer_cells.push(Cell::from(""));
+    }
+
     let table = Table::new(rows, widths)
         .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Processes"));
+        .footer(Row::new(footer_cells).style(summary_style()))
+        .block(Block::default().borders(Borders::ALL).title(table_title(app)));
 
     // Create table state and set selection to the currently selected process
     let mut table_state = TableState::default();
@@ -301,9 +378,8 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
 /// Render the process table (Windows build – no container or user columns)
 #[cfg(windows)]
 fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    use ratatui::widgets::{TableState, Row, Cell};
     use ratatui::layout::Constraint;
-    use ratatui::style::{Color, Style, Modifier};
+    use ratatui::style::{Color, Style};
 
     // Build header titles – simplified for Windows
     let header_titles_str: Vec<&str> = if app.show_total_columns {
@@ -313,6 +389,22 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     };
 
     let mut header_titles: Vec<String> = header_titles_str.iter().map(|s| s.to_string()).collect();
+    let show_port = area.width >= PORT_MIN_TABLE_WIDTH;
+    if show_port {
+        header_titles.push(PORT_TITLE.to_string());
+    }
+    let show_category = show_category_column(app, area.width);
+    if show_category {
+        header_titles.push(CATEGORY_TITLE.to_string());
+    }
+    let show_loopback = show_loopback_column(app, area.width);
+    if show_loopback {
+        header_titles.push(LOOPBACK_TITLE.to_string());
+    }
+    let show_trend = area.width >= TREND_MIN_TABLE_WIDTH;
+    if show_trend {
+        header_titles.push(TREND_TITLE.to_string());
+    }
 
     // Apply sort indicator – skip User / Container because they are hidden on Windows
     let sort_indicator = if app.sort_direction == SortDirection::Asc { " ▲" } else { " ▼" };
@@ -336,34 +428,47 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let header = Row::new(header_cells);
 
     let sorted_stats = app.sorted_stats();
-    let rows = sorted_stats.iter().map(|(pid, data)| {
-        let mut style = Style::default();
-        if app.alerts.contains_key(pid) {
-            style = style.bg(Color::Yellow).fg(Color::Black);
-        }
-        if app.selected_process == Some(**pid) { style = style.add_modifier(Modifier::BOLD); }
+    let total_rate = total_rate(app, &sorted_stats);
+    let now = app.start_time.elapsed().as_secs_f64();
+    let pinned = pinned_count(app, &sorted_stats);
+    let rows = sorted_stats.iter().enumerate().map(|(index, (pid, data))| {
+        let style = process_row_style(app, **pid, data, total_rate)
+            .patch(pinned_section_style(index, pinned, sorted_stats.len()));
+        let traffic = data.scoped(app.traffic_scope);
 
         // Build cells without user / container columns
         let mut cells = vec![
             Cell::from(pid.to_string()),
-            Cell::from(data.name.clone()),
+            Cell::from(process_name_label(app, **pid, data)),
         ];
 
         if app.show_total_columns {
-            cells.push(Cell::from(format!("{}/s", format_bytes(data.sent_rate))));
-            cells.push(Cell::from(format_bytes(data.sent)));
-            cells.push(Cell::from(format!("{}/s", format_bytes(data.received_rate))));
-            cells.push(Cell::from(format_bytes(data.received)));
+            cells.push(Cell::from(format_rate(traffic.sent_rate)));
+            cells.push(Cell::from(format_bytes(traffic.sent)));
+            cells.push(Cell::from(format_rate(traffic.received_rate)));
+            cells.push(Cell::from(format_bytes(traffic.received)));
         } else {
-            cells.push(Cell::from(format!("{}/s", format_bytes(data.sent_rate))));
-            cells.push(Cell::from(format!("{}/s", format_bytes(data.received_rate))));
+            cells.push(Cell::from(format_rate(traffic.sent_rate)));
+            cells.push(Cell::from(format_rate(traffic.received_rate)));
+        }
+        if show_port {
+            cells.push(Cell::from(top_port_label(data)));
+        }
+        if show_category {
+            cells.push(Cell::from(category_label(data)));
+        }
+        if show_loopback {
+            cells.push(Cell::from(format_rate(data.loopback_sent_rate + data.loopback_received_rate)));
+        }
+        if show_trend {
+            cells.push(Cell::from(trend_sparkline(data, now)));
         }
 
         Row::new(cells).style(style)
     });
 
     // Define column widths proportionally (they don't need to sum to 100)
-    let widths: Vec<Constraint> = if app.show_total_columns {
+    let mut widths: Vec<Constraint> = if app.show_total_columns {
         vec![
             Constraint::Percentage(12),  // PID
             Constraint::Percentage(28),  // Name
@@ -380,28 +485,525 @@ fn render_process_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
             Constraint::Percentage(23),  // Recv/s
         ]
     };
+    if show_port {
+        widths.push(Constraint::Length(PORT_WIDTH));
+    }
+    if show_category {
+        widths.push(Constraint::Length(CATEGORY_WIDTH));
+    }
+    if show_loopback {
+        widths.push(Constraint::Length(12));
+    }
+    if show_trend {
+        widths.push(Constraint::Length(TREND_WIDTH as u16));
+    }
+
+    // Pinned aggregate row with the sum of every numeric column shown
+    let mut footer_cells = vec![
+        Cell::from("Σ"),
+        Cell::from(format!("{} processes", sorted_stats.len())),
+    ];
+    footer_cells.extend(summary_cells(app, &sorted_stats));
 
     let table = Table::new(rows, &widths)
         .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Processes"));
+        .footer(Row::new(footer_cells).style(summary_style()))
+        .block(Block::default().borders(Borders::ALL).title(table_title(app)));
 
     // Stateful selection handling (same as Unix version)
     let mut table_state = TableState::default();
-    if let Some(selected_pid) = app.selected_process {
-        if let Some(index) = sorted_stats.iter().position(|(pid, _)| **pid == selected_pid) {
+    if let Some(selected_pid) = app.selected_process
+        && let Some(index) = sorted_stats.iter().position(|(pid, _)| **pid == selected_pid) {
             table_state.select(Some(index));
         }
-    }
     
     f.render_stateful_widget(table, area, &mut table_state);
 }
 
+/// Seconds of history shown in the trend column
+const TREND_SECS: f64 = 60.0;
+/// Characters in the trend column; each covers TREND_SECS / TREND_WIDTH seconds
+const TREND_WIDTH: usize = 12;
+const TREND_TITLE: &str = "Last 60s";
+/// Narrower tables drop the trend column to keep the numbers readable
+const TREND_MIN_TABLE_WIDTH: u16 = 90;
+
+const PORT_TITLE: &str = "Top Port";
+const PORT_WIDTH: u16 = 11;
+/// Narrower tables drop the top port column before the loopback one
+const PORT_MIN_TABLE_WIDTH: u16 = 110;
+
+/// The port most of a process's traffic goes over, e.g. "443 92%"
+fn top_port_label(data: &crate::types::ProcessInfo) -> String {
+    data.top_port()
+        .map(|(port, share)| format!("{} {:.0}%", port, share * 100.0))
+        .unwrap_or_default()
+}
+
+const CATEGORY_TITLE: &str = "Category";
+const CATEGORY_WIDTH: u16 = 16;
+const CATEGORY_MIN_TABLE_WIDTH: u16 = 120;
+
+/// Only with classification rules in the config
+fn show_category_column(app: &App, width: u16) -> bool {
+    !app.categories.is_empty() && width >= CATEGORY_MIN_TABLE_WIDTH
+}
+
+/// The category most of a process's traffic fell into, and how many others it has, e.g. "Backups +2"
+fn category_label(data: &crate::types::ProcessInfo) -> String {
+    let categories = data.categories();
+    match categories.first() {
+        Some((name, _)) if categories.len() > 1 => format!("{} +{}", name, categories.len() - 1),
+        Some((name, _)) => name.to_string(),
+        None => String::new(),
+    }
+}
+
+const LOOPBACK_TITLE: &str = "Loopback/s";
+/// Shown while any process has a bandwidth limit (Linux only)
+#[cfg(not(windows))]
+const LIMIT_TITLE: &str = "Limit";
+/// Narrower tables drop the loopback column before the trend
+const LOOPBACK_MIN_TABLE_WIDTH: u16 = 100;
+
+/// Loopback traffic gets its own column while the table counts all traffic
+fn show_loopback_column(app: &App, width: u16) -> bool {
+    app.traffic_scope == crate::types::TrafficScope::All && width >= LOOPBACK_MIN_TABLE_WIDTH
+}
+
+/// Narrower tables drop the RTT and retransmit columns (Linux only, from TCP_INFO)
+#[cfg(not(windows))]
+const QUALITY_MIN_TABLE_WIDTH: u16 = 120;
+/// Only the widest tables get the systemd unit column (Linux only)
+#[cfg(not(windows))]
+const UNIT_MIN_TABLE_WIDTH: u16 = 140;
+
+/// Mean RTT and retransmits of a process's TCP connections; the retransmit
+/// cell turns red once the recent share of retransmitted segments is high
+#[cfg(not(windows))]
+fn quality_cells(quality: &crate::types::TcpQuality) -> [Cell<'static>; 2] {
+    let rtt = quality.avg_rtt_ms.map_or_else(|| "-".to_string(), |rtt| format!("{:.0} ms", rtt));
+    let retransmits = match quality.retransmit_percent {
+        Some(percent) if quality.retransmits > 0 => format!("{} ({:.1}%)", quality.retransmits, percent),
+        _ => quality.retransmits.to_string(),
+    };
+    let style = if quality.retransmit_percent.is_some_and(|percent| percent >= crate::types::RETRANSMIT_WARNING_PERCENT) {
+        Style::default().fg(Color::LightRed)
+    } else {
+        Style::default()
+    };
+    [Cell::from(rtt), Cell::from(retransmits).style(style)]
+}
+
+/// Combined send and receive rate over the last minute as a one-line
+/// sparkline, drawn with the same bar symbols as ratatui's `Sparkline` and
+/// scaled to the process's own peak
+fn trend_sparkline(data: &crate::types::ProcessInfo, now: f64) -> String {
+    use ratatui::symbols::bar::NINE_LEVELS;
+    let levels = [
+        NINE_LEVELS.empty, NINE_LEVELS.one_eighth, NINE_LEVELS.one_quarter, NINE_LEVELS.three_eighths,
+        NINE_LEVELS.half, NINE_LEVELS.five_eighths, NINE_LEVELS.three_quarters, NINE_LEVELS.seven_eighths,
+        NINE_LEVELS.full,
+    ];
+
+    // Peak combined rate per bucket; the sent and received histories are sampled together
+    let start = now - TREND_SECS;
+    let mut buckets = [0f64; TREND_WIDTH];
+    for ((t, sent), (_, received)) in data.sent_history.iter().zip(&data.received_history) {
+        if *t < start {
+            continue;
+        }
+        let index = (((t - start) / TREND_SECS * TREND_WIDTH as f64) as usize).min(TREND_WIDTH - 1);
+        buckets[index] = buckets[index].max(sent + received);
+    }
+
+    let peak = buckets.iter().copied().fold(0.0, f64::max);
+    if peak <= 0.0 {
+        return String::new();
+    }
+    buckets
+        .iter()
+        .map(|value| {
+            // Any traffic at all shows at least the lowest bar
+            let level = (value / peak * 8.0).ceil() as usize;
+            levels[level.min(8)]
+        })
+        .collect()
+}
+
+/// Process name, marked when the process has exited or carries a VPN tunnel
+/// (and is left out of the totals), with the remaining mute time when its
+/// alert is muted and how far it is above its baseline when anomaly
+/// highlighting is on
+fn process_name_label(app: &App, pid: i32, data: &crate::types::ProcessInfo) -> String {
+    let label = process_state_label(app, pid, data);
+    if app.marked.contains(&pid) {
+        format!("✓ {}", label)
+    } else {
+        label
+    }
+}
+
+fn process_state_label(app: &App, pid: i32, data: &crate::types::ProcessInfo) -> String {
+    if app.is_exited(pid) {
+        return format!("{} (exited)", data.name);
+    }
+    let base = if app.is_pinned(data) {
+        format!("📌 {}", data.name)
+    } else {
+        data.name.clone()
+    };
+    let base = if data.tunnel { format!("{} 🔒 VPN", base) } else { base };
+    let base = match &data.restart {
+        Some(restart) => format!("{} ↻{}", base, restart.count),
+        None => base,
+    };
+    let base = if app.blocks.contains_key(&pid) { format!("{} ⛔", base) } else { base };
+    let name = match app.mute_remaining(pid) {
+        Some(remaining) => format!("{} 🔇 {}", base, format_countdown(remaining)),
+        None => base,
+    };
+    match app.anomalies.score(pid) {
+        Some(score) if app.anomalies.is_highlighted(pid) => format!("{} 📈 {:.0}σ", name, score.sigmas),
+        _ => name,
+    }
+}
+
+/// Key hints for the main view, following any remapped keys
+fn navigation_text(app: &App) -> String {
+    let keys = &app.keymap;
+    let mut sort_actions = vec![Action::SortPid, Action::SortName, Action::SortUser, Action::SortSent, Action::SortReceived];
+    if app.containers_mode {
+        sort_actions.push(Action::SortContainer);
+    }
+    let sort_keys: Vec<String> = sort_actions.into_iter().map(|action| keys.label(action)).collect();
+    format!(
+        "{}: quit | {}: switch mode | {}: sort | {}: direction | {}: sort menu | ↑/↓: select | Enter: actions | M: mute alert | Space: mark | f: pin | z: idle | ':' jump | ←/→: history | ?: help",
+        keys.label(Action::Quit),
+        keys.label(Action::NextMode),
+        sort_keys.join("/"),
+        keys.label(Action::ReverseSort),
+        keys.label(Action::SortMenu)
+    )
+}
+
+/// Number of pinned rows, which `sorted_stats` puts first
+fn pinned_count(app: &App, stats: &[(&i32, &crate::types::ProcessInfo)]) -> usize {
+    stats.iter().take_while(|(_, data)| app.is_pinned(data)).count()
+}
+
+/// Underline the last pinned row to set the pinned section off from the rest
+fn pinned_section_style(index: usize, pinned: usize, rows: usize) -> Style {
+    if index + 1 == pinned && pinned < rows {
+        Style::default().add_modifier(Modifier::UNDERLINED)
+    } else {
+        Style::default()
+    }
+}
+
+/// Combined send and receive rate of the listed processes
+fn total_rate(app: &App, stats: &[(&i32, &crate::types::ProcessInfo)]) -> u64 {
+    stats.iter()
+        .map(|(_, data)| data.scoped(app.traffic_scope))
+        .map(|traffic| traffic.sent_rate + traffic.received_rate)
+        .sum()
+}
+
+/// Row style for a process: exited, muted and alerting rows first, otherwise the heat
+/// tint for its share of the current total bandwidth and the style of the
+/// first highlight rule it matches, with red text when its socket states
+/// suggest a connection leak and magenta when it is far above its learned baseline.
+/// Marked rows are drawn reversed on top of that
+fn process_row_style(app: &App, pid: i32, data: &crate::types::ProcessInfo, total_rate: u64) -> Style {
+    let mut style = Style::default();
+    if app.is_exited(pid) {
+        style = style.fg(Color::DarkGray).add_modifier(Modifier::ITALIC);
+    } else if app.is_alert_muted(pid) {
+        style = style.fg(Color::DarkGray);
+    } else if app.alerts.contains_key(&pid) {
+        style = style.bg(Color::Yellow).fg(Color::Black);
+    } else {
+        if total_rate > 0 {
+            let traffic = data.scoped(app.traffic_scope);
+            let share = (traffic.sent_rate + traffic.received_rate) as f64 / total_rate as f64 * 100.0;
+            if let Some(color) = app.theme.heat_color(share) {
+                style = style.bg(color);
+            }
+        }
+        if let Some(highlight) = app.theme.highlight(&data.name, data.user_name.as_deref(), data.container_name.as_deref()) {
+            style = style.patch(highlight);
+        }
+        if data.socket_states.needs_attention() {
+            style = style.fg(Color::LightRed);
+        }
+        if app.anomalies.is_highlighted(pid) {
+            style = style.fg(Color::LightMagenta).add_modifier(Modifier::BOLD);
+        }
+    }
+    if app.marked.contains(&pid) {
+        style = style.add_modifier(Modifier::REVERSED);
+    }
+    if app.selected_process == Some(pid) {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    style
+}
+
+/// The container's pod, Compose service or image once looked up, its ID until then
+#[cfg(not(windows))]
+fn container_label(app: &App, data: &crate::types::ProcessInfo) -> String {
+    let Some(container) = &data.container_name else { return "host".to_string() };
+    app.containers.get(container)
+        .and_then(|meta| meta.label())
+        .unwrap_or_else(|| container.clone())
+}
+
+/// Render the table folded by `app.group_by`: one row per group with its summed traffic
+fn render_group_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let Some(by) = app.group_by else { return };
+    let groups = app.grouped_stats();
+    let header = Row::new(
+        [by.label(), "Processes", "Sent/s", "Recv/s", "Sent", "Received"]
+            .map(|title| Cell::from(title).style(Style::default().fg(Color::Red)))
+    );
+    let rows = groups.iter().map(|group| Row::new(vec![
+        Cell::from(group.key.clone()),
+        Cell::from(group.processes.to_string()),
+        Cell::from(format_rate(group.sent_rate)),
+        Cell::from(format_rate(group.received_rate)),
+        Cell::from(format_bytes(group.sent)),
+        Cell::from(format_bytes(group.received)),
+    ]));
+    let widths = [
+        Constraint::Percentage(35),
+        Constraint::Percentage(11),
+        Constraint::Percentage(13),
+        Constraint::Percentage(13),
+        Constraint::Percentage(14),
+        Constraint::Percentage(14),
+    ];
+    let title = format!("Processes grouped by {} – Enter: show a group's processes, g: next grouping, Esc: ungroup", by.label().to_lowercase());
+    let table = Table::new(rows, widths)
+        .header(header)
+        .row_highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    let mut table_state = TableState::default();
+    if !groups.is_empty() {
+        table_state.select(Some(app.group_selected.min(groups.len() - 1)));
+    }
+    f.render_stateful_widget(table, area, &mut table_state);
+}
+
+/// Cells summing each numeric column currently shown, in table order
+fn summary_cells(app: &App, stats: &[(&i32, &crate::types::ProcessInfo)]) -> Vec<Cell<'static>> {
+    let traffic: Vec<_> = stats.iter().map(|(_, data)| data.scoped(app.traffic_scope)).collect();
+    let sent_rate: u64 = traffic.iter().map(|t| t.sent_rate).sum();
+    let received_rate: u64 = traffic.iter().map(|t| t.received_rate).sum();
+    if app.show_total_columns {
+        let sent: u64 = traffic.iter().map(|t| t.sent).sum();
+        let received: u64 = traffic.iter().map(|t| t.received).sum();
+        vec![
+            Cell::from(format_rate(sent_rate)),
+            Cell::from(format_bytes(sent)),
+            Cell::from(format_rate(received_rate)),
+            Cell::from(format_bytes(received)),
+        ]
+    } else {
+        vec![
+            Cell::from(format_rate(sent_rate)),
+            Cell::from(format_rate(received_rate)),
+        ]
+    }
+}
+
+fn summary_style() -> Style {
+    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+}
+
+/// Title of the process table, flagging playback mode and the secondary sort key
+fn table_title(app: &App) -> String {
+    let mut title = "Processes".to_string();
+    if let Some((column, direction)) = app.secondary_sort {
+        title.push_str(&format!(" (then by {}{})", column.label(), direction.indicator()));
+    }
+    if let Some(filter) = &app.process_filter {
+        title.push_str(&format!(" – only {}", filter));
+    }
+    if let Some((by, key)) = &app.group_filter {
+        title.push_str(&format!(" – {} {} (G: show all)", by.label().to_lowercase(), key));
+    }
+    if let Some(port) = app.port_filter {
+        title.push_str(&format!(" – port {} (P: change)", port));
+    }
+    if let Some(scope) = app.traffic_scope.label() {
+        title.push_str(&format!(" – {}", scope));
+    }
+    if app.hide_idle {
+        let hidden = app.visible_stats().iter().filter(|(pid, info)| app.is_hidden_idle(**pid, info)).count();
+        title.push_str(&format!(" – {} idle hidden", hidden));
+    }
+    if let Some(stop_at) = app.stop_at {
+        let remaining = stop_at.saturating_duration_since(std::time::Instant::now());
+        title.push_str(&format!(" – stops in {}", crate::ui::utils::format_countdown(remaining)));
+    }
+    if let Some(label) = app.playback_label() {
+        title.push_str(&format!(" – {}", label));
+    }
+    title
+}
+
+/// Render the sort menu popup on top of the current view
+pub fn render_sort_menu(f: &mut Frame, app: &App) {
+    let area = crate::ui::utils::centered_rect(50, 60, f.area());
+    let columns = crate::types::SortColumn::menu_columns(app.containers_mode);
+
+    let mut lines: Vec<Line> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let mut role = String::new();
+            if app.sort_by == *column {
+                role = format!("  [1st{}]", app.sort_direction.indicator());
+            } else if let Some((secondary, direction)) = app.secondary_sort
+                && secondary == *column {
+                    role = format!("  [2nd{}]", direction.indicator());
+                }
+            let text = format!("{}{}", column.label(), role);
+            if i == app.sort_menu_selected {
+                Line::from(Span::styled(
+                    format!("> {}", text),
+                    Style::default().add_modifier(Modifier::BOLD).fg(Color::Cyan),
+                ))
+            } else {
+                Line::from(format!("  {}", text))
+            }
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Enter/1: primary | Space/2: secondary | x: clear secondary | Esc: close",
+        Style::default().fg(Color::Gray),
+    )));
+
+    let menu = Paragraph::new(Text::from(lines))
+        .wrap(ratatui::widgets::Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Sort By")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(menu, area);
+}
+
+/// Render the alert mute menu popup on top of the current view
+pub fn render_mute_menu(f: &mut Frame, app: &App) {
+    let Some(pid) = app.mute_menu_pid else { return };
+    let area = crate::ui::utils::centered_rect(40, 40, f.area());
+
+    let mut options: Vec<String> = crate::types::MuteDuration::ALL
+        .iter()
+        .map(|duration| format!("Mute for {}", duration.label().to_lowercase()))
+        .collect();
+    if let Some(remaining) = app.mute_remaining(pid) {
+        options.push(format!("Unmute ({} left)", format_countdown(remaining)));
+    }
+
+    let mut lines: Vec<Line> = options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| {
+            if i == app.mute_menu_selected {
+                Line::from(Span::styled(
+                    format!("> {}", option),
+                    Style::default().add_modifier(Modifier::BOLD).fg(Color::Cyan),
+                ))
+            } else {
+                Line::from(format!("  {}", option))
+            }
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Muted alerts take no action and show no notifications",
+        Style::default().fg(Color::Gray),
+    )));
+    lines.push(Line::from(Span::styled(
+        "Enter: apply | Esc: close",
+        Style::default().fg(Color::Gray),
+    )));
+
+    let name = app.stats.get(&pid).map(|info| info.name.as_str()).unwrap_or("unknown");
+    let menu = Paragraph::new(Text::from(lines))
+        .wrap(ratatui::widgets::Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Mute Alert – {} (PID {})", name, pid))
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(menu, area);
+}
+
 /// Render the action panel
 fn render_action_panel(f: &mut Frame, app: &App, area: ratatui::layout::Rect, is_cramped: bool) {
-    let action_panel_text = if let Some(pid) = app.selected_process {
-        let mut actions = vec!["Kill Process", "Set/Edit Bandwidth Alert", "Show Packet Details"];
-        if app.alerts.contains_key(&pid) {
-            actions.push("Remove Alert");
+    let action_panel_text = if let Some(pid) = app.kill_confirm {
+        let name = app.stats.get(&pid).map_or("this process", |info| info.name.as_str());
+        Text::from(vec![
+            Line::from(Span::styled(
+                format!("Kill {} (PID {})?", name, pid),
+                Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow),
+            )),
+            Line::from("  y/Enter  Kill now (SIGKILL)"),
+            Line::from("  t        SIGTERM, then SIGKILL after 5s (u cancels)"),
+            Line::from("  n/Esc    Keep it running"),
+        ])
+    } else if app.bulk_kill_confirm {
+        let names: Vec<String> = app.marked_pids().iter()
+            .filter_map(|pid| app.stats.get(pid).map(|info| format!("{} ({})", info.name, pid)))
+            .collect();
+        Text::from(vec![
+            Line::from(Span::styled(
+                format!("Kill {} marked processes?", names.len()),
+                Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow),
+            )),
+            Line::from(format!("  {}", names.join(", "))),
+            Line::from("  y/Enter  Kill them now (SIGKILL)"),
+            Line::from("  t        SIGTERM, then SIGKILL after 5s"),
+            Line::from("  n/Esc    Keep them running"),
+        ])
+    } else if app.selected_process.is_some() || !app.marked.is_empty() {
+        let mut actions = Vec::new();
+        if !app.marked.is_empty() {
+            let count = app.marked_pids().len();
+            actions = vec![
+                format!("Kill {} Marked Processes", count),
+                format!("Set Alert on {} Marked Processes", count),
+                format!("Export Packets of {} Marked Processes", count),
+                "Clear Marks".to_string(),
+            ];
+        } else if let Some(pid) = app.selected_process {
+            actions = app.process_actions(pid).into_iter().map(|action| match action {
+                "Kill" => "Kill Process".to_string(),
+                "Edit" => "Set/Edit Bandwidth Alert".to_string(),
+                "Details" => "Show Packet Details".to_string(),
+                "Remove" => "Remove Alert".to_string(),
+                "Mute" => match app.mute_remaining(pid) {
+                    Some(remaining) => format!("Mute Alert (🔇 {} left)", format_countdown(remaining)),
+                    None => "Mute Alert".to_string(),
+                },
+                "Limit" => match app.limits.get(&pid) {
+                    Some(limit) => format!("Change Bandwidth Limit ({})", format_rate(limit.rate)),
+                    None => "Limit Bandwidth".to_string(),
+                },
+                "Unlimit" => "Remove Bandwidth Limit".to_string(),
+                "Block" => "Block Network".to_string(),
+                "Unblock" => "Unblock Network".to_string(),
+                other => other.to_string(),
+            }).collect();
         }
 
         if (is_cramped || area.height < 6) && area.width > 50 {
@@ -447,7 +1049,7 @@ fn render_action_panel(f: &mut Frame, app: &App, area: ratatui::layout::Rect, is
             
             // Add a current selection indicator if we have limited space
             if area.height < 6 {
-                let selected_action_text = actions.get(app.selected_action).unwrap_or(&"Unknown");
+                let selected_action_text = actions.get(app.selected_action).map(String::as_str).unwrap_or("Unknown");
                 text_lines.push(Line::from(Span::styled(
                     format!(">> {}", selected_action_text),
                     Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow),
@@ -460,7 +1062,9 @@ fn render_action_panel(f: &mut Frame, app: &App, area: ratatui::layout::Rect, is
         Text::from("No process selected")
     };
 
-    let title = if let Some(pid) = app.selected_process {
+    let title = if !app.marked.is_empty() {
+        format!("Actions for {} marked processes (Esc in the table clears the marks)", app.marked_pids().len())
+    } else if let Some(pid) = app.selected_process {
         format!("Actions for PID {}", pid)
     } else {
         "Actions".to_string()
@@ -478,80 +1082,31 @@ fn render_action_panel(f: &mut Frame, app: &App, area: ratatui::layout::Rect, is
 
 /// Render the totals bar
 fn render_totals_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    if let Some(kill_msg) = &app.kill_notification {
-        // Split area: totals on top, kill notification below
-        let chunks = Layout::vertical([Constraint::Length(3), Constraint::Length(3)])
-            .split(area);
-        
-        // Render totals in top chunk
-        let (total_sent, total_received, total_sent_rate, total_received_rate) = app.totals();
-        let totals_text = format!(
-            "📊 TOTALS: Sent {}/s ({} total) | Received {}/s ({} total)",
-            format_bytes(total_sent_rate),
-            format_bytes(total_sent),
-            format_bytes(total_received_rate),
-            format_bytes(total_received)
-        );
-        let totals = Paragraph::new(totals_text)
-            .block(Block::default().borders(Borders::ALL).title("Network Totals"));
-        f.render_widget(totals, chunks[0]);
-        
-        // Render kill notification in bottom chunk
-        let kill_style = if kill_msg.starts_with("✅") {
-            Style::default().fg(Color::Green)
-        } else {
-            Style::default().fg(Color::Red)
-        };
-        let kill_notification = Paragraph::new(kill_msg.as_str())
-            .style(kill_style)
-            .block(Block::default().borders(Borders::ALL).title("Process Action"));
-        f.render_widget(kill_notification, chunks[1]);
-    } else {
-        // No kill notification, render totals normally
-        let (total_sent, total_received, total_sent_rate, total_received_rate) = app.totals();
-        let totals_text = format!(
-            "📊 TOTALS: Sent {}/s ({} total) | Received {}/s ({} total)",
-            format_bytes(total_sent_rate),
-            format_bytes(total_sent),
-            format_bytes(total_received_rate),
-            format_bytes(total_received)
-        );
-        let totals = Paragraph::new(totals_text)
-            .block(Block::default().borders(Borders::ALL).title("Network Totals"));
-        f.render_widget(totals, area);
-    }
+    let (total_sent, total_received, total_sent_rate, total_received_rate) = app.totals();
+    let totals_text = format!(
+        "📊 TOTALS: Sent {} ({} total) | Received {} ({} total)",
+        format_rate(total_sent_rate),
+        format_bytes(total_sent),
+        format_rate(total_received_rate),
+        format_bytes(total_received)
+    );
+    let totals = Paragraph::new(totals_text)
+        .block(Block::default().borders(Borders::ALL).title("Network Totals"));
+    f.render_widget(totals, area);
+}
+
+/// Rows the notification box needs: one per notification, fewer in a cramped terminal
+fn notification_stack_height(app: &App, is_cramped: bool) -> u16 {
+    let rows = app.notifications.len().min(if is_cramped { 2 } else { 4 });
+    if rows == 0 { 0 } else { rows as u16 + 2 }
 }
 
 /// Render the footer
 fn render_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    // Check if we have recent command execution or alert message
-    let recent_log_entry = app.command_execution_log.back();
-    
-    if let Some(msg) = &app.last_alert_message {
-        if let Some((timestamp, log_msg)) = recent_log_entry {
-            let chunks = Layout::vertical([Constraint::Length(3), Constraint::Length(3)])
-                .split(area);
-
-            // Command execution box (top box gets the dismiss guide)
-            let elapsed = timestamp.elapsed().as_secs();
-            let time_str = if elapsed < 60 { format!("{}s ago", elapsed) } else { format!("{}m ago", elapsed / 60) };
-            let mut parts = log_msg.splitn(2, '\n');
-            let header = parts.next().unwrap_or("Command Execution");
-            let body = parts.next().unwrap_or("");
-            let exec_paragraph = Paragraph::new(format!("{} ({})", body.trim(), time_str))
-                .style(Style::default().fg(Color::Cyan))
-                .block(Block::default().borders(Borders::ALL).title(header));
-            f.render_widget(exec_paragraph, chunks[0]);
-
-            // Alert message box (no dismiss guide since top box has it)
-            let alert_paragraph = format_alert_message(msg, false);
-            f.render_widget(alert_paragraph, chunks[1]);
-        } else {
-            // Only alert message
-            let alert_paragraph = format_alert_message(msg, true);
-            f.render_widget(alert_paragraph, area);
-        }
-    } else if let Some((timestamp, log_msg)) = recent_log_entry {
+    let log_height = if app.command_execution_log.back().is_some() { 3 } else { 0 };
+    let chunks = Layout::vertical([Constraint::Length(log_height), Constraint::Min(0)]).split(area);
+
+    if let Some((timestamp, log_msg)) = app.command_execution_log.back() {
         let elapsed = timestamp.elapsed().as_secs();
         let time_str = if elapsed < 60 { format!("{}s ago", elapsed) } else { format!("{}m ago", elapsed / 60) };
         let mut parts = log_msg.splitn(2, '\n');
@@ -560,21 +1115,117 @@ fn render_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         let exec_paragraph = Paragraph::new(format!("{} ({})", body.trim(), time_str))
             .style(Style::default().fg(Color::Cyan))
             .block(Block::default().borders(Borders::ALL).title(header));
-        f.render_widget(exec_paragraph, area);
+        f.render_widget(exec_paragraph, chunks[0]);
+    }
+    if !app.notifications.is_empty() {
+        render_notification_stack(f, app, chunks[1]);
     }
-    // If no messages to show, leave the space empty (removed the "No Action Executed" box)
 }
 
-fn format_alert_message(msg: &str, _show_dismiss_guide: bool) -> Paragraph<'_> {
-    if let Some(pos) = msg.find(':') {
-        let header = &msg[..=pos];
-        let body = msg[pos + 1..].trim();
-        Paragraph::new(body)
-            .style(Style::default().fg(Color::Yellow))
-            .block(Block::default().borders(Borders::ALL).title(header))
+/// Notifications, newest first, one line each and colored by how things went
+fn render_notification_stack(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    use crate::notifications::Severity;
+
+    let rows = area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = app.notifications.visible(rows)
+        .map(|notification| {
+            let color = match notification.severity {
+                Severity::Error => Color::Red,
+                Severity::Warning => Color::Yellow,
+                Severity::Success => Color::Green,
+                Severity::Info => Color::White,
+            };
+            // Multi-line messages ("…:\nUsage: …") fold into one line
+            let message = notification.message.split('\n').map(str::trim).collect::<Vec<_>>().join(" ");
+            let repeats = if notification.count > 1 { format!("×{} ", notification.count) } else { String::new() };
+            Line::from(vec![
+                Span::styled(repeats, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                Span::styled(message, Style::default().fg(color)),
+                Span::styled(format!(" ({}s ago)", notification.created.elapsed().as_secs()), Style::default().fg(Color::DarkGray)),
+            ])
+        })
+        .collect();
+
+    let total = app.notifications.len();
+    let first = app.notifications.scroll() + 1;
+    let mut title = if total > rows {
+        format!("Notifications {}-{} of {}", first, (first + rows - 1).min(total), total)
     } else {
-        Paragraph::new(msg)
-            .style(Style::default().fg(Color::Yellow))
-            .block(Block::default().borders(Borders::ALL).title("Alert"))
+        "Notifications".to_string()
+    };
+    title.push_str(" (x: dismiss, X: clear");
+    if total > rows {
+        title.push_str(", [/]: scroll");
+    }
+    if app.last_alert_pid.is_some() {
+        title.push_str(", M: mute");
     }
-} 
\ No newline at end of file
+    title.push(')');
+
+    let stack = Paragraph::new(Text::from(lines))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(stack, area);
+}
+/// Render the per-process changes since the baseline marked with `B`
+pub fn render_baseline_diff(f: &mut Frame, app: &App) {
+    let Some(baseline) = &app.baseline else { return };
+    let area = crate::ui::utils::centered_rect(70, 70, f.area());
+
+    let now = crate::diff::snapshot_of(&app.stats);
+    let changes = crate::diff::compare(&baseline.snapshot, &now);
+    let (sent, received) = changes.iter()
+        .filter(|change| change.after.is_some())
+        .fold((0, 0), |(sent, received), change| (sent + change.sent_delta(), received + change.received_delta()));
+
+    let visible = area.height.saturating_sub(4) as usize; // Borders and header
+    let scroll = app.baseline_scroll.min(changes.len().saturating_sub(visible));
+    let rows: Vec<Row> = changes.iter()
+        .skip(scroll)
+        .take(visible)
+        .map(|change| {
+            // Exited processes' counters are gone, so there is nothing to subtract from
+            let (sent, received, style) = match change.after {
+                Some(_) => (
+                    crate::diff::format_delta(change.sent_delta()),
+                    crate::diff::format_delta(change.received_delta()),
+                    Style::default(),
+                ),
+                None => ("-".to_string(), "-".to_string(), Style::default().fg(Color::DarkGray)),
+            };
+            let status = match change.status() {
+                "gone" => "exited",
+                status => status,
+            };
+            Row::new(vec![
+                Cell::from(change.process.clone()),
+                Cell::from(sent),
+                Cell::from(received),
+                Cell::from(status),
+            ]).style(style)
+        })
+        .collect();
+
+    let ago = chrono::Local::now().signed_duration_since(baseline.marked).num_seconds().max(0) as u64;
+    let title = format!(
+        "Since baseline {} ({} ago): {} sent, {} received | ↑/↓: scroll | B: re-mark | b/Esc: close",
+        baseline.marked.format("%H:%M:%S"),
+        format_countdown(std::time::Duration::from_secs(ago)),
+        crate::diff::format_delta(sent),
+        crate::diff::format_delta(received)
+    );
+    let table = Table::new(rows, [
+        Constraint::Min(20),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(7),
+    ])
+    .header(Row::new(vec!["Process", "Sent", "Received", ""]).style(Style::default().add_modifier(Modifier::BOLD)))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .style(Style::default().bg(Color::DarkGray))
+    );
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(table, area);
+}
@@ -1,12 +1,12 @@
 use ratatui::{
-    widgets::{Block, Borders, Paragraph, Table, Row, Cell, Gauge, BarChart, Bar, BarGroup, List, ListItem, ListState},
+    widgets::{Block, Borders, Clear, Paragraph, Table, Row, Cell, Gauge, BarChart, Bar, BarGroup, List, ListItem, ListState},
     layout::{Layout, Constraint},
     style::{Style, Color, Modifier},
     text::{Line, Span, Text},
     Frame
 };
 use crate::types::App;
-use crate::ui::utils::format_bytes;
+use crate::units::{format_bytes, format_rate};
 
 /// Render the system overview mode with dashboard metrics
 pub fn render(f: &mut Frame, app: &App) {
@@ -22,6 +22,10 @@ pub fn render(f: &mut Frame, app: &App) {
     render_title(f, app, main_chunks[0]);
     render_dashboard(f, app, main_chunks[1]);
     render_alert_progress(f, app, main_chunks[2]);
+
+    if app.show_scan_stats {
+        render_scan_stats(f, app);
+    }
 }
 
 /// Render the title header with navigation inside
@@ -34,22 +38,128 @@ fn render_title(f: &mut Frame, _app: &App, area: ratatui::layout::Rect) {
     f.render_widget(block, area);
 
     // Navigation guide paragraph rendered inside the block
-    let nav_text = "q: quit | Tab: switch mode | +/-: adjust quota | r: reset";
+    let nav_text = "q: quit | Tab: switch mode | +/-: adjust quota | r: reset | D: debug stats | ?: help";
     let nav_paragraph = Paragraph::new(nav_text);
     f.render_widget(nav_paragraph, inner);
 }
 
 /// Render the main dashboard area with gauge, charts, and system info
 fn render_dashboard(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    // Split main area vertically: gauge on top, protocol section below
+    // Split main area vertically: gauge on top, cost estimate if priced, categories if
+    // classified, protocol section below
+    let cost_height = if app.cost.is_enabled() { 6 } else { 0 };
+    let categories_height = if app.categories.is_empty() { 0 } else { (app.categories.len() as u16 + 4).min(10) };
     let dashboard_chunks = Layout::vertical([
         Constraint::Length(4),  // Bandwidth gauge on top
+        Constraint::Length(cost_height),
+        Constraint::Length(categories_height),
         Constraint::Min(0),     // Protocol section with integrated system info
     ])
     .split(area);
         
     render_bandwidth_gauge(f, app, dashboard_chunks[0]);
-    render_protocol_section(f, app, dashboard_chunks[1]);
+    if app.cost.is_enabled() {
+        render_cost(f, app, dashboard_chunks[1]);
+    }
+    if !app.categories.is_empty() {
+        render_categories(f, app, dashboard_chunks[2]);
+    }
+    render_protocol_section(f, app, dashboard_chunks[3]);
+}
+
+/// Render the traffic of each classification category, and what no rule matched
+fn render_categories(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let totals = app.category_totals();
+    let total: u64 = app.visible_stats().values()
+        .filter(|info| !info.tunnel)
+        .map(|info| info.sent + info.received)
+        .sum();
+    let classified: u64 = totals.iter().map(|(_, bytes, _)| bytes).sum();
+    let share = |bytes: u64| format!("{:.1}%", if total > 0 { bytes as f64 / total as f64 * 100.0 } else { 0.0 });
+
+    let mut rows: Vec<Row> = totals.iter()
+        .map(|(category, bytes, processes)| Row::new(vec![
+            Cell::from(category.to_string()),
+            Cell::from(format_bytes(*bytes)),
+            Cell::from(share(*bytes)),
+            Cell::from(processes.to_string()),
+        ]))
+        .collect();
+    rows.push(Row::new(vec![
+        Cell::from("Unclassified"),
+        Cell::from(format_bytes(total.saturating_sub(classified))),
+        Cell::from(share(total.saturating_sub(classified))),
+        Cell::from(""),
+    ]).style(Style::default().fg(Color::Gray)));
+
+    let table = Table::new(rows, [Constraint::Min(16), Constraint::Length(12), Constraint::Length(7), Constraint::Length(9)])
+        .header(Row::new(vec!["Category", "Total", "%", "Processes"]).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().title("Traffic by Category").borders(Borders::ALL))
+        .style(Style::default().fg(Color::White));
+    f.render_widget(table, area);
+}
+
+/// Render the estimated cost: total, hourly rate, budget and the most expensive processes
+fn render_cost(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let cost = &app.cost;
+    let billed = match cost.billed {
+        crate::cost::Billed::Both => "sent + received",
+        crate::cost::Billed::Sent => "sent",
+        crate::cost::Billed::Received => "received",
+    };
+    let block = Block::default()
+        .title(format!("Estimated Cost ({}/GB, {})", cost.format(cost.per_gb.unwrap_or(0.0)), billed))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::horizontal([
+        Constraint::Percentage(40), // Totals and budget
+        Constraint::Percentage(60), // Top processes by cost
+    ])
+    .split(inner);
+
+    let (total, per_hour) = app.estimated_cost();
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("Total: "),
+            Span::styled(cost.format(total), Style::default().add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(format!("Rate: {}/h", cost.format(per_hour))),
+    ];
+    if let Some(budget) = cost.budget {
+        let color = if total > budget {
+            Color::Red
+        } else if total > budget * 0.8 {
+            Color::Yellow
+        } else {
+            Color::Green
+        };
+        let percent = if budget > 0.0 { total / budget * 100.0 } else { 100.0 };
+        lines.push(Line::from(vec![
+            Span::raw(format!("Budget: {} ", cost.format(budget))),
+            Span::styled(format!("({:.0}%)", percent), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        ]));
+    }
+    f.render_widget(Paragraph::new(Text::from(lines)), chunks[0]);
+
+    let mut costs: Vec<(&str, f64)> = app.visible_stats().values()
+        .filter(|info| !info.tunnel)
+        .map(|info| (info.name.as_str(), app.process_cost(info)))
+        .filter(|(_, cost)| *cost > 0.0)
+        .collect();
+    costs.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let rows: Vec<Row> = costs.iter()
+        .take(chunks[1].height as usize)
+        .map(|(name, amount)| Row::new(vec![
+            Cell::from(name.to_string()),
+            Cell::from(cost.format(*amount)),
+            Cell::from(format!("{:.0}%", if total > 0.0 { amount / total * 100.0 } else { 0.0 })),
+        ]))
+        .collect();
+    let table = Table::new(rows, [Constraint::Min(12), Constraint::Length(12), Constraint::Length(5)])
+        .style(Style::default().fg(Color::White));
+    f.render_widget(table, chunks[1]);
 }
 
 /// Render the bandwidth usage gauge
@@ -58,6 +168,11 @@ fn render_bandwidth_gauge(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
     let total_bandwidth = total_sent + total_received;
     let total_rate = total_sent_rate + total_received_rate;
     
+    let quota_cost = if app.cost.is_enabled() {
+        format!(" (≈ {})", app.cost.format(app.cost.cost_of(app.total_quota_threshold)))
+    } else {
+        String::new()
+    };
     let quota_ratio = (total_bandwidth as f64 / app.total_quota_threshold as f64).min(1.0);
     let quota_exceeded = total_bandwidth > app.total_quota_threshold;
     
@@ -79,10 +194,11 @@ fn render_bandwidth_gauge(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
         .gauge_style(Style::default().fg(gauge_color).bg(Color::Black))
         .percent((quota_ratio * 100.0) as u16)
         .label(format!(
-            "Used: {} | Rate: {}/s | Quota: {}", 
+            "Used: {} | Rate: {} | Quota: {}{}", 
             format_bytes(total_bandwidth),
-            format_bytes(total_rate),
-            format_bytes(app.total_quota_threshold)
+            format_rate(total_rate),
+            format_bytes(app.total_quota_threshold),
+            quota_cost
         ));
     f.render_widget(bandwidth_gauge, area);
 }
@@ -105,8 +221,8 @@ fn render_protocol_section(f: &mut Frame, app: &App, area: ratatui::layout::Rect
     
     // Split right area vertically: table top, system info bottom
     let right_chunks = Layout::vertical([
-        Constraint::Percentage(70), // Protocol table
-        Constraint::Percentage(30), // System info
+        Constraint::Percentage(65), // Protocol table
+        Constraint::Percentage(35), // System info
     ])
     .split(protocol_chunks[1]);
     
@@ -120,16 +236,17 @@ fn render_protocol_chart(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
     let total_rate = total_sent_rate + total_received_rate;
     
     // Use current rates if non-zero, otherwise fall back to last non-zero rates for display persistence
-    let (display_tcp, display_udp, display_icmp, display_other) = if total_rate > 0 {
-        (app.system_stats.tcp_rate, app.system_stats.udp_rate, 
+    let (display_tcp, display_udp, display_quic, display_icmp, display_other) = if total_rate > 0 {
+        (app.system_stats.tcp_rate, app.system_stats.udp_rate, app.system_stats.quic_rate,
          app.system_stats.icmp_rate, app.system_stats.other_rate)
     } else {
         // Use last non-zero rates with reduced opacity to indicate they're historical
         (app.last_nonzero_system_stats.tcp_rate / 4, app.last_nonzero_system_stats.udp_rate / 4,
+         app.last_nonzero_system_stats.quic_rate / 4,
          app.last_nonzero_system_stats.icmp_rate / 4, app.last_nonzero_system_stats.other_rate / 4)
     };
     
-    let max_protocol_rate = [display_tcp, display_udp, display_icmp, display_other]
+    let max_protocol_rate = [display_tcp, display_udp, display_quic, display_icmp, display_other]
         .iter().max().copied().unwrap_or(1).max(1); // Ensure minimum of 1 for scaling
     
     let bars: Vec<Bar<'_>> = vec![
@@ -143,6 +260,11 @@ fn render_protocol_chart(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
             .label(Line::from("UDP"))
             .text_value(String::new())
             .style(Style::default().fg(if total_rate > 0 { Color::Green } else { Color::DarkGray })),
+        Bar::default()
+            .value(display_quic)
+            .label(Line::from(crate::quic::LABEL))
+            .text_value(String::new())
+            .style(Style::default().fg(if total_rate > 0 { Color::LightGreen } else { Color::DarkGray })),
         Bar::default()
             .value(display_icmp)
             .label(Line::from("ICMP"))
@@ -159,7 +281,7 @@ fn render_protocol_chart(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
 
     let protocol_chart = BarChart::default()
         .data(bar_group)
-        .bar_width(12)
+        .bar_width(10)
         .bar_gap(2)
         .max(max_protocol_rate)
         .value_style(Style::default().fg(Color::Black)) // Hidden
@@ -173,15 +295,16 @@ fn render_protocol_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
     let total_rate = total_sent_rate + total_received_rate;
     
     // Use current rates if non-zero, otherwise fall back to last non-zero rates for display persistence
-    let (display_tcp, display_udp, display_icmp, display_other) = if total_rate > 0 {
-        (app.system_stats.tcp_rate, app.system_stats.udp_rate, 
+    let (display_tcp, display_udp, display_quic, display_icmp, display_other) = if total_rate > 0 {
+        (app.system_stats.tcp_rate, app.system_stats.udp_rate, app.system_stats.quic_rate,
          app.system_stats.icmp_rate, app.system_stats.other_rate)
     } else {
         (app.last_nonzero_system_stats.tcp_rate, app.last_nonzero_system_stats.udp_rate,
+         app.last_nonzero_system_stats.quic_rate,
          app.last_nonzero_system_stats.icmp_rate, app.last_nonzero_system_stats.other_rate)
     };
     
-    let display_total = display_tcp + display_udp + display_icmp + display_other;
+    let display_total = display_tcp + display_udp + display_quic + display_icmp + display_other;
     
     let protocol_rows: Vec<Row> = vec![
         Row::new(vec![
@@ -196,6 +319,12 @@ fn render_protocol_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
             Cell::from(app.system_stats.udp_packets.to_string()),
             Cell::from(format!("{:.1}%", if display_total > 0 { (display_udp as f64 / display_total as f64) * 100.0 } else { 0.0 })),
         ]),
+        Row::new(vec![
+            Cell::from(Span::styled("■ QUIC", Style::default().fg(if total_rate > 0 { Color::LightGreen } else { Color::DarkGray }).add_modifier(Modifier::BOLD))),
+            Cell::from(format_bytes(display_quic)),
+            Cell::from(app.system_stats.quic_packets.to_string()),
+            Cell::from(format!("{:.1}%", if display_total > 0 { (display_quic as f64 / display_total as f64) * 100.0 } else { 0.0 })),
+        ]),
         Row::new(vec![
             Cell::from(Span::styled("■ ICMP", Style::default().fg(if total_rate > 0 { Color::Yellow } else { Color::DarkGray }).add_modifier(Modifier::BOLD))),
             Cell::from(format_bytes(display_icmp)),
@@ -241,14 +370,65 @@ fn render_system_info(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     
     let threshold_status = if quota_exceeded { "EXCEEDED!" } else { "Normal" };
     let threshold_color = if quota_exceeded { Color::Red } else { Color::Green };
+
+    let memory_text = format!(" | Mem: {} / {}",
+        format_bytes(app.memory_usage() as u64),
+        format_bytes(app.memory_budget));
+    let evicted_text = if app.evicted_processes.is_empty() {
+        String::new()
+    } else {
+        format!(" ({} evicted)", app.evicted_processes.len())
+    };
     
-    let info_text = vec![
+    let capture_line = match app.capture_stats {
+        Some(stats) => {
+            let drop_color = if stats.dropped + stats.if_dropped > 0 { Color::Yellow } else { Color::Green };
+            Line::from(vec![
+                Span::raw(format!("Capture: {} pkts | Drops: ", stats.received)),
+                Span::styled(
+                    format!("kernel {}, iface {}", stats.dropped, stats.if_dropped),
+                    Style::default().fg(drop_color),
+                ),
+            ])
+        }
+        None if app.demo_mode => Line::from("Capture: n/a (demo)"),
+        None if app.remote_hosts.is_some() => {
+            let hosts = app.remote_hosts.as_deref().unwrap_or_default();
+            let connected = hosts.iter().filter(|host| host.connected).count();
+            let color = if connected == hosts.len() { Color::Green } else { Color::Yellow };
+            let down: Vec<&str> = hosts.iter().filter(|host| !host.connected).map(|host| host.label.as_str()).collect();
+            let mut spans = vec![
+                Span::raw("Agents: "),
+                Span::styled(format!("{}/{} connected", connected, hosts.len()), Style::default().fg(color)),
+            ];
+            if !down.is_empty() {
+                spans.push(Span::raw(format!(" | down: {}", down.join(", "))));
+            }
+            Line::from(spans)
+        }
+        None => Line::from("Capture: waiting for stats"),
+    };
+
+    let mut info_text = vec![
         Line::from(format!("Up: {} | Proc: {} | Alerts: {}", uptime_text, process_count, active_alerts)),
         Line::from(vec![
             Span::raw("Quota: "),
             Span::styled(threshold_status, Style::default().fg(threshold_color).add_modifier(Modifier::BOLD)),
+            Span::raw(memory_text),
+            Span::styled(evicted_text, Style::default().fg(Color::Yellow)),
         ]),
+        capture_line,
     ];
+    // Packets the capture counted but couldn't put down to a process or read at all
+    if !app.traffic.is_empty() {
+        let unattributed: u64 = app.traffic.protocols().iter().map(|(_, stats)| stats.unattributed).sum();
+        let unparsed: u64 = app.traffic.interfaces.iter().map(|stats| stats.unparsed).sum();
+        let color = if unparsed > 0 { Color::Yellow } else { Color::Gray };
+        info_text.push(Line::from(vec![
+            Span::raw(format!("No process: {} pkts | ", unattributed)),
+            Span::styled(format!("Unparsed: {}", unparsed), Style::default().fg(color)),
+        ]));
+    }
 
     let info_paragraph = Paragraph::new(Text::from(info_text))
         .block(Block::default().borders(Borders::TOP));
@@ -261,8 +441,7 @@ fn render_alert_progress(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
         let alert_items: Vec<ListItem> = app.alerts.iter()
             .filter_map(|(pid, alert)| {
                 if let Some(process_info) = app.stats.get(pid) {
-                    let current_usage = process_info.sent + process_info.received;
-                    let progress = (current_usage as f64 / alert.threshold_bytes as f64).min(1.0);
+                    let progress = alert.progress(process_info, app.anomalies.score(*pid)).min(1.0);
                     let progress_percent = (progress * 100.0) as usize;
                     
                     let bar_length = 15; // Reduced from 20 to save space
@@ -292,10 +471,14 @@ fn render_alert_progress(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
                         process_info.name.clone()
                     };
 
-                    Some(ListItem::new(Line::from(vec![
+                    let mut spans = vec![
                         Span::raw(format!("{:12} ", name)), // Fixed width for alignment
                         Span::styled(format!("[{}] {:3}%", bar, progress_percent), Style::default().fg(color)),
-                    ])))
+                    ];
+                    if let Some(summary) = app.alert_states.get(pid).and_then(|state| state.summary(alert)) {
+                        spans.push(Span::styled(format!(" {}", summary), Style::default().fg(Color::DarkGray)));
+                    }
+                    Some(ListItem::new(Line::from(spans)))
                 } else {
                     None
                 }
@@ -318,4 +501,36 @@ fn render_alert_progress(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
             .block(Block::default().title("Alert Thresholds").borders(Borders::ALL));
         f.render_widget(no_alerts, area);
     }
-} 
\ No newline at end of file
+} 
+/// Render the debug popup with the cost of the last socket-to-process map refresh
+fn render_scan_stats(f: &mut Frame, app: &App) {
+    let area = crate::ui::utils::centered_rect(50, 40, f.area());
+
+    let field = |label: &'static str, value: String| Line::from(vec![
+        Span::styled(format!("  {:<20}", label), Style::default().fg(Color::Cyan)),
+        Span::raw(value),
+    ]);
+    let lines = match app.scan_stats {
+        Some(stats) => vec![
+            field("Duration", format!("{:.2} ms", stats.duration.as_secs_f64() * 1000.0)),
+            field("Scan", if stats.full { "full".to_string() } else { "incremental".to_string() }),
+            field("Processes scanned", stats.processes_scanned.to_string()),
+            field("Sockets", stats.sockets.to_string()),
+            field("Opened / closed", format!("+{} / -{}", stats.added, stats.removed)),
+            field("Unowned", stats.unowned.to_string()),
+        ],
+        None if app.demo_mode => vec![Line::from("No process maps in demo mode")],
+        None if app.remote_hosts.is_some() => vec![Line::from("Process maps are kept by the agents")],
+        None => vec![Line::from("Waiting for the first refresh")],
+    };
+
+    let popup = Paragraph::new(Text::from(lines))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Process Map Refresh (every 2 s | D/Esc: close)")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
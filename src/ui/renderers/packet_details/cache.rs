@@ -34,17 +34,19 @@ pub struct PacketRenderCacheItem {
 
 /// Ensure that `app.packet_cache` contains indices of packets that satisfy the
 /// current filter & sort settings. Rebuilds the vector only when something has
-/// changed (filter, sort, history length, or selected PID).
+/// changed (filter, sort, history length or evictions, or selected PID).
 pub fn ensure_packet_cache(app: &mut App, pid: i32) {
     let Some(process_info) = app.stats.get(&pid) else {
         return;
     };
 
     let history_len = process_info.packet_history.len();
+    let history_evicted = process_info.packet_history.evicted();
 
     let cache_is_valid = if let Some(meta) = &app.packet_cache_meta {
         meta.pid == pid
             && meta.history_len == history_len
+            && meta.history_evicted == history_evicted
             && filters_equal(&meta.filter, &app.packet_filter)
             && meta.sort_column == app.packet_sort_column
             && meta.sort_direction == app.packet_sort_direction
@@ -71,6 +73,10 @@ pub fn ensure_packet_cache(app: &mut App, pid: i32) {
                 && p.direction != dir {
                     return false;
                 }
+            if let Some(port) = filter.port
+                && p.src_port != port && p.dst_port != port {
+                    return false;
+                }
             if let Some(re) = &filter.search_regex {
                 let search_text = format!(
                     "{}:{} {}:{}",
@@ -124,6 +130,7 @@ pub fn ensure_packet_cache(app: &mut App, pid: i32) {
         sort_column: app.packet_sort_column,
         sort_direction: app.packet_sort_direction,
         history_len,
+        history_evicted,
     });
 
     // --- NEW: Build the render cache based on the newly sorted indices ---
@@ -164,6 +171,7 @@ pub fn filters_equal(
             fa.protocol == fb.protocol
                 && fa.direction == fb.direction
                 && fa.search_term == fb.search_term
+                && fa.port == fb.port
                 && match (&fa.search_regex, &fb.search_regex) {
                     (None, None) => true,
                     (Some(ra), Some(rb)) => ra.as_str() == rb.as_str(),
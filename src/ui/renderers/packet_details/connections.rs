@@ -0,0 +1,82 @@
+use ratatui::{Frame, layout::Constraint, widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table}, style::{Style, Color}};
+
+use crate::types::{ConnectionsView, SocketDetails};
+
+fn or_dash<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn socket_row(socket: &SocketDetails) -> Row<'static> {
+    let protocol = if socket.protocol == 6 { "TCP" } else { "UDP" };
+    let remote = if socket.remote.port() == 0 && socket.remote.ip().is_unspecified() {
+        "*".to_string()
+    } else {
+        socket.remote.to_string()
+    };
+    // Data piling up unread or unacknowledged is the interesting part
+    let queue_style = |bytes: Option<u32>| if bytes.unwrap_or(0) > 0 {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    Row::new(vec![
+        Cell::from(protocol),
+        Cell::from(socket.local.to_string()),
+        Cell::from(remote),
+        Cell::from(socket.state.map(|state| state.label()).unwrap_or("-")),
+        Cell::from(or_dash(socket.recv_queue)).style(queue_style(socket.recv_queue)),
+        Cell::from(or_dash(socket.send_queue)).style(queue_style(socket.send_queue)),
+        Cell::from(or_dash(socket.rtt_ms.map(|rtt| format!("{:.1} ms", rtt)))),
+        Cell::from(or_dash(socket.retrans)),
+    ])
+}
+
+/// Render the popup listing every socket of the selected process
+pub fn render_connections(f: &mut Frame, view: &ConnectionsView) {
+    let area = crate::ui::utils::centered_rect(85, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let title = |count: Option<usize>| match count {
+        Some(count) => format!("Connections of PID {} ({}) | ↑/↓: scroll | C/Esc: close", view.pid, count),
+        None => format!("Connections of PID {} | C/Esc: close", view.pid),
+    };
+    let sockets = match &view.sockets {
+        Some(Ok(sockets)) => sockets,
+        Some(Err(message)) => {
+            let text = Paragraph::new(message.as_str())
+                .style(Style::default().fg(Color::Red))
+                .block(Block::default().borders(Borders::ALL).title(title(None)).style(Style::default().bg(Color::DarkGray)));
+            f.render_widget(text, area);
+            return;
+        }
+        None => {
+            let text = Paragraph::new("Reading…")
+                .block(Block::default().borders(Borders::ALL).title(title(None)).style(Style::default().bg(Color::DarkGray)));
+            f.render_widget(text, area);
+            return;
+        }
+    };
+
+    let header = Row::new(["Proto", "Local", "Remote", "State", "Recv-Q", "Send-Q", "RTT", "Retrans"].map(|h| {
+        Cell::from(h).style(Style::default().fg(Color::Cyan))
+    }));
+    let widths = [
+        Constraint::Length(5),
+        Constraint::Min(22),
+        Constraint::Min(22),
+        Constraint::Length(11),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(7),
+    ];
+    let table = Table::new(sockets.iter().skip(view.scroll).map(socket_row), widths)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title(Some(sockets.len())))
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(table, area);
+}
@@ -42,6 +42,10 @@ pub fn export_packets_to_csv(
                     && p.direction != dir {
                         return false;
                     }
+                if let Some(port) = filter.port
+                    && p.src_port != port && p.dst_port != port {
+                        return false;
+                    }
                 if let Some(re) = &filter.search_regex {
                     let search_text = format!("{}:{} {}:{}", p.src_ip, p.src_port, p.dst_ip, p.dst_port);
                     if !re.is_match(&search_text) {
@@ -68,12 +72,7 @@ pub fn export_packets_to_csv(
             PacketDirection::Sent => "Sent",
             PacketDirection::Received => "Received",
         };
-        let protocol: String = match packet.protocol {
-            6 => "TCP".to_string(),
-            17 => "UDP".to_string(),
-            1 => "ICMP".to_string(),
-            other => other.to_string(),
-        };
+        let protocol = &packet.cached_proto;
 
         writeln!(
             file,
@@ -99,4 +98,119 @@ pub fn export_packets_to_csv(
     app.export_notification_time = Some(Instant::now());
 
     Ok(())
-} 
\ No newline at end of file
+} 
+
+/// Export the packets of several processes (the marked rows) to one CSV file, oldest first
+pub fn export_combined_packets_to_csv(
+    app: &mut App,
+    pids: &[i32],
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::fs::File;
+    use std::io::Write;
+    use std::env;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let filename = format!("packets_{}_processes_{}.csv", pids.len(), timestamp);
+
+    let current_dir = env::current_dir()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| "current directory".to_string());
+
+    let mut packets: Vec<_> = pids
+        .iter()
+        .filter_map(|pid| app.stats.get(pid).map(|info| (*pid, info)))
+        .flat_map(|(pid, info)| info.packet_history.iter().map(move |packet| (pid, info.name.as_str(), packet)))
+        .collect();
+    packets.sort_by_key(|(_, _, packet)| packet.timestamp);
+
+    let mut file = File::create(&filename)?;
+
+    writeln!(
+        file,
+        "Timestamp,PID,Process,Direction,Protocol,Source_IP,Source_Port,Dest_IP,Dest_Port,Size_Bytes"
+    )?;
+
+    for (pid, name, packet) in &packets {
+        let ts: chrono::DateTime<chrono::Local> = packet.timestamp.into();
+        let direction = match packet.direction {
+            PacketDirection::Sent => "Sent",
+            PacketDirection::Received => "Received",
+        };
+        let protocol = &packet.cached_proto;
+
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{},{},{}",
+            ts.format("%Y-%m-%d %H:%M:%S%.3f"),
+            pid,
+            name.replace(',', " "),
+            direction,
+            protocol,
+            packet.src_ip,
+            packet.src_port,
+            packet.dst_ip,
+            packet.dst_port,
+            packet.size
+        )?;
+    }
+
+    let export_msg = format!(
+        "✓ Exported {} packets of {} processes to file '{}' in {}",
+        packets.len(), pids.len(), filename, current_dir
+    );
+
+    app.notice(export_msg);
+
+    Ok(())
+}
+
+/// Export the first-contact audit trail of a process to CSV file
+pub fn export_host_contacts_to_csv(
+    app: &mut App,
+    process_info: &crate::types::ProcessInfo,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::fs::File;
+    use std::io::Write;
+    use std::env;
+    use std::time::Instant;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let filename = format!("hosts_{}_{}.csv", process_info.name.replace(" ", "_"), timestamp);
+
+    let current_dir = env::current_dir()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| "current directory".to_string());
+
+    let mut file = File::create(&filename)?;
+
+    writeln!(file, "First_Seen,Host,Port,Protocol")?;
+
+    for contact in &process_info.host_contacts {
+        let ts: chrono::DateTime<chrono::Local> = contact.first_seen.into();
+        let protocol: String = match contact.protocol {
+            6 => "TCP".to_string(),
+            17 => "UDP".to_string(),
+            1 => "ICMP".to_string(),
+            other => other.to_string(),
+        };
+
+        writeln!(
+            file,
+            "{},{},{},{}",
+            ts.format("%Y-%m-%d %H:%M:%S%.3f"),
+            contact.host,
+            contact.port,
+            protocol
+        )?;
+    }
+
+    let export_msg = format!(
+        "✓ Successfully exported {} hosts to file '{}' in {}",
+        process_info.host_contacts.len(), filename, current_dir
+    );
+
+    app.export_notification_state = crate::types::NotificationState::Active(export_msg);
+    app.export_notification_time = Some(Instant::now());
+
+    Ok(())
+}
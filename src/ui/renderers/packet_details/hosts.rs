@@ -0,0 +1,66 @@
+use ratatui::{Frame, layout::{Constraint, Rect}, widgets::{Block, Borders, Cell, Row, Table}, style::{Style, Color}};
+
+use crate::types::{App, ProcessInfo};
+
+use super::utils::{format_port_with_service, get_protocol_color, NARROW_TERMINAL_THRESHOLD};
+
+fn protocol_name(protocol: u8) -> String {
+    match protocol {
+        6 => "TCP".to_string(),
+        17 => "UDP".to_string(),
+        1 => "ICMP".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Render the first-contact audit trail of the selected process, oldest first
+pub fn render_host_contacts(f: &mut Frame, app: &App, process_info: &ProcessInfo, area: Rect) {
+    let total = process_info.host_contacts.len();
+    let visible_height = area.height.saturating_sub(3) as usize; // minus borders & header
+    let scroll_offset = app.host_contact_scroll_offset.min(total.saturating_sub(1));
+
+    let header = Row::new(["First seen", "Host", "Port", "Proto"].map(|h| {
+        Cell::from(h).style(Style::default().fg(Color::Yellow))
+    }));
+
+    let rows = process_info
+        .host_contacts
+        .iter()
+        .skip(scroll_offset)
+        .take(visible_height)
+        .map(|contact| {
+            let ts: chrono::DateTime<chrono::Local> = contact.first_seen.into();
+            let proto = protocol_name(contact.protocol);
+            Row::new(vec![
+                Cell::from(ts.format("%Y-%m-%d %H:%M:%S").to_string()),
+                Cell::from(contact.host.to_string()),
+                Cell::from(format_port_with_service(contact.port)),
+                Cell::from(proto.clone()).style(Style::default().fg(get_protocol_color(&proto))),
+            ])
+        });
+
+    let constraints = [
+        Constraint::Length(20),
+        Constraint::Min(16),
+        Constraint::Length(16),
+        Constraint::Length(6),
+    ];
+
+    let title = if total == 0 {
+        "First Contacts - No remote hosts yet".to_string()
+    } else if area.width < NARROW_TERMINAL_THRESHOLD {
+        format!("First Contacts {}/{}", scroll_offset + 1, total)
+    } else {
+        format!(
+            "First Contacts - Showing {}-{} of {} hosts",
+            scroll_offset + 1,
+            (scroll_offset + visible_height).min(total),
+            total
+        )
+    };
+
+    let table = Table::new(rows, constraints)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(table, area);
+}
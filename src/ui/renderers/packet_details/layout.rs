@@ -104,10 +104,11 @@ fn build_narrow_layout<'a>(
         // Timestamp (relative)
         let timestamp = format_relative_timestamp(p.timestamp, base_time, true);
 
-        // Direction + protocol combined string e.g., ↑TCP
+        // Direction + protocol combined string e.g., ↑TCP; QUIC/HTTP3 shortened to QUIC
+        let proto = p.cached_proto.split('/').next().unwrap_or_default();
         let proto_dir_str = match p.direction {
-            PacketDirection::Sent => format!("↑{}", p.cached_proto),
-            PacketDirection::Received => format!("↓{}", p.cached_proto),
+            PacketDirection::Sent => format!("↑{}", proto),
+            PacketDirection::Received => format!("↓{}", proto),
         };
         let proto_color = get_protocol_color(&p.cached_proto);
         let proto_cell = Cell::from(Span::styled(proto_dir_str, Style::default().fg(proto_color).add_modifier(Modifier::BOLD)));
@@ -288,7 +289,7 @@ fn build_medium_layout<'a>(
     let constraints = vec![
         Constraint::Length(12),     // Timestamp
         Constraint::Length(4),      // Direction
-        Constraint::Length(8),      // Protocol
+        Constraint::Length(10),     // Protocol
         Constraint::Percentage(40), // Source
         Constraint::Percentage(40), // Destination
         Constraint::Min(8),         // Size
@@ -407,7 +408,7 @@ fn build_wide_layout<'a>(
     let constraints = vec![
         Constraint::Length(12),     // Timestamp
         Constraint::Length(10),     // Direction
-        Constraint::Length(8),      // Protocol
+        Constraint::Length(10),     // Protocol
         Constraint::Percentage(40), // Source IP
         Constraint::Percentage(40), // Destination IP
         Constraint::Min(8),         // Size
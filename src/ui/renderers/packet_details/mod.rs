@@ -2,7 +2,12 @@ pub mod utils;
 pub mod layout;
 pub mod cache;
 pub mod export;
+mod connections;
+mod hosts;
+mod process;
 mod render;
+mod sizes;
+mod tcp_info;
 
 pub use render::render;
-pub use export::export_packets_to_csv; 
\ No newline at end of file
+pub use export::{export_combined_packets_to_csv, export_host_contacts_to_csv, export_packets_to_csv}; 
\ No newline at end of file
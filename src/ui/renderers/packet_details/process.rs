@@ -0,0 +1,121 @@
+use ratatui::{Frame, layout::Rect, widgets::{Block, Borders, Paragraph}, style::{Style, Color, Modifier}, text::{Line, Span}};
+
+use crate::types::{App, SocketStates, TcpQuality, CLOSE_WAIT_WARNING, HALF_OPEN_WARNING, RETRANSMIT_WARNING_PERCENT, TIME_WAIT_WARNING};
+
+/// Height of the process pane, borders included
+pub const PROCESS_PANE_HEIGHT: u16 = 7;
+
+fn label(text: &'static str) -> Span<'static> {
+    Span::styled(text, Style::default().fg(Color::Cyan))
+}
+
+fn or_dash(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "-".to_string())
+}
+
+/// TCP socket counts by state, with the ones past their warning level in red
+fn socket_state_spans(states: &SocketStates) -> Vec<Span<'static>> {
+    let count = |name: &'static str, value: u32, warning: Option<u32>| {
+        let style = if warning.is_some_and(|limit| value >= limit) {
+            Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        Span::styled(format!("{} {}  ", value, name), style)
+    };
+    vec![
+        Span::styled(format!("TCP sockets ({}): ", states.total()), Style::default().fg(Color::Cyan)),
+        count("established", states.established, None),
+        count("listen", states.listen, None),
+        count("half-open", states.half_open, Some(HALF_OPEN_WARNING)),
+        count("TIME_WAIT", states.time_wait, Some(TIME_WAIT_WARNING)),
+        count("CLOSE_WAIT", states.close_wait, Some(CLOSE_WAIT_WARNING)),
+        count("closing", states.closing, None),
+    ]
+}
+
+/// Mean and worst RTT and retransmits of the process's TCP connections
+fn tcp_quality_spans(quality: &TcpQuality) -> Vec<Span<'static>> {
+    let ms = |value: Option<f64>| value.map_or_else(|| "-".to_string(), |ms| format!("{:.1} ms", ms));
+    let lossy = quality.retransmit_percent.is_some_and(|percent| percent >= RETRANSMIT_WARNING_PERCENT);
+    let retransmits = match quality.retransmit_percent {
+        Some(percent) => format!("{} ({:.1}% of segments sent in the last 2 s)", quality.retransmits, percent),
+        None => quality.retransmits.to_string(),
+    };
+    vec![
+        label("RTT: "),
+        Span::raw(format!("avg {}, max {}", ms(quality.avg_rtt_ms), ms(quality.max_rtt_ms))),
+        label("  Retransmits: "),
+        Span::styled(retransmits, if lossy {
+            Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        }),
+    ]
+}
+
+/// Render the command line, executable, working directory, start time and
+/// socket count of the selected process, so identical names can be told apart
+pub fn render_process_details(f: &mut Frame, app: &App, area: Rect) {
+    // The pane is full, so the categories the process's traffic fell into go in the title
+    let categories = app.selected_process
+        .and_then(|pid| app.stats.get(&pid))
+        .map(|info| info.categories())
+        .unwrap_or_default();
+    let title = if categories.is_empty() {
+        "Process".to_string()
+    } else {
+        let tags: Vec<String> = categories.iter()
+            .map(|(name, bytes)| format!("{} {}", name, crate::units::format_bytes(*bytes)))
+            .collect();
+        format!("Process · {}", tags.join(", "))
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let Some(details) = app.process_details.as_ref().filter(|d| Some(d.pid) == app.selected_process) else {
+        f.render_widget(Paragraph::new("Reading process details…").block(block), area);
+        return;
+    };
+
+    let started = details.start_time.map_or_else(
+        || "-".to_string(),
+        |time| {
+            let running = chrono::Local::now().signed_duration_since(time).num_seconds().max(0) as u64;
+            format!(
+                "{} (running {})",
+                time.format("%Y-%m-%d %H:%M:%S"),
+                crate::ui::utils::format_countdown(std::time::Duration::from_secs(running))
+            )
+        },
+    );
+    let sockets = details.socket_count.map_or_else(|| "-".to_string(), |count| count.to_string());
+
+    let mut lines = vec![
+        Line::from(vec![label("Command: "), Span::raw(or_dash(&details.cmdline))]),
+        Line::from(vec![
+            label("Exe: "),
+            Span::raw(or_dash(&details.exe)),
+            label("  Cwd: "),
+            Span::raw(or_dash(&details.cwd)),
+        ]),
+        Line::from(vec![
+            label("Started: "),
+            Span::raw(started),
+            label("  Sockets: "),
+            Span::raw(sockets),
+        ]),
+    ];
+    if let Some(info) = app.stats.get(&details.pid) {
+        lines[2].spans.extend([
+            label("  User: "),
+            Span::raw(or_dash(&info.user_name)),
+            label("  New conns/s: "),
+            Span::raw(info.new_connection_rate.to_string()),
+        ]);
+        lines.push(Line::from(socket_state_spans(&info.socket_states)));
+        // Windows exposes no TCP_INFO, so there is nothing to show there
+        if cfg!(target_os = "linux") {
+            lines.push(Line::from(tcp_quality_spans(&info.tcp_quality)));
+        }
+    }
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
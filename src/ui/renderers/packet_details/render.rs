@@ -1,8 +1,9 @@
-use ratatui::{Frame, layout::{Constraint, Layout}, widgets::{Block, Borders, Paragraph, Table, Wrap}, style::{Style, Color}, text::{Span, Line}};
+use ratatui::{Frame, layout::{Constraint, Layout, Rect}, widgets::{Block, Borders, Paragraph, Table, Wrap}, style::{Style, Color}, text::{Span, Line}};
 
 use crate::types::{App, PacketDirection, PacketSortColumn, PacketSortDirection};
+use crate::units::format_bytes;
 
-use super::{cache::ensure_packet_cache, layout::build_responsive_table_data, utils::*};
+use super::{cache::ensure_packet_cache, connections::render_connections, hosts::render_host_contacts, layout::build_responsive_table_data, process::{render_process_details, PROCESS_PANE_HEIGHT}, sizes::{packet_rate, render_size_histogram, SIZE_HISTOGRAM_WIDTH}, tcp_info::render_tcp_info, utils::*};
 
 /// Render per-packet details for the selected process
 pub fn render(f: &mut Frame, app: &mut App) {
@@ -29,6 +30,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
     let chunks = if app.packet_search_mode {
         Layout::vertical([
             Constraint::Length(3), // Status/help line
+            Constraint::Length(PROCESS_PANE_HEIGHT), // Process metadata
             Constraint::Length(3), // Search input bar
             Constraint::Min(0),    // Main table
             Constraint::Length(export_footer_height), // Export notification (dynamic)
@@ -37,6 +39,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
     } else {
         Layout::vertical([
             Constraint::Length(3), // Status/help line
+            Constraint::Length(PROCESS_PANE_HEIGHT), // Process metadata
             Constraint::Min(0),    // Main table
             Constraint::Length(export_footer_height), // Export notification (dynamic)
         ])
@@ -87,6 +90,9 @@ pub fn render(f: &mut Frame, app: &mut App) {
                 PacketDirection::Received => "Received".into(),
             });
         }
+        if let Some(port) = filter.port {
+            parts.push(format!("Port {}", port));
+        }
         if let Some(search) = &filter.search_term {
             parts.push(format!("\"{}\"", search));
         }
@@ -117,9 +123,31 @@ pub fn render(f: &mut Frame, app: &mut App) {
         format!("Sort: {}{} | ", column_name, direction_arrow)
     };
 
-    let header_title = format!("Packet Details - {} (PID {})", process_info.name, pid);
+    // Packets the ring buffer or the global packet budget already dropped
+    let evicted = process_info.packet_history.evicted();
+    let evicted_info = if evicted > 0 {
+        format!(" | Evicted: {}", evicted)
+    } else {
+        String::new()
+    };
+
+    let header_title = format!(
+        "Packet Details - {} (PID {}) | IPv4: {} IPv6: {} | {:.0} pkt/s{}",
+        process_info.name,
+        pid,
+        format_bytes(process_info.ipv4_bytes),
+        format_bytes(process_info.ipv6_bytes),
+        packet_rate(process_info),
+        evicted_info
+    );
 
-    let status_text = if filtered_count == 0 {
+    let status_text = if app.show_host_contacts {
+        if terminal_width < NARROW_TERMINAL_THRESHOLD {
+            "↑↓:scroll e:export h/Esc:packets".to_string()
+        } else {
+            "Controls: ↑↓:scroll PgUp/PgDn:page e:export hosts h/Esc:back to packets".to_string()
+        }
+    } else if filtered_count == 0 {
         if total_packets == 0 {
             "Network activity will appear here in real-time.".to_string()
         } else {
@@ -127,15 +155,15 @@ pub fn render(f: &mut Frame, app: &mut App) {
         }
     } else {
         if terminal_width < NARROW_TERMINAL_THRESHOLD {
-            format!("{}{}↑↓:scroll /:search e:export Esc:back", filter_info, sort_info)
+            format!("{}{}↑↓:scroll /:search e:export h:hosts ?:help Esc:back", filter_info, sort_info)
         } else {
-            format!("{}{}Controls: ↑↓:scroll PgUp/PgDn:page 1-6:sort /:search e:export Esc:back", filter_info, sort_info)
+            format!("{}{}Controls: ↑↓:scroll PgUp/PgDn:page 1-8:sort /:search P:port e:export h:hosts T:tcp info C:connections ?:help Esc:back", filter_info, sort_info)
         }
     };
 
     let status = Paragraph::new(Line::from(vec![Span::styled(
         status_text,
-        Style::default().fg(if filtered_count == 0 {
+        Style::default().fg(if filtered_count == 0 && !app.show_host_contacts {
             Color::Yellow
         } else {
             Color::Cyan
@@ -146,6 +174,17 @@ pub fn render(f: &mut Frame, app: &mut App) {
     f.render_widget(status, chunks[chunk_idx]);
     chunk_idx += 1;
 
+    // The size histogram takes the right end of the process pane where there is room
+    if terminal_width >= WIDE_TERMINAL_THRESHOLD {
+        let pane = Layout::horizontal([Constraint::Min(0), Constraint::Length(SIZE_HISTOGRAM_WIDTH)])
+            .split(chunks[chunk_idx]);
+        render_process_details(f, app, pane[0]);
+        render_size_histogram(f, process_info, pane[1]);
+    } else {
+        render_process_details(f, app, chunks[chunk_idx]);
+    }
+    chunk_idx += 1;
+
     // Render search input if enabled
     if app.packet_search_mode {
         let search_text = format!("Search: {}", app.packet_search_input);
@@ -156,8 +195,16 @@ pub fn render(f: &mut Frame, app: &mut App) {
         chunk_idx += 1;
     }
 
+    if app.show_host_contacts {
+        app.packet_visible_rows = chunks[chunk_idx].height.saturating_sub(3) as usize;
+        render_host_contacts(f, app, process_info, chunks[chunk_idx]);
+        render_export_footer(f, app, chunks[chunk_idx + 1]);
+        return;
+    }
+
     // No packets? stop early.
     if filtered_count == 0 {
+        render_popups(f, app);
         return;
     }
 
@@ -199,29 +246,41 @@ pub fn render(f: &mut Frame, app: &mut App) {
 
     f.render_widget(table, chunks[chunk_idx]);
 
-    // --------------------------------
-    //  Export notification footer
-    // --------------------------------
+    let export_notification_index = if app.packet_search_mode { 4 } else { 3 };
+    render_export_footer(f, app, chunks[export_notification_index]);
+
+    render_popups(f, app);
+}
 
-    let export_notification_index = if app.packet_search_mode { 3 } else { 2 };
+/// Render the TCP info or connections popup over the packet list, if open
+fn render_popups(f: &mut Frame, app: &App) {
+    if let Some(view) = &app.tcp_info {
+        render_tcp_info(f, view);
+    }
+    if let Some(view) = &app.connections {
+        render_connections(f, view);
+    }
+}
 
+/// Render the export status footer shared by the packet and host tables
+fn render_export_footer(f: &mut Frame, app: &App, area: Rect) {
     match &app.export_notification_state {
         crate::types::NotificationState::Active(export_msg) => {
             let export_notification = Paragraph::new(export_msg.clone())
                 .style(Style::default().fg(Color::Green))
                 .wrap(Wrap { trim: true })
                 .block(Block::default().title("Export Status").borders(Borders::ALL).border_style(Style::default().fg(Color::Green)));
-            f.render_widget(export_notification, chunks[export_notification_index]);
+            f.render_widget(export_notification, area);
         }
         crate::types::NotificationState::Expiring => {
             let fading_notification = Paragraph::new("Notification clearing...")
                 .style(Style::default().fg(Color::DarkGray))
                 .block(Block::default().borders(Borders::NONE));
-            f.render_widget(fading_notification, chunks[export_notification_index]);
+            f.render_widget(fading_notification, area);
         }
         crate::types::NotificationState::None => {
             let empty_footer = Paragraph::new("").block(Block::default().borders(Borders::NONE));
-            f.render_widget(empty_footer, chunks[export_notification_index]);
+            f.render_widget(empty_footer, area);
         }
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,45 @@
+use std::time::{Duration, SystemTime};
+
+use ratatui::{Frame, layout::Rect, widgets::{Bar, BarChart, BarGroup, Block, Borders}, style::{Style, Color}, text::Line};
+
+use crate::packet_history::SIZE_BUCKETS;
+use crate::types::ProcessInfo;
+
+/// Width of the histogram next to the process pane: seven bars of five columns plus gaps and borders
+pub const SIZE_HISTOGRAM_WIDTH: u16 = 45;
+
+/// Window the packets-per-second figure averages over
+const PACKET_RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Packets per second of the process over the last few seconds
+pub fn packet_rate(info: &ProcessInfo) -> f64 {
+    info.packet_history.packet_rate(SystemTime::now(), PACKET_RATE_WINDOW)
+}
+
+/// Render how the held packets of the process spread over sizes: many small
+/// packets point to a chatty protocol, a tall right end to bulk transfers
+pub fn render_size_histogram(f: &mut Frame, info: &ProcessInfo, area: Rect) {
+    let counts = info.packet_history.size_histogram();
+    let total: u64 = counts.iter().sum();
+    let bars: Vec<Bar> = SIZE_BUCKETS.iter().zip(counts)
+        .map(|((_, label), count)| {
+            let percent = (count * 100).checked_div(total).unwrap_or(0);
+            Bar::default()
+                .value(count)
+                .label(Line::from(*label))
+                .text_value(format!("{}%", percent))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .block(Block::default()
+            .title(format!("Sizes | {:.0} pkt/s", packet_rate(info)))
+            .borders(Borders::ALL))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(5)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Cyan))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Cyan))
+        .label_style(Style::default().fg(Color::White));
+    f.render_widget(chart, area);
+}
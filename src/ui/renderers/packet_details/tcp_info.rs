@@ -0,0 +1,58 @@
+use ratatui::{Frame, widgets::{Block, Borders, Clear, Paragraph}, style::{Style, Color, Modifier}, text::{Line, Span, Text}};
+
+use crate::types::{TcpCongestionInfo, TcpInfoView};
+
+fn field<T: ToString>(label: &'static str, value: Option<T>) -> Line<'static> {
+    let value = value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+    Line::from(vec![
+        Span::styled(format!("  {:<15}", label), Style::default().fg(Color::Cyan)),
+        Span::raw(value),
+    ])
+}
+
+fn info_lines(info: &TcpCongestionInfo) -> Vec<Line<'static>> {
+    vec![
+        field("Algorithm", info.algorithm.clone()),
+        field("cwnd", info.cwnd.map(|cwnd| format!("{} segments", cwnd))),
+        field("ssthresh", info.ssthresh),
+        field("MSS", info.mss.map(|mss| format!("{} B", mss))),
+        field("RTT", info.rtt_ms.map(|rtt| format!("{:.2} ms", rtt))),
+        field("Send rate", info.send_rate.clone()),
+        field("Pacing rate", info.pacing_rate.clone()),
+        field("Delivery rate", info.delivery_rate.clone()),
+        field("Retransmits", info.retrans.clone()),
+    ]
+}
+
+/// Render the congestion control popup for the connection under the top packet row
+pub fn render_tcp_info(f: &mut Frame, view: &TcpInfoView) {
+    let area = crate::ui::utils::centered_rect(60, 50, f.area());
+
+    let mut lines = Vec::new();
+    if let Some((local, remote)) = view.connection {
+        lines.push(Line::from(Span::styled(
+            format!("{} → {}", local, remote),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(""));
+    }
+    match &view.info {
+        Some(Ok(info)) => lines.extend(info_lines(info)),
+        Some(Err(message)) => lines.push(Line::from(Span::styled(message.clone(), Style::default().fg(Color::Red)))),
+        None if view.connection.is_none() => lines.push(Line::from(Span::styled(
+            "No TCP packet at or below the top row (t filters TCP)",
+            Style::default().fg(Color::Yellow),
+        ))),
+        None => lines.push(Line::from("Reading…")),
+    }
+
+    let popup = Paragraph::new(Text::from(lines))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("TCP Info (updates every second | T/Esc: close)")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
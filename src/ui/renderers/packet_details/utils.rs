@@ -56,6 +56,7 @@ pub fn get_protocol_color(protocol: &str) -> Color {
     match protocol {
         "TCP" => Color::Red,
         "UDP" => Color::Green,
+        crate::quic::LABEL => Color::LightGreen,
         "ICMP" => Color::Yellow,
         _ => Color::White,
     }
@@ -0,0 +1,40 @@
+use ratatui::{
+    widgets::{Block, Borders, Clear, Paragraph},
+    style::{Style, Color},
+    text::{Line, Span, Text},
+    Frame
+};
+use crate::types::{App, AppMode};
+
+/// Render the port filter prompt, for the process table or the packet list
+pub fn render(f: &mut Frame, app: &App) {
+    let Some(input) = app.port_input.as_deref() else { return };
+    let area = crate::ui::utils::centered_rect(50, 20, f.area());
+    let applies_to = if app.mode == AppMode::PacketDetails {
+        "Shows only packets from or to this port"
+    } else {
+        "Lists only processes with traffic on this port"
+    };
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("🔌 ", Style::default().fg(Color::Yellow)),
+            Span::raw(input),
+            Span::styled("_", Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(format!("{}; leave empty to show all", applies_to), Style::default().fg(Color::Gray))),
+        Line::from(Span::styled("Enter: apply | Esc: cancel", Style::default().fg(Color::Gray))),
+    ];
+
+    let prompt = Paragraph::new(Text::from(lines))
+        .wrap(ratatui::widgets::Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Filter by Port")
+                .style(Style::default().bg(Color::DarkGray))
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
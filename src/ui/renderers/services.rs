@@ -0,0 +1,84 @@
+use ratatui::{
+    widgets::{Block, Borders, Paragraph, Table, Row, Cell},
+    layout::{Layout, Constraint},
+    style::{Style, Color, Modifier},
+    Frame
+};
+use crate::types::{App, ServiceTraffic};
+use crate::units::{format_bytes, format_rate};
+
+/// Render the services view: inbound traffic grouped by local listening port
+pub fn render(f: &mut Frame, app: &App) {
+    let main_chunks = Layout::vertical([
+        Constraint::Length(3), // Title (header with navigation)
+        Constraint::Min(0),    // Service table
+    ])
+    .margin(1)
+    .split(f.area());
+
+    render_title(f, main_chunks[0]);
+    render_service_table(f, app, main_chunks[1]);
+}
+
+/// Render the title header with navigation inside
+fn render_title(f: &mut Frame, area: ratatui::layout::Rect) {
+    let block = Block::default().title("Services (traffic by listening port)").borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let nav_text = "q: quit | Tab: switch mode | ↑/↓: scroll services | Esc: back to main | ?: help";
+    f.render_widget(Paragraph::new(nav_text), inner);
+}
+
+/// Render every service that saw traffic, largest volume first
+fn render_service_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let block = Block::default().title(format!("Services ({})", app.services.len())).borders(Borders::ALL);
+    if app.services.is_empty() {
+        let text = Paragraph::new("No traffic on listening ports yet.")
+            .style(Style::default().fg(Color::Yellow))
+            .block(block);
+        f.render_widget(text, area);
+        return;
+    }
+
+    let header = Row::new(["Service", "Process", "Received", "Sent", "In/s", "Out/s", "Connections", "Workers"].map(|h| {
+        Cell::from(h).style(Style::default().fg(Color::Red))
+    }));
+
+    let mut services: Vec<&ServiceTraffic> = app.services.iter().collect();
+    services.sort_by(|a, b| {
+        (b.sent + b.received).cmp(&(a.sent + a.received)).then(a.port.cmp(&b.port)).then(a.protocol.cmp(&b.protocol))
+    });
+
+    let rows = services.iter().skip(app.service_scroll_offset).map(|service| {
+        let active = service.sent_rate > 0 || service.received_rate > 0;
+        let style = if active { Style::default().add_modifier(Modifier::BOLD) } else { Style::default() };
+        let protocol = if service.protocol == 6 { "tcp" } else { "udp" };
+        Row::new(vec![
+            Cell::from(format!(":{}/{}", service.port, protocol)).style(Style::default().fg(Color::Cyan)),
+            Cell::from(format!("{} ({})", service.name, service.pid)),
+            Cell::from(format_bytes(service.received)),
+            Cell::from(format_bytes(service.sent)),
+            Cell::from(format_rate(service.received_rate)),
+            Cell::from(format_rate(service.sent_rate)),
+            Cell::from(service.connections.to_string()),
+            Cell::from(service.workers.to_string()),
+        ])
+        .style(style)
+    });
+
+    let widths = [
+        Constraint::Length(12),
+        Constraint::Min(20),
+        Constraint::Length(11),
+        Constraint::Length(11),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(8),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(block);
+    f.render_widget(table, area);
+}
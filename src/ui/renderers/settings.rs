@@ -7,6 +7,7 @@ use ratatui::{
 };
 use crate::types::App;
 use crate::config::load_config;
+use crate::ui::settings_fields;
 
 /// Render the settings mode for configuration management
 pub fn render(f: &mut Frame, app: &App) {
@@ -43,7 +44,7 @@ fn render_title(f: &mut Frame, area: ratatui::layout::Rect) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let nav_text = "q: quit | Tab: switch mode | r: remove config | Esc: back to main";
+    let nav_text = "q: quit | Tab: switch mode | r: remove config | Esc: back to main | ?: help";
     let nav_paragraph = Paragraph::new(nav_text);
     f.render_widget(nav_paragraph, inner);
 }
@@ -61,78 +62,52 @@ fn render_settings_content(f: &mut Frame, app: &App, area: ratatui::layout::Rect
     render_available_actions(f, chunks[1]);
 }
 
-/// Render current configuration information
+/// Render every setting under its section heading, scrolled to keep the selected one in view
 fn render_current_config(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let config_info = if let Some(config) = load_config() {
-        let mut lines = vec![
-            Line::from(vec![
-                Span::styled("📡 Interface: ", Style::default().fg(Color::Cyan)),
-                Span::raw(config.interface.clone()),
-            ]),
-            Line::from(vec![
-                Span::styled("📊 Mode: ", Style::default().fg(Color::Cyan)),
-                Span::raw(if config.json_mode { "JSON output" } else { "Interactive TUI" }),
-            ]),
-            Line::from(vec![
-                Span::styled("🐳 Container awareness: ", Style::default().fg(Color::Cyan)),
-                Span::raw(if config.containers_mode { "Enabled" } else { "Disabled" }),
-            ]),
-            Line::from(vec![
-                Span::styled("📈 Show total columns: ", Style::default().fg(Color::Cyan)),
-                Span::raw(if config.show_total_columns { "Yes" } else { "No" }),
-            ]),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("🔔 Active alerts: ", Style::default().fg(Color::Yellow)),
-                Span::raw(format!("{}", config.alerts.len())),
-            ]),
-            Line::from(""),
-            Line::from("Highlighting thresholds:"),
-        ];
-
-        let large_packet_style = if app.settings_selected_option == 0 {
-            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
-        } else {
-            Style::default()
-        };
+    let saved = load_config();
+    let mut lines = Vec::new();
+    if saved.is_none() {
         lines.push(Line::from(Span::styled(
-            format!("  Large packet: {} bytes", config.large_packet_threshold),
-            large_packet_style,
+            "No saved configuration yet: changing a setting creates one",
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::ITALIC)
         )));
+        lines.push(Line::from(""));
+    }
+    let config = saved.unwrap_or_else(|| settings_fields::session_config(app));
 
-        let frequent_conn_style = if app.settings_selected_option == 1 {
-            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
-        } else {
-            Style::default()
-        };
-        lines.push(Line::from(Span::styled(
-            format!("  Frequent connection: {} packets", config.frequent_connection_threshold),
-            frequent_conn_style,
-        )));
+    let mut selected_line = 0;
+    let mut index = 0;
+    for (section, settings) in settings_fields::SECTIONS {
+        lines.push(Line::from(Span::styled(format!("{}:", section), Style::default().fg(Color::Cyan))));
+        for setting in settings {
+            let style = if index == app.settings_selected_option {
+                selected_line = lines.len();
+                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let mut spans = vec![Span::styled(format!("  {}: {}", setting.label(), setting.value(&config)), style)];
+            if !setting.is_live() {
+                spans.push(Span::styled(" (next start)", Style::default().fg(Color::DarkGray)));
+            }
+            lines.push(Line::from(spans));
+            index += 1;
+        }
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(vec![
+        Span::styled("🔔 Saved alerts: ", Style::default().fg(Color::Yellow)),
+        Span::raw(format!("{}", config.alerts.len())),
+    ]));
 
-        lines
-    } else {
-        vec![
-            Line::from(Span::styled(
-                "No saved configuration found",
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::ITALIC)
-            )),
-            Line::from(""),
-            Line::from("Current session settings:"),
-            Line::from(vec![
-                Span::styled("🐳 Container awareness: ", Style::default().fg(Color::Cyan)),
-                Span::raw(if app.containers_mode { "Enabled" } else { "Disabled" }),
-            ]),
-            Line::from(vec![
-                Span::styled("📈 Show total columns: ", Style::default().fg(Color::Cyan)),
-                Span::raw(if app.show_total_columns { "Yes" } else { "No" }),
-            ]),
-        ]
-    };
+    // Keep the selected row on screen, with its section heading when it is the first row
+    let visible = area.height.saturating_sub(2) as usize;
+    let scroll = (selected_line + 2).saturating_sub(visible).min(selected_line.saturating_sub(1));
 
-    let config_widget = Paragraph::new(config_info)
+    let config_widget = Paragraph::new(lines)
         .block(Block::default().title("Current Configuration").borders(Borders::ALL))
-        .alignment(Alignment::Left);
+        .alignment(Alignment::Left)
+        .scroll((scroll as u16, 0));
     f.render_widget(config_widget, area);
 }
 
@@ -147,6 +122,10 @@ fn render_available_actions(f: &mut Frame, area: ratatui::layout::Rect) {
             Span::styled("←/→", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
             Span::raw(" - Adjust selected setting"),
         ]),
+        Line::from(vec![
+            Span::styled("Enter", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            Span::raw(" - Pick the interface from a list"),
+        ]),
         Line::from(""),
         Line::from(vec![
             Span::styled("r", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
@@ -164,11 +143,12 @@ fn render_available_actions(f: &mut Frame, area: ratatui::layout::Rect) {
         Line::from(vec![
             Span::styled("Tips:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
         ]),
-        Line::from("• Configuration is auto-saved when you"),
-        Line::from("  complete the guided setup"),
+        Line::from("• Every change is saved to the config"),
+        Line::from("  right away and applies to this session;"),
+        Line::from("  \"(next start)\" settings wait for a restart"),
+        Line::from("• A new interface is captured on at once"),
+        Line::from("  and used again at the next start"),
         Line::from("• Alerts persist between sessions"),
-        Line::from("• Reset if you want to change interface"),
-        Line::from("  or other core settings"),
     ];
 
     let actions_widget = Paragraph::new(actions)
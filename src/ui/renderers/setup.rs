@@ -0,0 +1,147 @@
+use ratatui::{
+    widgets::{Block, Borders, Paragraph},
+    layout::{Layout, Constraint, Rect},
+    style::{Style, Color, Modifier},
+    text::{Line, Span},
+    Frame
+};
+use crate::interactive::{SetupOption, SetupStep, SetupWizard, MEASURE_DURATION_SECS};
+use crate::units::format_bytes;
+
+/// Cells in a full traffic bar
+const BAR_WIDTH: usize = 20;
+
+/// Render the first-run setup wizard, which runs before the monitoring TUI starts
+pub fn render(f: &mut Frame, wizard: &SetupWizard) {
+    let chunks = Layout::vertical([
+        Constraint::Length(3), // Title
+        Constraint::Min(0),    // Step
+        Constraint::Length(3), // Keys
+    ])
+    .margin(1)
+    .split(f.area());
+
+    let step = match wizard.step {
+        SetupStep::Interface => "Step 1 of 2: choose the interface to monitor",
+        SetupStep::Options => "Step 2 of 2: options",
+    };
+    let title = Paragraph::new(step)
+        .block(Block::default().title("Welcome to Monitetoring - Setup").borders(Borders::ALL));
+    f.render_widget(title, chunks[0]);
+
+    let keys = match wizard.step {
+        SetupStep::Interface if wizard.measuring() => "↑/↓: choose | Enter: next | s: skip measuring | q/Esc: quit",
+        SetupStep::Interface => "↑/↓: choose | Enter: next | q/Esc: quit",
+        SetupStep::Options => "↑/↓: choose | Space: toggle | Enter: start monitoring | Esc: back | q: quit",
+    };
+    f.render_widget(Paragraph::new(keys).block(Block::default().borders(Borders::ALL)), chunks[2]);
+
+    match wizard.step {
+        SetupStep::Interface => render_interfaces(f, wizard, chunks[1]),
+        SetupStep::Options => render_options(f, wizard, chunks[1]),
+    }
+}
+
+/// Interfaces with a bar of the traffic each one saw, relative to the busiest
+fn render_interfaces(f: &mut Frame, wizard: &SetupWizard, area: Rect) {
+    let picker = &wizard.picker;
+    let dim = Style::default().fg(Color::Gray);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            if wizard.measuring() {
+                let left = picker.measured_by.saturating_duration_since(std::time::Instant::now());
+                format!("Measuring traffic for up to {} seconds to find the busiest interface… ({}s left)", MEASURE_DURATION_SECS, left.as_secs())
+            } else {
+                "Bars show the traffic each interface saw while measuring".to_string()
+            },
+            dim,
+        )),
+        Line::from(""),
+    ];
+
+    let busiest = picker.choices.iter().filter_map(|choice| choice.traffic_bytes).max().unwrap_or(0).max(1);
+    let name_width = picker.choices.iter().map(|choice| choice.name.chars().count()).max().unwrap_or(0);
+    let recommended = wizard.recommended();
+    let first_row = lines.len();
+    for (i, choice) in picker.choices.iter().enumerate() {
+        let selected = i == picker.selected;
+        let (status, status_color) = if choice.is_up { ("up  ", Color::Green) } else { ("down", Color::Red) };
+        let (bar, traffic) = match choice.traffic_bytes {
+            Some(bytes) => {
+                let filled = (bytes as f64 / busiest as f64 * BAR_WIDTH as f64).ceil() as usize;
+                (format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled)), format_bytes(bytes))
+            }
+            None if wizard.measuring() && choice.is_up => ("░".repeat(BAR_WIDTH), "…".to_string()),
+            None => (" ".repeat(BAR_WIDTH), String::new()),
+        };
+        let name_style = if selected {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![
+            Span::styled(if selected { "> " } else { "  " }, name_style),
+            Span::styled(status, Style::default().fg(status_color)),
+            Span::raw(" "),
+            Span::styled(format!("{:<width$}", choice.name, width = name_width), name_style),
+            Span::raw("  "),
+            Span::styled(bar, Style::default().fg(Color::Blue)),
+            Span::raw(format!(" {:>10}", traffic)),
+        ];
+        if Some(i) == recommended {
+            spans.push(Span::styled(" (recommended)", Style::default().fg(Color::Green)));
+        }
+        spans.push(Span::styled(format!("  {}", choice.description.as_deref().unwrap_or("No description")), dim));
+        lines.push(Line::from(spans));
+    }
+    if picker.choices.is_empty() {
+        lines.push(Line::from(Span::styled("No interfaces found", Style::default().fg(Color::Red))));
+    }
+    if wizard.confirm_down && let Some(choice) = picker.choices.get(picker.selected) {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("⚠️  {} appears to be down. Press Enter again to use it anyway.", choice.name),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+
+    // Keep the selected interface in view on long lists
+    let visible = area.height.saturating_sub(2) as usize;
+    let selected_line = first_row + picker.selected;
+    let scroll = (selected_line + 1).saturating_sub(visible);
+
+    let list = Paragraph::new(lines)
+        .block(Block::default().title("Network Interfaces").borders(Borders::ALL))
+        .scroll((scroll as u16, 0));
+    f.render_widget(list, area);
+}
+
+/// Checkboxes for the remaining choices, under the chosen interface
+fn render_options(f: &mut Frame, wizard: &SetupWizard, area: Rect) {
+    let interface = wizard.picker.choices.get(wizard.picker.selected).map_or("", |choice| choice.name.as_str());
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Interface: ", Style::default().fg(Color::Cyan)),
+            Span::raw(interface),
+        ]),
+        Line::from(""),
+    ];
+    for (i, option) in SetupOption::ALL.into_iter().enumerate() {
+        let checkbox = if wizard.is_checked(option) && option.available() { "[x]" } else { "[ ]" };
+        let style = if !option.available() {
+            Style::default().fg(Color::DarkGray)
+        } else if i == wizard.option_selected {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let marker = if i == wizard.option_selected { "> " } else { "  " };
+        lines.push(Line::from(Span::styled(format!("{}{} {}", marker, checkbox, option.label()), style)));
+        lines.push(Line::from(Span::styled(format!("      {}", option.detail()), Style::default().fg(Color::Gray))));
+        lines.push(Line::from(""));
+    }
+
+    let options = Paragraph::new(lines)
+        .block(Block::default().title("Options").borders(Borders::ALL));
+    f.render_widget(options, area);
+}
@@ -0,0 +1,325 @@
+// The rows of the Settings view. Each one shows a value of the saved config;
+// ←/→ step it, save the config (creating it from the running session when
+// there is none yet) and apply the change to this session where that works.
+// Settings only read at startup say so next to their value.
+
+use crate::accounting::ByteCounting;
+use crate::config::SavedConfig;
+use crate::types::App;
+use crate::units::{format_bytes, ByteUnits};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Setting {
+    Interface,
+    Containers,
+    CountBytes,
+    RefreshInterval,
+    Units,
+    BitRates,
+    HeatRows,
+    TotalColumns,
+    HistoryWindow,
+    SampleInterval,
+    MemoryBudget,
+    Quota,
+    LargePacket,
+    FrequentConnection,
+    AnomalyEnabled,
+    AnomalySigma,
+    KeepExited,
+    IdleAfter,
+}
+
+/// Settings grouped under the headings the view shows
+pub const SECTIONS: [(&str, &[Setting]); 7] = [
+    ("Capture", &[Setting::Interface, Setting::Containers, Setting::CountBytes]),
+    ("Display", &[Setting::RefreshInterval, Setting::Units, Setting::BitRates, Setting::HeatRows, Setting::TotalColumns]),
+    ("History", &[Setting::HistoryWindow, Setting::SampleInterval, Setting::MemoryBudget]),
+    ("Quota", &[Setting::Quota]),
+    ("Highlighting thresholds", &[Setting::LargePacket, Setting::FrequentConnection]),
+    ("Anomaly detection", &[Setting::AnomalyEnabled, Setting::AnomalySigma]),
+    ("Processes", &[Setting::KeepExited, Setting::IdleAfter]),
+];
+
+const MB: u64 = 1024 * 1024;
+
+/// Every setting in the order the view lists them
+pub fn all() -> impl Iterator<Item = Setting> {
+    SECTIONS.iter().flat_map(|(_, settings)| settings.iter().copied())
+}
+
+pub fn count() -> usize {
+    all().count()
+}
+
+pub fn nth(index: usize) -> Option<Setting> {
+    all().nth(index)
+}
+
+fn on_off(on: bool) -> String {
+    if on { "On" } else { "Off" }.to_string()
+}
+
+impl Setting {
+    pub fn label(self) -> &'static str {
+        match self {
+            Setting::Interface => "Interface",
+            Setting::Containers => "Container awareness",
+            Setting::CountBytes => "Process traffic counts",
+            Setting::RefreshInterval => "Refresh interval",
+            Setting::Units => "Byte units",
+            Setting::BitRates => "Rates in bits per second",
+            Setting::HeatRows => "Heat coloring",
+            Setting::TotalColumns => "Total columns",
+            Setting::HistoryWindow => "History window",
+            Setting::SampleInterval => "Sample interval",
+            Setting::MemoryBudget => "Memory budget",
+            Setting::Quota => "Data quota",
+            Setting::LargePacket => "Large packet",
+            Setting::FrequentConnection => "Frequent connection",
+            Setting::AnomalyEnabled => "Highlight unusual traffic",
+            Setting::AnomalySigma => "Sensitivity",
+            Setting::KeepExited => "Keep exited processes",
+            Setting::IdleAfter => "Idle after",
+        }
+    }
+
+    pub fn value(self, config: &SavedConfig) -> String {
+        match self {
+            Setting::Interface => config.interface.clone(),
+            Setting::Containers => on_off(config.containers_mode),
+            Setting::CountBytes => match config.count_bytes {
+                ByteCounting::Wire => "Wire bytes (headers included)".to_string(),
+                ByteCounting::Payload => "Payload bytes (TCP/UDP payload only)".to_string(),
+            },
+            Setting::RefreshInterval => format!("{} ms", config.refresh_interval_ms),
+            Setting::Units => match config.units.bytes {
+                ByteUnits::Binary => "Binary (1 KB = 1024 B)".to_string(),
+                ByteUnits::Iec => "IEC (KiB, MiB)".to_string(),
+                ByteUnits::Si => "SI (1 KB = 1000 B)".to_string(),
+            },
+            Setting::BitRates => on_off(config.units.bit_rates),
+            Setting::HeatRows => {
+                let [low, mid, high] = config.theme.heat_thresholds;
+                format!("{} (at {}% / {}% / {}% of total bandwidth)", on_off(config.theme.heat_rows), low, mid, high)
+            }
+            Setting::TotalColumns => on_off(config.show_total_columns),
+            Setting::HistoryWindow => format!("{} s", config.history_window_secs),
+            Setting::SampleInterval => format!("{} ms", config.sample_interval_ms),
+            Setting::MemoryBudget => format!("{} MB", config.memory_budget_mb),
+            Setting::Quota => format_bytes(config.quota_mb * MB),
+            Setting::LargePacket => format!("{} bytes", config.large_packet_threshold),
+            Setting::FrequentConnection => format!("{} packets", config.frequent_connection_threshold),
+            Setting::AnomalyEnabled => on_off(config.anomaly.enabled),
+            Setting::AnomalySigma => format!("{:.1}σ above the learned baseline", config.anomaly.sigma),
+            Setting::KeepExited => match config.exited_retention_secs {
+                0 => "Off (removed once exited)".to_string(),
+                secs => format!("{} s", secs),
+            },
+            Setting::IdleAfter => format!("{} s without traffic", config.idle_after_secs),
+        }
+    }
+
+    /// Whether a change reaches the running session; the others wait for the next start
+    pub fn is_live(self) -> bool {
+        !matches!(self, Setting::Containers | Setting::CountBytes)
+    }
+
+    /// Step the setting one notch down (`up == false`) or up; toggles flip either way
+    pub fn adjust(self, config: &mut SavedConfig, up: bool) {
+        fn step(value: u64, by: u64, min: u64, max: u64, up: bool) -> u64 {
+            let value = if up { value.saturating_add(by) } else { value.saturating_sub(by) };
+            value.clamp(min, max)
+        }
+        match self {
+            // Picked from the interface list instead
+            Setting::Interface => {}
+            Setting::Containers => config.containers_mode = !config.containers_mode,
+            Setting::CountBytes => {
+                config.count_bytes = match config.count_bytes {
+                    ByteCounting::Wire => ByteCounting::Payload,
+                    ByteCounting::Payload => ByteCounting::Wire,
+                };
+            }
+            Setting::RefreshInterval => config.refresh_interval_ms = step(config.refresh_interval_ms, 50, 50, 2000, up),
+            Setting::Units => {
+                const ORDER: [ByteUnits; 3] = [ByteUnits::Binary, ByteUnits::Iec, ByteUnits::Si];
+                let index = ORDER.iter().position(|units| *units == config.units.bytes).unwrap_or(0);
+                let next = if up { index + 1 } else { index + ORDER.len() - 1 };
+                config.units.bytes = ORDER[next % ORDER.len()];
+            }
+            Setting::BitRates => config.units.bit_rates = !config.units.bit_rates,
+            Setting::HeatRows => config.theme.heat_rows = !config.theme.heat_rows,
+            Setting::TotalColumns => config.show_total_columns = !config.show_total_columns,
+            Setting::HistoryWindow => config.history_window_secs = step(config.history_window_secs, 60, 60, u64::MAX, up),
+            Setting::SampleInterval => config.sample_interval_ms = step(config.sample_interval_ms, 100, 100, 10_000, up),
+            Setting::MemoryBudget => config.memory_budget_mb = step(config.memory_budget_mb, 64, 32, u64::MAX, up),
+            Setting::Quota => config.quota_mb = step(config.quota_mb, 100, 100, u64::MAX, up),
+            Setting::LargePacket => {
+                config.large_packet_threshold = step(config.large_packet_threshold as u64, 1000, 0, u64::MAX, up) as usize;
+            }
+            Setting::FrequentConnection => {
+                config.frequent_connection_threshold = step(config.frequent_connection_threshold as u64, 1, 0, u64::MAX, up) as usize;
+            }
+            Setting::AnomalyEnabled => config.anomaly.enabled = !config.anomaly.enabled,
+            Setting::AnomalySigma => {
+                let sigma = if up { config.anomaly.sigma + 0.5 } else { config.anomaly.sigma - 0.5 };
+                config.anomaly.sigma = sigma.clamp(2.0, 10.0);
+            }
+            Setting::KeepExited => config.exited_retention_secs = step(config.exited_retention_secs, 30, 0, 3600, up),
+            Setting::IdleAfter => config.idle_after_secs = step(config.idle_after_secs, 10, 10, 3600, up),
+        }
+    }
+
+    /// Carry the setting's saved value over to the running session
+    pub fn apply(self, config: &SavedConfig, app: &mut App) {
+        match self {
+            // The capture is moved by the interface list; container detection and
+            // the byte counting start with the capture
+            Setting::Interface | Setting::Containers | Setting::CountBytes => {}
+            Setting::RefreshInterval => app.refresh_interval_ms = config.refresh_interval_ms,
+            Setting::Units | Setting::BitRates => crate::units::set_style(config.units),
+            Setting::HeatRows => app.theme.heat_rows = config.theme.heat_rows,
+            Setting::TotalColumns => app.show_total_columns = config.show_total_columns,
+            Setting::HistoryWindow => app.history_window_secs = config.history_window_secs,
+            Setting::SampleInterval => app.sample_interval_ms = config.sample_interval_ms,
+            Setting::MemoryBudget => app.memory_budget = config.memory_budget_mb * MB,
+            Setting::Quota => app.total_quota_threshold = config.quota_mb * MB,
+            // The packet details read these from the config file
+            Setting::LargePacket | Setting::FrequentConnection => {}
+            Setting::AnomalyEnabled | Setting::AnomalySigma => app.anomalies.settings = config.anomaly.clone(),
+            Setting::KeepExited => app.exited_retention_secs = config.exited_retention_secs,
+            Setting::IdleAfter => app.idle_after_secs = config.idle_after_secs,
+        }
+    }
+}
+
+/// A config describing the running session, for saving settings before any config exists
+pub fn session_config(app: &App) -> SavedConfig {
+    SavedConfig {
+        interface: app.capture_iface.clone().unwrap_or_else(|| "any".to_string()),
+        containers_mode: app.containers_mode,
+        show_total_columns: app.show_total_columns,
+        memory_budget_mb: app.memory_budget / MB,
+        history_window_secs: app.history_window_secs,
+        sample_interval_ms: app.sample_interval_ms,
+        refresh_interval_ms: app.refresh_interval_ms,
+        quota_mb: app.total_quota_threshold / MB,
+        exited_retention_secs: app.exited_retention_secs,
+        cleanup_interval_secs: app.cleanup_interval_secs,
+        idle_after_secs: app.idle_after_secs,
+        theme: app.theme.clone(),
+        anomaly: app.anomalies.settings.clone(),
+        cost: app.cost.clone(),
+        units: crate::units::style(),
+        pinned: app.pinned.iter().cloned().collect(),
+        count_bytes: app.byte_counting,
+        ..Default::default()
+    }
+}
+
+/// The saved config to edit and whether it has to be created: this session's settings
+/// when there is no config file yet. A file that doesn't parse is an error, so saving
+/// never overwrites it.
+pub fn editable_config(app: &App) -> Result<(SavedConfig, bool), String> {
+    Ok(match crate::config::read_config()? {
+        Some(config) => (config, false),
+        None => (session_config(app), true),
+    })
+}
+
+/// Change `setting` in the saved config, creating the config if needed, and in this session
+pub fn change(app: &mut App, setting: Setting, up: bool) {
+    let message = match editable_config(app) {
+        Ok((mut config, created)) => {
+            setting.adjust(&mut config, up);
+            match crate::config::save_config(&config) {
+                Ok(()) => {
+                    setting.apply(&config, app);
+                    let mut message = format!("✅ {}: {}", setting.label(), setting.value(&config));
+                    if !setting.is_live() {
+                        message.push_str(" (takes effect at the next start)");
+                    }
+                    if created {
+                        message.push_str(" | created the config file");
+                    }
+                    message
+                }
+                Err(e) => format!("❌ Failed to save setting: {}", e),
+            }
+        }
+        Err(e) => format!("❌ Not saved: {}", e),
+    };
+    app.settings_notification = Some(message);
+    app.settings_notification_time = Some(std::time::Instant::now());
+}
+
+/// Save the interface picked from the interface list as the one to capture on at startup
+pub fn save_interface(app: &mut App, interface: &str) {
+    let message = match editable_config(app) {
+        Ok((mut config, _)) => {
+            config.interface = interface.to_string();
+            match crate::config::save_config(&config) {
+                Ok(()) => format!("✅ Interface: {}", interface),
+                Err(e) => format!("❌ Failed to save setting: {}", e),
+            }
+        }
+        Err(e) => format!("❌ Not saved: {}", e),
+    };
+    app.settings_notification = Some(message);
+    app.settings_notification_time = Some(std::time::Instant::now());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_come_back_to_where_they_started() {
+        let config = SavedConfig::default();
+        for setting in all() {
+            let mut stepped = config.clone();
+            setting.adjust(&mut stepped, true);
+            setting.adjust(&mut stepped, false);
+            assert_eq!(setting.value(&stepped), setting.value(&config), "{}", setting.label());
+        }
+    }
+
+    #[test]
+    fn steps_stay_in_range() {
+        let mut config = SavedConfig::default();
+        for _ in 0..100 {
+            Setting::RefreshInterval.adjust(&mut config, false);
+            Setting::AnomalySigma.adjust(&mut config, true);
+            Setting::KeepExited.adjust(&mut config, false);
+        }
+        assert_eq!(config.refresh_interval_ms, 50);
+        assert_eq!(config.anomaly.sigma, 10.0);
+        assert_eq!(config.exited_retention_secs, 0);
+    }
+
+    #[test]
+    fn saved_settings_reach_the_session_and_back() {
+        let mut config = SavedConfig::default();
+        let mut app = App::new(false, false);
+        // Units and bit rates set the process-wide unit style, which other tests format with
+        let settings: Vec<Setting> = all()
+            .filter(|setting| setting.is_live() && !matches!(setting, Setting::Units | Setting::BitRates))
+            .collect();
+        for setting in &settings {
+            setting.adjust(&mut config, true);
+            setting.apply(&config, &mut app);
+        }
+
+        let json = serde_json::to_string(&config).unwrap();
+        let loaded: SavedConfig = serde_json::from_str(&json).unwrap();
+        let session = session_config(&app);
+        for setting in all() {
+            assert_eq!(setting.value(&loaded), setting.value(&config), "{}", setting.label());
+        }
+        // The thresholds are read from the config file, not kept in the session
+        for setting in settings.iter().filter(|setting| !matches!(setting, Setting::LargePacket | Setting::FrequentConnection)) {
+            assert_eq!(setting.value(&session), setting.value(&config), "{}", setting.label());
+        }
+    }
+}
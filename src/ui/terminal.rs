@@ -1,6 +1,9 @@
 use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use crossterm::{
+    cursor::Show,
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -15,14 +18,74 @@ pub fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>, io::Er
     Terminal::new(backend)
 }
 
-/// Restore terminal to normal state
-pub fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<(), io::Error> {
+/// Restore terminal to normal state. Harmless when it already is, so every
+/// exit path can call it
+pub fn restore_terminal() -> Result<(), io::Error> {
     disable_raw_mode()?;
     execute!(
-        terminal.backend_mut(),
+        io::stdout(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        Show
     )?;
-    terminal.show_cursor()?;
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Restores the terminal when dropped, so neither an error returned from the
+/// UI loop nor a panic unwinding through it leaves the shell in raw mode
+pub struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore_terminal();
+    }
+}
+
+/// Restore the terminal before the panic message is printed, on any thread,
+/// so the message lands on the normal screen instead of the discarded one
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        default_hook(info);
+    }));
+}
+
+/// Flag raised when the process is asked to stop: SIGINT, SIGTERM or SIGHUP
+/// (closed terminal) on Unix, Ctrl+C or Ctrl+Break on Windows. Handling the
+/// signal instead of dying lets the UI loop exit through its normal cleanup.
+pub fn shutdown_on_signal() -> Arc<AtomicBool> {
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let flag = shutdown.clone();
+    tokio::spawn(async move {
+        wait_for_signal().await;
+        flag.store(true, Ordering::Relaxed);
+    });
+    shutdown
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    let (Ok(mut terminate), Ok(mut hangup)) = (signal(SignalKind::terminate()), signal(SignalKind::hangup())) else {
+        let _ = tokio::signal::ctrl_c().await;
+        return;
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+        _ = hangup.recv() => {}
+    }
+}
+
+#[cfg(windows)]
+async fn wait_for_signal() {
+    let Ok(mut ctrl_break) = tokio::signal::windows::ctrl_break() else {
+        let _ = tokio::signal::ctrl_c().await;
+        return;
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = ctrl_break.recv() => {}
+    }
+}
@@ -0,0 +1,189 @@
+// User-adjustable presentation settings, stored in the `theme` section of the config.
+
+use ratatui::style::{Color, Modifier, Style};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+fn default_heat_thresholds() -> [f64; 3] {
+    [5.0, 15.0, 35.0]
+}
+
+/// The heat levels are looked up from the strongest down, so their thresholds must ascend
+fn sorted_heat_thresholds<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<[f64; 3], D::Error> {
+    let mut thresholds = <[f64; 3]>::deserialize(deserializer)?;
+    thresholds.sort_by(f64::total_cmp);
+    Ok(thresholds)
+}
+
+/// Row backgrounds for the heat levels, from faint to strong
+const HEAT_COLORS: [Color; 3] = [Color::Indexed(52), Color::Indexed(88), Color::Indexed(124)];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Theme {
+    /// Tint process rows by their share of the current total bandwidth
+    #[serde(default)]
+    pub heat_rows: bool,
+    /// Share of total bandwidth (percent) at which each heat level starts
+    #[serde(default = "default_heat_thresholds", deserialize_with = "sorted_heat_thresholds")]
+    pub heat_thresholds: [f64; 3],
+    /// Rules styling the rows of matching processes, first match wins
+    #[serde(default)]
+    pub highlights: Vec<HighlightRule>,
+}
+
+/// What a highlight rule's pattern is matched against
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightField {
+    #[default]
+    Name,
+    User,
+    Container,
+}
+
+/// A regex on a process field and the style its rows get
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HighlightRule {
+    #[serde(default)]
+    pub field: HighlightField,
+    pub pattern: String,
+    /// Text color: a name such as `cyan` or `lightgreen`, `#rrggbb` or a 256-color index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub underline: bool,
+    /// Set by `Theme::compile_highlights`; rules that failed to compile stay unset and never match
+    #[serde(skip)]
+    compiled: Option<(Regex, Style)>,
+}
+
+fn parse_color(value: &str) -> Result<Color, String> {
+    value.trim().parse().map_err(|_| format!("'{}' is not a color", value))
+}
+
+impl HighlightRule {
+    fn compile(&self) -> Result<(Regex, Style), String> {
+        let regex = Regex::new(&self.pattern).map_err(|_| format!("'{}' is not a valid regex", self.pattern))?;
+        let mut style = Style::default();
+        if let Some(color) = &self.color {
+            style = style.fg(parse_color(color)?);
+        }
+        if let Some(background) = &self.background {
+            style = style.bg(parse_color(background)?);
+        }
+        if self.bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if self.underline {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        Ok((regex, style))
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            heat_rows: false,
+            heat_thresholds: default_heat_thresholds(),
+            highlights: Vec::new(),
+        }
+    }
+}
+
+impl Theme {
+    /// Background for a row carrying `share_percent` of the total bandwidth, if it is hot enough
+    pub fn heat_color(&self, share_percent: f64) -> Option<Color> {
+        if !self.heat_rows {
+            return None;
+        }
+        self.heat_thresholds
+            .iter()
+            .zip(HEAT_COLORS)
+            .rev()
+            .find(|(threshold, _)| share_percent >= **threshold)
+            .map(|(_, color)| color)
+    }
+
+    /// Compile the highlight rules after loading; returns the problems with the ones that don't
+    pub fn compile_highlights(&mut self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for rule in &mut self.highlights {
+            rule.compiled = rule.compile().map_err(|e| problems.push(e)).ok();
+        }
+        problems
+    }
+
+    /// Style of the first highlight rule matching this process
+    pub fn highlight(&self, name: &str, user: Option<&str>, container: Option<&str>) -> Option<Style> {
+        self.highlights.iter().find_map(|rule| {
+            let (regex, style) = rule.compiled.as_ref()?;
+            let value = match rule.field {
+                HighlightField::Name => Some(name),
+                HighlightField::User => user,
+                HighlightField::Container => container,
+            }?;
+            regex.is_match(value).then_some(*style)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(field: HighlightField, pattern: &str, color: &str) -> HighlightRule {
+        HighlightRule {
+            field,
+            pattern: pattern.to_string(),
+            color: Some(color.to_string()),
+            background: None,
+            bold: true,
+            underline: false,
+            compiled: None,
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let mut theme = Theme {
+            highlights: vec![
+                rule(HighlightField::Name, "^(postgres|nginx)$", "cyan"),
+                rule(HighlightField::User, "^www", "#ff8800"),
+                rule(HighlightField::Container, "db", "green"),
+            ],
+            ..Theme::default()
+        };
+        assert!(theme.compile_highlights().is_empty());
+        let cyan = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+        assert_eq!(theme.highlight("nginx", Some("www-data"), None), Some(cyan));
+        assert_eq!(theme.highlight("php-fpm", Some("www-data"), None).and_then(|s| s.fg), Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(theme.highlight("mysqld", None, Some("db-1")).and_then(|s| s.fg), Some(Color::Green));
+        assert_eq!(theme.highlight("curl", Some("alice"), None), None);
+    }
+
+    #[test]
+    fn sorts_heat_thresholds() {
+        let mut theme: Theme = serde_json::from_str(r#"{"heat_rows": true, "heat_thresholds": [35.0, 5.0, 15.0]}"#).unwrap();
+        assert_eq!(theme.heat_thresholds, [5.0, 15.0, 35.0]);
+        assert_eq!(theme.heat_color(10.0), Some(HEAT_COLORS[0]));
+        assert_eq!(theme.heat_color(40.0), Some(HEAT_COLORS[2]));
+        assert_eq!(theme.heat_color(1.0), None);
+        theme.heat_rows = false;
+        assert_eq!(theme.heat_color(40.0), None);
+    }
+
+    #[test]
+    fn reports_broken_rules() {
+        let mut theme = Theme {
+            highlights: vec![rule(HighlightField::Name, "(", "cyan"), rule(HighlightField::Name, "sshd", "chartreuse-ish")],
+            ..Theme::default()
+        };
+        assert_eq!(theme.compile_highlights().len(), 2);
+        assert_eq!(theme.highlight("sshd", None, None), None);
+    }
+}
@@ -1,47 +1,30 @@
-/// Parse user input to bytes with unit support (KB, MB, GB, TB)
-pub fn parse_input_to_bytes(input: &str) -> u64 {
-    let input = input.trim().to_uppercase();
-    let mut num_part = String::new();
-    let mut unit_part = String::new();
-
-    for c in input.chars() {
-        if c.is_ascii_digit() || c == '.' {
-            num_part.push(c);
-        } else {
-            unit_part.push(c);
-        }
+/// Format a remaining duration compactly, e.g. "1h 05m", "29m 10s", "45s"
+pub fn format_countdown(remaining: std::time::Duration) -> String {
+    let secs = remaining.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
     }
-
-    let num = num_part.parse::<f64>().unwrap_or(0.0);
-    let unit = unit_part.trim();
-
-    let multiplier = match unit {
-        "KB" => 1024.0,
-        "MB" => 1024.0 * 1024.0,
-        "GB" => 1024.0 * 1024.0 * 1024.0,
-        "TB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
-        _ => 1.0,
-    };
-
-    (num * multiplier) as u64
 }
 
-/// Format bytes to human-readable string with appropriate units
-pub fn format_bytes(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-    const THRESHOLD: f64 = 1024.0;
-    
-    if bytes == 0 {
-        return "0 B".to_string();
-    }
-    
-    let bytes_f = bytes as f64;
-    let unit_index = (bytes_f.log(THRESHOLD).floor() as usize).min(UNITS.len() - 1);
-    let size = bytes_f / THRESHOLD.powi(unit_index as i32);
-    
-    if unit_index == 0 {
-        format!("{} {}", bytes, UNITS[unit_index])
-    } else {
-        format!("{:.1} {}", size, UNITS[unit_index])
-    }
-} 
\ No newline at end of file
+/// Compute a rectangle of the given percentage size centered inside `area`
+pub fn centered_rect(percent_x: u16, percent_y: u16, area: ratatui::layout::Rect) -> ratatui::layout::Rect {
+    use ratatui::layout::{Constraint, Layout};
+
+    let vertical = Layout::vertical([
+        Constraint::Percentage((100 - percent_y) / 2),
+        Constraint::Percentage(percent_y),
+        Constraint::Percentage((100 - percent_y) / 2),
+    ])
+    .split(area);
+    Layout::horizontal([
+        Constraint::Percentage((100 - percent_x) / 2),
+        Constraint::Percentage(percent_x),
+        Constraint::Percentage((100 - percent_x) / 2),
+    ])
+    .split(vertical[1])[1]
+}
\ No newline at end of file
@@ -0,0 +1,344 @@
+// Byte quantities as shown to and typed by the user. Every view, the alert
+// editor, the setup wizard and the JSON output go through these functions so
+// "1.5 MB" means the same thing everywhere.
+//
+// Byte units are binary (1 KB = 1024 B) by default, matching what
+// `format_bytes` prints, so a formatted value can be typed back in unchanged;
+// the IEC spellings (KiB, MiB, ...) are accepted too. Bit units (Kb, Mbit,
+// Gbps, ...) are decimal, the way link speeds are quoted, and converted to bytes.
+//
+// The `units` config section (or `--units`/`--bit-rates`) switches the whole
+// program to IEC names or decimal SI units, and rates to bits per second. It
+// is set at startup and changed at runtime from the Settings view (`set_style`
+// in ui/settings_fields.rs), so the style lives in a global rather than being
+// passed to every one of the many places that format a number.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use serde::{Deserialize, Serialize};
+
+const BINARY_UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+const IEC_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+const SI_UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+const BIT_RATE_UNITS: [&str; 5] = ["bit/s", "kbit/s", "Mbit/s", "Gbit/s", "Tbit/s"];
+
+/// How byte quantities are scaled and named
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteUnits {
+    /// Powers of 1024 named KB, MB, ... (the long-standing default)
+    #[default]
+    Binary,
+    /// Powers of 1024 named KiB, MiB, ...
+    Iec,
+    /// Powers of 1000 named kB, MB, ...
+    Si,
+}
+
+impl std::str::FromStr for ByteUnits {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, String> {
+        match input.to_ascii_lowercase().as_str() {
+            "binary" => Ok(ByteUnits::Binary),
+            "iec" => Ok(ByteUnits::Iec),
+            "si" | "decimal" => Ok(ByteUnits::Si),
+            _ => Err(format!("'{}' is not one of binary, iec or si", input)),
+        }
+    }
+}
+
+/// The `units` config section
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct UnitStyle {
+    #[serde(default)]
+    pub bytes: ByteUnits,
+    /// Show rates in bits per second (Mbit/s), as link speeds are quoted
+    #[serde(default)]
+    pub bit_rates: bool,
+}
+
+impl UnitStyle {
+    fn encode(self) -> u8 {
+        let bytes = match self.bytes {
+            ByteUnits::Binary => 0,
+            ByteUnits::Iec => 1,
+            ByteUnits::Si => 2,
+        };
+        bytes | (u8::from(self.bit_rates) << 2)
+    }
+
+    fn decode(code: u8) -> Self {
+        let bytes = match code & 0b11 {
+            1 => ByteUnits::Iec,
+            2 => ByteUnits::Si,
+            _ => ByteUnits::Binary,
+        };
+        UnitStyle { bytes, bit_rates: code & 0b100 != 0 }
+    }
+}
+
+static STYLE: AtomicU8 = AtomicU8::new(0);
+
+/// Use `style` for everything formatted or parsed from now on
+pub fn set_style(style: UnitStyle) {
+    STYLE.store(style.encode(), Ordering::Relaxed);
+}
+
+pub fn style() -> UnitStyle {
+    UnitStyle::decode(STYLE.load(Ordering::Relaxed))
+}
+
+/// `value` in the largest of `units` it reaches, one decimal past the first step
+fn scale(value: u64, step: f64, units: &[&str; 5]) -> String {
+    if value == 0 {
+        return format!("0 {}", units[0]);
+    }
+    let value_f = value as f64;
+    let unit_index = (value_f.log(step).floor() as usize).min(units.len() - 1);
+    if unit_index == 0 {
+        return format!("{} {}", value, units[0]);
+    }
+    format!("{:.1} {}", value_f / step.powi(unit_index as i32), units[unit_index])
+}
+
+/// Format bytes to human-readable string with appropriate units
+pub fn format_bytes(bytes: u64) -> String {
+    format_bytes_as(bytes, style().bytes)
+}
+
+pub fn format_bytes_as(bytes: u64, units: ByteUnits) -> String {
+    match units {
+        ByteUnits::Binary => scale(bytes, 1024.0, &BINARY_UNITS),
+        ByteUnits::Iec => scale(bytes, 1024.0, &IEC_UNITS),
+        ByteUnits::Si => scale(bytes, 1000.0, &SI_UNITS),
+    }
+}
+
+/// A rate in bytes per second, e.g. "1.5 MB/s" or, with bit rates, "12.6 Mbit/s"
+pub fn format_rate(bytes_per_second: u64) -> String {
+    format_rate_as(bytes_per_second, style())
+}
+
+pub fn format_rate_as(bytes_per_second: u64, style: UnitStyle) -> String {
+    if style.bit_rates {
+        scale(bytes_per_second.saturating_mul(8), 1000.0, &BIT_RATE_UNITS)
+    } else {
+        format!("{}/s", format_bytes_as(bytes_per_second, style.bytes))
+    }
+}
+
+/// Parse a number that may use either '.' or ',' as the decimal separator and
+/// ',', '.', '_', ''' or spaces to group thousands ("1,5", "1.234,5", "1 000")
+fn parse_number(text: &str) -> Option<f64> {
+    let digits: String = text.chars().filter(|c| !matches!(c, '_' | '\'' | ' ')).collect();
+    let last_dot = digits.rfind('.');
+    let last_comma = digits.rfind(',');
+    let decimal = match (last_dot, last_comma) {
+        // Both present: whichever comes last separates the decimals
+        (Some(dot), Some(comma)) => Some(if dot > comma { '.' } else { ',' }),
+        // Only one kind: repeated separators group thousands ("1.000.000"), and so
+        // does a single comma before exactly three digits ("1,000", but not "0,500")
+        (Some(pos), None) | (None, Some(pos)) => {
+            let separator = digits.as_bytes()[pos] as char;
+            let repeated = digits.matches(separator).count() > 1;
+            let thousands = separator == ',' && digits.len() - pos - 1 == 3 && !digits.starts_with('0');
+            (!repeated && !thousands).then_some(separator)
+        }
+        (None, None) => None,
+    };
+
+    let normalized: String = digits
+        .chars()
+        .filter_map(|c| match c {
+            '.' | ',' if Some(c) == decimal => Some('.'),
+            '.' | ',' => None,
+            other => Some(other),
+        })
+        .collect();
+    normalized.parse::<f64>().ok().filter(|n| n.is_finite() && *n >= 0.0)
+}
+
+/// Bytes per unit; `None` for an unknown unit. With SI units, KB, MB, ... are
+/// powers of 1000 like the values they print as; KiB, MiB, ... never are
+fn unit_multiplier(unit: &str, units: ByteUnits) -> Option<f64> {
+    // Bits: "bit", "bits" or "bps", or a lowercase 'b' after an uppercase prefix
+    // ("Mb"); all-lowercase "mb" has always meant megabytes here
+    let bits = |rest: &str| -> Option<f64> {
+        let power = match rest.to_ascii_uppercase().as_str() {
+            "" => 0,
+            "K" => 1,
+            "M" => 2,
+            "G" => 3,
+            "T" => 4,
+            _ => return None,
+        };
+        Some(1000f64.powi(power) / 8.0)
+    };
+    let lower = unit.to_ascii_lowercase();
+    for suffix in ["bits", "bit", "bps"] {
+        if let Some(prefix) = lower.strip_suffix(suffix) {
+            return bits(prefix);
+        }
+    }
+    if let Some(prefix) = unit.strip_suffix('b')
+        && !prefix.is_empty()
+        && prefix.chars().all(|c| c.is_ascii_uppercase()) {
+        return bits(prefix);
+    }
+
+    // Bytes: "", "B", "K", "KB" and "KiB" are all binary; "iB" alone is not a unit
+    let upper = unit.to_ascii_uppercase();
+    if upper == "IB" {
+        return None;
+    }
+    let iec = upper.ends_with("IB");
+    let prefix = upper
+        .strip_suffix("IB")
+        .or_else(|| upper.strip_suffix('B'))
+        .unwrap_or(&upper);
+    let power = match prefix {
+        "" => 0,
+        "K" => 1,
+        "M" => 2,
+        "G" => 3,
+        "T" => 4,
+        _ => return None,
+    };
+    let step = if units == ByteUnits::Si && !iec { 1000f64 } else { 1024f64 };
+    Some(step.powi(power))
+}
+
+/// Parse a byte quantity such as "10MB", "1.5 GiB", "1,5 GB", "512" or
+/// "100 Mbit". A bare number is taken as bytes.
+pub fn parse_bytes(input: &str) -> Result<u64, String> {
+    parse_bytes_as(input, style().bytes)
+}
+
+pub fn parse_bytes_as(input: &str, units: ByteUnits) -> Result<u64, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Enter a size, e.g. 10MB".to_string());
+    }
+    let split = input
+        .find(|c: char| c.is_alphabetic())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+    let value = parse_number(number.trim())
+        .ok_or_else(|| format!("'{}' is not a number", number.trim()))?;
+    let multiplier = unit_multiplier(unit.trim(), units)
+        .ok_or_else(|| format!("Unknown unit '{}' (use B, KB, MB, GB, TB or Kb, Mb, Gb for bits)", unit.trim()))?;
+    Ok((value * multiplier).round() as u64)
+}
+
+/// Parse a connection-rate threshold such as "100 conn/s" or "50/s";
+/// `None` when the input is a byte threshold instead
+pub fn parse_connection_rate(input: &str) -> Option<u64> {
+    let input = input.trim().to_lowercase();
+    let number = input
+        .strip_suffix("conn/s")
+        .or_else(|| input.strip_suffix("c/s"))
+        .or_else(|| input.strip_suffix("/s"))?;
+    number.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    const GB: u64 = 1024 * MB;
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(KB), "1.0 KB");
+        assert_eq!(format_bytes(1536 * KB), "1.5 MB");
+        assert_eq!(format_bytes(3 * GB), "3.0 GB");
+        assert_eq!(format_bytes(5000 * GB), "4.9 TB");
+        assert_eq!(format_bytes(u64::MAX), "16777216.0 TB");
+    }
+
+    #[test]
+    fn parses_byte_units() {
+        assert_eq!(parse_bytes("512"), Ok(512));
+        assert_eq!(parse_bytes("512 B"), Ok(512));
+        assert_eq!(parse_bytes("512b"), Ok(512));
+        assert_eq!(parse_bytes("10MB"), Ok(10 * MB));
+        assert_eq!(parse_bytes("10 mb"), Ok(10 * MB));
+        assert_eq!(parse_bytes("10M"), Ok(10 * MB));
+        assert_eq!(parse_bytes("10 MiB"), Ok(10 * MB));
+        assert_eq!(parse_bytes("2GB"), Ok(2 * GB));
+        assert_eq!(parse_bytes("1 TB"), Ok(1024 * GB));
+    }
+
+    #[test]
+    fn parses_bits_as_decimal() {
+        assert_eq!(parse_bytes("8 bit"), Ok(1));
+        assert_eq!(parse_bytes("100 Mbit"), Ok(12_500_000));
+        assert_eq!(parse_bytes("100Mb"), Ok(12_500_000));
+        assert_eq!(parse_bytes("1 Gbps"), Ok(125_000_000));
+        assert_eq!(parse_bytes("64 kbits"), Ok(8_000));
+    }
+
+    #[test]
+    fn parses_decimals_in_any_locale() {
+        assert_eq!(parse_bytes("1.5 GB"), Ok(GB + GB / 2));
+        assert_eq!(parse_bytes("2.125 GB"), Ok(2 * GB + GB / 8));
+        assert_eq!(parse_bytes("1,5 GB"), Ok(GB + GB / 2));
+        assert_eq!(parse_bytes("0,500 KB"), Ok(512));
+        assert_eq!(parse_bytes("1,000 MB"), Ok(1000 * MB));
+        assert_eq!(parse_bytes("1.000.000"), Ok(1_000_000));
+        assert_eq!(parse_bytes("1.234,5 KB"), Ok(1_264_128));
+        assert_eq!(parse_bytes("1,234.5 KB"), Ok(1_264_128));
+        assert_eq!(parse_bytes("1 000 KB"), Ok(1000 * KB));
+        assert_eq!(parse_bytes("1_000"), Ok(1000));
+    }
+
+    #[test]
+    fn round_trips_formatted_values() {
+        for bytes in [0, 700, 2 * KB, 10 * MB, 3 * GB] {
+            assert_eq!(parse_bytes(&format_bytes(bytes)), Ok(bytes));
+        }
+    }
+
+    #[test]
+    fn formats_other_unit_styles() {
+        assert_eq!(format_bytes_as(1536 * KB, ByteUnits::Iec), "1.5 MiB");
+        assert_eq!(format_bytes_as(1_500_000, ByteUnits::Si), "1.5 MB");
+        assert_eq!(format_bytes_as(999, ByteUnits::Si), "999 B");
+        let bits = UnitStyle { bytes: ByteUnits::Binary, bit_rates: true };
+        assert_eq!(format_rate_as(12_500_000, bits), "100.0 Mbit/s");
+        assert_eq!(format_rate_as(100, bits), "800 bit/s");
+        assert_eq!(format_rate_as(2 * KB, UnitStyle::default()), "2.0 KB/s");
+        for units in [ByteUnits::Iec, ByteUnits::Si] {
+            for bytes in [0, 700, 2_000, 10 * MB, 3_000_000_000] {
+                let typed = parse_bytes_as(&format_bytes_as(bytes, units), units).unwrap();
+                // One decimal is within 5% of the value
+                assert!(typed.abs_diff(bytes) * 20 <= bytes, "{} vs {}", typed, bytes);
+            }
+        }
+        assert_eq!(parse_bytes_as("1 MiB", ByteUnits::Si), Ok(MB));
+        assert_eq!(UnitStyle::decode(bits.encode()), bits);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(parse_bytes("").is_err());
+        assert!(parse_bytes("MB").is_err());
+        assert!(parse_bytes("ten MB").is_err());
+        assert!(parse_bytes("10 XB").is_err());
+        assert!(parse_bytes("10 iB").is_err());
+        assert!(parse_bytes("-5 MB").is_err());
+    }
+
+    #[test]
+    fn parses_connection_rates() {
+        assert_eq!(parse_connection_rate("100 conn/s"), Some(100));
+        assert_eq!(parse_connection_rate("50/s"), Some(50));
+        assert_eq!(parse_connection_rate("20 C/S"), Some(20));
+        assert_eq!(parse_connection_rate("10MB"), None);
+        assert_eq!(parse_connection_rate("10MB/s"), None);
+    }
+}
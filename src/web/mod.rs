@@ -0,0 +1,171 @@
+// Minimal embedded web dashboard for headless boxes.
+//
+// Serves a single static page plus three endpoints backed by the snapshot
+// stream: `/api/snapshot` (latest frame as JSON), `/api/history` (recent
+// total rates, so a freshly opened page starts with a filled chart) and
+// `/api/stream` (Server-Sent Events, one JSON frame per update).
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use crate::stream::{FrameSender, StreamFrame};
+
+const INDEX_HTML: &str = include_str!("index.html");
+
+/// Minimum spacing between Server-Sent Events pushed to the browser
+const WEB_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Largest request head we are willing to read
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Rate samples kept for `/api/history`, one per update interval (the width of the page's chart)
+const HISTORY_SAMPLES: usize = 300;
+
+/// What the dashboard remembers between requests
+#[derive(Default)]
+struct Recent {
+    latest: Option<Arc<StreamFrame>>,
+    history: VecDeque<(u64, u64, u64)>, // (timestamp_ms, sent rate, received rate)
+}
+
+type SharedRecent = Arc<Mutex<Recent>>;
+
+/// Bind the dashboard listener and serve it in the background
+pub async fn start(addr: &str, frames: &FrameSender) -> io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    let local = listener.local_addr()?;
+    eprintln!("🌐 Web dashboard on http://{}", local);
+    if !local.ip().is_loopback() {
+        eprintln!("⚠️  The dashboard has no authentication: anyone who can reach {} sees every process, user and remote host", local);
+    }
+
+    let recent = SharedRecent::default();
+    tokio::spawn(record(frames.subscribe(), recent.clone()));
+
+    let frames = frames.clone();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let frames = frames.clone();
+            let recent = recent.clone();
+            tokio::spawn(async move {
+                let _ = handle_connection(socket, frames, recent).await;
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Keep the latest frame and a sample of the total rates per update interval
+async fn record(mut rx: broadcast::Receiver<Arc<StreamFrame>>, recent: SharedRecent) {
+    let mut last_sample: Option<Instant> = None;
+    loop {
+        let frame = match rx.recv().await {
+            Ok(frame) => frame,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let mut recent = recent.lock().unwrap();
+        if last_sample.is_none_or(|t| t.elapsed() >= WEB_UPDATE_INTERVAL) {
+            last_sample = Some(Instant::now());
+            if recent.history.len() == HISTORY_SAMPLES {
+                recent.history.pop_front();
+            }
+            let totals = &frame.totals;
+            recent.history.push_back((frame.timestamp_ms, totals.sent_rate_bytes, totals.received_rate_bytes));
+        }
+        recent.latest = Some(frame);
+    }
+}
+
+/// Read the request line and dispatch on the path; one request per connection
+async fn handle_connection(mut socket: TcpStream, frames: FrameSender, recent: SharedRecent) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = socket.read(&mut chunk).await?;
+        if n == 0 || buf.len() + n > MAX_REQUEST_BYTES {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&buf);
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    if method != "GET" {
+        return respond(&mut socket, "405 Method Not Allowed", "text/plain", b"Method Not Allowed").await;
+    }
+
+    match path {
+        "/" | "/index.html" => {
+            respond(&mut socket, "200 OK", "text/html; charset=utf-8", INDEX_HTML.as_bytes()).await
+        }
+        "/api/snapshot" => {
+            // Before the first update there is nothing to return yet, so wait for it
+            let latest = recent.lock().unwrap().latest.clone();
+            let frame = match latest {
+                Some(frame) => frame,
+                None => {
+                    let mut rx = frames.subscribe();
+                    loop {
+                        match rx.recv().await {
+                            Ok(frame) => break frame,
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                        }
+                    }
+                }
+            };
+            let body = serde_json::to_vec(&*frame).map_err(io::Error::other)?;
+            respond(&mut socket, "200 OK", "application/json", &body).await
+        }
+        "/api/history" => {
+            let body = serde_json::to_vec(&recent.lock().unwrap().history).map_err(io::Error::other)?;
+            respond(&mut socket, "200 OK", "application/json", &body).await
+        }
+        "/api/stream" => stream_events(socket, frames).await,
+        _ => respond(&mut socket, "404 Not Found", "text/plain", b"Not Found").await,
+    }
+}
+
+async fn respond(socket: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    socket.write_all(head.as_bytes()).await?;
+    socket.write_all(body).await?;
+    socket.shutdown().await
+}
+
+/// Push frames as Server-Sent Events until the browser disconnects
+async fn stream_events(mut socket: TcpStream, frames: FrameSender) -> io::Result<()> {
+    socket
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n")
+        .await?;
+
+    let mut rx = frames.subscribe();
+    let mut last_sent: Option<Instant> = None;
+    loop {
+        let frame = match rx.recv().await {
+            Ok(frame) => frame,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        };
+        if last_sent.is_some_and(|t| t.elapsed() < WEB_UPDATE_INTERVAL) {
+            continue;
+        }
+        last_sent = Some(Instant::now());
+
+        let json = serde_json::to_string(&*frame).map_err(io::Error::other)?;
+        socket.write_all(format!("data: {}\n\n", json).as_bytes()).await?;
+    }
+}